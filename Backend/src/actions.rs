@@ -0,0 +1,169 @@
+//! Registry of user-facing actions.
+//!
+//! Every operation reachable from the native menu is described once in [`ACTIONS`]: its id
+//! (the menu item id), title, category, keybinding and what it needs from the current
+//! repository. The menu is built from these entries and `list_actions` reports them with
+//! their enabled state, so a command palette in the webview shows exactly what the menu
+//! offers. Running an action goes through the same handler as clicking its menu item.
+
+use openvcs_core::models::{Capabilities, HeadState};
+use serde::Serialize;
+
+use crate::state::AppState;
+
+/// Something an action needs before it can run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Need {
+    /// A repository is open.
+    Repo,
+    /// The repository has a working tree (not bare).
+    Worktree,
+    /// The backend can commit.
+    Commits,
+    /// The repository is not on read-only media.
+    Writable,
+    /// The backend can fetch, pull and push.
+    PushPull,
+    /// HEAD is on a branch with at least one commit.
+    BornBranch,
+    /// The working tree has changes (assumed until a status refresh says otherwise).
+    Changes,
+}
+
+impl Need {
+    fn reason(self) -> &'static str {
+        match self {
+            Need::Repo => "No repository open",
+            Need::Worktree => "The repository has no working tree",
+            Need::Commits => "The backend cannot commit",
+            Need::Writable => "The repository is read-only",
+            Need::PushPull => "The backend cannot fetch or push",
+            Need::BornBranch => "HEAD is not on a branch with commits",
+            Need::Changes => "Nothing to commit",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ActionDef {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub category: &'static str,
+    pub keybinding: Option<&'static str>,
+    pub needs: &'static [Need],
+}
+
+pub const ACTIONS: &[ActionDef] = &[
+    ActionDef { id: "clone_repo", title: "Clone…", category: "File", keybinding: Some("Ctrl+Shift+C"), needs: &[] },
+    ActionDef { id: "init_repo", title: "New Repository…", category: "File", keybinding: Some("Ctrl+Shift+N"), needs: &[] },
+    ActionDef { id: "add_repo", title: "Add Existing…", category: "File", keybinding: Some("Ctrl+O"), needs: &[] },
+    ActionDef { id: "open_repo", title: "Switch…", category: "File", keybinding: Some("Ctrl+R"), needs: &[] },
+    ActionDef { id: "settings", title: "Preferences…", category: "File", keybinding: Some("Ctrl+P"), needs: &[] },
+    ActionDef { id: "exit", title: "Exit", category: "File", keybinding: None, needs: &[] },
+    ActionDef { id: "fetch", title: "Fetch/Pull", category: "Repository", keybinding: Some("F5"), needs: &[Need::Repo, Need::Writable, Need::PushPull] },
+    ActionDef { id: "push", title: "Push", category: "Repository", keybinding: Some("Ctrl+P"), needs: &[Need::Repo, Need::Writable, Need::PushPull, Need::BornBranch] },
+    ActionDef { id: "commit", title: "Commit", category: "Repository", keybinding: Some("Ctrl+Enter"), needs: &[Need::Repo, Need::Writable, Need::Worktree, Need::Commits, Need::Changes] },
+    ActionDef { id: "previous_branch", title: "Switch to Previous Branch", category: "Repository", keybinding: Some("Ctrl+Shift+B"), needs: &[Need::Repo, Need::Writable, Need::Worktree] },
+    ActionDef { id: "undo_head_move", title: "Undo Checkout/Reset", category: "Repository", keybinding: Some("Ctrl+Alt+Z"), needs: &[Need::Repo, Need::Writable, Need::Worktree] },
+    ActionDef { id: "repo-edit-gitignore", title: "Edit .gitignore", category: "Repository", keybinding: None, needs: &[Need::Repo, Need::Writable, Need::Worktree] },
+    ActionDef { id: "repo-edit-gitattributes", title: "Edit .gitattributes", category: "Repository", keybinding: None, needs: &[Need::Repo, Need::Writable, Need::Worktree] },
+    ActionDef { id: "repo-settings", title: "Repository Settings", category: "Repository", keybinding: None, needs: &[Need::Repo] },
+    ActionDef { id: "repo-health", title: "Health…", category: "Repository", keybinding: None, needs: &[Need::Repo] },
+    ActionDef { id: "docs", title: "Documentation", category: "Help", keybinding: None, needs: &[] },
+    ActionDef { id: "check_updates", title: "Check for Updates…", category: "Help", keybinding: None, needs: &[] },
+    ActionDef { id: "about", title: "About", category: "Help", keybinding: None, needs: &[] },
+];
+
+/// The registry entry for `id`.
+pub fn def(id: &str) -> Option<&'static ActionDef> {
+    ACTIONS.iter().find(|a| a.id == id)
+}
+
+/// An action with its state, for the command palette.
+#[derive(Debug, Clone, Serialize)]
+pub struct Action {
+    pub id: &'static str,
+    pub title: String,
+    pub category: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keybinding: Option<&'static str>,
+    pub enabled: bool,
+    /// Why the action is disabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+/// What the current repository offers, as far as actions care.
+#[derive(Debug, Default)]
+pub struct Context {
+    repo: bool,
+    caps: Capabilities,
+    head: Option<HeadState>,
+    /// Changed files and commits ahead, from the last status refresh.
+    changes: Option<usize>,
+    ahead: Option<u32>,
+}
+
+impl Context {
+    pub fn of(state: &AppState) -> Self {
+        match state.current_repo() {
+            Some(repo) => Self {
+                repo: true,
+                caps: repo.inner().caps(),
+                head: repo.inner().head_state().ok(),
+                ..Self::default()
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// Add the counts of a status refresh.
+    pub fn with_counts(mut self, changes: usize, ahead: u32) -> Self {
+        if self.repo {
+            self.changes = Some(changes);
+            self.ahead = Some(ahead);
+        }
+        self
+    }
+
+    fn has(&self, need: Need) -> bool {
+        match need {
+            Need::Repo => self.repo,
+            Need::Worktree => self.caps.worktree,
+            Need::Commits => self.caps.commits,
+            Need::Writable => self.caps.writable,
+            Need::PushPull => self.caps.push_pull,
+            Need::BornBranch => matches!(self.head, Some(HeadState::Branch { .. })),
+            Need::Changes => self.changes != Some(0),
+        }
+    }
+
+    /// Title of `def` with the counts it cares about, e.g. "Push (2)".
+    pub fn title(&self, def: &ActionDef) -> String {
+        let count = match def.id {
+            "push" => self.ahead.map(|n| n as usize),
+            "commit" => self.changes,
+            _ => None,
+        };
+        match count {
+            Some(n) if n > 0 => format!("{} ({n})", def.title),
+            _ => def.title.to_string(),
+        }
+    }
+
+    /// The first unmet need of `def`, as a message; `None` when it can run.
+    pub fn blocker(&self, def: &ActionDef) -> Option<&'static str> {
+        def.needs.iter().find(|n| !self.has(**n)).map(|n| n.reason())
+    }
+}
+
+/// Every action with its enabled state in `ctx`.
+pub fn list(ctx: &Context) -> Vec<Action> {
+    ACTIONS
+        .iter()
+        .map(|d| {
+            let reason = ctx.blocker(d);
+            Action { id: d.id, title: ctx.title(d), category: d.category, keybinding: d.keybinding, enabled: reason.is_none(), reason }
+        })
+        .collect()
+}
@@ -0,0 +1,101 @@
+//! Operations on a selection of files as a whole.
+//!
+//! Staging two hundred files or discarding a folder used to be one command per file from
+//! the UI, each taking the op gate and triggering its own refresh. [`run`] applies a whole
+//! selection as one operation: the paths go to the backend in chunks of [`CHUNK`] (keeping
+//! command lines short), a progress event follows every chunk and cancellation is checked
+//! in between. [`ignore`] adds patterns to the `.gitignore` at the top of the working tree.
+
+use std::path::{Path, PathBuf};
+
+use log::info;
+use openvcs_core::models::{ProgressPhase, VcsEvent};
+use openvcs_core::{OnEvent, Vcs, VcsError};
+use serde::Deserialize;
+
+/// Paths handed to the backend per call.
+pub const CHUNK: usize = 100;
+
+/// What to do with the selected files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchOp {
+    /// Stage them as they are in the working tree.
+    Stage,
+    /// Restore them (index and working tree) from HEAD.
+    Discard,
+}
+
+impl BatchOp {
+    pub fn name(self) -> &'static str {
+        match self {
+            BatchOp::Stage => "stage",
+            BatchOp::Discard => "discard",
+        }
+    }
+
+    fn verb(self) -> &'static str {
+        match self {
+            BatchOp::Stage => "Staging",
+            BatchOp::Discard => "Discarding",
+        }
+    }
+}
+
+/// Apply `op` to `paths` (files or directories), reporting progress to `on`.
+pub fn run(vcs: &dyn Vcs, op: BatchOp, paths: &[PathBuf], on: &OnEvent) -> openvcs_core::Result<()> {
+    let total = paths.len();
+    info!("batch: {} {total} path(s)", op.name());
+    let mut done = 0;
+    for chunk in paths.chunks(CHUNK) {
+        if openvcs_core::cancel::is_cancelled() {
+            info!("batch: {} cancelled after {done} of {total}", op.name());
+            return Err(VcsError::Cancelled);
+        }
+        crate::locks::retry_on_stale_lock(vcs.workdir(), || match op {
+            BatchOp::Stage => vcs.stage_paths(chunk),
+            BatchOp::Discard => vcs.discard_paths(chunk),
+        })?;
+        done += chunk.len();
+        on(VcsEvent::Progress {
+            phase: ProgressPhase::Other,
+            percent: Some((done * 100 / total) as u8),
+            detail: format!("{} {done}/{total} file(s)", op.verb()),
+        });
+    }
+    Ok(())
+}
+
+/// Append the `patterns` not listed yet to `<workdir>/.gitignore`, creating it if needed.
+/// Returns the patterns added.
+pub fn ignore(workdir: &Path, patterns: &[String]) -> Result<Vec<String>, String> {
+    let path = workdir.join(".gitignore");
+    let mut text = match std::fs::read_to_string(&path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read .gitignore: {e}")),
+    };
+    let mut added: Vec<String> = Vec::new();
+    for p in patterns.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        if p.starts_with('#') {
+            return Err(format!("'{p}' is a comment, not a pattern"));
+        }
+        if text.lines().any(|l| l.trim_end() == p) || added.iter().any(|a| a == p) {
+            continue;
+        }
+        added.push(p.to_string());
+    }
+    if added.is_empty() {
+        return Ok(added);
+    }
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    for p in &added {
+        text.push_str(p);
+        text.push('\n');
+    }
+    std::fs::write(&path, text).map_err(|e| format!("Failed to write .gitignore: {e}"))?;
+    info!("batch: ignored {} pattern(s)", added.len());
+    Ok(added)
+}
@@ -0,0 +1,121 @@
+//! `openvcs bench`: status, log and diff timings of every built-in backend, to see which
+//! one is faster on a given repository.
+//!
+//! ```text
+//! openvcs bench [REPO] [--files N] [--commits N] [--iterations N]
+//! ```
+//!
+//! Without `REPO` it generates a repository of `--files` files and `--commits` commits
+//! (see `openvcs_testkit::synthetic`) and removes it afterwards. The workloads are the
+//! ones the criterion benches run (`openvcs_core::bench`); each cell is the median of
+//! `--iterations` runs, and `*` marks the fastest backend of a row. Windows release
+//! builds have no console; run a debug build there.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use openvcs_core::backend_descriptor::list_backends;
+use openvcs_core::bench::{measure, Subject, WORKLOADS};
+use openvcs_core::VcsError;
+
+const USAGE: &str = "usage: openvcs bench [REPO] [--files N] [--commits N] [--iterations N]";
+
+struct Args {
+    repo: Option<PathBuf>,
+    files: usize,
+    commits: usize,
+    iterations: usize,
+}
+
+fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut out = Args { repo: None, files: 5000, commits: 500, iterations: 10 };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut number = |flag: &str| -> Result<usize, String> {
+            let v = args.next().ok_or_else(|| format!("{flag} needs a value"))?;
+            v.parse().map_err(|_| format!("{flag}: not a number: {v}"))
+        };
+        match arg.as_str() {
+            "--files" => out.files = number("--files")?,
+            "--commits" => out.commits = number("--commits")?,
+            "--iterations" => out.iterations = number("--iterations")?,
+            "-h" | "--help" => return Err(USAGE.into()),
+            a if a.starts_with('-') || out.repo.is_some() => return Err(format!("unexpected argument {a}\n{USAGE}")),
+            a => out.repo = Some(PathBuf::from(a)),
+        }
+    }
+    Ok(out)
+}
+
+/// Run `openvcs bench` with the arguments after `bench`; returns the exit code.
+pub fn main(args: Vec<String>) -> i32 {
+    match run(args) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("openvcs bench: {e}");
+            2
+        }
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let args = parse(args)?;
+    crate::git_exe::apply(&crate::settings::AppConfig::load_or_default().git.executable_path);
+
+    // Kept until the end: dropping it removes the generated repository
+    let mut _generated = None;
+    let repo = match args.repo {
+        Some(path) => path,
+        None => {
+            eprintln!("generating a repository with {} files and {} commits…", args.files, args.commits);
+            let spec = openvcs_testkit::synthetic(args.files, args.commits);
+            let fixture = spec
+                .build_temp_with(&crate::git_exe::program(), "bench")
+                .map_err(|e| format!("could not generate the repository: {e}"))?;
+            let path = fixture.path().to_path_buf();
+            _generated = Some(fixture);
+            path
+        }
+    };
+
+    let mut backends = Vec::new();
+    for desc in list_backends() {
+        match (desc.open)(&repo) {
+            Ok(vcs) => backends.push((desc.id.to_string(), vcs)),
+            Err(e) => eprintln!("{}: cannot open {}: {e}", desc.id, repo.display()),
+        }
+    }
+    let first = backends.first().ok_or_else(|| format!("no backend could open {}", repo.display()))?;
+    let subject = Subject::probe(first.1.as_ref()).map_err(|e| e.to_string())?;
+
+    println!("{} ({} iterations, median)", repo.display(), args.iterations);
+    print!("{:<12}", "");
+    for (id, _) in &backends {
+        print!("{id:>14}");
+    }
+    println!();
+    for workload in WORKLOADS {
+        let cells: Vec<Result<Duration, VcsError>> = backends
+            .iter()
+            .map(|(_, vcs)| measure(vcs.as_ref(), &subject, workload, args.iterations).map(|t| t.median()))
+            .collect();
+        let fastest = cells.iter().filter_map(|c| c.as_ref().ok()).min().copied();
+        print!("{:<12}", workload.name);
+        for cell in &cells {
+            let text = match cell {
+                Ok(d) => format!("{:.1} ms{}", d.as_secs_f64() * 1000.0, if Some(*d) == fastest { " *" } else { "  " }),
+                Err(VcsError::Unsupported(_)) => "n/a  ".into(),
+                Err(_) => "failed  ".into(),
+            };
+            print!("{text:>14}");
+        }
+        println!();
+        for ((id, _), cell) in backends.iter().zip(&cells) {
+            match cell {
+                Err(VcsError::Unsupported(_)) | Ok(_) => {}
+                Err(e) => eprintln!("  {id}: {e}"),
+            }
+        }
+    }
+    Ok(())
+}
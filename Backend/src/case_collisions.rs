@@ -0,0 +1,39 @@
+//! Case-collision detection.
+//!
+//! Tracked paths that differ only by case (`Readme.md` / `README.md`, usually committed from
+//! Linux) name one file on macOS and Windows: a checkout writes both to the same place and
+//! the loser shows up as permanently modified. [`find`] reports such paths when the
+//! repository lives on a case-insensitive filesystem; the status refresh attaches them to
+//! `repo:status` and the health check lists them on any filesystem.
+
+use std::path::Path;
+
+use log::{debug, warn};
+use openvcs_core::models::CaseCollision;
+use openvcs_core::Vcs;
+
+/// Whether the filesystem holding `workdir` ignores case, probed through the `.git` entry.
+pub fn is_case_insensitive(workdir: &Path) -> bool {
+    let git = workdir.join(".git");
+    git.exists() && workdir.join(".GIT").exists()
+}
+
+/// Colliding tracked paths of `vcs`; empty on case-sensitive filesystems. Blocking.
+pub fn find(vcs: &dyn Vcs) -> Vec<CaseCollision> {
+    if !is_case_insensitive(vcs.workdir()) {
+        return Vec::new();
+    }
+    match vcs.tracked_paths() {
+        Ok(paths) => {
+            let found = CaseCollision::find(&paths);
+            if !found.is_empty() {
+                warn!("case_collisions: {} path(s) collide by case in {}", found.len(), vcs.workdir().display());
+            }
+            found
+        }
+        Err(e) => {
+            debug!("case_collisions: cannot list tracked paths: {e}");
+            Vec::new()
+        }
+    }
+}
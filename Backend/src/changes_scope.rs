@@ -0,0 +1,61 @@
+//! Scoping the Changes view to one directory of the repository.
+//!
+//! In a monorepo most of the status is other projects' noise. The directory a user works
+//! in is kept in the repository's local config under [`CHANGES_SCOPE_KEY`] (so it survives
+//! restarts and stays with the clone) and becomes the `prefix` of every status the Changes
+//! view gets. Status is polled every few seconds, so the value is cached here per
+//! repository instead of being read from git each time.
+
+use std::collections::BTreeMap;
+
+use log::{debug, info};
+use openvcs_core::models::{normalize_prefix, StatusOptions};
+use openvcs_core::Vcs;
+use parking_lot::Mutex;
+
+/// Repository config key holding the directory the Changes view is limited to.
+pub const CHANGES_SCOPE_KEY: &str = "openvcs.changesScope";
+
+/// Scope per repository (`paths::key`), once read.
+static CACHE: Mutex<BTreeMap<String, Option<String>>> = Mutex::new(BTreeMap::new());
+
+/// The directory the Changes view of `vcs` is limited to, if any.
+pub fn get(vcs: &dyn Vcs) -> Option<String> {
+    let key = crate::paths::key(vcs.workdir());
+    if let Some(scope) = CACHE.lock().get(&key) {
+        return scope.clone();
+    }
+    let scope = match vcs.local_config(CHANGES_SCOPE_KEY) {
+        Ok(v) => v.as_deref().and_then(normalize_prefix),
+        Err(e) => {
+            debug!("changes_scope: cannot read {CHANGES_SCOPE_KEY}: {e}");
+            None
+        }
+    };
+    CACHE.lock().insert(key, scope.clone());
+    scope
+}
+
+/// Limit the Changes view of `vcs` to `dir` (repo-relative), or show everything again
+/// when `None` or empty. Returns the scope as stored.
+pub fn set(vcs: &dyn Vcs, dir: Option<&str>) -> Result<Option<String>, String> {
+    let scope = dir.and_then(normalize_prefix);
+    if let Some(dir) = scope.as_deref() {
+        if dir.split('/').any(|p| p == "..") {
+            return Err(format!("{dir} is outside the repository"));
+        }
+        if !vcs.workdir().join(dir).is_dir() {
+            return Err(format!("{dir} is not a directory of the repository"));
+        }
+    }
+    vcs.set_local_config(CHANGES_SCOPE_KEY, scope.as_deref()).map_err(|e| e.to_string())?;
+    info!("changes_scope: {}", scope.as_deref().unwrap_or("(whole tree)"));
+    let key = crate::paths::key(vcs.workdir());
+    CACHE.lock().insert(key, scope.clone());
+    Ok(scope)
+}
+
+/// `opts` limited to the scope of `vcs`.
+pub fn apply(vcs: &dyn Vcs, opts: StatusOptions) -> StatusOptions {
+    StatusOptions { prefix: get(vcs), ..opts }
+}
@@ -0,0 +1,69 @@
+//! Chunked transfer of large command results.
+//!
+//! A result serialized into one multi-megabyte IPC message stalls the webview while it is
+//! received and parsed. Commands that can return very long lists (diff lines, log pages)
+//! go through [`transfer`]: a result under [`INLINE_LIMIT`] bytes of JSON is returned as
+//! is; a larger one is emitted as `ipc:chunk` events of about [`CHUNK_BYTES`] each, in
+//! `seq` order, and the command returns only the job and the chunk count, which the UI
+//! waits for (`invokeChunked` in the frontend). Status has its own stream
+//! (`git_status_stream`).
+
+use log::debug;
+use openvcs_core::models::JobId;
+use serde::Serialize;
+use tauri::{Emitter, Runtime};
+
+/// Event carrying one chunk.
+pub const CHUNK_EVENT: &str = "ipc:chunk";
+/// Results up to this many bytes of JSON are returned in the command response.
+pub const INLINE_LIMIT: usize = 512 * 1024;
+/// Target size of a chunk; a single larger item is sent alone.
+pub const CHUNK_BYTES: usize = 256 * 1024;
+
+/// What a chunk-capable command returns.
+#[derive(Debug, Serialize)]
+#[serde(tag = "transfer", rename_all = "lowercase")]
+pub enum Transfer<T> {
+    Inline { items: Vec<T> },
+    /// `chunks` events with this `job` carry the `total` items.
+    Chunked { job: JobId, chunks: u32, total: usize },
+}
+
+#[derive(Serialize, Clone)]
+struct Chunk {
+    job: JobId,
+    seq: u32,
+    /// The chunk's items as a JSON array, parsed by the UI; serialized once here instead of
+    /// once per item and again for the event.
+    json: String,
+}
+
+/// Return `items` inline when small, else emit them as chunk events.
+pub fn transfer<R: Runtime, T: Serialize>(app: &tauri::AppHandle<R>, items: Vec<T>) -> Result<Transfer<T>, String> {
+    let encoded = items.iter().map(serde_json::to_string).collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())?;
+    let bytes: usize = encoded.iter().map(|s| s.len() + 1).sum();
+    if bytes <= INLINE_LIMIT {
+        return Ok(Transfer::Inline { items });
+    }
+
+    let job = JobId::next();
+    let total = encoded.len();
+    let mut seq = 0u32;
+    let mut json = String::with_capacity(CHUNK_BYTES + 1024);
+    for item in encoded {
+        json.push(if json.is_empty() { '[' } else { ',' });
+        json.push_str(&item);
+        if json.len() >= CHUNK_BYTES {
+            json.push(']');
+            let _ = app.emit(CHUNK_EVENT, Chunk { job, seq, json: std::mem::take(&mut json) });
+            seq += 1;
+        }
+    }
+    if !json.is_empty() {
+        json.push(']');
+        let _ = app.emit(CHUNK_EVENT, Chunk { job, seq, json });
+        seq += 1;
+    }
+    debug!("chunked: {total} item(s), {bytes} bytes in {seq} chunk(s) ({job})");
+    Ok(Transfer::Chunked { job, chunks: seq, total })
+}
@@ -0,0 +1,139 @@
+//! Clone recovery: cleanup after a failed or cancelled clone, and resume where possible.
+//!
+//! A failed clone used to leave its destination behind, so retrying failed with
+//! "destination path already exists". Now, after a failure:
+//! - if the transfer had completed (origin's branches were fetched; checkout is what
+//!   failed), the directory is kept and marked, and the next clone of the same URL into it
+//!   fetches and checks out instead of starting over;
+//! - otherwise whatever the clone created is removed.
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::Path;
+
+use log::{info, warn};
+use openvcs_core::{OnEvent, Vcs};
+
+/// Marker file inside the git dir of a kept partial clone; holds the clone URL.
+const MARKER: &str = "openvcs-partial-clone";
+
+/// Snapshot of the destination taken before cloning, so cleanup only removes what the
+/// clone created.
+pub struct Destination {
+    existed: bool,
+    was_empty: bool,
+    /// Names of the entries that were already there.
+    entries: HashSet<OsString>,
+}
+
+impl Destination {
+    pub fn inspect(target: &Path) -> Self {
+        let entries: HashSet<OsString> = std::fs::read_dir(target)
+            .map(|d| d.flatten().map(|e| e.file_name()).collect())
+            .unwrap_or_default();
+        Self { existed: target.exists(), was_empty: entries.is_empty(), entries }
+    }
+
+    /// Refuse a destination with files in it: git would fail on it anyway, and cleanup
+    /// must never touch what the user put there.
+    pub fn ensure_clonable(&self, target: &Path) -> Result<(), String> {
+        if self.existed && !target.is_dir() {
+            return Err(format!("{} exists and is not a directory", target.display()));
+        }
+        if !self.was_empty {
+            return Err(format!("{} already exists and is not empty", target.display()));
+        }
+        Ok(())
+    }
+
+    /// The repository in it is this clone's: the destination was absent or empty.
+    fn created_by_clone(&self) -> bool {
+        !self.existed || self.was_empty
+    }
+}
+
+/// Whether `target` is a partial clone of `url` kept by [`cleanup_failed`].
+pub fn is_resumable(target: &Path, url: &str) -> bool {
+    let marker = crate::locks::git_dir(target).join(MARKER);
+    std::fs::read_to_string(marker).is_ok_and(|u| u.trim() == url.trim())
+}
+
+/// Origin's branches made it into the repository (loose or packed).
+fn has_remote_branches(target: &Path) -> bool {
+    let git_dir = crate::locks::git_dir(target);
+    let loose = std::fs::read_dir(git_dir.join("refs").join("remotes").join("origin"))
+        .is_ok_and(|mut d| d.next().is_some());
+    loose
+        || std::fs::read_to_string(git_dir.join("packed-refs"))
+            .is_ok_and(|s| s.contains(" refs/remotes/origin/"))
+}
+
+/// Clean up after a clone of `url` into `target` failed or was cancelled.
+pub fn cleanup_failed(target: &Path, url: &str, before: &Destination) {
+    if !target.exists() {
+        return;
+    }
+    // Never mark (and later check out over) a repository this clone didn't create
+    if before.created_by_clone() && has_remote_branches(target) {
+        let marker = crate::locks::git_dir(target).join(MARKER);
+        match std::fs::write(&marker, url) {
+            Ok(()) => {
+                info!("clone: kept partial clone at {} for resume", target.display());
+                return;
+            }
+            Err(e) => warn!("clone: cannot mark {} for resume: {e}", marker.display()),
+        }
+    }
+
+    let res = if before.existed {
+        // Only what appeared since the snapshot
+        std::fs::read_dir(target).and_then(|entries| {
+            entries.flatten().filter(|e| !before.entries.contains(&e.file_name())).try_for_each(|e| {
+                let p = e.path();
+                if p.is_dir() { std::fs::remove_dir_all(p) } else { std::fs::remove_file(p) }
+            })
+        })
+    } else {
+        std::fs::remove_dir_all(target)
+    };
+    match res {
+        Ok(()) => info!("clone: removed partial clone at {}", target.display()),
+        Err(e) => warn!("clone: failed to clean up {}: {e}", target.display()),
+    }
+}
+
+/// Finish a kept partial clone: fetch what's missing, then check out the default branch.
+pub fn resume(vcs: &dyn Vcs, on: Option<OnEvent>) -> openvcs_core::Result<()> {
+    let target = vcs.workdir().to_path_buf();
+    info!("clone: resuming partial clone at {}", target.display());
+    vcs.fetch("origin", "+refs/heads/*:refs/remotes/origin/*", on)?;
+
+    let branch = default_branch(vcs, &target);
+    if let Some(b) = &branch {
+        vcs.checkout_branch(b)?;
+    } else {
+        warn!("clone: no branch to check out after resume");
+    }
+    let _ = std::fs::remove_file(crate::locks::git_dir(&target).join(MARKER));
+    Ok(())
+}
+
+/// The branch HEAD points at if origin has it, else `main`/`master`, else any.
+fn default_branch(vcs: &dyn Vcs, target: &Path) -> Option<String> {
+    let remote: Vec<String> = vcs
+        .branches()
+        .ok()?
+        .into_iter()
+        .filter_map(|b| b.full_ref.strip_prefix("refs/remotes/origin/").map(str::to_string))
+        .filter(|n| n != "HEAD")
+        .collect();
+    let head = std::fs::read_to_string(crate::locks::git_dir(target).join("HEAD")).ok();
+    let head = head
+        .as_deref()
+        .and_then(|h| h.trim().strip_prefix("ref: refs/heads/"))
+        .map(str::to_string);
+    head.into_iter()
+        .chain(["main".to_string(), "master".to_string()])
+        .find(|n| remote.contains(n))
+        .or_else(|| remote.into_iter().next())
+}
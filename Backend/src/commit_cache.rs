@@ -0,0 +1,261 @@
+//! On-disk cache of per-commit derived data (patch text, diffstat, file list).
+//!
+//! A commit's diff never changes, so once computed it is stored under `commit-cache/` in the
+//! app data dir, keyed by the full commit id. Reopening a commit in the inspector or redrawing
+//! the history graph then reads a small JSON file instead of asking git for the diff again.
+//! File contents by blob id are kept the same way for the time-machine view.
+//! Only full object ids are cached (a ref like `HEAD` moves); very large patches are not
+//! stored, and the oldest entries are pruned once the cache grows past [`MAX_ENTRIES`].
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+
+use directories::ProjectDirs;
+use log::{debug, trace, warn};
+use openvcs_core::models::{FileChangeStat, ModeChange};
+use openvcs_core::{Vcs, VcsError};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// Patches larger than this (in bytes) are computed every time instead of stored.
+const MAX_DIFF_BYTES: usize = 2 * 1024 * 1024;
+/// Files kept before the oldest are pruned.
+const MAX_ENTRIES: usize = 4000;
+/// Writes between prune passes.
+const PRUNE_EVERY: usize = 100;
+
+static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStat {
+    pub path: String,
+    /// `A`, `M`, `D`, `R` or `T`.
+    pub status: String,
+    pub additions: u32,
+    pub deletions: u32,
+    pub binary: bool,
+    /// New size minus old size in bytes (0 when only the patch text was available).
+    #[serde(default)]
+    pub size_delta: i64,
+    /// Previous path of a rename.
+    #[serde(default)]
+    pub old_path: Option<String>,
+    /// Executable-bit, symlink or type change.
+    #[serde(default)]
+    pub mode_change: Option<ModeChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitStats {
+    pub id: String,
+    pub files: Vec<FileStat>,
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+/// Contents of one blob as the file viewer shows it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlobText {
+    pub oid: String,
+    pub size: usize,
+    /// `None` for binary (NUL bytes or not UTF-8) contents.
+    pub text: Option<String>,
+}
+
+fn cache_dir() -> PathBuf {
+    if let Some(pd) = ProjectDirs::from("dev", "OpenVCS", "OpenVCS") {
+        pd.data_dir().join("commit-cache")
+    } else {
+        PathBuf::from("commit-cache")
+    }
+}
+
+/// SHA-1 or SHA-256 object id in hex.
+fn is_full_oid(id: &str) -> bool {
+    matches!(id.len(), 40 | 64) && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn entry_path(id: &str, kind: &str) -> PathBuf {
+    let id = id.to_ascii_lowercase();
+    cache_dir().join(&id[..2]).join(format!("{id}.{kind}.json"))
+}
+
+fn read<T: DeserializeOwned>(id: &str, kind: &str) -> Option<T> {
+    let s = std::fs::read_to_string(entry_path(id, kind)).ok()?;
+    serde_json::from_str(&s).ok()
+}
+
+fn write<T: Serialize>(id: &str, kind: &str, value: &T) {
+    let path = entry_path(id, kind);
+    let res = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| serde_json::to_vec(value).map_err(std::io::Error::other))
+        .and_then(|bytes| std::fs::write(&path, bytes));
+    if let Err(e) = res {
+        warn!("commit_cache: failed to write {}: {e}", path.display());
+        return;
+    }
+    if WRITES.fetch_add(1, Ordering::Relaxed) % PRUNE_EVERY == PRUNE_EVERY - 1 {
+        prune();
+    }
+}
+
+fn prune() {
+    let Ok(shards) = std::fs::read_dir(cache_dir()) else { return };
+    let mut files: Vec<(SystemTime, PathBuf)> = shards
+        .flatten()
+        .filter_map(|s| std::fs::read_dir(s.path()).ok())
+        .flat_map(|entries| entries.flatten())
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+    if files.len() <= MAX_ENTRIES {
+        return;
+    }
+    files.sort_by_key(|(t, _)| std::cmp::Reverse(*t));
+    let removed = files.len() - MAX_ENTRIES;
+    for (_, p) in files.into_iter().skip(MAX_ENTRIES) {
+        let _ = std::fs::remove_file(p);
+    }
+    debug!("commit_cache: pruned {removed} entr(ies)");
+}
+
+/// Patch lines of commit `id` (as returned by `Vcs::diff_commit`). Blocking.
+pub fn diff(vcs: &dyn Vcs, id: &str) -> Result<Vec<String>, String> {
+    let cacheable = is_full_oid(id);
+    if cacheable {
+        if let Some(lines) = read::<Vec<String>>(id, "diff") {
+            trace!("commit_cache: diff hit {id}");
+            return Ok(lines);
+        }
+    }
+    let lines = vcs.diff_commit(id).map_err(|e| e.to_string())?;
+    if cacheable && lines.iter().map(|l| l.len() + 1).sum::<usize>() <= MAX_DIFF_BYTES {
+        write(id, "diff", &lines);
+    }
+    Ok(lines)
+}
+
+/// Diffstat and file list of commit `id`: the backend's numstat when it has one, otherwise
+/// counted from the patch text. Blocking.
+pub fn stats(vcs: &dyn Vcs, id: &str) -> Result<CommitStats, String> {
+    let cacheable = is_full_oid(id);
+    if cacheable {
+        if let Some(stats) = read::<CommitStats>(id, "filestats") {
+            trace!("commit_cache: stats hit {id}");
+            return Ok(stats);
+        }
+    }
+    let stats = match vcs.commit_file_stats(id) {
+        Ok(files) => from_numstat(id, files),
+        Err(VcsError::Unsupported(_)) => summarize(id, &diff(vcs, id)?),
+        Err(e) => return Err(e.to_string()),
+    };
+    if cacheable {
+        write(id, "filestats", &stats);
+    }
+    Ok(stats)
+}
+
+fn from_numstat(id: &str, files: Vec<FileChangeStat>) -> CommitStats {
+    let files: Vec<FileStat> = files
+        .into_iter()
+        .map(|f| FileStat {
+            mode_change: f.mode_change(),
+            binary: f.additions.is_none(),
+            additions: f.additions.unwrap_or(0),
+            deletions: f.deletions.unwrap_or(0),
+            status: if f.status == "C" { "A".into() } else { f.status },
+            path: f.path,
+            old_path: f.old_path,
+            size_delta: f.size_delta,
+        })
+        .collect();
+    CommitStats {
+        id: id.to_string(),
+        additions: files.iter().map(|f| f.additions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+        files,
+    }
+}
+
+/// Contents of blob `oid`. Blocking.
+pub fn blob(vcs: &dyn Vcs, oid: &str) -> Result<BlobText, String> {
+    let cacheable = is_full_oid(oid);
+    if cacheable {
+        if let Some(b) = read::<BlobText>(oid, "blob") {
+            trace!("commit_cache: blob hit {oid}");
+            return Ok(b);
+        }
+    }
+    let bytes = vcs.read_blob(oid).map_err(|e| e.to_string())?;
+    let size = bytes.len();
+    let text = if bytes.iter().take(8000).any(|&b| b == 0) { None } else { String::from_utf8(bytes).ok() };
+    let b = BlobText { oid: oid.to_string(), size, text };
+    if cacheable && size <= MAX_DIFF_BYTES {
+        write(oid, "blob", &b);
+    }
+    Ok(b)
+}
+
+/// Build the diffstat from unified patch lines.
+fn summarize(id: &str, lines: &[String]) -> CommitStats {
+    let mut files: Vec<FileStat> = Vec::new();
+    let mut in_hunk = false;
+
+    for l in lines {
+        if let Some(rest) = l.strip_prefix("diff --git ") {
+            // "a/<path> b/<path>"; prefer the b side, like the inspector does
+            let path = rest
+                .rsplit_once(" b/")
+                .map(|(_, b)| b)
+                .unwrap_or_else(|| rest.split(' ').nth(1).unwrap_or(rest));
+            files.push(FileStat {
+                path: path.to_string(),
+                status: "M".into(),
+                additions: 0,
+                deletions: 0,
+                binary: false,
+                size_delta: 0,
+                old_path: None,
+                mode_change: None,
+            });
+            in_hunk = false;
+            continue;
+        }
+        let Some(f) = files.last_mut() else { continue };
+        if in_hunk {
+            if l.starts_with('+') {
+                f.additions += 1;
+            } else if l.starts_with('-') {
+                f.deletions += 1;
+            } else if l.starts_with("@@") || l.starts_with(' ') || l.starts_with('\\') {
+                // context, next hunk or "\ No newline at end of file"
+            } else {
+                in_hunk = false;
+            }
+            continue;
+        }
+        if l.starts_with("@@") {
+            in_hunk = true;
+        } else if l.starts_with("new file mode") {
+            f.status = "A".into();
+        } else if l.starts_with("deleted file mode") {
+            f.status = "D".into();
+        } else if let Some(from) = l.strip_prefix("rename from ") {
+            f.old_path = Some(from.to_string());
+        } else if let Some(to) = l.strip_prefix("rename to ") {
+            f.status = "R".into();
+            f.path = to.to_string();
+        } else if l.starts_with("Binary files ") || l == "GIT binary patch" {
+            f.binary = true;
+        }
+    }
+
+    CommitStats {
+        id: id.to_string(),
+        additions: files.iter().map(|f| f.additions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+        files,
+    }
+}
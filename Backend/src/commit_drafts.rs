@@ -0,0 +1,184 @@
+//! Commit message drafts and repository-provided commit templates.
+//!
+//! The message being written is saved per repository and branch as the user types, to
+//! `commit-drafts.json` in the app data dir, so a crash or restart does not lose it; a
+//! successful commit on the branch discards the draft. When there is no draft, the commit
+//! box can start from the repository's template: `commit.template` from its config, else
+//! a template under `.github/`, else `.gitmessage`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use directories::ProjectDirs;
+use log::{debug, warn};
+use openvcs_core::Vcs;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Repository files tried, in order, when `commit.template` is not set.
+const TEMPLATE_FILES: &[&str] = &[
+    ".github/COMMIT_TEMPLATE.md",
+    ".github/commit_template.md",
+    ".github/COMMIT_TEMPLATE",
+    ".github/.gitmessage",
+    ".gitmessage",
+];
+
+/// Serializes read-modify-write of the drafts file.
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommitDraft {
+    pub summary: String,
+    pub description: String,
+    /// Unix time of the last save.
+    pub updated: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitTemplate {
+    /// Where it came from, relative to the working tree when inside it.
+    pub source: String,
+    pub text: String,
+}
+
+/// What the commit box should start with for the current branch.
+#[derive(Debug, Clone, Serialize)]
+pub struct DraftState {
+    pub branch: String,
+    pub draft: Option<CommitDraft>,
+    pub template: Option<CommitTemplate>,
+}
+
+fn drafts_path() -> PathBuf {
+    if let Some(pd) = ProjectDirs::from("dev", "OpenVCS", "OpenVCS") {
+        pd.data_dir().join("commit-drafts.json")
+    } else {
+        PathBuf::from("commit-drafts.json")
+    }
+}
+
+type Drafts = BTreeMap<String, BTreeMap<String, CommitDraft>>;
+
+fn load() -> Drafts {
+    std::fs::read_to_string(drafts_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save(all: &Drafts) -> Result<(), String> {
+    let p = drafts_path();
+    if let Some(parent) = p.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(all).map_err(|e| e.to_string())?;
+    // Write then rename, so a crash mid-save keeps the previous drafts.
+    let tmp = p.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &p).map_err(|e| e.to_string())
+}
+
+fn repo_key(workdir: &Path) -> String {
+    crate::paths::key(workdir)
+}
+
+/// Drafts are kept per branch; a detached HEAD has one shared slot.
+fn branch_key(vcs: &dyn Vcs) -> String {
+    vcs.current_branch().ok().flatten().unwrap_or_else(|| "HEAD".into())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Edit the drafts of `workdir` under the file lock and persist them.
+fn edit(workdir: &Path, f: impl FnOnce(&mut BTreeMap<String, CommitDraft>)) -> Result<(), String> {
+    let _guard = LOCK.lock();
+    let mut all = load();
+    let key = repo_key(workdir);
+    let drafts = all.entry(key.clone()).or_default();
+    f(drafts);
+    if drafts.is_empty() {
+        all.remove(&key);
+    }
+    save(&all)
+}
+
+/// Save the message being written on the current branch; an empty one removes the draft.
+pub fn save_draft(vcs: &dyn Vcs, summary: &str, description: &str) -> Result<(), String> {
+    let branch = branch_key(vcs);
+    let empty = summary.trim().is_empty() && description.trim().is_empty();
+    edit(vcs.workdir(), |drafts| {
+        if empty {
+            drafts.remove(&branch);
+        } else {
+            let draft = CommitDraft { summary: summary.into(), description: description.into(), updated: now_secs() };
+            drafts.insert(branch.clone(), draft);
+        }
+    })
+}
+
+/// Discard the current branch's draft after it was committed. Failures are logged only.
+pub fn clear(vcs: &dyn Vcs) {
+    let branch = branch_key(vcs);
+    if let Err(e) = edit(vcs.workdir(), |drafts| { drafts.remove(&branch); }) {
+        warn!("commit_drafts: cannot clear the draft of {branch}: {e}");
+    }
+}
+
+/// The current branch's draft and the repository's template.
+pub fn state(vcs: &dyn Vcs) -> DraftState {
+    let branch = branch_key(vcs);
+    let draft = load().remove(&repo_key(vcs.workdir())).and_then(|mut d| d.remove(&branch));
+    DraftState { branch, draft, template: template(vcs) }
+}
+
+/// `~/` expanded; relative paths are taken from the working tree, as git does for hooks.
+fn resolve(workdir: &Path, path: &str) -> PathBuf {
+    match path.strip_prefix("~/").zip(dirs::home_dir()) {
+        Some((rest, home)) => home.join(rest),
+        None => workdir.join(path),
+    }
+}
+
+/// Template text without comment lines: `#` lines for git templates, `<!-- -->` blocks for
+/// Markdown ones (where `#` starts a heading).
+fn strip_comments(text: &str, markdown: bool) -> String {
+    let mut out = String::new();
+    if markdown {
+        let mut rest = text;
+        while let Some(start) = rest.find("<!--") {
+            out.push_str(&rest[..start]);
+            rest = rest[start..].find("-->").map_or("", |end| &rest[start + end + 3..]);
+        }
+        out.push_str(rest);
+    } else {
+        for line in text.lines().filter(|l| !l.starts_with('#')) {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out.trim().to_string()
+}
+
+fn template(vcs: &dyn Vcs) -> Option<CommitTemplate> {
+    let workdir = vcs.workdir();
+    let configured = vcs.config_value("commit.template").ok().flatten().map(|v| v.value);
+    let candidates: Vec<PathBuf> = match configured {
+        Some(p) => vec![resolve(workdir, p.trim())],
+        None => TEMPLATE_FILES.iter().map(|f| workdir.join(f)).collect(),
+    };
+    candidates.into_iter().find_map(|path| {
+        let raw = std::fs::read_to_string(&path).ok()?;
+        let markdown = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("md"));
+        let text = strip_comments(&raw, markdown);
+        if text.is_empty() {
+            return None;
+        }
+        let source = path.strip_prefix(workdir).unwrap_or(&path).display().to_string();
+        debug!("commit_drafts: template from {source}");
+        Some(CommitTemplate { source, text })
+    })
+}
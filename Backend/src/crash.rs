@@ -0,0 +1,255 @@
+//! Local crash reports (`general.crash_reports`).
+//!
+//! A panic hook writes a JSON report (message, location, backtrace, thread, OS, backend and
+//! the last operation that was running) to `crashes/` in the app data dir. Nothing is sent
+//! automatically: on the next launch the UI lists pending reports and the user chooses to
+//! submit or dismiss each one. Submission needs an endpoint compiled in
+//! (`OPENVCS_CRASH_URL`); without one, reports can only be dismissed.
+//!
+//! The hook only `try_lock`s shared state (the last operation, the log ring) and doesn't
+//! log, so a panic while one of those is held can't deadlock it; if the JSON report can't be
+//! written, a plain-text one is.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Submission endpoint, fixed at build time.
+const ENDPOINT: Option<&str> = option_env!("OPENVCS_CRASH_URL");
+/// Reports kept on disk; older ones are pruned when a new one is written.
+const MAX_REPORTS: usize = 20;
+/// Log lines from the ring attached to each report.
+const LOG_TAIL: usize = 40;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static LAST_OP: Mutex<Option<OpContext>> = Mutex::new(None);
+
+/// The most recent operation started through the job pipeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpContext {
+    pub op: String,
+    pub backend: String,
+    pub job: Option<String>,
+    pub started_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub time: i64,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub thread: String,
+    pub message: String,
+    pub location: Option<String>,
+    pub backtrace: String,
+    /// Backend used by the last operation, if any ran.
+    pub backend: Option<String>,
+    pub last_op: Option<OpContext>,
+    pub log_tail: Vec<String>,
+}
+
+/// What the launch prompt offers: the pending reports, and whether they can be sent.
+#[derive(Debug, Clone, Serialize)]
+pub struct Pending {
+    pub reports: Vec<CrashSummary>,
+    pub can_submit: bool,
+}
+
+/// What the launch prompt shows for each pending report.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashSummary {
+    pub id: String,
+    pub time: i64,
+    pub message: String,
+    pub last_op: Option<String>,
+}
+
+fn crash_dir() -> PathBuf {
+    if let Some(pd) = ProjectDirs::from("dev", "OpenVCS", "OpenVCS") {
+        pd.data_dir().join("crashes")
+    } else {
+        PathBuf::from("crashes")
+    }
+}
+
+fn report_path(id: &str) -> Option<PathBuf> {
+    // ids are generated by us; refuse anything that could escape the directory
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    Some(crash_dir().join(format!("{id}.json")))
+}
+
+/// Apply the `general.crash_reports` setting.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Remember the operation now running, for context if it panics.
+pub fn note_op(op: &str, backend: &str) {
+    let ctx = OpContext {
+        op: op.to_string(),
+        backend: backend.to_string(),
+        job: crate::logging::current_job(),
+        started_at: OffsetDateTime::now_utc().unix_timestamp(),
+    };
+    if let Ok(mut cur) = LAST_OP.lock() {
+        *cur = Some(ctx);
+    }
+}
+
+/// Install the panic hook (chained before the default one, so stderr output is unchanged).
+pub fn install(enabled: bool) {
+    set_enabled(enabled);
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if ENABLED.load(Ordering::Relaxed) {
+            // Not through `log`: the panic may have happened inside the logger
+            match write_report(info) {
+                Ok(path) => eprintln!("crash: report written to {}", path.display()),
+                Err(e) => eprintln!("crash: failed to write report: {e}"),
+            }
+        }
+        default_hook(info);
+    }));
+}
+
+fn panic_message(info: &std::panic::PanicHookInfo<'_>) -> String {
+    info.payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "<non-string panic payload>".into())
+}
+
+fn write_report(info: &std::panic::PanicHookInfo<'_>) -> std::io::Result<PathBuf> {
+    let dir = crash_dir();
+    std::fs::create_dir_all(&dir)?;
+    match write_json(info, &dir) {
+        Ok(path) => Ok(path),
+        Err(e) => {
+            let path = dir.join(format!("crash-{}.txt", OffsetDateTime::now_utc().unix_timestamp_nanos()));
+            let location = info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())).unwrap_or_default();
+            let text = format!(
+                "OpenVCS {} ({} {})\n{} at {location}\n(JSON report failed: {e})\n\n{}\n",
+                env!("CARGO_PKG_VERSION"),
+                std::env::consts::OS,
+                std::env::consts::ARCH,
+                panic_message(info),
+                std::backtrace::Backtrace::force_capture(),
+            );
+            std::fs::write(&path, text)?;
+            Ok(path)
+        }
+    }
+}
+
+fn write_json(info: &std::panic::PanicHookInfo<'_>, dir: &std::path::Path) -> std::io::Result<PathBuf> {
+    let message = panic_message(info);
+    let now = OffsetDateTime::now_utc();
+    let last_op = LAST_OP.try_lock().ok().and_then(|g| g.clone());
+    let log_tail = crate::logging::try_tail(LOG_TAIL)
+        .into_iter()
+        .map(|e| format!("{} {:?} {}: {}", e.ts_ms, e.level, e.target, e.message))
+        .collect();
+
+    let report = CrashReport {
+        id: format!("crash-{}", now.unix_timestamp_nanos()),
+        time: now.unix_timestamp(),
+        app_version: env!("CARGO_PKG_VERSION").into(),
+        os: std::env::consts::OS.into(),
+        arch: std::env::consts::ARCH.into(),
+        thread: std::thread::current().name().unwrap_or("<unnamed>").into(),
+        message,
+        location: info.location().map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        backend: last_op.as_ref().map(|o| o.backend.clone()),
+        last_op,
+        log_tail,
+    };
+
+    let path = dir.join(format!("{}.json", report.id));
+    std::fs::write(&path, serde_json::to_vec_pretty(&report)?)?;
+    prune(MAX_REPORTS);
+    Ok(path)
+}
+
+fn load_all() -> Vec<CrashReport> {
+    let Ok(entries) = std::fs::read_dir(crash_dir()) else { return Vec::new() };
+    let mut out: Vec<CrashReport> = entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+        .filter_map(|e| std::fs::read_to_string(e.path()).ok())
+        .filter_map(|s| serde_json::from_str(&s).ok())
+        .collect();
+    out.sort_by_key(|r| std::cmp::Reverse(r.time));
+    out
+}
+
+fn prune(keep: usize) {
+    for old in load_all().into_iter().skip(keep) {
+        if let Some(p) = report_path(&old.id) { let _ = std::fs::remove_file(p); }
+    }
+}
+
+/// Reports waiting for the user's decision, newest first; none while crash reports are off.
+pub fn pending() -> Pending {
+    let reports = if ENABLED.load(Ordering::Relaxed) { load_all() } else { Vec::new() };
+    Pending {
+        reports: reports
+            .into_iter()
+            .map(|r| CrashSummary {
+                id: r.id,
+                time: r.time,
+                message: r.message,
+                last_op: r.last_op.map(|o| format!("{} ({})", o.op, o.backend)),
+            })
+            .collect(),
+        can_submit: ENDPOINT.is_some(),
+    }
+}
+
+/// Full report, so the user can see exactly what would be submitted.
+pub fn load(id: &str) -> Result<CrashReport, String> {
+    let path = report_path(id).ok_or("invalid crash report id")?;
+    let s = std::fs::read_to_string(&path).map_err(|e| format!("read {}: {e}", path.display()))?;
+    serde_json::from_str(&s).map_err(|e| format!("parse crash report: {e}"))
+}
+
+/// Delete a report without sending it.
+pub fn dismiss(id: &str) -> Result<(), String> {
+    let path = report_path(id).ok_or("invalid crash report id")?;
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("remove {}: {e}", path.display())),
+    }
+}
+
+/// Send a report and remove it locally on success. Blocking.
+pub fn submit(id: &str) -> Result<(), String> {
+    let endpoint = ENDPOINT.ok_or("crash report submission is not configured in this build")?;
+    let report = load(id)?;
+    tauri::async_runtime::block_on(async {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| e.to_string())?;
+        client
+            .post(endpoint)
+            .json(&report)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("submit failed: {e}"))
+    })?;
+    log::info!("crash: submitted {id}");
+    dismiss(id)
+}
@@ -0,0 +1,99 @@
+//! Desktop shell integration: the window title and the OS "recent documents" list.
+//!
+//! The main window is titled `repo-name (branch) — OpenVCS` after the current repository,
+//! refreshed when a repository is opened and after each status refresh (which follows
+//! checkouts). Repositories opened by the user are also registered with the OS so they
+//! can be reopened from the shell: the Windows jump list / Recent items, the macOS dock
+//! and "Open Recent" menu, and GTK's recently-used list on Linux.
+
+use std::path::Path;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::state::AppState;
+
+const APP_NAME: &str = "OpenVCS";
+
+/// Title for the current repository, or the bare app name when none is open.
+fn title(state: &AppState) -> String {
+    let Some(repo) = state.current_repo() else { return APP_NAME.to_string() };
+    let workdir = repo.inner().workdir();
+    let name = workdir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| workdir.display().to_string());
+    match repo.inner().current_branch() {
+        Ok(Some(branch)) => format!("{name} ({branch}) — {APP_NAME}"),
+        _ => format!("{name} — {APP_NAME}"),
+    }
+}
+
+/// Retitle the main window after the current repository and branch.
+pub fn update_title<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = app.get_webview_window("main") else { return };
+    let title = title(&app.state::<AppState>());
+    if window.title().ok().as_deref() == Some(title.as_str()) {
+        return;
+    }
+    if let Err(e) = window.set_title(&title) {
+        log::warn!("desktop: cannot set window title: {e}");
+    }
+}
+
+/// Register `path` with the OS recent-documents list. Best effort; failures are logged.
+pub fn note_recent<R: Runtime>(app: &AppHandle<R>, path: &Path) {
+    let path = path.to_path_buf();
+    // AppKit and GTK must be called from the main thread.
+    if let Err(e) = app.run_on_main_thread(move || add_recent(&path)) {
+        log::warn!("desktop: cannot schedule recent-documents update: {e}");
+    }
+}
+
+#[cfg(windows)]
+fn add_recent(path: &Path) {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::Win32::UI::Shell::{SHAddToRecentDocs, SHARD_PATHW};
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    // SAFETY: `wide` is a NUL-terminated UTF-16 path that outlives the call.
+    unsafe { SHAddToRecentDocs(SHARD_PATHW.0 as u32, Some(wide.as_ptr().cast())) };
+    log::debug!("desktop: added {} to recent documents", path.display());
+}
+
+#[cfg(target_os = "macos")]
+fn add_recent(path: &Path) {
+    use objc2::MainThreadMarker;
+    use objc2_app_kit::NSDocumentController;
+    use objc2_foundation::{NSString, NSURL};
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        log::warn!("desktop: recent documents must be updated on the main thread");
+        return;
+    };
+    let path_str = NSString::from_str(&path.to_string_lossy());
+    // SAFETY: called on the main thread, as AppKit requires.
+    unsafe {
+        let url = NSURL::fileURLWithPath(&path_str);
+        NSDocumentController::sharedDocumentController(mtm).noteNewRecentDocumentURL(&url);
+    }
+    log::debug!("desktop: added {} to recent documents", path.display());
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn add_recent(path: &Path) {
+    use gtk::prelude::RecentManagerExt;
+
+    let uri = match gtk::glib::filename_to_uri(path, None) {
+        Ok(uri) => uri,
+        Err(e) => {
+            log::warn!("desktop: no URI for {}: {e}", path.display());
+            return;
+        }
+    };
+    let Some(manager) = gtk::RecentManager::default() else { return };
+    if manager.add_item(&uri) {
+        log::debug!("desktop: added {uri} to recent documents");
+    } else {
+        log::warn!("desktop: GTK refused recent document {uri}");
+    }
+}
@@ -0,0 +1,221 @@
+//! Environment doctor: probes the tools and configuration Git operations depend on
+//! (git, libgit2, git-lfs, credential helper, SSH agent, identity) and reports
+//! each as a finding with a hint the user can act on.
+
+use std::process::Command;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity { Ok, Warn, Error }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    /// Stable identifier (e.g. `git`, `ssh-agent`) for the UI and support tickets.
+    pub id: &'static str,
+    pub label: &'static str,
+    pub severity: Severity,
+    pub detail: String,
+    /// What to do about it; `None` when nothing needs doing.
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorReport {
+    pub git_version: Option<String>,
+    pub libgit2_version: Option<String>,
+    pub lfs_version: Option<String>,
+    pub credential_helper: Option<String>,
+    pub ssh_agent: Option<String>,
+    pub identity: Option<(String, String)>,
+    pub findings: Vec<Finding>,
+}
+
+impl Finding {
+    pub(crate) fn ok(id: &'static str, label: &'static str, detail: impl Into<String>) -> Self {
+        Self { id, label, severity: Severity::Ok, detail: detail.into(), hint: None }
+    }
+    pub(crate) fn warn(id: &'static str, label: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { id, label, severity: Severity::Warn, detail: detail.into(), hint: Some(hint.into()) }
+    }
+    pub(crate) fn error(id: &'static str, label: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { id, label, severity: Severity::Error, detail: detail.into(), hint: Some(hint.into()) }
+    }
+}
+
+/// Run `program args…` and return (exit code, trimmed stdout). `None` if it could not be spawned.
+pub(crate) fn probe(program: impl AsRef<std::ffi::OsStr>, args: &[&str]) -> Option<(i32, String)> {
+    let mut cmd = Command::new(program);
+    openvcs_git::no_window(&mut cmd);
+    capture(cmd.args(args))
+}
+
+/// [`probe`] for git, set up as the system backend runs it (`openvcs_git::git_command`).
+pub(crate) fn probe_git(args: &[&str]) -> Option<(i32, String)> {
+    capture(openvcs_git::git_command(None).args(args))
+}
+
+fn capture(cmd: &mut Command) -> Option<(i32, String)> {
+    let out = cmd.env("GIT_TERMINAL_PROMPT", "0").output().ok()?;
+    let stdout = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    Some((out.status.code().unwrap_or(-1), stdout))
+}
+
+/// `git config --global --get <key>`, when git is available and the key is set.
+pub(crate) fn git_config_global(key: &str) -> Option<String> {
+    match probe_git(&["config", "--global", "--get", key]) {
+        Some((0, v)) if !v.is_empty() => Some(v),
+        _ => None,
+    }
+}
+
+/// Probe the environment. Blocking; run off the async runtime.
+pub fn run() -> DoctorReport {
+    let mut findings = Vec::new();
+
+    // git executable
+    let git = crate::git_exe::program();
+    let git_version = match probe_git(&["--version"]) {
+        Some((0, v)) => {
+            let v = openvcs_git::version_from_output(&v);
+            let min = crate::git_exe::MIN_VERSION;
+            match openvcs_git::parse_version(&v) {
+                Some(found) if found < min => findings.push(Finding::warn(
+                    "git", "Git executable",
+                    format!("git {v} is older than {}.{}.{}", min.0, min.1, min.2),
+                    "Update Git, or set a newer one as the Git executable in Settings.",
+                )),
+                _ => findings.push(Finding::ok("git", "Git executable", format!("git {v} ({})", git.display()))),
+            }
+            Some(v)
+        }
+        Some((code, _)) => {
+            findings.push(Finding::error(
+                "git", "Git executable",
+                format!("`git --version` exited with {code}"),
+                "Reinstall Git; the installed executable does not run correctly.",
+            ));
+            None
+        }
+        None => {
+            findings.push(Finding::error(
+                "git", "Git executable",
+                format!("{} could not be run", git.display()),
+                "Install Git (https://git-scm.com/downloads) and make sure it is on PATH or set as the Git executable in Settings, or switch to the libgit2 backend.",
+            ));
+            None
+        }
+    };
+    let has_git = git_version.is_some();
+
+    // libgit2 (linked into this build)
+    #[cfg(feature = "with-git-libgit2")]
+    let libgit2_version = Some(openvcs_git_libgit2::libgit2_version());
+    #[cfg(not(feature = "with-git-libgit2"))]
+    let libgit2_version: Option<String> = None;
+    match &libgit2_version {
+        Some(v) => findings.push(Finding::ok("libgit2", "libgit2", format!("libgit2 {v}"))),
+        None => findings.push(Finding::warn(
+            "libgit2", "libgit2",
+            "this build does not include the libgit2 backend",
+            "Use the system Git backend.",
+        )),
+    }
+
+    // git-lfs
+    let lfs_version = if has_git {
+        match probe_git(&["lfs", "version"]) {
+            Some((0, v)) => {
+                findings.push(Finding::ok("git-lfs", "Git LFS", v.clone()));
+                Some(v)
+            }
+            _ => {
+                findings.push(Finding::warn(
+                    "git-lfs", "Git LFS",
+                    "git-lfs is not installed",
+                    "Install Git LFS (https://git-lfs.com) and run `git lfs install` if your repositories use LFS.",
+                ));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // credential helper
+    let credential_helper = if has_git {
+        match probe_git(&["config", "--get", "credential.helper"]) {
+            Some((0, v)) if !v.is_empty() => {
+                findings.push(Finding::ok("credential-helper", "Credential helper", v.clone()));
+                Some(v)
+            }
+            _ => {
+                findings.push(Finding::warn(
+                    "credential-helper", "Credential helper",
+                    "no credential.helper is configured",
+                    "HTTPS remotes will fail without a way to supply credentials; configure one (e.g. Git Credential Manager) or use SSH.",
+                ));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // SSH agent: `ssh-add -l` exits 0 (keys loaded), 1 (no identities), 2 (no agent)
+    let ssh_agent = match probe("ssh-add", &["-l"]) {
+        Some((0, keys)) => {
+            let n = keys.lines().filter(|l| !l.trim().is_empty()).count();
+            let detail = format!("agent running, {n} key(s) loaded");
+            findings.push(Finding::ok("ssh-agent", "SSH agent", detail.clone()));
+            Some(detail)
+        }
+        Some((1, _)) => {
+            findings.push(Finding::warn(
+                "ssh-agent", "SSH agent",
+                "agent running, but no keys are loaded",
+                "Add your key with `ssh-add` so SSH remotes can authenticate non-interactively.",
+            ));
+            Some("agent running, no keys loaded".into())
+        }
+        Some(_) => {
+            findings.push(Finding::warn(
+                "ssh-agent", "SSH agent",
+                "no SSH agent is reachable",
+                "Start ssh-agent (or enable the OpenSSH Authentication Agent service on Windows) and add your key; OpenVCS cannot prompt for SSH passphrases.",
+            ));
+            None
+        }
+        None => {
+            findings.push(Finding::warn(
+                "ssh-agent", "SSH agent",
+                "ssh-add was not found",
+                "Install an OpenSSH client if you use SSH remotes.",
+            ));
+            None
+        }
+    };
+
+    // default identity
+    let identity = if has_git {
+        match (git_config_global("user.name"), git_config_global("user.email")) {
+            (Some(name), Some(email)) => {
+                findings.push(Finding::ok("identity", "Default identity", format!("{name} <{email}>")));
+                Some((name, email))
+            }
+            _ => {
+                findings.push(Finding::warn(
+                    "identity", "Default identity",
+                    "user.name and/or user.email are not set globally",
+                    "Set them with `git config --global user.name \"Your Name\"` and `git config --global user.email you@example.com`, or per repository in Repository Settings.",
+                ));
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    DoctorReport { git_version, libgit2_version, lfs_version, credential_helper, ssh_agent, identity, findings }
+}
@@ -0,0 +1,152 @@
+//! Commit history export (CSV or JSON) for audit reports.
+//!
+//! [`write`] walks the history selected by a [`LogQuery`] in batches, looks up the full
+//! metadata of each batch ([`Vcs::commit_records`]) and streams it to a temporary file next
+//! to the destination, which replaces the destination once the export is complete.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use log::{debug, info};
+use openvcs_core::models::{CommitRecord, LogQuery};
+use openvcs_core::Vcs;
+use serde::{Deserialize, Serialize};
+
+/// Commits read and written per batch.
+const BATCH: usize = 500;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat { Csv, Json }
+
+impl ExportFormat {
+    /// Guess from the file extension (`.json`, otherwise CSV).
+    pub fn from_path(p: &Path) -> Self {
+        match p.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub path: String,
+    pub format: ExportFormat,
+    pub commits: usize,
+}
+
+const CSV_HEADER: &str = "id,parents,author_name,author_email,author_date,committer_name,committer_email,committer_date,summary,body";
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn csv_row(r: &CommitRecord) -> String {
+    [
+        &r.id,
+        &r.parents.join(" "),
+        &r.author_name,
+        &r.author_email,
+        &r.author_date,
+        &r.committer_name,
+        &r.committer_email,
+        &r.committer_date,
+        &r.summary,
+        &r.body,
+    ]
+    .map(|f| csv_field(f))
+    .join(",")
+}
+
+fn tmp_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+/// Export the commits selected by `query` (`skip`/`limit` apply; `limit == 0` exports
+/// everything) to `dest`. `on_progress` gets the running count after each batch. Blocking.
+pub fn write(
+    vcs: &dyn Vcs,
+    query: &LogQuery,
+    format: ExportFormat,
+    dest: &Path,
+    on_progress: impl Fn(usize),
+) -> Result<ExportReport, String> {
+    let tmp = tmp_path(dest);
+    let res = write_to(vcs, query, format, &tmp, on_progress)
+        .and_then(|n| std::fs::rename(&tmp, dest).map(|_| n).map_err(|e| e.to_string()));
+    match res {
+        Ok(commits) => {
+            info!("export: {commits} commit(s) to {} ({format:?})", dest.display());
+            Ok(ExportReport { path: dest.to_string_lossy().to_string(), format, commits })
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+fn write_to(
+    vcs: &dyn Vcs,
+    query: &LogQuery,
+    format: ExportFormat,
+    path: &Path,
+    on_progress: impl Fn(usize),
+) -> Result<usize, String> {
+    let mut out = BufWriter::new(File::create(path).map_err(|e| e.to_string())?);
+    let mut walk = vcs.history_walk(query).map_err(|e| e.to_string())?;
+    let (mut skip, limit) = (query.skip as usize, query.limit as usize);
+    let mut written = 0usize;
+
+    match format {
+        ExportFormat::Csv => writeln!(out, "{CSV_HEADER}"),
+        ExportFormat::Json => write!(out, "["),
+    }
+    .map_err(|e| e.to_string())?;
+
+    loop {
+        if openvcs_core::cancel::is_cancelled() {
+            return Err("Export cancelled".into());
+        }
+        let batch = walk.next_batch(BATCH).map_err(|e| e.to_string())?;
+        let done = batch.len() < BATCH;
+        let mut ids: Vec<String> = batch.into_iter().map(|c| c.id).collect();
+        let skipped = skip.min(ids.len());
+        ids.drain(..skipped);
+        skip -= skipped;
+        if limit > 0 {
+            ids.truncate(limit - written);
+        }
+
+        for r in vcs.commit_records(&ids).map_err(|e| e.to_string())? {
+            match format {
+                ExportFormat::Csv => writeln!(out, "{}", csv_row(&r)),
+                ExportFormat::Json => {
+                    let json = serde_json::to_string(&r).map_err(|e| e.to_string())?;
+                    write!(out, "{}\n  {json}", if written == 0 { "" } else { "," })
+                }
+            }
+            .map_err(|e| e.to_string())?;
+            written += 1;
+        }
+        debug!("export: {written} commit(s) written");
+        on_progress(written);
+        if done || (limit > 0 && written >= limit) {
+            break;
+        }
+    }
+
+    if format == ExportFormat::Json {
+        writeln!(out, "{}]", if written == 0 { "" } else { "\n" }).map_err(|e| e.to_string())?;
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    Ok(written)
+}
@@ -0,0 +1,266 @@
+//! Releases on the repository's forge (GitHub, GitHub Enterprise, Forgejo/Gitea).
+//!
+//! [`ForgeRepo::detect`] works out the forge and `owner/repo` from a remote URL, using
+//! `integrations.host_overrides` / `issue_provider` for hosts it cannot tell by name.
+//! [`create_release`] publishes a release for a tag that is already on the remote and
+//! uploads the asset files one by one. Tokens come from the environment (`GH_TOKEN`,
+//! `FORGEJO_TOKEN`, …) or, for GitHub, a logged-in `gh`; they are never stored.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use log::{debug, info};
+use serde::Serialize;
+
+use crate::settings::{Integrations, IssueProvider};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    Github,
+    Forgejo,
+}
+
+impl ForgeKind {
+    fn label(self) -> &'static str {
+        match self {
+            ForgeKind::Github => "GitHub",
+            ForgeKind::Forgejo => "Forgejo",
+        }
+    }
+
+    /// Environment variables checked for a token, in order.
+    fn token_vars(self) -> &'static [&'static str] {
+        match self {
+            ForgeKind::Github => &["GH_TOKEN", "GITHUB_TOKEN"],
+            ForgeKind::Forgejo => &["FORGEJO_TOKEN", "GITEA_TOKEN"],
+        }
+    }
+}
+
+/// A repository on a forge.
+#[derive(Debug, Clone, Serialize)]
+pub struct ForgeRepo {
+    pub kind: ForgeKind,
+    pub host: String,
+    pub owner: String,
+    pub name: String,
+}
+
+/// A published release.
+#[derive(Debug, Clone, Serialize)]
+pub struct Release {
+    pub id: u64,
+    /// Web page of the release.
+    pub url: String,
+    /// File names of the uploaded assets.
+    pub assets: Vec<String>,
+}
+
+/// What to publish; the tag must already be on the remote.
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseRequest {
+    pub tag: String,
+    pub title: String,
+    pub notes: String,
+    pub assets: Vec<PathBuf>,
+    pub draft: bool,
+    pub prerelease: bool,
+}
+
+impl ForgeRepo {
+    /// The forge behind remote `url`.
+    pub fn detect(url: &str, integrations: &Integrations) -> Result<Self, String> {
+        let parsed = crate::validate::parse_git_url(url)?;
+        let host = parsed.host.ok_or_else(|| format!("{url} is not a hosted repository"))?;
+        let segs: Vec<&str> = parsed.path.split(['/', ':']).filter(|s| !s.is_empty()).collect();
+        let [.., owner, name] = segs.as_slice() else {
+            return Err(format!("Cannot tell the owner and repository from {url}"));
+        };
+
+        let host_lc = host.to_ascii_lowercase();
+        let provider = integrations
+            .host_overrides
+            .get(&host_lc)
+            .copied()
+            .unwrap_or(integrations.issue_provider);
+        let kind = match provider {
+            IssueProvider::Github => ForgeKind::Github,
+            IssueProvider::Forgejo => ForgeKind::Forgejo,
+            IssueProvider::Gitlab => return Err("Publishing releases on GitLab is not supported".into()),
+            IssueProvider::Auto if host_lc == "github.com" => ForgeKind::Github,
+            IssueProvider::Auto
+                if host_lc == "codeberg.org" || host_lc.contains("forgejo") || host_lc.contains("gitea") =>
+            {
+                ForgeKind::Forgejo
+            }
+            IssueProvider::Auto => {
+                return Err(format!("Cannot tell which forge {host} runs; set it under Settings → Integrations"))
+            }
+        };
+        Ok(Self { kind, host: host_lc, owner: owner.to_string(), name: name.trim_end_matches(".git").to_string() })
+    }
+
+    fn api_base(&self) -> String {
+        match self.kind {
+            ForgeKind::Github if self.host == "github.com" => "https://api.github.com".into(),
+            ForgeKind::Github => format!("https://{}/api/v3", self.host),
+            ForgeKind::Forgejo => format!("https://{}/api/v1", self.host),
+        }
+    }
+
+    fn auth(&self, token: &str) -> String {
+        match self.kind {
+            ForgeKind::Github => format!("Bearer {token}"),
+            ForgeKind::Forgejo => format!("token {token}"),
+        }
+    }
+
+    /// API token from the environment, or from `gh auth token` for GitHub.
+    pub fn token(&self) -> Result<String, String> {
+        let from_env = self
+            .kind
+            .token_vars()
+            .iter()
+            .find_map(|v| std::env::var(v).ok().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()));
+        let from_cli = || match crate::doctor::probe("gh", &["auth", "token", "--hostname", &self.host]) {
+            Some((0, t)) if !t.is_empty() => Some(t),
+            _ => None,
+        };
+        from_env
+            .or_else(|| (self.kind == ForgeKind::Github).then(from_cli).flatten())
+            .ok_or_else(|| {
+                format!("No {} token found; set {}", self.kind.label(), self.kind.token_vars().join(" or "))
+            })
+    }
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    // No overall timeout: assets can take a while to upload
+    reqwest::Client::builder()
+        .user_agent(concat!("OpenVCS/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(Duration::from_secs(15))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Error text from a failed API response (`message` field when there is one).
+async fn api_error(what: &str, resp: reqwest::Response) -> String {
+    let status = resp.status();
+    let body: serde_json::Value = resp.json().await.unwrap_or_default();
+    match body.get("message").and_then(|m| m.as_str()) {
+        Some(m) => format!("{what}: {m} ({status})"),
+        None => format!("{what}: {status}"),
+    }
+}
+
+/// Content type, head and tail of a `multipart/form-data` body with one file field; the
+/// file is streamed between them. reqwest is built without multipart.
+fn form_file(field: &str, file_name: &str) -> (String, Vec<u8>, Vec<u8>) {
+    let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+    let boundary = format!("openvcs-{nanos:x}");
+    let file_name = file_name.replace(['"', '\r', '\n'], "_");
+    let head = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"{field}\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+    );
+    let tail = format!("\r\n--{boundary}--\r\n");
+    (format!("multipart/form-data; boundary={boundary}"), head.into_bytes(), tail.into_bytes())
+}
+
+async fn upload_asset(
+    client: &reqwest::Client,
+    repo: &ForgeRepo,
+    token: &str,
+    id: u64,
+    upload_url: &str,
+    path: &Path,
+) -> Result<String, String> {
+    use futures_util::{stream, StreamExt};
+    use tokio_util::bytes::Bytes;
+
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| format!("{} has no file name", path.display()))?
+        .to_string();
+    // Streamed from disk: release assets (installers, archives) can be large
+    let file = tokio::fs::File::open(path).await.map_err(|e| format!("{}: {e}", path.display()))?;
+    let len = file.metadata().await.map_err(|e| format!("{}: {e}", path.display()))?.len();
+    let contents = tokio_util::io::ReaderStream::new(file);
+    debug!("forge: uploading {name} ({len} bytes)");
+    let req = match repo.kind {
+        ForgeKind::Github => {
+            // `upload_url` is a URI template: ".../assets{?name,label}"
+            let url = upload_url.split('{').next().unwrap_or(upload_url);
+            client
+                .post(url)
+                .query(&[("name", name.as_str())])
+                .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+                .header(reqwest::header::CONTENT_LENGTH, len)
+                .body(reqwest::Body::wrap_stream(contents))
+        }
+        ForgeKind::Forgejo => {
+            let url = format!("{}/repos/{}/{}/releases/{id}/assets", repo.api_base(), repo.owner, repo.name);
+            let (content_type, head, tail) = form_file("attachment", &name);
+            let total = head.len() as u64 + len + tail.len() as u64;
+            let body = stream::once(async { Ok(Bytes::from(head)) })
+                .chain(contents)
+                .chain(stream::once(async { Ok::<_, std::io::Error>(Bytes::from(tail)) }));
+            client
+                .post(url)
+                .query(&[("name", name.as_str())])
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .header(reqwest::header::CONTENT_LENGTH, total)
+                .body(reqwest::Body::wrap_stream(body))
+        }
+    };
+    let resp = req
+        .header(reqwest::header::AUTHORIZATION, repo.auth(token))
+        .send()
+        .await
+        .map_err(|e| format!("uploading {name} failed: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(api_error(&format!("uploading {name} failed"), resp).await);
+    }
+    Ok(name)
+}
+
+/// Create the release for `req.tag` and upload its assets. When an upload fails the
+/// release stays published and the error names it.
+pub async fn create_release(repo: &ForgeRepo, token: &str, req: &ReleaseRequest) -> Result<Release, String> {
+    let client = http_client()?;
+    let url = format!("{}/repos/{}/{}/releases", repo.api_base(), repo.owner, repo.name);
+    info!("forge: creating release {} on {}/{} ({})", req.tag, repo.owner, repo.name, repo.kind.label());
+    let title = if req.title.trim().is_empty() { req.tag.as_str() } else { req.title.trim() };
+    let resp = client
+        .post(url)
+        .header(reqwest::header::AUTHORIZATION, repo.auth(token))
+        .header(reqwest::header::ACCEPT, "application/json")
+        .json(&serde_json::json!({
+            "tag_name": req.tag,
+            "name": title,
+            "body": req.notes,
+            "draft": req.draft,
+            "prerelease": req.prerelease,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Creating the release: {e}"))?;
+    if !resp.status().is_success() {
+        return Err(api_error("Creating the release", resp).await);
+    }
+    let json: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let id = json.get("id").and_then(|v| v.as_u64()).ok_or("The release response has no id")?;
+    let html_url = json.get("html_url").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+    let upload_url = json.get("upload_url").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+    let mut assets = Vec::with_capacity(req.assets.len());
+    for path in &req.assets {
+        let name = upload_asset(&client, repo, token, id, &upload_url, path)
+            .await
+            .map_err(|e| format!("Release {} was created at {html_url}, but {e}", req.tag))?;
+        assets.push(name);
+    }
+    info!("forge: release {} published with {} asset(s)", req.tag, assets.len());
+    Ok(Release { id, url: html_url, assets })
+}
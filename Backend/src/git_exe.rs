@@ -0,0 +1,113 @@
+//! The git executable the app runs, and whether it is new enough.
+//!
+//! `git.executable_path` names a specific git to use instead of the first one on PATH
+//! (on Windows, typically a Git for Windows install: either `git.exe` itself or the
+//! install directory). [`apply`] hands the choice to the system-git backend and to the
+//! app's own probes (doctor, onboarding, health checks). [`check`] runs `git --version`
+//! at startup and whenever the setting changes, and warns when the version is older than
+//! [`MIN_VERSION`].
+
+use std::path::PathBuf;
+
+use log::{info, warn};
+use parking_lot::RwLock;
+use serde::Serialize;
+
+/// Oldest git the system backend is written against: porcelain v2 status, `stash push`,
+/// `rev-parse --is-shallow-repository`, sparse-checkout cone mode and
+/// `config --show-scope`.
+pub const MIN_VERSION: (u32, u32, u32) = (2, 26, 0);
+
+/// Places of `git` inside an install directory, most specific first.
+const IN_INSTALL_DIR: &[&str] = &["cmd/git.exe", "bin/git.exe", "cmd/git", "bin/git", "git.exe", "git"];
+
+/// The configured executable; `None` runs `git` from PATH.
+static PROGRAM: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// What the startup probe found.
+#[derive(Debug, Clone, Serialize)]
+pub struct GitProbe {
+    /// The executable that was run (`git` when taken from PATH).
+    pub program: String,
+    /// `None` when it could not be run.
+    pub version: Option<String>,
+    /// The version is at least [`MIN_VERSION`] (false when unknown).
+    pub supported: bool,
+    pub minimum: String,
+}
+
+/// The executable `setting` names: `None` for an empty setting (PATH), an error when it
+/// names nothing runnable.
+pub fn resolve(setting: &str) -> Result<Option<PathBuf>, String> {
+    let setting = setting.trim();
+    if setting.is_empty() {
+        return Ok(None);
+    }
+    let path = crate::paths::expand(setting);
+    if path.is_file() {
+        return Ok(Some(path));
+    }
+    if path.is_dir() {
+        return IN_INSTALL_DIR
+            .iter()
+            .map(|rel| path.join(rel))
+            .find(|p| p.is_file())
+            .map(Some)
+            .ok_or_else(|| format!("No git executable in {}", path.display()));
+    }
+    Err(format!("Git executable not found: {setting}"))
+}
+
+/// Use the executable `setting` names from now on; an invalid one falls back to PATH.
+pub fn apply(setting: &str) {
+    let program = resolve(setting).unwrap_or_else(|e| {
+        warn!("git_exe: {e}; using git from PATH");
+        None
+    });
+    if *PROGRAM.read() == program {
+        return;
+    }
+    openvcs_git::set_executable(program.clone());
+    *PROGRAM.write() = program;
+}
+
+/// The executable to run for git.
+pub fn program() -> PathBuf {
+    PROGRAM.read().clone().unwrap_or_else(|| PathBuf::from("git"))
+}
+
+fn version_string((major, minor, patch): (u32, u32, u32)) -> String {
+    format!("{major}.{minor}.{patch}")
+}
+
+/// Run `git --version` with the current executable and log what was found. Blocking.
+pub fn check() -> GitProbe {
+    let program = program();
+    let version = openvcs_git::git_version();
+    let supported = version.as_deref().and_then(openvcs_git::parse_version).is_some_and(|v| v >= MIN_VERSION);
+    let probe = GitProbe {
+        program: program.display().to_string(),
+        version,
+        supported,
+        minimum: version_string(MIN_VERSION),
+    };
+    match &probe.version {
+        Some(v) if supported => info!("git_exe: {} is git {v}", probe.program),
+        Some(v) => warn!("git_exe: {} is git {v}, older than {}", probe.program, probe.minimum),
+        None => warn!("git_exe: {} does not run", probe.program),
+    }
+    probe
+}
+
+/// A warning for the user about `probe`, or `None` when the executable is fine.
+pub fn warning(probe: &GitProbe) -> Option<String> {
+    match &probe.version {
+        Some(_) if probe.supported => None,
+        Some(v) => Some(format!(
+            "Git {v} is older than {}, the oldest version OpenVCS supports; some features may fail. Update Git or pick another one in Settings.",
+            probe.minimum
+        )),
+        None => Some(format!("Git could not be run ({}). Install Git or pick another one in Settings.", probe.program)),
+    }
+}
+
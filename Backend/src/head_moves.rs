@@ -0,0 +1,132 @@
+//! Undo for the last checkout or reset, and `git checkout -`.
+//!
+//! HEAD's reflog records where it was before every checkout and reset, so a branch switched
+//! by a wrong click, or a branch moved by a reset or squash, can be put back with one
+//! action. Only those moves are undone; when the last entry is a commit, merge or pull
+//! there is nothing to offer. [`previous_branch`] reads the same log for the quick switch
+//! back to the branch checked out before the current one.
+
+use std::collections::HashSet;
+
+use log::debug;
+use openvcs_core::models::{BranchKind, HeadState, LogQuery, ReflogEntry};
+use openvcs_core::Vcs;
+use serde::Serialize;
+
+/// Reflog entries searched for the previous branch.
+const PREVIOUS_BRANCH_DEPTH: usize = 100;
+
+/// Commits looked at on each side when working out what moving the branch back drops.
+const DROPPED_DEPTH: u32 = 500;
+
+/// Reflog message prefixes of moves undone by moving the branch back.
+const RESET_PREFIXES: &[&str] = &["reset: moving to ", "openvcs: squash "];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UndoKind {
+    /// Check out `target` again.
+    Checkout,
+    /// Move the current branch back to commit `target`, keeping local changes.
+    Reset,
+}
+
+/// How to undo the last move of HEAD.
+#[derive(Debug, Clone, Serialize)]
+pub struct HeadMove {
+    pub kind: UndoKind,
+    /// Branch or commit to return to.
+    pub target: String,
+    /// What undoing does, e.g. "Switch back to main".
+    pub label: String,
+    /// The reflog message of the move.
+    pub message: String,
+    /// For a reset: the commits moving the branch back takes off it, newest first. Checked
+    /// with `history_guard` before undoing.
+    pub dropped: Vec<String>,
+}
+
+fn short(id: &str) -> &str {
+    id.get(..7).unwrap_or(id)
+}
+
+/// `from` of a "checkout: moving from <from> to <to>" entry.
+fn checkout_from(e: &ReflogEntry) -> Option<&str> {
+    let rest = e.message.strip_prefix("checkout: moving from ")?;
+    rest.split_once(" to ").map(|(from, _)| from)
+}
+
+fn local_branches(vcs: &dyn Vcs) -> Vec<String> {
+    vcs.branches()
+        .map(|list| list.into_iter().filter(|b| matches!(b.kind, BranchKind::Local)).map(|b| b.name).collect())
+        .unwrap_or_default()
+}
+
+fn head_commit(vcs: &dyn Vcs) -> Option<String> {
+    match vcs.head_state().ok()? {
+        HeadState::Branch { commit, .. } | HeadState::Detached { commit, .. } => Some(commit),
+        HeadState::Unborn { .. } => None,
+    }
+}
+
+/// How to undo the last move of HEAD; `None` when it was not a checkout or reset.
+pub fn last_move(vcs: &dyn Vcs) -> Option<HeadMove> {
+    let entry = vcs.head_reflog(1).ok()?.into_iter().next()?;
+    // HEAD moved since without a reflog entry (another tool, or the log was expired)
+    if head_commit(vcs).as_deref() != Some(entry.new.as_str()) || entry.old.is_empty() {
+        debug!("head_moves: last reflog entry does not match HEAD");
+        return None;
+    }
+    if let Some(from) = checkout_from(&entry) {
+        // A detached HEAD is logged by commit id, so a name that is no longer a branch
+        // returns to the commit instead
+        let (target, label) = if local_branches(vcs).iter().any(|b| b == from) {
+            (from.to_string(), format!("Switch back to {from}"))
+        } else {
+            (entry.old.clone(), format!("Return to {}", short(&entry.old)))
+        };
+        return Some(HeadMove { kind: UndoKind::Checkout, target, label, message: entry.message, dropped: Vec::new() });
+    }
+    if RESET_PREFIXES.iter().any(|p| entry.message.starts_with(p)) {
+        let branch = vcs.current_branch().ok().flatten().unwrap_or_else(|| "HEAD".into());
+        let label = format!("Move {branch} back to {}", short(&entry.old));
+        let dropped = dropped(vcs, &entry.old);
+        return Some(HeadMove { kind: UndoKind::Reset, target: entry.old, label, message: entry.message, dropped });
+    }
+    None
+}
+
+/// Commits reachable from HEAD but not from `target` (`git rev-list target..HEAD`), within
+/// [`DROPPED_DEPTH`] commits of each.
+fn dropped(vcs: &dyn Vcs, target: &str) -> Vec<String> {
+    let walk = |rev: &str| {
+        let q = LogQuery { rev: Some(rev.to_string()), include_merges: true, ..LogQuery::head(DROPPED_DEPTH) };
+        vcs.log_commits(&q).map(|v| v.into_iter().map(|c| c.id).collect::<Vec<_>>()).unwrap_or_default()
+    };
+    let kept: HashSet<String> = walk(target).into_iter().collect();
+    walk("HEAD").into_iter().filter(|id| !kept.contains(id)).collect()
+}
+
+/// Undo the last move of HEAD. Local changes are kept; a reset that would overwrite one
+/// fails instead. Returns what was undone.
+pub fn undo(vcs: &dyn Vcs) -> Result<HeadMove, String> {
+    let m = last_move(vcs).ok_or_else(|| "Nothing to undo: the last change of HEAD was not a checkout or reset".to_string())?;
+    let res = match m.kind {
+        UndoKind::Checkout => vcs.checkout_branch(&m.target),
+        UndoKind::Reset => vcs.reset_keep(&m.target),
+    };
+    res.map_err(|e| format!("{} failed: {e}", m.label))?;
+    Ok(m)
+}
+
+/// The local branch checked out before the current one (`git checkout -`).
+pub fn previous_branch(vcs: &dyn Vcs) -> Option<String> {
+    let current = vcs.current_branch().ok().flatten();
+    let entries = vcs.head_reflog(PREVIOUS_BRANCH_DEPTH).ok()?;
+    let locals = local_branches(vcs);
+    entries
+        .iter()
+        .filter_map(checkout_from)
+        .find(|from| Some(*from) != current.as_deref() && locals.iter().any(|b| b == from))
+        .map(str::to_string)
+}
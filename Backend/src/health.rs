@@ -0,0 +1,183 @@
+//! Repository health check: quick structural checks on the open repository
+//! (HEAD, object connectivity, refs, loose objects) reported as doctor-style findings.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use openvcs_core::models::CaseCollision;
+
+use crate::doctor::Finding;
+
+/// Loose-object count above which `git gc --auto` would repack (git's default `gc.auto`).
+const LOOSE_OBJECT_LIMIT: u64 = 6700;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub path: String,
+    pub findings: Vec<Finding>,
+}
+
+struct Output {
+    code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+/// Run git in `workdir`. Set up by `openvcs_git::git_command`, so messages are in English
+/// for the checks that match them (`fsck`).
+fn git(workdir: &Path, args: &[&str]) -> Option<Output> {
+    let out = openvcs_git::git_command(Some(workdir))
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .ok()?;
+    Some(Output {
+        code: out.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&out.stdout).trim().to_string(),
+        stderr: String::from_utf8_lossy(&out.stderr).trim().to_string(),
+    })
+}
+
+/// Run all checks against the repository at `workdir`. Blocking; run off the async runtime.
+pub fn run(workdir: &Path) -> HealthReport {
+    let path = workdir.display().to_string();
+    let mut findings = Vec::new();
+
+    if git(workdir, &["--version"]).is_none() {
+        findings.push(Finding::error(
+            "git", "Git executable",
+            "git was not found on PATH",
+            "Health checks run through the git CLI; install Git to use them.",
+        ));
+        return HealthReport { path, findings };
+    }
+
+    check_head(workdir, &mut findings);
+    check_refs(workdir, &mut findings);
+    check_connectivity(workdir, &mut findings);
+    check_loose_objects(workdir, &mut findings);
+    check_case_collisions(workdir, &mut findings);
+
+    HealthReport { path, findings }
+}
+
+fn check_head(workdir: &Path, findings: &mut Vec<Finding>) {
+    let symbolic = git(workdir, &["symbolic-ref", "-q", "HEAD"]).filter(|o| o.code == 0).map(|o| o.stdout);
+    let resolves = git(workdir, &["rev-parse", "--verify", "-q", "HEAD"]).is_some_and(|o| o.code == 0);
+    let has_refs = git(workdir, &["for-each-ref", "--count=1", "--format=%(refname)"])
+        .is_some_and(|o| !o.stdout.is_empty());
+
+    let finding = match (symbolic, resolves) {
+        (Some(branch), true) => Finding::ok("head", "HEAD", format!("on {branch}")),
+        (None, true) => Finding::warn(
+            "head", "HEAD",
+            "HEAD is detached",
+            "Check out a branch before committing, or create one from here to keep new commits.",
+        ),
+        (Some(branch), false) if !has_refs => Finding::ok("head", "HEAD", format!("{branch} has no commits yet")),
+        (Some(branch), false) => Finding::error(
+            "head", "HEAD",
+            format!("HEAD points to {branch}, which does not exist"),
+            "Check out an existing branch (e.g. `git checkout main`) to repair HEAD.",
+        ),
+        (None, false) => Finding::error(
+            "head", "HEAD",
+            "HEAD does not resolve to a commit",
+            "HEAD is corrupt; check out a known branch, or re-clone the repository.",
+        ),
+    };
+    findings.push(finding);
+}
+
+fn check_refs(workdir: &Path, findings: &mut Vec<Finding>) {
+    let Some(out) = git(workdir, &["for-each-ref", "--format=%(refname)"]) else { return };
+    let broken: Vec<&str> = out
+        .stderr
+        .lines()
+        .filter_map(|l| l.strip_prefix("warning: ignoring broken ref "))
+        .collect();
+    if broken.is_empty() {
+        findings.push(Finding::ok("refs", "References", format!("{} ref(s), none broken", out.stdout.lines().count())));
+    } else {
+        findings.push(Finding::warn(
+            "refs", "References",
+            format!("broken refs: {}", broken.join(", ")),
+            "Delete the broken refs (`git update-ref -d <ref>`) or fetch to restore remote-tracking refs.",
+        ));
+    }
+}
+
+fn check_connectivity(workdir: &Path, findings: &mut Vec<Finding>) {
+    let Some(out) = git(workdir, &["fsck", "--connectivity-only", "--no-progress", "--no-dangling"]) else { return };
+    let problems: Vec<&str> = out
+        .stdout
+        .lines()
+        .chain(out.stderr.lines())
+        .filter(|l| l.starts_with("missing") || l.starts_with("broken link") || l.starts_with("error"))
+        .collect();
+    if out.code == 0 && problems.is_empty() {
+        findings.push(Finding::ok("objects", "Object connectivity", "all reachable objects are present"));
+    } else {
+        let shown: Vec<&str> = problems.iter().take(5).copied().collect();
+        let more = problems.len().saturating_sub(shown.len());
+        let mut detail = if shown.is_empty() { format!("git fsck exited with {}", out.code) } else { shown.join("; ") };
+        if more > 0 { detail.push_str(&format!(" (+{more} more)")); }
+        findings.push(Finding::error(
+            "objects", "Object connectivity",
+            detail,
+            "Objects are missing. Try `git fetch --refetch` from a healthy remote; if that fails, re-clone and copy over uncommitted work.",
+        ));
+    }
+}
+
+fn check_loose_objects(workdir: &Path, findings: &mut Vec<Finding>) {
+    let Some(out) = git(workdir, &["count-objects", "-v"]).filter(|o| o.code == 0) else { return };
+    let field = |name: &str| -> u64 {
+        out.stdout
+            .lines()
+            .find_map(|l| l.strip_prefix(name).and_then(|v| v.trim().parse().ok()))
+            .unwrap_or(0)
+    };
+    let count = field("count:");
+    let size_kib = field("size:");
+    let garbage = field("garbage:");
+
+    if count > LOOSE_OBJECT_LIMIT {
+        findings.push(Finding::warn(
+            "loose-objects", "Loose objects",
+            format!("{count} loose objects ({} MiB)", size_kib / 1024),
+            "Run `git gc` to pack them; large numbers of loose objects slow down status and log.",
+        ));
+    } else {
+        findings.push(Finding::ok("loose-objects", "Loose objects", format!("{count} loose objects")));
+    }
+    if garbage > 0 {
+        findings.push(Finding::warn(
+            "garbage", "Garbage files",
+            format!("{garbage} garbage file(s) in the object store"),
+            "Leftovers from interrupted operations; `git gc --prune=now` removes them.",
+        ));
+    }
+}
+
+fn check_case_collisions(workdir: &Path, findings: &mut Vec<Finding>) {
+    let Some(out) = git(workdir, &["ls-files", "-z"]).filter(|o| o.code == 0) else { return };
+    let paths: Vec<&str> = out.stdout.split('\0').filter(|p| !p.is_empty()).collect();
+    let collisions = CaseCollision::find(&paths);
+    if collisions.is_empty() {
+        findings.push(Finding::ok("case-collisions", "Path case", "no tracked paths differ only by case"));
+        return;
+    }
+    let shown: Vec<String> = collisions.iter().take(5).map(|c| c.paths.join(" / ")).collect();
+    let more = collisions.len().saturating_sub(shown.len());
+    let mut detail = shown.join("; ");
+    if more > 0 { detail.push_str(&format!(" (+{more} more)")); }
+    let hint = if crate::case_collisions::is_case_insensitive(workdir) {
+        "This filesystem ignores case, so only one of each can be checked out and the other shows as modified. \
+         Keep one spelling: untrack the others (`git rm --cached <path>`) and commit."
+    } else {
+        "These break checkouts on macOS and Windows. Rename or remove all but one spelling with `git mv` / `git rm` and commit."
+    };
+    findings.push(Finding::warn("case-collisions", "Path case", detail, hint));
+}
@@ -0,0 +1,166 @@
+//! Windowed commit history for virtual scrolling.
+//!
+//! Rerunning `git log --skip N` for every scroll position gets slower the deeper the user
+//! scrolls. Instead each repository keeps one open [`HistoryWalk`] and the commits read so
+//! far; [`HistoryCache::window`] serves a slice around an anchor commit (or index), pulling
+//! more from the walk only when the slice reaches past what's loaded. The cache resets
+//! when HEAD moves. In a shallow clone the walk ends at the clone depth, which the window
+//! reports as `truncated`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use log::{debug, info};
+use openvcs_core::models::{CommitItem, LogQuery};
+use openvcs_core::{HistoryWalk, Vcs};
+use serde::Serialize;
+
+/// Commits pulled from the walk per refill.
+const BATCH: usize = 2000;
+/// Repositories whose walks are kept open at once.
+const MAX_REPOS: usize = 4;
+
+struct Entry {
+    head: String,
+    shallow: bool,
+    walk: Option<Box<dyn HistoryWalk>>,
+    items: Vec<CommitItem>,
+    index: HashMap<String, usize>,
+}
+
+impl Entry {
+    fn open(vcs: &dyn Vcs, head: String) -> Result<Self, String> {
+        let q = LogQuery { topo_order: true, include_merges: true, ..Default::default() };
+        let walk = vcs.history_walk(&q).map_err(|e| e.to_string())?;
+        let shallow = vcs.is_shallow().unwrap_or(false);
+        Ok(Self { head, shallow, walk: Some(walk), items: Vec::new(), index: HashMap::new() })
+    }
+
+    /// Read one more batch; `false` once the walk is exhausted.
+    fn refill(&mut self) -> Result<bool, String> {
+        let Some(walk) = self.walk.as_mut() else { return Ok(false) };
+        let batch = walk.next_batch(BATCH).map_err(|e| e.to_string())?;
+        if batch.len() < BATCH {
+            // short read: history is complete, release the walk (and its process/handle)
+            self.walk = None;
+        }
+        for c in batch {
+            self.index.insert(c.id.clone(), self.items.len());
+            self.items.push(c);
+        }
+        Ok(self.walk.is_some())
+    }
+
+    fn fill_to(&mut self, len: usize) -> Result<(), String> {
+        while self.items.len() < len && self.refill()? {}
+        Ok(())
+    }
+
+    fn find(&mut self, id: &str) -> Result<Option<usize>, String> {
+        loop {
+            if let Some(&i) = self.index.get(id) {
+                return Ok(Some(i));
+            }
+            if !self.refill()? {
+                return Ok(self.index.get(id).copied());
+            }
+        }
+    }
+}
+
+/// A slice of history starting at `start`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogWindow {
+    pub start: usize,
+    pub items: Vec<CommitItem>,
+    /// Position of the anchor commit, when one was given.
+    pub anchor_index: Option<usize>,
+    /// Commits read so far (the total once `complete`).
+    pub loaded: usize,
+    pub complete: bool,
+    /// The repository is a shallow clone, so the history stops at its depth rather than
+    /// at the first commit.
+    pub truncated: bool,
+}
+
+/// Per-repository open walks. Cheap to clone; clones share the cache.
+#[derive(Default, Clone)]
+pub struct HistoryCache {
+    entries: Arc<Mutex<HashMap<PathBuf, Arc<Mutex<Entry>>>>>,
+}
+
+fn head_id(vcs: &dyn Vcs) -> Result<String, String> {
+    let q = LogQuery { rev: Some("HEAD".into()), limit: 1, ..Default::default() };
+    let head = vcs.log_commits(&q).map_err(|e| e.to_string())?;
+    Ok(head.into_iter().next().map(|c| c.id).unwrap_or_default())
+}
+
+impl HistoryCache {
+    fn entry(&self, workdir: &Path, vcs: &dyn Vcs, head: String) -> Result<Arc<Mutex<Entry>>, String> {
+        let mut entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        if let Some(e) = entries.get(workdir) {
+            let fresh = e.lock().unwrap_or_else(|p| p.into_inner()).head == head;
+            if fresh {
+                return Ok(e.clone());
+            }
+            debug!("history: HEAD moved in {}, resetting walk", workdir.display());
+        }
+        if entries.len() >= MAX_REPOS && !entries.contains_key(workdir) {
+            entries.clear();
+        }
+        let e = Arc::new(Mutex::new(Entry::open(vcs, head)?));
+        entries.insert(workdir.to_path_buf(), e.clone());
+        Ok(e)
+    }
+
+    /// Drop the walk of `workdir` (history changed without HEAD moving, e.g. unshallow).
+    pub fn invalidate(&self, workdir: &Path) {
+        let mut entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        if entries.remove(workdir).is_some() {
+            debug!("history: invalidated {}", workdir.display());
+        }
+    }
+
+    /// Commits `before` ahead of and `after` behind the anchor: the commit `anchor` if
+    /// given, else position `index` (default 0, i.e. HEAD). Blocking.
+    pub fn window(
+        &self,
+        vcs: &dyn Vcs,
+        anchor: Option<&str>,
+        index: Option<usize>,
+        before: usize,
+        after: usize,
+    ) -> Result<LogWindow, String> {
+        let workdir = vcs.workdir().to_path_buf();
+        let entry = self.entry(&workdir, vcs, head_id(vcs)?)?;
+        let mut e = entry.lock().unwrap_or_else(|p| p.into_inner());
+
+        let (pos, anchor_index) = match anchor {
+            Some(id) => {
+                let i = e.find(id)?.ok_or_else(|| format!("commit {id} is not in this history"))?;
+                (i, Some(i))
+            }
+            None => (index.unwrap_or(0), None),
+        };
+
+        let end = pos.saturating_add(after).saturating_add(1);
+        e.fill_to(end)?;
+        let end = end.min(e.items.len());
+        let start = pos.saturating_sub(before).min(end);
+
+        let window = LogWindow {
+            start,
+            items: e.items[start..end].to_vec(),
+            anchor_index,
+            loaded: e.items.len(),
+            complete: e.walk.is_none(),
+            truncated: e.shallow,
+        };
+        info!(
+            "history: window {}..{} of {}{} for {}",
+            start, end, window.loaded, if window.complete { "" } else { "+" }, workdir.display()
+        );
+        Ok(window)
+    }
+}
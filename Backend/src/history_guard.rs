@@ -0,0 +1,53 @@
+//! Guard for commands that rewrite history: squash and undoing a reset now; amend, reword
+//! and rebase are to call [`check`] too.
+//!
+//! Rewriting a commit that is already on a remote forks the history others have pulled,
+//! and the next push needs `--force`. Such commands look up which of their commits were
+//! pushed ([`Vcs::is_pushed`]) and refuse unless the user acknowledged it; the UI asks
+//! [`pushed`] first so its confirmation can name the remote branches involved.
+
+use log::{debug, warn};
+use openvcs_core::{Vcs, VcsError};
+use serde::Serialize;
+
+/// A commit that is already on a remote.
+#[derive(Debug, Clone, Serialize)]
+pub struct PushedCommit {
+    pub id: String,
+    /// Remote-tracking branches containing it (`origin/main`).
+    pub remotes: Vec<String>,
+}
+
+/// The commits of `ids` that were pushed, with where to.
+pub fn pushed(vcs: &dyn Vcs, ids: &[String]) -> Result<Vec<PushedCommit>, String> {
+    let mut out = Vec::new();
+    for id in ids {
+        match vcs.is_pushed(id) {
+            Ok(remotes) if remotes.is_empty() => {}
+            Ok(remotes) => out.push(PushedCommit { id: id.clone(), remotes }),
+            // Without remote information there is nothing to protect
+            Err(VcsError::Unsupported(_)) => debug!("history_guard: is_pushed unsupported"),
+            Err(e) => return Err(format!("Cannot tell whether {id} was pushed: {e}")),
+        }
+    }
+    Ok(out)
+}
+
+/// Refuse to rewrite `ids` when any was pushed, unless `force` acknowledges it. `what`
+/// names the operation in the message ("Squash").
+pub fn check(vcs: &dyn Vcs, what: &str, ids: &[String], force: bool) -> Result<(), String> {
+    let pushed = pushed(vcs, ids)?;
+    let Some(first) = pushed.first() else { return Ok(()) };
+    let mut remotes: Vec<&str> = pushed.iter().flat_map(|p| p.remotes.iter().map(String::as_str)).collect();
+    remotes.sort_unstable();
+    remotes.dedup();
+    if force {
+        warn!("history_guard: {what} rewrites {} pushed commit(s) on {}", pushed.len(), remotes.join(", "));
+        return Ok(());
+    }
+    let short = &first.id[..first.id.len().min(7)];
+    Err(match pushed.len() {
+        1 => format!("{what} refused: {short} is already on {}", remotes.join(", ")),
+        n => format!("{what} refused: {n} commits, including {short}, are already on {}", remotes.join(", ")),
+    })
+}
@@ -0,0 +1,234 @@
+//! Git hook templates.
+//!
+//! Teams that standardize hooks can install them from bundled templates
+//! (`Backend/templates/hooks/`) instead of following README instructions. Installed scripts
+//! carry a `# openvcs-hook-template: <id> <version>` marker, so [`list`] can tell them
+//! apart from hooks written by hand or by other tools and report when a newer template
+//! version ships. Hooks go to the directory git actually runs them from
+//! ([`Vcs::hooks_dir`]), and installing one follows `git.allow_hooks`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use openvcs_core::Vcs;
+use serde::Serialize;
+
+use crate::settings::HookPolicy;
+
+const MARKER: &str = "# openvcs-hook-template:";
+
+/// Suffix of the copy kept of a hand-written hook replaced by a template.
+const BACKUP_SUFFIX: &str = ".openvcs-backup";
+
+/// Hook names git runs (`githooks(5)`); other files in the hooks directory are ignored.
+const HOOK_NAMES: &[&str] = &[
+    "applypatch-msg", "pre-applypatch", "post-applypatch", "pre-commit", "pre-merge-commit",
+    "prepare-commit-msg", "commit-msg", "post-commit", "pre-rebase", "post-checkout",
+    "post-merge", "pre-push", "pre-receive", "update", "proc-receive", "post-receive",
+    "post-update", "reference-transaction", "push-to-checkout", "pre-auto-gc",
+    "post-rewrite", "sendemail-validate", "fsmonitor-watchman", "p4-changelist",
+    "p4-prepare-changelist", "p4-post-changelist", "p4-pre-submit", "post-index-change",
+];
+
+struct Template {
+    id: &'static str,
+    hook: &'static str,
+    title: &'static str,
+    description: &'static str,
+    script: &'static str,
+}
+
+const TEMPLATES: &[Template] = &[
+    Template {
+        id: "pre-commit-framework",
+        hook: "pre-commit",
+        title: "pre-commit framework",
+        description: "Runs the hooks in .pre-commit-config.yaml with pre-commit (pre-commit.com).",
+        script: include_str!("../templates/hooks/pre-commit-framework.sh"),
+    },
+    Template {
+        id: "commit-msg-conventional",
+        hook: "commit-msg",
+        title: "Conventional Commits lint",
+        description: "Rejects commit messages that are not `type(scope): summary`.",
+        script: include_str!("../templates/hooks/commit-msg-conventional.sh"),
+    },
+];
+
+/// A template the user can install.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookTemplateInfo {
+    pub id: &'static str,
+    /// Hook it installs as.
+    pub hook: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub version: u32,
+}
+
+/// Where an installed hook came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind")]
+pub enum HookSource {
+    /// Installed from one of our templates.
+    Template { id: String, version: u32, outdated: bool },
+    /// Written by `pre-commit install`.
+    PreCommit,
+    /// Managed by Husky (`core.hooksPath` under `.husky`).
+    Husky,
+    /// Anything else.
+    Custom,
+}
+
+/// A hook present in the repository's hooks directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookInfo {
+    pub name: String,
+    pub path: String,
+    pub source: HookSource,
+    /// Git skips hooks that are not executable (always true on Windows).
+    pub executable: bool,
+}
+
+/// `(id, version)` from a script's marker line.
+fn marker(script: &str) -> Option<(&str, u32)> {
+    let rest = script.lines().take(5).find_map(|l| l.strip_prefix(MARKER))?;
+    let mut parts = rest.split_whitespace();
+    let id = parts.next()?;
+    let version = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+    Some((id, version))
+}
+
+impl Template {
+    fn version(&self) -> u32 {
+        marker(self.script).map_or(0, |(_, v)| v)
+    }
+}
+
+/// Bundled templates.
+pub fn templates() -> Vec<HookTemplateInfo> {
+    TEMPLATES
+        .iter()
+        .map(|t| HookTemplateInfo {
+            id: t.id,
+            hook: t.hook,
+            title: t.title,
+            description: t.description,
+            version: t.version(),
+        })
+        .collect()
+}
+
+fn hooks_dir(vcs: &dyn Vcs) -> Result<PathBuf, String> {
+    vcs.hooks_dir().map_err(|e| format!("Cannot locate the hooks directory: {e}"))
+}
+
+fn source_of(dir: &Path, script: &str) -> HookSource {
+    if let Some((id, version)) = marker(script) {
+        let latest = TEMPLATES.iter().find(|t| t.id == id).map_or(version, |t| t.version());
+        return HookSource::Template { id: id.to_string(), version, outdated: version < latest };
+    }
+    if script.contains("File generated by pre-commit") {
+        HookSource::PreCommit
+    } else if dir.components().any(|c| c.as_os_str() == ".husky") {
+        HookSource::Husky
+    } else {
+        HookSource::Custom
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+fn info(dir: &Path, name: &str) -> Option<HookInfo> {
+    let path = dir.join(name);
+    if !path.is_file() {
+        return None;
+    }
+    let script = fs::read(&path).map(|b| String::from_utf8_lossy(&b).into_owned()).unwrap_or_default();
+    Some(HookInfo {
+        name: name.to_string(),
+        source: source_of(dir, &script),
+        executable: is_executable(&path),
+        path: path.display().to_string(),
+    })
+}
+
+/// Hooks installed in the repository (sample hooks are not listed).
+pub fn list(vcs: &dyn Vcs) -> Result<Vec<HookInfo>, String> {
+    let dir = hooks_dir(vcs)?;
+    Ok(HOOK_NAMES.iter().filter_map(|name| info(&dir, name)).collect())
+}
+
+/// Install (or update) template `id`, as `policy` allows. `approved` is the user's answer
+/// when the policy is `ask`; without one nothing is written so the UI can ask first.
+/// A hand-written hook it replaces is kept next to it with [`BACKUP_SUFFIX`].
+pub fn install(vcs: &dyn Vcs, id: &str, policy: HookPolicy, approved: Option<bool>) -> Result<HookInfo, String> {
+    let t = TEMPLATES.iter().find(|t| t.id == id).ok_or_else(|| format!("Unknown hook template: {id}"))?;
+    match (policy, approved) {
+        (HookPolicy::Deny, _) => {
+            return Err("Hooks are disabled in settings (Git > Allow hooks)".into());
+        }
+        (HookPolicy::Ask, None) => return Err(format!("Installing the {} hook needs confirmation", t.hook)),
+        (HookPolicy::Ask, Some(false)) => return Err(format!("Installing the {} hook was declined", t.hook)),
+        (HookPolicy::Ask, Some(true)) | (HookPolicy::Allow, _) => {}
+    }
+
+    let dir = hooks_dir(vcs)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Cannot create {}: {e}", dir.display()))?;
+    let path = dir.join(t.hook);
+
+    if let Some(existing) = info(&dir, t.hook) {
+        if !matches!(existing.source, HookSource::Template { .. }) {
+            let backup = dir.join(format!("{}{BACKUP_SUFFIX}", t.hook));
+            if backup.exists() {
+                return Err(format!(
+                    "{} already exists and so does its backup {}; move one of them first",
+                    path.display(),
+                    backup.display()
+                ));
+            }
+            fs::rename(&path, &backup).map_err(|e| format!("Cannot back up {}: {e}", path.display()))?;
+            info!("hooks: kept the existing {} as {}", t.hook, backup.display());
+        }
+    }
+
+    fs::write(&path, t.script).map_err(|e| format!("Cannot write {}: {e}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Cannot make {} executable: {e}", path.display()))?;
+    }
+    info!("hooks: installed template {} v{} as {}", t.id, t.version(), path.display());
+    info(&dir, t.hook).ok_or_else(|| format!("{} vanished after writing it", path.display()))
+}
+
+/// Remove hook `name` if it was installed from a template, restoring any backup.
+/// Hooks from other sources belong to their tools and are left alone.
+pub fn remove(vcs: &dyn Vcs, name: &str) -> Result<(), String> {
+    let dir = hooks_dir(vcs)?;
+    let Some(existing) = info(&dir, name) else { return Ok(()) };
+    if !matches!(existing.source, HookSource::Template { .. }) {
+        return Err(format!("The {name} hook was not installed by OpenVCS; remove it by hand"));
+    }
+    let path = dir.join(name);
+    fs::remove_file(&path).map_err(|e| format!("Cannot remove {}: {e}", path.display()))?;
+    let backup = dir.join(format!("{name}{BACKUP_SUFFIX}"));
+    if backup.exists() {
+        if let Err(e) = fs::rename(&backup, &path) {
+            warn!("hooks: cannot restore {}: {e}", backup.display());
+        }
+    }
+    info!("hooks: removed {}", path.display());
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Cancellation of running jobs.
+//!
+//! Long network operations register their [`JobId`] here and install a [`CancelToken`] on
+//! the worker thread; `cancel_job` flips the token and the backend stops at its next
+//! check (system git is killed, libgit2 aborts from its progress callback).
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use log::{debug, info};
+use openvcs_core::cancel::{self, CancelToken};
+use openvcs_core::models::JobId;
+
+/// Tokens of the jobs currently running. Cheap to clone; clones share the registry.
+#[derive(Default, Clone)]
+pub struct Jobs {
+    tokens: Arc<Mutex<HashMap<JobId, CancelToken>>>,
+}
+
+/// Keeps a job cancellable on the current thread; unregisters it on drop.
+pub struct JobGuard {
+    jobs: Jobs,
+    job: JobId,
+    _scope: cancel::ScopeGuard,
+}
+
+impl Jobs {
+    /// Register `job` and make its token current on this thread. Call from the worker
+    /// thread that runs the operation.
+    pub fn enter(&self, job: JobId) -> JobGuard {
+        let token = CancelToken::new();
+        self.tokens.lock().unwrap_or_else(|p| p.into_inner()).insert(job, token.clone());
+        debug!("jobs: {job} registered");
+        JobGuard { jobs: self.clone(), job, _scope: cancel::scope(token) }
+    }
+
+    /// Request cancellation; `false` if the job isn't running (already done or unknown).
+    pub fn cancel(&self, job: JobId) -> bool {
+        let tokens = self.tokens.lock().unwrap_or_else(|p| p.into_inner());
+        match tokens.get(&job) {
+            Some(t) => {
+                info!("jobs: cancelling {job}");
+                t.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        self.jobs.tokens.lock().unwrap_or_else(|p| p.into_inner()).remove(&self.job);
+    }
+}
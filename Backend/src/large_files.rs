@@ -0,0 +1,88 @@
+//! Large-file commit guard.
+//!
+//! A big blob, once pushed, stays in every clone's history for good. Before a commit the UI
+//! asks [`check`] about the files it is about to commit; anything over the threshold
+//! (`lfs.large_file_warn_mb`) comes back as a [`LargeFileWarning`] with the `git lfs track`
+//! patterns that would keep it out of history. Paths already handled by the LFS filter are
+//! stored as small pointers and are not reported.
+
+use std::collections::BTreeMap;
+
+use log::{debug, info};
+use openvcs_core::{Vcs, VcsError};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeFile {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LargeFileWarning {
+    pub threshold_bytes: u64,
+    /// Largest first.
+    pub files: Vec<LargeFile>,
+    /// `git lfs track` patterns covering `files` (`*.psd`, or the path itself).
+    pub lfs_patterns: Vec<String>,
+    pub suggestion: String,
+}
+
+/// Files over `threshold_bytes` among the blobs already staged and `files`, which the commit
+/// will stage from the working tree. `None` when there are none. Blocking.
+pub fn check(vcs: &dyn Vcs, files: &[String], threshold_bytes: u64) -> Result<Option<LargeFileWarning>, String> {
+    let mut sizes: BTreeMap<String, u64> = BTreeMap::new();
+    match vcs.staged_blob_sizes() {
+        Ok(staged) => sizes.extend(staged.into_iter().map(|b| (b.path, b.size))),
+        Err(VcsError::Unsupported(_)) => debug!("large_files: backend cannot size staged blobs"),
+        Err(e) => return Err(e.to_string()),
+    }
+    for f in files {
+        // The working-tree copy is what gets staged; deleted files have nothing to add.
+        match std::fs::metadata(vcs.workdir().join(f)) {
+            Ok(m) if m.is_file() => {
+                sizes.insert(f.clone(), m.len());
+            }
+            _ => {}
+        }
+    }
+
+    let mut large: Vec<LargeFile> =
+        sizes.into_iter().filter(|(_, size)| *size > threshold_bytes).map(|(path, size)| LargeFile { path, size }).collect();
+    if large.is_empty() {
+        return Ok(None);
+    }
+
+    let paths: Vec<String> = large.iter().map(|f| f.path.clone()).collect();
+    let lfs: Vec<String> = vcs
+        .path_attributes(&paths, &["filter"])
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|a| a.value == "lfs")
+        .map(|a| a.path)
+        .collect();
+    large.retain(|f| !lfs.contains(&f.path));
+    if large.is_empty() {
+        return Ok(None);
+    }
+    large.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    let mut lfs_patterns: Vec<String> = large.iter().map(|f| lfs_pattern(&f.path)).collect();
+    lfs_patterns.sort();
+    lfs_patterns.dedup();
+    let suggestion = format!(
+        "Files this large stay in the repository history forever once pushed. Consider tracking them with Git LFS: {}",
+        lfs_patterns.iter().map(|p| format!("git lfs track \"{p}\"")).collect::<Vec<_>>().join("; ")
+    );
+    info!("large_files: {} file(s) over {} bytes", large.len(), threshold_bytes);
+    Ok(Some(LargeFileWarning { threshold_bytes, files: large, lfs_patterns, suggestion }))
+}
+
+/// `*.<ext>` for files with an extension, otherwise the path itself.
+fn lfs_pattern(path: &str) -> String {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() => format!("*.{ext}"),
+        _ => path.to_string(),
+    }
+}
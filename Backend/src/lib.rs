@@ -1,7 +1,6 @@
-use tauri::{Emitter, Manager};
+use tauri::{async_runtime, Emitter, Manager};
 use std::sync::Arc;
-use openvcs_core::{backend_id, BackendId};
-use tauri_plugin_updater::UpdaterExt;
+use openvcs_core::BackendId;
 
 mod utilities;
 mod tauri_commands;
@@ -12,6 +11,52 @@ mod validate;
 mod settings;
 mod repo_settings;
 mod logging;
+mod updater;
+mod doctor;
+mod locks;
+mod health;
+mod telemetry;
+mod crash;
+mod onboarding;
+mod refresh;
+mod ops;
+mod history;
+mod summary;
+mod commit_cache;
+mod jobs;
+mod clone;
+mod templates;
+mod mirror;
+mod export;
+mod wip;
+mod case_collisions;
+mod recent_branches;
+mod large_files;
+mod secret_scan;
+mod pre_push;
+mod plugins;
+mod actions;
+mod desktop;
+mod shell_integration;
+mod hooks;
+mod worktrees;
+mod ref_watch;
+mod commit_drafts;
+mod history_guard;
+mod head_moves;
+mod chunked;
+mod safe_directory;
+mod paths;
+mod forge;
+mod sequence;
+mod net_stats;
+mod repo_lost;
+mod batch;
+mod changes_scope;
+mod partial_clone;
+mod prefetch;
+mod git_exe;
+mod bench;
 
 #[cfg(feature = "with-git")]
 #[allow(unused_imports)]
@@ -21,39 +66,72 @@ use openvcs_git as _;
 #[allow(unused_imports)]
 use openvcs_git_libgit2 as _;
 
-pub const GIT_SYSTEM_ID: BackendId = backend_id!("git-system");
+pub use openvcs_git::GIT_SYSTEM_ID;
+#[cfg(feature = "with-git-libgit2")]
+pub use openvcs_git_libgit2::GIT_LIBGIT2_ID;
+
+/// Folder given on the command line (e.g. by the file manager's "Open in OpenVCS").
+fn cli_repo_arg() -> Option<std::path::PathBuf> {
+    std::env::args_os()
+        .skip(1)
+        .map(std::path::PathBuf::from)
+        .find(|p| p.is_dir())
+}
 
-/// Attempt to reopen the most recent repository at startup if the
-/// global setting `general.reopen_last_repos` is enabled.
-fn try_reopen_last_repo<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) {
+/// Open the repository to show at startup: the folder passed on the command line, else
+/// the most recent repository if the global setting `general.reopen_last_repos` is enabled.
+fn open_initial_repo<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>) {
     use openvcs_core::{backend_descriptor::get_backend, Repo};
-    use std::path::Path;
 
     let state = app_handle.state::<state::AppState>();
     let app_config = state.config();
-    if !app_config.general.reopen_last_repos { return; }
-
-    let recents = state.recents();
-    if let Some(path) = recents.into_iter().find(|p| p.exists()) {
-        let backend: BackendId = match app_config.git.backend {
-            settings::GitBackend::System => GIT_SYSTEM_ID,
-            settings::GitBackend::Libgit2 => backend_id!("libgit2"),
-        };
-
-        let path_str = path.to_string_lossy().to_string();
-        match get_backend(&backend) {
-            Some(description) => match (description.open)(Path::new(&path)) {
-                Ok(backend_handle) => {
-                    let existing_repo = Arc::new(Repo::new(backend_handle));
-                    state.set_current_repo(existing_repo);
-                    if let Err(error) = app_handle.emit("repo:selected", &path_str) {
-                        log::warn!("startup reopen: failed to emit repo:selected: {}", error);
-                    }
-                }
-                Err(error) => log::warn!("startup reopen: failed to open repo: {}", error),
-            },
-            None => log::warn!("startup reopen: unknown backend `{}`", backend),
+    let path = match cli_repo_arg() {
+        Some(path) => {
+            log::info!("startup: opening {} from the command line", path.display());
+            path
+        }
+        None if app_config.general.reopen_last_repos => {
+            let Some(path) = state.recents().into_iter().find(|p| p.exists()) else { return };
+            path
         }
+        None => return,
+    };
+
+    let backend: BackendId = match app_config.git.backend {
+        settings::GitBackend::System => GIT_SYSTEM_ID,
+        #[cfg(feature = "with-git-libgit2")]
+        settings::GitBackend::Libgit2 => GIT_LIBGIT2_ID,
+        // Not built in: the system backend is the only one there is
+        #[cfg(not(feature = "with-git-libgit2"))]
+        settings::GitBackend::Libgit2 => GIT_SYSTEM_ID,
+    };
+
+    let path_str = path.to_string_lossy().to_string();
+    match get_backend(&backend) {
+        Some(description) => match (description.open)(&path) {
+            Ok(backend_handle) => {
+                let existing_repo = Arc::new(Repo::new(backend_handle));
+                state.set_current_repo(existing_repo);
+                if let Err(error) = app_handle.emit("repo:selected", &path_str) {
+                    log::warn!("startup reopen: failed to emit repo:selected: {}", error);
+                }
+                menus::sync(app_handle, None);
+                desktop::update_title(app_handle);
+                plugins::dispatch(app_handle, plugins::PluginEvent::RepoOpened, &[]);
+            }
+            Err(error) => log::warn!("startup reopen: failed to open repo: {}", error),
+        },
+        None => log::warn!("startup reopen: unknown backend `{}`", backend),
+    }
+}
+
+/// Subcommands that run instead of the app, e.g. `openvcs bench`; `None` to start the app.
+/// Returns the process exit code.
+pub fn run_subcommand() -> Option<i32> {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some("bench") => Some(bench::main(args.collect())),
+        _ => None,
     }
 }
 
@@ -78,11 +156,46 @@ pub fn run() {
 
     tauri::Builder::default()
         .manage(state::AppState::new_with_config())
+        .manage(updater::UpdaterState::default())
+        .manage(refresh::RefreshState::default())
+        .manage(menus::MenuState::default())
         .setup(|app| {
             menus::build_and_attach_menu(app)?;
+            menus::sync(app.handle(), None);
+
+            let (telemetry_on, crash_reports_on) = app
+                .state::<state::AppState>()
+                .with_config(|c| (c.general.telemetry, c.general.crash_reports));
+            telemetry::start(telemetry_on);
+            crash::install(crash_reports_on);
+
+            // Use the configured git, and warn when it is missing or too old.
+            let git_path = app.state::<state::AppState>().with_config(|c| c.git.executable_path.clone());
+            git_exe::apply(&git_path);
+            let app_handle = app.handle().clone();
+            async_runtime::spawn_blocking(move || {
+                if let Some(msg) = git_exe::warning(&git_exe::check()) {
+                    let _ = app_handle.emit("ui:notify", msg);
+                }
+            });
+
+            mirror::start_scheduler(app.handle().clone());
+            wip::start_service(app.handle().clone());
+            ref_watch::start(app.handle().clone());
+            plugins::start(app.handle());
 
-            // On startup, optionally reopen the last repository if enabled in settings.
-            try_reopen_last_repo(&app.handle());
+            // On startup, open the repository given on the command line, or optionally
+            // reopen the last one if enabled in settings.
+            open_initial_repo(app.handle());
+
+            // Rewrite the file manager entries in case the app has moved since.
+            if app.state::<state::AppState>().with_config(|c| c.general.shell_integration) {
+                async_runtime::spawn_blocking(|| {
+                    if let Err(e) = shell_integration::set_enabled(true) {
+                        log::warn!("startup: {e}");
+                    }
+                });
+            }
 
             // Optionally check for updates on launch and show custom dialog when available.
             let app_handle = app.handle().clone();
@@ -92,13 +205,8 @@ pub fn run() {
             };
             if check_updates {
                 tauri::async_runtime::spawn(async move {
-                    if let Ok(updater) = app_handle.updater() {
-                        match updater.check().await {
-                            Ok(Some(_u)) => {
-                                let _ = app_handle.emit("ui:update-available", serde_json::json!({"source":"startup"}));
-                            }
-                            _ => {}
-                        }
+                    if let Err(e) = updater::check_and_announce(&app_handle, "startup").await {
+                        log::warn!("updater: startup check failed: {}", e);
                     }
                 });
             }
@@ -111,60 +219,166 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .invoke_handler(build_invoke_handler::<_>())
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Apply an update that was downloaded with "install on quit".
+            if let tauri::RunEvent::Exit = event {
+                telemetry::save();
+                updater::install_staged(app);
+            }
+        });
 }
 
 /// Returns the set of command handlers for the app.
 fn build_invoke_handler<R: tauri::Runtime>() -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
     tauri::generate_handler![
         tauri_commands::about_info,
+        tauri_commands::environment_doctor,
+        tauri_commands::first_run_status,
+        tauri_commands::repo_health,
+        tauri_commands::get_telemetry_preview,
+        tauri_commands::list_crash_reports,
+        tauri_commands::get_crash_report,
+        tauri_commands::submit_crash_report,
+        tauri_commands::dismiss_crash_report,
+        tauri_commands::get_recent_logs,
+        tauri_commands::subscribe_logs,
+        tauri_commands::unsubscribe_logs,
         tauri_commands::show_licenses,
         tauri_commands::browse_directory,
         tauri_commands::add_repo,
         tauri_commands::validate_git_url,
+        tauri_commands::remote_refs,
+        tauri_commands::sync_remotes,
+        tauri_commands::list_remote_syncs,
+        tauri_commands::schedule_remote_sync,
         tauri_commands::validate_add_path,
         tauri_commands::validate_clone_input,
         tauri_commands::current_repo_path,
         tauri_commands::list_recent_repos,
         tauri_commands::git_list_branches,
         tauri_commands::git_status,
+        tauri_commands::git_status_stream,
+        tauri_commands::git_worktree_stats,
+        tauri_commands::git_untrack_paths,
+        tauri_commands::request_status_refresh,
         tauri_commands::git_log,
+        tauri_commands::git_log_window,
+        tauri_commands::export_history,
         tauri_commands::git_head_status,
+        tauri_commands::git_checkout_tag,
         tauri_commands::git_checkout_branch,
+        tauri_commands::previous_branch,
+        tauri_commands::last_head_move,
+        tauri_commands::undo_last_head_move,
         tauri_commands::git_create_branch,
+        tauri_commands::git_list_tags,
+        tauri_commands::git_create_tag,
+        tauri_commands::git_delete_tag,
+        tauri_commands::git_push_tags,
+        tauri_commands::git_verify_tag,
+        tauri_commands::create_release,
         tauri_commands::git_rename_branch,
+        tauri_commands::set_branch_description,
+        tauri_commands::set_branch_notes,
+        tauri_commands::recent_branches,
+        tauri_commands::git_relink_or_delete_branch,
+        tauri_commands::git_upstream_suggestions,
         tauri_commands::git_current_branch,
         tauri_commands::get_repo_summary,
         tauri_commands::open_repo,
+        tauri_commands::mark_safe_directory,
+        tauri_commands::relocate_repo,
+        tauri_commands::close_repo,
         tauri_commands::clone_repo,
+        tauri_commands::init_repo,
+        tauri_commands::create_demo_repo,
+        tauri_commands::list_repo_templates,
         tauri_commands::git_diff_file,
         tauri_commands::git_delete_branch,
         tauri_commands::git_merge_branch,
         tauri_commands::git_diff_commit,
+        tauri_commands::git_diff_file_structured,
+        tauri_commands::git_diff_dir,
+        tauri_commands::get_changes_scope,
+        tauri_commands::set_changes_scope,
+        tauri_commands::git_diff_commit_structured,
+        tauri_commands::git_commit_stats,
+        tauri_commands::git_commit_detail,
+        tauri_commands::git_commit_fetch_need,
+        tauri_commands::git_file_revisions,
+        tauri_commands::git_read_blob,
+        tauri_commands::wip_snapshot_now,
+        tauri_commands::wip_list,
+        tauri_commands::wip_restore,
         tauri_commands::commit_changes,
+        tauri_commands::check_large_files,
+        tauri_commands::scan_secrets,
         tauri_commands::commit_selected,
         tauri_commands::commit_patch,
         tauri_commands::commit_patch_and_files,
         tauri_commands::git_discard_paths,
+        tauri_commands::inspect_index_lock,
+        tauri_commands::clear_stale_lock,
         tauri_commands::git_discard_patch,
+        tauri_commands::batch_stage,
+        tauri_commands::batch_discard,
+        tauri_commands::batch_ignore,
         tauri_commands::git_fetch,
+        tauri_commands::unshallow_repo,
         tauri_commands::git_pull,
         tauri_commands::git_push,
+        tauri_commands::delete_remote_branch,
+        tauri_commands::delete_remote_tag,
+        tauri_commands::cancel_job,
+        tauri_commands::get_repo_busy,
+        tauri_commands::network_stats,
         tauri_commands::get_global_settings,
         tauri_commands::set_global_settings,
         tauri_commands::get_repo_settings,
         tauri_commands::set_repo_settings,
         tauri_commands::updater_install_now,
+        tauri_commands::set_update_channel,
+        tauri_commands::get_update_info,
+        tauri_commands::updater_download_for_quit,
+        tauri_commands::list_plugins,
+        tauri_commands::reload_plugins,
+        tauri_commands::set_plugin_granted,
+        tauri_commands::list_actions,
+        tauri_commands::run_action,
+        tauri_commands::register_shell_integration,
+        tauri_commands::list_hook_templates,
+        tauri_commands::list_hooks,
+        tauri_commands::install_hook,
+        tauri_commands::remove_hook,
+        tauri_commands::list_worktrees,
+        tauri_commands::get_commit_draft,
+        tauri_commands::save_commit_draft,
+        tauri_commands::pushed_commits,
+        tauri_commands::squash_commits,
+        tauri_commands::cherry_pick_commits,
+        tauri_commands::revert_commits,
+        tauri_commands::operation_progress,
+        tauri_commands::operation_continue,
+        tauri_commands::operation_skip,
+        tauri_commands::operation_abort,
+        tauri_commands::stash_save,
+        tauri_commands::stash_list,
+        tauri_commands::stash_diff,
+        tauri_commands::stash_apply,
+        tauri_commands::stash_pop,
+        tauri_commands::stash_drop,
+        tauri_commands::stash_restore_paths,
+        tauri_commands::list_backends_cmd,
+        tauri_commands::set_backend_cmd,
     ]
 }
 
 fn handle_window_event<R: tauri::Runtime>(win: &tauri::Window<R>, event: &tauri::WindowEvent) {
-    match event {
-        tauri::WindowEvent::Focused(true) => {
-            // Fire a custom event to the frontend
-            let _ = win.emit("app:focus", ());
-        }
-        _ => {}
+    if let tauri::WindowEvent::Focused(true) = event {
+        // Fire a custom event to the frontend
+        let _ = win.emit("app:focus", ());
+        refresh::request(win.app_handle(), refresh::Trigger::Focus);
     }
 }
@@ -0,0 +1,213 @@
+//! Stale `.git/index.lock` detection and recovery.
+//!
+//! git creates `index.lock` while it rewrites the index and removes it on exit. A crashed
+//! git (or an editor integration killed mid-operation) leaves it behind and every later
+//! write fails with "Unable to create '.../index.lock': File exists". A lock is treated as
+//! stale when it is older than [`STALE_AFTER`] and no git process is working in the repository.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use log::{info, warn};
+use openvcs_core::VcsError;
+use serde::Serialize;
+
+/// Locks younger than this may belong to an operation that is still running.
+const STALE_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LockInfo {
+    pub path: String,
+    pub age_secs: u64,
+    /// Whether a git process was working in this repository when the lock was inspected;
+    /// `None` when git was running but its repository could not be determined.
+    pub git_running: Option<bool>,
+    pub stale: bool,
+}
+
+/// Resolve the git directory for `workdir`, following a `.git` file (`gitdir: ...`) for
+/// linked worktrees and submodules. A bare repository is its own git directory.
+pub(crate) fn git_dir(workdir: &Path) -> PathBuf {
+    let dot_git = workdir.join(".git");
+    if dot_git.is_file() {
+        if let Ok(s) = std::fs::read_to_string(&dot_git) {
+            if let Some(rest) = s.trim().strip_prefix("gitdir:") {
+                let p = PathBuf::from(rest.trim());
+                return if p.is_absolute() { p } else { workdir.join(p) };
+            }
+        }
+    }
+    if !dot_git.exists() && workdir.join("HEAD").is_file() && workdir.join("objects").is_dir() {
+        return workdir.to_path_buf();
+    }
+    dot_git
+}
+
+/// A running process, as far as the platform shows it.
+struct Proc {
+    pid: u32,
+    ppid: u32,
+    git: bool,
+    args: Vec<String>,
+    /// Working directory; `None` when it can't be read.
+    cwd: Option<PathBuf>,
+}
+
+#[cfg(target_os = "linux")]
+fn processes() -> Option<Vec<Proc>> {
+    let entries = std::fs::read_dir("/proc").ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter_map(|e| {
+                let pid = e.file_name().to_str()?.parse().ok()?;
+                // "pid (comm) state ppid ...": comm may contain spaces and parentheses
+                let stat = std::fs::read_to_string(e.path().join("stat")).ok()?;
+                let (head, rest) = stat.rsplit_once(')')?;
+                let ppid = rest.split_whitespace().nth(1)?.parse().ok()?;
+                let git = head.split_once('(').is_some_and(|(_, comm)| comm == "git");
+                let args = std::fs::read(e.path().join("cmdline")).unwrap_or_default();
+                let args = args.split(|b| *b == 0).map(|a| String::from_utf8_lossy(a).into_owned()).collect();
+                let cwd = if git { std::fs::read_link(e.path().join("cwd")).ok() } else { None };
+                Some(Proc { pid, ppid, git, args, cwd })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(windows)]
+fn processes() -> Option<Vec<Proc>> {
+    const LIST: &str = "Get-CimInstance Win32_Process | ForEach-Object { \"$($_.ProcessId)`t$($_.ParentProcessId)`t$($_.Name)`t$($_.CommandLine)\" }";
+    let out = openvcs_git::no_window(&mut std::process::Command::new("powershell"))
+        .args(["-NoProfile", "-NonInteractive", "-Command", LIST])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(|l| {
+                let mut f = l.splitn(4, '\t');
+                let pid = f.next()?.trim().parse().ok()?;
+                let ppid = f.next()?.trim().parse().ok()?;
+                let git = f.next()?.trim().eq_ignore_ascii_case("git.exe");
+                let args = f.next().unwrap_or_default().split_whitespace().map(|a| a.trim_matches('"').to_string()).collect();
+                Some(Proc { pid, ppid, git, args, cwd: None })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn processes() -> Option<Vec<Proc>> {
+    let out = std::process::Command::new("ps").args(["-A", "-o", "pid=,ppid=,ucomm=,args="]).output().ok()?;
+    Some(
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(|l| {
+                let mut f = l.split_whitespace();
+                let pid = f.next()?.parse().ok()?;
+                let ppid = f.next()?.parse().ok()?;
+                let git = f.next()? == "git";
+                // Only the arguments are visible here, not the working directory
+                Some(Proc { pid, ppid, git, args: f.map(str::to_string).collect(), cwd: None })
+            })
+            .collect(),
+    )
+}
+
+/// Best-effort check for a git process working in the repository at `workdir`: its working
+/// directory or one of its path arguments is inside the work tree or the git directory.
+/// Our own git children (the `cat-file` helpers and history walks that live as long as a
+/// repository is open) are left out: they never hold `index.lock`, and our writes are
+/// serialized by the op gate. `None` when another git is running but its repository can't
+/// be told (another user's process, or a platform that doesn't expose working directories).
+fn git_process_running(workdir: &Path) -> Option<bool> {
+    let roots: Vec<PathBuf> = [workdir.to_path_buf(), git_dir(workdir)]
+        .into_iter()
+        .map(|p| std::fs::canonicalize(&p).unwrap_or(p))
+        .collect();
+    let inside = |p: &Path| p.is_absolute() && roots.iter().any(|r| p.starts_with(r));
+
+    let procs = processes()?;
+    let parents: std::collections::HashMap<u32, u32> = procs.iter().map(|p| (p.pid, p.ppid)).collect();
+    let me = std::process::id();
+    let ours = |mut pid: u32| {
+        // Bounded: a recycled pid could make the parent chain loop
+        for _ in 0..64 {
+            if pid == me {
+                return true;
+            }
+            match parents.get(&pid) {
+                Some(&ppid) if ppid != pid && ppid != 0 => pid = ppid,
+                _ => return false,
+            }
+        }
+        false
+    };
+
+    let mut unknown = false;
+    for p in procs.iter().filter(|p| p.git && !ours(p.pid)) {
+        let by_args = p.args.iter().any(|a| inside(Path::new(a.split_once('=').map_or(a.as_str(), |(_, v)| v))));
+        match &p.cwd {
+            _ if by_args => return Some(true),
+            Some(cwd) if inside(cwd) => return Some(true),
+            Some(_) => {}
+            None => unknown = true,
+        }
+    }
+    if unknown { None } else { Some(false) }
+}
+
+/// Inspect `index.lock` for the repository at `workdir`; `None` when there is no lock.
+pub fn index_lock(workdir: &Path) -> Option<LockInfo> {
+    let path = git_dir(workdir).join("index.lock");
+    let meta = std::fs::metadata(&path).ok()?;
+    let age = meta
+        .modified()
+        .ok()
+        .and_then(|m| SystemTime::now().duration_since(m).ok())
+        .unwrap_or_default();
+    let git_running = git_process_running(workdir);
+    Some(LockInfo {
+        path: path.display().to_string(),
+        age_secs: age.as_secs(),
+        git_running,
+        stale: age >= STALE_AFTER && git_running == Some(false),
+    })
+}
+
+/// Remove `index.lock` if it is stale (or unconditionally with `force`).
+/// Returns the removed lock, `None` if there was none, or an error if it looks live.
+pub fn clear_stale_lock(workdir: &Path, force: bool) -> Result<Option<LockInfo>, String> {
+    let Some(lock) = index_lock(workdir) else { return Ok(None) };
+    if !lock.stale && !force {
+        return Err(match lock.git_running {
+            Some(true) => "index.lock is in use by a git process running in this repository".into(),
+            None => "index.lock may be in use: git is running, but it could not be determined \
+                     whether it works in this repository"
+                .into(),
+            Some(false) => format!("index.lock was created {}s ago and may still be in use", lock.age_secs),
+        });
+    }
+    std::fs::remove_file(&lock.path).map_err(|e| format!("failed to remove {}: {e}", lock.path))?;
+    info!("locks: removed {} (age {}s, forced={})", lock.path, lock.age_secs, force);
+    Ok(Some(lock))
+}
+
+/// Run `op`; if it fails because of a stale index.lock, remove the lock and retry once.
+pub fn retry_on_stale_lock<T>(
+    workdir: &Path,
+    mut op: impl FnMut() -> openvcs_core::Result<T>,
+) -> openvcs_core::Result<T> {
+    match op() {
+        Err(VcsError::LockHeld { path }) => match clear_stale_lock(workdir, false) {
+            Ok(Some(_)) => {
+                warn!("locks: retrying after removing stale lock ({path})");
+                op()
+            }
+            _ => Err(VcsError::LockHeld { path }),
+        },
+        other => other,
+    }
+}
@@ -1,11 +1,270 @@
+use std::collections::VecDeque;
 use std::fs::{self, OpenOptions};
 use std::io::Write;
-use std::sync::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, RwLock};
+use serde::{Deserialize, Serialize};
 use time::{OffsetDateTime, UtcOffset};
-use crate::settings::{AppConfig, LogLevel};
+use crate::settings::{AppConfig, LogFormat, LogLevel};
 use zip::{write::FileOptions, CompressionMethod, ZipWriter};
 
-/// Initialize logging: console (env_logger) + append to `./logs/openvcs.log`.
+/// How many recent records the in-memory ring keeps for the live viewer.
+const RING_CAPACITY: usize = 2000;
+
+/// One captured log record, as served to the diagnostics pane.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// Monotonic sequence number; use it to page (`since_seq`) without duplicates.
+    pub seq: u64,
+    /// Unix timestamp in milliseconds.
+    pub ts_ms: i64,
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+    /// Correlation id of the operation that produced this record, if any.
+    pub job: Option<String>,
+}
+
+/// Filter for `get_recent_logs`. All fields are optional and combine with AND.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LogFilter {
+    /// Minimum severity to include (e.g. `warn` returns warn + error).
+    pub min_level: Option<LogLevel>,
+    /// Only records whose target starts with this prefix.
+    pub target: Option<String>,
+    /// Case-insensitive substring match on the message.
+    pub contains: Option<String>,
+    /// Only records newer than this sequence number.
+    pub since_seq: Option<u64>,
+    /// Return at most this many (newest) records.
+    pub limit: Option<usize>,
+}
+
+/// Fixed-size buffer of recent records plus a broadcast for live subscribers.
+struct LogRing {
+    buf: Mutex<VecDeque<LogEntry>>,
+    next_seq: std::sync::atomic::AtomicU64,
+    live: tokio::sync::broadcast::Sender<LogEntry>,
+}
+
+static RING: OnceLock<LogRing> = OnceLock::new();
+
+fn ring() -> &'static LogRing {
+    RING.get_or_init(|| LogRing {
+        buf: Mutex::new(VecDeque::with_capacity(RING_CAPACITY)),
+        next_seq: std::sync::atomic::AtomicU64::new(1),
+        live: tokio::sync::broadcast::channel(256).0,
+    })
+}
+
+fn to_level(l: log::Level) -> LogLevel {
+    match l {
+        log::Level::Trace => LogLevel::Trace,
+        log::Level::Debug => LogLevel::Debug,
+        log::Level::Info  => LogLevel::Info,
+        log::Level::Warn  => LogLevel::Warn,
+        log::Level::Error => LogLevel::Error,
+    }
+}
+
+/// Severity rank for filtering (higher is more severe).
+fn rank(l: LogLevel) -> u8 {
+    match l {
+        LogLevel::Trace => 0,
+        LogLevel::Debug => 1,
+        LogLevel::Info  => 2,
+        LogLevel::Warn  => 3,
+        LogLevel::Error => 4,
+    }
+}
+
+fn capture(r: &log::Record) {
+    let ring = ring();
+    let entry = LogEntry {
+        seq: ring.next_seq.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+        ts_ms: (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1_000_000) as i64,
+        level: to_level(r.level()),
+        target: r.target().to_string(),
+        message: r.args().to_string(),
+        job: job_context(),
+    };
+    if let Ok(mut buf) = ring.buf.lock() {
+        if buf.len() == RING_CAPACITY { buf.pop_front(); }
+        buf.push_back(entry.clone());
+    }
+    // No receivers is the common case; ignore the error.
+    let _ = ring.live.send(entry);
+}
+
+/// Snapshot of buffered records matching `filter`, oldest first.
+pub fn recent(filter: &LogFilter) -> Vec<LogEntry> {
+    let needle = filter.contains.as_deref().map(str::to_lowercase).filter(|s| !s.is_empty());
+    let min = filter.min_level.map(rank).unwrap_or(0);
+    let Ok(buf) = ring().buf.lock() else { return Vec::new(); };
+    let mut out: Vec<LogEntry> = buf
+        .iter()
+        .filter(|e| rank(e.level) >= min)
+        .filter(|e| filter.since_seq.is_none_or(|s| e.seq > s))
+        .filter(|e| filter.target.as_deref().is_none_or(|t| e.target.starts_with(t)))
+        .filter(|e| needle.as_deref().is_none_or(|n| e.message.to_lowercase().contains(n)))
+        .cloned()
+        .collect();
+    if let Some(limit) = filter.limit {
+        let skip = out.len().saturating_sub(limit);
+        out.drain(..skip);
+    }
+    out
+}
+
+/// The last `n` buffered records, or none if the ring is locked (for the panic hook, which
+/// may run while it is).
+pub fn try_tail(n: usize) -> Vec<LogEntry> {
+    let Ok(buf) = ring().buf.try_lock() else { return Vec::new() };
+    buf.iter().skip(buf.len().saturating_sub(n)).cloned().collect()
+}
+
+/// Forwarding task for the live viewer; at most one runs at a time.
+static LIVE_PUMP: Mutex<Option<tauri::async_runtime::JoinHandle<()>>> = Mutex::new(None);
+
+/// Start streaming every newly captured record to the frontend as `logs:entry` events.
+/// Replaces any previous subscription. Records dropped because the UI fell behind are
+/// reported with a `logs:lagged` event carrying the number skipped.
+pub fn start_live_pump<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
+    use tauri::Emitter;
+    use tokio::sync::broadcast::error::RecvError;
+
+    let mut rx = ring().live.subscribe();
+    let task = tauri::async_runtime::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(entry) => { let _ = app.emit("logs:entry", entry); }
+                Err(RecvError::Lagged(n)) => { let _ = app.emit("logs:lagged", n); }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+    if let Ok(mut slot) = LIVE_PUMP.lock() {
+        if let Some(prev) = slot.replace(task) { prev.abort(); }
+    }
+}
+
+/// Stop the live stream started by [`start_live_pump`], if any.
+pub fn stop_live_pump() {
+    if let Ok(mut slot) = LIVE_PUMP.lock() {
+        if let Some(prev) = slot.take() { prev.abort(); }
+    }
+}
+
+/* -------- record context (stamped onto JSON lines) -------- */
+
+/// Workdir of the repository currently open in the app, if any.
+static REPO_CONTEXT: RwLock<Option<String>> = RwLock::new(None);
+
+thread_local! {
+    /// Job id of the operation running on this thread, if any.
+    static JOB_CONTEXT: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Record which repository subsequent log lines relate to.
+pub fn set_repo_context(path: Option<&Path>) {
+    if let Ok(mut cur) = REPO_CONTEXT.write() {
+        *cur = path.map(|p| p.display().to_string());
+    }
+}
+
+fn repo_context() -> Option<String> {
+    REPO_CONTEXT.read().ok().and_then(|c| c.clone())
+}
+
+fn job_context() -> Option<String> {
+    JOB_CONTEXT.with(|j| j.borrow().clone())
+}
+
+/// Job id of the operation running on the calling thread, if any.
+pub fn current_job() -> Option<String> {
+    job_context()
+}
+
+/// Tags log records on this thread with a job id until dropped (restoring any outer job).
+/// Don't hold across `.await`; create it inside the blocking closure doing the work.
+pub struct JobScope(Option<String>);
+
+pub fn job_scope(job: openvcs_core::models::JobId) -> JobScope {
+    JobScope(JOB_CONTEXT.with(|j| j.borrow_mut().replace(job.to_string())))
+}
+
+impl Drop for JobScope {
+    fn drop(&mut self) {
+        let prev = self.0.take();
+        JOB_CONTEXT.with(|j| *j.borrow_mut() = prev);
+    }
+}
+
+/* -------- active log file -------- */
+
+/// The session log file, rotated into an archive when it exceeds `max_bytes`.
+struct ActiveLog {
+    file: Option<std::fs::File>,
+    dir: PathBuf,
+    format: LogFormat,
+    written: u64,
+    /// 0 disables size-based rotation.
+    max_bytes: u64,
+    retain: usize,
+}
+
+impl ActiveLog {
+    fn write_record(&mut self, r: &log::Record) {
+        let line = match self.format {
+            LogFormat::Text => match job_context() {
+                Some(job) => format!("{} [{}] [{}] {}\n", r.level(), r.target(), job, r.args()),
+                None => format!("{} [{}] {}\n", r.level(), r.target(), r.args()),
+            },
+            LogFormat::Json => {
+                let ts = OffsetDateTime::now_utc()
+                    .format(&time::format_description::well_known::Rfc3339)
+                    .unwrap_or_default();
+                let obj = serde_json::json!({
+                    "ts": ts,
+                    "level": r.level().as_str(),
+                    "target": r.target(),
+                    "msg": r.args().to_string(),
+                    "repo": repo_context(),
+                    "job": job_context(),
+                });
+                format!("{obj}\n")
+            }
+        };
+
+        if self.max_bytes > 0 && self.written > 0 && self.written + line.len() as u64 > self.max_bytes {
+            self.rotate();
+        }
+        if let Some(f) = self.file.as_mut() {
+            if f.write_all(line.as_bytes()).is_ok() {
+                self.written += line.len() as u64;
+            }
+        }
+    }
+
+    /// Close the current file, archive it, and start a fresh one.
+    fn rotate(&mut self) {
+        if let Some(mut f) = self.file.take() {
+            let _ = f.flush();
+        }
+        self.file = open_session_log(&self.dir, self.retain);
+        self.written = 0;
+    }
+
+    fn flush(&mut self) {
+        if let Some(f) = self.file.as_mut() {
+            let _ = f.flush();
+        }
+    }
+}
+
+/// Initialize logging: console (env_logger), `./logs/openvcs.log`, and an in-memory
+/// ring buffer backing the live log viewer.
 /// Respects `RUST_LOG` for filtering; sets a sensible default if missing.
 pub fn init() {
     // Load persisted settings early (does not require AppState) for logging configuration
@@ -13,7 +272,7 @@ pub fn init() {
 
     struct DualLogger {
         console: env_logger::Logger,
-        file: Mutex<std::fs::File>,
+        file: Option<Mutex<ActiveLog>>,
     }
     impl log::Log for DualLogger {
         fn enabled(&self, m: &log::Metadata) -> bool {
@@ -23,15 +282,16 @@ pub fn init() {
         fn log(&self, r: &log::Record) {
             if self.enabled(r.metadata()) {
                 self.console.log(r);
-                if let Ok(mut f) = self.file.lock() {
-                    let _ = writeln!(f, "{} [{}] {}", r.level(), r.target(), r.args());
+                if let Some(Ok(mut f)) = self.file.as_ref().map(|f| f.lock()) {
+                    f.write_record(r);
                 }
+                capture(r);
             }
         }
         fn flush(&self) {
             self.console.flush();
-            if let Ok(mut f) = self.file.lock() {
-                let _ = f.flush();
+            if let Some(Ok(mut f)) = self.file.as_ref().map(|f| f.lock()) {
+                f.flush();
             }
         }
     }
@@ -52,31 +312,36 @@ pub fn init() {
     }
     let console_logger = builder.build();
 
-    // Ensure ./logs exists and rotate existing openvcs.log into a timestamped .zip archive
-    let logfile = (|| -> Option<std::fs::File> {
-        let dir = std::path::Path::new("logs");
-        let _ = fs::create_dir_all(dir); // best effort
+    let dir = PathBuf::from("logs");
+    let retain = cfg.logging.retain_archives as usize;
+    let logfile = open_session_log(&dir, retain).map(|file| ActiveLog {
+        file: Some(file),
+        dir,
+        format: cfg.logging.format,
+        written: 0,
+        max_bytes: u64::from(cfg.logging.max_file_size_mb) * 1024 * 1024,
+        retain,
+    });
 
-        rotate_existing_log(dir);
-        prune_archives(dir, cfg.logging.retain_archives as usize);
+    // Without a file we still log to the console and the in-memory ring.
+    let dual = DualLogger { console: console_logger, file: logfile.map(Mutex::new) };
+    let _ = log::set_boxed_logger(Box::new(dual));
+    log::set_max_level(log::LevelFilter::Trace);
+}
 
-        // Open (truncate) the active log file for this session
-        let active = dir.join("openvcs.log");
-        OpenOptions::new().create(true).write(true).truncate(true).open(active).ok()
-    })();
+/// Ensure `dir` exists, rotate the previous `openvcs.log` into a timestamped .zip archive,
+/// prune old archives, and open (truncate) the active log file for this session.
+fn open_session_log(dir: &Path, retain_archives: usize) -> Option<std::fs::File> {
+    let _ = fs::create_dir_all(dir); // best effort
 
-    if let Some(file) = logfile {
-        let dual = DualLogger { console: console_logger, file: Mutex::new(file) };
-        let _ = log::set_boxed_logger(Box::new(dual));
-        log::set_max_level(log::LevelFilter::Trace);
-    } else {
-        // Fallback to console-only
-        let _ = log::set_boxed_logger(Box::new(console_logger));
-        log::set_max_level(log::LevelFilter::Trace);
-    }
+    rotate_existing_log(dir);
+    prune_archives(dir, retain_archives);
+
+    let active = dir.join("openvcs.log");
+    OpenOptions::new().create(true).write(true).truncate(true).open(active).ok()
 }
 
-fn rotate_existing_log(dir: &std::path::Path) {
+fn rotate_existing_log(dir: &Path) {
     let active = dir.join("openvcs.log");
     let Ok(mut src) = std::fs::File::open(&active) else { return; };
     // Skip empty files
@@ -127,8 +392,7 @@ fn rotate_existing_log(dir: &std::path::Path) {
     }
 }
 
-fn prune_archives(dir: &std::path::Path, keep: usize) {
-    use std::path::PathBuf;
+fn prune_archives(dir: &Path, keep: usize) {
     let Ok(read) = fs::read_dir(dir) else { return; };
     let mut entries: Vec<(PathBuf, std::time::SystemTime)> = Vec::new();
     for e in read.flatten() {
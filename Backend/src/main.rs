@@ -2,5 +2,8 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    if let Some(code) = openvcs_lib::run_subcommand() {
+        std::process::exit(code);
+    }
     openvcs_lib::run()
 }
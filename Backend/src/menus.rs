@@ -1,12 +1,13 @@
 use tauri::{async_runtime, menu, Emitter, Manager};
-use tauri_plugin_updater::UpdaterExt;
-use tauri::menu::{Menu, MenuBuilder, MenuEvent, MenuItem};
+use tauri::menu::{Menu, MenuBuilder, MenuEvent, MenuItem, MenuItemKind};
 use tauri_plugin_opener::OpenerExt;
 
-use crate::utilities::utilities;
+use crate::actions;
+use crate::utilities;
 use crate::state::AppState;
+use openvcs_core::models::StatusPayload;
 use std::fs::OpenOptions;
-use std::path::PathBuf;
+use std::sync::Mutex;
 
 const WIKI_URL: &str = "https://github.com/jordonbc/OpenVCS/wiki";
 
@@ -26,16 +27,18 @@ pub fn build_and_attach_menu<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::R
 
 /// ----- File -----
 fn build_file_menu<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::Result<menu::Submenu<R>> {
-    let clone_item = MenuItem::with_id(app, "clone_repo", "Clone…", true, Some("Ctrl+Shift+C"))?;
-    let add_repo_item   = MenuItem::with_id(app, "add_repo",   "Add Existing…", true, Some("Ctrl+O"))?;
-    let open_repo_item  = MenuItem::with_id(app, "open_repo",  "Switch…", true, Some("Ctrl+R"))?;
-    let settings_item = MenuItem::with_id(app, "settings", "Preferences…", true, Some("Ctrl+P"))?;
+    let clone_item = action_item(app, "clone_repo")?;
+    let init_repo_item  = action_item(app, "init_repo")?;
+    let add_repo_item   = action_item(app, "add_repo")?;
+    let open_repo_item  = action_item(app, "open_repo")?;
+    let settings_item = action_item(app, "settings")?;
 
     // macOS: keep native Quit in the App/File menu
     #[cfg(target_os = "macos")]
     {
         return menu::SubmenuBuilder::new(app, "File")
             .item(&clone_item)
+            .item(&init_repo_item)
             .item(&add_repo_item)
             .item(&open_repo_item)
             .separator()
@@ -48,43 +51,52 @@ fn build_file_menu<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::Result<menu
     // Other platforms: add explicit "Exit" item
     #[cfg(not(target_os = "macos"))]
     {
-        let exit_item = MenuItem::with_id(app, "exit", "Exit", true, None::<&str>)?;
-        return menu::SubmenuBuilder::new(app, "File")
+        let exit_item = action_item(app, "exit")?;
+        menu::SubmenuBuilder::new(app, "File")
             .item(&clone_item)
+            .item(&init_repo_item)
             .item(&add_repo_item)
             .item(&open_repo_item)
             .separator()
             .item(&settings_item)
             .separator()
             .item(&exit_item)
-            .build();
+            .build()
     }
 }
 
 /// ----- Repository -----
 fn build_repository_menu<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::Result<menu::Submenu<R>> {
-    let fetch_item  = MenuItem::with_id(app, "fetch",  "Fetch/Pull",  true, Some("F5"))?;
-    let push_item   = MenuItem::with_id(app, "push",   "Push",   true, Some("Ctrl+P"))?;
-    let commit_item = MenuItem::with_id(app, "commit", "Commit", true, Some("Ctrl+Enter"))?;
-    let repo_settings_item = MenuItem::with_id(app, "repo-settings", "Repository Settings", true, None::<&str>)?;
-    let edit_gitignore_item = MenuItem::with_id(app, "repo-edit-gitignore", "Edit .gitignore", true, None::<&str>)?;
-    let edit_gitattributes_item = MenuItem::with_id(app, "repo-edit-gitattributes", "Edit .gitattributes", true, None::<&str>)?;
+    let fetch_item  = action_item(app, "fetch")?;
+    let push_item   = action_item(app, "push")?;
+    let commit_item = action_item(app, "commit")?;
+    let previous_branch_item = action_item(app, "previous_branch")?;
+    let undo_head_move_item = action_item(app, "undo_head_move")?;
+    let repo_settings_item = action_item(app, "repo-settings")?;
+    let edit_gitignore_item = action_item(app, "repo-edit-gitignore")?;
+    let edit_gitattributes_item = action_item(app, "repo-edit-gitattributes")?;
+    let health_item = action_item(app, "repo-health")?;
     menu::SubmenuBuilder::new(app, "Repository")
         .item(&fetch_item)
         .item(&push_item)
         .item(&commit_item)
         .separator()
+        .item(&previous_branch_item)
+        .item(&undo_head_move_item)
+        .separator()
         .item(&edit_gitignore_item)
         .item(&edit_gitattributes_item)
         .item(&repo_settings_item)
+        .separator()
+        .item(&health_item)
         .build()
 }
 
 /// ----- Help -----
 fn build_help_menu<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::Result<menu::Submenu<R>> {
-    let docs_item  = MenuItem::with_id(app, "docs",  "Documentation", true, None::<&str>)?;
-    let updates_item = MenuItem::with_id(app, "check_updates", "Check for Updates…", true, None::<&str>)?;
-    let about_item = MenuItem::with_id(app, "about", "About",         true, None::<&str>)?;
+    let docs_item  = action_item(app, "docs")?;
+    let updates_item = action_item(app, "check_updates")?;
+    let about_item = action_item(app, "about")?;
     menu::SubmenuBuilder::new(app, "Help")
         .item(&docs_item)
         .item(&updates_item)
@@ -92,11 +104,63 @@ fn build_help_menu<R: tauri::Runtime>(app: &tauri::App<R>) -> tauri::Result<menu
         .build()
 }
 
+/// Managed state behind [`sync`]: counts from the last status refresh of the current repo.
+#[derive(Default)]
+pub struct MenuState {
+    counts: Mutex<Option<(usize, u32)>>,
+}
+
+/// What the current repository allows, including the last status counts.
+pub fn context<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> actions::Context {
+    let ctx = actions::Context::of(&app.state::<AppState>());
+    match app.state::<MenuState>().counts.lock().ok().and_then(|c| *c) {
+        Some((changes, ahead)) => ctx.with_counts(changes, ahead),
+        None => ctx,
+    }
+}
+
+/// Re-enable and re-label the menu after the current repository changed (`status: None`)
+/// or its status was refreshed.
+pub fn sync<R: tauri::Runtime>(app: &tauri::AppHandle<R>, status: Option<&StatusPayload>) {
+    if let Ok(mut counts) = app.state::<MenuState>().counts.lock() {
+        // Ignored entries are listed when asked for, but are nothing to commit
+        *counts = status.map(|s| (s.files.iter().filter(|f| f.status != "!").count(), s.ahead));
+    }
+    let Some(menu) = app.menu() else { return };
+    let ctx = context(app);
+    let mut pending = menu.items().unwrap_or_default();
+    while let Some(kind) = pending.pop() {
+        match kind {
+            MenuItemKind::Submenu(sub) => pending.extend(sub.items().unwrap_or_default()),
+            MenuItemKind::MenuItem(item) => {
+                let Some(def) = actions::def(&item.id().0) else { continue };
+                if let Err(e) = item
+                    .set_enabled(ctx.blocker(def).is_none())
+                    .and_then(|_| item.set_text(ctx.title(def)))
+                {
+                    log::warn!("menus: cannot update `{}`: {e}", def.id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Menu item for registry action `id`, titled and bound as [`crate::actions::ACTIONS`] says.
+fn action_item<R: tauri::Runtime>(app: &tauri::App<R>, id: &str) -> tauri::Result<MenuItem<R>> {
+    let def = crate::actions::def(id).expect("menu item missing from the action registry");
+    MenuItem::with_id(app, def.id, def.title, true, def.keybinding)
+}
+
 /// Centralized native menu event handler.
-/// Emits `"menu"` to the webview for everything except explicit items we intercept.
 pub fn handle_menu_event<R: tauri::Runtime>(app: &tauri::AppHandle<R>, event: MenuEvent) {
-    let id = event.id().0.to_string();
-    match id.as_str() {
+    dispatch(app, &event.id().0);
+}
+
+/// Run action `id`, from the menu or the command palette.
+/// Emits `"menu"` to the webview for everything except explicit items we intercept.
+pub fn dispatch<R: tauri::Runtime>(app: &tauri::AppHandle<R>, id: &str) {
+    match id {
         "exit" => {
             // Gracefully exit the application
             app.exit(0);
@@ -131,23 +195,21 @@ pub fn handle_menu_event<R: tauri::Runtime>(app: &tauri::AppHandle<R>, event: Me
             // Tell the webview to open the Settings modal
             let _ = app.emit("ui:open-repo-settings", ());
         }
+        "repo-health" => {
+            // The webview runs `repo_health` and shows the findings
+            let _ = app.emit("ui:open-repo-health", ());
+        }
         "check_updates" => {
             let app_cloned = app.clone();
             async_runtime::spawn(async move {
-                match app_cloned.updater() {
-                    Ok(updater) => match updater.check().await {
-                        Ok(Some(_u)) => {
-                            let _ = app_cloned.emit("ui:update-available", serde_json::json!({"source":"updater"}));
-                        }
-                        Ok(None) => {
-                            let _ = app_cloned.emit("ui:notify", "Already up to date");
-                        }
-                        Err(_) => {
-                            let _ = app_cloned.emit("ui:notify", "Update check failed");
-                        }
-                    },
-                    Err(_) => {
-                        let _ = app_cloned.emit("ui:notify", "Updater unavailable");
+                match crate::updater::check_and_announce(&app_cloned, "updater").await {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        let _ = app_cloned.emit("ui:notify", "Already up to date");
+                    }
+                    Err(e) => {
+                        log::warn!("updater: check failed: {}", e);
+                        let _ = app_cloned.emit("ui:notify", "Update check failed");
                     }
                 }
             });
@@ -171,13 +233,14 @@ fn open_repo_dotfile<R: tauri::Runtime>(app_handle: &tauri::AppHandle<R>, name:
         }
     };
 
-    let mut path = PathBuf::from(root);
+    let mut path = root;
     path.push(name);
 
     // Ensure the file exists (create if missing)
     if !path.exists() {
         let _ = OpenOptions::new()
             .create(true)
+            .truncate(false)
             .write(true)
             .open(&path);
     }
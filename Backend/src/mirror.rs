@@ -0,0 +1,255 @@
+//! Mirroring one remote into another (e.g. an internal Forgejo into a GitHub mirror).
+//!
+//! [`sync`] compares the branches/tags `source` and `target` advertise, fetches the ones
+//! that differ into a private namespace (`refs/openvcs-mirror/<source>/…`, so local branches
+//! and `refs/remotes` are left alone) and force-pushes them to `target` one by one (libgit2
+//! cannot push glob refspecs). Refs deleted on `source` are not deleted on `target`.
+//!
+//! Syncs can also be scheduled per repository; the schedules live in `remote-syncs.json` in
+//! the app data dir and [`start_scheduler`] runs the due ones in the background.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use directories::ProjectDirs;
+use log::{debug, info, warn};
+use openvcs_core::backend_descriptor::get_backend;
+use openvcs_core::models::{JobId, RemoteRefs, VcsEvent};
+use openvcs_core::{OnEvent, Vcs, VcsError};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::state::AppState;
+
+/// Mirrored when no refs are given.
+const DEFAULT_REFS: &[&str] = &["refs/heads/*", "refs/tags/*"];
+/// How often the scheduler looks for due syncs.
+const TICK: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncReport {
+    pub source: String,
+    pub target: String,
+    /// Refs pushed to `target`.
+    pub pushed: Vec<String>,
+    /// Refs that already matched.
+    pub up_to_date: usize,
+}
+
+/// A recurring sync of one repository's remotes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncSchedule {
+    pub path: String,
+    pub backend: String,
+    pub source: String,
+    pub target: String,
+    #[serde(default)]
+    pub refs: Vec<String>,
+    pub interval_minutes: u32,
+    /// Unix time of the last run.
+    #[serde(default)]
+    pub last_run: Option<u64>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SyncedEvent {
+    path: String,
+    source: String,
+    target: String,
+    job: JobId,
+    pushed: usize,
+    error: Option<String>,
+}
+
+/// Check and normalize the ref patterns to mirror: full branch or tag names, or a prefix
+/// ending in `*`. Empty means all branches and tags.
+pub fn patterns(refs: &[String]) -> Result<Vec<String>, String> {
+    if refs.is_empty() {
+        return Ok(DEFAULT_REFS.iter().map(|s| s.to_string()).collect());
+    }
+    refs.iter()
+        .map(|r| r.trim())
+        .map(|r| {
+            let ok = (r.starts_with("refs/heads/") || r.starts_with("refs/tags/"))
+                && !r.strip_suffix('*').unwrap_or(r).contains('*');
+            if ok { Ok(r.to_string()) } else { Err(format!("Cannot mirror `{r}`: use refs/heads/… or refs/tags/…")) }
+        })
+        .collect()
+}
+
+fn matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+fn namespaced(source: &str, full_ref: &str) -> String {
+    format!("refs/openvcs-mirror/{source}/{}", full_ref.trim_start_matches("refs/"))
+}
+
+fn remote_urls(vcs: &dyn Vcs, source: &str, target: &str) -> openvcs_core::Result<(String, String)> {
+    let remotes = vcs.list_remotes()?;
+    let find = |name: &str| {
+        remotes.iter().find(|r| r.name == name).ok_or_else(|| VcsError::Backend {
+            backend: vcs.id(),
+            msg: format!("no remote named `{name}`"),
+        })
+    };
+    Ok((find(source)?.fetch_url.clone(), find(target)?.push_url.clone()))
+}
+
+/// Mirror the refs matching `patterns` from remote `source` to remote `target`. Blocking.
+pub fn sync(
+    vcs: &dyn Vcs,
+    list_refs: fn(&str) -> openvcs_core::Result<RemoteRefs>,
+    source: &str,
+    target: &str,
+    patterns: &[String],
+    on: Option<OnEvent>,
+) -> openvcs_core::Result<SyncReport> {
+    let (source_url, target_url) = remote_urls(vcs, source, target)?;
+    let src = list_refs(&source_url)?;
+    let dst = list_refs(&target_url)?;
+
+    let wanted: Vec<_> = src.refs.iter().filter(|r| patterns.iter().any(|p| matches(p, &r.full_ref))).collect();
+    let changed: Vec<_> = wanted
+        .iter()
+        .filter(|r| !dst.refs.iter().any(|d| d.full_ref == r.full_ref && d.oid == r.oid))
+        .collect();
+    let mut report = SyncReport {
+        source: source.to_string(),
+        target: target.to_string(),
+        pushed: Vec::new(),
+        up_to_date: wanted.len() - changed.len(),
+    };
+    info!("mirror: {source} → {target}: {} to push, {} up to date", changed.len(), report.up_to_date);
+    if changed.is_empty() {
+        return Ok(report);
+    }
+
+    for p in patterns {
+        vcs.fetch(source, &format!("+{p}:{}", namespaced(source, p)), on.clone())?;
+    }
+    for r in changed {
+        if openvcs_core::cancel::is_cancelled() {
+            return Err(VcsError::Cancelled);
+        }
+        debug!("mirror: pushing {} to {target}", r.full_ref);
+        vcs.push(target, &format!("+{}:{}", namespaced(source, &r.full_ref), r.full_ref), on.clone())?;
+        if let Some(on) = &on {
+            on(VcsEvent::RemoteMessage(format!("Mirrored {} → {target}", r.full_ref)));
+        }
+        report.pushed.push(r.full_ref.clone());
+    }
+    Ok(report)
+}
+
+/* ---------------- schedules ---------------- */
+
+fn schedules_path() -> PathBuf {
+    if let Some(pd) = ProjectDirs::from("dev", "OpenVCS", "OpenVCS") {
+        pd.data_dir().join("remote-syncs.json")
+    } else {
+        PathBuf::from("remote-syncs.json")
+    }
+}
+
+pub fn load_schedules() -> Vec<SyncSchedule> {
+    std::fs::read_to_string(schedules_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_schedules(list: &[SyncSchedule]) -> Result<(), String> {
+    let p = schedules_path();
+    if let Some(parent) = p.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(list).map_err(|e| e.to_string())?;
+    std::fs::write(&p, json).map_err(|e| e.to_string())
+}
+
+/// Add, replace or (with `interval_minutes == 0`) remove the schedule for
+/// `path`/`source`/`target`. Returns the schedules of `path`.
+pub fn set_schedule(mut sched: SyncSchedule) -> Result<Vec<SyncSchedule>, String> {
+    let mut list = load_schedules();
+    let same = |s: &SyncSchedule| s.path == sched.path && s.source == sched.source && s.target == sched.target;
+    let previous = list.iter().position(same).map(|i| list.remove(i));
+    if sched.interval_minutes > 0 {
+        sched.last_run = previous.and_then(|p| p.last_run);
+        list.push(sched.clone());
+    }
+    save_schedules(&list)?;
+    Ok(list.into_iter().filter(|s| s.path == sched.path).collect())
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn is_due(s: &SyncSchedule, now: u64) -> bool {
+    s.last_run.is_none_or(|t| now >= t + u64::from(s.interval_minutes) * 60)
+}
+
+fn run_scheduled<R: Runtime>(app: &AppHandle<R>, s: &SyncSchedule) -> Result<SyncReport, String> {
+    let desc = get_backend(&s.backend).ok_or_else(|| format!("Backend not found: {}", s.backend))?;
+    let patterns = patterns(&s.refs)?;
+    let vcs = (desc.open)(Path::new(&s.path)).map_err(|e| e.to_string())?;
+
+    let job = JobId::next();
+    let state = app.state::<AppState>();
+    let _job = crate::logging::job_scope(job);
+    let _cancel = state.jobs().enter(job);
+    let _op = state.op_gates().begin(app, vcs.workdir(), "sync", job)?;
+    let res = sync(vcs.as_ref(), desc.remote_refs, &s.source, &s.target, &patterns, None).map_err(|e| e.to_string());
+
+    let _ = app.emit("repo:remote-synced", SyncedEvent {
+        path: s.path.clone(),
+        source: s.source.clone(),
+        target: s.target.clone(),
+        job,
+        pushed: res.as_ref().map_or(0, |r| r.pushed.len()),
+        error: res.as_ref().err().cloned(),
+    });
+    res
+}
+
+/// Run due scheduled syncs every minute, for as long as the app runs.
+pub fn start_scheduler<R: Runtime>(app: AppHandle<R>) {
+    let spawned = std::thread::Builder::new().name("remote-sync".into()).spawn(move || loop {
+        std::thread::sleep(TICK);
+        let now = now_secs();
+        let mut list = load_schedules();
+        let mut ran = false;
+        for s in list.iter_mut().filter(|s| is_due(s, now)) {
+            info!("mirror: scheduled sync {} → {} in {}", s.source, s.target, s.path);
+            let res = run_scheduled(&app, s);
+            if let Err(e) = &res {
+                warn!("mirror: scheduled sync of {} failed: {e}", s.path);
+            }
+            s.last_run = Some(now);
+            s.last_error = res.err();
+            ran = true;
+        }
+        if ran {
+            // Merge run results into the current file; schedules may have changed meanwhile
+            let mut current = load_schedules();
+            for c in current.iter_mut() {
+                if let Some(r) = list.iter().find(|s| s.path == c.path && s.source == c.source && s.target == c.target) {
+                    c.last_run = r.last_run;
+                    c.last_error = r.last_error.clone();
+                }
+            }
+            if let Err(e) = save_schedules(&current) {
+                warn!("mirror: failed to save schedules: {e}");
+            }
+        }
+    });
+    if let Err(e) = spawned {
+        warn!("mirror: failed to start the scheduler: {e}");
+    }
+}
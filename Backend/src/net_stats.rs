@@ -0,0 +1,154 @@
+//! Transfer statistics of network operations, for the diagnostics panel.
+//!
+//! A [`Meter`] watches the progress events of one fetch, pull, push or clone: how many
+//! objects the remote enumerated and sent, the deltas resolved and the bytes moved, read
+//! from the same git-style meter lines both backends report. [`Meter::finish`] logs a
+//! one-line summary, so users on metered connections can see what each operation cost, and
+//! keeps the last [`KEEP`] operations in memory for `network_stats`.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use log::info;
+use openvcs_core::models::{JobId, ProgressPhase, VcsEvent};
+use openvcs_core::OnEvent;
+use parking_lot::Mutex;
+use serde::Serialize;
+
+/// Operations kept for `network_stats`.
+pub const KEEP: usize = 50;
+
+static RECENT: Mutex<VecDeque<TransferStats>> = Mutex::new(VecDeque::new());
+
+/// What one network operation transferred.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TransferStats {
+    /// `fetch`, `pull`, `push` or `clone`.
+    pub op: &'static str,
+    /// Remote name; for a clone, the repository's name (the URL may carry credentials).
+    pub remote: String,
+    pub job: Option<JobId>,
+    /// Unix time the operation started.
+    pub started: u64,
+    pub duration_ms: u64,
+    /// Objects the remote enumerated while negotiating what to send.
+    pub enumerated: Option<u64>,
+    /// Objects received (fetch, pull, clone) or written (push).
+    pub objects: u64,
+    pub deltas: u64,
+    pub bytes: u64,
+    /// Average over the whole operation, negotiation included.
+    pub bytes_per_sec: u64,
+    /// `ok`, or the error kind it failed with.
+    pub outcome: String,
+}
+
+#[derive(Debug, Default)]
+struct Counts {
+    enumerated: Option<u64>,
+    objects: u64,
+    deltas: u64,
+    bytes: u64,
+}
+
+impl Counts {
+    fn observe(&mut self, line: &str) {
+        let (phase, _) = ProgressPhase::parse_line(line);
+        let (count, bytes) = ProgressPhase::parse_amounts(line);
+        let count = count.unwrap_or(0);
+        match phase {
+            ProgressPhase::Counting => self.enumerated = self.enumerated.max(Some(count)),
+            ProgressPhase::Receiving | ProgressPhase::Writing => self.objects = self.objects.max(count),
+            ProgressPhase::Resolving => self.deltas = self.deltas.max(count),
+            _ => return,
+        }
+        self.bytes = self.bytes.max(bytes.unwrap_or(0));
+    }
+}
+
+/// Collects the statistics of one operation from its progress events.
+pub struct Meter {
+    op: &'static str,
+    remote: String,
+    job: Option<JobId>,
+    started_at: u64,
+    started: Instant,
+    counts: Arc<Mutex<Counts>>,
+}
+
+impl Meter {
+    pub fn start(op: &'static str, remote: &str, job: Option<JobId>) -> Self {
+        Self {
+            op,
+            remote: remote.to_string(),
+            job,
+            started_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            started: Instant::now(),
+            counts: Arc::default(),
+        }
+    }
+
+    /// `on`, also feeding this meter.
+    pub fn watch(&self, on: OnEvent) -> OnEvent {
+        let counts = Arc::clone(&self.counts);
+        Arc::new(move |evt| {
+            if let VcsEvent::Progress { detail, .. } = &evt {
+                counts.lock().observe(detail);
+            }
+            on(evt)
+        })
+    }
+
+    /// Record the operation with its outcome and log the summary.
+    pub fn finish<T>(self, res: &openvcs_core::Result<T>) -> TransferStats {
+        let elapsed = self.started.elapsed();
+        let counts = std::mem::take(&mut *self.counts.lock());
+        let secs = elapsed.as_secs_f64();
+        let stats = TransferStats {
+            op: self.op,
+            remote: self.remote,
+            job: self.job,
+            started: self.started_at,
+            duration_ms: elapsed.as_millis() as u64,
+            enumerated: counts.enumerated,
+            objects: counts.objects,
+            deltas: counts.deltas,
+            bytes: counts.bytes,
+            bytes_per_sec: if secs > 0.0 { (counts.bytes as f64 / secs) as u64 } else { 0 },
+            outcome: match res {
+                Ok(_) => "ok".into(),
+                Err(e) => e.kind().to_string(),
+            },
+        };
+        info!(
+            "net: {} {}: {} object(s), {} in {:.1}s ({}/s){}",
+            stats.op,
+            stats.remote,
+            stats.objects,
+            size(stats.bytes),
+            secs,
+            size(stats.bytes_per_sec),
+            if stats.outcome == "ok" { String::new() } else { format!(", failed: {}", stats.outcome) }
+        );
+        let mut recent = RECENT.lock();
+        if recent.len() == KEEP {
+            recent.pop_front();
+        }
+        recent.push_back(stats.clone());
+        stats
+    }
+}
+
+fn size(bytes: u64) -> String {
+    match bytes {
+        b if b < 1 << 10 => format!("{b} B"),
+        b if b < 1 << 20 => format!("{:.1} KiB", b as f64 / 1024.0),
+        b => format!("{:.1} MiB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
+
+/// The last `limit` operations, newest first.
+pub fn recent(limit: usize) -> Vec<TransferStats> {
+    RECENT.lock().iter().rev().take(limit).cloned().collect()
+}
@@ -0,0 +1,84 @@
+//! First-run status: what a new user still has to set up before OpenVCS is useful
+//! (git, identity, SSH keys, a forge token). Drives the frontend's setup wizard.
+
+use std::path::PathBuf;
+
+use directories::BaseDirs;
+use serde::Serialize;
+
+use crate::doctor::git_config_global;
+
+/// Environment variables forge CLIs and git credential helpers read tokens from.
+const TOKEN_VARS: &[&str] = &["GH_TOKEN", "GITHUB_TOKEN", "GITLAB_TOKEN", "GL_TOKEN", "FORGEJO_TOKEN", "GITEA_TOKEN"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FirstRunStatus {
+    pub git_installed: bool,
+    pub git_version: Option<String>,
+    /// Global `user.name` / `user.email`, when both are set.
+    pub identity: Option<(String, String)>,
+    /// Public key files found in `~/.ssh` (file names only).
+    pub ssh_keys: Vec<String>,
+    /// Where a forge token was found (e.g. `env:GITHUB_TOKEN`, `gh`); never the token itself.
+    pub forge_token: Option<String>,
+    /// Git and an identity are present; everything else is optional.
+    pub ready: bool,
+}
+
+fn home() -> Option<PathBuf> {
+    BaseDirs::new().map(|b| b.home_dir().to_path_buf())
+}
+
+fn ssh_public_keys() -> Vec<String> {
+    let Some(dir) = home().map(|h| h.join(".ssh")) else { return Vec::new() };
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut keys: Vec<String> = entries
+        .flatten()
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|n| n.ends_with(".pub"))
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// A forge CLI (gh, glab) that is logged in, judged by a token in its config file.
+fn forge_cli_token() -> Option<String> {
+    let config = BaseDirs::new()?.config_dir().to_path_buf();
+    let candidates = [
+        ("gh", config.join("gh").join("hosts.yml"), "oauth_token"),
+        ("glab", config.join("glab-cli").join("config.yml"), "token:"),
+    ];
+    candidates.into_iter().find_map(|(name, path, needle)| {
+        let text = std::fs::read_to_string(path).ok()?;
+        text.contains(needle).then(|| name.to_string())
+    })
+}
+
+fn forge_token() -> Option<String> {
+    TOKEN_VARS
+        .iter()
+        .find(|v| std::env::var(v).is_ok_and(|t| !t.trim().is_empty()))
+        .map(|v| format!("env:{v}"))
+        .or_else(forge_cli_token)
+}
+
+/// Gather the status. Blocking (spawns git); run off the async runtime.
+pub fn status() -> FirstRunStatus {
+    let git_version = openvcs_git::git_version();
+    let git_installed = git_version.is_some();
+    let identity = if git_installed {
+        git_config_global("user.name").zip(git_config_global("user.email"))
+    } else {
+        None
+    };
+    let ready = git_installed && identity.is_some();
+
+    FirstRunStatus {
+        git_installed,
+        git_version,
+        identity,
+        ssh_keys: ssh_public_keys(),
+        forge_token: forge_token(),
+        ready,
+    }
+}
@@ -0,0 +1,163 @@
+//! Per-repository serialization of mutating operations.
+//!
+//! Commit, fetch, pull, push, checkout, discard, … each hold the repository's gate while
+//! they run, so a commit fired during a pull waits instead of racing it for
+//! `index.lock`. Reads (status, log, diff) don't take the gate and run concurrently.
+//! Waiting and starting are reported as `repo:op-queued` / `repo:op-started` /
+//! `repo:op-finished` events. On top of those, `repo:busy { path, op, job_id }` tells which
+//! operation holds the repository whenever that changes, and `repo:idle { path }` that the
+//! last queued one is done, so the UI can keep conflicting actions disabled for the whole
+//! stretch. A queued operation gives up when its job is cancelled (see `Jobs::enter`), so
+//! `cancel_job` works before it has started. Starting an operation stops the repository's
+//! background prefetch; finishing one drops its cached sidebar summary.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use log::{debug, info};
+use openvcs_core::cancel;
+use openvcs_core::models::JobId;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::state::AppState;
+
+/// How often a queued operation checks whether its job was cancelled.
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Serialize)]
+struct Running {
+    op: &'static str,
+    job: JobId,
+}
+
+#[derive(Default)]
+struct Gate {
+    running: Mutex<Option<Running>>,
+    waiters: Mutex<usize>,
+    freed: Condvar,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct OpEvent {
+    path: String,
+    op: &'static str,
+    job: JobId,
+    /// For `repo:op-queued`: the operation being waited on and how many are queued.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    behind: Option<Running>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    queued: Option<usize>,
+}
+
+/// Payload of `repo:busy`, and what [`OpGates::busy`] reports.
+#[derive(Debug, Clone, Serialize)]
+pub struct Busy {
+    pub path: String,
+    pub op: &'static str,
+    pub job_id: JobId,
+}
+
+/// Payload of `repo:idle`.
+#[derive(Debug, Clone, Serialize)]
+struct Idle {
+    path: String,
+}
+
+/// One gate per repository workdir, keyed by `paths::key` so two spellings of a path share
+/// it. Cheap to clone; clones share the gates.
+#[derive(Default, Clone)]
+pub struct OpGates {
+    gates: Arc<Mutex<HashMap<String, Arc<Gate>>>>,
+}
+
+/// Held for the duration of a mutating operation; releases the gate on drop.
+pub struct OpGuard<R: Runtime> {
+    gate: Arc<Gate>,
+    workdir: PathBuf,
+    app: AppHandle<R>,
+    event: OpEvent,
+}
+
+impl OpGates {
+    fn gate(&self, workdir: &Path) -> Arc<Gate> {
+        let key = crate::paths::key(workdir);
+        let mut gates = self.gates.lock().unwrap_or_else(|p| p.into_inner());
+        gates.entry(key).or_default().clone()
+    }
+
+    /// An operation currently holds the repository at `workdir`.
+    pub fn is_busy(&self, workdir: &Path) -> bool {
+        let gate = self.gate(workdir);
+        let running = gate.running.lock().unwrap_or_else(|p| p.into_inner());
+        running.is_some()
+    }
+
+    /// The operation holding the repository at `workdir`, if any.
+    pub fn busy(&self, workdir: &Path) -> Option<Busy> {
+        let gate = self.gate(workdir);
+        let running = gate.running.lock().unwrap_or_else(|p| p.into_inner());
+        let path = workdir.display().to_string();
+        running.as_ref().map(|r| Busy { path, op: r.op, job_id: r.job })
+    }
+
+    /// Wait for the repository at `workdir` to be free, then claim it for `op`. Blocking;
+    /// call from a blocking task, never from async code or a synchronous command (those run
+    /// on the main thread). Fails if the thread's job (see `Jobs::enter`) is cancelled while
+    /// waiting.
+    pub fn begin<R: Runtime>(&self, app: &AppHandle<R>, workdir: &Path, op: &'static str, job: JobId) -> Result<OpGuard<R>, String> {
+        crate::prefetch::preempt(app, workdir);
+        let gate = self.gate(workdir);
+        let path = workdir.display().to_string();
+        let mut event = OpEvent { path, op, job, behind: None, queued: None };
+
+        let mut running = gate.running.lock().unwrap_or_else(|p| p.into_inner());
+        if let Some(busy) = running.as_ref() {
+            let queued = {
+                let mut w = gate.waiters.lock().unwrap_or_else(|p| p.into_inner());
+                *w += 1;
+                *w
+            };
+            info!("ops: {op} ({job}) queued behind {} ({})", busy.op, busy.job);
+            let _ = app.emit("repo:op-queued", OpEvent { behind: Some(busy.clone()), queued: Some(queued), ..event.clone() });
+            // Callers that didn't register the job still get a token for the wait
+            let _cancel = cancel::current().is_none().then(|| app.state::<AppState>().jobs().enter(job));
+            while running.is_some() && !cancel::is_cancelled() {
+                running = gate.freed.wait_timeout(running, CANCEL_POLL).unwrap_or_else(|p| p.into_inner()).0;
+            }
+            *gate.waiters.lock().unwrap_or_else(|p| p.into_inner()) -= 1;
+            if running.is_some() {
+                drop(running);
+                info!("ops: {op} ({job}) cancelled while queued");
+                return Err(openvcs_core::VcsError::Cancelled.to_string());
+            }
+        }
+        *running = Some(Running { op, job });
+        // Under the lock, so it can't overtake the previous holder's `repo:idle`
+        let _ = app.emit("repo:busy", Busy { path: event.path.clone(), op, job_id: job });
+        drop(running);
+
+        debug!("ops: {op} ({job}) started");
+        event.behind = None;
+        let _ = app.emit("repo:op-started", event.clone());
+        Ok(OpGuard { gate, workdir: workdir.to_path_buf(), app: app.clone(), event })
+    }
+}
+
+impl<R: Runtime> Drop for OpGuard<R> {
+    fn drop(&mut self) {
+        let mut running = self.gate.running.lock().unwrap_or_else(|p| p.into_inner());
+        *running = None;
+        // A queued operation takes over and reports `repo:busy` itself
+        if *self.gate.waiters.lock().unwrap_or_else(|p| p.into_inner()) == 0 {
+            let _ = self.app.emit("repo:idle", Idle { path: self.event.path.clone() });
+        }
+        drop(running);
+        self.gate.freed.notify_one();
+        debug!("ops: {} ({}) finished", self.event.op, self.event.job);
+        self.app.state::<AppState>().summaries().invalidate(&self.workdir);
+        let _ = self.app.emit("repo:op-finished", self.event.clone());
+    }
+}
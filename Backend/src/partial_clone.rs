@@ -0,0 +1,76 @@
+//! Partial clones (`git clone --filter=blob:none`).
+//!
+//! Such a clone only has the file contents it checked out; the rest stay on a promisor
+//! remote and git fetches them the first time something reads them. For history that means
+//! a network round trip on every commit opened, and a diff that hangs when offline. [`need`]
+//! tells the UI up front which files of a commit would be fetched and from where, and with
+//! `git.offline_partial_clone` on [`check`] refuses such diffs instead of letting git reach
+//! out. Promisor remotes are read once per repository.
+
+use std::collections::BTreeMap;
+
+use log::{debug, info};
+use openvcs_core::{Vcs, VcsError};
+use parking_lot::Mutex;
+use serde::Serialize;
+
+/// Promisor remotes per repository (`paths::key`), once read.
+static PROMISORS: Mutex<BTreeMap<String, Vec<String>>> = Mutex::new(BTreeMap::new());
+
+/// Contents a commit diff would have to download first.
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchNeed {
+    /// Promisor remote the contents come from.
+    pub remote: String,
+    /// Changed files whose old or new contents are not local.
+    pub paths: Vec<String>,
+    /// `git.offline_partial_clone` is on, so the diff will not be fetched.
+    pub offline: bool,
+}
+
+/// Promisor remotes of `vcs`; empty for complete clones and backends that can't tell.
+pub fn promisors(vcs: &dyn Vcs) -> Vec<String> {
+    let key = crate::paths::key(vcs.workdir());
+    if let Some(remotes) = PROMISORS.lock().get(&key) {
+        return remotes.clone();
+    }
+    let remotes = match vcs.promisor_remotes() {
+        Ok(r) => r,
+        Err(VcsError::Unsupported(_)) => Vec::new(),
+        Err(e) => {
+            debug!("partial_clone: cannot read promisor remotes: {e}");
+            Vec::new()
+        }
+    };
+    if !remotes.is_empty() {
+        info!("partial_clone: {} is a partial clone of {}", vcs.workdir().display(), remotes.join(", "));
+    }
+    PROMISORS.lock().insert(key, remotes.clone());
+    remotes
+}
+
+/// What diffing commit `id` would fetch; `None` when everything is local.
+pub fn need(vcs: &dyn Vcs, id: &str, offline: bool) -> Result<Option<FetchNeed>, String> {
+    let Some(remote) = promisors(vcs).into_iter().next() else { return Ok(None) };
+    let paths = match vcs.missing_blobs(id) {
+        Ok(p) => p,
+        Err(VcsError::Unsupported(_)) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    Ok((!paths.is_empty()).then_some(FetchNeed { remote, paths, offline }))
+}
+
+/// Refuse to diff commit `id` when `offline` and its contents are not local.
+pub fn check(vcs: &dyn Vcs, id: &str, offline: bool) -> Result<(), String> {
+    if !offline {
+        return Ok(());
+    }
+    match need(vcs, id, offline)? {
+        Some(n) => Err(format!(
+            "Not downloaded: {} file(s) of this commit are only on {} (partial clone, offline mode)",
+            n.paths.len(),
+            n.remote
+        )),
+        None => Ok(()),
+    }
+}
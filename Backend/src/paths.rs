@@ -0,0 +1,54 @@
+//! One spelling per repository path.
+//!
+//! The same repository can be reached as `~/src/app`, `/home/me/src/app/`, through a
+//! symlinked parent, or on Windows as `C:\src\app`, `c:\src\app` and `\\?\C:\src\app`
+//! (UNC shares as `\\?\UNC\server\share`). Recents, per-repository stores and operation
+//! gates go through [`normalize`] and [`key`] so those spellings are one repository.
+
+use std::path::{Component, Path, PathBuf};
+
+/// Expand a leading `~` to the home directory and trim surrounding whitespace.
+pub fn expand(input: &str) -> PathBuf {
+    let s = input.trim();
+    let home_rest = s.strip_prefix('~').filter(|rest| rest.is_empty() || rest.starts_with(['/', '\\']));
+    match home_rest.zip(dirs::home_dir()) {
+        Some((rest, home)) => home.join(rest.trim_start_matches(['/', '\\'])),
+        None => PathBuf::from(s),
+    }
+}
+
+/// The absolute, canonical form of `path`: symlinks resolved, no trailing separator, and
+/// on Windows without the `\\?\` prefix where the plain form means the same (`dunce`).
+/// A path that does not exist is only cleaned up lexically.
+pub fn normalize(path: &Path) -> PathBuf {
+    if let Ok(p) = dunce::canonicalize(path) {
+        return p;
+    }
+    let mut out = PathBuf::new();
+    for c in dunce::simplified(path).components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(out.components().next_back(), Some(Component::Normal(_))) => {
+                out.pop();
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Map key for a repository path: [`normalize`]d, and lowercased where the default
+/// filesystems ignore case (Windows, macOS).
+pub fn key(path: &Path) -> String {
+    let s = normalize(path).to_string_lossy().into_owned();
+    if cfg!(any(windows, target_os = "macos")) {
+        s.to_lowercase()
+    } else {
+        s
+    }
+}
+
+/// Whether `a` and `b` name the same repository.
+pub fn same(a: &Path, b: &Path) -> bool {
+    a == b || key(a) == key(b)
+}
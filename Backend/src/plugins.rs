@@ -0,0 +1,349 @@
+//! Plugin host: Rhai scripts reacting to app events.
+//!
+//! Plugins live in `<config dir>/plugins/<id>/`: a `plugin.toml` manifest next to a Rhai
+//! script (`main.rhai` unless the manifest names another). A script handles an event by
+//! defining `on_<event>(ctx)` (`on_post_commit`, `on_post_pull`, `on_repo_opened`), where
+//! `ctx` is a map with the event name, the repository path and the event's details.
+//!
+//! Scripts have no file, network or process access of their own. The only ways out are the
+//! functions registered in [`engine`], each behind a [`Permission`] the manifest declares.
+//! A plugin runs only once the user has granted everything it declares (`plugins.granted`);
+//! until then the UI is asked through `plugins:permission-request`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use log::{debug, info, warn};
+use openvcs_core::models::JobId;
+use openvcs_core::Repo;
+use parking_lot::RwLock;
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+use tauri::{async_runtime, AppHandle, Emitter, Manager, Runtime};
+
+use crate::settings::AppConfig;
+use crate::state::AppState;
+
+const MANIFEST: &str = "plugin.toml";
+/// Budget per handler call, so a runaway loop cannot hang a worker thread.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// App events plugins can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginEvent {
+    PostCommit,
+    PostPull,
+    RepoOpened,
+}
+
+impl PluginEvent {
+    const ALL: [PluginEvent; 3] = [PluginEvent::PostCommit, PluginEvent::PostPull, PluginEvent::RepoOpened];
+
+    fn name(self) -> &'static str {
+        match self {
+            PluginEvent::PostCommit => "post-commit",
+            PluginEvent::PostPull => "post-pull",
+            PluginEvent::RepoOpened => "repo-opened",
+        }
+    }
+
+    /// Script function handling the event.
+    fn handler(self) -> &'static str {
+        match self {
+            PluginEvent::PostCommit => "on_post_commit",
+            PluginEvent::PostPull => "on_post_pull",
+            PluginEvent::RepoOpened => "on_repo_opened",
+        }
+    }
+}
+
+/// What a plugin may do beyond computing and logging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Permission {
+    /// `notify(message)`: show a message in the UI.
+    Notify,
+    /// `current_branch()`, `changed_files()`: read repository state.
+    ReadRepo,
+    /// `fetch()`: fetch `origin`.
+    Fetch,
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default = "default_script")]
+    script: String,
+    #[serde(default)]
+    permissions: Vec<Permission>,
+}
+
+fn default_script() -> String {
+    "main.rhai".into()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginInfo {
+    /// Directory name under `plugins/`.
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub path: String,
+    /// Events the script has handlers for.
+    pub events: Vec<PluginEvent>,
+    pub permissions: Vec<Permission>,
+    /// Whether the user has granted every permission in `permissions`.
+    pub granted: bool,
+    /// Why the plugin could not be loaded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+struct Plugin {
+    info: PluginInfo,
+    ast: Option<Arc<AST>>,
+}
+
+/// Plugins found at the last [`PluginHost::reload`]. Cheap to clone; clones share the list.
+#[derive(Default, Clone)]
+pub struct PluginHost {
+    plugins: Arc<RwLock<Vec<Plugin>>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct NotifyEvent {
+    plugin: String,
+    message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ErrorEvent {
+    plugin: String,
+    event: PluginEvent,
+    message: String,
+}
+
+/// `<config dir>/plugins`, next to `openvcs.conf`.
+pub fn dir() -> PathBuf {
+    AppConfig::path().parent().map(Path::to_path_buf).unwrap_or_default().join("plugins")
+}
+
+impl PluginHost {
+    /// Rescan the plugins directory and compile every script.
+    pub fn reload(&self, cfg: &AppConfig) -> Vec<PluginInfo> {
+        let mut found: Vec<Plugin> = match std::fs::read_dir(dir()) {
+            Ok(entries) => entries
+                .flatten()
+                .filter(|e| e.path().join(MANIFEST).is_file())
+                .map(|e| load(&e.path(), cfg))
+                .collect(),
+            Err(e) => {
+                debug!("plugins: no plugins directory ({e})");
+                Vec::new()
+            }
+        };
+        found.sort_by(|a, b| a.info.id.cmp(&b.info.id));
+        info!("plugins: {} plugin(s) found", found.len());
+        *self.plugins.write() = found;
+        self.list(cfg)
+    }
+
+    /// Known plugins, with `granted` evaluated against `cfg`.
+    pub fn list(&self, cfg: &AppConfig) -> Vec<PluginInfo> {
+        self.plugins
+            .read()
+            .iter()
+            .map(|p| PluginInfo { granted: is_granted(cfg, &p.info), ..p.info.clone() })
+            .collect()
+    }
+
+    fn subscribed(&self, event: PluginEvent) -> Vec<Plugin> {
+        self.plugins.read().iter().filter(|p| p.ast.is_some() && p.info.events.contains(&event)).cloned().collect()
+    }
+}
+
+fn is_granted(cfg: &AppConfig, info: &PluginInfo) -> bool {
+    let granted = cfg.plugins.granted.get(&info.id);
+    info.permissions.iter().all(|p| granted.is_some_and(|g| g.contains(p)))
+}
+
+fn load(path: &Path, cfg: &AppConfig) -> Plugin {
+    let id = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut info = PluginInfo {
+        id: id.clone(),
+        name: id.clone(),
+        description: String::new(),
+        path: path.display().to_string(),
+        events: Vec::new(),
+        permissions: Vec::new(),
+        granted: false,
+        error: None,
+    };
+    let compiled = std::fs::read_to_string(path.join(MANIFEST))
+        .map_err(|e| format!("read {MANIFEST}: {e}"))
+        .and_then(|s| toml::from_str::<Manifest>(&s).map_err(|e| format!("{MANIFEST}: {e}")))
+        .and_then(|m| {
+            if !m.name.trim().is_empty() {
+                info.name = m.name.trim().to_string();
+            }
+            info.description = m.description;
+            info.permissions = m.permissions;
+            info.permissions.sort();
+            info.permissions.dedup();
+            sandbox().compile_file(path.join(&m.script)).map_err(|e| format!("{}: {e}", m.script))
+        });
+    let ast = match compiled {
+        Ok(ast) => {
+            info.events = PluginEvent::ALL
+                .into_iter()
+                .filter(|ev| ast.iter_functions().any(|f| f.name == ev.handler() && f.params.len() == 1))
+                .collect();
+            Some(Arc::new(ast))
+        }
+        Err(e) => {
+            warn!("plugins: cannot load '{id}': {e}");
+            info.error = Some(e);
+            None
+        }
+    };
+    info.granted = is_granted(cfg, &info);
+    Plugin { info, ast }
+}
+
+/// Load the plugins at startup and ask for the permissions of those not yet granted.
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    let state = app.state::<AppState>();
+    let cfg = state.config();
+    if !cfg.plugins.enabled {
+        return;
+    }
+    request_permissions(app, state.plugins().reload(&cfg));
+}
+
+/// Emit `plugins:permission-request` for loadable plugins still waiting for permissions.
+pub fn request_permissions<R: Runtime>(app: &AppHandle<R>, plugins: Vec<PluginInfo>) {
+    let pending: Vec<PluginInfo> = plugins.into_iter().filter(|p| p.error.is_none() && !p.granted).collect();
+    if !pending.is_empty() {
+        info!("plugins: {} plugin(s) waiting for permissions", pending.len());
+        let _ = app.emit("plugins:permission-request", pending);
+    }
+}
+
+/// Run the handlers for `event` in the current repository in the background. `details`
+/// are added to the handlers' `ctx` map. Cheap when no plugin subscribes.
+pub fn dispatch<R: Runtime>(app: &AppHandle<R>, event: PluginEvent, details: &[(&str, String)]) {
+    let state = app.state::<AppState>();
+    let cfg = state.config();
+    if !cfg.plugins.enabled {
+        return;
+    }
+    let Some(repo) = state.current_repo() else { return };
+    let plugins: Vec<Plugin> = state.plugins().subscribed(event).into_iter().filter(|p| is_granted(&cfg, &p.info)).collect();
+    if plugins.is_empty() {
+        return;
+    }
+
+    let mut ctx = Map::new();
+    ctx.insert("event".into(), event.name().into());
+    ctx.insert("repo".into(), repo.inner().workdir().display().to_string().into());
+    for (k, v) in details {
+        ctx.insert((*k).into(), v.clone().into());
+    }
+
+    let app = app.clone();
+    async_runtime::spawn_blocking(move || {
+        for plugin in plugins {
+            let Some(ast) = &plugin.ast else { continue };
+            let id = &plugin.info.id;
+            debug!("plugins: {} → {id}", event.name());
+            let engine = engine(&app, &repo, &plugin.info);
+            if let Err(e) = engine.call_fn::<Dynamic>(&mut Scope::new(), ast, event.handler(), (ctx.clone(),)) {
+                warn!("plugins: {id} failed on {}: {e}", event.name());
+                let _ = app.emit("plugins:error", ErrorEvent { plugin: id.clone(), event, message: e.to_string() });
+            }
+        }
+    });
+}
+
+/// An engine with resource limits and without `import`, so scripts cannot load files.
+fn sandbox() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(32);
+    engine.set_max_string_size(1 << 20);
+    engine.set_max_array_size(10_000);
+    engine.set_max_map_size(10_000);
+    engine
+}
+
+/// A sandboxed engine exposing the functions `plugin` declared permissions for.
+fn engine<R: Runtime>(app: &AppHandle<R>, repo: &Arc<Repo>, plugin: &PluginInfo) -> Engine {
+    let mut engine = sandbox();
+
+    let id = plugin.id.clone();
+    engine.on_print(move |s| info!("plugin {id}: {s}"));
+
+    let allowed = |perm: Permission| {
+        let declared = plugin.permissions.contains(&perm);
+        move || -> Result<(), Box<EvalAltResult>> {
+            if declared { Ok(()) } else { Err(format!("permission `{perm:?}` not declared in {MANIFEST}").into()) }
+        }
+    };
+
+    let (app_n, id, check) = (app.clone(), plugin.id.clone(), allowed(Permission::Notify));
+    engine.register_fn("notify", move |message: &str| -> Result<(), Box<EvalAltResult>> {
+        check()?;
+        let _ = app_n.emit("plugins:notify", NotifyEvent { plugin: id.clone(), message: message.to_string() });
+        Ok(())
+    });
+
+    let (r, check) = (repo.clone(), allowed(Permission::ReadRepo));
+    engine.register_fn("current_branch", move || -> Result<String, Box<EvalAltResult>> {
+        check()?;
+        r.inner().current_branch().map(Option::unwrap_or_default).map_err(|e| e.to_string().into())
+    });
+    let (r, check) = (repo.clone(), allowed(Permission::ReadRepo));
+    engine.register_fn("changed_files", move || -> Result<i64, Box<EvalAltResult>> {
+        check()?;
+        let status = r.inner().status_payload(&Default::default()).map_err(|e| e.to_string())?;
+        Ok(status.files.len() as i64)
+    });
+
+    let (app_f, r, check) = (app.clone(), repo.clone(), allowed(Permission::Fetch));
+    engine.register_fn("fetch", move || -> Result<(), Box<EvalAltResult>> {
+        check()?;
+        let state = app_f.state::<AppState>();
+        let prune = state.config().git.prune_on_fetch;
+        let _op = state.op_gates().begin(&app_f, r.inner().workdir(), "plugin-fetch", JobId::next())?;
+        r.inner().fetch_all("origin", prune, None).map_err(|e| e.to_string())?;
+        crate::refresh::request(&app_f, crate::refresh::Trigger::Action);
+        Ok(())
+    });
+
+    engine
+}
+
+/// Grant (or revoke) everything plugin `id` declares.
+pub fn set_granted(state: &AppState, id: &str, grant: bool) -> Result<(), String> {
+    let cfg = state.config();
+    let declared = state
+        .plugins()
+        .list(&cfg)
+        .into_iter()
+        .find(|p| p.id == id)
+        .map(|p| p.permissions)
+        .ok_or_else(|| format!("Unknown plugin: {id}"))?;
+    state.edit_config(|c| {
+        if grant {
+            c.plugins.granted.insert(id.to_string(), declared);
+        } else {
+            c.plugins.granted.remove(id);
+        }
+    })
+}
@@ -0,0 +1,137 @@
+//! App-level pre-push verification.
+//!
+//! Independent of git's own `pre-push` hook: each repository can name a command (tests, a
+//! linter) in its local config under [`PRE_PUSH_COMMAND_KEY`]. `git_push` runs it from the
+//! working tree before contacting any remote, streams its output as progress of the push
+//! job and aborts the push when it exits non-zero. Whether the app may run it at all
+//! follows `git.allow_hooks`.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use log::{debug, info, warn};
+use openvcs_core::models::{ProgressPhase, VcsEvent};
+use openvcs_core::{OnEvent, Vcs};
+
+use crate::settings::HookPolicy;
+
+/// Repository config key holding the pre-push command.
+pub const PRE_PUSH_COMMAND_KEY: &str = "openvcs.prePushCommand";
+
+/// Output lines kept for the error message of a failed check.
+const OUTPUT_TAIL: usize = 20;
+
+/// The repository's pre-push command, if one is set.
+pub fn command(vcs: &dyn Vcs) -> Option<String> {
+    match vcs.local_config(PRE_PUSH_COMMAND_KEY) {
+        Ok(cmd) => cmd.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()),
+        Err(e) => {
+            debug!("pre_push: cannot read {PRE_PUSH_COMMAND_KEY}: {e}");
+            None
+        }
+    }
+}
+
+/// Run the pre-push command of `vcs`, if any, as `policy` allows. `approved` is the user's
+/// answer when the policy is `ask`; without one the push is refused so the UI can ask first.
+/// Blocking; honours the current job's cancellation.
+pub fn verify(vcs: &dyn Vcs, policy: HookPolicy, approved: Option<bool>, on: &OnEvent) -> Result<(), String> {
+    let Some(cmd) = command(vcs) else { return Ok(()) };
+    let run = match (policy, approved) {
+        (HookPolicy::Deny, _) => false,
+        (HookPolicy::Allow, _) => true,
+        (HookPolicy::Ask, Some(yes)) => yes,
+        (HookPolicy::Ask, None) => return Err(format!("Running the pre-push check `{cmd}` needs confirmation")),
+    };
+    if !run {
+        warn!("pre_push: skipping `{cmd}` (policy {policy:?})");
+        on(VcsEvent::Warning(format!("Pre-push check `{cmd}` skipped")));
+        return Ok(());
+    }
+    run_command(vcs.workdir(), &cmd, on)
+}
+
+fn run_command(workdir: &Path, cmd: &str, on: &OnEvent) -> Result<(), String> {
+    info!("pre_push: running `{cmd}` in {}", workdir.display());
+    on(progress(format!("Running pre-push check: {cmd}")));
+
+    let mut child = shell(cmd)
+        .current_dir(workdir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Could not start pre-push check `{cmd}`: {e}"))?;
+
+    let readers: Vec<_> = [
+        child.stdout.take().map(|s| Box::new(s) as Box<dyn Read + Send>),
+        child.stderr.take().map(|s| Box::new(s) as Box<dyn Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|stream| {
+        let on = on.clone();
+        std::thread::spawn(move || {
+            let mut tail = VecDeque::with_capacity(OUTPUT_TAIL);
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                on(progress(line.clone()));
+                if tail.len() == OUTPUT_TAIL { tail.pop_front(); }
+                tail.push_back(line);
+            }
+            tail
+        })
+    })
+    .collect();
+
+    // Poll rather than block in wait(), so a cancelled push can kill the command.
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(e) => return Err(format!("pre-push check `{cmd}`: {e}")),
+        }
+        if openvcs_core::cancel::is_cancelled() {
+            info!("pre_push: cancelled; killing `{cmd}`");
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("Push cancelled during the pre-push check".into());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    let tail: Vec<String> = readers.into_iter().filter_map(|h| h.join().ok()).flatten().collect();
+
+    if status.success() {
+        info!("pre_push: `{cmd}` passed");
+        on(progress("Pre-push check passed".into()));
+        Ok(())
+    } else {
+        warn!("pre_push: `{cmd}` failed ({status})");
+        let mut msg = format!("Pre-push check `{cmd}` failed ({status}); push aborted");
+        if !tail.is_empty() {
+            msg.push_str(":\n");
+            msg.push_str(&tail.join("\n"));
+        }
+        Err(msg)
+    }
+}
+
+#[cfg(windows)]
+fn shell(cmd: &str) -> Command {
+    let mut c = Command::new("cmd");
+    c.args(["/C", cmd]);
+    c
+}
+
+#[cfg(not(windows))]
+fn shell(cmd: &str) -> Command {
+    let mut c = Command::new("sh");
+    c.args(["-c", cmd]);
+    c
+}
+
+fn progress(detail: String) -> VcsEvent {
+    VcsEvent::Progress { phase: ProgressPhase::Other, percent: None, detail }
+}
@@ -0,0 +1,124 @@
+//! Warming caches right after a repository is opened.
+//!
+//! Opening a repository used to leave every panel to ask for its own data, one command at a
+//! time, after the `repo:selected` round trip. [`start`] instead reads what the first paint
+//! needs in the background: status (with ahead/behind, pushed as `repo:status`), the first
+//! page of history, the branch list and the HEAD commit. Each piece is pushed as a
+//! `repo:prefetch` event as soon as it is read, and lands in the history, summary and commit
+//! caches so the UI's own requests hit them.
+//!
+//! Prefetching is a cancellable job: any operation taking the repository's op gate
+//! ([`preempt`]), or opening another repository, stops it at its next step.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use log::{debug, info};
+use openvcs_core::models::{CommitRecord, JobId};
+use openvcs_core::Repo;
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{async_runtime, AppHandle, Emitter, Manager, Runtime};
+
+use crate::history::LogWindow;
+use crate::refresh::Trigger;
+use crate::state::AppState;
+use crate::summary::RepoSummary;
+
+/// Commits in the history page read ahead.
+const LOG_PAGE: usize = 100;
+
+/// The prefetch running, with the repository (`paths::key`) it is for.
+static RUNNING: Mutex<Option<(String, JobId)>> = Mutex::new(None);
+
+/// One piece of data read ahead.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "kebab-case")]
+enum Warmed {
+    /// The first page of history, as `git_log_window` returns it.
+    Log(LogWindow),
+    /// Branches, as `get_repo_summary` returns them.
+    Branches(RepoSummary),
+    /// The commit HEAD points at, as `git_commit_detail` returns it.
+    HeadCommit(CommitRecord),
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PrefetchEvent {
+    path: String,
+    job: JobId,
+    #[serde(flatten)]
+    warmed: Warmed,
+}
+
+/// Read ahead what the UI shows first for `repo`, replacing any prefetch still running.
+pub fn start<R: Runtime>(app: &AppHandle<R>, repo: Arc<Repo>) {
+    let key = crate::paths::key(repo.inner().workdir());
+    let job = JobId::next();
+    if let Some((_, previous)) = RUNNING.lock().replace((key, job)) {
+        app.state::<AppState>().jobs().cancel(previous);
+    }
+
+    let app = app.clone();
+    async_runtime::spawn_blocking(move || {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = app.state::<AppState>().jobs().enter(job);
+        match run(&app, &repo, job) {
+            Ok(()) => debug!("prefetch: done"),
+            Err(e) => debug!("prefetch: stopped: {e}"),
+        }
+        let mut running = RUNNING.lock();
+        if running.as_ref().is_some_and(|(_, j)| *j == job) {
+            *running = None;
+        }
+    });
+}
+
+/// Stop the prefetch of the repository at `workdir`, if one is running, so an operation
+/// doesn't compete with it.
+pub fn preempt<R: Runtime>(app: &AppHandle<R>, workdir: &Path) {
+    let key = crate::paths::key(workdir);
+    let job = match RUNNING.lock().as_ref() {
+        Some((k, job)) if *k == key => *job,
+        _ => return,
+    };
+    if app.state::<AppState>().jobs().cancel(job) {
+        info!("prefetch: preempted in {}", workdir.display());
+    }
+}
+
+fn run<R: Runtime>(app: &AppHandle<R>, repo: &Arc<Repo>, job: JobId) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let vcs = repo.inner();
+    let path = vcs.workdir().display().to_string();
+    let emit = |warmed: Warmed| -> Result<(), String> {
+        // Stop once cancelled or once another repository has been opened
+        let current = state.current_repo().is_some_and(|r| Arc::ptr_eq(&r, repo));
+        if openvcs_core::cancel::is_cancelled() || !current {
+            return Err("cancelled".into());
+        }
+        let _ = app.emit("repo:prefetch", PrefetchEvent { path: path.clone(), job, warmed });
+        Ok(())
+    };
+
+    crate::refresh::request(app, Trigger::Manual);
+
+    let log = state.history().window(vcs, None, None, 0, LOG_PAGE)?;
+    let head = log.items.first().map(|c| c.id.clone());
+    emit(Warmed::Log(log))?;
+
+    let summary = crate::tauri_commands::get_repo_summary(app.state())?;
+    emit(Warmed::Branches(summary))?;
+
+    let Some(head) = head else { return Ok(()) };
+    let record = vcs.commit_records(std::slice::from_ref(&head)).map_err(|e| e.to_string())?.pop();
+    if let Some(record) = record {
+        emit(Warmed::HeadCommit(record))?;
+    }
+    // Diffstat of HEAD for the inspector, unless it would download contents first
+    let offline = state.with_config(|c| c.git.offline_partial_clone);
+    if crate::partial_clone::need(vcs, &head, offline)?.is_none() {
+        crate::commit_cache::stats(vcs, &head)?;
+    }
+    Ok(())
+}
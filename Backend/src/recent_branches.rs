@@ -0,0 +1,112 @@
+//! Branch checkout history, for a quick-switcher that offers the last used branches first.
+//!
+//! Every successful checkout through the app moves the branch left behind and the branch
+//! checked out to the front of the repository's list. The lists live in
+//! `recent-branches.json` in the app data dir, keyed by working directory.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use directories::ProjectDirs;
+use log::{debug, warn};
+use openvcs_core::models::BranchKind;
+use openvcs_core::Vcs;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Entries kept per repository; older ones are dropped.
+const MAX_PER_REPO: usize = 50;
+
+/// Serializes read-modify-write of the history file.
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentBranch {
+    pub name: String,
+    /// Unix time of the last checkout.
+    pub last_used: u64,
+}
+
+fn history_path() -> PathBuf {
+    if let Some(pd) = ProjectDirs::from("dev", "OpenVCS", "OpenVCS") {
+        pd.data_dir().join("recent-branches.json")
+    } else {
+        PathBuf::from("recent-branches.json")
+    }
+}
+
+fn load() -> BTreeMap<String, Vec<RecentBranch>> {
+    std::fs::read_to_string(history_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save(all: &BTreeMap<String, Vec<RecentBranch>>) -> Result<(), String> {
+    let p = history_path();
+    if let Some(parent) = p.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(all).map_err(|e| e.to_string())?;
+    std::fs::write(&p, json).map_err(|e| e.to_string())
+}
+
+fn repo_key(workdir: &Path) -> String {
+    crate::paths::key(workdir)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Edit the list of `workdir` under the file lock and persist it. Failures are logged only:
+/// losing history must never fail the operation that produced it.
+fn edit(workdir: &Path, f: impl FnOnce(&mut Vec<RecentBranch>)) {
+    let _guard = LOCK.lock();
+    let mut all = load();
+    let key = repo_key(workdir);
+    let list = all.entry(key.clone()).or_default();
+    f(list);
+    list.truncate(MAX_PER_REPO);
+    if list.is_empty() {
+        all.remove(&key);
+    }
+    if let Err(e) = save(&all) {
+        warn!("recent_branches: cannot save history: {e}");
+    }
+}
+
+/// Record a checkout from `previous` (if on a branch) to `current`.
+pub fn record_checkout(workdir: &Path, previous: Option<&str>, current: Option<&str>) {
+    let now = now_secs();
+    edit(workdir, |list| {
+        // `previous` goes in first so `current` ends up in front of it.
+        for name in [previous, current].into_iter().flatten() {
+            list.retain(|b| b.name != name);
+            list.insert(0, RecentBranch { name: name.to_string(), last_used: now });
+        }
+    });
+    debug!("recent_branches: {previous:?} -> {current:?} in {}", workdir.display());
+}
+
+/// Keep the history of a renamed branch.
+pub fn record_rename(workdir: &Path, old: &str, new: &str) {
+    edit(workdir, |list| {
+        list.retain(|b| b.name != new);
+        if let Some(b) = list.iter_mut().find(|b| b.name == old) {
+            b.name = new.to_string();
+        }
+    });
+}
+
+/// Up to `limit` recently checked out local branches of `vcs`, most recent first. The
+/// current branch and branches that no longer exist are left out.
+pub fn recent(vcs: &dyn Vcs, limit: usize) -> Result<Vec<RecentBranch>, String> {
+    let branches = vcs.branches().map_err(|e| e.to_string())?;
+    let live = |name: &str| {
+        branches.iter().any(|b| matches!(b.kind, BranchKind::Local) && !b.current && b.name == name)
+    };
+    let list = load().remove(&repo_key(vcs.workdir())).unwrap_or_default();
+    Ok(list.into_iter().filter(|b| live(&b.name)).take(limit).collect())
+}
@@ -0,0 +1,67 @@
+//! Watch the open repository's refs for changes made outside the app.
+//!
+//! Using git in a terminal next to the app moves HEAD and branches behind its back. A
+//! background thread polls the repository's [`refs_key`] (HEAD plus the mtimes of
+//! `packed-refs` and the directories under `refs/heads`, `refs/remotes` and `refs/tags`) and, when
+//! it changes while no operation of ours holds the repository, emits `repo:refs-changed`
+//! and requests a status refresh so branch lists, ahead/behind and history catch up. It
+//! also notices the working tree disappearing (see [`crate::repo_lost`]).
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use log::{debug, warn};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::refresh::{self, Trigger};
+use crate::state::AppState;
+use crate::summary::{refs_key, RefsKey};
+
+/// How often refs are checked.
+const TICK: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize)]
+struct RefsChanged {
+    path: String,
+}
+
+/// Start polling the current repository's refs.
+pub fn start<R: Runtime>(app: AppHandle<R>) {
+    let spawned = std::thread::Builder::new().name("ref-watch".into()).spawn(move || {
+        let mut last: Option<(PathBuf, RefsKey)> = None;
+        loop {
+            std::thread::sleep(TICK);
+            let state = app.state::<AppState>();
+            let Some(repo) = state.current_repo() else {
+                last = None;
+                continue;
+            };
+            let workdir = repo.inner().workdir().to_path_buf();
+            // A repository that moved away stays "unchanged" until it is back
+            let head = last.as_ref().filter(|(p, _)| *p == workdir).map(|(_, k)| k.head().to_string());
+            if crate::repo_lost::poll(&app, &workdir, head.as_deref().unwrap_or_default()) {
+                continue;
+            }
+            let key = refs_key(&workdir);
+            let changed = match &last {
+                Some((path, prev)) => *path == workdir && *prev != key,
+                None => false,
+            };
+            last = Some((workdir.clone(), key));
+            if !changed {
+                continue;
+            }
+            // Our own operations announce their results themselves.
+            if state.op_gates().is_busy(&workdir) {
+                continue;
+            }
+            debug!("ref_watch: refs changed externally in {}", workdir.display());
+            let _ = app.emit("repo:refs-changed", RefsChanged { path: workdir.display().to_string() });
+            refresh::request(&app, Trigger::Watcher);
+        }
+    });
+    if let Err(e) = spawned {
+        warn!("ref_watch: failed to start: {e}");
+    }
+}
@@ -0,0 +1,135 @@
+//! Status refresh orchestrator.
+//!
+//! Focus changes, watcher events and finished user actions all want fresh status. Instead
+//! of each one making the UI call `git_status`, they call [`request`]: at most one refresh
+//! runs at a time, refreshes are spaced by `performance.status_refresh_min_ms`, and any
+//! requests arriving meanwhile collapse into a single follow-up run. Results are pushed
+//! to the frontend as `repo:status` events.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use openvcs_core::models::StatusPayload;
+use serde::Serialize;
+use tauri::{async_runtime, AppHandle, Emitter, Manager, Runtime};
+
+use crate::state::AppState;
+
+/// Why a refresh was requested (reported with the result, for diagnostics).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trigger {
+    /// The main window gained focus.
+    Focus,
+    /// A file or ref watcher saw the repository change.
+    Watcher,
+    /// A mutating command (commit, fetch, push, …) finished.
+    Action,
+    /// The UI asked explicitly.
+    Manual,
+}
+
+#[derive(Default)]
+struct Inner {
+    running: bool,
+    /// Triggers received since the current run started; non-empty means "run again".
+    pending: BTreeSet<Trigger>,
+    last_run: Option<Instant>,
+}
+
+/// Managed state backing the orchestrator.
+#[derive(Default)]
+pub struct RefreshState {
+    inner: Mutex<Inner>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct StatusEvent {
+    path: String,
+    triggers: Vec<Trigger>,
+    status: StatusPayload,
+}
+
+fn min_interval<R: Runtime>(app: &AppHandle<R>) -> Duration {
+    let ms = app.state::<AppState>().with_config(|c| c.performance.status_refresh_min_ms);
+    Duration::from_millis(ms as u64)
+}
+
+/// Ask for a status refresh of the current repository. Cheap; never blocks.
+pub fn request<R: Runtime>(app: &AppHandle<R>, trigger: Trigger) {
+    if trigger == Trigger::Watcher {
+        let state = app.state::<AppState>();
+        if let Some(repo) = state.current_repo() {
+            state.summaries().invalidate(repo.inner().workdir());
+        }
+    }
+
+    let rs = app.state::<RefreshState>();
+    let Ok(mut inner) = rs.inner.lock() else { return };
+    inner.pending.insert(trigger);
+    if inner.running {
+        log::trace!("refresh: coalesced {trigger:?}");
+        return;
+    }
+    inner.running = true;
+    drop(inner);
+
+    let app = app.clone();
+    async_runtime::spawn_blocking(move || run_loop(&app));
+}
+
+fn run_loop<R: Runtime>(app: &AppHandle<R>) {
+    let rs = app.state::<RefreshState>();
+    loop {
+        // Respect the minimum spacing, then take everything queued so far.
+        let wait = {
+            let Ok(inner) = rs.inner.lock() else { return };
+            let gap = min_interval(app);
+            inner.last_run.map(|t| gap.saturating_sub(t.elapsed())).unwrap_or_default()
+        };
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+        let triggers: Vec<Trigger> = {
+            let Ok(mut inner) = rs.inner.lock() else { return };
+            std::mem::take(&mut inner.pending).into_iter().collect()
+        };
+
+        refresh_once(app, triggers);
+
+        let Ok(mut inner) = rs.inner.lock() else { return };
+        inner.last_run = Some(Instant::now());
+        if inner.pending.is_empty() {
+            inner.running = false;
+            return;
+        }
+    }
+}
+
+fn refresh_once<R: Runtime>(app: &AppHandle<R>, triggers: Vec<Trigger>) {
+    let state = app.state::<AppState>();
+    let Some(repo) = state.current_repo() else { return };
+    if !repo.inner().caps().worktree {
+        return; // bare: no working tree to report on
+    }
+    if crate::repo_lost::is_lost(repo.inner().workdir()) {
+        return; // moved or unmounted: the UI was told via `repo:lost`
+    }
+    let opts = crate::changes_scope::apply(repo.inner(), state.config().ux.status_options());
+    let started = Instant::now();
+    match repo.inner().status_payload(&opts) {
+        Ok(mut status) => {
+            status.case_collisions = crate::case_collisions::find(repo.inner());
+            log::debug!(
+                "refresh: {} file(s), ahead={}, behind={} in {:?} ({triggers:?})",
+                status.files.len(), status.ahead, status.behind, started.elapsed()
+            );
+            let path = repo.inner().workdir().display().to_string();
+            crate::menus::sync(app, Some(&status));
+            crate::desktop::update_title(app);
+            let _ = app.emit("repo:status", StatusEvent { path, triggers, status });
+        }
+        Err(e) => log::warn!("refresh: status failed: {e}"),
+    }
+}
@@ -0,0 +1,96 @@
+//! The open repository disappearing from under the app.
+//!
+//! A working tree that was renamed, moved or sits on a drive that got unmounted makes every
+//! git call fail, and the status poll would log the same error every few seconds. The ref
+//! watcher calls [`poll`] each tick: the first time the repository is gone it emits
+//! `repo:lost` with what the UI can offer (relocate, close) and sibling directories that
+//! hold the same checkout under another name, and from then on [`is_lost`] keeps the
+//! refresh and autosave loops away from it. If it comes back (drive remounted, rename
+//! undone) `repo:found` is emitted and everything resumes.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Directories of a parent looked at for the moved repository.
+const MAX_SIBLINGS: usize = 500;
+
+/// Repository keys (`paths::key`) whose working tree is gone.
+static LOST: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Payload of `repo:lost`.
+#[derive(Debug, Clone, Serialize)]
+pub struct LostRepo {
+    pub path: String,
+    /// Directories next to it with the same HEAD commit, most likely the repository renamed.
+    pub candidates: Vec<String>,
+    /// What the UI can offer: `relocate` (`relocate_repo`) and `close` (`close_repo`).
+    pub options: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct FoundRepo {
+    path: String,
+}
+
+/// The working tree at `workdir` was reported lost and has not come back.
+pub fn is_lost(workdir: &Path) -> bool {
+    LOST.lock().contains(&crate::paths::key(workdir))
+}
+
+/// Forget `workdir` (relocated or closed).
+pub fn clear(workdir: &Path) {
+    LOST.lock().remove(&crate::paths::key(workdir));
+}
+
+/// `.git` (a directory, or a file for linked worktrees) is still there.
+fn present(workdir: &Path) -> bool {
+    workdir.join(".git").exists()
+}
+
+/// Check `workdir`, last seen with HEAD at `head`; emits `repo:lost` / `repo:found` on a
+/// change. Returns whether it is missing.
+pub fn poll<R: Runtime>(app: &AppHandle<R>, workdir: &Path, head: &str) -> bool {
+    let key = crate::paths::key(workdir);
+    let was_lost = LOST.lock().contains(&key);
+    let missing = !present(workdir);
+    match (was_lost, missing) {
+        (false, true) => {
+            warn!("repo_lost: {} is gone", workdir.display());
+            LOST.lock().insert(key);
+            let lost = LostRepo {
+                path: workdir.display().to_string(),
+                candidates: candidates(workdir, head),
+                options: vec!["relocate", "close"],
+            };
+            let _ = app.emit("repo:lost", &lost);
+        }
+        (true, false) => {
+            info!("repo_lost: {} is back", workdir.display());
+            LOST.lock().remove(&key);
+            let _ = app.emit("repo:found", FoundRepo { path: workdir.display().to_string() });
+        }
+        _ => {}
+    }
+    missing
+}
+
+/// Repositories next to `workdir` whose HEAD is `head`.
+fn candidates(workdir: &Path, head: &str) -> Vec<String> {
+    let Some(parent) = workdir.parent() else { return Vec::new() };
+    let Ok(entries) = std::fs::read_dir(parent) else { return Vec::new() };
+    if head.is_empty() {
+        return Vec::new();
+    }
+    entries
+        .filter_map(Result::ok)
+        .take(MAX_SIBLINGS)
+        .map(|e| e.path())
+        .filter(|p| p.is_dir() && present(p) && crate::summary::refs_key(p).head() == head)
+        .map(|p| p.display().to_string())
+        .collect()
+}
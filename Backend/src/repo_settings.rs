@@ -1,20 +1,30 @@
+use std::collections::BTreeMap;
+
+use openvcs_core::models::ConfigValue;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Key under `branch.<name>` holding the app's notes for that branch. Keeping them in the
+/// repository config makes them per-repository and lets `git branch -m` carry them along.
+pub const BRANCH_NOTES_KEY: &str = "openvcs-notes";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RepoConfig {
-    /// Repository-local user.name (if set)
+    /// Effective user.name, as git resolves it (see `sources`)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_name: Option<String>,
-    /// Repository-local user.email (if set)
+    /// Effective user.email, as git resolves it (see `sources`)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user_email: Option<String>,
     /// Convenience: the URL for the 'origin' remote (if present)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub origin_url: Option<String>,
-}
-
-impl Default for RepoConfig {
-    fn default() -> Self {
-        Self { user_name: None, user_email: None, origin_url: None }
-    }
+    /// Free-form notes per local branch name (see [`BRANCH_NOTES_KEY`])
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub branch_notes: BTreeMap<String, String>,
+    /// Command run before every push; a failure aborts the push (see `pre_push`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_push_command: Option<String>,
+    /// Where `user.name`, `user.email` and `core.hooksPath` come from (read-only)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sources: BTreeMap<String, ConfigValue>,
 }
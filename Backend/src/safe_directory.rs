@@ -0,0 +1,60 @@
+//! Repositories git refuses to open because another user owns them.
+//!
+//! Since 2.35.2, git and libgit2 refuse a repository owned by someone else (network shares,
+//! external drives, another account's checkout) until its path is listed in
+//! `safe.directory`. Opening one reports `VcsError::DubiousOwnership`; the open command
+//! remembers the path and tells the UI, which explains the risk and, if the user agrees,
+//! calls [`mark`]. Only paths a failed open reported can be marked, so the command cannot
+//! be used to trust arbitrary directories.
+
+use std::collections::BTreeSet;
+
+use log::{info, warn};
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use crate::doctor::probe_git;
+
+/// Paths refused for dubious ownership since startup.
+static REFUSED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Payload of `repo:dubious-ownership`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnershipIssue {
+    /// What the user asked to open.
+    pub requested: String,
+    /// The repository directory to mark safe, as git reported it.
+    pub path: String,
+}
+
+/// Remember that opening `requested` was refused for `path`; returns the UI payload.
+pub fn note_refused(requested: &str, path: &str) -> OwnershipIssue {
+    // git names the working tree; a report of the .git directory itself names its parent
+    let path = path.strip_suffix("/.git").unwrap_or(path);
+    let path = if path.is_empty() { requested } else { path };
+    warn!("safe_directory: {path} is owned by another user");
+    REFUSED.lock().insert(path.to_string());
+    OwnershipIssue { requested: requested.to_string(), path: path.to_string() }
+}
+
+/// Add `path` to the global `safe.directory` list. The user must have agreed in the UI;
+/// `path` must be one a failed open reported.
+pub fn mark(path: &str) -> Result<(), String> {
+    if !REFUSED.lock().contains(path) {
+        return Err(format!("{path} was not reported as a repository with dubious ownership"));
+    }
+    let listed = match probe_git(&["config", "--global", "--get-all", "safe.directory"]) {
+        Some((_, v)) => v.lines().any(|l| l.trim() == path),
+        None => return Err(format!("git is not available; run `git config --global --add safe.directory {path}`")),
+    };
+    if !listed {
+        match probe_git(&["config", "--global", "--add", "safe.directory", path]) {
+            Some((0, _)) => {}
+            Some((code, _)) => return Err(format!("`git config --global --add safe.directory` exited with {code}")),
+            None => return Err("git is not available".into()),
+        }
+    }
+    info!("safe_directory: marked {path} as safe");
+    REFUSED.lock().remove(path);
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! Opt-in secret scan before a commit (`git.secret_scan`).
+//!
+//! Runs the core [`SecretScanner`] with the configured rules over what a commit is about to
+//! take: the working-tree diffs of the files it stages and the patch of selected hunks. The
+//! UI shows the findings as a dialog that either lets the user continue (`warn`) or not
+//! (`block`).
+
+use std::path::Path;
+
+use log::{debug, info};
+use openvcs_core::diff::parse_unified;
+use openvcs_core::models::WhitespaceMode;
+use openvcs_core::secrets::{SecretFinding, SecretScanner};
+use openvcs_core::Vcs;
+use serde::Serialize;
+
+use crate::settings::{Git, SecretScanMode};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretScanReport {
+    pub mode: SecretScanMode,
+    pub findings: Vec<SecretFinding>,
+}
+
+/// Scan `files` (repo-relative) and `patch`. Blocking.
+pub fn scan(vcs: &dyn Vcs, cfg: &Git, files: &[String], patch: Option<&str>) -> Result<SecretScanReport, String> {
+    let mut report = SecretScanReport { mode: cfg.secret_scan, findings: Vec::new() };
+    if cfg.secret_scan == SecretScanMode::Off {
+        return Ok(report);
+    }
+    let scanner = SecretScanner::with_defaults(&cfg.secret_rules, &cfg.disabled_secret_rules)?;
+
+    for f in files {
+        let lines = vcs.diff_file(Path::new(f)).map_err(|e| e.to_string())?;
+        report.findings.extend(scanner.scan_diffs(&parse_unified(&lines, WhitespaceMode::None)));
+    }
+    if let Some(patch) = patch.filter(|p| !p.trim().is_empty()) {
+        let lines: Vec<&str> = patch.lines().collect();
+        report.findings.extend(scanner.scan_diffs(&parse_unified(&lines, WhitespaceMode::None)));
+    }
+
+    if report.findings.is_empty() {
+        debug!("secret_scan: nothing found in {} file(s)", files.len());
+    } else {
+        info!("secret_scan: {} finding(s)", report.findings.len());
+    }
+    Ok(report)
+}
@@ -0,0 +1,225 @@
+//! Multi-commit cherry-picks and reverts that survive conflicts and restarts.
+//!
+//! [`start`] records the commits to apply and applies them one at a time with
+//! [`Vcs::pick_commit`]. When one conflicts the run stops with the conflicted files left in
+//! the worktree; once they are resolved and staged, [`continue_run`] commits that step and
+//! goes on, [`skip`] drops it, and [`abort`] puts the branch back where it was. Progress is
+//! saved per repository to `operations.json` in the app data dir after every step, so the
+//! app can be closed mid-way and [`current`] still knows what is left.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use directories::ProjectDirs;
+use log::{info, warn};
+use openvcs_core::models::{HeadState, PickKind};
+use openvcs_core::{Vcs, VcsError};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Serializes read-modify-write of the operations file.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// A cherry-pick or revert of several commits in progress.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub kind: PickKind,
+    /// HEAD before the first commit was applied; [`abort`] returns to it.
+    pub orig_head: String,
+    /// Full ids, in the order they are applied.
+    pub commits: Vec<String>,
+    /// How many of `commits` are applied or skipped.
+    pub done: usize,
+    /// Commits dropped because they changed nothing or the user skipped them.
+    pub skipped: Vec<String>,
+    /// Files `commits[done]` conflicted in; empty while nothing waits for the user.
+    pub conflicts: Vec<String>,
+    /// Unix time the operation started.
+    pub started: u64,
+}
+
+impl Operation {
+    fn current_commit(&self) -> Option<&str> {
+        self.commits.get(self.done).map(String::as_str)
+    }
+
+    fn verb(&self) -> &'static str {
+        match self.kind {
+            PickKind::CherryPick => "Cherry-pick",
+            PickKind::Revert => "Revert",
+        }
+    }
+}
+
+fn operations_path() -> PathBuf {
+    if let Some(pd) = ProjectDirs::from("dev", "OpenVCS", "OpenVCS") {
+        pd.data_dir().join("operations.json")
+    } else {
+        PathBuf::from("operations.json")
+    }
+}
+
+type Operations = BTreeMap<String, Operation>;
+
+fn load() -> Operations {
+    std::fs::read_to_string(operations_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save(all: &Operations) -> Result<(), String> {
+    let p = operations_path();
+    if let Some(parent) = p.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(all).map_err(|e| e.to_string())?;
+    // Write then rename, so a crash mid-save keeps the previous state.
+    let tmp = p.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    std::fs::rename(&tmp, &p).map_err(|e| e.to_string())
+}
+
+/// Store `op` for `workdir`, or forget the operation there when `None`.
+fn store(workdir: &Path, op: Option<&Operation>) -> Result<(), String> {
+    let _guard = LOCK.lock();
+    let mut all = load();
+    let key = crate::paths::key(workdir);
+    match op {
+        Some(op) => all.insert(key, op.clone()),
+        None => all.remove(&key),
+    };
+    save(&all)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// The operation in progress in `workdir`, if any.
+pub fn current(workdir: &Path) -> Option<Operation> {
+    let _guard = LOCK.lock();
+    load().remove(&crate::paths::key(workdir))
+}
+
+/// Apply `commits` (in the given order) on top of HEAD. Returns the operation when it
+/// stopped on a conflict, `None` once every commit is applied.
+pub fn start(vcs: &dyn Vcs, kind: PickKind, commits: &[String]) -> Result<Option<Operation>, String> {
+    if commits.is_empty() {
+        return Err("No commits selected".into());
+    }
+    if let Some(op) = current(vcs.workdir()) {
+        return Err(format!(
+            "A {} is already in progress ({} of {} done); continue or abort it first",
+            op.verb().to_lowercase(),
+            op.done,
+            op.commits.len()
+        ));
+    }
+    let summary = vcs.status_summary().map_err(|e| e.to_string())?;
+    if summary.modified + summary.staged + summary.conflicted > 0 {
+        return Err("Commit or stash your changes first".into());
+    }
+    let orig_head = match vcs.head_state().map_err(|e| e.to_string())? {
+        HeadState::Branch { commit, .. } | HeadState::Detached { commit, .. } => commit,
+        HeadState::Unborn { .. } => return Err("The current branch has no commits yet".into()),
+    };
+    let records = vcs.commit_records(commits).map_err(|e| e.to_string())?;
+    let op = Operation {
+        kind,
+        orig_head,
+        commits: records.into_iter().map(|r| r.id).collect(),
+        done: 0,
+        skipped: Vec::new(),
+        conflicts: Vec::new(),
+        started: now_secs(),
+    };
+    info!("sequence: {} of {} commit(s) started", op.verb(), op.commits.len());
+    store(vcs.workdir(), Some(&op))?;
+    run(vcs, op)
+}
+
+/// Apply the remaining commits until one conflicts or all are done.
+fn run(vcs: &dyn Vcs, mut op: Operation) -> Result<Option<Operation>, String> {
+    while let Some(id) = op.current_commit().map(str::to_string) {
+        match vcs.pick_commit(&id, op.kind) {
+            Ok(_) => op.done += 1,
+            Err(VcsError::NothingToCommit) => {
+                info!("sequence: {id} changes nothing here, skipped");
+                vcs.hard_reset_head().map_err(|e| e.to_string())?;
+                op.skipped.push(id);
+                op.done += 1;
+            }
+            Err(VcsError::MergeConflict { files }) => {
+                info!("sequence: {id} conflicts in {} file(s)", files.len());
+                op.conflicts = files;
+                store(vcs.workdir(), Some(&op))?;
+                return Ok(Some(op));
+            }
+            Err(e) => {
+                warn!("sequence: {} of {id} failed: {e}", op.verb());
+                store(vcs.workdir(), Some(&op))?;
+                return Err(format!("{} of {} failed: {e}", op.verb(), short(&id)));
+            }
+        }
+        store(vcs.workdir(), Some(&op))?;
+    }
+    info!("sequence: {} finished ({} skipped)", op.verb(), op.skipped.len());
+    store(vcs.workdir(), None)?;
+    Ok(None)
+}
+
+fn short(id: &str) -> &str {
+    id.get(..7).unwrap_or(id)
+}
+
+fn in_progress(vcs: &dyn Vcs) -> Result<Operation, String> {
+    current(vcs.workdir()).ok_or_else(|| "No cherry-pick or revert is in progress".to_string())
+}
+
+/// Commit the resolved conflicts of the current step and apply the rest.
+pub fn continue_run(vcs: &dyn Vcs) -> Result<Option<Operation>, String> {
+    let mut op = in_progress(vcs)?;
+    let Some(id) = op.current_commit().map(str::to_string) else {
+        return run(vcs, op);
+    };
+    match vcs.commit_picked(&id, op.kind) {
+        Ok(_) => {}
+        Err(VcsError::NothingToCommit) => op.skipped.push(id),
+        Err(VcsError::MergeConflict { files }) => {
+            let n = files.len();
+            op.conflicts = files;
+            store(vcs.workdir(), Some(&op))?;
+            return Err(format!("{n} file(s) still have conflicts; resolve and stage them first"));
+        }
+        Err(e) => return Err(e.to_string()),
+    }
+    op.conflicts.clear();
+    op.done += 1;
+    store(vcs.workdir(), Some(&op))?;
+    run(vcs, op)
+}
+
+/// Drop the current step (discarding its changes) and apply the rest.
+pub fn skip(vcs: &dyn Vcs) -> Result<Option<Operation>, String> {
+    let mut op = in_progress(vcs)?;
+    vcs.hard_reset_head().map_err(|e| e.to_string())?;
+    if let Some(id) = op.current_commit().map(str::to_string) {
+        info!("sequence: skipping {id}");
+        op.skipped.push(id);
+        op.done += 1;
+    }
+    op.conflicts.clear();
+    store(vcs.workdir(), Some(&op))?;
+    run(vcs, op)
+}
+
+/// Discard the current step and move the branch back to where the operation started.
+pub fn abort(vcs: &dyn Vcs) -> Result<(), String> {
+    let op = in_progress(vcs)?;
+    vcs.hard_reset_head().map_err(|e| e.to_string())?;
+    vcs.reset_keep(&op.orig_head).map_err(|e| e.to_string())?;
+    info!("sequence: {} aborted after {} of {} commit(s)", op.verb(), op.done, op.commits.len());
+    store(vcs.workdir(), None)
+}
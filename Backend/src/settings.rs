@@ -1,8 +1,12 @@
 use std::{fs, io};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
-use std::time::Duration;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+pub use openvcs_core::models::WhitespaceMode;
+use openvcs_core::models::StatusOptions;
+use openvcs_core::secrets::SecretRule;
+use crate::plugins::Permission;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -19,6 +23,7 @@ pub struct AppConfig {
     #[serde(default)] pub experimental: Experimental,
     #[serde(default)] pub logging: Logging,
     #[serde(default)] pub network: Network,
+    #[serde(default)] pub plugins: Plugins,
 }
 
 impl Default for AppConfig {
@@ -37,6 +42,7 @@ impl Default for AppConfig {
             experimental: Default::default(),
             logging: Default::default(),
             network: Default::default(),
+            plugins: Default::default(),
         }
     }
 }
@@ -51,6 +57,8 @@ pub struct General {
     #[serde(default)] pub checks_on_launch: bool,
     #[serde(default)] pub telemetry: bool,
     #[serde(default)] pub crash_reports: bool,
+    /// "Open in OpenVCS" in the OS file manager's folder context menu.
+    #[serde(default)] pub shell_integration: bool,
 }
 impl Default for General {
     fn default() -> Self {
@@ -63,6 +71,7 @@ impl Default for General {
             checks_on_launch: true,
             telemetry: false,
             crash_reports: false,
+            shell_integration: false,
             }
     }
 }
@@ -70,20 +79,43 @@ impl Default for General {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Git {
     #[serde(default)] pub backend: GitBackend,
+    /// Git to run instead of the first one on PATH: the executable or its install
+    /// directory. Empty uses PATH.
+    #[serde(default)] pub executable_path: String,
     /// Default branch name used when creating new repos or inferring defaults
     #[serde(default)] pub default_branch: String,
     #[serde(default)] pub prune_on_fetch: bool,
+    /// Stash local changes before a pull and re-apply them afterwards.
+    #[serde(default)] pub autostash_on_pull: bool,
+    /// Snapshot the working tree to `refs/openvcs/wip/<branch>` this often (0 = off).
+    #[serde(default)] pub wip_autosave_minutes: u32,
     #[serde(default)] pub allow_hooks: HookPolicy,
     #[serde(default)] pub respect_core_autocrlf: bool,
+    /// Scan changes for secrets before committing (off unless opted in).
+    #[serde(default)] pub secret_scan: SecretScanMode,
+    /// Extra scan rules; a rule with a built-in id replaces that rule.
+    #[serde(default)] pub secret_rules: Vec<SecretRule>,
+    /// Ids of rules (built-in or extra) to skip.
+    #[serde(default)] pub disabled_secret_rules: Vec<String>,
+    /// In partial clones, don't fetch missing file contents to diff a commit; the diff is
+    /// marked as not downloaded instead.
+    #[serde(default)] pub offline_partial_clone: bool,
 }
 impl Default for Git {
     fn default() -> Self {
         Self {
             backend: GitBackend::System,
+            executable_path: String::new(),
             default_branch: "main".into(),
             prune_on_fetch: true,
+            autostash_on_pull: false,
+            wip_autosave_minutes: 0,
             allow_hooks: HookPolicy::Ask,
             respect_core_autocrlf: true,
+            secret_scan: SecretScanMode::Off,
+            secret_rules: Vec::new(),
+            disabled_secret_rules: Vec::new(),
+            offline_partial_clone: false,
         }
     }
 }
@@ -144,6 +176,8 @@ pub struct Lfs {
     #[serde(default)] pub concurrency: u8,
     #[serde(default)] pub require_lock_before_edit: bool,
     #[serde(default)] pub background_fetch_on_checkout: bool,
+    /// Warn before committing files larger than this (MB); 0 turns the check off.
+    #[serde(default = "default_large_file_warn_mb")] pub large_file_warn_mb: u32,
 }
 impl Default for Lfs {
     fn default() -> Self {
@@ -152,25 +186,30 @@ impl Default for Lfs {
             concurrency: 4,
             require_lock_before_edit: false,
             background_fetch_on_checkout: true,
+            large_file_warn_mb: default_large_file_warn_mb(),
         }
     }
 }
+// GitHub rejects files over 100 MB and starts warning at 50 MB.
+fn default_large_file_warn_mb() -> u32 { 50 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Performance {
     #[serde(default)] pub progressive_render: bool,
     #[serde(default)] pub gpu_accel: bool,
-    
+    /// Minimum spacing between background status refreshes.
+    #[serde(default = "default_status_refresh_ms")] pub status_refresh_min_ms: u32,
 }
 impl Default for Performance {
     fn default() -> Self {
         Self {
             progressive_render: true,
             gpu_accel: true,
-            
+            status_refresh_min_ms: default_status_refresh_ms(),
         }
     }
 }
+fn default_status_refresh_ms() -> u32 { 1500 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Integrations {
@@ -197,6 +236,10 @@ pub struct Ux {
     #[serde(default)] pub color_blind_mode: ColorBlindMode,
     /// Max number of recent repositories to keep in MRU list
     #[serde(default)] pub recents_limit: u32,
+    /// List ignored files in Changes (an ignored directory as one row)
+    #[serde(default)] pub show_ignored: bool,
+    /// List a new directory as one row instead of every file in it
+    #[serde(default)] pub collapse_untracked_dirs: bool,
 }
 impl Default for Ux {
     fn default() -> Self {
@@ -206,13 +249,24 @@ impl Default for Ux {
             vim_nav: false,
             color_blind_mode: ColorBlindMode::None,
             recents_limit: 10,
+            show_ignored: false,
+            collapse_untracked_dirs: false,
         }
     }
 }
+impl Ux {
+    /// What the Changes list asks of status (the directory scope is per repository, see
+    /// `changes_scope`).
+    pub fn status_options(&self) -> StatusOptions {
+        StatusOptions { include_ignored: self.show_ignored, collapse_untracked_dirs: self.collapse_untracked_dirs, prefix: None }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Advanced {
     #[serde(default)] pub confirm_force_push: ForcePushPolicy,
+    /// Ask before deleting branches/tags on a remote.
+    #[serde(default = "default_confirm_remote_delete")] pub confirm_remote_delete: bool,
     #[serde(default)] pub ssl_verify: bool,
     #[serde(default)] pub proxy: Proxy,
 }
@@ -220,27 +274,20 @@ impl Default for Advanced {
     fn default() -> Self {
         Self {
             confirm_force_push: ForcePushPolicy::Always,
+            confirm_remote_delete: true,
             ssl_verify: true,
             proxy: Proxy::system(),
         }
     }
 }
+fn default_confirm_remote_delete() -> bool { true }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Experimental {
     #[serde(default)] pub parallel_history_scan: bool,
     #[serde(default)] pub background_blame_index: bool,
     #[serde(default)] pub sparse_checkout_ui: bool,
 }
-impl Default for Experimental {
-    fn default() -> Self {
-        Self {
-            parallel_history_scan: false,
-            background_blame_index: false,
-            sparse_checkout_ui: false,
-        }
-    }
-}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Logging {
@@ -250,6 +297,10 @@ pub struct Logging {
     /// How many archived logs to keep after rotation.
     /// Use a serde default of 10 when the field is omitted in existing configs.
     #[serde(default = "default_retain_archives")] pub retain_archives: u32,
+    /// Line format of the log file (`text` for humans, `json` for support tooling).
+    #[serde(default)] pub format: LogFormat,
+    /// Rotate the active log once it grows past this many MiB (0 = only rotate per session).
+    #[serde(default = "default_log_max_file_mb")] pub max_file_size_mb: u32,
 }
 impl Default for Logging {
     fn default() -> Self {
@@ -257,11 +308,14 @@ impl Default for Logging {
             level: LogLevel::Info,
             live_viewer: false,
             retain_archives: 10,
+            format: LogFormat::Text,
+            max_file_size_mb: 20,
         }
     }
 }
 
 fn default_retain_archives() -> u32 { 10 }
+fn default_log_max_file_mb() -> u32 { 20 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Network {
@@ -279,50 +333,51 @@ impl Default for Network {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Plugins {
+    /// Load plugins from the config dir's `plugins/` and run them on app events.
+    #[serde(default)] pub enabled: bool,
+    /// Permissions the user granted, by plugin id.
+    #[serde(default)] pub granted: BTreeMap<String, Vec<Permission>>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum Theme { Light, Dark, System }
-impl Default for Theme { fn default() -> Self { Theme::System } }
+pub enum Theme { Light, Dark, #[default] System }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum Language { System, EN }
-impl Default for Language { fn default() -> Self { Language::System } }
+pub enum Language { #[default] System, EN }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum UpdateChannel { Stable, Beta, Nightly }
-impl Default for UpdateChannel { fn default() -> Self { UpdateChannel::Stable } }
+pub enum UpdateChannel { #[default] Stable, Beta, Nightly }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum GitBackend { System, Libgit2 }
-impl Default for GitBackend { fn default() -> Self { GitBackend::System } }
+pub enum GitBackend { #[default] System, Libgit2 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum DefaultBackend { Git }
-impl Default for DefaultBackend { fn default() -> Self { DefaultBackend::Git } }
+pub enum DefaultBackend { #[default] Git }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum HookPolicy { Deny, Ask, Allow }
-impl Default for HookPolicy { fn default() -> Self { HookPolicy::Ask } }
+pub enum HookPolicy { Deny, #[default] Ask, Allow }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+/// What a secret found in a commit does: nothing (`off`), a dialog the user can dismiss
+/// (`warn`), or a dialog that refuses the commit (`block`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum CredentialHelper { OsKeychain, None }
-impl Default for CredentialHelper { fn default() -> Self { CredentialHelper::OsKeychain } }
+pub enum SecretScanMode { #[default] Off, Warn, Block }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum SshAgent { Env, OnePassword, Pageant, None }
-impl Default for SshAgent { fn default() -> Self { SshAgent::Env } }
+pub enum CredentialHelper { #[default] OsKeychain, None }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum WhitespaceMode { None, Eol, All }
-impl Default for WhitespaceMode { fn default() -> Self { WhitespaceMode::None } }
+pub enum SshAgent { #[default] Env, OnePassword, Pageant, None }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalTool {
@@ -335,25 +390,21 @@ impl ExternalTool {
 }
 impl Default for ExternalTool { fn default() -> Self { Self::disabled() } }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum EditorChoice { System, Code, Clion, Rider, Neovim, Custom }
-impl Default for EditorChoice { fn default() -> Self { EditorChoice::System } }
+pub enum EditorChoice { #[default] System, Code, Clion, Rider, Neovim, Custom }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum IssueProvider { Auto, Github, Gitlab, Forgejo }
-impl Default for IssueProvider { fn default() -> Self { IssueProvider::Auto } }
+pub enum IssueProvider { #[default] Auto, Github, Gitlab, Forgejo }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum ColorBlindMode { None, Protanopia, Deuteranopia, Tritanopia }
-impl Default for ColorBlindMode { fn default() -> Self { ColorBlindMode::None } }
+pub enum ColorBlindMode { #[default] None, Protanopia, Deuteranopia, Tritanopia }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum ForcePushPolicy { Always, TrackedRemotes, Never }
-impl Default for ForcePushPolicy { fn default() -> Self { ForcePushPolicy::Always } }
+pub enum ForcePushPolicy { #[default] Always, TrackedRemotes, Never }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Proxy {
@@ -365,26 +416,22 @@ impl Proxy {
 }
 impl Default for Proxy { fn default() -> Self { Proxy::system() } }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProxyMode { #[default] System, Manual, Off }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum ProxyMode { System, Manual, Off }
-impl Default for ProxyMode { fn default() -> Self { ProxyMode::System } }
+pub enum LogLevel { Trace, Debug, #[default] Info, Warn, Error }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "kebab-case")]
-pub enum LogLevel { Trace, Debug, Info, Warn, Error }
-impl Default for LogLevel { fn default() -> Self { LogLevel::Info } }
+pub enum LogFormat { #[default] Text, Json }
 
 //
 // ──────────────────────────────────────────────────────────────────────────────
 // Convenience
 // ──────────────────────────────────────────────────────────────────────────────
-impl Network {
-    pub fn http_low_speed_time(&self) -> Duration {
-        Duration::from_secs(self.http_low_speed_time_secs)
-    }
-}
-
 impl AppConfig {
     /// ~/.config/openvcs/openvcs.conf (XDG/macOS/Windows aware)
     pub fn path() -> PathBuf {
@@ -441,8 +488,10 @@ impl AppConfig {
 
         // LFS
         self.lfs.concurrency = self.lfs.concurrency.clamp(1, 16);
+        self.lfs.large_file_warn_mb = self.lfs.large_file_warn_mb.min(4096);
 
         // Performance
+        self.performance.status_refresh_min_ms = self.performance.status_refresh_min_ms.clamp(250, 60_000);
 
         // Network
         self.network.http_low_speed_time_secs =
@@ -456,5 +505,6 @@ impl AppConfig {
         // Logging
         if self.logging.retain_archives == 0 { self.logging.retain_archives = 1; }
         self.logging.retain_archives = self.logging.retain_archives.clamp(1, 100);
+        self.logging.max_file_size_mb = self.logging.max_file_size_mb.min(1024);
     }
 }
@@ -0,0 +1,296 @@
+//! "Open in OpenVCS" entries in the OS file manager.
+//!
+//! Opt-in via `general.shell_integration`. Registration is per user and needs no
+//! elevation:
+//! - Windows: a folder (and folder background) verb under `HKCU\Software\Classes\Directory`.
+//! - Linux: a Nautilus script and a Dolphin service menu (KF5 and KF6 locations).
+//! - macOS: a Finder Quick Action (`~/Library/Services/Open in OpenVCS.workflow`).
+//!
+//! Every entry launches this executable with the folder as its argument, which startup
+//! opens as the repository (see `open_initial_repo` in `lib.rs`). Registering again
+//! rewrites the entries, so it also repairs them after the app has moved.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use log::{debug, info};
+
+const ENTRY_TITLE: &str = "Open in OpenVCS";
+
+/// Register (`true`) or remove (`false`) the file manager entries. Blocking.
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    if enabled {
+        let exe = launcher().map_err(|e| format!("Cannot locate the OpenVCS executable: {e}"))?;
+        info!("shell_integration: registering for {}", exe.display());
+        register(&exe).map_err(|e| format!("Could not add \"{ENTRY_TITLE}\": {e}"))
+    } else {
+        info!("shell_integration: removing");
+        unregister().map_err(|e| format!("Could not remove \"{ENTRY_TITLE}\": {e}"))
+    }
+}
+
+/// The path file managers should launch: the AppImage itself rather than its
+/// temporary mount, otherwise the running executable.
+fn launcher() -> io::Result<PathBuf> {
+    match std::env::var_os("APPIMAGE") {
+        Some(p) if !p.is_empty() => Ok(PathBuf::from(p)),
+        _ => std::env::current_exe(),
+    }
+}
+
+/// Write `contents` to `path`, creating parent directories; `exec` marks it executable.
+#[cfg(not(windows))]
+fn write_file(path: &Path, contents: &str, exec: bool) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, contents)?;
+    if exec {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    debug!("shell_integration: wrote {}", path.display());
+    Ok(())
+}
+
+/// Remove a file or directory tree, treating "already gone" as success.
+#[cfg(not(windows))]
+fn remove(path: &Path) -> io::Result<()> {
+    let res = if path.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) };
+    match res {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Single-quote `s` for `sh`.
+#[cfg(not(windows))]
+fn sh_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+// ----- Windows -----
+
+#[cfg(windows)]
+const VERB_KEYS: [(&str, &str); 2] = [
+    // Right-click on a folder: the folder is %1.
+    (r"HKCU\Software\Classes\Directory\shell\OpenVCS", "%1"),
+    // Right-click inside a folder: the folder shown is %V.
+    (r"HKCU\Software\Classes\Directory\Background\shell\OpenVCS", "%V"),
+];
+
+#[cfg(windows)]
+fn reg(args: &[&str]) -> io::Result<()> {
+    let out = openvcs_git::no_window(&mut std::process::Command::new("reg")).args(args).output()?;
+    if out.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(String::from_utf8_lossy(&out.stderr).trim().to_string()))
+    }
+}
+
+#[cfg(windows)]
+fn register(exe: &Path) -> io::Result<()> {
+    let exe = exe.display().to_string();
+    for (key, arg) in VERB_KEYS {
+        reg(&["add", key, "/ve", "/d", ENTRY_TITLE, "/f"])?;
+        reg(&["add", key, "/v", "Icon", "/d", &exe, "/f"])?;
+        let command = format!("\"{exe}\" \"{arg}\"");
+        reg(&["add", &format!(r"{key}\command"), "/ve", "/d", &command, "/f"])?;
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn unregister() -> io::Result<()> {
+    for (key, _) in VERB_KEYS {
+        // Fails when the key is absent, which is what we want anyway.
+        if let Err(e) = reg(&["delete", key, "/f"]) {
+            debug!("shell_integration: {key}: {e}");
+        }
+    }
+    Ok(())
+}
+
+// ----- Linux and other freedesktop systems -----
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn linux_paths() -> io::Result<(PathBuf, [PathBuf; 2])> {
+    let data = dirs::data_dir().ok_or_else(|| io::Error::other("no XDG data directory"))?;
+    Ok((
+        data.join("nautilus/scripts").join(ENTRY_TITLE),
+        [
+            data.join("kio/servicemenus/openvcs.desktop"),
+            data.join("kservices5/ServiceMenus/openvcs.desktop"),
+        ],
+    ))
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn register(exe: &Path) -> io::Result<()> {
+    let (nautilus, dolphin) = linux_paths()?;
+    let exe_sh = sh_quote(&exe.to_string_lossy());
+    // Nautilus passes the selection as arguments and runs in the folder being shown.
+    let script = format!(
+        "#!/bin/sh\n\
+         # Added by OpenVCS (Settings > General > file manager integration).\n\
+         for f in \"$@\"; do [ -d \"$f\" ] && exec {exe_sh} \"$f\"; done\n\
+         exec {exe_sh} \"$PWD\"\n"
+    );
+    write_file(&nautilus, &script, true)?;
+
+    let service = format!(
+        "[Desktop Entry]\n\
+         Type=Service\n\
+         MimeType=inode/directory;\n\
+         Actions=openInOpenVCS;\n\
+         X-KDE-ServiceTypes=KonqPopupMenu/Plugin\n\
+         \n\
+         [Desktop Action openInOpenVCS]\n\
+         Name={ENTRY_TITLE}\n\
+         Icon=openvcs\n\
+         Exec=\"{}\" %f\n",
+        exe.display()
+    );
+    // KF6 only loads executable service menus.
+    for path in &dolphin {
+        write_file(path, &service, true)?;
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn unregister() -> io::Result<()> {
+    let (nautilus, dolphin) = linux_paths()?;
+    remove(&nautilus)?;
+    dolphin.iter().try_for_each(|p| remove(p))
+}
+
+// ----- macOS -----
+
+#[cfg(target_os = "macos")]
+fn workflow_path() -> io::Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| io::Error::other("no home directory"))?;
+    Ok(home.join("Library/Services").join(format!("{ENTRY_TITLE}.workflow")))
+}
+
+#[cfg(target_os = "macos")]
+fn register(exe: &Path) -> io::Result<()> {
+    let wf = workflow_path()?;
+    let info = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>NSServices</key>
+	<array>
+		<dict>
+			<key>NSMenuItem</key>
+			<dict><key>default</key><string>{ENTRY_TITLE}</string></dict>
+			<key>NSMessage</key><string>runWorkflowAsService</string>
+			<key>NSRequiredContext</key>
+			<dict><key>NSApplicationIdentifier</key><string>com.apple.finder</string></dict>
+			<key>NSSendFileTypes</key>
+			<array><string>public.folder</string></array>
+		</dict>
+	</array>
+</plist>
+"#
+    );
+    // Detach so the Quick Action finishes while OpenVCS keeps running.
+    let command = xml_escape(&format!(
+        "for f in \"$@\"; do {} \"$f\" >/dev/null 2>&1 & done",
+        sh_quote(&exe.to_string_lossy())
+    ));
+    let document = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>AMApplicationBuild</key><string>523</string>
+	<key>AMApplicationVersion</key><string>2.10</string>
+	<key>AMDocumentVersion</key><string>2</string>
+	<key>actions</key>
+	<array>
+		<dict>
+			<key>action</key>
+			<dict>
+				<key>AMAccepts</key>
+				<dict>
+					<key>Container</key><string>List</string>
+					<key>Optional</key><true/>
+					<key>Types</key><array><string>com.apple.cocoa.path</string></array>
+				</dict>
+				<key>AMActionVersion</key><string>2.0.3</string>
+				<key>AMParameterProperties</key>
+				<dict>
+					<key>COMMAND_STRING</key><dict/>
+					<key>CheckedForUserDefaultShell</key><dict/>
+					<key>inputMethod</key><dict/>
+					<key>shell</key><dict/>
+					<key>source</key><dict/>
+				</dict>
+				<key>AMProvides</key>
+				<dict>
+					<key>Container</key><string>List</string>
+					<key>Types</key><array><string>com.apple.cocoa.string</string></array>
+				</dict>
+				<key>ActionBundlePath</key><string>/System/Library/Automator/Run Shell Script.action</string>
+				<key>ActionName</key><string>Run Shell Script</string>
+				<key>ActionParameters</key>
+				<dict>
+					<key>COMMAND_STRING</key><string>{command}</string>
+					<key>CheckedForUserDefaultShell</key><true/>
+					<key>inputMethod</key><integer>1</integer>
+					<key>shell</key><string>/bin/sh</string>
+					<key>source</key><string></string>
+				</dict>
+				<key>BundleIdentifier</key><string>com.apple.RunShellScript</string>
+				<key>CFBundleVersion</key><string>2.0.3</string>
+				<key>CanShowSelectedItemsWhenRun</key><false/>
+				<key>CanShowWhenRun</key><true/>
+				<key>Class Name</key><string>RunShellScriptAction</string>
+				<key>InputUUID</key><string>6F1B2A43-6C6F-4E0C-9C59-0E4E2B1D0A01</string>
+				<key>OutputUUID</key><string>6F1B2A43-6C6F-4E0C-9C59-0E4E2B1D0A02</string>
+				<key>UUID</key><string>6F1B2A43-6C6F-4E0C-9C59-0E4E2B1D0A03</string>
+				<key>isViewVisible</key><true/>
+			</dict>
+		</dict>
+	</array>
+	<key>connectors</key><dict/>
+	<key>workflowMetaData</key>
+	<dict>
+		<key>serviceInputTypeIdentifier</key><string>com.apple.Automator.fileSystemObject.folder</string>
+		<key>serviceOutputTypeIdentifier</key><string>com.apple.Automator.nothing</string>
+		<key>serviceProcessesInput</key><integer>0</integer>
+		<key>workflowTypeIdentifier</key><string>com.apple.Automator.servicesMenu</string>
+	</dict>
+</dict>
+</plist>
+"#
+    );
+    write_file(&wf.join("Contents/Info.plist"), &info, false)?;
+    write_file(&wf.join("Contents/document.wflow"), &document, false)?;
+    refresh_services();
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn unregister() -> io::Result<()> {
+    remove(&workflow_path()?)?;
+    refresh_services();
+    Ok(())
+}
+
+/// Ask the pasteboard server to rescan services so Finder picks up the change now.
+#[cfg(target_os = "macos")]
+fn refresh_services() {
+    if let Err(e) = std::process::Command::new("/System/Library/CoreServices/pbs").arg("-update").status() {
+        debug!("shell_integration: pbs -update failed: {e}");
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
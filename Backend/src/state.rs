@@ -1,5 +1,5 @@
 use std::{fs, io};
-use std::{path::PathBuf, sync::Arc};
+use std::{path::{Path, PathBuf}, sync::Arc};
 
 use log::{debug, info};
 use parking_lot::RwLock;
@@ -29,12 +29,27 @@ pub struct AppState {
 
     /// MRU list for “Recents”
     recents: RwLock<Vec<PathBuf>>,
+
+    /// Per-repo gates serializing mutating operations
+    op_gates: crate::ops::OpGates,
+
+    /// Per-repo open history walks backing `git_log_window`
+    history: crate::history::HistoryCache,
+
+    /// Per-repo sidebar summaries, keyed by HEAD + refs
+    summaries: crate::summary::SummaryCache,
+
+    /// Cancellation tokens of running jobs
+    jobs: crate::jobs::Jobs,
+
+    /// Loaded plugins
+    plugins: crate::plugins::PluginHost,
 }
 
 impl AppState {
     pub fn new_with_config() -> Self {
         let cfg = AppConfig::load_or_default(); // reads ~/.config/openvcs/openvcs.conf
-        let mut s = Self {
+        let s = Self {
             config: RwLock::new(cfg),
             repo_config: RwLock::new(RepoConfig::default()),
             ..Default::default()
@@ -46,12 +61,6 @@ impl AppState {
         s
     }
 
-    /// Persist current config to disk.
-    pub fn save_config(&self) -> Result<(), String> {
-        let cfg = self.config.read().clone();
-        cfg.save().map_err(|e| e.to_string())
-    }
-
     /* -------- config access -------- */
 
     /// Snapshot of current config (cheap clone; sections are small).
@@ -74,6 +83,9 @@ impl AppState {
         next.validate();
         next.save().map_err(|e| e.to_string())?;
         *self.config.write() = next;
+        crate::telemetry::set_enabled(self.config.read().general.telemetry);
+        crate::crash::set_enabled(self.config.read().general.crash_reports);
+        crate::git_exe::apply(&self.config.read().git.executable_path);
         self.enforce_recents_limit_and_persist();
         Ok(())
     }
@@ -102,18 +114,17 @@ impl AppState {
         next.validate();
         next.save().map_err(|e| e.to_string())?;
         *self.config.write() = next;
+        crate::telemetry::set_enabled(self.config.read().general.telemetry);
+        crate::crash::set_enabled(self.config.read().general.crash_reports);
+        crate::git_exe::apply(&self.config.read().git.executable_path);
         self.enforce_recents_limit_and_persist();
         Ok(())
     }
 
     /* -------- repo lifecycle -------- */
 
-    pub fn has_repo(&self) -> bool {
-        self.current_repo.read().is_some()
-    }
-
     pub fn set_current_repo(&self, repo: Arc<Repo>) {
-        let path = repo.inner().workdir().to_path_buf();
+        let path = crate::paths::normalize(repo.inner().workdir());
 
         info!(
             "AppState: set current repo (backend={}, path={})",
@@ -122,10 +133,11 @@ impl AppState {
         );
 
         *self.current_repo.write() = Some(repo);
+        crate::logging::set_repo_context(Some(&path));
 
         // Update recents (front insert, unique, cap N from settings)
         let mut r = self.recents.write();
-        r.retain(|p| p != &path);
+        r.retain(|p| !crate::paths::same(p, &path));
         r.insert(0, path.clone());
         let limit = self.config.read().ux.recents_limit as usize;
         let max_items = if limit == 0 { MAX_RECENTS } else { limit };
@@ -147,9 +159,19 @@ impl AppState {
 
     pub fn clear_current_repo(&self) {
         *self.current_repo.write() = None;
+        crate::logging::set_repo_context(None);
         info!("AppState: cleared current repository");
     }
 
+    /// Drop `path` from the recents (it moved or no longer exists).
+    pub fn forget_recent(&self, path: &Path) {
+        let mut r = self.recents.write();
+        r.retain(|p| !crate::paths::same(p, path));
+        if let Err(e) = save_recents_to_disk(&r.clone()) {
+            log::warn!("AppState: failed to persist recents: {}", e);
+        }
+    }
+
     /* -------- getters -------- */
 
     pub fn current_repo(&self) -> Option<Arc<Repo>> {
@@ -159,6 +181,31 @@ impl AppState {
     pub fn recents(&self) -> Vec<PathBuf> {
         self.recents.read().clone()
     }
+
+    /// Handle to the per-repo operation gates (shares state; safe to move into tasks).
+    pub fn op_gates(&self) -> crate::ops::OpGates {
+        self.op_gates.clone()
+    }
+
+    /// Handle to the history window cache (shares state; safe to move into tasks).
+    pub fn history(&self) -> crate::history::HistoryCache {
+        self.history.clone()
+    }
+
+    /// Handle to the repo summary cache (shares state; safe to move into tasks).
+    pub fn summaries(&self) -> crate::summary::SummaryCache {
+        self.summaries.clone()
+    }
+
+    /// Handle to the running-jobs registry (shares state; safe to move into tasks).
+    pub fn jobs(&self) -> crate::jobs::Jobs {
+        self.jobs.clone()
+    }
+
+    /// Handle to the plugin host (shares state; safe to move into tasks).
+    pub fn plugins(&self) -> crate::plugins::PluginHost {
+        self.plugins.clone()
+    }
 }
 
 // ──────────────────────────────────────────────────────────────────────────────
@@ -187,28 +234,28 @@ fn load_recents_from_disk() -> Result<Vec<PathBuf>, String> {
 
     // Accept: [ { path }, ... ] or ["/path", ...]
     let mut out: Vec<PathBuf> = Vec::new();
-    match serde_json::from_str::<serde_json::Value>(&data) {
-        Ok(serde_json::Value::Array(items)) => {
-            for it in items {
-                match it {
-                    serde_json::Value::String(s) => {
+    if let Ok(serde_json::Value::Array(items)) = serde_json::from_str::<serde_json::Value>(&data) {
+        for it in items {
+            match it {
+                serde_json::Value::String(s) if !s.trim().is_empty() => {
+                    out.push(PathBuf::from(s));
+                }
+                serde_json::Value::Object(map) => {
+                    if let Some(serde_json::Value::String(s)) = map.get("path") {
                         if !s.trim().is_empty() { out.push(PathBuf::from(s)); }
                     }
-                    serde_json::Value::Object(map) => {
-                        if let Some(serde_json::Value::String(s)) = map.get("path") {
-                            if !s.trim().is_empty() { out.push(PathBuf::from(s)); }
-                        }
-                    }
-                    _ => {}
                 }
+                _ => {}
             }
         }
-        _ => {}
     }
+    // Older versions stored whatever spelling was opened; keep the first of each repository
+    let mut seen = std::collections::HashSet::new();
+    out.retain(|p| seen.insert(crate::paths::key(p)));
     Ok(out)
 }
 
-fn save_recents_to_disk(list: &Vec<PathBuf>) -> Result<(), String> {
+fn save_recents_to_disk(list: &[PathBuf]) -> Result<(), String> {
     let p = recents_file_path();
     if let Some(parent) = p.parent() { fs::create_dir_all(parent).map_err(|e| e.to_string())?; }
     let entries: Vec<RecentFileEntry> = list
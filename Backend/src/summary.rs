@@ -0,0 +1,145 @@
+//! Cached repository summaries for the sidebar.
+//!
+//! A summary (current branch + normalized branch list) only changes when refs do, so it
+//! is cached per repository under a [`RefsKey`]: the HEAD commit id plus a hash over the
+//! ref files' sizes and mtimes. Both are read straight from the git directory, which is
+//! far cheaper than listing branches. Entries are also dropped outright when a mutating
+//! operation finishes or the watcher reports a change.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+
+use log::trace;
+use openvcs_core::models::BranchItem;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoSummary {
+    pub path: String,
+    pub current_branch: String,
+    pub branches: Vec<BranchItem>,
+    /// Shallow clone: history and ahead/behind counts may be incomplete until
+    /// `unshallow_repo`.
+    pub is_shallow: bool,
+    /// All worktrees when there are linked ones; not cached, see `get_repo_summary`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub worktrees: Vec<crate::worktrees::WorktreeStatus>,
+}
+
+/// What a cached summary is valid for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefsKey {
+    head: String,
+    refs: u64,
+}
+
+impl RefsKey {
+    /// Commit HEAD resolved to (empty when unborn or unreadable).
+    pub fn head(&self) -> &str {
+        &self.head
+    }
+}
+
+/// Per-repository summary cache. Cheap to clone; clones share the cache.
+#[derive(Default, Clone)]
+pub struct SummaryCache {
+    entries: Arc<Mutex<HashMap<PathBuf, (RefsKey, RepoSummary)>>>,
+}
+
+impl SummaryCache {
+    pub fn get(&self, workdir: &Path, key: &RefsKey) -> Option<RepoSummary> {
+        let entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        let (k, s) = entries.get(workdir)?;
+        (k == key).then(|| s.clone())
+    }
+
+    pub fn put(&self, workdir: &Path, key: RefsKey, summary: RepoSummary) {
+        let mut entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        entries.insert(workdir.to_path_buf(), (key, summary));
+    }
+
+    pub fn invalidate(&self, workdir: &Path) {
+        let mut entries = self.entries.lock().unwrap_or_else(|p| p.into_inner());
+        if entries.remove(workdir).is_some() {
+            trace!("summary: invalidated {}", workdir.display());
+        }
+    }
+}
+
+/// Directory holding shared refs (`commondir` for linked worktrees).
+fn common_dir(git_dir: &Path) -> PathBuf {
+    match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(s) => {
+            let p = PathBuf::from(s.trim());
+            if p.is_absolute() { p } else { git_dir.join(p) }
+        }
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+fn resolve_ref(common: &Path, name: &str) -> Option<String> {
+    if let Ok(s) = std::fs::read_to_string(common.join(name)) {
+        return Some(s.trim().to_string());
+    }
+    let packed = std::fs::read_to_string(common.join("packed-refs")).ok()?;
+    packed.lines().find_map(|l| {
+        let (oid, r) = l.split_once(' ')?;
+        (r == name).then(|| oid.to_string())
+    })
+}
+
+/// Hash the mtimes of `dir` and the directories below it. Git updates a loose ref by
+/// renaming a lock file over it, which touches the directory, so the ref files
+/// themselves never need to be read or listed.
+fn hash_dirs(dir: &Path, h: &mut DefaultHasher) {
+    let Ok(meta) = std::fs::metadata(dir) else { return };
+    hash_stat(dir, &meta, h);
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    for d in dirs {
+        hash_dirs(&d, h);
+    }
+}
+
+fn hash_stat(path: &Path, meta: &std::fs::Metadata, h: &mut DefaultHasher) {
+    path.hash(h);
+    meta.len().hash(h);
+    if let Ok(t) = meta.modified().map(|t| t.duration_since(UNIX_EPOCH).unwrap_or_default()) {
+        t.hash(h);
+    }
+}
+
+/// Compute the cache key for the repository at `workdir` from the filesystem alone.
+pub fn refs_key(workdir: &Path) -> RefsKey {
+    let git_dir = crate::locks::git_dir(workdir);
+    let common = common_dir(&git_dir);
+
+    let head_file = std::fs::read_to_string(git_dir.join("HEAD")).unwrap_or_default();
+    let head_file = head_file.trim();
+    let head = match head_file.strip_prefix("ref:") {
+        Some(name) => resolve_ref(&common, name.trim()).unwrap_or_default(),
+        None => head_file.to_string(),
+    };
+
+    let mut h = DefaultHasher::new();
+    head_file.hash(&mut h);
+    for file in [git_dir.join("HEAD"), common.join("packed-refs")] {
+        if let Ok(meta) = std::fs::metadata(&file) {
+            hash_stat(&file, &meta, &mut h);
+        }
+    }
+    hash_dirs(&common.join("refs").join("heads"), &mut h);
+    hash_dirs(&common.join("refs").join("remotes"), &mut h);
+    hash_dirs(&common.join("refs").join("tags"), &mut h);
+
+    RefsKey { head, refs: h.finish() }
+}
@@ -1,50 +1,207 @@
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::Arc;
 
-use log::{debug, error, info, warn};
+use log::{debug, error, info, trace, warn};
 use tauri::{async_runtime, Emitter, Manager, Runtime, State, Window};
 use crate::state::AppState;
-use crate::utilities::utilities;
+use crate::summary::RepoSummary;
+use crate::utilities;
 use crate::validate;
 
-use openvcs_core::{OnEvent, models::{BranchItem, StatusPayload, CommitItem}, Repo, BackendId, backend_id};
+use openvcs_core::{OnEvent, models::{BranchItem, BranchPage, BranchQuery, StatusPayload, CommitItem}, Repo, BackendId};
 use serde::Serialize;
 use openvcs_core::backend_descriptor::{get_backend, list_backends};
-use openvcs_core::models::{VcsEvent};
-use crate::settings::AppConfig;
-use crate::repo_settings::RepoConfig;
-use tauri_plugin_updater::UpdaterExt;
+use openvcs_core::models::{CommitOptions, FetchSummary, FileDiff, HeadState, JobId, MergeOptions, MergeOutcome, PickKind, ProgressPhase, StashEntry, VcsEvent};
+use crate::settings::{AppConfig, UpdateChannel};
+use crate::repo_settings::{RepoConfig, BRANCH_NOTES_KEY};
+use crate::pre_push::PRE_PUSH_COMMAND_KEY;
+use crate::plugins::PluginEvent;
 
 #[derive(serde::Serialize)]
 struct RepoSelectedPayload {
     path: String,
     backend: String,
+    /// Opened on unwritable media: writing commands are refused.
+    read_only: bool,
 }
 
-// Bridge core events → UI messages
-fn progress_bridge<R: Runtime>(app: tauri::AppHandle<R>) -> OnEvent {
-    Arc::new(move |evt| {
-        let msg = match evt {
-            VcsEvent::Progress{ detail, .. } => detail,
-            VcsEvent::RemoteMessage(s) => s,
-            VcsEvent::Auth{ method, detail } => format!("auth[{method}]: {detail}"),
-            VcsEvent::PushStatus{ refname, status } =>
-                status.map(|s| format!("{refname} → {s}")).unwrap_or_else(|| format!("{refname} ok")),
-            VcsEvent::Info(s) => s.to_string(),
-            VcsEvent::Warning(s) | VcsEvent::Error(s) => s,
+// Bridge core events → UI messages, tagged with the job they belong to
+fn progress_bridge<R: Runtime>(app: tauri::AppHandle<R>, job: JobId) -> OnEvent {
+    job.on_event(Arc::new(move |job, evt| {
+        let (msg, phase, percent) = match evt {
+            VcsEvent::Progress{ phase, percent, detail } => (detail, Some(phase), percent),
+            other => (event_message(other), None, None),
         };
-        let _ = app.emit("git-progress", ProgressPayload { message: msg });
-    })
+        trace!("{job}: {msg}");
+        let _ = app.emit("git-progress", ProgressPayload { message: msg, job, phase, percent });
+    }))
+}
+
+fn event_message(evt: VcsEvent) -> String {
+    match evt {
+        VcsEvent::Progress{ detail, .. } => detail,
+        VcsEvent::RemoteMessage(s) => s,
+        VcsEvent::Auth{ method, detail } => format!("auth[{method}]: {detail}"),
+        VcsEvent::PushStatus{ remote, refname, status } =>
+            status.map(|s| format!("{remote}: {refname} → {s}")).unwrap_or_else(|| format!("{remote}: {refname} ok")),
+        VcsEvent::Info(s) => s.to_string(),
+        VcsEvent::Warning(s) | VcsEvent::Error(s) => s,
+    }
+}
+
+/// The current repository, for commands that need a working tree (bare repositories
+/// open read-only).
+fn worktree_repo(state: &AppState) -> Result<Arc<Repo>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    if !repo.inner().caps().worktree {
+        return Err("This is a bare repository (read-only); it has no working tree".into());
+    }
+    Ok(repo)
+}
+
+/// Refuse writing to a repository that opened read-only because its working tree or
+/// `.git` is on unwritable media, before the operation gets halfway.
+fn ensure_writable(repo: &Repo) -> Result<(), String> {
+    if repo.inner().caps().writable {
+        return Ok(());
+    }
+    Err(format!("{} is read-only (unwritable media or no write permission)", repo.inner().workdir().display()))
+}
+
+/// Run the body of a command that takes the op gate on a blocking thread. Synchronous
+/// commands run on the main thread, where waiting for the gate would freeze the window.
+async fn off_main<R: Runtime, T: Send + 'static>(
+    window: &Window<R>,
+    name: &'static str,
+    f: impl FnOnce(&tauri::AppHandle<R>, &AppState) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let app = window.app_handle().clone();
+    async_runtime::spawn_blocking(move || f(&app, &app.state::<AppState>()))
+        .await
+        .map_err(|e| format!("{name} task failed: {e}"))?
+}
+
+/// The commit dialog's overrides, checked before anything is staged.
+fn commit_options(options: Option<CommitOptions>) -> Result<CommitOptions, String> {
+    let options = options.unwrap_or_default();
+    if let Some(author) = &options.author {
+        if !author.is_valid() {
+            return Err(format!("'{author}' is not a valid author; use 'Name <email>'"));
+        }
+    }
+    Ok(options)
+}
+
+/// Emit `vcs:error` with the structured kind so the UI can react (e.g. offer credentials
+/// on `auth-failed`), and return the message for the command's `Err`.
+fn report_vcs_error<R: Runtime>(app: &tauri::AppHandle<R>, job: JobId, e: &openvcs_core::VcsError) -> String {
+    let msg = e.to_string();
+    let _ = app.emit("vcs:error", serde_json::json!({ "kind": e.kind(), "message": msg, "job": job }));
+    msg
 }
 
 #[derive(serde::Serialize, Clone)]
 struct ProgressPayload {
-    message: String
+    message: String,
+    job: JobId,
+    /// Transfer phase and percentage, for progress bars (absent for plain messages).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    phase: Option<ProgressPhase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    percent: Option<u8>,
+}
+
+impl ProgressPayload {
+    fn new(message: String, job: JobId) -> Self {
+        Self { message, job, phase: None, percent: None }
+    }
+}
+
+/// App metadata plus the runtime versions (git, libgit2, WebView) needed in bug reports.
+#[tauri::command]
+pub async fn about_info() -> Result<utilities::AboutInfo, String> {
+    async_runtime::spawn_blocking(utilities::AboutInfo::gather)
+        .await
+        .map_err(|e| format!("about info failed: {e}"))
+}
+
+/// Diagnose the Git toolchain and configuration (git, libgit2, git-lfs, credentials, SSH, identity).
+#[tauri::command]
+pub async fn environment_doctor() -> Result<crate::doctor::DoctorReport, String> {
+    async_runtime::spawn_blocking(crate::doctor::run)
+        .await
+        .map_err(|e| format!("environment doctor failed: {e}"))
+}
+
+/// What a new user still needs to set up (git, identity, SSH keys, forge token).
+#[tauri::command]
+pub async fn first_run_status() -> Result<crate::onboarding::FirstRunStatus, String> {
+    async_runtime::spawn_blocking(crate::onboarding::status)
+        .await
+        .map_err(|e| format!("first-run status failed: {e}"))
+}
+
+/// Quick structural health check of the current repository.
+#[tauri::command]
+pub async fn repo_health(state: State<'_, AppState>) -> Result<crate::health::HealthReport, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    let workdir = repo.inner().workdir().to_path_buf();
+    async_runtime::spawn_blocking(move || crate::health::run(&workdir))
+        .await
+        .map_err(|e| format!("repo health check failed: {e}"))
+}
+
+/// Exactly what the next telemetry batch would contain (anonymized aggregates only).
+#[tauri::command]
+pub fn get_telemetry_preview() -> crate::telemetry::TelemetryPreview {
+    crate::telemetry::preview()
+}
+
+/// Crash reports from earlier sessions awaiting a submit/dismiss decision. The UI asks on
+/// boot, once it is listening.
+#[tauri::command]
+pub fn list_crash_reports() -> crate::crash::Pending {
+    crate::crash::pending()
+}
+
+/// Full contents of a crash report (shown before the user submits it).
+#[tauri::command]
+pub fn get_crash_report(id: String) -> Result<crate::crash::CrashReport, String> {
+    crate::crash::load(&id)
+}
+
+#[tauri::command]
+pub async fn submit_crash_report(id: String) -> Result<(), String> {
+    async_runtime::spawn_blocking(move || crate::crash::submit(&id))
+        .await
+        .map_err(|e| format!("submit task failed: {e}"))?
+}
+
+#[tauri::command]
+pub fn dismiss_crash_report(id: String) -> Result<(), String> {
+    crate::crash::dismiss(&id)
+}
+
+/// Recent log records from the in-memory ring, oldest first.
+#[tauri::command]
+pub fn get_recent_logs(filter: Option<crate::logging::LogFilter>) -> Vec<crate::logging::LogEntry> {
+    crate::logging::recent(&filter.unwrap_or_default())
+}
+
+/// Stream new log records to the frontend as `logs:entry` events.
+/// Requires `logging.live_viewer` to be enabled.
+#[tauri::command]
+pub fn subscribe_logs<R: Runtime>(window: Window<R>, state: State<'_, AppState>) -> Result<(), String> {
+    if !state.with_config(|c| c.logging.live_viewer) {
+        return Err("Live log viewer is disabled in Settings → Logging".into());
+    }
+    crate::logging::start_live_pump(window.app_handle().clone());
+    Ok(())
 }
 
 #[tauri::command]
-pub fn about_info() -> utilities::AboutInfo {
-  utilities::AboutInfo::gather()
+pub fn unsubscribe_logs() {
+    crate::logging::stop_live_pump();
 }
 
 #[tauri::command]
@@ -61,6 +218,7 @@ pub async fn browse_directory<R: Runtime>(
     let title = match purpose.as_deref() {
         Some("clone_dest") => "Choose destination folder",
         Some("add_repo")   => "Select an existing Git repository folder",
+        Some("init_repo")  => "Choose a folder for the new repository",
         _                  => "Select a folder",
     };
     utilities::browse_directory_async(window.app_handle().clone(), title).await
@@ -73,7 +231,7 @@ pub async fn add_repo<R: Runtime>(
     path: String,
     backend_id: Option<BackendId>,
 ) -> Result<(), String> {
-    let be = backend_id.unwrap_or_else(|| backend_id!("git-system"));
+    let be = backend_id.unwrap_or(crate::GIT_SYSTEM_ID);
     add_repo_internal(window, state, path, be).await
 }
 
@@ -85,6 +243,8 @@ pub async fn add_repo_internal<R: Runtime>(
 ) -> Result<(), String> {
     info!("add_repo: requested path = {}, backend = {}", path, backend_id);
 
+    // One spelling per repository (`~`, trailing separators, symlinks, `\\?\`)
+    let path = crate::paths::normalize(&crate::paths::expand(&path)).to_string_lossy().into_owned();
     if !Path::new(&path).exists() {
         let m = format!("Path does not exist: {}", path);
         error!("{m}");
@@ -98,27 +258,76 @@ pub async fn add_repo_internal<R: Runtime>(
     })?;
 
     let handle = (desc.open)(Path::new(&path)).map_err(|e| {
+        // Owned by another user: the UI offers to mark it safe and retry
+        if let openvcs_core::VcsError::DubiousOwnership { path: unsafe_path } = &e {
+            let issue = crate::safe_directory::note_refused(&path, unsafe_path);
+            let _ = window.app_handle().emit("repo:dubious-ownership", &issue);
+        }
         let m = format!("Failed to open repo with backend `{backend_id}`: {e}");
         error!("{m}");
         m
     })?;
 
     let repo = Arc::new(Repo::new(handle));
-    state.set_current_repo(repo);
+    let read_only = !repo.inner().caps().writable;
+    state.set_current_repo(repo.clone());
 
     // structured event
     let payload = RepoSelectedPayload {
         path: path.clone(),
         backend: backend_id.as_ref().to_owned(),
+        read_only,
     };
     if let Err(e) = window.app_handle().emit("repo:selected", &payload) {
         warn!("add_repo: failed to emit repo:selected: {}", e);
     }
+    crate::menus::sync(window.app_handle(), None);
+    crate::desktop::update_title(window.app_handle());
+    crate::desktop::note_recent(window.app_handle(), Path::new(&path));
+    crate::plugins::dispatch(window.app_handle(), PluginEvent::RepoOpened, &[]);
+    crate::prefetch::start(window.app_handle(), repo);
 
     info!("add_repo: repository opened and stored (backend = {})", backend_id);
     Ok(())
 }
 
+/// Trust a repository owned by another user by adding it to the global `safe.directory`
+/// list. Only for a path a failed open reported, after the user agreed.
+#[tauri::command]
+pub fn mark_safe_directory(path: String) -> Result<(), String> {
+    crate::safe_directory::mark(&path)
+}
+
+/// Open the current repository from where it was moved to (after `repo:lost`), with the
+/// same backend, and drop the old location from the recents.
+#[tauri::command]
+pub async fn relocate_repo<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<(), String> {
+    let repo = state.current_repo().ok_or("No repository selected")?;
+    let old = repo.inner().workdir().to_path_buf();
+    let backend = repo.id();
+    drop(repo);
+    add_repo_internal(window, state.clone(), path, backend).await?;
+    info!("relocate_repo: {} moved", old.display());
+    crate::repo_lost::clear(&old);
+    state.forget_recent(&old);
+    Ok(())
+}
+
+/// Close the current repository (e.g. one whose working tree disappeared).
+#[tauri::command]
+pub fn close_repo<R: Runtime>(window: Window<R>, state: State<'_, AppState>) {
+    if let Some(repo) = state.current_repo() {
+        crate::repo_lost::clear(repo.inner().workdir());
+    }
+    state.clear_current_repo();
+    crate::menus::sync(window.app_handle(), None);
+    crate::desktop::update_title(window.app_handle());
+}
+
 #[tauri::command]
 pub async fn clone_repo<R: Runtime>(
     window: Window<R>,
@@ -130,36 +339,199 @@ pub async fn clone_repo<R: Runtime>(
     use std::fs;
     use std::path::PathBuf;
 
-    let be = backend_id.unwrap_or_else(|| backend_id!("git-system"));
+    let be = backend_id.unwrap_or(crate::GIT_SYSTEM_ID);
     let desc = get_backend(&be).ok_or_else(|| format!("Backend not found: {be}"))?;
 
-    // Compute target path: <dest>/<repo-name>
-    let folder = infer_repo_dir_from_url(&url);
-    if folder.is_empty() {
+    // Clone the canonical form of the URL into <dest>/<repo-name>
+    let parsed = validate::parse_git_url(&url)?;
+    if parsed.repo_name.is_empty() {
         return Err("Cannot infer target directory from URL".into());
     }
-    let target: PathBuf = Path::new(&dest).join(&folder);
+    let url = parsed.canonical;
+    let target: PathBuf = Path::new(&dest).join(&parsed.repo_name);
 
     // Ensure parent exists
     fs::create_dir_all(&dest).map_err(|e| format!("Failed to create dest: {e}"))?;
 
-    // Clone via the backend, with progress bridge
-    let on = Some(progress_bridge(window.app_handle().clone()));
-    info!("clone_repo: cloning via backend {} into {}", be, target.display());
-    (desc.clone_repo)(&url, &target, on).map_err(|e| format!("Clone failed: {e}"))?;
+    // Clone via the backend, with progress bridge; a kept partial clone is resumed instead
+    let job = JobId::next();
+    let app = window.app_handle().clone();
+    let jobs = state.jobs();
+    let target_for_task = target.clone();
+    let repo_name = parsed.repo_name.clone();
+    async_runtime::spawn_blocking(move || -> Result<(), String> {
+        let (target, be) = (target_for_task, &desc.id);
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let meter = crate::net_stats::Meter::start("clone", &repo_name, Some(job));
+        let on = Some(meter.watch(progress_bridge(app.clone(), job)));
+
+        let res = if crate::clone::is_resumable(&target, &url) {
+            info!("clone_repo: resuming partial clone via backend {} in {}", be, target.display());
+            crate::telemetry::track("clone", be.as_ref(), || {
+                (desc.open)(&target).and_then(|vcs| crate::clone::resume(vcs.as_ref(), on))
+            })
+        } else {
+            let before = crate::clone::Destination::inspect(&target);
+            before.ensure_clonable(&target)?;
+            info!("clone_repo: cloning via backend {} into {}", be, target.display());
+            crate::telemetry::track("clone", be.as_ref(), || (desc.clone_repo)(&url, &target, on).map(|_| ()))
+                .inspect_err(|_| crate::clone::cleanup_failed(&target, &url, &before))
+        };
+        meter.finish(&res);
+        res.map_err(|e| {
+            error!("clone_repo: {e}");
+            format!("Clone failed: {}", report_vcs_error(&app, job, &e))
+        })
+    })
+    .await
+    .map_err(|e| format!("clone task failed: {e}"))??;
 
     // Open the freshly cloned repo and set it current
     add_repo_internal(window, state, target.to_string_lossy().to_string(), be).await
 }
 
+/// Branches and tags a URL advertises, listed before cloning (for the clone dialog).
+#[tauri::command]
+pub async fn remote_refs(url: String, backend_id: Option<BackendId>) -> Result<openvcs_core::models::RemoteRefs, String> {
+    let be = backend_id.unwrap_or(crate::GIT_SYSTEM_ID);
+    let desc = get_backend(&be).ok_or_else(|| format!("Backend not found: {be}"))?;
+    let url = url.trim().to_string();
+
+    async_runtime::spawn_blocking(move || {
+        (desc.remote_refs)(&url).map_err(|e| {
+            warn!("remote_refs: {url}: {e}");
+            e.to_string()
+        })
+    })
+    .await
+    .map_err(|e| format!("remote_refs task failed: {e}"))?
+}
+
+/// `.gitignore` presets and licenses offered when creating a repository.
+#[tauri::command]
+pub fn list_repo_templates() -> crate::templates::Templates {
+    crate::templates::list()
+}
+
+/// Create a repository at `path`, optionally with a `.gitignore` (combining the presets in
+/// `gitignore`) and a `LICENSE` committed as the initial commit, then open it.
+#[tauri::command]
+pub async fn init_repo<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    path: String,
+    backend_id: Option<BackendId>,
+    gitignore: Vec<String>,
+    license: Option<String>,
+) -> Result<(), String> {
+    use std::path::PathBuf;
+
+    let be = backend_id.unwrap_or(crate::GIT_SYSTEM_ID);
+    let desc = get_backend(&be).ok_or_else(|| format!("Backend not found: {be}"))?;
+    let target = PathBuf::from(path.trim());
+    if !target.is_absolute() {
+        return Err("Enter an absolute path".into());
+    }
+    if target.join(".git").exists() {
+        return Err(format!("{} is already a repository", target.display()));
+    }
+    info!("init_repo: {} via {be} (gitignore={gitignore:?}, license={license:?})", target.display());
+
+    // Fetch templates before touching the disk, so a failed download leaves nothing behind
+    let ignore = if gitignore.is_empty() { None } else { Some(crate::templates::gitignore(&gitignore).await?) };
+    let license = match license.filter(|l| !l.is_empty()) {
+        Some(id) => Some(crate::templates::license(&id).await?),
+        None => None,
+    };
+
+    let job = JobId::next();
+    let app = window.app_handle().clone();
+    let target_for_task = target.clone();
+    async_runtime::spawn_blocking(move || -> Result<(), String> {
+        let target = target_for_task;
+        let _job = crate::logging::job_scope(job);
+        std::fs::create_dir_all(&target).map_err(|e| format!("Failed to create {}: {e}", target.display()))?;
+        let vcs = (desc.init_repo)(&target).map_err(|e| {
+            error!("init_repo: {e}");
+            format!("Init failed: {}", report_vcs_error(&app, job, &e))
+        })?;
+
+        let (name, email) = vcs
+            .get_identity()
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| ("OpenVCS".into(), "openvcs@example".into()));
+
+        // Never overwrite files already in the folder
+        let mut written: Vec<PathBuf> = Vec::new();
+        let files = [(".gitignore", ignore), ("LICENSE", license.map(|t| crate::templates::fill_license(&t, &name)))];
+        for (file, text) in files {
+            let Some(text) = text else { continue };
+            let p = target.join(file);
+            if p.exists() {
+                warn!("init_repo: {} exists, leaving it alone", p.display());
+                continue;
+            }
+            std::fs::write(&p, text).map_err(|e| format!("Failed to write {file}: {e}"))?;
+            written.push(p);
+        }
+
+        if !written.is_empty() {
+            let oid = vcs.commit("Initial commit", &name, &email, &written, &Default::default()).map_err(|e| {
+                error!("init_repo: initial commit failed: {e}");
+                format!("Initial commit failed: {}", report_vcs_error(&app, job, &e))
+            })?;
+            info!("init_repo: initial commit {oid}");
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("init task failed: {e}"))??;
+
+    add_repo_internal(window, state, target.to_string_lossy().to_string(), be).await
+}
+
+/// Build the demo repository (`openvcs_testkit::demo`: a few authors, a merged branch,
+/// tags and uncommitted changes) at `path` and open it. Not in any menu; for onboarding
+/// walkthroughs and screenshots.
+#[tauri::command]
+pub async fn create_demo_repo<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    path: String,
+    backend_id: Option<BackendId>,
+) -> Result<(), String> {
+    let be = backend_id.unwrap_or(crate::GIT_SYSTEM_ID);
+    let target = std::path::PathBuf::from(path.trim());
+    if !target.is_absolute() {
+        return Err("Enter an absolute path".into());
+    }
+    info!("create_demo_repo: {}", target.display());
+    let git = crate::git_exe::program();
+    let dest = target.clone();
+    async_runtime::spawn_blocking(move || openvcs_testkit::demo().build_with(&git, &dest))
+        .await
+        .map_err(|e| format!("demo task failed: {e}"))?
+        .map_err(|e| {
+            error!("create_demo_repo: {e}");
+            format!("Could not build the demo repository: {e}")
+        })?;
+
+    add_repo_internal(window, state, target.to_string_lossy().to_string(), be).await
+}
+
 #[tauri::command]
 pub fn validate_git_url(url: String) -> validate::Validation {
     validate::validate_git_url(url)
 }
 
 #[tauri::command]
-pub fn validate_add_path(path: String) -> validate::Validation {
-    validate::validate_add_path(path)
+pub async fn validate_add_path(path: String) -> Result<validate::Validation, String> {
+    // runs git rev-parse; keep it off the IPC thread
+    async_runtime::spawn_blocking(move || validate::validate_add_path(path))
+        .await
+        .map_err(|e| format!("validate task failed: {e}"))
 }
 
 
@@ -190,14 +562,6 @@ pub fn list_recent_repos(state: State<'_, AppState>) -> Vec<RecentRepoDto> {
         .collect()
 }
 
-/* ---------- helpers ---------- */
-fn get_repo_root(state: &State<'_, AppState>) -> Result<PathBuf, String> {
-    state
-        .current_repo()
-        .map(|repo| repo.inner().workdir().to_path_buf())
-        .ok_or_else(|| "No repository selected".to_string())
-}
-
 #[tauri::command]
 pub async fn open_repo<R: Runtime>(
     window: Window<R>,
@@ -205,23 +569,21 @@ pub async fn open_repo<R: Runtime>(
     path: String,
     backend_id: Option<BackendId>,
 ) -> Result<(), String> {
-    let be = backend_id.unwrap_or_else(|| backend_id!("git-system"));
+    let be = backend_id.unwrap_or(crate::GIT_SYSTEM_ID);
     add_repo_internal(window, state, path, be).await
 }
 
-fn infer_repo_dir_from_url(url: &str) -> String {
-    let trimmed = url.trim_end_matches('/');
-    let last = trimmed.rsplit('/').next().unwrap_or(trimmed);
-    last.trim_end_matches(".git").to_string()
-}
-
 /* ---------- list_branches ---------- */
+/// One page of branches matching `query` (all branches, current first, when omitted). The
+/// whole matching list is cleaned up and deduplicated before the page is cut, so `total` and
+/// the cursors agree with what the pages hold.
 #[tauri::command]
-pub fn git_list_branches(state: State<'_, AppState>) -> Result<Vec<BranchItem>, String> {
-    use openvcs_core::models::{BranchItem, BranchKind};
-    use std::collections::HashSet;
-
-    info!("list_branches: fetching unified branches via Vcs::branches()");
+pub fn git_list_branches(
+    state: State<'_, AppState>,
+    query: Option<BranchQuery>,
+) -> Result<BranchPage, String> {
+    let query = query.unwrap_or_default();
+    info!("list_branches: fetching branch page ({query:?})");
 
     let repo = state
         .current_repo()
@@ -230,18 +592,39 @@ pub fn git_list_branches(state: State<'_, AppState>) -> Result<Vec<BranchItem>,
 
     debug!("list_branches: workdir={}", vcs.workdir().display());
 
-    // Ask backend for unified branches and current branch name
-    let mut items = vcs
-        .branches()
+    let current_local = vcs
+        .current_branch()
         .map_err(|e| {
-            error!("list_branches: branches() failed: {e:?}");
+            error!("list_branches: current_branch failed: {e:?}");
             e.to_string()
         })?;
 
-    let current_local = vcs
-        .current_branch()
+    let out = normalized_branches(vcs, &query, current_local.as_deref())?;
+    // Already filtered and in order: this only cuts the page
+    let page = query.page(out);
+
+    debug!(
+        "list_branches: current_local={:?}, returned={} of {}",
+        current_local,
+        page.items.len(),
+        page.total
+    );
+
+    Ok(page)
+}
+
+/// Every branch matching `query`, sanitized, deduplicated and with notes attached; `current`
+/// is the checked-out local branch.
+fn normalized_branches(vcs: &dyn openvcs_core::Vcs, query: &BranchQuery, current: Option<&str>) -> Result<Vec<BranchItem>, String> {
+    use openvcs_core::models::BranchKind;
+    use std::collections::HashSet;
+
+    // Backend filters and sorts; the page is cut after sanitizing
+    let all = BranchQuery { limit: 0, cursor: None, ..query.clone() };
+    let mut matching = vcs
+        .branch_page(&all)
         .map_err(|e| {
-            error!("list_branches: current_branch failed: {e:?}");
+            error!("list_branches: branch_page() failed: {e:?}");
             e.to_string()
         })?;
 
@@ -261,13 +644,19 @@ pub fn git_list_branches(state: State<'_, AppState>) -> Result<Vec<BranchItem>,
     }
 
     // Sanitize, infer kind where Unknown, and enforce a single "current"
-    let current_name = current_local.as_deref();
+    let current_name = current;
+
+    // Notes live in the repo config next to the descriptions; backends do not know them
+    let mut notes = vcs.branch_config(BRANCH_NOTES_KEY).unwrap_or_else(|e| {
+        debug!("list_branches: branch notes unavailable: {e}");
+        Default::default()
+    });
 
     // Deduplicate by full_ref (stable identity)
     let mut seen: HashSet<String> = HashSet::new();
-    let mut out: Vec<BranchItem> = Vec::with_capacity(items.len());
+    let mut out: Vec<BranchItem> = Vec::with_capacity(matching.items.len());
 
-    for mut it in items.drain(..) {
+    for mut it in matching.items.drain(..) {
         // Trim + validate
         it.name = it.name.trim().to_string();
         it.full_ref = it.full_ref.trim().to_string();
@@ -294,31 +683,11 @@ pub fn git_list_branches(state: State<'_, AppState>) -> Result<Vec<BranchItem>,
             continue;
         }
 
+        if matches!(it.kind, BranchKind::Local) {
+            it.notes = notes.remove(&it.name);
+        }
         out.push(it);
     }
-
-    // Sort: current → local → remote → unknown, then by name
-    out.sort_by(|a, b| {
-        let bucket = |x: &BranchItem| {
-            if x.current {
-                0
-            } else {
-                match x.kind {
-                    BranchKind::Local => 1,
-                    BranchKind::Remote { .. } => 2,
-                    BranchKind::Unknown => 3,
-                }
-            }
-        };
-        bucket(a).cmp(&bucket(b)).then_with(|| a.name.cmp(&b.name))
-    });
-
-    debug!(
-        "list_branches: current_local={:?}, returned={}",
-        current_local,
-        out.len()
-    );
-
     Ok(out)
 }
 
@@ -327,15 +696,15 @@ pub fn git_list_branches(state: State<'_, AppState>) -> Result<Vec<BranchItem>,
 pub fn git_status(state: State<'_, AppState>) -> Result<StatusPayload, String> {
     info!("git_status: fetching repo status");
 
-    let repo = state
-        .current_repo()
-        .ok_or_else(|| "No repository selected".to_string())?;
+    let repo = worktree_repo(&state)?;
     let vcs = repo.inner();
+    let opts = crate::changes_scope::apply(vcs, state.config().ux.status_options());
 
-    let payload = vcs.status_payload().map_err(|e| {
+    let mut payload = vcs.status_payload(&opts).map_err(|e| {
         error!("git_status: failed to compute status: {e}");
         e.to_string()
     })?;
+    payload.case_collisions = crate::case_collisions::find(vcs);
 
     debug!(
         "git_status: files={}, ahead={}, behind={}",
@@ -347,12 +716,121 @@ pub fn git_status(state: State<'_, AppState>) -> Result<StatusPayload, String> {
     Ok(payload)
 }
 
+/// Status entries per `repo:status-chunk` event in `git_status_stream`.
+const STATUS_CHUNK: usize = 500;
+
+#[derive(serde::Serialize, Clone)]
+struct StatusChunk {
+    job: JobId,
+    seq: u32,
+    files: Vec<openvcs_core::models::FileEntry>,
+}
+
+/// Stream status for large worktrees: entries arrive as `repo:status-chunk` events
+/// (in `seq` order) and the totals as `repo:status-done`, which is also the return value.
+#[tauri::command]
+pub async fn git_status_stream<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+) -> Result<openvcs_core::models::StatusTotals, String> {
+    let repo = worktree_repo(&state)?;
+    let app = window.app_handle().clone();
+    let job = JobId::next();
+    let opts = crate::changes_scope::apply(repo.inner(), state.config().ux.status_options());
+
+    async_runtime::spawn_blocking(move || {
+        let _job = crate::logging::job_scope(job);
+        let mut seq = 0u32;
+        let totals = repo
+            .inner()
+            .status_stream(&opts, STATUS_CHUNK, &mut |files| {
+                let _ = app.emit("repo:status-chunk", StatusChunk { job, seq, files });
+                seq += 1;
+            })
+            .map_err(|e| {
+                error!("git_status_stream: {e}");
+                e.to_string()
+            })?;
+        debug!("git_status_stream: {} file(s) in {seq} chunk(s)", totals.total);
+        let _ = app.emit("repo:status-done", serde_json::json!({ "job": job, "totals": totals }));
+        Ok(totals)
+    })
+    .await
+    .map_err(|e| format!("status task failed: {e}"))?
+}
+
+/// Line and size changes of the changed files, as entries carrying only those counts.
+/// Status leaves them out so it isn't held up reading every file; the UI asks for them
+/// once a status is shown.
+#[tauri::command]
+pub async fn git_worktree_stats(state: State<'_, AppState>) -> Result<Vec<openvcs_core::models::FileEntry>, String> {
+    let repo = worktree_repo(&state)?;
+    let scope = crate::changes_scope::apply(repo.inner(), state.config().ux.status_options()).scope();
+
+    async_runtime::spawn_blocking(move || {
+        let stats = repo.inner().worktree_file_stats(scope.as_deref()).map_err(|e| e.to_string())?;
+        Ok(stats
+            .iter()
+            .map(|st| {
+                let mut entry = openvcs_core::models::FileEntry { path: st.path.clone(), status: st.status.clone(), ..Default::default() };
+                entry.apply_stat(st);
+                entry
+            })
+            .collect())
+    })
+    .await
+    .map_err(|e| format!("stats task failed: {e}"))?
+}
+
+/// Remove `paths` from the index but keep the files, e.g. the extra spellings of a case
+/// collision; the removal is staged for the next commit.
+#[tauri::command]
+pub async fn git_untrack_paths<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    paths: Vec<String>,
+) -> Result<(), String> {
+    let repo = worktree_repo(&state)?;
+    ensure_writable(&repo)?;
+    info!("git_untrack_paths: {} path(s)", paths.len());
+    let paths: Vec<std::path::PathBuf> = paths.into_iter().map(Into::into).collect();
+    let app = window.app_handle().clone();
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
+
+    async_runtime::spawn_blocking(move || {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "untrack", job)?;
+        let res = vcs.untrack_paths(&paths);
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        res.map_err(|e| {
+            error!("untrack: {e}");
+            report_vcs_error(&app, job, &e)
+        })
+    })
+    .await
+    .map_err(|e| format!("untrack task failed: {e}"))?
+}
+
+/// Queue a coalesced status refresh; the result arrives as a `repo:status` event.
+#[tauri::command]
+pub fn request_status_refresh<R: Runtime>(window: Window<R>) {
+    crate::refresh::request(window.app_handle(), crate::refresh::Trigger::Manual);
+}
+
 /* ---------- git_log ---------- */
+/// A page of history; large pages arrive in chunks (see `chunked`). `paths` limits it to
+/// commits touching those pathspecs (`:!` excludes).
 #[tauri::command]
-pub fn git_log(
+pub fn git_log<R: Runtime>(
+    window: Window<R>,
     state: State<'_, AppState>,
     limit: Option<usize>,
-) -> Result<Vec<CommitItem>, String> {
+    paths: Option<Vec<String>>,
+) -> Result<crate::chunked::Transfer<CommitItem>, String> {
     use openvcs_core::models::LogQuery;
 
     let repo = state
@@ -363,6 +841,7 @@ pub fn git_log(
     let q = LogQuery {
         rev: None,
         path: None,
+        paths: paths.unwrap_or_default(),
         since_utc: None,
         until_utc: None,
         author_contains: None,
@@ -372,199 +851,1038 @@ pub fn git_log(
         include_merges: true,
     };
 
-    vcs.log_commits(&q).map_err(|e| e.to_string())
-}
-
-/* ---------- git_head_status ---------- */
-#[derive(Serialize)]
-pub struct HeadStatus {
-    pub detached: bool,
-    pub branch: Option<String>,
-    pub commit: Option<String>,
+    let items = vcs.log_commits(&q).map_err(|e| e.to_string())?;
+    crate::chunked::transfer(window.app_handle(), items)
 }
 
+/// A window of history around `anchor` (a commit id) or `index` (default HEAD), served
+/// from the per-repo walk cache so deep scrolling doesn't rerun `git log --skip`.
 #[tauri::command]
-pub fn git_head_status(state: State<'_, AppState>) -> Result<HeadStatus, String> {
-    use openvcs_core::models::LogQuery;
-
+pub async fn git_log_window(
+    state: State<'_, AppState>,
+    anchor: Option<String>,
+    index: Option<usize>,
+    before: Option<usize>,
+    after: Option<usize>,
+) -> Result<crate::history::LogWindow, String> {
     let repo = state
         .current_repo()
         .ok_or_else(|| "No repository selected".to_string())?;
-    let vcs = repo.inner();
+    let cache = state.history();
+    let before = before.unwrap_or(0).min(5000);
+    let after = after.unwrap_or(100).min(5000);
 
-    let branch = vcs.current_branch().map_err(|e| e.to_string())?;
-    let q = LogQuery { rev: Some("HEAD".into()), limit: 1, ..Default::default() };
-    let head = vcs.log_commits(&q).map_err(|e| e.to_string())?;
-    let commit = head.get(0).map(|c| c.id.clone());
-
-    Ok(HeadStatus { detached: branch.is_none(), branch, commit })
+    async_runtime::spawn_blocking(move || {
+        cache.window(repo.inner(), anchor.as_deref(), index, before, after)
+    })
+    .await
+    .map_err(|e| format!("log task failed: {e}"))?
+}
+
+/// Stream the history selected by `query` to `dest` as CSV or JSON with full author and
+/// committer metadata. `format` defaults to the destination's extension.
+#[tauri::command]
+pub async fn export_history<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    query: openvcs_core::models::LogQuery,
+    format: Option<crate::export::ExportFormat>,
+    dest: String,
+) -> Result<crate::export::ExportReport, String> {
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    let dest = std::path::PathBuf::from(dest);
+    let format = format.unwrap_or_else(|| crate::export::ExportFormat::from_path(&dest));
+    let app = window.app_handle().clone();
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
+
+    async_runtime::spawn_blocking(move || {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "export", job)?;
+
+        let report = crate::export::write(vcs, &query, format, &dest, |n| {
+            let _ = app.emit("git-progress", ProgressPayload::new(format!("Exported {n} commit(s)…"), job));
+        })
+        .map_err(|e| {
+            error!("export_history to {}: {e}", dest.display());
+            e
+        })?;
+
+        let _ = app.emit(
+            "git-progress",
+            ProgressPayload::new(format!("Export complete ({} commit(s))", report.commits), job),
+        );
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("export task failed: {e}"))?
+}
+
+/* ---------- git_head_status ---------- */
+#[derive(Serialize)]
+pub struct HeadStatus {
+    pub detached: bool,
+    pub branch: Option<String>,
+    pub commit: Option<String>,
+    /// Full HEAD state, and its display form ("HEAD detached at v1.2.0", …).
+    pub state: HeadState,
+    pub label: String,
+}
+
+#[tauri::command]
+pub fn git_head_status(state: State<'_, AppState>) -> Result<HeadStatus, String> {
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    let head = repo.inner().head_state().map_err(|e| e.to_string())?;
+
+    Ok(HeadStatus {
+        detached: matches!(head, HeadState::Detached { .. }),
+        branch: head.branch().map(str::to_string),
+        commit: head.commit().map(str::to_string),
+        label: head.to_string(),
+        state: head,
+    })
+}
+
+/// Check out tag `name` (detached HEAD); "create branch here" is `git_create_branch`.
+#[tauri::command]
+pub async fn git_checkout_tag<R: Runtime>(window: Window<R>, name: String) -> Result<(), String> {
+    off_main(&window, "checkout", move |app, state| {
+        let tag = name.trim();
+        if tag.is_empty() {
+            return Err("Tag name cannot be empty".to_string());
+        }
+
+        info!("git_checkout_tag: checking out '{tag}'");
+
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "checkout", JobId::next())?;
+        let previous = vcs.current_branch().ok().flatten();
+
+        crate::telemetry::track("checkout_tag", repo.id().as_ref(), || {
+            crate::locks::retry_on_stale_lock(vcs.workdir(), || vcs.checkout_tag(tag))
+        }).map_err(|e| {
+            error!("git_checkout_tag: failed to checkout '{tag}': {e}");
+            e.to_string()
+        })?;
+        crate::recent_branches::record_checkout(vcs.workdir(), previous.as_deref(), None);
+        Ok(())
+    })
+    .await
 }
 
 /* ---------- optional: branch ops used by your JS ---------- */
 #[tauri::command]
-pub fn git_checkout_branch(state: State<'_, AppState>, name: String) -> Result<(), String> {
-    let branch = name.trim();
+pub async fn git_checkout_branch<R: Runtime>(window: Window<R>, name: String) -> Result<(), String> {
+    let branch = name.trim().to_string();
     if branch.is_empty() {
         return Err("Branch name cannot be empty".to_string());
     }
+    off_main(&window, "checkout", move |app, state| checkout_branch(app, state, &branch)).await
+}
 
+/// Check out `branch` (or a commit, detaching HEAD) in the current repository, refusing a
+/// branch held by another worktree and recording it for the quick-switcher. Blocking.
+fn checkout_branch<R: Runtime>(app: &tauri::AppHandle<R>, state: &AppState, branch: &str) -> Result<(), String> {
     info!("git_checkout_branch: attempting to checkout '{branch}'");
 
-    let repo = state
-        .current_repo()
-        .ok_or_else(|| "No repository selected".to_string())?;
+    let repo = worktree_repo(state)?;
+    ensure_writable(&repo)?;
     let vcs = repo.inner();
+    if let Some(path) = crate::worktrees::claimed_by(vcs, branch) {
+        warn!("git_checkout_branch: '{branch}' is checked out in {path}");
+        return Err(format!("'{branch}' is already checked out in the worktree at {path}"));
+    }
+    let _op = state.op_gates().begin(app, vcs.workdir(), "checkout", JobId::next())?;
+    let previous = vcs.current_branch().ok().flatten();
 
-    vcs.checkout_branch(branch).map_err(|e| {
+    crate::telemetry::track("checkout", repo.id().as_ref(), || {
+        crate::locks::retry_on_stale_lock(vcs.workdir(), || vcs.checkout_branch(branch))
+    }).map_err(|e| {
         error!("git_checkout_branch: failed to checkout '{branch}': {e}");
         e.to_string()
     })?;
+    // A remote branch is checked out as a local one of another name; record what HEAD is now.
+    let current = vcs.current_branch().ok().flatten();
+    crate::recent_branches::record_checkout(vcs.workdir(), previous.as_deref(), current.as_deref());
 
     info!("git_checkout_branch: successfully checked out '{branch}'");
     Ok(())
 }
 
 #[tauri::command]
-pub fn git_delete_branch(state: State<'_, AppState>, name: String, force: Option<bool>) -> Result<(), String> {
-    let name = name.trim();
-    if name.is_empty() { return Err("Branch name cannot be empty".to_string()); }
+pub async fn git_delete_branch<R: Runtime>(window: Window<R>, name: String, force: Option<bool>) -> Result<(), String> {
+    off_main(&window, "delete-branch", move |app, state| {
+        let name = name.trim();
+        if name.is_empty() { return Err("Branch name cannot be empty".to_string()); }
+        let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "delete-branch", JobId::next())?;
+        vcs.delete_branch(name, force.unwrap_or(false)).map_err(|e| e.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn git_rename_branch<R: Runtime>(window: Window<R>, old_name: String, new_name: String) -> Result<(), String> {
+    off_main(&window, "rename-branch", move |app, state| {
+        let old = old_name.trim();
+        let newn = new_name.trim();
+        if old.is_empty() || newn.is_empty() { return Err("Branch name cannot be empty".into()); }
+        if old == newn { return Ok(()); }
+        let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "rename-branch", JobId::next())?;
+        vcs.rename_branch(old, newn).map_err(|e| e.to_string())?;
+        crate::recent_branches::record_rename(vcs.workdir(), old, newn);
+        Ok(())
+    })
+    .await
+}
+
+/// The branch checked out before the current one, for "Switch to Previous Branch"
+/// (`git checkout -`).
+#[tauri::command]
+pub fn previous_branch(state: State<'_, AppState>) -> Result<Option<String>, String> {
     let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
-    let vcs = repo.inner();
-    vcs.delete_branch(name, force.unwrap_or(false)).map_err(|e| e.to_string())
+    Ok(crate::head_moves::previous_branch(repo.inner()))
 }
 
+/// How the last checkout or reset would be undone; `None` when the last move of HEAD was
+/// something else.
 #[tauri::command]
-pub fn git_rename_branch(state: State<'_, AppState>, old_name: String, new_name: String) -> Result<(), String> {
-    let old = old_name.trim();
-    let newn = new_name.trim();
-    if old.is_empty() || newn.is_empty() { return Err("Branch name cannot be empty".into()); }
-    if old == newn { return Ok(()); }
+pub fn last_head_move(state: State<'_, AppState>) -> Result<Option<crate::head_moves::HeadMove>, String> {
     let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
-    let vcs = repo.inner();
-    vcs.rename_branch(old, newn).map_err(|e| e.to_string())
+    Ok(crate::head_moves::last_move(repo.inner()))
 }
 
+/// Undo the last checkout or reset using HEAD's reflog. Returns what was undone. Undoing a
+/// reset that takes pushed commits off the branch needs `force` (see `history_guard`).
 #[tauri::command]
-pub fn git_merge_branch(state: State<'_, AppState>, name: String) -> Result<(), String> {
+pub async fn undo_last_head_move<R: Runtime>(window: Window<R>, force: Option<bool>) -> Result<crate::head_moves::HeadMove, String> {
+    let force = force.unwrap_or(false);
+    off_main(&window, "undo", move |app, state| {
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let Some(last) = crate::head_moves::last_move(vcs) else {
+            return Err("Nothing to undo: the last change of HEAD was not a checkout or reset".into());
+        };
+        info!("undo_last_head_move: {} (undoing \"{}\")", last.label, last.message);
+        if last.kind == crate::head_moves::UndoKind::Checkout {
+            checkout_branch(app, state, &last.target)?;
+            return Ok(last);
+        }
+        let _op = state.op_gates().begin(app, vcs.workdir(), "reset", JobId::next())?;
+        crate::history_guard::check(vcs, "Undo", &last.dropped, force)?;
+        let done = crate::locks::retry_on_stale_lock(vcs.workdir(), || Ok(crate::head_moves::undo(vcs)))
+            .map_err(|e| e.to_string())??;
+        crate::refresh::request(app, crate::refresh::Trigger::Action);
+        Ok(done)
+    })
+    .await
+}
+
+/// Up to `limit` (default 10) recently checked out local branches, most recent first, for
+/// the quick-switcher. The current branch is not included.
+#[tauri::command]
+pub fn recent_branches(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<crate::recent_branches::RecentBranch>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    crate::recent_branches::recent(repo.inner(), limit.unwrap_or(10))
+}
+
+/// Set or clear (empty text) `branch.<name>.description` of a local branch.
+#[tauri::command]
+pub fn set_branch_description(state: State<'_, AppState>, name: String, description: Option<String>) -> Result<(), String> {
+    set_branch_text(&state, &name, "description", description.as_deref())
+}
+
+/// Set or clear (empty text) the app's notes for a local branch.
+#[tauri::command]
+pub fn set_branch_notes(state: State<'_, AppState>, name: String, notes: Option<String>) -> Result<(), String> {
+    set_branch_text(&state, &name, BRANCH_NOTES_KEY, notes.as_deref())?;
+    let mut cfg = state.repo_config();
+    match branch_text(notes.as_deref()) {
+        Some(text) => cfg.branch_notes.insert(name.trim().to_string(), text.to_string()),
+        None => cfg.branch_notes.remove(name.trim()),
+    };
+    state.set_repo_config(cfg)
+}
+
+/// Text as stored: trailing whitespace dropped, blank text meaning "unset".
+fn branch_text(text: Option<&str>) -> Option<&str> {
+    text.map(str::trim_end).filter(|t| !t.trim().is_empty())
+}
+
+fn set_branch_text(state: &State<'_, AppState>, name: &str, key: &str, text: Option<&str>) -> Result<(), String> {
     let name = name.trim();
-    if name.is_empty() { return Err("Branch name cannot be empty".to_string()); }
+    if name.is_empty() { return Err("Branch name cannot be empty".into()); }
     let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
     let vcs = repo.inner();
-    vcs.merge_into_current(name).map_err(|e| e.to_string())
+    let branches = vcs.branches().map_err(|e| e.to_string())?;
+    if !branches.iter().any(|b| matches!(b.kind, openvcs_core::models::BranchKind::Local) && b.name == name) {
+        return Err(format!("No local branch named '{name}'"));
+    }
+    info!("set_branch_text: branch.{name}.{key}");
+    vcs.set_branch_config(name, key, branch_text(text)).map_err(|e| e.to_string())
 }
 
+/// Clean up a local branch whose upstream is gone: point it at `upstream` (e.g.
+/// `origin/main`) when given, otherwise delete it.
 #[tauri::command]
-pub fn git_create_branch(
-    state: State<'_, AppState>,
+pub async fn git_relink_or_delete_branch<R: Runtime>(
+    window: Window<R>,
+    name: String,
+    upstream: Option<String>,
+    force: Option<bool>,
+) -> Result<(), String> {
+    off_main(&window, "relink-branch", move |app, state| {
+        let name = name.trim();
+        if name.is_empty() { return Err("Branch name cannot be empty".to_string()); }
+        let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        match upstream.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+            Some(up) => {
+                info!("git_relink_or_delete_branch: '{name}' now tracks '{up}'");
+                let _op = state.op_gates().begin(app, vcs.workdir(), "relink-branch", JobId::next())?;
+                vcs.set_upstream(name, up).map_err(|e| e.to_string())
+            }
+            None => {
+                info!("git_relink_or_delete_branch: deleting '{name}'");
+                let _op = state.op_gates().begin(app, vcs.workdir(), "delete-branch", JobId::next())?;
+                vcs.delete_branch(name, force.unwrap_or(false)).map_err(|e| e.to_string())
+            }
+        }
+    })
+    .await
+}
+
+/// Remote-tracking branches worth offering as the new upstream of `name`: same-named
+/// branches on any remote first, then each remote's main/master/develop.
+#[tauri::command]
+pub fn git_upstream_suggestions(state: State<'_, AppState>, name: String) -> Result<Vec<String>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    let remotes: Vec<String> = repo
+        .inner()
+        .branches()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|b| matches!(b.kind, openvcs_core::models::BranchKind::Remote { .. }))
+        .map(|b| b.name)
+        .collect();
+    let short = |b: &str| b.split_once('/').map(|(_, s)| s.to_string()).unwrap_or_default();
+    let mut out: Vec<String> = remotes.iter().filter(|b| short(b) == name).cloned().collect();
+    for fallback in ["main", "master", "develop"] {
+        if fallback != name {
+            out.extend(remotes.iter().filter(|b| short(b) == fallback).cloned());
+        }
+    }
+    Ok(out)
+}
+
+#[tauri::command]
+pub async fn git_merge_branch<R: Runtime>(
+    window: Window<R>,
+    name: String,
+    options: Option<MergeOptions>,
+) -> Result<MergeOutcome, String> {
+    off_main(&window, "merge", move |app, state| {
+        let name = name.trim();
+        if name.is_empty() { return Err("Branch name cannot be empty".to_string()); }
+        let mut options = options.unwrap_or_default();
+        options.message = options.message.filter(|m| !m.trim().is_empty());
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "merge", JobId::next())?;
+        info!("git_merge_branch: '{name}' ({:?})", options.fast_forward);
+        let outcome = crate::telemetry::track("merge", repo.id().as_ref(), || vcs.merge_branch(name, &options)).map_err(|e| {
+            error!("git_merge_branch: merging '{name}' failed: {e}");
+            e.to_string()
+        })?;
+        crate::refresh::request(app, crate::refresh::Trigger::Action);
+        Ok(outcome)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn git_create_branch<R: Runtime>(
+    window: Window<R>,
     name: String,
     from: Option<String>,
     checkout: Option<bool>,
 ) -> Result<(), String> {
-    info!(
-        "git_create_branch: requested branch '{}', from={:?}, checkout={:?}",
-        name, from, checkout
-    );
+    off_main(&window, "create-branch", move |app, state| {
+        info!(
+            "git_create_branch: requested branch '{}', from={:?}, checkout={:?}",
+            name, from, checkout
+        );
+
+        let repo = state
+            .current_repo()
+            .ok_or_else(|| "No repository selected".to_string())?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "create-branch", JobId::next())?;
+        let previous = vcs.current_branch().ok().flatten();
+
+        // The base only names the start point; HEAD and the worktree stay as they are
+        let start_point = from.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        vcs.create_branch(&name, start_point, checkout.unwrap_or(false))
+            .map_err(|e| {
+                error!("git_create_branch: failed to create branch '{name}': {e}");
+                e.to_string()
+            })?;
+
+        if checkout.unwrap_or(false) {
+            let current = vcs.current_branch().ok().flatten();
+            crate::recent_branches::record_checkout(vcs.workdir(), previous.as_deref(), current.as_deref());
+        }
+
+        info!("git_create_branch: successfully created branch '{name}'");
+        Ok(())
+    })
+    .await
+}
+
+/// Local tags, sorted by name.
+#[tauri::command]
+pub fn git_list_tags(state: State<'_, AppState>) -> Result<Vec<openvcs_core::models::TagItem>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    repo.inner().list_tags().map_err(|e| e.to_string())
+}
+
+/// Create a tag on `target` (default HEAD): annotated with `message`, lightweight without
+/// one (unless signed). `sign` defaults to the "sign commits" preference; the key comes
+/// from `credentials.signing_key`.
+#[tauri::command]
+pub async fn git_create_tag<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    name: String,
+    target: Option<String>,
+    message: Option<String>,
+    sign: Option<bool>,
+) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() { return Err("Tag name cannot be empty".into()); }
+    let message = message.filter(|m| !m.trim().is_empty());
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    let creds = state.config().credentials;
+    let key = sign.unwrap_or(creds.sign_commits).then(|| {
+        let mut key = openvcs_core::models::SigningKey::new(creds.signing_key.trim());
+        if key.format == openvcs_core::models::SignatureFormat::Gpg && !creds.gpg_program.trim().is_empty() {
+            key.program = Some(creds.gpg_program.trim().to_string());
+        }
+        key
+    });
+    let app = window.app_handle().clone();
+    let job = JobId::next();
+    let gates = state.op_gates();
+
+    // Signing may wait on a pinentry or hardware key; keep it off the main thread.
+    async_runtime::spawn_blocking(move || {
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "create-tag", job)?;
+        info!("git_create_tag: '{name}' on {target:?} (signed: {})", key.is_some());
+        vcs.create_tag(&name, target.as_deref(), message.as_deref(), key.as_ref()).map_err(|e| {
+            error!("git_create_tag: failed to create tag '{name}': {e}");
+            e.to_string()
+        })?;
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("tag task failed: {e}"))?
+}
+
+#[tauri::command]
+pub async fn git_delete_tag<R: Runtime>(window: Window<R>, name: String) -> Result<(), String> {
+    off_main(&window, "delete-tag", move |app, state| {
+        let name = name.trim();
+        if name.is_empty() { return Err("Tag name cannot be empty".into()); }
+        let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "delete-tag", JobId::next())?;
+        info!("git_delete_tag: '{name}'");
+        vcs.delete_tag(name).map_err(|e| e.to_string())?;
+        crate::refresh::request(app, crate::refresh::Trigger::Action);
+        Ok(())
+    })
+    .await
+}
+
+/// Push `tags` (default: every local tag) to `remote` (default `origin`).
+#[tauri::command]
+pub async fn git_push_tags<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    remote: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<(), String> {
+    let remote = remote.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).unwrap_or_else(|| "origin".into());
+    let tags: Vec<String> = tags.unwrap_or_default().iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    let app = window.app_handle().clone();
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
+
+    async_runtime::spawn_blocking(move || {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "push-tags", job)?;
+        let what = if tags.is_empty() { "all tags".to_string() } else { tags.join(", ") };
+        info!("git_push_tags: {what} to '{remote}'");
+        crate::telemetry::track("push", repo.id().as_ref(), || {
+            vcs.push_tags(&remote, &tags, Some(progress_bridge(app.clone(), job)))
+        })
+        .map_err(|e| {
+            error!("git_push_tags: pushing {what} to '{remote}' failed: {e}");
+            report_vcs_error(&app, job, &e)
+        })?;
+
+        let _ = app.emit("git-progress", ProgressPayload::new(format!("Pushed {what} to '{remote}'"), job));
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("push task failed: {e}"))?
+}
+
+/// Check the signature of tag `name`.
+#[tauri::command]
+pub async fn git_verify_tag(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<openvcs_core::models::TagVerification, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    async_runtime::spawn_blocking(move || {
+        let v = repo.inner().verify_tag(name.trim()).map_err(|e| e.to_string())?;
+        info!("git_verify_tag: '{}' signed={} valid={} signer={:?}", v.tag, v.signed, v.valid, v.signer);
+        Ok(v)
+    })
+    .await
+    .map_err(|e| format!("verify task failed: {e}"))?
+}
+
+/// Publish a release for tag `tag` on the forge behind `remote` (default `origin`): push the
+/// tag there, create the release and upload the `assets` files. Create the tag first with
+/// `git_create_tag`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_release<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    tag: String,
+    title: String,
+    notes: String,
+    assets: Vec<String>,
+    remote: Option<String>,
+    draft: Option<bool>,
+    prerelease: Option<bool>,
+) -> Result<crate::forge::Release, String> {
+    let tag = tag.trim().to_string();
+    if tag.is_empty() { return Err("Tag name cannot be empty".into()); }
+    let remote = remote.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).unwrap_or_else(|| "origin".into());
+    let assets: Vec<std::path::PathBuf> = assets.iter().map(|a| crate::paths::expand(a)).collect();
+    if let Some(missing) = assets.iter().find(|a| !a.is_file()) {
+        return Err(format!("Asset {} is not a file", missing.display()));
+    }
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    let integrations = state.config().integrations;
+    let app = window.app_handle().clone();
+    let job = JobId::next();
+    let gates = state.op_gates();
+
+    // Find the forge and token before pushing anything
+    let url = repo
+        .inner()
+        .list_remotes()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|r| r.name == remote)
+        .map(|r| r.push_url)
+        .ok_or_else(|| format!("No remote named '{remote}'"))?;
+    let forge = crate::forge::ForgeRepo::detect(&url, &integrations)?;
+    let token = forge.token()?;
+
+    let pushed = {
+        let (app, tag, remote) = (app.clone(), tag.clone(), remote.clone());
+        async_runtime::spawn_blocking(move || {
+            let vcs = repo.inner();
+            let _op = gates.begin(&app, vcs.workdir(), "release", job)?;
+            let refspec = format!("refs/tags/{tag}:refs/tags/{tag}");
+            info!("create_release: pushing {refspec} to {remote}");
+            crate::telemetry::track("push", repo.id().as_ref(), || {
+                vcs.push(&remote, &refspec, Some(progress_bridge(app.clone(), job)))
+            })
+            .map_err(|e| {
+                error!("create_release: pushing tag '{tag}' failed: {e}");
+                report_vcs_error(&app, job, &e)
+            })
+        })
+        .await
+        .map_err(|e| format!("release task failed: {e}"))?
+    };
+    pushed?;
+
+    let req = crate::forge::ReleaseRequest {
+        tag,
+        title,
+        notes,
+        assets,
+        draft: draft.unwrap_or(false),
+        prerelease: prerelease.unwrap_or(false),
+    };
+    let release = crate::forge::create_release(&forge, &token, &req).await.inspect_err(|e| error!("create_release: {e}"))?;
+    crate::refresh::request(&app, crate::refresh::Trigger::Action);
+    Ok(release)
+}
 
+/// Diff lines of one working tree file; large diffs arrive in chunks (see `chunked`).
+#[tauri::command]
+pub fn git_diff_file<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<crate::chunked::Transfer<String>, String> {
+    use std::path::PathBuf;
+
+    let repo = worktree_repo(&state)?;
+    let vcs = repo.inner();
+
+    // Allow either absolute or repo-relative; backend handles stripping
+    let lines = vcs.diff_file(&PathBuf::from(path)).map_err(|e| e.to_string())?;
+    crate::chunked::transfer(window.app_handle(), lines)
+}
+
+/* ---------- git_diff_commit ---------- */
+/// Diff lines of a commit; large diffs arrive in chunks (see `chunked`).
+#[tauri::command]
+pub fn git_diff_commit<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<crate::chunked::Transfer<String>, String> {
     let repo = state
         .current_repo()
         .ok_or_else(|| "No repository selected".to_string())?;
+    crate::partial_clone::check(repo.inner(), &id, state.config().git.offline_partial_clone)?;
+    let lines = crate::commit_cache::diff(repo.inner(), &id)?;
+    crate::chunked::transfer(window.app_handle(), lines)
+}
+
+/// `git_diff_file` parsed into hunks, with whitespace-only changes marked per
+/// `diff.ignore_whitespace` so the UI can hide them without asking again.
+#[tauri::command]
+pub fn git_diff_file_structured(state: State<'_, AppState>, path: String) -> Result<Vec<FileDiff>, String> {
+    let repo = worktree_repo(&state)?;
+    let lines = repo.inner().diff_file(Path::new(&path)).map_err(|e| e.to_string())?;
+    let mut files = openvcs_core::diff::parse_unified(&lines, state.config().diff.ignore_whitespace);
+    // Untracked files are diffed against /dev/null by absolute path; report the one asked for.
+    if let [file] = files.as_mut_slice() {
+        file.path = path;
+    }
+    mark_filters(repo.inner(), &mut files);
+    Ok(files)
+}
+
+/// Every change under directory `prefix` (default: the Changes scope) against HEAD, parsed
+/// into per-file hunks; many files arrive in chunks (see `chunked`).
+#[tauri::command]
+pub fn git_diff_dir<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    prefix: Option<String>,
+) -> Result<crate::chunked::Transfer<FileDiff>, String> {
+    let repo = worktree_repo(&state)?;
     let vcs = repo.inner();
+    let prefix = prefix.or_else(|| crate::changes_scope::get(vcs)).unwrap_or_default();
+    let lines = vcs.diff_dir(&prefix).map_err(|e| e.to_string())?;
+    let mut files = openvcs_core::diff::parse_unified(&lines, state.config().diff.ignore_whitespace);
+    mark_filters(vcs, &mut files);
+    crate::chunked::transfer(window.app_handle(), files)
+}
 
-    // If a base branch is provided, check it out first.
-    if let Some(from) = from {
-        match vcs.checkout_branch(&from) {
-            Ok(_) => info!("git_create_branch: successfully checked out base branch '{from}'"),
-            Err(e) => {
-                error!(
-                    "git_create_branch: failed to checkout base branch '{from}': {e}"
-                );
-                return Err(format!("base branch not found or cannot checkout: {e}"));
+/// The directory the Changes view is limited to, if any.
+#[tauri::command]
+pub fn get_changes_scope(state: State<'_, AppState>) -> Result<Option<String>, String> {
+    let repo = worktree_repo(&state)?;
+    Ok(crate::changes_scope::get(repo.inner()))
+}
+
+/// Limit the Changes view (status and `git_diff_dir`) to directory `dir` of the repository,
+/// or show the whole tree again with `None`. Returns the scope as stored.
+#[tauri::command]
+pub fn set_changes_scope<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    dir: Option<String>,
+) -> Result<Option<String>, String> {
+    let repo = worktree_repo(&state)?;
+    ensure_writable(&repo)?;
+    let scope = crate::changes_scope::set(repo.inner(), dir.as_deref())?;
+    crate::refresh::request(window.app_handle(), crate::refresh::Trigger::Manual);
+    Ok(scope)
+}
+
+/// `git_diff_commit` parsed into per-file hunks (see `git_diff_file_structured`).
+#[tauri::command]
+pub fn git_diff_commit_structured(state: State<'_, AppState>, id: String) -> Result<Vec<FileDiff>, String> {
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    crate::partial_clone::check(repo.inner(), &id, state.config().git.offline_partial_clone)?;
+    let lines = crate::commit_cache::diff(repo.inner(), &id)?;
+    let mut files = openvcs_core::diff::parse_unified(&lines, state.config().diff.ignore_whitespace);
+    mark_filters(repo.inner(), &mut files);
+    Ok(files)
+}
+
+/// Fill `FileDiff::filter` from the current gitattributes, so the UI can say that a diff shows
+/// cleaned content (or that an LFS file's content was not diffed). Best effort.
+fn mark_filters(vcs: &dyn openvcs_core::Vcs, files: &mut [FileDiff]) {
+    let paths: Vec<String> = files.iter().filter(|f| !f.path.is_empty()).map(|f| f.path.clone()).collect();
+    if paths.is_empty() {
+        return;
+    }
+    match vcs.path_attributes(&paths, &["filter"]) {
+        Ok(attrs) => {
+            for f in files.iter_mut() {
+                f.filter = attrs
+                    .iter()
+                    .find(|a| a.path == f.path && a.value != "unset")
+                    .map(|a| a.value.clone());
             }
         }
+        Err(e) => debug!("diff: no attributes for {} path(s): {e}", paths.len()),
     }
+}
 
-    vcs.create_branch(&name, checkout.unwrap_or(false))
-        .map_err(|e| {
-            error!("git_create_branch: failed to create branch '{name}': {e}");
-            e.to_string()
-        })?;
+/// Per-file additions/deletions of commit `id` (cached on disk by commit id).
+#[tauri::command]
+pub async fn git_commit_stats(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<crate::commit_cache::CommitStats, String> {
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    let offline = state.config().git.offline_partial_clone;
+    async_runtime::spawn_blocking(move || {
+        crate::partial_clone::check(repo.inner(), &id, offline)?;
+        crate::commit_cache::stats(repo.inner(), &id)
+    })
+    .await
+    .map_err(|e| format!("stats task failed: {e}"))?
+}
 
-    info!("git_create_branch: successfully created branch '{name}'");
-    Ok(())
+/// In a partial clone, the files of commit `id` whose contents a diff would first fetch
+/// from the promisor remote; `None` when everything is local.
+#[tauri::command]
+pub async fn git_commit_fetch_need(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<Option<crate::partial_clone::FetchNeed>, String> {
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    let offline = state.config().git.offline_partial_clone;
+    async_runtime::spawn_blocking(move || crate::partial_clone::need(repo.inner(), &id, offline))
+        .await
+        .map_err(|e| format!("fetch check failed: {e}"))?
 }
 
+/// Full metadata of commit `id`, with the trailers of its message as key/value pairs.
 #[tauri::command]
-pub fn git_diff_file(state: State<'_, AppState>, path: String) -> Result<Vec<String>, String> {
-    use std::path::PathBuf;
+pub async fn git_commit_detail(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<openvcs_core::models::CommitRecord, String> {
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    async_runtime::spawn_blocking(move || {
+        let mut records = repo.inner().commit_records(std::slice::from_ref(&id)).map_err(|e| e.to_string())?;
+        records.pop().ok_or_else(|| format!("No such commit: {id}"))
+    })
+    .await
+    .map_err(|e| format!("commit task failed: {e}"))?
+}
 
+/// Revisions of `path` (newest first, following renames) for the time-machine slider;
+/// each carries the blob id to fetch with `git_read_blob`.
+#[tauri::command]
+pub async fn git_file_revisions(
+    state: State<'_, AppState>,
+    path: String,
+    limit: Option<usize>,
+) -> Result<Vec<openvcs_core::models::FileRevision>, String> {
     let repo = state
         .current_repo()
         .ok_or_else(|| "No repository selected".to_string())?;
-    let vcs = repo.inner();
+    let limit = limit.unwrap_or(200).min(5000);
+    async_runtime::spawn_blocking(move || {
+        repo.inner().file_revisions(Path::new(&path), limit).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| format!("file history task failed: {e}"))?
+}
 
-    // Allow either absolute or repo-relative; backend handles stripping
-    vcs.diff_file(&PathBuf::from(path)).map_err(|e| e.to_string())
+/// Contents of blob `oid` (cached on disk by object id).
+#[tauri::command]
+pub async fn git_read_blob(
+    state: State<'_, AppState>,
+    oid: String,
+) -> Result<crate::commit_cache::BlobText, String> {
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    async_runtime::spawn_blocking(move || crate::commit_cache::blob(repo.inner(), oid.trim()))
+        .await
+        .map_err(|e| format!("blob task failed: {e}"))?
 }
 
-/* ---------- git_diff_commit ---------- */
+/// Snapshot the working tree to the current branch's autosave ref now (`None` when it
+/// hasn't changed since the last snapshot).
 #[tauri::command]
-pub fn git_diff_commit(state: State<'_, AppState>, id: String) -> Result<Vec<String>, String> {
+pub async fn wip_snapshot_now(state: State<'_, AppState>) -> Result<Option<crate::wip::Saved>, String> {
     let repo = state
         .current_repo()
         .ok_or_else(|| "No repository selected".to_string())?;
-    let vcs = repo.inner();
-    vcs.diff_commit(&id).map_err(|e| e.to_string())
+    ensure_writable(&repo)?;
+    async_runtime::spawn_blocking(move || crate::wip::snapshot(repo.inner()))
+        .await
+        .map_err(|e| format!("snapshot task failed: {e}"))?
+}
+
+/// Autosave snapshots of `branch` (default: the current branch), newest first.
+#[tauri::command]
+pub async fn wip_list(
+    state: State<'_, AppState>,
+    branch: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<CommitItem>, String> {
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    let limit = limit.unwrap_or(100).min(1000);
+    async_runtime::spawn_blocking(move || {
+        let vcs = repo.inner();
+        let branch = match branch {
+            Some(b) => Some(b),
+            None => vcs.current_branch().map_err(|e| e.to_string())?,
+        };
+        Ok(crate::wip::list(vcs, branch.as_deref(), limit))
+    })
+    .await
+    .map_err(|e| format!("snapshot list task failed: {e}"))?
 }
 
-#[tauri::command]
-pub fn git_discard_paths(state: State<'_, AppState>, paths: Vec<String>) -> Result<(), String> {
-    use std::path::PathBuf;
-    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
-    let pb: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
-    repo.inner().discard_paths(&pb).map_err(|e| e.to_string())
+/// Write `paths` (everything when omitted) from autosave snapshot `commit` into the working
+/// tree. The current state is snapshotted first, so a restore can itself be undone.
+#[tauri::command]
+pub async fn wip_restore<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    commit: String,
+    paths: Option<Vec<String>>,
+) -> Result<(), String> {
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    let app = window.app_handle().clone();
+    let gates = state.op_gates();
+
+    async_runtime::spawn_blocking(move || {
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "wip-restore", JobId::next())?;
+        crate::wip::snapshot(vcs)?;
+        let paths: Vec<std::path::PathBuf> = paths.unwrap_or_default().into_iter().map(Into::into).collect();
+        info!("wip_restore: {} path(s) from {commit}", paths.len());
+        vcs.restore_worktree(commit.trim(), &paths).map_err(|e| {
+            error!("wip_restore: failed to restore from {commit}: {e}");
+            e.to_string()
+        })?;
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("restore task failed: {e}"))?
+}
+
+/// Inspect `.git/index.lock` of the current repository (`None` when absent).
+#[tauri::command]
+pub fn inspect_index_lock(state: State<'_, AppState>) -> Result<Option<crate::locks::LockInfo>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    Ok(crate::locks::index_lock(repo.inner().workdir()))
+}
+
+/// Remove a stale `.git/index.lock`. Refuses a lock that looks live unless `force` is set.
+#[tauri::command]
+pub fn clear_stale_lock(state: State<'_, AppState>, force: Option<bool>) -> Result<Option<crate::locks::LockInfo>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    crate::locks::clear_stale_lock(repo.inner().workdir(), force.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn git_discard_paths<R: Runtime>(window: Window<R>, paths: Vec<String>) -> Result<(), String> {
+    off_main(&window, "discard", move |app, state| {
+        use std::path::PathBuf;
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let pb: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+        let _op = state.op_gates().begin(app, repo.inner().workdir(), "discard", JobId::next())?;
+        crate::locks::retry_on_stale_lock(repo.inner().workdir(), || repo.inner().discard_paths(&pb))
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn git_discard_patch<R: Runtime>(window: Window<R>, patch: String) -> Result<(), String> {
+    off_main(&window, "discard", move |app, state| {
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let _op = state.op_gates().begin(app, repo.inner().workdir(), "discard", JobId::next())?;
+        crate::locks::retry_on_stale_lock(repo.inner().workdir(), || repo.inner().apply_reverse_patch(&patch))
+            .map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Stage a selection of files and folders as one operation, with `git-progress` events.
+#[tauri::command]
+pub async fn batch_stage<R: Runtime>(window: Window<R>, state: State<'_, AppState>, paths: Vec<String>) -> Result<(), String> {
+    run_batch(window, state, crate::batch::BatchOp::Stage, paths).await
+}
+
+/// Discard the changes of a selection of files and folders as one operation, with
+/// `git-progress` events.
+#[tauri::command]
+pub async fn batch_discard<R: Runtime>(window: Window<R>, state: State<'_, AppState>, paths: Vec<String>) -> Result<(), String> {
+    run_batch(window, state, crate::batch::BatchOp::Discard, paths).await
+}
+
+async fn run_batch<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    op: crate::batch::BatchOp,
+    paths: Vec<String>,
+) -> Result<(), String> {
+    let repo = worktree_repo(&state)?;
+    ensure_writable(&repo)?;
+    if paths.is_empty() {
+        return Ok(());
+    }
+    let paths: Vec<std::path::PathBuf> = paths.into_iter().map(Into::into).collect();
+    let app = window.app_handle().clone();
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
+
+    async_runtime::spawn_blocking(move || {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), op.name(), job)?;
+        let on = progress_bridge(app.clone(), job);
+        let res = crate::batch::run(vcs, op, &paths, &on);
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        res.map_err(|e| {
+            error!("batch {}: {e}", op.name());
+            report_vcs_error(&app, job, &e)
+        })
+    })
+    .await
+    .map_err(|e| format!("{} task failed: {e}", op.name()))?
 }
 
+/// Add ignore patterns (e.g. `*.log` for every file with that extension, `build/`) to the
+/// top-level `.gitignore`. Returns the patterns that were not listed yet.
 #[tauri::command]
-pub fn git_discard_patch(state: State<'_, AppState>, patch: String) -> Result<(), String> {
-    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
-    repo.inner().apply_reverse_patch(&patch).map_err(|e| e.to_string())
-}
-
-#[derive(serde::Serialize)]
-pub struct RepoSummary {
-    path: String,
-    current_branch: String,
-    branches: Vec<BranchItem>,
+pub fn batch_ignore<R: Runtime>(window: Window<R>, state: State<'_, AppState>, patterns: Vec<String>) -> Result<Vec<String>, String> {
+    let repo = worktree_repo(&state)?;
+    ensure_writable(&repo)?;
+    let added = crate::batch::ignore(repo.inner().workdir(), &patterns)?;
+    if !added.is_empty() {
+        crate::refresh::request(window.app_handle(), crate::refresh::Trigger::Action);
+    }
+    Ok(added)
 }
 
 #[tauri::command]
 pub fn get_repo_summary(state: State<'_, AppState>) -> Result<RepoSummary, String> {
     let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
     let vcs = repo.inner();
+    let workdir = vcs.workdir();
+
+    let cache = state.summaries();
+    let key = crate::summary::refs_key(workdir);
+    // Other worktrees change without touching our refs, so their state is never cached.
+    if let Some(hit) = cache.get(workdir, &key) {
+        trace!("get_repo_summary: cache hit");
+        return Ok(RepoSummary { worktrees: crate::worktrees::statuses(&repo), ..hit });
+    }
 
-    let path = vcs.workdir().to_string_lossy().to_string();
-
-    let branches = vcs.branches().map_err(|e| e.to_string())?;
-    let current = vcs.current_branch().map_err(|e| e.to_string())?
-        .unwrap_or_else(|| "HEAD".into());
+    let path = workdir.to_string_lossy().to_string();
 
-    // Reuse your existing normalization by calling the tauri command directly:
-    let normalized = git_list_branches(state)?;
+    let current = vcs.current_branch().map_err(|e| e.to_string())?;
+    let normalized = normalized_branches(vcs, &BranchQuery::default(), current.as_deref())?;
 
-    Ok(RepoSummary {
+    let summary = RepoSummary {
         path,
-        current_branch: current,
+        current_branch: current.unwrap_or_else(|| "HEAD".into()),
         branches: normalized,
-    })
+        is_shallow: vcs.is_shallow().unwrap_or(false),
+        worktrees: Vec::new(),
+    };
+    cache.put(workdir, key, summary.clone());
+    Ok(RepoSummary { worktrees: crate::worktrees::statuses(&repo), ..summary })
+}
+
+/// Worktrees of the current repository with their branch and dirty count (empty when
+/// there are no linked worktrees).
+#[tauri::command]
+pub async fn list_worktrees(state: State<'_, AppState>) -> Result<Vec<crate::worktrees::WorktreeStatus>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    async_runtime::spawn_blocking(move || crate::worktrees::statuses(&repo))
+        .await
+        .map_err(|e| format!("list_worktrees task failed: {e}"))
 }
 
 #[tauri::command]
 pub fn git_current_branch(state: State<'_, AppState>) -> Result<String, String> {
     let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
-    repo.inner()
-        .current_branch()
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Detached HEAD".to_string())
+    let vcs = repo.inner();
+    match vcs.current_branch().map_err(|e| e.to_string())? {
+        Some(branch) => Ok(branch),
+        None => Err(vcs.head_state().map_or_else(|_| "Detached HEAD".to_string(), |h| h.to_string())),
+    }
 }
 
 
@@ -574,12 +1892,13 @@ pub async fn commit_changes<R: Runtime>(
     state: State<'_, AppState>,
     summary: String,
     description: String,
+    options: Option<CommitOptions>,
 ) -> Result<String, String> {
     info!("commit_changes called (summary: \"{}\")", summary);
 
-    let repo = state
-        .current_repo()
-        .ok_or_else(|| "No repository selected".to_string())?;
+    let repo = worktree_repo(&state)?;
+    ensure_writable(&repo)?;
+    let options = commit_options(options)?;
     let repo = repo.clone(); // move into blocking task
     let app = window.app_handle().clone();
 
@@ -589,8 +1908,13 @@ pub async fn commit_changes<R: Runtime>(
         format!("{summary}\n\n{description}")
     };
 
+    let job = JobId::next();
+    let refresh_app = window.app_handle().clone();
+    let gates = state.op_gates();
     async_runtime::spawn_blocking(move || {
-        let on = progress_bridge(app);
+        let _job = crate::logging::job_scope(job);
+        let _op = gates.begin(&refresh_app, repo.inner().workdir(), "commit", job)?;
+        let on = progress_bridge(app, job);
         on(VcsEvent::Info("Staging changes…"));
         info!("Staging changes for commit");
 
@@ -609,16 +1933,19 @@ pub async fn commit_changes<R: Runtime>(
         info!("Using identity: {} <{}>", name, email);
 
         on(VcsEvent::Info("Writing commit…"));
-        let oid = repo
-            .inner()
-            .commit(&message, &name, &email, &[])
-            .map_err(|e| {
-                error!("Commit failed: {e}");
-                e.to_string()
-            })?;
+        let oid = crate::telemetry::track("commit", repo.id().as_ref(), || {
+            crate::locks::retry_on_stale_lock(repo.inner().workdir(), || repo.inner().commit(&message, &name, &email, &[], &options))
+        })
+        .map_err(|e| {
+            error!("Commit failed: {e}");
+            e.to_string()
+        })?;
         info!("Commit created successfully: {oid}");
 
         on(VcsEvent::Info("Commit created."));
+        crate::refresh::request(&refresh_app, crate::refresh::Trigger::Action);
+        crate::commit_drafts::clear(repo.inner());
+        crate::plugins::dispatch(&refresh_app, PluginEvent::PostCommit, &[("commit", oid.clone())]);
         Ok(oid)
     })
         .await
@@ -628,6 +1955,43 @@ pub async fn commit_changes<R: Runtime>(
         })?
 }
 
+/// Files over the `lfs.large_file_warn_mb` threshold among the staged blobs and `files`
+/// (paths the commit will stage from the working tree); `None` when all are fine or the
+/// check is off. Call before committing and let the user confirm.
+#[tauri::command]
+pub async fn check_large_files(
+    state: State<'_, AppState>,
+    files: Option<Vec<String>>,
+) -> Result<Option<crate::large_files::LargeFileWarning>, String> {
+    let limit_mb = state.config().lfs.large_file_warn_mb;
+    if limit_mb == 0 {
+        return Ok(None);
+    }
+    let repo = worktree_repo(&state)?;
+    async_runtime::spawn_blocking(move || {
+        crate::large_files::check(repo.inner(), &files.unwrap_or_default(), u64::from(limit_mb) * 1024 * 1024)
+    })
+    .await
+    .map_err(|e| format!("large file check failed: {e}"))?
+}
+
+/// Secret scan of what a commit of `files` plus `patch` would add, per `git.secret_scan`
+/// (no findings when it is off). Call before committing.
+#[tauri::command]
+pub async fn scan_secrets(
+    state: State<'_, AppState>,
+    files: Option<Vec<String>>,
+    patch: Option<String>,
+) -> Result<crate::secret_scan::SecretScanReport, String> {
+    let cfg = state.config().git;
+    let repo = worktree_repo(&state)?;
+    async_runtime::spawn_blocking(move || {
+        crate::secret_scan::scan(repo.inner(), &cfg, &files.unwrap_or_default(), patch.as_deref())
+    })
+    .await
+    .map_err(|e| format!("secret scan failed: {e}"))?
+}
+
 #[tauri::command]
 pub async fn commit_selected<R: Runtime>(
     window: Window<R>,
@@ -635,12 +1999,13 @@ pub async fn commit_selected<R: Runtime>(
     summary: String,
     description: String,
     files: Vec<String>,
+    options: Option<CommitOptions>,
 ) -> Result<String, String> {
     info!("commit_selected called ({} file(s))", files.len());
 
-    let repo = state
-        .current_repo()
-        .ok_or_else(|| "No repository selected".to_string())?;
+    let repo = worktree_repo(&state)?;
+    ensure_writable(&repo)?;
+    let options = commit_options(options)?;
     let repo = repo.clone();
     let app = window.app_handle().clone();
 
@@ -650,8 +2015,13 @@ pub async fn commit_selected<R: Runtime>(
         format!("{summary}\n\n{description}")
     };
 
+    let job = JobId::next();
+    let refresh_app = window.app_handle().clone();
+    let gates = state.op_gates();
     async_runtime::spawn_blocking(move || {
-        let on = progress_bridge(app);
+        let _job = crate::logging::job_scope(job);
+        let _op = gates.begin(&refresh_app, repo.inner().workdir(), "commit", job)?;
+        let on = progress_bridge(app, job);
         on(VcsEvent::Info("Staging selected files…"));
 
         let (name, email) = repo
@@ -666,16 +2036,19 @@ pub async fn commit_selected<R: Runtime>(
             })
             .unwrap_or_else(|| ("OpenVCS".into(), "openvcs@example".into()));
 
-        let paths: Vec<std::path::PathBuf> = files.into_iter().map(|s| std::path::PathBuf::from(s)).collect();
+        let paths: Vec<std::path::PathBuf> = files.into_iter().map(std::path::PathBuf::from).collect();
 
         on(VcsEvent::Info("Writing commit…"));
-        let oid = repo
-            .inner()
-            .commit(&message, &name, &email, &paths)
-            .map_err(|e| {
-                error!("Commit (selected) failed: {e}");
-                e.to_string()
-            })?;
+        let oid = crate::telemetry::track("commit", repo.id().as_ref(), || {
+            crate::locks::retry_on_stale_lock(repo.inner().workdir(), || repo.inner().commit(&message, &name, &email, &paths, &options))
+        })
+        .map_err(|e| {
+            error!("Commit (selected) failed: {e}");
+            e.to_string()
+        })?;
+        crate::refresh::request(&refresh_app, crate::refresh::Trigger::Action);
+        crate::commit_drafts::clear(repo.inner());
+        crate::plugins::dispatch(&refresh_app, PluginEvent::PostCommit, &[("commit", oid.clone())]);
         Ok(oid)
     })
         .await
@@ -689,21 +2062,27 @@ pub async fn commit_patch<R: Runtime>(
     summary: String,
     description: String,
     patch: String,
+    options: Option<CommitOptions>,
 ) -> Result<String, String> {
     info!("commit_patch called (patch size: {} bytes)", patch.len());
-    let repo = state
-        .current_repo()
-        .ok_or_else(|| "No repository selected".to_string())?;
+    let repo = worktree_repo(&state)?;
+    ensure_writable(&repo)?;
+    let options = commit_options(options)?;
     let repo = repo.clone();
     let app = window.app_handle().clone();
 
     let message = if description.trim().is_empty() { summary.clone() } else { format!("{summary}\n\n{description}") };
 
+    let job = JobId::next();
+    let refresh_app = window.app_handle().clone();
+    let gates = state.op_gates();
     async_runtime::spawn_blocking(move || {
-        let on = progress_bridge(app);
+        let _job = crate::logging::job_scope(job);
+        let _op = gates.begin(&refresh_app, repo.inner().workdir(), "commit", job)?;
+        let on = progress_bridge(app, job);
         on(VcsEvent::Info("Staging selected hunks…"));
 
-        repo.inner().stage_patch(&patch).map_err(|e| {
+        crate::locks::retry_on_stale_lock(repo.inner().workdir(), || repo.inner().stage_patch(&patch)).map_err(|e| {
             error!("stage_patch failed: {e}");
             e.to_string()
         })?;
@@ -720,166 +2099,688 @@ pub async fn commit_patch<R: Runtime>(
             })
             .unwrap_or_else(|| ("OpenVCS".into(), "openvcs@example".into()));
 
-        on(VcsEvent::Info("Committing staged hunks…"));
-        let oid = repo.inner().commit_index(&message, &name, &email).map_err(|e| {
-            error!("commit_index failed: {e}");
-            e.to_string()
+        on(VcsEvent::Info("Committing staged hunks…"));
+        let oid = crate::telemetry::track("commit", repo.id().as_ref(), || {
+            crate::locks::retry_on_stale_lock(repo.inner().workdir(), || repo.inner().commit_index(&message, &name, &email, &options))
+        }).map_err(|e| {
+            error!("commit_index failed: {e}");
+            e.to_string()
+        })?;
+        crate::refresh::request(&refresh_app, crate::refresh::Trigger::Action);
+        crate::commit_drafts::clear(repo.inner());
+        crate::plugins::dispatch(&refresh_app, PluginEvent::PostCommit, &[("commit", oid.clone())]);
+        Ok(oid)
+    })
+    .await
+    .map_err(|e| format!("commit_patch task failed: {e}"))?
+}
+
+#[tauri::command]
+pub async fn commit_patch_and_files<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    summary: String,
+    description: String,
+    patch: String,
+    files: Vec<String>,
+    options: Option<CommitOptions>,
+) -> Result<String, String> {
+    use std::path::PathBuf;
+
+    info!("commit_patch_and_files called (patch bytes={}, files={})", patch.len(), files.len());
+    let repo = worktree_repo(&state)?;
+    ensure_writable(&repo)?;
+    let options = commit_options(options)?;
+    let repo = repo.clone();
+    let app = window.app_handle().clone();
+
+    let message = if description.trim().is_empty() { summary.clone() } else { format!("{summary}\n\n{description}") };
+
+    let job = JobId::next();
+    let refresh_app = window.app_handle().clone();
+    let gates = state.op_gates();
+    async_runtime::spawn_blocking(move || {
+        let _job = crate::logging::job_scope(job);
+        let _op = gates.begin(&refresh_app, repo.inner().workdir(), "commit", job)?;
+        let on = progress_bridge(app, job);
+        on(VcsEvent::Info("Staging selected hunks…"));
+
+        if !patch.trim().is_empty() {
+            crate::locks::retry_on_stale_lock(repo.inner().workdir(), || repo.inner().stage_patch(&patch)).map_err(|e| {
+                error!("stage_patch failed: {e}");
+                e.to_string()
+            })?;
+        }
+
+        let (name, email) = repo
+            .inner()
+            .get_identity()
+            .ok()
+            .flatten()
+            .or_else(|| {
+                let n = std::env::var("GIT_AUTHOR_NAME").ok();
+                let e = std::env::var("GIT_AUTHOR_EMAIL").ok();
+                match (n, e) { (Some(n), Some(e)) if !n.is_empty() && !e.is_empty() => Some((n, e)), _ => None }
+            })
+            .unwrap_or_else(|| ("OpenVCS".into(), "openvcs@example".into()));
+
+        on(VcsEvent::Info("Writing commit…"));
+        let oid = if files.is_empty() {
+            crate::telemetry::track("commit", repo.id().as_ref(), || {
+                crate::locks::retry_on_stale_lock(repo.inner().workdir(), || repo.inner().commit_index(&message, &name, &email, &options))
+            }).map_err(|e| e.to_string())?
+        } else {
+            let paths: Vec<PathBuf> = files.into_iter().map(PathBuf::from).collect();
+            crate::telemetry::track("commit", repo.id().as_ref(), || {
+                crate::locks::retry_on_stale_lock(repo.inner().workdir(), || repo.inner().commit(&message, &name, &email, &paths, &options))
+            }).map_err(|e| e.to_string())?
+        };
+        on(VcsEvent::Info("Commit complete"));
+        crate::refresh::request(&refresh_app, crate::refresh::Trigger::Action);
+        crate::commit_drafts::clear(repo.inner());
+        crate::plugins::dispatch(&refresh_app, PluginEvent::PostCommit, &[("commit", oid.clone())]);
+        Ok(oid)
+    })
+    .await
+    .map_err(|e| format!("commit_patch_and_files task failed: {e}"))?
+}
+
+/// Which of `ids` are already on a remote, and on which remote branches, so the UI can
+/// ask before rewriting them.
+#[tauri::command]
+pub async fn pushed_commits(state: State<'_, AppState>, ids: Vec<String>) -> Result<Vec<crate::history_guard::PushedCommit>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    async_runtime::spawn_blocking(move || crate::history_guard::pushed(repo.inner(), &ids))
+        .await
+        .map_err(|e| format!("pushed_commits task failed: {e}"))?
+}
+
+/// Squash consecutive commits of the current branch into one with `message` (history
+/// context menu). Commits already on a remote are refused unless `force`. Returns the new
+/// HEAD.
+#[tauri::command]
+pub async fn squash_commits<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    ids: Vec<String>,
+    message: String,
+    force: Option<bool>,
+) -> Result<String, String> {
+    let force = force.unwrap_or(false);
+    let message = message.trim().to_string();
+    if message.is_empty() { return Err("Commit message cannot be empty".into()); }
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    let app = window.app_handle().clone();
+    let gates = state.op_gates();
+
+    async_runtime::spawn_blocking(move || {
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "squash", JobId::next())?;
+        info!("squash_commits: {} commit(s) (force: {force})", ids.len());
+        crate::history_guard::check(vcs, "Squash", &ids, force)?;
+        let head = crate::telemetry::track("squash", repo.id().as_ref(), || {
+            crate::locks::retry_on_stale_lock(vcs.workdir(), || vcs.squash_commits(&ids, &message, force))
+        })
+        .map_err(|e| {
+            error!("squash_commits: {e}");
+            e.to_string()
+        })?;
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        Ok(head)
+    })
+    .await
+    .map_err(|e| format!("squash task failed: {e}"))?
+}
+
+/// Cherry-pick `commits` onto the current branch, in the given order. Stops on the first
+/// conflict and returns the operation; `None` when every commit was applied.
+#[tauri::command]
+pub async fn cherry_pick_commits<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    commits: Vec<String>,
+) -> Result<Option<crate::sequence::Operation>, String> {
+    run_sequence(window, state, "cherry-pick", move |vcs| {
+        crate::sequence::start(vcs, PickKind::CherryPick, &commits)
+    })
+    .await
+}
+
+/// Revert `commits` on the current branch, in the given order (newest first undoes them
+/// cleanly). Like [`cherry_pick_commits`], stops on the first conflict.
+#[tauri::command]
+pub async fn revert_commits<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    commits: Vec<String>,
+) -> Result<Option<crate::sequence::Operation>, String> {
+    run_sequence(window, state, "revert", move |vcs| crate::sequence::start(vcs, PickKind::Revert, &commits)).await
+}
+
+/// The cherry-pick or revert in progress in the current repository, if any.
+#[tauri::command]
+pub fn operation_progress(state: State<'_, AppState>) -> Result<Option<crate::sequence::Operation>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    Ok(crate::sequence::current(repo.inner().workdir()))
+}
+
+/// Commit the resolved conflicts of the operation in progress and apply the rest.
+#[tauri::command]
+pub async fn operation_continue<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+) -> Result<Option<crate::sequence::Operation>, String> {
+    run_sequence(window, state, "operation-continue", crate::sequence::continue_run).await
+}
+
+/// Skip the conflicted commit of the operation in progress and apply the rest.
+#[tauri::command]
+pub async fn operation_skip<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+) -> Result<Option<crate::sequence::Operation>, String> {
+    run_sequence(window, state, "operation-skip", crate::sequence::skip).await
+}
+
+/// Abandon the operation in progress and put the branch back where it started.
+#[tauri::command]
+pub async fn operation_abort<R: Runtime>(window: Window<R>, state: State<'_, AppState>) -> Result<(), String> {
+    run_sequence(window, state, "operation-abort", crate::sequence::abort).await
+}
+
+/// Run a step of a multi-commit operation under the repository's op gate.
+async fn run_sequence<R: Runtime, T: Send + 'static>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    name: &'static str,
+    f: impl FnOnce(&dyn openvcs_core::Vcs) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    let app = window.app_handle().clone();
+    let gates = state.op_gates();
+
+    async_runtime::spawn_blocking(move || {
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), name, JobId::next())?;
+        let res = f(vcs).inspect_err(|e| error!("{name}: {e}"));
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        res
+    })
+    .await
+    .map_err(|e| format!("{name} task failed: {e}"))?
+}
+
+/// Shelve local modifications (see `StashOptions`) and reset the working tree to HEAD.
+/// Returns the new entry, or `None` when there was nothing to stash.
+#[tauri::command]
+pub async fn stash_save<R: Runtime>(
+    window: Window<R>,
+    options: Option<openvcs_core::models::StashOptions>,
+) -> Result<Option<StashEntry>, String> {
+    off_main(&window, "stash-save", move |app, state| {
+        let options = options.unwrap_or_default();
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "stash-save", JobId::next())?;
+        info!("stash_save: untracked={} keep_index={}", options.include_untracked, options.keep_index);
+        let res = crate::telemetry::track("stash_save", repo.id().as_ref(), || vcs.stash_save(&options));
+        crate::refresh::request(app, crate::refresh::Trigger::Action);
+        res.map_err(|e| {
+            error!("stash_save: {e}");
+            e.to_string()
+        })
+    })
+    .await
+}
+
+/// The stash list, newest first, with the branch and time of each entry.
+#[tauri::command]
+pub fn stash_list(state: State<'_, AppState>) -> Result<Vec<StashEntry>, String> {
+    let repo = worktree_repo(&state)?;
+    repo.inner().stash_list().map_err(|e| e.to_string())
+}
+
+/// Stash `index` as per-file hunks, to preview it before applying (see
+/// `git_diff_file_structured`).
+#[tauri::command]
+pub fn stash_diff(state: State<'_, AppState>, index: usize) -> Result<Vec<FileDiff>, String> {
+    let repo = worktree_repo(&state)?;
+    let lines = repo.inner().stash_diff(index).map_err(|e| e.to_string())?;
+    let mut files = openvcs_core::diff::parse_unified(&lines, state.config().diff.ignore_whitespace);
+    mark_filters(repo.inner(), &mut files);
+    Ok(files)
+}
+
+/// Apply stash `index`, keeping it in the list; `restore_index` restores what was staged
+/// as staged. With `branch`, that branch is checked out first, so a stash made on one branch
+/// can be applied to another.
+#[tauri::command]
+pub async fn stash_apply<R: Runtime>(
+    window: Window<R>,
+    index: usize,
+    restore_index: Option<bool>,
+    branch: Option<String>,
+) -> Result<(), String> {
+    off_main(&window, "stash-apply", move |app, state| {
+        let restore_index = restore_index.unwrap_or(false);
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let branch = branch.as_deref().map(str::trim).filter(|b| !b.is_empty());
+        if let Some(b) = branch {
+            if vcs.current_branch().ok().flatten().as_deref() != Some(b) {
+                checkout_branch(app, state, b)?;
+            }
+        }
+        let _op = state.op_gates().begin(app, vcs.workdir(), "stash-apply", JobId::next())?;
+        info!("stash_apply: stash@{{{index}}} (index: {restore_index})");
+        let res = crate::telemetry::track("stash_apply", repo.id().as_ref(), || vcs.stash_apply(index, restore_index));
+        crate::refresh::request(app, crate::refresh::Trigger::Action);
+        res.map_err(|e| {
+            error!("stash_apply: stash@{{{index}}}: {e}");
+            e.to_string()
+        })
+    })
+    .await
+}
+
+/// Apply stash `index` and drop it from the list; on conflict it is kept.
+#[tauri::command]
+pub async fn stash_pop<R: Runtime>(
+    window: Window<R>,
+    index: usize,
+    restore_index: Option<bool>,
+) -> Result<(), String> {
+    off_main(&window, "stash-pop", move |app, state| {
+        let restore_index = restore_index.unwrap_or(false);
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "stash-pop", JobId::next())?;
+        info!("stash_pop: stash@{{{index}}} (index: {restore_index})");
+        let res = crate::telemetry::track("stash_pop", repo.id().as_ref(), || vcs.stash_pop(index, restore_index));
+        crate::refresh::request(app, crate::refresh::Trigger::Action);
+        res.map_err(|e| {
+            error!("stash_pop: stash@{{{index}}}: {e}");
+            e.to_string()
+        })
+    })
+    .await
+}
+
+/// Delete stash `index` without applying it.
+#[tauri::command]
+pub async fn stash_drop<R: Runtime>(window: Window<R>, index: usize) -> Result<(), String> {
+    off_main(&window, "stash-drop", move |app, state| {
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "stash-drop", JobId::next())?;
+        info!("stash_drop: stash@{{{index}}}");
+        vcs.stash_drop(index).map_err(|e| {
+            error!("stash_drop: stash@{{{index}}}: {e}");
+            e.to_string()
+        })
+    })
+    .await
+}
+
+/// Recover `paths` from stash `index` into the working tree without applying the rest of
+/// it (useful when the whole stash conflicts). The stash stays in the list.
+#[tauri::command]
+pub async fn stash_restore_paths<R: Runtime>(
+    window: Window<R>,
+    index: usize,
+    paths: Vec<String>,
+) -> Result<(), String> {
+    off_main(&window, "stash-restore", move |app, state| {
+        if paths.is_empty() {
+            return Err("No files selected".into());
+        }
+        let repo = worktree_repo(state)?;
+        ensure_writable(&repo)?;
+        let vcs = repo.inner();
+        let paths: Vec<std::path::PathBuf> = paths.iter().map(std::path::PathBuf::from).collect();
+        let _op = state.op_gates().begin(app, vcs.workdir(), "stash-restore", JobId::next())?;
+        info!("stash_restore_paths: {} path(s) from stash@{{{index}}}", paths.len());
+        let res = vcs.stash_restore_paths(index, &paths);
+        crate::refresh::request(app, crate::refresh::Trigger::Action);
+        res.map_err(|e| {
+            error!("stash_restore_paths: stash@{{{index}}}: {e}");
+            e.to_string()
+        })
+    })
+    .await
+}
+#[tauri::command]
+pub async fn git_fetch<R: Runtime>(window: Window<R>, state: State<'_, AppState>) -> Result<FetchSummary, String> {
+    info!("git_fetch called");
+
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    let prune = state.config().git.prune_on_fetch;
+    let app = window.app_handle().clone();
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
+
+    async_runtime::spawn_blocking(move || -> Result<FetchSummary, String> {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "fetch", job)?;
+        let meter = crate::net_stats::Meter::start("fetch", "origin", Some(job));
+        let on = Some(meter.watch(progress_bridge(app.clone(), job)));
+
+        info!("Fetching from origin (prune: {prune})");
+
+        let res = crate::telemetry::track("fetch", repo.id().as_ref(), || vcs.fetch_all("origin", prune, on));
+        meter.finish(&res);
+        let summary = res.map_err(|e| {
+            error!("Fetch from origin failed: {e}");
+            report_vcs_error(&app, job, &e)
         })?;
-        Ok(oid)
+
+        info!(
+            "Fetch completed: {} new, {} updated, {} pruned, {} diverged",
+            summary.new.len(), summary.updated.len(), summary.pruned.len(), summary.diverged.len()
+        );
+        let _ = app.emit("repo:fetch-summary", FetchSummaryEvent { job, summary: summary.clone() });
+        let _ = app.emit(
+            "git-progress",
+            ProgressPayload::new(fetch_message(&summary), job)
+        );
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        Ok(summary)
     })
     .await
-    .map_err(|e| format!("commit_patch task failed: {e}"))?
+    .map_err(|e| format!("fetch task failed: {e}"))?
 }
 
+/// Fetch the history a shallow clone is missing from `remote` (default `origin`).
 #[tauri::command]
-pub async fn commit_patch_and_files<R: Runtime>(
+pub async fn unshallow_repo<R: Runtime>(
     window: Window<R>,
     state: State<'_, AppState>,
-    summary: String,
-    description: String,
-    patch: String,
-    files: Vec<String>,
-) -> Result<String, String> {
-    use std::path::PathBuf;
-
-    info!("commit_patch_and_files called (patch bytes={}, files={})", patch.len(), files.len());
+    remote: Option<String>,
+) -> Result<(), String> {
     let repo = state
         .current_repo()
         .ok_or_else(|| "No repository selected".to_string())?;
-    let repo = repo.clone();
+    ensure_writable(&repo)?;
+    let remote = remote.unwrap_or_else(|| "origin".into());
     let app = window.app_handle().clone();
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
+    let history = state.history();
+    let summaries = state.summaries();
 
-    let message = if description.trim().is_empty() { summary.clone() } else { format!("{summary}\n\n{description}") };
-
-    async_runtime::spawn_blocking(move || {
-        let on = progress_bridge(app);
-        on(VcsEvent::Info("Staging selected hunks…"));
-
-        if !patch.trim().is_empty() {
-            repo.inner().stage_patch(&patch).map_err(|e| {
-                error!("stage_patch failed: {e}");
-                e.to_string()
-            })?;
-        }
-
-        let (name, email) = repo
-            .inner()
-            .get_identity()
-            .ok()
-            .flatten()
-            .or_else(|| {
-                let n = std::env::var("GIT_AUTHOR_NAME").ok();
-                let e = std::env::var("GIT_AUTHOR_EMAIL").ok();
-                match (n, e) { (Some(n), Some(e)) if !n.is_empty() && !e.is_empty() => Some((n, e)), _ => None }
-            })
-            .unwrap_or_else(|| ("OpenVCS".into(), "openvcs@example".into()));
+    async_runtime::spawn_blocking(move || -> Result<(), String> {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "fetch", job)?;
+        let meter = crate::net_stats::Meter::start("unshallow", &remote, Some(job));
+        let on = Some(meter.watch(progress_bridge(app.clone(), job)));
+
+        info!("Unshallowing from {remote}");
+        let res = vcs.unshallow(&remote, on);
+        meter.finish(&res);
+        res.map_err(|e| {
+            error!("Unshallow from {remote} failed: {e}");
+            report_vcs_error(&app, job, &e)
+        })?;
 
-        on(VcsEvent::Info("Writing commit…"));
-        let oid = if files.is_empty() {
-            repo.inner().commit_index(&message, &name, &email).map_err(|e| e.to_string())?
-        } else {
-            let paths: Vec<PathBuf> = files.into_iter().map(PathBuf::from).collect();
-            repo.inner().commit(&message, &name, &email, &paths).map_err(|e| e.to_string())?
-        };
-        on(VcsEvent::Info("Commit complete"));
-        Ok(oid)
+        history.invalidate(vcs.workdir());
+        summaries.invalidate(vcs.workdir());
+        let _ = app.emit("git-progress", ProgressPayload::new("Full history fetched".into(), job));
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        Ok(())
     })
     .await
-    .map_err(|e| format!("commit_patch_and_files task failed: {e}"))?
+    .map_err(|e| format!("unshallow task failed: {e}"))?
+}
+
+#[derive(Clone, Serialize)]
+struct FetchSummaryEvent {
+    job: JobId,
+    summary: FetchSummary,
+}
+
+/// One-line description of a fetch for the status bar.
+fn fetch_message(s: &FetchSummary) -> String {
+    if s.is_empty() && s.diverged.is_empty() {
+        return format!("Fetch complete ({} is up to date)", s.remote);
+    }
+    let mut parts = Vec::new();
+    for (n, what) in [(s.new.len(), "new"), (s.updated.len(), "updated"), (s.pruned.len(), "pruned")] {
+        if n > 0 {
+            parts.push(format!("{n} {what}"));
+        }
+    }
+    if !s.diverged.is_empty() {
+        let names: Vec<&str> = s.diverged.iter().map(|d| d.branch.as_str()).collect();
+        parts.push(format!("diverged: {}", names.join(", ")));
+    }
+    format!("Fetch complete ({}: {})", s.remote, parts.join("; "))
 }
+
+/// Mirror the branches/tags matching `refs` (default: all) from remote `source` to `target`.
 #[tauri::command]
-pub fn git_fetch<R: Runtime>(window: Window<R>, state: State<'_, AppState>) -> Result<(), String> {
-    info!("git_fetch called");
+pub async fn sync_remotes<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    source: String,
+    target: String,
+    refs: Option<Vec<String>>,
+) -> Result<crate::mirror::SyncReport, String> {
+    info!("sync_remotes called ({source} → {target}, refs={refs:?})");
+    if source == target {
+        return Err("Source and target must be different remotes".into());
+    }
+    let patterns = crate::mirror::patterns(&refs.unwrap_or_default())?;
 
     let repo = state
         .current_repo()
         .ok_or_else(|| "No repository selected".to_string())?;
-    let vcs = repo.inner();
-
+    ensure_writable(&repo)?;
+    let desc = get_backend(repo.id()).ok_or_else(|| format!("Backend not found: {}", repo.id()))?;
     let app = window.app_handle().clone();
-    let on = Some(progress_bridge(app));
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
 
-    let current = vcs
-        .current_branch()
+    async_runtime::spawn_blocking(move || {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "sync", job)?;
+        let on = Some(progress_bridge(app.clone(), job));
+
+        let report = crate::telemetry::track("sync_remotes", repo.id().as_ref(), || {
+            crate::mirror::sync(vcs, desc.remote_refs, &source, &target, &patterns, on)
+        })
         .map_err(|e| {
-            error!("Failed to get current branch: {e}");
-            e.to_string()
-        })?
-        .ok_or_else(|| {
-            warn!("Detached HEAD detected, cannot determine upstream branch");
-            "Detached HEAD; cannot determine upstream".to_string()
+            error!("sync_remotes: {source} → {target}: {e}");
+            report_vcs_error(&app, job, &e)
         })?;
 
-    info!("Fetching branch '{current}' from origin");
+        let _ = app.emit(
+            "git-progress",
+            ProgressPayload::new(format!("Sync complete ({} ref(s) pushed to {target})", report.pushed.len()), job),
+        );
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        Ok(report)
+    })
+    .await
+    .map_err(|e| format!("sync task failed: {e}"))?
+}
 
-    vcs.fetch("origin", &current, on).map_err(|e| {
-        error!("Fetch failed for branch '{current}': {e}");
-        e.to_string()
-    })?;
+/// Scheduled remote syncs of the current repository.
+#[tauri::command]
+pub fn list_remote_syncs(state: State<'_, AppState>) -> Result<Vec<crate::mirror::SyncSchedule>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    let path = repo.inner().workdir().to_string_lossy().to_string();
+    Ok(crate::mirror::load_schedules().into_iter().filter(|s| s.path == path).collect())
+}
 
-    info!("Fetch completed successfully for branch '{current}'");
-    let _ = window.app_handle().emit(
-        "git-progress",
-        ProgressPayload { message: format!("Fetch complete ({current})") }
-    );
-    Ok(())
+/// Sync `source` to `target` every `interval_minutes` (0 removes the schedule).
+#[tauri::command]
+pub fn schedule_remote_sync(
+    state: State<'_, AppState>,
+    source: String,
+    target: String,
+    refs: Option<Vec<String>>,
+    interval_minutes: u32,
+) -> Result<Vec<crate::mirror::SyncSchedule>, String> {
+    if source == target {
+        return Err("Source and target must be different remotes".into());
+    }
+    let refs = refs.unwrap_or_default();
+    crate::mirror::patterns(&refs)?;
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    info!("schedule_remote_sync: {source} → {target} every {interval_minutes} min");
+    crate::mirror::set_schedule(crate::mirror::SyncSchedule {
+        path: repo.inner().workdir().to_string_lossy().to_string(),
+        backend: repo.id().as_ref().to_owned(),
+        source,
+        target,
+        refs,
+        interval_minutes,
+        last_run: None,
+        last_error: None,
+    })
 }
 
 #[tauri::command]
-pub fn git_pull<R: Runtime>(window: Window<R>, state: State<'_, AppState>) -> Result<(), String> {
+pub async fn git_pull<R: Runtime>(window: Window<R>, state: State<'_, AppState>) -> Result<(), String> {
     info!("git_pull called");
 
-    let repo = state
-        .current_repo()
-        .ok_or_else(|| "No repository selected".to_string())?;
-    let vcs = repo.inner();
-
+    let repo = worktree_repo(&state)?;
+    ensure_writable(&repo)?;
+    let autostash = state.config().git.autostash_on_pull;
     let app = window.app_handle().clone();
-    let on = Some(progress_bridge(app));
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
 
-    let current = vcs
-        .current_branch()
-        .map_err(|e| {
-            error!("Failed to get current branch: {e}");
-            e.to_string()
-        })?
-        .ok_or_else(|| {
-            warn!("Detached HEAD detected, cannot determine upstream branch for pull");
-            "Detached HEAD; cannot determine upstream".to_string()
+    async_runtime::spawn_blocking(move || -> Result<(), String> {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "pull", job)?;
+        let meter = crate::net_stats::Meter::start("pull", "origin", Some(job));
+        let on = Some(meter.watch(progress_bridge(app.clone(), job)));
+
+        let current = vcs
+            .current_branch()
+            .map_err(|e| {
+                error!("Failed to get current branch: {e}");
+                e.to_string()
+            })?
+            .ok_or_else(|| {
+                warn!("Detached HEAD detected, cannot determine upstream branch for pull");
+                "Detached HEAD; cannot determine upstream".to_string()
+            })?;
+
+        info!("Fast-forward pulling branch '{current}' from origin (autostash: {autostash})");
+
+        let res = crate::telemetry::track("pull", repo.id().as_ref(), || {
+            if autostash {
+                vcs.pull_ff_only_autostash("origin", &current, on)
+            } else {
+                vcs.pull_ff_only("origin", &current, on)
+            }
+        });
+        meter.finish(&res);
+        res.map_err(|e| {
+            error!("Pull (ff-only) failed for branch '{current}': {e}");
+            report_vcs_error(&app, job, &e)
         })?;
 
-    info!("Fast-forward pulling branch '{current}' from origin");
+        info!("Pull (ff-only) completed successfully for branch '{current}'");
+        let _ = app.emit(
+            "git-progress",
+            ProgressPayload::new(format!("Pull complete ({current})"), job)
+        );
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        crate::plugins::dispatch(&app, PluginEvent::PostPull, &[("branch", current)]);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("pull task failed: {e}"))?
+}
 
-    vcs.pull_ff_only("origin", &current, on).map_err(|e| {
-        error!("Pull (ff-only) failed for branch '{current}': {e}");
-        e.to_string()
-    })?;
+/// Transfer statistics of the last `limit` (default all kept) fetches, pulls, pushes and
+/// clones, newest first.
+#[tauri::command]
+pub fn network_stats(limit: Option<usize>) -> Vec<crate::net_stats::TransferStats> {
+    crate::net_stats::recent(limit.unwrap_or(crate::net_stats::KEEP))
+}
 
-    info!("Pull (ff-only) completed successfully for branch '{current}'");
-    let _ = window.app_handle().emit(
-        "git-progress",
-        ProgressPayload { message: format!("Pull complete ({current})") }
-    );
-    Ok(())
+/// The operation holding the current repository (as last sent in `repo:busy`), or `None`
+/// when it is idle or no repository is open.
+#[tauri::command]
+pub fn get_repo_busy(state: State<'_, AppState>) -> Option<crate::ops::Busy> {
+    let repo = state.current_repo()?;
+    state.op_gates().busy(repo.inner().workdir())
 }
 
+/// Cancel a running fetch/pull/push by job id. Returns `false` if it already finished.
+#[tauri::command]
+pub fn cancel_job(state: State<'_, AppState>, job: JobId) -> bool {
+    state.jobs().cancel(job)
+}
+
+/// Outcome of pushing to one remote.
+#[derive(Debug, Clone, Serialize)]
+pub struct RemotePushResult {
+    pub remote: String,
+    /// Why the push failed; `None` on success.
+    pub error: Option<String>,
+}
+
+/// Push the current branch to `origin`, or to every configured remote with `all_remotes`.
+/// `options` are passed as `--push-option`s (e.g. GitLab's `ci.skip`). Per-ref results
+/// arrive as push-status progress events. The repository's pre-push check runs first;
+/// `run_pre_push` is the user's answer when `git.allow_hooks` is `ask`.
 #[tauri::command]
 pub async fn git_push<R: Runtime>(
     window: Window<R>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    info!("git_push called");
+    options: Option<Vec<String>>,
+    all_remotes: Option<bool>,
+    run_pre_push: Option<bool>,
+) -> Result<Vec<RemotePushResult>, String> {
+    let options = options.unwrap_or_default();
+    let all_remotes = all_remotes.unwrap_or(false);
+    info!("git_push called (options: {options:?}, all_remotes: {all_remotes})");
+    let hook_policy = state.config().git.allow_hooks;
 
     let repo = state
         .current_repo()
         .ok_or_else(|| "No repository selected".to_string())?
         .clone();
+    ensure_writable(&repo)?;
 
     let app_for_worker = window.app_handle().clone();
+    let app_for_error  = window.app_handle().clone();
     let app_for_final  = window.app_handle().clone();
 
-    async_runtime::spawn_blocking(move || -> Result<(), String> {
-        let on = Some(progress_bridge(app_for_worker));
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
+    let results = async_runtime::spawn_blocking(move || -> Result<Vec<RemotePushResult>, String> {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let _op = gates.begin(&app_for_error, repo.inner().workdir(), "push", job)?;
+        let bridge = progress_bridge(app_for_worker, job);
 
         let current = repo.inner()
             .current_branch()
@@ -891,16 +2792,40 @@ pub async fn git_push<R: Runtime>(
                 warn!("Detached HEAD, cannot push");
                 "detached HEAD".to_string()
             })?;
+        if let Ok(HeadState::Unborn { .. }) = repo.inner().head_state() {
+            return Err("No commits yet; create your first commit before pushing".to_string());
+        }
 
-        let refspec = format!("refs/heads/{0}:refs/heads/{0}", current);
-        info!("Pushing branch '{current}' with refspec '{refspec}'");
+        let remotes: Vec<String> = if all_remotes {
+            repo.inner().list_remotes().map_err(|e| e.to_string())?.into_iter().map(|r| r.name).collect()
+        } else {
+            vec!["origin".into()]
+        };
+        if remotes.is_empty() {
+            return Err("No remotes configured".into());
+        }
+        crate::pre_push::verify(repo.inner(), hook_policy, run_pre_push, &bridge)?;
 
-        repo.inner()
-            .push("origin", &refspec, on)
-            .map_err(|e| {
-                error!("Push failed for branch '{current}': {e}");
-                e.to_string()
-            })
+        let refspec = format!("refs/heads/{0}:refs/heads/{0}", current);
+        let mut results = Vec::with_capacity(remotes.len());
+        for remote in remotes {
+            if openvcs_core::cancel::is_cancelled() {
+                break;
+            }
+            info!("Pushing branch '{current}' to '{remote}' with refspec '{refspec}'");
+            let meter = crate::net_stats::Meter::start("push", &remote, Some(job));
+            let on = Some(meter.watch(bridge.clone()));
+            let res = crate::telemetry::track("push", repo.id().as_ref(), || {
+                repo.inner().push_with_options(&remote, &refspec, &options, on)
+            });
+            meter.finish(&res);
+            let error = res.err().map(|e| {
+                error!("Push of '{current}' to '{remote}' failed: {e}");
+                report_vcs_error(&app_for_error, job, &e)
+            });
+            results.push(RemotePushResult { remote, error });
+        }
+        Ok(results)
     })
         .await
         .map_err(|e| {
@@ -908,12 +2833,204 @@ pub async fn git_push<R: Runtime>(
             e.to_string()
         })??;
 
+    crate::refresh::request(&app_for_final, crate::refresh::Trigger::Action);
+    let failed: Vec<String> = results
+        .iter()
+        .filter_map(|r| r.error.as_ref().map(|e| format!("{}: {e}", r.remote)))
+        .collect();
+    if !failed.is_empty() {
+        return Err(failed.join("; "));
+    }
+
     let _ = app_for_final.emit(
         "git-progress",
-        ProgressPayload { message: "Push complete".into() }
+        ProgressPayload::new("Push complete".into(), job)
     );
+    info!("{job}: push completed successfully.");
+    Ok(results)
+}
+
+#[derive(Clone, Copy)]
+enum RemoteRef { Branch, Tag }
+
+/// Delete a branch or tag on `remote`. Unless `advanced.confirm_remote_delete` is off, the
+/// caller has to pass `confirmed` after asking the user.
+async fn delete_remote_ref<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    remote: String,
+    name: String,
+    kind: RemoteRef,
+    confirmed: bool,
+) -> Result<(), String> {
+    let (remote, name) = (remote.trim().to_string(), name.trim().to_string());
+    let what = match kind { RemoteRef::Branch => "branch", RemoteRef::Tag => "tag" };
+    if remote.is_empty() || name.is_empty() {
+        return Err(format!("Remote and {what} name cannot be empty"));
+    }
+    if state.config().advanced.confirm_remote_delete && !confirmed {
+        return Err(format!("Deleting {what} '{name}' on '{remote}' needs confirmation"));
+    }
+
+    let repo = state
+        .current_repo()
+        .ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    let app = window.app_handle().clone();
+    let job = JobId::next();
+    let gates = state.op_gates();
+    let jobs = state.jobs();
+
+    async_runtime::spawn_blocking(move || {
+        let _job = crate::logging::job_scope(job);
+        let _cancel = jobs.enter(job);
+        let vcs = repo.inner();
+        let _op = gates.begin(&app, vcs.workdir(), "delete-remote-ref", job)?;
+        let on = Some(progress_bridge(app.clone(), job));
+
+        info!("Deleting {what} '{name}' on '{remote}'");
+        crate::telemetry::track("delete_remote_ref", repo.id().as_ref(), || match kind {
+            RemoteRef::Branch => vcs.delete_remote_branch(&remote, &name, on),
+            RemoteRef::Tag => vcs.delete_remote_tag(&remote, &name, on),
+        })
+        .map_err(|e| {
+            error!("Deleting {what} '{name}' on '{remote}' failed: {e}");
+            report_vcs_error(&app, job, &e)
+        })?;
+
+        let _ = app.emit(
+            "git-progress",
+            ProgressPayload::new(format!("Deleted {what} '{name}' on '{remote}'"), job),
+        );
+        crate::refresh::request(&app, crate::refresh::Trigger::Action);
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("delete task failed: {e}"))?
+}
+
+#[tauri::command]
+pub async fn delete_remote_branch<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    remote: String,
+    branch: String,
+    confirmed: Option<bool>,
+) -> Result<(), String> {
+    delete_remote_ref(window, state, remote, branch, RemoteRef::Branch, confirmed.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn delete_remote_tag<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    remote: String,
+    tag: String,
+    confirmed: Option<bool>,
+) -> Result<(), String> {
+    delete_remote_ref(window, state, remote, tag, RemoteRef::Tag, confirmed.unwrap_or(false)).await
+}
+
+/// Plugins found in the config dir's `plugins/` at the last (re)load.
+#[tauri::command]
+pub fn list_plugins(state: State<'_, AppState>) -> Vec<crate::plugins::PluginInfo> {
+    state.plugins().list(&state.config())
+}
+
+/// Rescan the plugins directory (after adding or editing a plugin) and ask for the
+/// permissions of new ones.
+#[tauri::command]
+pub fn reload_plugins<R: Runtime>(window: Window<R>, state: State<'_, AppState>) -> Vec<crate::plugins::PluginInfo> {
+    let list = state.plugins().reload(&state.config());
+    crate::plugins::request_permissions(window.app_handle(), list.clone());
+    list
+}
+
+/// Answer a plugin's permission request: grant everything it declares, or revoke it.
+#[tauri::command]
+pub fn set_plugin_granted(state: State<'_, AppState>, id: String, granted: bool) -> Result<(), String> {
+    info!("set_plugin_granted: {id} -> {granted}");
+    crate::plugins::set_granted(&state, &id, granted)
+}
+
+/// Hook templates that can be installed.
+#[tauri::command]
+pub fn list_hook_templates() -> Vec<crate::hooks::HookTemplateInfo> {
+    crate::hooks::templates()
+}
+
+/// Hooks in the current repository's hooks directory, with where they came from.
+#[tauri::command]
+pub fn list_hooks(state: State<'_, AppState>) -> Result<Vec<crate::hooks::HookInfo>, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    crate::hooks::list(repo.inner())
+}
+
+/// Install or update a hook from a template. With `git.allow_hooks = ask` the UI passes the
+/// user's answer as `confirmed`.
+#[tauri::command]
+pub fn install_hook(state: State<'_, AppState>, template: String, confirmed: Option<bool>) -> Result<crate::hooks::HookInfo, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    let policy = state.with_config(|c| c.git.allow_hooks);
+    crate::hooks::install(repo.inner(), &template, policy, confirmed)
+}
+
+/// Remove a template-installed hook, restoring the hook it replaced.
+#[tauri::command]
+pub fn remove_hook(state: State<'_, AppState>, name: String) -> Result<(), String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    ensure_writable(&repo)?;
+    crate::hooks::remove(repo.inner(), &name)
+}
+
+/// The saved commit message draft for the current branch and the repository's commit
+/// template, to prefill the commit box.
+#[tauri::command]
+pub fn get_commit_draft(state: State<'_, AppState>) -> Result<crate::commit_drafts::DraftState, String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    Ok(crate::commit_drafts::state(repo.inner()))
+}
+
+/// Remember the commit message being written on the current branch; an empty message
+/// discards the draft.
+#[tauri::command]
+pub fn save_commit_draft(state: State<'_, AppState>, summary: String, description: String) -> Result<(), String> {
+    let repo = state.current_repo().ok_or_else(|| "No repository selected".to_string())?;
+    crate::commit_drafts::save_draft(repo.inner(), &summary, &description)
+}
+
+/// Every menu action with whether it can run against the current repository, for the
+/// command palette.
+#[tauri::command]
+pub fn list_actions<R: Runtime>(window: Window<R>) -> Vec<crate::actions::Action> {
+    crate::actions::list(&crate::menus::context(window.app_handle()))
+}
+
+/// Run a registry action as if its menu item was clicked.
+#[tauri::command]
+pub fn run_action<R: Runtime>(window: Window<R>, id: String) -> Result<(), String> {
+    let def = crate::actions::def(&id).ok_or_else(|| format!("Unknown action: {id}"))?;
+    if let Some(reason) = crate::menus::context(window.app_handle()).blocker(def) {
+        return Err(format!("{}: {reason}", def.title));
+    }
+    info!("run_action: {id}");
+    crate::menus::dispatch(window.app_handle(), def.id);
+    Ok(())
+}
 
-    info!("Push completed successfully.");
+/// Add or remove "Open in OpenVCS" in the file manager and remember the choice in
+/// `general.shell_integration`.
+#[tauri::command]
+pub async fn register_shell_integration(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    async_runtime::spawn_blocking(move || crate::shell_integration::set_enabled(enabled))
+        .await
+        .map_err(|e| format!("register_shell_integration task failed: {e}"))??;
+    let mut cfg = state.config();
+    if cfg.general.shell_integration != enabled {
+        cfg.general.shell_integration = enabled;
+        state.set_config(cfg)?;
+    }
     Ok(())
 }
 
@@ -934,7 +3051,7 @@ pub fn list_backends_cmd() -> Vec<(String, String)> {
 }
 
 #[tauri::command]
-pub fn set_backend_cmd(state: State<'_, AppState>, backend_id: BackendId) -> Result<(), String> {
+pub fn set_backend_cmd<R: Runtime>(window: Window<R>, state: State<'_, AppState>, backend_id: BackendId) -> Result<(), String> {
     info!("set_backend_cmd: requested backend = {}", backend_id);
 
     let desc = match get_backend(&backend_id) {
@@ -958,6 +3075,7 @@ pub fn set_backend_cmd(state: State<'_, AppState>, backend_id: BackendId) -> Res
             Ok(handle) => {
                 let new_repo = Arc::new(Repo::new(handle));
                 state.set_current_repo(new_repo);
+                crate::menus::sync(window.app_handle(), None);
                 info!(
                     "set_backend_cmd: repo reopened with backend `{}` (path={})",
                     backend_id,
@@ -988,20 +3106,42 @@ pub fn get_global_settings(state: State<'_, AppState>) -> Result<AppConfig, Stri
 }
 
 #[tauri::command]
-pub fn set_global_settings(
+pub fn set_global_settings<R: Runtime>(
+    window: Window<R>,
     state: State<'_, AppState>,
     cfg: AppConfig,
 ) -> Result<(), String> {
-    state.set_config(cfg)
+    let list_changed = cfg.ux.status_options() != state.config().ux.status_options();
+    let git_changed = cfg.git.executable_path.trim() != state.config().git.executable_path.trim();
+    if git_changed {
+        crate::git_exe::resolve(&cfg.git.executable_path)?;
+    }
+    state.set_config(cfg)?;
+    if git_changed {
+        let app = window.app_handle().clone();
+        async_runtime::spawn_blocking(move || {
+            if let Some(msg) = crate::git_exe::warning(&crate::git_exe::check()) {
+                let _ = app.emit("ui:notify", msg);
+            }
+        });
+    }
+    // Showing ignored files or collapsing new folders changes the Changes list now
+    if list_changed {
+        crate::refresh::request(window.app_handle(), crate::refresh::Trigger::Manual);
+    }
+    Ok(())
 }
 
+/// Config keys whose origin `get_repo_settings` reports.
+const CONFIG_SOURCE_KEYS: &[&str] = &["user.name", "user.email", "core.hooksPath"];
+
 #[tauri::command]
 pub fn get_repo_settings(state: State<'_, AppState>) -> Result<RepoConfig, String> {
     let mut cfg = state.repo_config();
     // If a repo is open, enrich settings from actual Git config
     if let Some(repo) = state.current_repo() {
         let vcs = repo.inner();
-        // identity (repository-local)
+        // identity as git would use it, and which config file each part comes from
         match vcs.get_identity() {
             Ok(Some((name, email))) => {
                 cfg.user_name = Some(name);
@@ -1012,16 +3152,30 @@ pub fn get_repo_settings(state: State<'_, AppState>) -> Result<RepoConfig, Strin
                 warn!("get_repo_settings: get_identity failed: {e}");
             }
         }
+        for key in CONFIG_SOURCE_KEYS {
+            match vcs.config_value(key) {
+                Ok(Some(v)) => { cfg.sources.insert(key.to_string(), v); }
+                Ok(None) => {}
+                Err(e) => debug!("get_repo_settings: {key} origin unavailable: {e}"),
+            }
+        }
 
         // remotes: capture 'origin' URL if present
         match vcs.list_remotes() {
             Ok(list) => {
-                if let Some((_, url)) = list.into_iter().find(|(n, _)| n == "origin") {
-                    cfg.origin_url = Some(url);
+                if let Some(origin) = list.into_iter().find(|r| r.name == "origin") {
+                    cfg.origin_url = Some(origin.fetch_url);
                 }
             }
             Err(e) => warn!("get_repo_settings: list_remotes failed: {e}"),
         }
+
+        match vcs.branch_config(BRANCH_NOTES_KEY) {
+            Ok(notes) => cfg.branch_notes = notes,
+            Err(e) => debug!("get_repo_settings: branch notes unavailable: {e}"),
+        }
+
+        cfg.pre_push_command = crate::pre_push::command(vcs);
     }
 
     Ok(cfg)
@@ -1037,10 +3191,15 @@ pub fn set_repo_settings(
 
     // Apply to Git if a repo is open
     if let Some(repo) = state.current_repo() {
+        ensure_writable(&repo)?;
         let vcs = repo.inner();
-        // Identity: set when both present
+        // Identity: set locally when both present and different from what git resolves
+        // already, so one inherited from global config or an includeIf is not pinned here
         if let (Some(name), Some(email)) = (cfg.user_name.as_deref(), cfg.user_email.as_deref()) {
-            vcs.set_identity_local(name, email).map_err(|e| e.to_string())?;
+            let effective = vcs.get_identity().ok().flatten();
+            if effective.as_ref().is_none_or(|(n, e)| n != name || e != email) {
+                vcs.set_identity_local(name, email).map_err(|e| e.to_string())?;
+            }
         }
         // Origin remote URL
         if let Some(url) = cfg.origin_url.as_deref() {
@@ -1048,31 +3207,38 @@ pub fn set_repo_settings(
                 vcs.ensure_remote("origin", url).map_err(|e| e.to_string())?;
             }
         }
+        let pre_push = cfg.pre_push_command.as_deref().map(str::trim).filter(|c| !c.is_empty());
+        vcs.set_local_config(PRE_PUSH_COMMAND_KEY, pre_push).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
+/// Switch the update channel at runtime and immediately re-check against the new feed.
+/// Returns whether an update is available on the selected channel.
+#[tauri::command]
+pub async fn set_update_channel<R: Runtime>(
+    window: Window<R>,
+    state: State<'_, AppState>,
+    channel: UpdateChannel,
+) -> Result<bool, String> {
+    state.edit_config(|c| c.general.update_channel = channel)?;
+    info!("updater: switched to {:?} channel", channel);
+    crate::updater::check_and_announce(window.app_handle(), "channel").await
+}
+
+/// Details of the update available on the configured channel, or `None` if up to date.
+#[tauri::command]
+pub async fn get_update_info<R: Runtime>(window: Window<R>) -> Result<Option<crate::updater::UpdateInfo>, String> {
+    crate::updater::update_info(window.app_handle()).await
+}
+
+/// Download the available update now (emitting `update:progress`) and install it on quit.
+#[tauri::command]
+pub async fn updater_download_for_quit<R: Runtime>(window: Window<R>) -> Result<Option<String>, String> {
+    crate::updater::download_for_quit(window.app_handle()).await
+}
+
 #[tauri::command]
 pub async fn updater_install_now<R: Runtime>(window: Window<R>) -> Result<(), String> {
-    let app = window.app_handle();
-    let updater = app.updater().map_err(|e| e.to_string())?;
-    match updater.check().await.map_err(|e| e.to_string())? {
-        Some(update) => {
-            let app2 = app.clone();
-            update
-                .download_and_install(
-                    |received, total| {
-                        let payload = serde_json::json!({ "kind": "progress", "received": received, "total": total });
-                        let _ = app2.emit("update:progress", payload);
-                    },
-                    || {
-                        let _ = app2.emit("update:progress", serde_json::json!({ "kind": "downloaded" }));
-                    },
-                )
-                .await
-                .map_err(|e| e.to_string())?;
-            Ok(())
-        }
-        None => Ok(()),
-    }
+    crate::updater::install_now(window.app_handle()).await
 }
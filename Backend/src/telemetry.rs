@@ -0,0 +1,210 @@
+//! Opt-in, anonymized usage metrics (`general.telemetry`).
+//!
+//! Only aggregate operation data is collected: operation name, backend id, outcome
+//! category (`ok` or a `VcsError::kind()`), count and durations. Never paths, URLs,
+//! branch names, commit data or error messages. Records are aggregated in memory,
+//! persisted to the app data dir, and sent in batches when an endpoint is compiled in
+//! (`OPENVCS_TELEMETRY_URL`); without one, nothing leaves the machine.
+//! `get_telemetry_preview` shows the exact payload that would be sent next.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Collection endpoint, fixed at build time. `None` keeps metrics local-only.
+const ENDPOINT: Option<&str> = option_env!("OPENVCS_TELEMETRY_URL");
+/// How often a batch is sent (when enabled and an endpoint exists).
+const FLUSH_EVERY: Duration = Duration::from_secs(15 * 60);
+/// Upper bound on distinct aggregate rows kept while unsent.
+const MAX_ROWS: usize = 500;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static QUEUE: OnceLock<Mutex<Queue>> = OnceLock::new();
+
+/// One aggregated metric row.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpStats {
+    pub op: String,
+    pub backend: String,
+    /// `ok` or a `VcsError::kind()` category.
+    pub outcome: String,
+    pub count: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+}
+
+/// The batch as it would be sent.
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetryBatch {
+    pub schema: u32,
+    /// Random per-install id; not derived from any user or machine data.
+    pub install_id: String,
+    pub app_version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub ops: Vec<OpStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetryPreview {
+    pub enabled: bool,
+    /// Where batches go; `None` means metrics never leave this machine.
+    pub endpoint: Option<&'static str>,
+    pub next_batch: TelemetryBatch,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Queue {
+    install_id: String,
+    period_start: i64,
+    /// Keyed by "op|backend|outcome".
+    rows: BTreeMap<String, OpStats>,
+}
+
+fn queue_path() -> PathBuf {
+    if let Some(pd) = ProjectDirs::from("dev", "OpenVCS", "OpenVCS") {
+        pd.data_dir().join("telemetry.json")
+    } else {
+        PathBuf::from("telemetry.json")
+    }
+}
+
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+fn new_install_id() -> String {
+    use std::hash::{BuildHasher, Hasher};
+    let mut id = String::new();
+    for _ in 0..2 {
+        let mut h = std::collections::hash_map::RandomState::new().build_hasher();
+        h.write_u128(OffsetDateTime::now_utc().unix_timestamp_nanos() as u128);
+        id.push_str(&format!("{:016x}", h.finish()));
+    }
+    id
+}
+
+fn queue() -> &'static Mutex<Queue> {
+    QUEUE.get_or_init(|| {
+        let mut q: Queue = std::fs::read_to_string(queue_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        if q.install_id.is_empty() { q.install_id = new_install_id(); }
+        if q.period_start == 0 { q.period_start = now(); }
+        Mutex::new(q)
+    })
+}
+
+fn persist(q: &Queue) {
+    let p = queue_path();
+    if let Some(parent) = p.parent() { let _ = std::fs::create_dir_all(parent); }
+    if let Ok(json) = serde_json::to_string(q) {
+        let _ = std::fs::write(p, json);
+    }
+}
+
+fn batch_of(q: &Queue) -> TelemetryBatch {
+    TelemetryBatch {
+        schema: 1,
+        install_id: q.install_id.clone(),
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        period_start: q.period_start,
+        period_end: now(),
+        ops: q.rows.values().cloned().collect(),
+    }
+}
+
+/// Apply the `general.telemetry` setting. Opting out discards anything not yet sent.
+pub fn set_enabled(enabled: bool) {
+    let was = ENABLED.swap(enabled, Ordering::Relaxed);
+    if was && !enabled {
+        if let Ok(mut q) = queue().lock() {
+            q.rows.clear();
+            q.period_start = now();
+        }
+        let _ = std::fs::remove_file(queue_path());
+        log::info!("telemetry: disabled; pending metrics discarded");
+    }
+}
+
+/// Record one finished operation (no-op unless opted in).
+pub fn record(op: &'static str, backend: &str, elapsed: Duration, outcome: &str) {
+    if !ENABLED.load(Ordering::Relaxed) { return; }
+    let ms = elapsed.as_millis() as u64;
+    let Ok(mut q) = queue().lock() else { return };
+    let key = format!("{op}|{backend}|{outcome}");
+    if !q.rows.contains_key(&key) && q.rows.len() >= MAX_ROWS { return; }
+    let row = q.rows.entry(key).or_insert_with(|| OpStats {
+        op: op.to_string(),
+        backend: backend.to_string(),
+        outcome: outcome.to_string(),
+        ..Default::default()
+    });
+    row.count += 1;
+    row.total_ms += ms;
+    row.max_ms = row.max_ms.max(ms);
+}
+
+/// Time `f` and record its outcome under `op`.
+pub fn track<T>(op: &'static str, backend: &str, f: impl FnOnce() -> openvcs_core::Result<T>) -> openvcs_core::Result<T> {
+    crate::crash::note_op(op, backend);
+    let started = Instant::now();
+    let res = f();
+    let outcome = match &res { Ok(_) => "ok", Err(e) => e.kind() };
+    record(op, backend, started.elapsed(), outcome);
+    res
+}
+
+pub fn preview() -> TelemetryPreview {
+    let next_batch = queue().lock().map(|q| batch_of(&q)).unwrap_or_else(|p| batch_of(&p.into_inner()));
+    TelemetryPreview { enabled: ENABLED.load(Ordering::Relaxed), endpoint: ENDPOINT, next_batch }
+}
+
+/// Persist pending metrics (called on exit so nothing is lost between sessions).
+pub fn save() {
+    if !ENABLED.load(Ordering::Relaxed) { return; }
+    if let Ok(q) = queue().lock() { persist(&q); }
+}
+
+/// Send the pending batch, clearing it on success.
+fn flush() {
+    let Some(endpoint) = ENDPOINT else { return };
+    if !ENABLED.load(Ordering::Relaxed) { return; }
+    let batch = match queue().lock() {
+        Ok(q) if !q.rows.is_empty() => batch_of(&q),
+        _ => return,
+    };
+    let sent = tauri::async_runtime::block_on(async {
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(20)).build().ok()?;
+        client.post(endpoint).json(&batch).send().await.ok()?.error_for_status().ok()
+    });
+    if let Ok(mut q) = queue().lock() {
+        if sent.is_some() {
+            log::debug!("telemetry: sent {} row(s)", batch.ops.len());
+            q.rows.clear();
+            q.period_start = now();
+        }
+        persist(&q);
+    }
+}
+
+/// Start the periodic batch sender.
+pub fn start(enabled: bool) {
+    set_enabled(enabled);
+    if ENDPOINT.is_none() { return; }
+    std::thread::spawn(|| loop {
+        std::thread::sleep(FLUSH_EVERY);
+        flush();
+    });
+}
@@ -0,0 +1,150 @@
+//! `.gitignore` and license templates for new repositories.
+//!
+//! A few common presets ship with the app (`Backend/templates/`); the rest are downloaded
+//! on demand: `.gitignore` files from github/gitignore, license texts from the GitHub
+//! licenses API. Several `.gitignore` presets can be combined into one file.
+
+use log::{debug, info};
+use serde::Serialize;
+
+const GITIGNORE_URL: &str = "https://raw.githubusercontent.com/github/gitignore/main";
+const LICENSE_API: &str = "https://api.github.com/licenses";
+
+const BUNDLED_GITIGNORE: &[(&str, &str)] = &[
+    ("Rust", include_str!("../templates/gitignore/Rust.gitignore")),
+    ("Node", include_str!("../templates/gitignore/Node.gitignore")),
+    ("Python", include_str!("../templates/gitignore/Python.gitignore")),
+    ("Go", include_str!("../templates/gitignore/Go.gitignore")),
+    ("Java", include_str!("../templates/gitignore/Java.gitignore")),
+    ("C++", include_str!("../templates/gitignore/C++.gitignore")),
+    ("Editors", include_str!("../templates/gitignore/Editors.gitignore")),
+];
+
+/// Presets fetched from github/gitignore (file name without `.gitignore`).
+const REMOTE_GITIGNORE: &[&str] = &[
+    "Android", "CMake", "Dart", "Elixir", "Godot", "Haskell", "Kotlin", "Laravel",
+    "Ruby", "Swift", "Unity", "UnrealEngine", "VisualStudio", "Zig",
+];
+
+/// (SPDX id, display name, bundled text)
+const BUNDLED_LICENSES: &[(&str, &str, &str)] = &[
+    ("MIT", "MIT License", include_str!("../templates/license/MIT.txt")),
+    ("BSD-3-Clause", "BSD 3-Clause License", include_str!("../templates/license/BSD-3-Clause.txt")),
+    ("Unlicense", "The Unlicense", include_str!("../templates/license/Unlicense.txt")),
+];
+
+/// (SPDX id, display name) of licenses fetched from the GitHub licenses API.
+const REMOTE_LICENSES: &[(&str, &str)] = &[
+    ("Apache-2.0", "Apache License 2.0"),
+    ("GPL-3.0", "GNU GPL v3.0"),
+    ("LGPL-3.0", "GNU LGPL v3.0"),
+    ("AGPL-3.0", "GNU AGPL v3.0"),
+    ("MPL-2.0", "Mozilla Public License 2.0"),
+    ("BSL-1.0", "Boost Software License 1.0"),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateInfo {
+    pub id: String,
+    pub name: String,
+    /// Ships with the app; the others need a network connection.
+    pub bundled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Templates {
+    pub gitignore: Vec<TemplateInfo>,
+    pub licenses: Vec<TemplateInfo>,
+}
+
+pub fn list() -> Templates {
+    let info = |id: &str, name: &str, bundled| TemplateInfo { id: id.into(), name: name.into(), bundled };
+    Templates {
+        gitignore: BUNDLED_GITIGNORE
+            .iter()
+            .map(|(n, _)| info(n, n, true))
+            .chain(REMOTE_GITIGNORE.iter().map(|n| info(n, n, false)))
+            .collect(),
+        licenses: BUNDLED_LICENSES
+            .iter()
+            .map(|(id, name, _)| info(id, name, true))
+            .chain(REMOTE_LICENSES.iter().map(|(id, name)| info(id, name, false)))
+            .collect(),
+    }
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent(concat!("OpenVCS/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+async fn fetch_gitignore(client: &reqwest::Client, name: &str) -> Result<String, String> {
+    if !REMOTE_GITIGNORE.contains(&name) {
+        return Err(format!("Unknown .gitignore template: {name}"));
+    }
+    debug!("templates: downloading {name}.gitignore");
+    let url = format!("{GITIGNORE_URL}/{name}.gitignore");
+    let resp = client.get(url).send().await.and_then(|r| r.error_for_status());
+    let resp = resp.map_err(|e| format!("Could not download the {name} .gitignore: {e}"))?;
+    resp.text().await.map_err(|e| e.to_string())
+}
+
+/// Contents of a `.gitignore` combining the presets `names`, one section each.
+pub async fn gitignore(names: &[String]) -> Result<String, String> {
+    let mut out = String::new();
+    let mut client = None;
+    for name in names {
+        let body = match BUNDLED_GITIGNORE.iter().find(|(n, _)| n == name) {
+            Some((_, text)) => text.to_string(),
+            None => {
+                if client.is_none() {
+                    client = Some(http_client()?);
+                }
+                fetch_gitignore(client.as_ref().unwrap(), name).await?
+            }
+        };
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("# ---- {name} ----\n"));
+        out.push_str(body.trim_end());
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// License text `id`, with `[year]`/`[fullname]` placeholders (see [`fill_license`]).
+pub async fn license(id: &str) -> Result<String, String> {
+    let text = match BUNDLED_LICENSES.iter().find(|(i, _, _)| *i == id) {
+        Some((_, _, text)) => text.to_string(),
+        None => {
+            if !REMOTE_LICENSES.iter().any(|(i, _)| *i == id) {
+                return Err(format!("Unknown license: {id}"));
+            }
+            info!("templates: downloading license {id}");
+            let url = format!("{LICENSE_API}/{}", id.to_ascii_lowercase());
+            let resp = http_client()?
+                .get(url)
+                .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+                .send()
+                .await
+                .and_then(|r| r.error_for_status())
+                .map_err(|e| format!("Could not download the {id} license: {e}"))?;
+            let json: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+            json.get("body")
+                .and_then(|b| b.as_str())
+                .map(str::to_string)
+                .ok_or_else(|| format!("The {id} license text is missing from the response"))?
+        }
+    };
+    Ok(text)
+}
+
+/// Fill in the current year and the copyright holder.
+pub fn fill_license(text: &str, holder: &str) -> String {
+    let year = time::OffsetDateTime::now_utc().year().to_string();
+    text.replace("[year]", &year).replace("[fullname]", holder)
+}
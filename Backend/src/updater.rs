@@ -0,0 +1,217 @@
+//! Updater wiring.
+//!
+//! `build.rs` still bakes endpoints for the compile-time `OPENVCS_UPDATE_CHANNEL` into the
+//! generated Tauri config, but every check goes through [`build_updater`], which replaces
+//! them with the endpoints of `general.update_channel`. The baked ones are never queried,
+//! so users can switch channels without a rebuild.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime, Url};
+use tauri_plugin_updater::{Update, Updater, UpdaterExt};
+
+use crate::settings::UpdateChannel;
+use crate::state::AppState;
+
+const STABLE_ENDPOINT: &str = "https://github.com/Jordonbc/OpenVCS/releases/latest/download/latest.json";
+const NIGHTLY_ENDPOINT: &str = "https://github.com/Jordonbc/OpenVCS/releases/download/openvcs-nightly/latest.json";
+
+// GitHub release APIs, used for release notes when latest.json carries none.
+const STABLE_RELEASE_API: &str = "https://api.github.com/repos/Jordonbc/OpenVCS/releases/latest";
+const NIGHTLY_RELEASE_API: &str = "https://api.github.com/repos/Jordonbc/OpenVCS/releases/tags/openvcs-nightly";
+
+/// Endpoints to query for a channel, in priority order (mirrors `build.rs`).
+pub fn endpoints_for(channel: UpdateChannel) -> Vec<&'static str> {
+    match channel {
+        // Nightly/Beta: check nightly first, then stable
+        UpdateChannel::Nightly | UpdateChannel::Beta => vec![NIGHTLY_ENDPOINT, STABLE_ENDPOINT],
+        // Stable: stable only
+        UpdateChannel::Stable => vec![STABLE_ENDPOINT],
+    }
+}
+
+/// Build an updater whose endpoints follow the currently configured channel.
+pub fn build_updater<R: Runtime>(app: &AppHandle<R>) -> Result<Updater, String> {
+    let channel = app.state::<AppState>().with_config(|c| c.general.update_channel);
+    let endpoints = endpoints_for(channel)
+        .into_iter()
+        .map(|u| Url::parse(u).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    log::debug!("updater: channel {:?} -> {:?}", channel, endpoints_for(channel));
+
+    app.updater_builder()
+        .endpoints(endpoints)
+        .map_err(|e| e.to_string())?
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Check the configured channel for an update.
+pub async fn check<R: Runtime>(app: &AppHandle<R>) -> Result<Option<Update>, String> {
+    build_updater(app)?.check().await.map_err(|e| e.to_string())
+}
+
+/// Check for an update and emit `ui:update-available` (tagged with `source`) when one exists.
+/// Returns whether an update was found.
+pub async fn check_and_announce<R: Runtime>(app: &AppHandle<R>, source: &str) -> Result<bool, String> {
+    match check(app).await? {
+        Some(_) => {
+            let _ = app.emit("ui:update-available", serde_json::json!({ "source": source }));
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/* -------- update info / staged install -------- */
+
+/// What the frontend needs to present an available update.
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    pub notes: Option<String>,
+    /// RFC 3339 publish date, when the feed provides one.
+    pub date: Option<String>,
+    /// Download size in bytes, when the server reports it.
+    pub size: Option<u64>,
+    /// True when this version is already downloaded and will install on quit.
+    pub staged: bool,
+}
+
+/// An update downloaded ahead of time and waiting to be installed.
+struct Staged {
+    update: Update,
+    bytes: Vec<u8>,
+}
+
+/// Managed state holding a downloaded-but-not-installed update.
+#[derive(Default)]
+pub struct UpdaterState {
+    staged: Mutex<Option<Staged>>,
+}
+
+impl UpdaterState {
+    fn staged_version(&self) -> Option<String> {
+        self.staged.lock().as_ref().map(|s| s.update.version.clone())
+    }
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .user_agent(concat!("OpenVCS/", env!("CARGO_PKG_VERSION")))
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Release notes from the GitHub release backing `channel` (best-effort).
+async fn github_release_notes(client: &reqwest::Client, channel: UpdateChannel) -> Option<String> {
+    let url = match channel {
+        UpdateChannel::Stable => STABLE_RELEASE_API,
+        UpdateChannel::Nightly | UpdateChannel::Beta => NIGHTLY_RELEASE_API,
+    };
+    let json: serde_json::Value = client.get(url).send().await.ok()?.error_for_status().ok()?.json().await.ok()?;
+    json.get("body")
+        .and_then(|b| b.as_str())
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+}
+
+/// Size of the update artifact from a HEAD request (best-effort).
+async fn download_size(client: &reqwest::Client, url: &Url) -> Option<u64> {
+    let resp = client.head(url.as_str()).send().await.ok()?.error_for_status().ok()?;
+    resp.headers()
+        .get(reqwest::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Describe the update available on the configured channel, if any.
+pub async fn update_info<R: Runtime>(app: &AppHandle<R>) -> Result<Option<UpdateInfo>, String> {
+    let Some(update) = check(app).await? else { return Ok(None) };
+    let channel = app.state::<AppState>().with_config(|c| c.general.update_channel);
+    let client = http_client()?;
+
+    let mut notes = update.body.clone().map(|b| b.trim().to_string()).filter(|b| !b.is_empty());
+    if notes.is_none() {
+        notes = github_release_notes(&client, channel).await;
+    }
+    let size = download_size(&client, &update.download_url).await;
+    let date = update
+        .date
+        .and_then(|d| d.format(&time::format_description::well_known::Rfc3339).ok());
+    let staged = app.state::<UpdaterState>().staged_version().as_deref() == Some(update.version.as_str());
+
+    Ok(Some(UpdateInfo {
+        version: update.version,
+        current_version: update.current_version,
+        channel,
+        notes,
+        date,
+        size,
+        staged,
+    }))
+}
+
+/// Download `update`, emitting `update:progress` events as chunks arrive.
+async fn download<R: Runtime>(app: &AppHandle<R>, update: &Update) -> Result<Vec<u8>, String> {
+    let mut received: u64 = 0;
+    let app_done = app.clone();
+    update
+        .download(
+            |chunk, total| {
+                received += chunk as u64;
+                let payload = serde_json::json!({ "kind": "progress", "received": received, "total": total });
+                let _ = app.emit("update:progress", payload);
+            },
+            || {
+                let _ = app_done.emit("update:progress", serde_json::json!({ "kind": "downloaded" }));
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Download the available update now and keep it staged; it is installed when the app exits.
+/// Returns the staged version, or `None` when already up to date.
+pub async fn download_for_quit<R: Runtime>(app: &AppHandle<R>) -> Result<Option<String>, String> {
+    let Some(update) = check(app).await? else { return Ok(None) };
+    let st = app.state::<UpdaterState>();
+    if st.staged_version().as_deref() == Some(update.version.as_str()) {
+        return Ok(Some(update.version));
+    }
+
+    let bytes = download(app, &update).await?;
+    let version = update.version.clone();
+    log::info!("updater: staged {} ({} bytes) for install on quit", version, bytes.len());
+    *st.staged.lock() = Some(Staged { update, bytes });
+    let _ = app.emit("update:progress", serde_json::json!({ "kind": "staged", "version": version }));
+    Ok(Some(version))
+}
+
+/// Install immediately, reusing a staged download when it matches the latest version.
+pub async fn install_now<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let Some(update) = check(app).await? else { return Ok(()) };
+    let staged = app.state::<UpdaterState>().staged.lock().take();
+    let bytes = match staged {
+        Some(s) if s.update.version == update.version => s.bytes,
+        _ => download(app, &update).await?,
+    };
+    update.install(bytes).map_err(|e| e.to_string())?;
+    let _ = app.emit("update:progress", serde_json::json!({ "kind": "installed" }));
+    Ok(())
+}
+
+/// Install a staged update, if any. Called on app exit.
+pub fn install_staged<R: Runtime>(app: &AppHandle<R>) {
+    let Some(staged) = app.state::<UpdaterState>().staged.lock().take() else { return };
+    log::info!("updater: installing staged {} on exit", staged.update.version);
+    if let Err(e) = staged.update.install(&staged.bytes) {
+        log::error!("updater: failed to install staged update: {}", e);
+    }
+}
@@ -11,6 +11,18 @@ pub struct AboutInfo {
   pub authors:     String,
   pub os:          String,
   pub arch:        String,
+  /// Version of the git executable the system backend runs, if it was found.
+  pub git_version:     Option<String>,
+  pub libgit2_version: Option<String>,
+  pub backends:        Vec<BackendInfo>,
+  pub webview_version: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BackendInfo {
+  pub id:      String,
+  pub name:    String,
+  pub version: Option<String>,
 }
 
 impl AboutInfo {
@@ -28,7 +40,22 @@ impl AboutInfo {
     let os          = std::env::consts::OS.to_string();
     let arch        = std::env::consts::ARCH.to_string();
 
-    Self { name, version, build, description, homepage, repository, authors, os, arch }
+    // Runtime versions, asked of each registered backend (spawns `git --version`)
+    let backends: Vec<BackendInfo> = openvcs_core::backend_descriptor::list_backends()
+      .map(|b| BackendInfo { id: b.id.to_string(), name: b.name.to_string(), version: (b.version)() })
+      .collect();
+    let version_of = |id: openvcs_core::BackendId| backends.iter().find(|b| b.id == id.as_str()).and_then(|b| b.version.clone());
+    let git_version     = version_of(crate::GIT_SYSTEM_ID);
+    #[cfg(feature = "with-git-libgit2")]
+    let libgit2_version = version_of(crate::GIT_LIBGIT2_ID);
+    #[cfg(not(feature = "with-git-libgit2"))]
+    let libgit2_version = None;
+    let webview_version = tauri::webview_version().ok();
+
+    Self {
+      name, version, build, description, homepage, repository, authors, os, arch,
+      git_version, libgit2_version, backends, webview_version,
+    }
   }
 }
 
@@ -1 +0,0 @@
-pub mod utilities;
\ No newline at end of file
@@ -4,39 +4,205 @@ use std::{path::{Path}};
 pub struct Validation {
     pub ok: bool,
     pub reason: Option<String>,
+    /// Canonical form of the input when it differs from what was typed (e.g. a GitHub
+    /// page URL trimmed to the repository).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// What kind of repository a path is (for `validate_add_path`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<RepoKind>,
 }
 
-// Expand ~ to home; leave other paths untouched.
+impl Validation {
+    fn ok() -> Self {
+        Validation { ok: true, reason: None, suggestion: None, kind: None }
+    }
+
+    fn fail(reason: impl Into<String>) -> Self {
+        Validation { ok: false, reason: Some(reason.into()), suggestion: None, kind: None }
+    }
+}
+
+// Expand ~ and normalize (see `paths`).
 // Returns (normalized_path_string, exists, is_dir)
 fn normalize_and_probe(input: &str) -> (String, bool, bool) {
-    let mut s = input.trim().to_string();
-    if s.starts_with('~') {
-        if let Some(home) = dirs::home_dir() {
-            s = s.replacen('~', home.to_string_lossy().as_ref(), 1);
-        }
-    }
-    let p = Path::new(&s);
-    (s.clone(), p.exists(), p.is_dir())
+    let p = crate::paths::normalize(&crate::paths::expand(input));
+    (p.to_string_lossy().into_owned(), p.exists(), p.is_dir())
 }
 
-fn is_probably_git_url(u: &str) -> bool {
-    let u = u.trim();
-    if u.is_empty() { return false; }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UrlScheme {
+    Https,
+    Http,
+    Ssh,
+    Git,
+    File,
+    /// A plain filesystem path.
+    Local,
+}
 
-    // http(s)://.../*.git
-    if (u.starts_with("http://") || u.starts_with("https://")) && u.ends_with(".git") {
-        return true;
+/// A parsed clone source.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GitUrl {
+    pub scheme: UrlScheme,
+    pub user: Option<String>,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub path: String,
+    /// Normalized URL to clone: trailing slashes dropped, forge page URLs trimmed to the
+    /// repository. Same scheme and form as the input.
+    pub canonical: String,
+    /// Directory name a clone creates (last path segment without `.git`).
+    pub repo_name: String,
+}
+
+/// Hosts whose repository URLs are exactly `/<owner>/<repo>`; anything longer is a page
+/// inside the repository (`/tree/main`, `/src/main`, …).
+const FORGES: &[&str] = &["github.com", "bitbucket.org", "codeberg.org"];
+
+/// Hosts with nested namespaces (`/<group>/<subgroup>/…/<repo>`), where pages inside the
+/// repository start at a `-`, `tree` or `blob` segment (`/-/issues`, `/tree/main`).
+const NESTED_FORGES: &[&str] = &["gitlab.com"];
+
+fn is_local_path(u: &str) -> bool {
+    u.starts_with('/') || u.starts_with('~') || u.starts_with("\\\\") || looks_like_path(u)
+}
+
+fn repo_name(path: &str) -> String {
+    let last = path.trim_end_matches(['/', '\\']).rsplit(['/', '\\', ':']).next().unwrap_or("");
+    last.trim_end_matches(".git").to_string()
+}
+
+/// Parse anything git accepts as a clone source: `http(s)://`, `ssh://` (optionally with
+/// a port), `git://`, `file://`, scp-like `user@host:path`, and local paths.
+pub fn parse_git_url(input: &str) -> Result<GitUrl, String> {
+    let u = input.trim();
+    if u.is_empty() {
+        return Err("Enter a Git URL".into());
     }
-    // ssh://user@host/.../*.git
-    if u.starts_with("ssh://") && u.ends_with(".git") {
-        return true;
+    if u.chars().any(char::is_whitespace) {
+        return Err("URL must not contain spaces".into());
     }
-    // scp-like: git@host:org/repo.git
-    let scp_like = regex::Regex::new(r"^[\w.-]+@[\w.-]+:[\w./-]+\.git$").unwrap();
-    if scp_like.is_match(u) {
-        return true;
+    if u.starts_with('-') {
+        return Err("Not a recognized Git URL".into());
     }
-    false
+
+    if let Some((scheme, rest)) = u.split_once("://") {
+        let scheme = match scheme.to_ascii_lowercase().as_str() {
+            "https" => UrlScheme::Https,
+            "http" => UrlScheme::Http,
+            "ssh" | "git+ssh" | "ssh+git" => UrlScheme::Ssh,
+            "git" => UrlScheme::Git,
+            "file" => UrlScheme::File,
+            other => return Err(format!("Unsupported URL scheme: {other}")),
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, ""),
+        };
+        if scheme == UrlScheme::File {
+            if path.len() <= 1 && authority.is_empty() {
+                return Err("file:// URL has no path".into());
+            }
+            let canonical = u.trim_end_matches('/').to_string();
+            return Ok(GitUrl { scheme, user: None, host: None, port: None, repo_name: repo_name(path), path: path.to_string(), canonical });
+        }
+
+        let (user, hostport) = match authority.rsplit_once('@') {
+            Some((user, h)) => (Some(user.to_string()), h),
+            None => (None, authority),
+        };
+        // "[v6]:port" keeps its brackets; otherwise the port follows the last ':'
+        let split = match hostport.strip_prefix('[') {
+            Some(_) => hostport.find("]:").map(|i| (&hostport[..=i], &hostport[i + 2..])),
+            None => hostport.rsplit_once(':'),
+        };
+        let (host, port) = match split {
+            Some((h, p)) => {
+                let port = p.parse::<u16>().map_err(|_| format!("Invalid port: {p}"))?;
+                (h, Some(port))
+            }
+            None => (hostport, None),
+        };
+        if host.is_empty() {
+            return Err("URL has no host".into());
+        }
+        let mut path = path.trim_end_matches('/').to_string();
+        if path.is_empty() {
+            return Err("URL has no repository path".into());
+        }
+        let host_lc = host.to_ascii_lowercase();
+        if matches!(scheme, UrlScheme::Https | UrlScheme::Http) {
+            let segs: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+            let end = if FORGES.contains(&host_lc.as_str()) {
+                Some(2)
+            } else if NESTED_FORGES.contains(&host_lc.as_str()) {
+                Some(segs.iter().position(|s| matches!(*s, "-" | "tree" | "blob")).unwrap_or(segs.len()))
+            } else {
+                None
+            };
+            if let Some(end) = end {
+                if segs.len() < 2 || end < 2 {
+                    return Err("URL does not point at a repository".into());
+                }
+                path = format!("/{}", segs[..end].join("/"));
+            }
+        }
+
+        let mut canonical = format!("{}://", &u[..u.find("://").unwrap_or(0)]);
+        if let Some(user) = &user {
+            canonical.push_str(user);
+            canonical.push('@');
+        }
+        canonical.push_str(host);
+        if let Some(p) = port {
+            canonical.push_str(&format!(":{p}"));
+        }
+        canonical.push_str(&path);
+
+        return Ok(GitUrl {
+            scheme,
+            user: user.map(|u| u.split(':').next().unwrap_or("").to_string()),
+            host: Some(host.to_string()),
+            port,
+            repo_name: repo_name(&path),
+            path,
+            canonical,
+        });
+    }
+
+    if is_local_path(u) {
+        let canonical = u.trim_end_matches(['/', '\\']).to_string();
+        let canonical = if canonical.is_empty() { u.to_string() } else { canonical };
+        return Ok(GitUrl {
+            scheme: UrlScheme::Local,
+            user: None,
+            host: None,
+            port: None,
+            repo_name: repo_name(&canonical),
+            path: canonical.clone(),
+            canonical,
+        });
+    }
+
+    // scp-like: [user@]host:path (git treats it as ssh)
+    let scp = regex::Regex::new(r"^(?:([\w.+-]+)@)?([\w.-]+):([^/\\].*|/.+)$").unwrap();
+    if let Some(c) = scp.captures(u) {
+        let path = c[3].trim_end_matches('/').to_string();
+        if path.is_empty() {
+            return Err("URL has no repository path".into());
+        }
+        let user = c.get(1).map(|m| m.as_str().to_string());
+        let host = c[2].to_string();
+        let canonical = match &user {
+            Some(user) => format!("{user}@{host}:{path}"),
+            None => format!("{host}:{path}"),
+        };
+        return Ok(GitUrl { scheme: UrlScheme::Ssh, user, host: Some(host), port: None, repo_name: repo_name(&path), path, canonical });
+    }
+
+    Err("Not a recognized Git URL".into())
 }
 
 fn looks_like_path(s: &str) -> bool {
@@ -44,59 +210,157 @@ fn looks_like_path(s: &str) -> bool {
     if s.is_empty() { return false; }
     // POSIX absolute or ~
     if s.starts_with('/') || s.starts_with('~') { return true; }
+    // Windows UNC share or verbatim path, e.g. \\server\share\repo
+    if s.starts_with("\\\\") { return true; }
     // Windows drive letter absolute, e.g. C:\...
     let win_abs = regex::Regex::new(r"^[A-Za-z]:[\\/]").unwrap();
     win_abs.is_match(s)
 }
 
+fn url_validation(url: &str) -> Result<Validation, Validation> {
+    let parsed = parse_git_url(url).map_err(Validation::fail)?;
+    if parsed.repo_name.is_empty() {
+        return Err(Validation::fail("Cannot infer the repository name from the URL"));
+    }
+    let mut v = Validation::ok();
+    if parsed.canonical != url.trim() {
+        v.suggestion = Some(parsed.canonical);
+    }
+    Ok(v)
+}
+
 pub fn validate_git_url(url: String) -> Validation {
-    if is_probably_git_url(&url) {
-        Validation { ok: true, reason: None }
-    } else {
-        Validation { ok: false, reason: Some("Not a recognized Git URL (http(s), ssh, or scp-like ending in .git)".into()) }
+    url_validation(&url).unwrap_or_else(|e| e)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RepoKind {
+    /// A regular checkout with its own `.git` directory.
+    Worktree,
+    /// A `git worktree add` checkout (`.git` is a file pointing into another repo).
+    LinkedWorktree,
+    /// A submodule checkout (git dir lives under the superproject's `.git/modules`).
+    Submodule,
+    /// No working tree at all; opened read-only.
+    Bare,
+}
+
+struct RepoProbe {
+    kind: RepoKind,
+    /// Top of the working tree (absent for bare repositories).
+    toplevel: Option<String>,
+}
+
+fn rev_parse(path: &str, args: &[&str]) -> Option<Vec<String>> {
+    let mut argv = vec!["-C", path, "rev-parse"];
+    argv.extend_from_slice(args);
+    match crate::doctor::probe_git(&argv) {
+        Some((0, out)) => Some(out.lines().map(str::to_string).collect()),
+        _ => None,
+    }
+}
+
+/// Ask git what `path` is. `None` when it isn't inside a repository (or git is missing).
+fn probe_repo(path: &str) -> Option<RepoProbe> {
+    // These all work in bare repositories too (unlike --show-toplevel)
+    let facts = rev_parse(path, &["--is-bare-repository", "--is-inside-work-tree", "--absolute-git-dir", "--git-common-dir"])?;
+    let [bare, inside, git_dir, common_dir] = facts.as_slice() else { return None };
+    if bare == "true" || inside != "true" {
+        return Some(RepoProbe { kind: RepoKind::Bare, toplevel: None });
     }
+
+    let toplevel = rev_parse(path, &["--show-toplevel"]).and_then(|l| l.into_iter().next());
+    let superproject = rev_parse(path, &["--show-superproject-working-tree"])
+        .and_then(|l| l.into_iter().next())
+        .filter(|s| !s.is_empty());
+
+    let common = Path::new(common_dir);
+    let common = if common.is_absolute() { common.to_path_buf() } else { Path::new(path).join(common) };
+    let same_dir = std::fs::canonicalize(&common).ok() == std::fs::canonicalize(git_dir).ok();
+
+    let kind = if superproject.is_some() {
+        RepoKind::Submodule
+    } else if !same_dir {
+        RepoKind::LinkedWorktree
+    } else {
+        RepoKind::Worktree
+    };
+    Some(RepoProbe { kind, toplevel })
 }
 
 pub fn validate_add_path(path: String) -> Validation {
     if !looks_like_path(&path) {
-        return Validation { ok: false, reason: Some("Enter an absolute path".into()) };
+        return Validation::fail("Enter an absolute path");
     }
     let (norm, exists, is_dir) = normalize_and_probe(&path);
-    if !exists { return Validation { ok: false, reason: Some(format!("Path does not exist: {norm}")) }; }
-    if !is_dir { return Validation { ok: false, reason: Some(format!("Not a directory: {norm}")) }; }
+    if !exists { return Validation::fail(format!("Path does not exist: {norm}")); }
+    if !is_dir { return Validation::fail(format!("Not a directory: {norm}")); }
 
-    // Optional: require .git folder present
-    let is_repo = Path::new(&norm).join(".git").exists();
-    if !is_repo {
-        return Validation { ok: false, reason: Some("Folder does not look like a Git repository (.git missing)".into()) };
+    let Some(repo) = probe_repo(&norm) else {
+        return Validation::fail("Folder is not inside a Git repository");
+    };
+    let mut v = Validation::ok();
+    if repo.kind == RepoKind::Bare {
+        v.reason = Some("Bare repository: opens read-only (history and branches only)".into());
     }
-
-    Validation { ok: true, reason: None }
+    v.kind = Some(repo.kind);
+    // Opened from a subfolder: point at the top of the working tree
+    if let Some(top) = repo.toplevel {
+        if !crate::paths::same(Path::new(&top), Path::new(&norm)) { v.suggestion = Some(top); }
+    }
+    v
 }
 
 pub fn validate_clone_input(url: String, dest: String) -> Validation {
-    if !is_probably_git_url(&url) {
-        return Validation { ok: false, reason: Some("Invalid Git URL".into()) };
-    }
+    let url_ok = match url_validation(&url) {
+        Ok(v) => v,
+        Err(e) => return e,
+    };
     if !looks_like_path(&dest) {
-        return Validation { ok: false, reason: Some("Destination must be an absolute path".into()) };
+        return Validation::fail("Destination must be an absolute path");
     }
     let (norm, exists, is_dir) = normalize_and_probe(&dest);
     if !exists {
         // Allow non-existent parent? Keep strict: require parent exists.
         if let Some(parent) = Path::new(&norm).parent() {
             if !parent.exists() {
-                return Validation { ok: false, reason: Some("Parent folder does not exist".into()) };
+                return Validation::fail("Parent folder does not exist");
             }
         }
-        return Validation { ok: true, reason: None }; // Okay to create at clone time
+        return url_ok; // Okay to create at clone time
     }
     if !is_dir {
-        return Validation { ok: false, reason: Some("Destination is not a directory".into()) };
+        return Validation::fail("Destination is not a directory");
     }
     // If directory exists, ensure it's empty-ish (no .git)
     if Path::new(&norm).join(".git").exists() {
-        return Validation { ok: false, reason: Some("Destination already contains a Git repo".into()) };
+        return Validation::fail("Destination already contains a Git repo");
+    }
+    url_ok
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(url: &str) -> String {
+        parse_git_url(url).unwrap().canonical
+    }
+
+    #[test]
+    fn forge_pages_trim_to_the_repository() {
+        assert_eq!(canonical("https://github.com/owner/repo/tree/main/src"), "https://github.com/owner/repo");
+        assert_eq!(canonical("https://gitlab.com/group/repo/-/issues"), "https://gitlab.com/group/repo");
+        assert!(parse_git_url("https://github.com/owner").is_err());
+    }
+
+    #[test]
+    fn gitlab_subgroups_keep_their_namespace() {
+        let url = parse_git_url("https://gitlab.com/group/subgroup/repo.git").unwrap();
+        assert_eq!(url.canonical, "https://gitlab.com/group/subgroup/repo.git");
+        assert_eq!(url.repo_name, "repo");
+        assert_eq!(canonical("https://gitlab.com/group/sub/deeper/repo/-/tree/main"), "https://gitlab.com/group/sub/deeper/repo");
+        assert_eq!(canonical("https://gitlab.com/group/subgroup/repo/blob/main/README.md"), "https://gitlab.com/group/subgroup/repo");
     }
-    Validation { ok: true, reason: None }
 }
@@ -0,0 +1,106 @@
+//! Working tree autosave ("local history").
+//!
+//! [`snapshot`] commits the dirty working tree to `refs/openvcs/wip/<branch>` through
+//! `Vcs::snapshot_worktree`, which leaves the index and HEAD alone. Each snapshot's parent is
+//! the previous one, so the ref's history is the branch's autosave timeline; [`list`] reads it
+//! back and `Vcs::restore_worktree` puts files from a snapshot into the working tree.
+//! [`start_service`] snapshots the open repository every `git.wip_autosave_minutes` (off by
+//! default).
+
+use std::time::{Duration, Instant};
+
+use log::{debug, info, trace, warn};
+use openvcs_core::models::{CommitItem, LogQuery};
+use openvcs_core::Vcs;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::state::AppState;
+
+const PREFIX: &str = "refs/openvcs/wip/";
+/// How often the service checks whether a snapshot is due.
+const TICK: Duration = Duration::from_secs(60);
+
+/// A snapshot just taken.
+#[derive(Debug, Clone, Serialize)]
+pub struct Saved {
+    pub refname: String,
+    pub commit: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct SavedEvent {
+    path: String,
+    #[serde(flatten)]
+    saved: Saved,
+}
+
+/// Snapshot ref of `branch` (`None` = detached HEAD).
+pub fn ref_for(branch: Option<&str>) -> String {
+    format!("{PREFIX}{}", branch.unwrap_or("detached"))
+}
+
+/// Snapshot the working tree of the current branch. `None` when nothing changed since the
+/// last snapshot. Blocking.
+pub fn snapshot(vcs: &dyn Vcs) -> Result<Option<Saved>, String> {
+    let branch = vcs.current_branch().map_err(|e| e.to_string())?;
+    let refname = ref_for(branch.as_deref());
+    let msg = format!("WIP autosave on {}", branch.as_deref().unwrap_or("detached HEAD"));
+    match vcs.snapshot_worktree(&refname, &msg).map_err(|e| e.to_string())? {
+        Some(commit) => {
+            debug!("wip: snapshot {commit} → {refname}");
+            Ok(Some(Saved { refname, commit }))
+        }
+        None => {
+            trace!("wip: {refname} is up to date");
+            Ok(None)
+        }
+    }
+}
+
+/// Newest snapshots of `branch` first (empty if there are none yet). Blocking.
+pub fn list(vcs: &dyn Vcs, branch: Option<&str>, limit: usize) -> Vec<CommitItem> {
+    let q = LogQuery { rev: Some(ref_for(branch)), limit: limit as u32, include_merges: true, ..Default::default() };
+    vcs.log_commits(&q).unwrap_or_else(|e| {
+        debug!("wip: no snapshots for {:?}: {e}", branch);
+        Vec::new()
+    })
+}
+
+/// Snapshot the open repository periodically while autosave is enabled.
+pub fn start_service<R: Runtime>(app: AppHandle<R>) {
+    let spawned = std::thread::Builder::new().name("wip-autosave".into()).spawn(move || {
+        let mut last: Option<Instant> = None;
+        loop {
+            std::thread::sleep(TICK);
+            let state = app.state::<AppState>();
+            let minutes = state.config().git.wip_autosave_minutes;
+            if minutes == 0 {
+                last = None;
+                continue;
+            }
+            if last.is_some_and(|t| t.elapsed() < Duration::from_secs(u64::from(minutes) * 60)) {
+                continue;
+            }
+            let Some(repo) = state.current_repo() else { continue };
+            if !repo.inner().caps().writable || crate::repo_lost::is_lost(repo.inner().workdir()) {
+                continue;
+            }
+            last = Some(Instant::now());
+
+            let vcs = repo.inner();
+            match snapshot(vcs) {
+                Ok(Some(saved)) => {
+                    info!("wip: autosaved {} ({})", vcs.workdir().display(), saved.commit);
+                    let path = vcs.workdir().to_string_lossy().to_string();
+                    let _ = app.emit("repo:wip-saved", SavedEvent { path, saved });
+                }
+                Ok(None) => {}
+                Err(e) => warn!("wip: autosave of {} failed: {e}", vcs.workdir().display()),
+            }
+        }
+    });
+    if let Err(e) = spawned {
+        warn!("wip: failed to start the autosave service: {e}");
+    }
+}
@@ -0,0 +1,72 @@
+//! Other worktrees of the current repository.
+//!
+//! A branch can be checked out in only one worktree at a time, and git's refusal to check
+//! it out in a second one reads as a raw error. The repo summary therefore lists every
+//! worktree with its branch and a quick dirty count, and checkout refuses early with a
+//! message naming the worktree that holds the branch.
+
+use std::path::Path;
+
+use log::debug;
+use openvcs_core::backend_descriptor::get_backend;
+use openvcs_core::models::WorktreeInfo;
+use openvcs_core::{Repo, Vcs, VcsError};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeStatus {
+    #[serde(flatten)]
+    pub info: WorktreeInfo,
+    /// Changed, staged, untracked and conflicted files; `None` when it could not be read.
+    pub changes: Option<usize>,
+}
+
+fn list(vcs: &dyn Vcs) -> Vec<WorktreeInfo> {
+    match vcs.list_worktrees() {
+        Ok(list) => list,
+        Err(VcsError::Unsupported(_)) => Vec::new(),
+        Err(e) => {
+            debug!("worktrees: cannot list: {e}");
+            Vec::new()
+        }
+    }
+}
+
+fn changes(repo: &Repo, info: &WorktreeInfo) -> Option<usize> {
+    if info.bare || info.prunable {
+        return None;
+    }
+    let summary = if info.current {
+        repo.inner().status_summary()
+    } else {
+        let open = get_backend(repo.id())?.open;
+        open(Path::new(&info.path)).and_then(|vcs| vcs.status_summary())
+    };
+    match summary {
+        Ok(s) => Some(s.untracked + s.modified + s.staged + s.conflicted),
+        Err(e) => {
+            debug!("worktrees: no status for {}: {e}", info.path);
+            None
+        }
+    }
+}
+
+/// Every worktree with its dirty count; empty unless the repository has linked worktrees.
+/// Blocking: runs a status in each worktree.
+pub fn statuses(repo: &Repo) -> Vec<WorktreeStatus> {
+    let list = list(repo.inner());
+    if list.len() < 2 {
+        return Vec::new();
+    }
+    list.into_iter()
+        .map(|info| WorktreeStatus { changes: changes(repo, &info), info })
+        .collect()
+}
+
+/// Path of another worktree that has `branch` checked out.
+pub fn claimed_by(vcs: &dyn Vcs, branch: &str) -> Option<String> {
+    list(vcs)
+        .into_iter()
+        .find(|w| !w.current && w.branch.as_deref() == Some(branch))
+        .map(|w| w.path)
+}
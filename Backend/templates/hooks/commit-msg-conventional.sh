@@ -0,0 +1,24 @@
+#!/bin/sh
+# openvcs-hook-template: commit-msg-conventional 1
+# Rejects commit messages whose subject is not a Conventional Commit
+# ("type(scope)!: summary", https://www.conventionalcommits.org).
+# Managed by OpenVCS (Repository Settings > Hooks).
+
+subject=$(grep -v '^#' "$1" | sed -n '/[^[:space:]]/{p;q;}')
+
+case "$subject" in
+    "Merge "*|"Revert "*|"fixup! "*|"squash! "*|"amend! "*) exit 0 ;;
+esac
+
+types='build|chore|ci|docs|feat|fix|perf|refactor|revert|style|test'
+if ! printf '%s\n' "$subject" | grep -Eq "^($types)(\([^()]+\))?!?: [^[:space:]]"; then
+    echo "commit-msg: the subject must look like 'type(scope): summary'" >&2
+    echo "commit-msg: where type is one of: $(echo "$types" | sed 's/|/, /g')" >&2
+    echo "commit-msg: got: $subject" >&2
+    exit 1
+fi
+
+if [ "${#subject}" -gt 100 ]; then
+    echo "commit-msg: the subject is ${#subject} characters long; keep it to 100" >&2
+    exit 1
+fi
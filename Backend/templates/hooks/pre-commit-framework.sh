@@ -0,0 +1,19 @@
+#!/bin/sh
+# openvcs-hook-template: pre-commit-framework 1
+# Runs the hooks configured in .pre-commit-config.yaml with the pre-commit framework
+# (https://pre-commit.com). Managed by OpenVCS (Repository Settings > Hooks).
+
+[ -f .pre-commit-config.yaml ] || exit 0
+
+if command -v pre-commit >/dev/null 2>&1; then
+    exec pre-commit run --config .pre-commit-config.yaml --hook-stage pre-commit
+fi
+for py in python3 python; do
+    if command -v "$py" >/dev/null 2>&1 && "$py" -c 'import pre_commit' 2>/dev/null; then
+        exec "$py" -m pre_commit run --config .pre-commit-config.yaml --hook-stage pre-commit
+    fi
+done
+
+echo "pre-commit: the pre-commit framework is not installed." >&2
+echo "pre-commit: install it with 'pipx install pre-commit' (or 'pip install pre-commit')." >&2
+exit 1
@@ -21,17 +21,34 @@ export async function openAbout(): Promise<void> {
             build?: string;
             homepage?: string;
             repository?: string;
+            os?: string;
+            arch?: string;
+            git_version?: string | null;
+            libgit2_version?: string | null;
+            webview_version?: string | null;
+            backends?: { id: string; name: string; version?: string | null }[];
         }
             | null;
 
         const aboutVersion  = q<HTMLElement>("#about-version", modal);
         const aboutBuild    = q<HTMLElement>("#about-build", modal);
+        const aboutRuntime  = q<HTMLElement>("#about-runtime", modal);
         const aboutHome     = q<HTMLAnchorElement>("#about-home", modal);
         const aboutRepo     = q<HTMLAnchorElement>("#about-repo", modal);
         const aboutLicenses = q<HTMLAnchorElement>("#about-licenses", modal);
 
         if (aboutVersion) aboutVersion.textContent = info?.version ? `v${info.version}` : "";
         if (aboutBuild)   aboutBuild.textContent   = info?.build ?? "";
+        if (aboutRuntime) {
+            const lines = [
+                `Git: ${info?.git_version ?? "not found"}`,
+                `libgit2: ${info?.libgit2_version ?? "n/a"}`,
+                `WebView: ${info?.webview_version ?? "unknown"}`,
+                `Backends: ${(info?.backends ?? []).map(b => b.id).join(", ") || "none"}`,
+                `Platform: ${info?.os ?? ""} ${info?.arch ?? ""}`.trim(),
+            ];
+            aboutRuntime.textContent = info ? lines.join("\n") : "";
+        }
 
         if (aboutHome) {
             aboutHome.href = info?.homepage || "#";
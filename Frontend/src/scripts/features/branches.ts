@@ -7,8 +7,10 @@ import { openModal } from '../ui/modals';
 import { openRenameBranch } from './renameBranch';
 import { buildCtxMenu } from '../lib/menu';
 import { renderList } from './repo';
+import { loadBranchPages } from '../lib/branchPages';
+import type { GlobalSettings, HeadMove, HeadStatus, MergeOutcome, PushedCommit, WorktreeStatus } from '../types';
 
-type Branch = { name: string; current?: boolean; kind?: { type?: string; remote?: string } };
+type Branch = { name: string; current?: boolean; kind?: { type?: string; remote?: string }; upstream_gone?: boolean };
 
 const branchBtn    = qs<HTMLButtonElement>('#branch-switch');
 const branchName   = qs<HTMLElement>('#branch-name');
@@ -17,23 +19,35 @@ const branchFilter = qs<HTMLInputElement>('#branch-filter');
 const branchList   = qs<HTMLElement>('#branch-list');
 const repoBranchEl = qs<HTMLElement>('#repo-branch');
 
+/** Branches checked out in other worktrees, by name. */
+let claimed: Record<string, WorktreeStatus> = {};
+
 /* ---------------- data load ---------------- */
 
 async function loadBranches() {
     if (!TAURI.has) return;
     try {
-        const branches = await TAURI.invoke<Branch[]>('git_list_branches');
-        state.branches = Array.isArray(branches) ? branches : [];
+        // Each page shows as it arrives
+        const all = await loadBranchPages('branch-popover', (items) => {
+            state.branches = items;
+            renderBranches();
+        });
+        if (!all) return; // a newer load took over
+
+        const worktrees = await TAURI.invoke<WorktreeStatus[]>('list_worktrees').catch(() => []);
+        claimed = {};
+        for (const w of worktrees) if (!w.current && w.branch) claimed[w.branch] = w;
 
-        const head = await TAURI.invoke<{ detached: boolean; branch?: string; commit?: string }>('git_head_status');
+        const head = await TAURI.invoke<HeadStatus>('git_head_status');
         if (head?.branch) state.branch = head.branch;
-        const short = (head?.commit || '').slice(0, 7);
-        const label = head?.detached ? `Detached HEAD ${short ? '(' + short + ')' : ''}` : (state.branch || '—');
+        const label = head?.label || state.branch || '—';
         if (branchName) branchName.textContent = label;
         if (repoBranchEl) repoBranchEl.textContent = label;
+        // Detached: offer "New branch" to keep work done here
+        branchBtn?.setAttribute('title', head?.detached ? `${label}. Use "New branch" to create a branch here.` : '');
 
         renderBranches();
-        setBranchUIEnabled(!!state.branch);
+        setBranchUIEnabled(!!state.branch || !!head?.detached);
     } catch {
         state.branches = [];
         renderBranches();
@@ -52,14 +66,19 @@ function renderBranches() {
         const remote   = b.kind?.remote || '';
         let kindLabel = '';
         if (kindType.toLowerCase() === 'local') kindLabel = '<span class="badge kind">Local</span>';
+        const goneLabel = b.upstream_gone ? '<span class="badge gone" title="The upstream branch was deleted on the remote">Upstream gone</span>' : '';
         else if (kindType.toLowerCase() === 'remote') kindLabel = `<span class="badge kind">Remote:${remote || 'remote'}</span>`;
+        const wt = claimed[b.name];
+        const wtLabel = wt
+            ? `<span class="badge kind" title="Checked out in ${wt.path}${wt.changes ? ` (${wt.changes} changed)` : ''}">Worktree${wt.changes ? ' •' : ''}</span>`
+            : '';
         return `
       <li role="option" data-branch="${b.name}" aria-selected="${b.current ? 'true' : 'false'}">
         <span class="label">
           <span class="branch-dot" aria-hidden="true" style="box-shadow:none;${b.current?'':'opacity:.5'}"></span>
           <span class="name" title="${b.name}">${b.name}</span>
         </span>
-        ${b.current ? '<span class="badge">Current</span>' : kindLabel}
+        ${goneLabel}${wtLabel}${b.current ? '<span class="badge">Current</span>' : kindLabel}
       </li>`;
     }).join('');
 }
@@ -117,18 +136,48 @@ export function bindBranchUI() {
         const items: { label: string; action: () => void }[] = [];
         items.push({ label: 'Checkout', action: async () => {
             try { if (TAURI.has) await TAURI.invoke('git_checkout_branch', { name }); await loadBranches(); notify(`Switched to ${name}`); renderList(); }
-            catch { notify('Checkout failed'); }
+            catch (err) { notify(`Checkout failed: ${err}`); }
         }});
         items.push({ label: 'Merge into current…', action: async () => {
             if (name === cur) { notify('Cannot merge a branch into itself'); return; }
             const ok = window.confirm(`Merge '${name}' into '${cur}'?`);
             if (!ok) return;
-            try { if (TAURI.has) await TAURI.invoke('git_merge_branch', { name }); notify(`Merged '${name}' into '${cur}'`); await Promise.allSettled([renderList(), loadBranches()]); }
-            catch { notify('Merge failed'); }
+            try {
+                if (!TAURI.has) return;
+                const res = await TAURI.invoke<MergeOutcome>('git_merge_branch', { name });
+                switch (res.kind) {
+                    case 'up-to-date': notify(`'${cur}' is already up to date with '${name}'`); break;
+                    case 'fast-forward': notify(`Fast-forwarded '${cur}' to '${name}'`); break;
+                    case 'merged': notify(`Merged '${name}' into '${cur}'`); break;
+                    case 'conflicted': {
+                        const shown = res.files.slice(0, 3).join(', ') + (res.files.length > 3 ? ` and ${res.files.length - 3} more` : '');
+                        notify(`Merge stopped on conflicts in ${shown}; resolve them and commit`);
+                        break;
+                    }
+                }
+                await Promise.allSettled([renderList(), loadBranches()]);
+            }
+            catch (err) { notify(`Merge failed: ${err}`); }
         }});
         if (kind !== 'remote') {
             items.push({ label: '---', action: () => {} });
             items.push({ label: 'Rename…', action: () => openRenameBranch(name) });
+            if (b?.upstream_gone) {
+                items.push({ label: 'Re-link upstream…', action: async () => {
+                    let suggested: string[] = [];
+                    try { if (TAURI.has) suggested = await TAURI.invoke<string[]>('git_upstream_suggestions', { name }); } catch {}
+                    const upstream = window.prompt(`The upstream of '${name}' no longer exists. Track which remote branch instead?`, suggested[0] || '');
+                    if (!upstream?.trim()) return;
+                    try {
+                        if (TAURI.has) await TAURI.invoke('git_relink_or_delete_branch', { name, upstream: upstream.trim() });
+                        notify(`'${name}' now tracks '${upstream.trim()}'`);
+                        await loadBranches();
+                    } catch (e) {
+                        const msg = String(e || '');
+                        notify(`Re-link failed${msg ? `: ${msg}` : ''}`);
+                    }
+                }});
+            }
             items.push({ label: wantForce ? 'Force delete…' : 'Delete…', action: async () => {
                 if (name === cur) { notify('Cannot delete the current branch'); return; }
                 const ok = window.confirm(`${wantForce ? 'Force delete' : 'Delete'} local branch '${name}'? This cannot be undone.`);
@@ -150,6 +199,23 @@ export function bindBranchUI() {
                     } catch { notify('Force delete failed'); }
                 }
             }});
+        } else if (b?.kind?.remote) {
+            const remote = b.kind.remote;
+            const branch = name.startsWith(`${remote}/`) ? name.slice(remote.length + 1) : name;
+            items.push({ label: '---', action: () => {} });
+            items.push({ label: `Delete on ${remote}…`, action: async () => {
+                let ask = true;
+                try { ask = (await TAURI.invoke<GlobalSettings>('get_global_settings'))?.advanced?.confirm_remote_delete !== false; } catch {}
+                if (ask && !window.confirm(`Delete branch '${branch}' on '${remote}'? This affects everyone using the remote.`)) return;
+                try {
+                    if (TAURI.has) await TAURI.invoke('delete_remote_branch', { remote, branch, confirmed: true });
+                    notify(`Deleted '${branch}' on ${remote}`);
+                    await loadBranches();
+                } catch (e) {
+                    const msg = String(e || '');
+                    notify(`Delete failed${msg ? `: ${msg}` : ''}`);
+                }
+            }});
         }
         buildCtxMenu(items, x, y);
     });
@@ -190,3 +256,47 @@ export function bindBranchUI() {
     // Initial state
     setBranchUIEnabled(!!state.branch);
 }
+
+/* ---------------- quick actions ---------------- */
+
+/** Check out the branch that was checked out before the current one (`git checkout -`). */
+export async function switchToPreviousBranch() {
+    if (!TAURI.has) return;
+    const name = await TAURI.invoke<string | null>('previous_branch').catch(() => null);
+    if (!name) { notify('No previous branch'); return; }
+    try {
+        await TAURI.invoke('git_checkout_branch', { name });
+        await loadBranches();
+        notify(`Switched to ${name}`);
+        renderList();
+    } catch (err) {
+        notify(`Checkout failed: ${err}`);
+    }
+}
+
+/** Undo the last checkout or reset, after confirming what it will do. */
+export async function undoHeadMove() {
+    if (!TAURI.has) return;
+    const last = await TAURI.invoke<HeadMove | null>('last_head_move').catch(() => null);
+    if (!last) { notify('Nothing to undo'); return; }
+    if (!window.confirm(`${last.label}?\n\nUndoes: ${last.message}`)) return;
+    let force = false;
+    try {
+        const pushed = last.dropped.length ? await TAURI.invoke<PushedCommit[]>('pushed_commits', { ids: last.dropped }) : [];
+        if (pushed.length) {
+            const remotes = Array.from(new Set(pushed.flatMap(p => p.remotes))).join(', ');
+            const ok = window.confirm(
+                `${pushed.length} of the commits this takes off the branch ${pushed.length === 1 ? 'is' : 'are'} already on ${remotes}.\n\n` +
+                'Moving the branch back rewrites published history: pushing needs --force. Continue?'
+            );
+            if (!ok) return;
+            force = true;
+        }
+        const done = await TAURI.invoke<HeadMove>('undo_last_head_move', { force });
+        await loadBranches();
+        notify(done.label);
+        renderList();
+    } catch (err) {
+        notify(`Undo failed: ${err}`);
+    }
+}
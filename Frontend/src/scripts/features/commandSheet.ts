@@ -3,11 +3,15 @@ import { TAURI } from "../lib/tauri";
 import { notify } from "../lib/notify";
 import { openModal, closeModal, hydrate } from "../ui/modals";
 import { state } from "../state/state";
+import type { WorktreeStatus } from "../types";
 
-type Which = "clone" | "add" | "switch";
+type Which = "clone" | "init" | "add" | "switch";
 
 type Branch = { name: string; current?: boolean; kind?: { type?: string; remote?: string } };
-type RepoSummary = { path: string; current_branch: string; branches: Branch[] };
+type RepoSummary = { path: string; current_branch: string; branches: Branch[]; is_shallow?: boolean; worktrees?: WorktreeStatus[] };
+
+// Shallow repositories already offered a full fetch this session.
+const shallowOffered = new Set<string>();
 
 // Elements inside the modal
 let root: HTMLElement | null = null;
@@ -18,6 +22,11 @@ let cloneUrl: HTMLInputElement | null = null;
 let clonePath: HTMLInputElement | null = null;
 let doCloneBtn: HTMLButtonElement | null = null;
 
+let initPath: HTMLInputElement | null = null;
+let initIgnore: HTMLElement | null = null;
+let initLicense: HTMLSelectElement | null = null;
+let doInitBtn: HTMLButtonElement | null = null;
+
 let addPath: HTMLInputElement | null = null;
 let doAddBtn: HTMLButtonElement | null = null;
 
@@ -41,26 +50,75 @@ async function validateClone() {
     const url = cloneUrl?.value.trim();
     const dest = clonePath?.value.trim();
     try {
-        const res = await TAURI.invoke<{ ok: boolean; reason?: string }>("validate_clone_input", { url, dest });
+        const res = await TAURI.invoke<{ ok: boolean; reason?: string; suggestion?: string }>("validate_clone_input", { url, dest });
         setDisabled("do-clone", !res?.ok);
-        if (!res?.ok && res?.reason) notify(res.reason);
+        if (res?.reason) notify(res.reason);
+        const hint = el<HTMLElement>("#clone-url-hint", root || document);
+        if (hint && res?.suggestion) hint.textContent = `Will clone ${res.suggestion}`;
     } catch {
         setDisabled("do-clone", true);
     }
 }
 
+type RemoteRefs = { default_branch?: string | null; refs: { name: string; kind: "branch" | "tag" }[] };
+
+// Peek at the URL's branches/tags once the user stops typing, so they know it's reachable
+let refsSeq = 0;
+async function previewRemoteRefs() {
+    if (!TAURI.has) return;
+    const hint = el<HTMLElement>("#clone-url-hint", root || document);
+    const url = cloneUrl?.value.trim();
+    const seq = ++refsSeq;
+    if (!hint || !url) return;
+    try {
+        const res = await TAURI.invoke<RemoteRefs>("remote_refs", { url });
+        if (seq !== refsSeq) return;
+        const branches = res.refs.filter(r => r.kind === "branch").length;
+        const tags = res.refs.length - branches;
+        const def = res.default_branch ? ` (default: ${res.default_branch})` : "";
+        hint.textContent = `${branches} branch${branches === 1 ? "" : "es"}, ${tags} tag${tags === 1 ? "" : "s"}${def}`;
+    } catch {
+        if (seq === refsSeq) hint.textContent = "Could not list branches for this URL.";
+    }
+}
+
 async function validateAdd() {
     if (!TAURI.has) return;
     const path = addPath?.value.trim();
     try {
-        const res = await TAURI.invoke<{ ok: boolean; reason?: string }>("validate_add_path", { path });
+        const res = await TAURI.invoke<{ ok: boolean; reason?: string; suggestion?: string; kind?: string }>("validate_add_path", { path });
         setDisabled("do-add", !res?.ok);
         if (!res?.ok && res?.reason) notify(res.reason);
+        // Picked a folder inside a repository: use the top of its working tree
+        if (res?.ok && res.suggestion && addPath) addPath.value = res.suggestion;
     } catch {
         setDisabled("do-add", true);
     }
 }
 
+type TemplateInfo = { id: string; name: string; bundled: boolean };
+
+// Fill the .gitignore chips and license list of the New tab (once)
+async function loadTemplates() {
+    if (!TAURI.has || !initIgnore || initIgnore.childElementCount > 0) return;
+    try {
+        const t = await TAURI.invoke<{ gitignore: TemplateInfo[]; licenses: TemplateInfo[] }>("list_repo_templates");
+        const cloud = (i: TemplateInfo) => (i.bundled ? "" : " ☁");
+        initIgnore.innerHTML = t.gitignore
+            .map(i => `<button type="button" class="chip" data-id="${i.id}" aria-pressed="false">${i.name}${cloud(i)}</button>`)
+            .join("");
+        if (initLicense) {
+            initLicense.innerHTML = `<option value="">None</option>` +
+                t.licenses.map(i => `<option value="${i.id}">${i.name}${cloud(i)}</option>`).join("");
+        }
+    } catch {}
+}
+
+function validateInit() {
+    const path = initPath?.value.trim() || "";
+    setDisabled("do-init", !(path.startsWith("/") || /^[A-Za-z]:[\\/]/.test(path)));
+}
+
 /* ---------------- repo summary + broadcast ---------------- */
 
 async function refreshRepoSummary() {
@@ -74,6 +132,20 @@ async function refreshRepoSummary() {
     if (repoBranch) repoBranch.textContent = state.branch || "—";
     // Broadcast for any listeners (branches UI, status bar, etc.)
     window.dispatchEvent(new CustomEvent("app:repo-selected", { detail: { path: (info as any).path } }));
+    if (info.is_shallow) offerUnshallow(info.path);
+}
+
+/** A shallow clone's history, ahead/behind counts and merges can be incomplete; offer to fetch the rest once. */
+function offerUnshallow(path: string) {
+    if (shallowOffered.has(path)) return;
+    shallowOffered.add(path);
+    const ok = window.confirm(
+        "This is a shallow clone: history stops at the clone depth, and ahead/behind counts or merges "
+        + "may be incomplete.\n\nFetch the full history from origin now?");
+    if (!ok) { notify("Shallow clone: history may be incomplete"); return; }
+    TAURI.invoke("unshallow_repo", {})
+        .then(() => notify("Full history fetched"))
+        .catch((e: unknown) => notify(`Fetching full history failed: ${e}`));
 }
 
 /* ---------------- slider indicator helpers ---------------- */
@@ -117,10 +189,12 @@ function setSheet(which: Which) {
     });
 
     // Panels
-    (["clone", "add", "switch"] as Which[]).forEach((k) => {
+    (["clone", "init", "add", "switch"] as Which[]).forEach((k) => {
         panels[k].classList.toggle("hidden", k !== which);
     });
 
+    if (which === "init") loadTemplates();
+
     // Reposition the slider after layout changes
     positionIndicator();
 }
@@ -131,7 +205,7 @@ export function openSheet(which: Which = "clone") {
     setSheet(which);
 
     // Focus first relevant input without scrolling
-    const focusId = which === "clone" ? "clone-url" : which === "add" ? "add-path" : null;
+    const focusId = which === "clone" ? "clone-url" : which === "init" ? "init-path" : which === "add" ? "add-path" : null;
     if (focusId) setTimeout(() => root?.querySelector<HTMLInputElement>("#" + focusId)?.focus({ preventScroll: true }), 0);
 
     // Align the pill once frame is painted
@@ -155,6 +229,7 @@ export function bindCommandSheet() {
     tabs = Array.from(root.querySelectorAll<HTMLButtonElement>(".sheet-head .seg .seg-btn"));
     panels = {
         clone: root.querySelector("#sheet-clone") as HTMLElement,
+        init: root.querySelector("#sheet-init") as HTMLElement,
         add: root.querySelector("#sheet-add") as HTMLElement,
         switch: root.querySelector("#sheet-switch") as HTMLElement,
     };
@@ -165,6 +240,11 @@ export function bindCommandSheet() {
     clonePath = el<HTMLInputElement>("#clone-path", root);
     doCloneBtn = el<HTMLButtonElement>("#do-clone", root);
 
+    initPath = el<HTMLInputElement>("#init-path", root);
+    initIgnore = el<HTMLElement>("#init-gitignore", root);
+    initLicense = el<HTMLSelectElement>("#init-license", root);
+    doInitBtn = el<HTMLButtonElement>("#do-init", root);
+
     addPath = el<HTMLInputElement>("#add-path", root);
     doAddBtn = el<HTMLButtonElement>("#do-add", root);
 
@@ -198,9 +278,16 @@ export function bindCommandSheet() {
 
     // Validation
     cloneUrl?.addEventListener("input", validateClone);
+    cloneUrl?.addEventListener("change", previewRemoteRefs);
     clonePath?.addEventListener("input", validateClone);
+    initPath?.addEventListener("input", validateInit);
     addPath?.addEventListener("input", validateAdd);
 
+    initIgnore?.addEventListener("click", (e) => {
+        const chip = (e.target as HTMLElement).closest<HTMLElement>(".chip");
+        if (chip) chip.setAttribute("aria-pressed", chip.getAttribute("aria-pressed") === "true" ? "false" : "true");
+    });
+
     // Browse buttons
     el<HTMLButtonElement>("#browse-clone", root)?.addEventListener("click", async () => {
         if (!TAURI.has) return;
@@ -213,6 +300,17 @@ export function bindCommandSheet() {
         } catch {}
     });
 
+    el<HTMLButtonElement>("#browse-init", root)?.addEventListener("click", async () => {
+        if (!TAURI.has) return;
+        try {
+            const dir = await TAURI.invoke<string>("browse_directory", { purpose: "init_repo" });
+            if (dir && initPath) {
+                initPath.value = dir;
+                validateInit();
+            }
+        } catch {}
+    });
+
     el<HTMLButtonElement>("#browse-add", root)?.addEventListener("click", async () => {
         if (!TAURI.has) return;
         try {
@@ -239,6 +337,26 @@ export function bindCommandSheet() {
         }
     });
 
+    doInitBtn?.addEventListener("click", async () => {
+        const path = initPath?.value.trim();
+        if (!path) return;
+        const gitignore = Array.from(initIgnore?.querySelectorAll<HTMLElement>('.chip[aria-pressed="true"]') ?? [])
+            .map(c => c.dataset.id || "")
+            .filter(Boolean);
+        const license = initLicense?.value || null;
+        try {
+            if (doInitBtn) doInitBtn.disabled = true;
+            if (TAURI.has) await TAURI.invoke("init_repo", { path, gitignore, license });
+            await refreshRepoSummary();               // ensure state + event
+            notify(`Created ${path}`);
+            closeSheet();
+        } catch (e) {
+            notify(`Create failed: ${e}`);
+        } finally {
+            validateInit();
+        }
+    });
+
     doAddBtn?.addEventListener("click", async () => {
         const path = addPath?.value.trim();
         if (!path) return;
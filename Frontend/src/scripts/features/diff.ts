@@ -1,17 +1,26 @@
 import { qs } from '../lib/dom';
 import { TAURI } from '../lib/tauri';
+import { invokeChunked } from '../lib/chunked';
 import { notify } from '../lib/notify';
-import { state } from '../state/state';
+import { state, hasChanges } from '../state/state';
 import { hydrateStatus, hydrateCommits } from './repo';
+import type { CommitDraftState, CommitOptions, LargeFileWarning, SecretScanReport } from '../types';
+
+// Pending `save_commit_draft`; a commit cancels it so the committed message is not saved again.
+let draftSaveTimer: number | undefined;
 
 export function bindCommit() {
     const commitBtn     = qs<HTMLButtonElement>('#commit-btn');
     const commitSummary = qs<HTMLInputElement>('#commit-summary');
     const commitDesc    = qs<HTMLTextAreaElement>('#commit-desc');
+    const commitAuthor  = qs<HTMLInputElement>('#commit-author');
+    const commitDate    = qs<HTMLInputElement>('#commit-author-date');
 
     commitBtn?.addEventListener('click', async () => {
         const summary = commitSummary?.value.trim() || '';
         if (!summary) { commitSummary?.focus(); notify('Summary is required'); return; }
+        const options = commitOptions(commitAuthor?.value || '', commitDate?.value || '');
+        if (!options) { commitAuthor?.focus(); notify('Author must look like: Name <email>'); return; }
         const hunksMap: Record<string, number[]> = (state as any).selectedHunksByFile || {};
         const hasHunks = Object.keys(hunksMap).some(p => Array.isArray(hunksMap[p]) && hunksMap[p].length > 0);
         const selectedFiles = state.selectedFiles ? Array.from(state.selectedFiles) : [];
@@ -47,7 +56,7 @@ export function bindCommit() {
             let combinedPatch = '';
             for (const path of partialFiles) {
                 let lines: string[] = [];
-                try { lines = await TAURI.invoke<string[]>('git_diff_file', { path }); } catch {}
+                try { lines = await invokeChunked<string>('git_diff_file', { path }); } catch {}
                 if (!Array.isArray(lines) || lines.length === 0) continue;
                 combinedPatch += buildPatchForSelectedHunks(path, lines, hunksMap[path]) + '\n';
             }
@@ -55,15 +64,20 @@ export function bindCommit() {
             const fullFiles = selectedFiles.filter(f => !partialFiles.includes(f));
             if (TAURI.has) {
                 if (combinedPatch.trim().length > 0 || fullFiles.length > 0) {
-                    await TAURI.invoke('commit_patch_and_files', { summary, description, patch: combinedPatch, files: fullFiles });
+                    if (!(await confirmSecretScan(fullFiles, combinedPatch))) { notify('Commit cancelled'); return; }
+                    if (!(await confirmLargeFiles(fullFiles))) { notify('Commit cancelled'); return; }
+                    await TAURI.invoke('commit_patch_and_files', { summary, description, patch: combinedPatch, files: fullFiles, options });
                 } else {
                     notify('Select files or hunks to commit');
                     return;
                 }
             }
+            window.clearTimeout(draftSaveTimer);
             notify(`Committed to ${state.branch}: ${summary}`);
             if (commitSummary) commitSummary.value = '';
             if (commitDesc)    commitDesc.value = '';
+            if (commitAuthor)  commitAuthor.value = '';
+            if (commitDate)    commitDate.value = '';
             // Clear selection state
             state.selectedFiles.clear();
             state.selectedHunks = [];
@@ -77,6 +91,100 @@ export function bindCommit() {
             clearBusy('Ready');
         }
     });
+
+    bindCommitDraft(commitSummary, commitDesc);
+}
+
+// Author override from the commit panel: `Name <email>` and a local date-time, both optional.
+// Returns null when the author is filled in but malformed.
+function commitOptions(author: string, date: string): CommitOptions | null {
+    const options: CommitOptions = {};
+    const a = author.trim();
+    if (a) {
+        const m = /^([^<>]+?)\s*<([^<>]+)>$/.exec(a);
+        if (!m) return null;
+        options.author = { name: m[1].trim(), email: m[2].trim() };
+    }
+    if (date) {
+        const d = new Date(date);
+        if (!isNaN(d.getTime())) {
+            options.author_date = { seconds: Math.floor(d.getTime() / 1000), offset_minutes: -d.getTimezoneOffset() };
+        }
+    }
+    return options;
+}
+
+// Keep the commit message as a per-branch draft in the backend (it survives restarts and
+// is dropped by a successful commit), and start an empty box from the repo's template.
+function bindCommitDraft(summary: HTMLInputElement | null, desc: HTMLTextAreaElement | null) {
+    if (!TAURI.has || !summary || !desc) return;
+    let loadedBranch: string | null = null;
+
+    const load = async () => {
+        if (!hasChanges()) return;
+        if (loadedBranch === state.branch && (summary.value || desc.value)) return;
+        let res: CommitDraftState;
+        try { res = await TAURI.invoke<CommitDraftState>('get_commit_draft'); } catch { return; }
+        const switched = loadedBranch !== null && res.branch !== loadedBranch;
+        // Text typed on the previous branch stays with it; never clobber typing otherwise.
+        if (switched) { window.clearTimeout(draftSaveTimer); summary.value = ''; desc.value = ''; }
+        loadedBranch = res.branch;
+        if (summary.value || desc.value) return;
+        if (res.draft) {
+            summary.value = res.draft.summary;
+            desc.value = res.draft.description;
+        } else if (res.template) {
+            desc.value = res.template.text;
+        }
+        summary.dispatchEvent(new Event('input'));
+    };
+
+    const save = () => {
+        window.clearTimeout(draftSaveTimer);
+        draftSaveTimer = window.setTimeout(() => {
+            TAURI.invoke('save_commit_draft', { summary: summary.value, description: desc.value }).catch(() => {});
+        }, 500);
+    };
+
+    summary.addEventListener('input', (e) => { if (e.isTrusted) save(); });
+    desc.addEventListener('input', save);
+    window.addEventListener('app:repo-selected', () => {
+        window.clearTimeout(draftSaveTimer);
+        loadedBranch = null;
+        summary.value = '';
+        desc.value = '';
+        void load();
+    });
+    window.addEventListener('app:branches-updated', () => void load());
+    window.addEventListener('app:status-updated', () => void load());
+}
+
+// Show secret-scan findings; true to go ahead (never in "block" mode).
+async function confirmSecretScan(files: string[], patch: string): Promise<boolean> {
+    let report: SecretScanReport | null = null;
+    try { report = await TAURI.invoke<SecretScanReport>('scan_secrets', { files, patch }); } catch { return true; }
+    if (!report?.findings?.length) return true;
+    const list = report.findings.slice(0, 10).map(f => `  • ${f.path}:${f.line} — ${f.description}\n      ${f.excerpt}`).join('\n');
+    const more = report.findings.length > 10 ? `\n  …and ${report.findings.length - 10} more` : '';
+    const head = `Possible secrets in this commit:\n${list}${more}`;
+    if (report.mode === 'block') {
+        window.alert(`${head}\n\nRemove them (or mark the line with 'openvcs:allow-secret') before committing.`);
+        return false;
+    }
+    return window.confirm(`${head}\n\nCommit anyway?`);
+}
+
+// Ask before committing files over the configured size; true to go ahead.
+async function confirmLargeFiles(files: string[]): Promise<boolean> {
+    let warning: LargeFileWarning | null = null;
+    try { warning = await TAURI.invoke<LargeFileWarning | null>('check_large_files', { files }); } catch { return true; }
+    if (!warning?.files?.length) return true;
+    const mb = (n: number) => `${(n / (1024 * 1024)).toFixed(1)} MB`;
+    const list = warning.files.slice(0, 10).map(f => `  • ${f.path} (${mb(f.size)})`).join('\n');
+    const more = warning.files.length > 10 ? `\n  …and ${warning.files.length - 10} more` : '';
+    return window.confirm(
+        `${warning.files.length} file(s) exceed ${mb(warning.threshold_bytes)}:\n${list}${more}\n\n${warning.suggestion}\n\nCommit anyway?`
+    );
 }
 
 // Construct a minimal patch for one file by combining the file header and selected hunks.
@@ -2,8 +2,11 @@
 import { qs, qsa, escapeHtml } from '../lib/dom';
 import { buildCtxMenu } from '../lib/menu';
 import { TAURI } from '../lib/tauri';
+import { invokeChunked } from '../lib/chunked';
+import { loadBranchPages } from '../lib/branchPages';
 import { notify } from '../lib/notify';
 import { state, prefs, statusLabel, statusClass } from '../state/state';
+import type { CaseCollision, CommitItem, CommitRecord, FetchNeed, FileStatus, HeadStatus, LogWindow, ModeChange, Operation, Prefetch, PushedCommit } from '../types';
 
 const filterInput   = qs<HTMLInputElement>('#filter');
 const selectAllBox  = qs<HTMLInputElement>('#select-all');
@@ -23,6 +26,8 @@ let dragStartIndex: number = -1;
 let dragCurrentIndex: number = -1;
 let dragPreDiff = new Set<string>();
 let dragPrePicked = new Set<string>();
+// HEAD commit detail read ahead after opening the repository
+let prefetchedDetail: CommitRecord | null = null;
 
 // Global guards to suppress native text selection/drag while we paint-select
 document.addEventListener('selectstart', (e) => { if (isDragSelecting) e.preventDefault(); }, true);
@@ -68,12 +73,14 @@ export function renderList() {
     if (isHistory) {
         const commits = (state.commits || []).filter(c =>
             !q || c.msg?.toLowerCase().includes(q) || c.id?.includes(q)
+                || c.author_name?.toLowerCase().includes(q)
         );
 
         countEl.textContent = `${commits.length} commit${commits.length === 1 ? '' : 's'}`;
 
         if (!commits.length) {
-            listEl.innerHTML = `<li class="row" aria-disabled="true"><div class="file">No commits loaded.</div></li>`;
+            const empty = state.unborn ? 'No commits yet — create your first commit.' : 'No commits loaded.';
+            listEl.innerHTML = `<li class="row" aria-disabled="true"><div class="file">${empty}</div></li>`;
             diffHeadPath.textContent = 'Commit details';
             diffEl.innerHTML = '';
             return;
@@ -89,22 +96,7 @@ export function renderList() {
             listEl.appendChild(info);
         }
 
-        commits.forEach((c, i) => {
-            const li = document.createElement('li');
-            li.className = 'row commit';
-            const short = (c.id || '').slice(0, 7);
-            const whenRaw = String(c.meta || '').split('•')[0].trim();
-            const rel = formatTimeAgo(whenRaw);
-            const exact = (c.meta || '').trim();
-            const statusTag = i < ahead ? `<span class="tag up" title="Not on remote yet">↑ outgoing</span>` : '';
-            li.innerHTML = `
-        <span class="badge hash" title="${escapeHtml(c.id || '')}">${escapeHtml(short)}</span>
-        <div class="file" title="${escapeHtml(c.msg || '')}">${escapeHtml(c.msg || '(no message)')}</div>
-        ${statusTag}
-        <span class="badge time" title="${escapeHtml(exact)}">${escapeHtml(rel)}</span>`;
-            li.addEventListener('click', () => selectHistory(c, i));
-            listEl.appendChild(li);
-        });
+        commits.forEach((c, i) => listEl.appendChild(commitRow(c, i, !q, ahead)));
         selectHistory(commits[0], 0);
         return;
     }
@@ -113,7 +105,7 @@ export function renderList() {
         !q || (f.path || '').toLowerCase().includes(q)
     );
     countEl.textContent = `${files.length} file${files.length === 1 ? '' : 's'}`;
-    updateSelectAllState(files);
+    updateSelectAllState(files.filter(f => f.status !== '!'));
 
     if (!files.length) {
         listEl.innerHTML = `<li class="row" aria-disabled="true"><div class="file">No changes. Clone or add a repository to get started.</div></li>`;
@@ -131,12 +123,16 @@ export function renderList() {
         li.setAttribute('data-path', f.path || '');
         const picked = state.selectedFiles.has(f.path);
         const diffsel = state.diffSelectedFiles.has(f.path);
+        const ignored = f.status === '!';
         li.classList.toggle('picked', picked);
         li.classList.toggle('diffsel', diffsel);
+        li.classList.toggle('ignored', ignored);
+        // Ignored entries are listed for information only: nothing to pick for a commit
         li.innerHTML = `
-      <input type="checkbox" class="pick" aria-label="Select file" ${picked ? 'checked' : ''} />
-      <span class="status ${statusClass(f.status)}">${escapeHtml(f.status || '')}</span>
-      <div class="file" title="${escapeHtml(f.path || '')}">${escapeHtml(f.path || '')}</div>
+      <input type="checkbox" class="pick" aria-label="Select file" ${picked ? 'checked' : ''} ${ignored ? 'disabled' : ''} />
+      <span class="status ${statusClass(f.status)}" title="${statusLabel(f.status)}">${escapeHtml(f.status || '')}</span>
+      <div class="file" title="${escapeHtml(f.path || '')}">${escapeHtml(f.path || '')}${dirNote(f)}</div>
+      ${modeBadge(f.mode_change)}${churnBadge(f)}
       <span class="pick-mark" aria-hidden="true">✓</span>`;
         li.addEventListener('click', (e) => onFileClick(e as MouseEvent, f, i, files));
         li.addEventListener('mousedown', (e) => onFileMouseDown(e as MouseEvent, f, i, files, li));
@@ -153,7 +149,7 @@ export function renderList() {
         cb?.addEventListener('click', (ev) => {
             ev.stopPropagation();
             toggleFilePick(f.path, !!cb?.checked);
-            updateSelectAllState(files);
+            updateSelectAllState(files.filter(f => f.status !== '!'));
             li.classList.toggle('picked', !!cb?.checked && !(cb as any).indeterminate);
         });
         listEl.appendChild(li);
@@ -171,22 +167,49 @@ export function renderList() {
     updateCommitButton();
 }
 
+// `index` is the row's position in the list; it is the commit's index when `unfiltered`
+function commitRow(c: CommitItem, index: number, unfiltered: boolean, ahead: number): HTMLLIElement {
+    const li = document.createElement('li');
+    li.className = 'row commit';
+    const short = c.short_id || (c.id || '').slice(0, 7);
+    const seconds = c.author_time?.seconds ?? c.timestamp ?? 0;
+    const rel = formatTimeAgo(seconds);
+    const exact = `${new Date(seconds * 1000).toLocaleString()}${c.author_name ? ` • ${c.author_name}` : ''}`;
+    const statusTag = index < ahead ? `<span class="tag up" title="Not on remote yet">↑ outgoing</span>` : '';
+    li.innerHTML = `
+        <span class="badge hash" title="${escapeHtml(c.id || '')}">${escapeHtml(short)}</span>
+        <div class="file" title="${escapeHtml(c.msg || '')}">${escapeHtml(c.msg || '(no message)')}</div>
+        ${statusTag}
+        <span class="badge time" title="${escapeHtml(exact)}">${escapeHtml(rel)}</span>`;
+    li.addEventListener('click', () => selectHistory(c, index));
+    // Squashing needs consecutive commits, so only offer it on the unfiltered list
+    if (unfiltered) li.addEventListener('contextmenu', (ev) => void onCommitContextMenu(ev, index));
+    return li;
+}
+
 function highlightRow(index: number) {
     const sel = (prefs.tab === 'history') ? '.row.commit' : '.row';
     const rows = qsa<HTMLElement>(sel, listEl || (undefined as any));
     rows.forEach((el, i) => el.classList.toggle('active', i === index));
 }
 
-async function selectFile(file: { path: string }, index: number) {
+async function selectFile(file: { path: string; file_count?: number | null }, index: number) {
     if (!diffHeadPath || !diffEl) return;
     highlightRow(index);
     diffHeadPath.textContent = file.path || '(unknown file)';
+    if (file.path?.endsWith('/')) {
+        // A whole directory (new or ignored) has no single diff to show
+        state.currentFile = file.path;
+        state.currentDiff = [];
+        diffEl.innerHTML = `<div class="hunk"><div class="hline"><div class="gutter"></div><div class="code">${escapeHtml(formatFileCount(file.file_count))} in this folder</div></div></div>`;
+        return;
+    }
     diffEl.innerHTML = '<div class="hunk"><div class="hline"><div class="gutter"></div><div class="code">Loading…</div></div></div>';
 
     try {
         let lines: string[] = [];
         if (TAURI.has && file.path) {
-            lines = await TAURI.invoke<string[]>('git_diff_file', { path: file.path });
+            lines = await invokeChunked<string>('git_diff_file', { path: file.path });
         }
         state.currentFile = file.path;
         state.currentDiff = lines || [];
@@ -243,7 +266,7 @@ async function selectFile(file: { path: string }, index: number) {
                         let patch = '';
                         for (const p of filesWithSel) {
                             let lines: string[] = [];
-                            try { lines = await TAURI.invoke<string[]>('git_diff_file', { path: p }); } catch {}
+                            try { lines = await invokeChunked<string>('git_diff_file', { path: p }); } catch {}
                             if (!Array.isArray(lines) || lines.length === 0) continue;
                             patch += buildPatchForSelectedHunks(p, lines, hunksMap[p]) + '\n';
                         }
@@ -275,9 +298,23 @@ async function selectFile(file: { path: string }, index: number) {
     }
 }
 
+/** Add the trailers of `detail` below the Message row of the commit header. */
+function showTrailers(detail: CommitRecord | null) {
+    const header = diffEl?.querySelector('.hunk');
+    if (!header || !detail?.trailers?.length) return;
+    header.insertAdjacentHTML('beforeend', detail.trailers.map(t =>
+        `<div class="hline trailer"><div class="gutter">${escapeHtml(t.key)}</div><div class="code">${escapeHtml(t.value)}</div></div>`,
+    ).join(''));
+}
+
 async function selectHistory(commit: any, index: number) {
     if (!diffHeadPath || !diffEl) return;
     highlightRow(index);
+    const detail: Promise<CommitRecord | null> = prefetchedDetail && prefetchedDetail.id === commit.id
+        ? Promise.resolve(prefetchedDetail)
+        : TAURI.has && commit.id
+            ? TAURI.invoke<CommitRecord>('git_commit_detail', { id: commit.id }).catch(() => null)
+            : Promise.resolve(null);
     const id = (commit.id || '').slice(0,7);
     diffHeadPath.textContent = `Commit ${id || '(unknown)'}`;
     diffEl.innerHTML = `
@@ -292,7 +329,16 @@ async function selectHistory(commit: any, index: number) {
     try {
         let lines: string[] = [];
         if (TAURI.has && commit.id) {
-            lines = await TAURI.invoke<string[]>('git_diff_commit', { id: commit.id });
+            // Partial clone: say what has to come from the remote (or that it won't, offline)
+            const need = await TAURI.invoke<FetchNeed | null>('git_commit_fetch_need', { id: commit.id }).catch(() => null);
+            const loading = diffEl.querySelector('.hunk:last-child .code');
+            if (need && need.offline) {
+                if (loading) loading.textContent = `Not downloaded: ${need.paths.length} file(s) of this commit are only on ${need.remote} (partial clone, offline mode)`;
+                showTrailers(await detail);
+                return;
+            }
+            if (need && loading) loading.textContent = `Fetching ${need.paths.length} file(s) from ${need.remote}…`;
+            lines = await invokeChunked<string>('git_diff_commit', { id: commit.id });
         }
         const files = parseCommitDiffByFile(lines || []);
         if (files.length === 0) {
@@ -305,6 +351,7 @@ async function selectHistory(commit: any, index: number) {
       <div class="hline"><div class="gutter">Message</div><div class="code">${escapeHtml(commit.msg || '')}</div></div>
     </div>
     ${label}${diffHtml || ''}`;
+            showTrailers(await detail);
             return;
         }
 
@@ -324,8 +371,19 @@ async function selectHistory(commit: any, index: number) {
       <div class="hline"><div class="gutter">Author</div><div class="code">${escapeHtml(commit.author || 'You <you@example.com>')}</div></div>
       <div class="hline"><div class="gutter">Message</div><div class="code">${escapeHtml(commit.msg || '')}</div></div>
     </div>
-    <div class="hunk"><div class="hline"><div class="gutter"></div><div class="code">${files.length} file${files.length===1?'':'s'} changed</div></div></div>
+    <div class="hunk"><div class="hline"><div class="gutter"></div><div class="code commit-stat">${files.length} file${files.length===1?'':'s'} changed</div></div></div>
     <div class="commit-diff" style="display:flex; min-height: 240px; gap: 8px;">${sidebar}${right}</div>`;
+        showTrailers(await detail);
+
+        // Diffstat comes from the backend cache; fill it in when it arrives
+        if (TAURI.has && commit.id) {
+            TAURI.invoke<{ additions: number; deletions: number }>('git_commit_stats', { id: commit.id })
+                .then(st => {
+                    const el = diffEl?.querySelector('.commit-stat');
+                    if (el) el.textContent += `, +${st.additions} −${st.deletions}`;
+                })
+                .catch(() => {});
+        }
 
         // Sidebar interactions
         const sideEl = diffEl.querySelector('.commit-files');
@@ -355,43 +413,46 @@ async function selectHistory(commit: any, index: number) {
 export async function hydrateBranches() {
     if (!TAURI.has) return;
     try {
-        const list = await TAURI.invoke<any[]>('git_list_branches');
-        const head = await TAURI.invoke<{ detached: boolean; branch?: string; commit?: string }>('git_head_status').catch(() => ({ detached: false } as any));
+        const head = await TAURI.invoke<HeadStatus>('git_head_status').catch(() => ({ detached: false } as any));
+        // A fresh repository has no branches to list yet, but HEAD already names one
+        state.unborn = head?.state?.kind === 'unborn';
 
-        const has = Array.isArray(list) && list.length > 0;
-        state.hasRepo = state.hasRepo || has; // don’t flip to false if another hydrate confirms true
+        // Each page (current branch first) is applied as it arrives
+        await loadBranchPages('hydrate', (list) => {
+            const has = list.length > 0;
+            state.hasRepo = state.hasRepo || has; // don’t flip to false if another hydrate confirms true
 
-        if (has) {
-            state.branches = list as any;
-            state.branch = (head as any)?.branch || (list.find((b: any) => b.current)?.name) || state.branch || 'main';
-            window.dispatchEvent(new CustomEvent('app:branches-updated'));
-        }
+            if (state.unborn && head.branch) {
+                state.hasRepo = true;
+                state.branches = [];
+                state.branch = head.branch;
+                window.dispatchEvent(new CustomEvent('app:branches-updated'));
+            } else if (has) {
+                state.branches = list as any;
+                state.branch = (head as any)?.branch || (list.find((b: any) => b.current)?.name) || state.branch || 'main';
+                window.dispatchEvent(new CustomEvent('app:branches-updated'));
+            }
+        });
     } catch (e) {
         // Don’t nuke state here; status/summary calls will decide hasRepo
         console.warn('hydrateBranches failed', e);
     }
 }
 
+/** Once a plain `git_status` takes longer than this, or lists more files, switch to streamed status. */
+const SLOW_STATUS_MS = 1000;
+const LARGE_STATUS_FILES = 5000;
+let streamStatus = false;
+
 /** Status drives file list; on failure we clear files but don’t assert repo absence unless it’s consistent */
 export async function hydrateStatus() {
     if (!TAURI.has) return;
     try {
+        if (streamStatus) { await hydrateStatusStreamed(); return; }
+        const started = performance.now();
         const result = await TAURI.invoke<{ files: any[]; ahead?: number; behind?: number }>('git_status');
-        state.hasRepo = true;
-        state.files = Array.isArray(result?.files) ? (result.files as any) : [];
-        // Default-select all files unless the user has modified selection
-        const currentPaths = new Set((state.files || []).map(f => f.path));
-        if (state.defaultSelectAll) {
-            state.selectedFiles = new Set(Array.from(currentPaths));
-        } else {
-            // prune stale selections no longer present
-            state.selectedFiles.forEach(p => { if (!currentPaths.has(p)) state.selectedFiles.delete(p); });
-        }
-        // ahead/behind are optional in older backends; default to 0
-        (state as any).ahead = Number((result as any)?.ahead || 0);
-        (state as any).behind = Number((result as any)?.behind || 0);
-        renderList();
-        window.dispatchEvent(new CustomEvent('app:status-updated'));
+        streamStatus = performance.now() - started > SLOW_STATUS_MS || (result?.files?.length ?? 0) > LARGE_STATUS_FILES;
+        applyStatus(result);
     } catch (e) {
         console.warn('hydrateStatus failed', e);
         state.files = [];
@@ -401,16 +462,174 @@ export async function hydrateStatus() {
     }
 }
 
+/** Large worktrees: render `repo:status-chunk` batches as they arrive, then apply the totals. */
+async function hydrateStatusStreamed() {
+    const files: any[] = [];
+    const ahead = Number((state as any).ahead || 0);
+    const behind = Number((state as any).behind || 0);
+    const sub: any = await TAURI.listen<{ files: any[] }>('repo:status-chunk', ({ payload }) => {
+        files.push(...(payload?.files ?? []));
+        applyStatus({ files: files.slice(), ahead, behind }, false);
+    });
+    try {
+        const totals = await TAURI.invoke<{ total: number; ahead: number; behind: number }>('git_status_stream');
+        applyStatus({ files, ahead: totals?.ahead, behind: totals?.behind });
+    } finally {
+        if (typeof sub === 'function') sub(); else sub?.unlisten?.();
+    }
+}
+
+/** Render a status payload (from `git_status` or a pushed `repo:status` event), then fetch
+ *  the line counts unless more of it is still streaming in. */
+export function applyStatus(result: { files: any[]; ahead?: number; behind?: number; case_collisions?: CaseCollision[] }, complete = true) {
+    state.hasRepo = true;
+    state.files = Array.isArray(result?.files) ? (result.files as any) : [];
+    // Default-select all files unless the user has modified selection; ignored ones are never picked
+    const currentPaths = new Set((state.files || []).filter(f => f.status !== '!').map(f => f.path));
+    if (state.defaultSelectAll) {
+        state.selectedFiles = new Set(Array.from(currentPaths));
+    } else {
+        // prune stale selections no longer present
+        state.selectedFiles.forEach(p => { if (!currentPaths.has(p)) state.selectedFiles.delete(p); });
+    }
+    // ahead/behind are optional in older backends; default to 0
+    (state as any).ahead = Number((result as any)?.ahead || 0);
+    (state as any).behind = Number((result as any)?.behind || 0);
+    renderList();
+    window.dispatchEvent(new CustomEvent('app:status-updated'));
+    warnCaseCollisions(result?.case_collisions || []);
+    if (complete) void loadFileStats();
+}
+
+let statsGeneration = 0;
+
+/** Fill in the "+12 −4" badges of the listed files; a newer status supersedes the request. */
+async function loadFileStats() {
+    if (!TAURI.has) return;
+    const generation = ++statsGeneration;
+    let stats: FileStatus[];
+    try {
+        stats = await TAURI.invoke<FileStatus[]>('git_worktree_stats');
+    } catch (e) {
+        console.warn('loadFileStats failed', e);
+        return;
+    }
+    if (generation !== statsGeneration) return;
+    const byPath = new Map(stats.map(st => [st.path, st]));
+    const rows = new Map<string, HTMLElement>();
+    if (prefs.tab === 'changes') listEl?.querySelectorAll<HTMLElement>('li.row[data-path]').forEach(li => rows.set(li.dataset.path || '', li));
+    for (const f of state.files || []) {
+        const st = byPath.get(f.path);
+        if (!st) continue;
+        Object.assign(f, { additions: st.additions, deletions: st.deletions, size_delta: st.size_delta, mode_change: st.mode_change });
+        const row = rows.get(f.path);
+        // Status can't tell a type change from a modification; the diff can
+        if (st.status === 'T' && f.status === 'M') {
+            f.status = 'T';
+            const badge = row?.querySelector<HTMLElement>('.status');
+            if (badge) { badge.textContent = 'T'; badge.title = statusLabel('T'); badge.className = `status ${statusClass('T')}`; }
+        }
+        row?.querySelectorAll('.mode-badge, .churn').forEach(el => el.remove());
+        row?.querySelector('.pick-mark')?.insertAdjacentHTML('beforebegin', `${modeBadge(f.mode_change)}${churnBadge(f)}`);
+    }
+}
+
+let lastCollisionKey = '';
+
+// Offer to untrack all but one spelling of paths that collide on a case-insensitive filesystem.
+// Asks once per distinct set of collisions.
+function warnCaseCollisions(collisions: CaseCollision[]) {
+    const key = collisions.map(c => c.paths.join('|')).join('\n');
+    if (key === lastCollisionKey) return;
+    lastCollisionKey = key;
+    if (!collisions.length) return;
+
+    const list = collisions.slice(0, 10).map(c => `  ${c.paths.join('  /  ')}`).join('\n');
+    const more = collisions.length > 10 ? `\n  …and ${collisions.length - 10} more` : '';
+    const ok = window.confirm(
+        `These tracked files differ only by case, so only one of each can exist on this filesystem:\n\n${list}${more}\n\n`
+        + 'Untrack all but the last spelling of each? The files stay on disk; commit to record the removal.');
+    if (!ok) return;
+    const extra = collisions.flatMap(c => c.paths.slice(0, -1));
+    TAURI.invoke('git_untrack_paths', { paths: extra })
+        .then(() => notify(`Untracked ${extra.length} file(s); commit to finish`))
+        .catch((e: unknown) => notify(`Untrack failed: ${e}`));
+}
+
+// History is read in windows from the backend's open walk as the list scrolls
+const HISTORY_WINDOW = 200;
+let loadingCommits = false;
+
 export async function hydrateCommits() {
     if (!TAURI.has) return;
     try {
-        const list = await TAURI.invoke<any[]>('git_log', { limit: 100 });
+        const w = await TAURI.invoke<LogWindow>('git_log_window', { after: HISTORY_WINDOW });
         state.hasRepo = true;
-        state.commits = Array.isArray(list) ? (list as any) : [];
+        state.commits = w.items;
+        state.commitsComplete = w.complete;
         if (prefs.tab === 'history') renderList();
     } catch (e) {
         console.warn('hydrateCommits failed', e);
         state.commits = [];
+        state.commitsComplete = true;
+    }
+}
+
+/** Append the next window of history; rows are added without repainting the list. */
+async function loadMoreCommits() {
+    if (loadingCommits || state.commitsComplete || !TAURI.has) return;
+    loadingCommits = true;
+    const from = state.commits.length;
+    const head = state.commits[0]?.id;
+    try {
+        const w = await TAURI.invoke<LogWindow>('git_log_window', { index: from, after: HISTORY_WINDOW });
+        // A refresh replaced the list meanwhile
+        if (state.commits.length !== from || state.commits[0]?.id !== head) return;
+        state.commits = state.commits.concat(w.items);
+        state.commitsComplete = w.complete || !w.items.length;
+        if (prefs.tab === 'history') appendCommitRows(w.items);
+    } catch (e) {
+        console.warn('loadMoreCommits failed', e);
+    } finally {
+        loadingCommits = false;
+    }
+}
+
+function appendCommitRows(items: CommitItem[]) {
+    if (!listEl || !countEl || !filterInput) return;
+    const q = filterInput.value.trim().toLowerCase();
+    const ahead = Number((state as any).ahead || 0);
+    let index = qsa<HTMLElement>('.row.commit', listEl).length;
+    for (const c of items) {
+        if (q && !(c.msg?.toLowerCase().includes(q) || c.id?.includes(q) || c.author_name?.toLowerCase().includes(q))) continue;
+        listEl.appendChild(commitRow(c, index++, !q, ahead));
+    }
+    countEl.textContent = `${index} commit${index === 1 ? '' : 's'}`;
+}
+
+// Read more history when the list is scrolled near its end
+listEl?.addEventListener('scroll', () => {
+    if (prefs.tab !== 'history' || !listEl) return;
+    if (listEl.scrollTop + listEl.clientHeight >= listEl.scrollHeight - 400) void loadMoreCommits();
+});
+
+/** Paint data the backend read ahead after opening a repository (`repo:prefetch`). */
+export function applyPrefetch(p: Prefetch) {
+    state.hasRepo = true;
+    switch (p.kind) {
+        case 'log':
+            state.commits = p.data.items;
+            state.commitsComplete = p.data.complete;
+            if (prefs.tab === 'history') renderList();
+            break;
+        case 'branches':
+            state.branch = p.data.current_branch || state.branch;
+            state.branches = p.data.branches as any;
+            window.dispatchEvent(new CustomEvent('app:branches-updated'));
+            break;
+        case 'head-commit':
+            prefetchedDetail = p.data;
+            break;
     }
 }
 
@@ -680,19 +899,151 @@ function onFileContextMenu(ev: MouseEvent, f: { path: string }) {
         try { await TAURI.invoke('git_discard_paths', { paths: [f.path] }); await Promise.allSettled([hydrateStatus()]); }
         catch { notify('Discard failed'); }
     }});
+    const slash = f.path.lastIndexOf('/');
+    if (slash > 0) {
+        const folder = f.path.slice(0, slash);
+        items.push({ label: `Discard changes in ${folder}/`, action: async () => {
+            if (!TAURI.has) return;
+            const ok = window.confirm(`Discard all changes in ${folder}/? This cannot be undone.`);
+            if (!ok) return;
+            await batchStep('batch_discard', [folder], 'Discard');
+        }});
+    }
+    // monorepos: limit the Changes list to one directory
+    if (slash > 0) {
+        const folder = f.path.slice(0, slash);
+        items.push({ label: `Show only changes in ${folder}/`, action: () => void setChangesScope(folder) });
+    }
+    if (state.changesScope) {
+        items.push({ label: 'Show all changes', action: () => void setChangesScope(null) });
+    }
     if (hasSelectedFiles) {
+        items.push({ label: 'Stage selected files', action: async () => {
+            if (!TAURI.has) return;
+            await batchStep('batch_stage', Array.from(state.selectedFiles), 'Stage');
+        }});
         items.push({ label: 'Discard selected files', action: async () => {
             if (!TAURI.has) return;
             const paths = Array.from(state.selectedFiles);
             const ok = window.confirm(`Discard all changes in ${paths.length} selected file(s)? This cannot be undone.`);
             if (!ok) return;
-            try { await TAURI.invoke('git_discard_paths', { paths }); await Promise.allSettled([hydrateStatus()]); }
-            catch { notify('Discard failed'); }
+            await batchStep('batch_discard', paths, 'Discard');
+        }});
+    }
+    // one `*.ext` pattern per extension among the clicked/selected files
+    const exts = new Set(
+        [f.path, ...(hasSelectedFiles ? Array.from(state.selectedFiles) : [])]
+            .map(p => p.split('/').pop() || '')
+            .filter(name => name.lastIndexOf('.') > 0)
+            .map(name => `*${name.slice(name.lastIndexOf('.'))}`)
+    );
+    if (exts.size > 0) {
+        const patterns = Array.from(exts);
+        items.push({ label: `Ignore ${patterns.join(', ')}`, action: async () => {
+            if (!TAURI.has) return;
+            try {
+                const added = await TAURI.invoke<string[]>('batch_ignore', { patterns });
+                notify(added.length ? `Added ${added.join(', ')} to .gitignore` : 'Already ignored');
+                await Promise.allSettled([hydrateStatus()]);
+            } catch (e) { notify(`Ignore failed: ${e}`); }
         }});
     }
     buildCtxMenu(items, x, y);
 }
 
+/** Scope the Changes list to `dir` (`null`: the whole tree) and reload it. */
+async function setChangesScope(dir: string | null) {
+    if (!TAURI.has) return;
+    try {
+        state.changesScope = await TAURI.invoke<string | null>('set_changes_scope', { dir });
+        notify(state.changesScope ? `Showing changes in ${state.changesScope}/ only` : 'Showing all changes');
+    } catch (e) { notify(`Could not change the scope: ${e}`); }
+    await Promise.allSettled([hydrateStatus()]);
+}
+
+/** Run a batch command over `paths` as one backend operation, then refresh. */
+async function batchStep(cmd: 'batch_stage' | 'batch_discard', paths: string[], what: string) {
+    try { await TAURI.invoke(cmd, { paths }); }
+    catch (e) { notify(`${what} failed: ${e}`); }
+    await Promise.allSettled([hydrateStatus()]);
+}
+
+// History rows: squash consecutive commits (pushed ones need an explicit confirmation),
+// revert commits, and drive a cherry-pick/revert that stopped on a conflict.
+async function onCommitContextMenu(ev: MouseEvent, index: number) {
+    ev.preventDefault();
+    const commits = state.commits || [];
+    const items: { label: string; action: () => void }[] = [];
+    const op = TAURI.has ? await TAURI.invoke<Operation | null>('operation_progress').catch(() => null) : null;
+    if (op) {
+        const verb = op.kind === 'revert' ? 'revert' : 'cherry-pick';
+        items.push({ label: `Continue ${verb} (${op.done + 1} of ${op.commits.length})`, action: () => void sequenceStep('operation_continue') });
+        items.push({ label: 'Skip this commit', action: () => void sequenceStep('operation_skip') });
+        items.push({ label: `Abort ${verb}`, action: () => void sequenceStep('operation_abort') });
+        buildCtxMenu(items, ev.clientX, ev.clientY);
+        return;
+    }
+    items.push({ label: 'Revert commit', action: () => void sequenceStep('revert_commits', { commits: [commits[index].id] }) });
+    if (index >= 1) {
+        // Newest first, so each revert applies on top of the previous one cleanly
+        items.push({ label: `Revert ${index + 1} newest commits`, action: () => void sequenceStep('revert_commits', { commits: commits.slice(0, index + 1).map(c => c.id) }) });
+    }
+    if (index + 1 < commits.length) {
+        items.push({ label: 'Squash into parent…', action: () => squashCommits(commits.slice(index, index + 2)) });
+    }
+    if (index >= 1) {
+        items.push({ label: `Squash ${index + 1} newest commits…`, action: () => squashCommits(commits.slice(0, index + 1)) });
+    }
+    buildCtxMenu(items, ev.clientX, ev.clientY);
+}
+
+// Start or advance a multi-commit cherry-pick/revert; a conflict leaves it waiting.
+async function sequenceStep(cmd: string, args?: Record<string, unknown>) {
+    if (!TAURI.has) return;
+    try {
+        const op = await TAURI.invoke<Operation | null | undefined>(cmd, args as any);
+        if (op?.conflicts.length) {
+            notify(`Conflicts in ${op.conflicts.length} file(s): resolve and stage them, then continue from the history menu`);
+        } else {
+            notify(cmd === 'operation_abort' ? 'Aborted' : 'Done');
+        }
+    } catch (e) {
+        notify(`${e}`);
+    }
+    await Promise.allSettled([hydrateStatus(), hydrateCommits()]);
+}
+
+async function squashCommits(list: { id: string; msg: string }[]) {
+    if (!TAURI.has || list.length < 2) return;
+    const ids = list.map(c => c.id);
+    let force = false;
+    try {
+        const pushed = await TAURI.invoke<PushedCommit[]>('pushed_commits', { ids });
+        if (pushed.length) {
+            const remotes = Array.from(new Set(pushed.flatMap(p => p.remotes))).join(', ');
+            const ok = window.confirm(
+                `${pushed.length} of these commits ${pushed.length === 1 ? 'is' : 'are'} already on ${remotes}.\n\n` +
+                'Squashing rewrites published history: others who pulled it will diverge, and pushing needs --force. Continue?'
+            );
+            if (!ok) return;
+            force = true;
+        }
+    } catch (e) {
+        notify(`Squash failed: ${e}`);
+        return;
+    }
+    // Newest first, like the list; the oldest message is the natural default
+    const message = window.prompt(`Message for the squashed commit (${list.length} commits):`, list[list.length - 1].msg || '');
+    if (!message?.trim()) return;
+    try {
+        await TAURI.invoke('squash_commits', { ids, message, force });
+        notify(`Squashed ${list.length} commits`);
+        await Promise.allSettled([hydrateCommits()]);
+    } catch (e) {
+        notify(`Squash failed: ${e}`);
+    }
+}
+
 function toggleFilePick(path: string, on: boolean) {
     if (!path) return;
     state.defaultSelectAll = false;
@@ -732,7 +1083,7 @@ function updateSelectAllState(visible: { path: string }[]) {
 function getVisibleFiles(): { path: string }[] {
     if (prefs.tab !== 'changes') return [];
     const q = (filterInput?.value || '').trim().toLowerCase();
-    return (state.files || []).filter(f => !q || (f.path || '').toLowerCase().includes(q));
+    return (state.files || []).filter(f => f.status !== '!' && (!q || (f.path || '').toLowerCase().includes(q)));
 }
 
 function toggleSelectAll(on: boolean, visible: { path: string }[]) {
@@ -835,7 +1186,7 @@ async function renderCombinedDiff(paths: string[]) {
     let html = '';
     for (const p of files) {
         try {
-            const lines = TAURI.has ? await TAURI.invoke<string[]>('git_diff_file', { path: p }) : [];
+            const lines = TAURI.has ? await invokeChunked<string>('git_diff_file', { path: p }) : [];
             html += `<div class="hunk"><div class="hline"><div class="gutter"></div><div class="code">${escapeHtml(p)}</div></div></div>`;
             html += renderHunksWithSelection(lines || []);
         } catch {
@@ -866,32 +1217,63 @@ function updateCommitButton() {
     btn.disabled = !(summaryFilled && (hunksSelected || filesSelected));
 }
 
-// Convert an ISO/RFC3339 datetime string into a short relative phrase.
-function formatTimeAgo(isoMaybe: string): string {
-    try {
-        const d = new Date(String(isoMaybe || '').trim());
-        const t = d.getTime();
-        if (!isFinite(t)) return (isoMaybe || '').trim();
-        const now = Date.now();
-        let sec = Math.max(0, Math.round((now - t) / 1000));
-        if (sec < 45) return 'just now';
-        if (sec < 90) return '1 minute ago';
-        let min = Math.round(sec / 60);
-        if (min < 60) return `${min} minute${min === 1 ? '' : 's'} ago`;
-        let hr = Math.round(min / 60);
-        if (hr < 24) return `${hr} hour${hr === 1 ? '' : 's'} ago`;
-        let day = Math.round(hr / 24);
-        if (day === 1) return 'yesterday';
-        if (day < 7) return `${day} day${day === 1 ? '' : 's'} ago`;
-        let wk = Math.round(day / 7);
-        if (wk === 1) return '1 week ago';
-        if (wk < 5) return `${wk} weeks ago`;
-        let mon = Math.round(day / 30);
-        if (mon === 1) return '1 month ago';
-        if (mon < 12) return `${mon} months ago`;
-        let yr = Math.round(day / 365);
-        return `${yr} year${yr === 1 ? '' : 's'} ago`;
-    } catch {
-        return (isoMaybe || '').trim();
+// Marks changes git would otherwise only show as "M": exec bit, symlink target, file type.
+function modeBadge(m: ModeChange | null | undefined): string {
+    if (!m) return '';
+    const [label, title] =
+        m.kind === 'executable' ? [m.executable ? '+x' : '−x', m.executable ? 'Made executable' : 'No longer executable'] :
+            m.kind === 'symlink_target' ? ['→', 'Symlink target changed'] :
+                [`${m.from}→${m.to}`, `Changed from ${m.from} to ${m.to}`];
+    return `<span class="mode-badge" title="${title}">${escapeHtml(label)}</span>`;
+}
+
+// "+12 −4" for text files, the size change for binary ones; empty when not computed.
+function churnBadge(f: { additions?: number | null; deletions?: number | null; size_delta?: number | null }): string {
+    const size = typeof f.size_delta === 'number' && f.size_delta !== 0
+        ? `${f.size_delta > 0 ? '+' : '−'}${formatBytes(Math.abs(f.size_delta))}` : '';
+    if (typeof f.additions !== 'number' || typeof f.deletions !== 'number') {
+        return size ? `<span class="churn" title="Size change">${size}</span>` : '';
     }
+    const title = `${f.additions} insertion(s), ${f.deletions} deletion(s)${size ? `, ${size}` : ''}`;
+    return `<span class="churn" title="${title}"><span class="ins">+${f.additions}</span> <span class="del">−${f.deletions}</span></span>`;
+}
+
+/** " (ignored, 12k files)" after a directory row; empty for files. */
+function dirNote(f: { path: string; status: string; file_count?: number | null }): string {
+    if (!f.path?.endsWith('/') && f.status !== '!') return '';
+    const parts = [f.status === '!' ? 'ignored' : '', f.path?.endsWith('/') ? formatFileCount(f.file_count) : ''].filter(Boolean);
+    return parts.length ? ` <span class="dir-note">(${parts.join(', ')})</span>` : '';
+}
+
+function formatFileCount(n: number | null | undefined): string {
+    if (typeof n !== 'number') return 'files';
+    const num = n >= 100000 ? '100k+' : n >= 1000 ? `${Math.round(n / 1000)}k` : String(n);
+    return `${num} file${n === 1 ? '' : 's'}`;
+}
+
+function formatBytes(n: number): string {
+    if (n < 1024) return `${n} B`;
+    if (n < 1024 * 1024) return `${(n / 1024).toFixed(1)} KB`;
+    return `${(n / 1024 / 1024).toFixed(1)} MB`;
+}
+
+// Short relative phrase for a Unix time; mirrors `Timestamp::relative_to` in the core crate.
+function formatTimeAgo(seconds: number): string {
+    if (!seconds) return '';
+    const sec = Math.max(0, Math.round(Date.now() / 1000 - seconds));
+    if (sec < 45) return 'just now';
+    if (sec < 90) return '1 minute ago';
+    const min = Math.round(sec / 60);
+    if (min < 60) return `${min} minute${min === 1 ? '' : 's'} ago`;
+    const hr = Math.round(min / 60);
+    if (hr < 24) return `${hr} hour${hr === 1 ? '' : 's'} ago`;
+    const day = Math.round(hr / 24);
+    if (day === 1) return 'yesterday';
+    if (day < 7) return `${day} days ago`;
+    const wk = Math.round(day / 7);
+    if (wk < 5) return `${wk} week${wk === 1 ? '' : 's'} ago`;
+    const mon = Math.round(day / 30);
+    if (mon < 12) return `${mon} month${mon === 1 ? '' : 's'} ago`;
+    const yr = Math.round(day / 365);
+    return `${yr} year${yr === 1 ? '' : 's'} ago`;
 }
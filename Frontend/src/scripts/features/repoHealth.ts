@@ -0,0 +1,51 @@
+import { TAURI } from '../lib/tauri';
+import { openModal } from '../ui/modals';
+import { notify } from '../lib/notify';
+
+interface Finding { id: string; label: string; severity: 'ok'|'warn'|'error'; detail: string; hint?: string | null }
+interface HealthReport { path: string; findings: Finding[] }
+
+const ICON: Record<string, string> = { ok: '✓', warn: '!', error: '✕' };
+
+export function wireRepoHealth() {
+  const modal = document.getElementById('repo-health-modal') as HTMLElement | null;
+  if (!modal || (modal as any).__wired) return;
+  (modal as any).__wired = true;
+  modal.querySelector('#repo-health-rerun')?.addEventListener('click', () => { runRepoHealth(); });
+}
+
+export async function openRepoHealth() {
+  openModal('repo-health-modal');
+  await runRepoHealth();
+}
+
+async function runRepoHealth() {
+  const modal = document.getElementById('repo-health-modal') as HTMLElement | null;
+  if (!modal || !TAURI.has) return;
+  const pathEl = modal.querySelector('#repo-health-path') as HTMLElement | null;
+  const list = modal.querySelector('#repo-health-findings') as HTMLElement | null;
+  if (pathEl) pathEl.textContent = 'Checking…';
+  if (list) list.innerHTML = '';
+  try {
+    const report = await TAURI.invoke<HealthReport>('repo_health');
+    if (pathEl) pathEl.textContent = report.path;
+    if (!list) return;
+    for (const f of report.findings) {
+      const li = document.createElement('li');
+      li.className = `health-${f.severity}`;
+      const head = document.createElement('div');
+      head.textContent = `${ICON[f.severity] || '•'} ${f.label}: ${f.detail}`;
+      li.appendChild(head);
+      if (f.hint) {
+        const hint = document.createElement('div');
+        hint.className = 'meta';
+        hint.textContent = f.hint;
+        li.appendChild(hint);
+      }
+      list.appendChild(li);
+    }
+  } catch (e) {
+    if (pathEl) pathEl.textContent = '';
+    notify(`Health check failed: ${e}`);
+  }
+}
@@ -1,7 +1,7 @@
 import { TAURI } from '../lib/tauri';
 import { openModal, closeModal } from '../ui/modals';
 import { notify } from '../lib/notify';
-import type { RepoSettings } from '../types';
+import type { ConfigValue, GlobalSettings, HookInfo, HookTemplate, RepoSettings } from '../types';
 
 export function openRepoSettings(){ openModal('repo-settings-modal'); }
 
@@ -13,6 +13,7 @@ export async function wireRepoSettings() {
     const nameInput  = modal.querySelector('#git-user-name') as HTMLInputElement | null;
     const emailInput = modal.querySelector('#git-user-email') as HTMLInputElement | null;
     const originInput= modal.querySelector('#git-origin-url') as HTMLInputElement | null;
+    const prePushInput = modal.querySelector('#pre-push-command') as HTMLInputElement | null;
     const saveBtn = modal.querySelector('#repo-settings-save') as HTMLButtonElement | null;
 
     if (TAURI.has) {
@@ -21,14 +22,27 @@ export async function wireRepoSettings() {
             if (nameInput && cfg?.user_name) nameInput.value = cfg.user_name;
             if (emailInput && cfg?.user_email) emailInput.value = cfg.user_email;
             if (originInput && cfg?.origin_url) originInput.value = cfg.origin_url;
+            if (prePushInput && cfg?.pre_push_command) prePushInput.value = cfg.pre_push_command;
+            const hint = modal.querySelector('#git-identity-source') as HTMLElement | null;
+            const from = [cfg?.sources?.['user.name'], cfg?.sources?.['user.email']].map(configSource);
+            if (hint && from.some(Boolean)) {
+                hint.textContent = `Name from ${from[0] || 'nowhere'}, email from ${from[1] || 'nowhere'}. `
+                    + 'Saving a different identity sets it for this repository only.';
+            }
         } catch { /* ignore */ }
     }
 
+    const hooksList = modal.querySelector('#repo-hooks') as HTMLUListElement | null;
+    if (TAURI.has && hooksList) {
+        renderHooks(hooksList).catch(() => { hooksList.textContent = 'Hooks unavailable'; });
+    }
+
     saveBtn?.addEventListener('click', async () => {
         const next: RepoSettings = {
             user_name: nameInput?.value || undefined,
             user_email: emailInput?.value || undefined,
             origin_url: originInput?.value || undefined,
+            pre_push_command: prePushInput?.value || undefined,
         };
         try {
             if (TAURI.has) await TAURI.invoke('set_repo_settings', { cfg: next });
@@ -38,3 +52,81 @@ export async function wireRepoSettings() {
         }
     });
 }
+
+/** "global config (~/.gitconfig)", "local config (/work/inc)". */
+function configSource(v?: ConfigValue): string {
+    if (!v) return '';
+    return v.origin ? `${v.scope} config (${v.origin})` : `${v.scope} config`;
+}
+
+function sourceLabel(h: HookInfo): string {
+    switch (h.source.kind) {
+        case 'template': return `template ${h.source.id} v${h.source.version}`;
+        case 'pre-commit': return 'pre-commit framework';
+        case 'husky': return 'Husky';
+        default: return 'custom script';
+    }
+}
+
+/** List templates (install/update/remove) and the other hooks already in the repository. */
+async function renderHooks(list: HTMLUListElement) {
+    const [templates, hooks] = await Promise.all([
+        TAURI.invoke<HookTemplate[]>('list_hook_templates'),
+        TAURI.invoke<HookInfo[]>('list_hooks'),
+    ]);
+    list.replaceChildren();
+
+    const row = (text: string, title: string, button?: [string, () => Promise<void>]) => {
+        const li = document.createElement('li');
+        li.style.cssText = 'display:flex; align-items:center; gap:.5rem;';
+        const span = document.createElement('span');
+        span.style.flex = '1';
+        span.textContent = text;
+        span.title = title;
+        li.appendChild(span);
+        if (button) {
+            const btn = document.createElement('button');
+            btn.type = 'button';
+            btn.className = 'tbtn';
+            btn.textContent = button[0];
+            btn.addEventListener('click', async () => {
+                btn.disabled = true;
+                try { await button[1](); } catch (e) { notify(String(e)); }
+                await renderHooks(list).catch(() => {});
+            });
+            li.appendChild(btn);
+        }
+        list.appendChild(li);
+    };
+
+    for (const t of templates) {
+        const current = hooks.find(h => h.name === t.hook);
+        const ours = current?.source.kind === 'template' && current.source.id === t.id ? current : undefined;
+        const install = async () => {
+            const cfg = await TAURI.invoke<GlobalSettings>('get_global_settings');
+            let confirmed: boolean | undefined;
+            if (cfg?.git?.allow_hooks === 'ask') {
+                const replaces = current && !ours ? `\n\nThe existing ${t.hook} hook (${sourceLabel(current)}) will be kept as a backup.` : '';
+                confirmed = window.confirm(`Install the "${t.title}" ${t.hook} hook?${replaces}`);
+                if (!confirmed) return;
+            }
+            await TAURI.invoke('install_hook', { template: t.id, confirmed });
+            notify(`Installed ${t.hook} hook`);
+        };
+        if (!ours) {
+            row(`${t.title} (${t.hook})`, t.description, ['Install', install]);
+        } else if (ours.source.kind === 'template' && ours.source.outdated) {
+            row(`${t.title} (${t.hook}) — v${ours.source.version}, v${t.version} available`, t.description, ['Update', install]);
+        } else {
+            row(`${t.title} (${t.hook}) — installed`, t.description, ['Remove', async () => {
+                await TAURI.invoke('remove_hook', { name: t.hook });
+                notify(`Removed ${t.hook} hook`);
+            }]);
+        }
+    }
+
+    for (const h of hooks) {
+        if (h.source.kind === 'template' && templates.some(t => t.hook === h.name)) continue;
+        row(`${h.name}: ${sourceLabel(h)}${h.executable ? '' : ' (not executable)'}`, h.path);
+    }
+}
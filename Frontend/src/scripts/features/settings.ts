@@ -48,6 +48,7 @@ export function wireSettings() {
             const baseRaw = (modal as HTMLElement).dataset.currentCfg || '{}';
             const base = JSON.parse(baseRaw || '{}');
             const prevBackend: string = String(base?.git?.backend || 'system');
+            const prevChannel: string = String(base?.general?.update_channel || 'stable');
             const next = collectSettingsFromForm(modal);
 
             if (TAURI.has) {
@@ -59,6 +60,24 @@ export function wireSettings() {
                     const backend_id = (newBackend === 'libgit2') ? 'git-libgit2' : 'git-system';
                     try { await TAURI.invoke('set_backend_cmd', { backend_id }); } catch {}
                 }
+
+                // Pick up plugins right away when they were just switched on
+                if (next?.plugins?.enabled && !base?.plugins?.enabled) {
+                    try { await TAURI.invoke('reload_plugins'); } catch {}
+                }
+
+                // Add or remove the file manager entry when the toggle changed
+                const shell = !!next?.general?.shell_integration;
+                if (shell !== !!base?.general?.shell_integration) {
+                    try { await TAURI.invoke('register_shell_integration', { enabled: shell }); }
+                    catch (e) { notify(String(e)); }
+                }
+
+                // If the update channel changed, switch feeds and re-check right away
+                const newChannel: string = String(next?.general?.update_channel || 'stable');
+                if (newChannel !== prevChannel) {
+                    try { await TAURI.invoke('set_update_channel', { channel: newChannel }); } catch {}
+                }
             }
 
             // Apply visual prefs immediately (no restart): theme, tab width, UI scale, mono font
@@ -85,13 +104,16 @@ export function wireSettings() {
             if (!TAURI.has) return;
             const cur = await TAURI.invoke<GlobalSettings>('get_global_settings');
 
-            cur.general = { theme: 'system', language: 'system', default_backend: 'git', update_channel: 'stable', reopen_last_repos: true, checks_on_launch: true, telemetry: false, crash_reports: false };
-            cur.git = { backend: 'system', default_branch: 'main', prune_on_fetch: true, allow_hooks: 'ask', respect_core_autocrlf: true };
+            if (cur.general?.shell_integration) {
+                try { await TAURI.invoke('register_shell_integration', { enabled: false }); } catch {}
+            }
+            cur.general = { theme: 'system', language: 'system', default_backend: 'git', update_channel: 'stable', reopen_last_repos: true, checks_on_launch: true, telemetry: false, crash_reports: false, shell_integration: false };
+            cur.git = { backend: 'system', executable_path: '', default_branch: 'main', prune_on_fetch: true, autostash_on_pull: false, wip_autosave_minutes: 0, allow_hooks: 'ask', respect_core_autocrlf: true, secret_scan: 'off', secret_rules: [], disabled_secret_rules: [], offline_partial_clone: false };
             cur.diff = { tab_width: 4, ignore_whitespace: 'none', max_file_size_mb: 10, intraline: true, show_binary_placeholders: true, external_diff: {enabled:false,path:'',args:''}, external_merge: {enabled:false,path:'',args:''}, binary_exts: ['png','jpg','dds','uasset'] };
-            cur.lfs = { enabled: true, concurrency: 4, require_lock_before_edit: false, background_fetch_on_checkout: true };
-            cur.performance = { progressive_render: true, gpu_accel: true };
-            cur.ux = { ui_scale: 1.0, font_mono: 'monospace', vim_nav: false, color_blind_mode: 'none', recents_limit: 10 };
-            cur.logging = { level: 'info', live_viewer: false, retain_archives: 10 };
+            cur.lfs = { enabled: true, concurrency: 4, require_lock_before_edit: false, background_fetch_on_checkout: true, large_file_warn_mb: 50 };
+            cur.performance = { progressive_render: true, gpu_accel: true, status_refresh_min_ms: 1500 };
+            cur.ux = { ui_scale: 1.0, font_mono: 'monospace', vim_nav: false, color_blind_mode: 'none', recents_limit: 10, show_ignored: false, collapse_untracked_dirs: false };
+            cur.logging = { level: 'info', live_viewer: false, retain_archives: 10, format: 'text', max_file_size_mb: 20 };
 
             await TAURI.invoke('set_global_settings', { cfg: cur });
             await loadSettingsIntoForm(modal);
@@ -117,14 +139,23 @@ function collectSettingsFromForm(root: HTMLElement): GlobalSettings {
         update_channel: (() => { const v = get<HTMLSelectElement>('#set-update-channel')?.value; return v === 'beta' ? 'nightly' : v; })(),
         reopen_last_repos: !!get<HTMLInputElement>('#set-reopen-last')?.checked,
         checks_on_launch: !!get<HTMLInputElement>('#set-checks-on-launch')?.checked,
+        shell_integration: !!get<HTMLInputElement>('#set-shell-integration')?.checked,
     };
 
     o.git = {
         ...o.git,
         backend: get<HTMLSelectElement>('#set-git-backend')?.value as any,
+        executable_path: (get<HTMLInputElement>('#set-git-executable')?.value ?? '').trim(),
         prune_on_fetch: !!get<HTMLInputElement>('#set-prune-on-fetch')?.checked,
+        autostash_on_pull: !!get<HTMLInputElement>('#set-autostash-on-pull')?.checked,
+        offline_partial_clone: !!get<HTMLInputElement>('#set-offline-partial-clone')?.checked,
+        wip_autosave_minutes: (() => {
+            const raw = get<HTMLInputElement>('#set-wip-autosave-minutes')?.value ?? '';
+            return raw.trim() === '' ? 0 : Math.max(0, Math.min(1440, Math.round(Number(raw)) || 0));
+        })(),
         allow_hooks: get<HTMLSelectElement>('#set-hook-policy')?.value,
         respect_core_autocrlf: !!get<HTMLInputElement>('#set-respect-autocrlf')?.checked,
+        secret_scan: (get<HTMLSelectElement>('#set-secret-scan')?.value || 'off') as any,
     };
 
     o.diff = {
@@ -142,12 +173,17 @@ function collectSettingsFromForm(root: HTMLElement): GlobalSettings {
         concurrency: Number(get<HTMLInputElement>('#set-lfs-concurrency')?.value ?? 0),
         require_lock_before_edit: !!get<HTMLInputElement>('#set-lfs-require-lock')?.checked,
         background_fetch_on_checkout: !!get<HTMLInputElement>('#set-lfs-bg-fetch')?.checked,
+        large_file_warn_mb: Number(get<HTMLInputElement>('#set-lfs-large-file-mb')?.value ?? 50),
     };
 
     o.performance = {
         ...o.performance,
         progressive_render: !!get<HTMLInputElement>('#set-progressive-render')?.checked,
         gpu_accel: !!get<HTMLInputElement>('#set-gpu-accel')?.checked,
+        status_refresh_min_ms: (() => {
+            const raw = get<HTMLInputElement>('#set-status-refresh-ms')?.value ?? '';
+            return raw.trim() === '' ? 1500 : Math.max(250, Math.min(60000, Number(raw)));
+        })(),
     };
 
     const rlRaw = get<HTMLInputElement>('#set-recents-limit')?.value ?? '';
@@ -159,15 +195,26 @@ function collectSettingsFromForm(root: HTMLElement): GlobalSettings {
         vim_nav: !!get<HTMLInputElement>('#set-vim-nav')?.checked,
         color_blind_mode: get<HTMLSelectElement>('#set-cb-mode')?.value,
         recents_limit: recentsLimit,
+        show_ignored: !!get<HTMLInputElement>('#set-show-ignored')?.checked,
+        collapse_untracked_dirs: !!get<HTMLInputElement>('#set-collapse-untracked')?.checked,
     };
 
     // Logging
     const keepRaw = get<HTMLInputElement>('#set-log-keep')?.value ?? '';
     const keep = keepRaw.trim() === '' ? 10 : Math.max(1, Math.min(100, Number(keepRaw)));
+    const maxRaw = get<HTMLInputElement>('#set-log-max-size')?.value ?? '';
+    const maxMb = maxRaw.trim() === '' ? 20 : Math.max(0, Math.min(1024, Number(maxRaw)));
     o.logging = {
         ...o.logging,
         level: (get<HTMLSelectElement>('#set-log-level')?.value || 'info') as any,
         retain_archives: keep,
+        format: (get<HTMLSelectElement>('#set-log-format')?.value || 'text') as any,
+        max_file_size_mb: maxMb,
+    };
+
+    o.plugins = {
+        ...o.plugins,
+        enabled: !!get<HTMLInputElement>('#set-plugins-enabled')?.checked,
     };
 
     return o;
@@ -191,6 +238,7 @@ export async function loadSettingsIntoForm(root?: HTMLElement) {
     }
     const elReo   = get<HTMLInputElement>('#set-reopen-last'); if (elReo) elReo.checked = !!cfg.general?.reopen_last_repos;
     const elChk   = get<HTMLInputElement>('#set-checks-on-launch'); if (elChk) elChk.checked = !!cfg.general?.checks_on_launch;
+    const elShell = get<HTMLInputElement>('#set-shell-integration'); if (elShell) elShell.checked = !!cfg.general?.shell_integration;
     const elRl    = get<HTMLInputElement>('#set-recents-limit'); if (elRl) elRl.value = String(cfg.ux?.recents_limit ?? 10);
 
     const backend = toKebab(cfg.git?.backend) || 'system';
@@ -199,9 +247,14 @@ export async function loadSettingsIntoForm(root?: HTMLElement) {
         // Map to enum string values used by backend settings
         elGb.value = backend === 'libgit2' ? 'libgit2' : 'system';
     }
+    const elGx = get<HTMLInputElement>('#set-git-executable'); if (elGx) elGx.value = cfg.git?.executable_path ?? '';
     const elPr = get<HTMLInputElement>('#set-prune-on-fetch'); if (elPr) elPr.checked = !!cfg.git?.prune_on_fetch;
+    const elAs = get<HTMLInputElement>('#set-autostash-on-pull'); if (elAs) elAs.checked = !!cfg.git?.autostash_on_pull;
+    const elOp = get<HTMLInputElement>('#set-offline-partial-clone'); if (elOp) elOp.checked = !!cfg.git?.offline_partial_clone;
+    const elWip = get<HTMLInputElement>('#set-wip-autosave-minutes'); if (elWip) elWip.value = String(cfg.git?.wip_autosave_minutes ?? 0);
     
     const elHp = get<HTMLSelectElement>('#set-hook-policy'); if (elHp) elHp.value = toKebab(cfg.git?.allow_hooks);
+    const elSs = get<HTMLSelectElement>('#set-secret-scan'); if (elSs) elSs.value = toKebab(cfg.git?.secret_scan || 'off');
     const elRc = get<HTMLInputElement>('#set-respect-autocrlf'); if (elRc) elRc.checked = !!cfg.git?.respect_core_autocrlf;
 
     const elTw = get<HTMLInputElement>('#set-tab-width'); if (elTw) elTw.value = String(cfg.diff?.tab_width ?? 0);
@@ -212,19 +265,25 @@ export async function loadSettingsIntoForm(root?: HTMLElement) {
 
     const elLe = get<HTMLInputElement>('#set-lfs-enabled'); if (elLe) elLe.checked = !!cfg.lfs?.enabled;
     const elLc = get<HTMLInputElement>('#set-lfs-concurrency'); if (elLc) elLc.value = String(cfg.lfs?.concurrency ?? 0);
-    
+    const elLw = get<HTMLInputElement>('#set-lfs-large-file-mb'); if (elLw) elLw.value = String(cfg.lfs?.large_file_warn_mb ?? 50);
     const elLl = get<HTMLInputElement>('#set-lfs-require-lock'); if (elLl) elLl.checked = !!cfg.lfs?.require_lock_before_edit;
     const elBg = get<HTMLInputElement>('#set-lfs-bg-fetch'); if (elBg) elBg.checked = !!cfg.lfs?.background_fetch_on_checkout;
 
     const elPrg= get<HTMLInputElement>('#set-progressive-render'); if (elPrg) elPrg.checked = !!cfg.performance?.progressive_render;
     const elGpu= get<HTMLInputElement>('#set-gpu-accel'); if (elGpu) elGpu.checked = !!cfg.performance?.gpu_accel;
+    const elRef= get<HTMLInputElement>('#set-status-refresh-ms'); if (elRef) elRef.value = String(cfg.performance?.status_refresh_min_ms ?? 1500);
 
     const elUi = get<HTMLInputElement>('#set-ui-scale'); if (elUi) elUi.value = String(cfg.ux?.ui_scale ?? 1.0);
     const elFm = get<HTMLInputElement>('#set-font-mono'); if (elFm) elFm.value = cfg.ux?.font_mono ?? 'monospace';
     const elVn = get<HTMLInputElement>('#set-vim-nav'); if (elVn) elVn.checked = !!cfg.ux?.vim_nav;
+    const elSi = get<HTMLInputElement>('#set-show-ignored'); if (elSi) elSi.checked = !!cfg.ux?.show_ignored;
+    const elCu = get<HTMLInputElement>('#set-collapse-untracked'); if (elCu) elCu.checked = !!cfg.ux?.collapse_untracked_dirs;
     const elCb = get<HTMLSelectElement>('#set-cb-mode'); if (elCb) elCb.value = toKebab(cfg.ux?.color_blind_mode);
 
     // Logging
     const elLvl = get<HTMLSelectElement>('#set-log-level'); if (elLvl) elLvl.value = toKebab(cfg.logging?.level || 'info');
     const elKeep= get<HTMLInputElement>('#set-log-keep'); if (elKeep) elKeep.value = String(cfg.logging?.retain_archives ?? 10);
+    const elFmt = get<HTMLSelectElement>('#set-log-format'); if (elFmt) elFmt.value = toKebab(cfg.logging?.format || 'text');
+    const elMax = get<HTMLInputElement>('#set-log-max-size'); if (elMax) elMax.value = String(cfg.logging?.max_file_size_mb ?? 20);
+    const elPlg = get<HTMLInputElement>('#set-plugins-enabled'); if (elPlg) elPlg.checked = !!cfg.plugins?.enabled;
 }
@@ -2,71 +2,90 @@ import { TAURI } from '../lib/tauri';
 import { openModal, closeModal } from '../ui/modals';
 import { notify } from '../lib/notify';
 
+interface UpdateInfo {
+  version: string;
+  current_version: string;
+  channel: string;
+  notes?: string | null;
+  date?: string | null;
+  size?: number | null;
+  staged: boolean;
+}
+
+const fmtBytes = (n: number) => n >= 1048576 ? `${(n / 1048576).toFixed(1)} MB` : `${Math.max(1, Math.round(n / 1024))} KB`;
+
 export function wireUpdate() {
   const modal = document.getElementById('update-modal') as HTMLElement | null;
   if (!modal || (modal as any).__wired) return;
   (modal as any).__wired = true;
 
   const installBtn = modal.querySelector('#update-install') as HTMLButtonElement | null;
+  const onQuitBtn = modal.querySelector('#update-install-on-quit') as HTMLButtonElement | null;
+  const progressEl = modal.querySelector('#update-progress') as HTMLElement | null;
+
+  TAURI.listen?.('update:progress', ({ payload }) => {
+    const p = payload as any;
+    if (!progressEl) return;
+    progressEl.classList.remove('hidden');
+    if (p?.kind === 'progress') {
+      const got = Number(p.received || 0), total = Number(p.total || 0);
+      progressEl.textContent = total > 0
+        ? `Downloading… ${fmtBytes(got)} of ${fmtBytes(total)} (${Math.floor(got * 100 / total)}%)`
+        : `Downloading… ${fmtBytes(got)}`;
+    } else if (p?.kind === 'downloaded') {
+      progressEl.textContent = 'Download complete';
+    } else if (p?.kind === 'staged') {
+      progressEl.textContent = 'Update will be installed when you quit OpenVCS';
+    }
+  });
+
+  const setBusy = (b: boolean) => { if (installBtn) installBtn.disabled = b; if (onQuitBtn) onQuitBtn.disabled = b; };
+
   installBtn?.addEventListener('click', async () => {
     try {
       if (!TAURI.has) return;
+      setBusy(true);
       notify('Downloading update…');
       await TAURI.invoke('updater_install_now');
       notify('Update installed. Restart to apply.');
       closeModal('update-modal');
     } catch {
       notify('Update failed');
-    }
+    } finally { setBusy(false); }
+  });
+
+  onQuitBtn?.addEventListener('click', async () => {
+    try {
+      if (!TAURI.has) return;
+      setBusy(true);
+      const v = await TAURI.invoke<string | null>('updater_download_for_quit');
+      notify(v ? `Version ${v} will install on quit` : 'Already up to date');
+      closeModal('update-modal');
+    } catch {
+      notify('Update download failed');
+    } finally { setBusy(false); }
   });
 }
 
 export async function showUpdateDialog(_data: any) {
   try {
     if (!TAURI.has) return;
-    const cfg = await TAURI.invoke<any>('get_global_settings');
-    const about = await TAURI.invoke<any>('about_info');
-    const channel = String(cfg?.general?.update_channel || 'stable');
-    const current = String(about?.version || '').trim();
-
-    const fetchJson = async (url: string) => {
-      const r = await fetch(url, { cache: 'no-store' }); return r.ok ? r.json() : null;
-    };
-
-    const stable = await fetchJson('https://api.github.com/repos/Jordonbc/OpenVCS/releases/latest');
-    const nightly = await fetchJson('https://api.github.com/repos/Jordonbc/OpenVCS/releases/tags/openvcs-nightly');
-
-    const norm = (v: string) => String(v || '').replace(/^v/i, '').trim();
-    const stableTag = norm(stable?.tag_name || stable?.name || '');
-    const nightlyTag = norm(nightly?.tag_name || nightly?.name || '');
-
-    const newerThanCurrent = (v: string) => v && v !== '' && norm(current) !== norm(v);
-
-    let show = false;
-    let pick = null as any;
-
-    if (channel === 'stable') {
-      if (newerThanCurrent(stableTag)) { show = true; pick = stable; }
-    } else {
-      // Nightly: pick the most recent by published_at timestamp and ensure it's newer than current
-      const sDate = Date.parse(String(stable?.published_at || stable?.created_at || '')) || 0;
-      const nDate = Date.parse(String(nightly?.published_at || nightly?.created_at || '')) || 0;
-      pick = (nDate > sDate ? nightly : stable) || nightly || stable;
-      const pickTag = norm(pick?.tag_name || pick?.name || '');
-      show = newerThanCurrent(pickTag);
-    }
-
-    if (!show || !pick) { notify('Already up to date'); return; }
+    const info = await TAURI.invoke<UpdateInfo | null>('get_update_info');
+    if (!info) { notify('Already up to date'); return; }
 
     openModal('update-modal');
     const modal = document.getElementById('update-modal') as HTMLElement | null;
     if (!modal) return;
     const verEl = modal.querySelector('#update-version');
     const notesEl = modal.querySelector('#update-notes');
-    const v = pick?.tag_name || pick?.name || '';
-    const body = String(pick?.body || '').trim();
-    if (verEl) verEl.textContent = v ? `Version ${v}` : 'Update available';
-    if (notesEl) (notesEl as HTMLElement).textContent = body || '(No changelog provided)';
+    const progressEl = modal.querySelector('#update-progress') as HTMLElement | null;
+    const size = info.size ? ` · ${fmtBytes(info.size)}` : '';
+    if (verEl) verEl.textContent = `Version ${info.version} (current ${info.current_version})${size}`;
+    if (notesEl) (notesEl as HTMLElement).textContent = String(info.notes || '').trim() || '(No changelog provided)';
+    if (progressEl) {
+      progressEl.classList.toggle('hidden', !info.staged);
+      progressEl.textContent = info.staged ? 'Downloaded — will install when you quit OpenVCS' : '';
+    }
   } catch {
     notify('Update check failed');
   }
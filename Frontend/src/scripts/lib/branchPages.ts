@@ -0,0 +1,36 @@
+// src/scripts/lib/branchPages.ts
+// Branches come a page at a time (`git_list_branches` with a `BranchQuery`), so a repository
+// with thousands of remote branches shows its first ones without waiting for the rest.
+import { TAURI } from './tauri';
+import type { Branch } from '../types';
+
+/** Branches per `git_list_branches` call. */
+const BRANCH_PAGE = 200;
+
+interface BranchPage { items: Branch[]; next_cursor?: string | null; total: number }
+
+/** Latest load per caller; an older one stops once a newer one starts. */
+const generations = new Map<string, number>();
+
+/**
+ * Load every branch, current first, page by page. `onPage` gets the list so far after each
+ * page (`done` on the last). Resolves to the full list, or `null` when a newer load with the
+ * same `key` took over.
+ */
+export async function loadBranchPages(
+    key: string,
+    onPage: (items: Branch[], done: boolean) => void,
+): Promise<Branch[] | null> {
+    const gen = (generations.get(key) ?? 0) + 1;
+    generations.set(key, gen);
+    const items: Branch[] = [];
+    let cursor: string | null = null;
+    do {
+        const page: BranchPage = await TAURI.invoke<BranchPage>('git_list_branches', { query: { limit: BRANCH_PAGE, cursor } });
+        if (generations.get(key) !== gen) return null;
+        items.push(...(Array.isArray(page?.items) ? page.items : []));
+        cursor = page?.next_cursor ?? null;
+        onPage(items.slice(), !cursor);
+    } while (cursor);
+    return items;
+}
@@ -0,0 +1,49 @@
+// src/scripts/lib/chunked.ts
+// Results of chunk-capable commands (`git_diff_file`, `git_diff_commit`, `git_log`): small
+// ones come back inline, large ones as `ipc:chunk` events so no single IPC message is huge.
+import { TAURI } from './tauri';
+
+type Transfer<T> =
+    | { transfer: 'inline'; items: T[] }
+    | { transfer: 'chunked'; job: number; chunks: number; total: number };
+
+interface Chunk { job: number; seq: number; json: string }
+
+/** Give up on missing chunks after this long without one arriving. */
+const CHUNK_TIMEOUT_MS = 15000;
+
+/** Invoke `cmd` and return its items, reassembling them when they were sent in chunks. */
+export async function invokeChunked<T>(cmd: string, args?: Record<string, unknown>): Promise<T[]> {
+    // Listen first: chunks are emitted before the command returns which job they belong to
+    const received = new Map<number, Map<number, string>>();
+    let notify: (() => void) | null = null;
+    const sub: any = await TAURI.listen<Chunk>('ipc:chunk', ({ payload }) => {
+        if (!payload) return;
+        let byJob = received.get(payload.job);
+        if (!byJob) received.set(payload.job, byJob = new Map());
+        byJob.set(payload.seq, payload.json);
+        notify?.();
+    });
+    try {
+        const res = await TAURI.invoke<Transfer<T> | undefined>(cmd, args as any);
+        if (!res) return [];
+        if (res.transfer === 'inline') return res.items ?? [];
+
+        const chunks = () => received.get(res.job) ?? new Map<number, string>();
+        while (chunks().size < res.chunks) {
+            const arrived = await new Promise<boolean>((resolve) => {
+                const timer = setTimeout(() => resolve(false), CHUNK_TIMEOUT_MS);
+                notify = () => { clearTimeout(timer); resolve(true); };
+            });
+            notify = null;
+            if (!arrived) throw new Error(`${cmd}: ${chunks().size} of ${res.chunks} chunk(s) arrived`);
+        }
+        const items: T[] = [];
+        for (let seq = 0; seq < res.chunks; seq++) {
+            for (const item of JSON.parse(chunks().get(seq) ?? '[]') as T[]) items.push(item);
+        }
+        return items;
+    } finally {
+        if (typeof sub === 'function') sub(); else sub?.unlisten?.();
+    }
+}
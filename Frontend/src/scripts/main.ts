@@ -7,14 +7,16 @@ import {
     bindLayoutActionState
 } from './ui/layout';
 import { bindCommandSheet, openSheet, closeSheet } from './features/commandSheet';
-import { bindRepoHotkeys, bindFilter, renderList, hydrateBranches, hydrateStatus, hydrateCommits } from './features/repo';
-import { bindBranchUI } from './features/branches';
+import { bindRepoHotkeys, bindFilter, renderList, hydrateBranches, hydrateStatus, hydrateCommits, applyStatus, applyPrefetch } from './features/repo';
+import { bindBranchUI, switchToPreviousBranch, undoHeadMove } from './features/branches';
 import { bindCommit } from './features/diff';
 import { openAbout } from './features/about';
 import { openModal } from './ui/modals';
 import { openSettings, loadSettingsIntoForm } from './features/settings';
 import { showUpdateDialog } from './features/update';
+import { openRepoHealth } from './features/repoHealth';
 import { openRepoSettings } from './features/repoSettings';
+import type { FetchSummary, GlobalSettings, LostRepo, OwnershipIssue, PluginInfo, Prefetch, RepoBusy, RepoSettings } from './types';
 
 // Title bar actions
 const fetchBtn = qs<HTMLButtonElement>('#fetch-btn');
@@ -23,6 +25,29 @@ const cloneBtn = qs<HTMLButtonElement>('#clone-btn');
 const repoSwitch = qs<HTMLButtonElement>('#repo-switch');
 const commitBtn = qs<HTMLButtonElement>('#commit-btn');
 
+/** Ask before the repository's pre-push check when hooks are set to "ask"; `undefined` otherwise. */
+async function askPrePush(): Promise<boolean | undefined> {
+    const [repo, cfg] = await Promise.all([
+        TAURI.invoke<RepoSettings>('get_repo_settings'),
+        TAURI.invoke<GlobalSettings>('get_global_settings'),
+    ]);
+    if (!repo?.pre_push_command || cfg?.git?.allow_hooks !== 'ask') return undefined;
+    return window.confirm(`Run the pre-push check before pushing?\n\n  ${repo.pre_push_command}`);
+}
+
+/** Short toast text for a fetch: what moved, and which branches now need a merge. */
+function fetchNotice(s: FetchSummary): string {
+    const parts: string[] = [];
+    if (s.new.length) parts.push(`${s.new.length} new`);
+    if (s.updated.length) parts.push(`${s.updated.length} updated`);
+    if (s.pruned.length) parts.push(`${s.pruned.length} pruned`);
+    let msg = parts.length ? `Fetched ${s.remote}: ${parts.join(', ')}` : `Fetched ${s.remote}: up to date`;
+    if (s.diverged.length) {
+        msg += ` — diverged: ${s.diverged.map(d => `${d.branch} (+${d.ahead}/-${d.behind})`).join(', ')}`;
+    }
+    return msg;
+}
+
 function boot() {
     // theme & basic layout
     // Prefer native settings for theme; fall back to current in-memory default
@@ -77,26 +102,33 @@ function boot() {
                 notify(behind > 0 ? 'Pulled (fast-forward)' : 'Already up to date');
             } else {
                 setBusy('Fetching…');
-                await TAURI.invoke('git_fetch', {});
-                notify('Fetched');
+                const s = await TAURI.invoke<FetchSummary>('git_fetch', {});
+                notify(fetchNotice(s));
             }
             await Promise.allSettled([hydrateStatus(), hydrateCommits()]);
-        } catch {
-            notify('Fetch/Pull failed');
+        } catch (e) {
+            const msg = String(e || '');
+            notify(`Fetch/Pull failed${msg ? `: ${msg}` : ''}`);
         } finally { clearBusy(); }
     });
-    pushBtn?.addEventListener('click', async () => {
+    // Shift+click pushes to every configured remote
+    pushBtn?.addEventListener('click', async (e: MouseEvent) => {
+        const allRemotes = e.shiftKey;
         const statusEl = document.getElementById('status');
         const setBusy = (msg: string) => {
             if (statusEl) { statusEl.textContent = msg; statusEl.classList.add('busy'); }
         };
         const clearBusy = () => { if (statusEl) statusEl.classList.remove('busy'); };
         try {
-            if (TAURI.has) { setBusy('Pushing…'); await TAURI.invoke('git_push', {}); }
-            notify('Pushed');
+            if (TAURI.has) {
+                const runPrePush = await askPrePush();
+                setBusy(allRemotes ? 'Pushing to all remotes…' : 'Pushing…');
+                await TAURI.invoke('git_push', { allRemotes, runPrePush });
+            }
+            notify(allRemotes ? 'Pushed to all remotes' : 'Pushed');
             // Refresh status/commits so ahead/behind and history update immediately
             await Promise.allSettled([hydrateStatus(), hydrateCommits()]);
-        } catch { notify('Push failed'); } finally { clearBusy(); }
+        } catch (err) { notify(allRemotes ? `Push failed: ${err}` : 'Push failed'); } finally { clearBusy(); }
     });
     cloneBtn?.addEventListener('click', () => openSheet('clone'));
     repoSwitch?.addEventListener('click', () => openSheet('switch'));
@@ -116,11 +148,14 @@ function boot() {
     TAURI.listen?.('menu', ({ payload: id }) => {
         switch (id) {
             case 'clone_repo': openSheet('clone'); break;
+            case 'init_repo':  openSheet('init');  break;
             case 'add_repo':   openSheet('add');   break;
             case 'open_repo':  openSheet('switch');break;
             case 'fetch': fetchBtn?.click(); break;
             case 'push':  pushBtn?.click();  break;
             case 'commit': commitBtn?.click(); break;
+            case 'previous_branch': void switchToPreviousBranch(); break;
+            case 'undo_head_move': void undoHeadMove(); break;
             case 'docs': notify('Open docs…'); break;
             case 'about': openAbout(); break;
             case 'settings': openSettings(); break;
@@ -137,15 +172,48 @@ function boot() {
             s.textContent = msg || 'Working…';
             s.classList.add('busy');
             if (busyTimer) clearTimeout(busyTimer);
-            // Clear after a short quiet period
+            // Clear after a short quiet period, unless an operation still holds the repo
             busyTimer = setTimeout(() => {
+                if (state.busy) return;
                 s.classList.remove('busy');
                 s.textContent = 'Ready';
             }, 1500);
         };
+        // Clicking the busy status cancels the job that last reported progress
+        let lastJob: number | null = null;
         TAURI.listen?.('git-progress', ({ payload }) => {
+            const job = (payload as any)?.job;
+            if (typeof job === 'number') lastJob = job;
             setBusy(String((payload as any)?.message || 'Working…'));
         });
+        // An operation holds the repository from `repo:busy` until `repo:idle`; conflicting
+        // actions stay disabled meanwhile (see refreshRepoActions)
+        const showHeld = (busy: RepoBusy | null) => {
+            state.busy = busy;
+            const s = document.getElementById('status');
+            if (s) {
+                s.classList.toggle('busy', !!busy);
+                s.textContent = busy ? `${busy.op.charAt(0).toUpperCase()}${busy.op.slice(1).replace(/-/g, ' ')}…` : 'Ready';
+            }
+            if (busy) lastJob = busy.job_id;
+            refreshRepoActions();
+        };
+        // ...or the one that is waiting for the repository, so it can be withdrawn
+        TAURI.listen?.('repo:op-queued', ({ payload }) => {
+            const job = (payload as any)?.job;
+            if (typeof job === 'number') lastJob = job;
+        });
+        TAURI.listen?.('repo:busy', ({ payload }) => showHeld(payload as RepoBusy));
+        TAURI.listen?.('repo:idle', () => showHeld(null));
+        window.addEventListener('app:repo-selected', () => {
+            TAURI.invoke<RepoBusy | null>('get_repo_busy').then(showHeld).catch(() => {});
+        });
+        document.getElementById('status')?.addEventListener('click', async (e) => {
+            const s = e.currentTarget as HTMLElement;
+            if (!s.classList.contains('busy') || lastJob == null) return;
+            const cancelled = await TAURI.invoke<boolean>('cancel_job', { job: lastJob }).catch(() => false);
+            if (cancelled) notify('Cancelling…');
+        });
     })();
 
   // repo selected -> refresh
@@ -153,12 +221,13 @@ function boot() {
         const path = typeof payload === 'string'
             ? payload
             : (payload?.path ?? payload?.repoPath ?? payload?.repo ?? payload?.dir ?? '');
-        if (path) notify(`Opened ${path}`);
+        if (path) notify(payload?.read_only ? `Opened ${path} read-only (the folder cannot be written)` : `Opened ${path}`);
         setRepoHeader(path);
         closeSheet();
 
         await hydrateBranches();
         setRepoHeader(path);
+        state.changesScope = await TAURI.invoke<string | null>('get_changes_scope').catch(() => null);
         await Promise.allSettled([hydrateStatus(), hydrateCommits()]);
 
         // Broadcast app-level event so branch UI and actions can sync
@@ -187,6 +256,119 @@ function boot() {
       try { notify(String((payload as any) ?? '')); } catch {}
   });
 
+    // git refused a repository owned by another user -> offer to trust it, then retry
+    TAURI.listen?.('repo:dubious-ownership', async ({ payload }) => {
+        const issue = payload as OwnershipIssue | undefined;
+        if (!issue?.path) return;
+        const ok = window.confirm(
+            `${issue.path} is owned by another user, so Git refuses to open it.\n\n` +
+            `This is common for network shares, external drives and other accounts' checkouts. ` +
+            `Only trust it if you know who controls this folder: its hooks and config can run commands as you.\n\n` +
+            `Mark it as a safe directory (git config --global --add safe.directory) and open it?`
+        );
+        if (!ok) return;
+        try {
+            await TAURI.invoke('mark_safe_directory', { path: issue.path });
+            await TAURI.invoke('open_repo', { path: issue.requested });
+        } catch (e) {
+            notify(`Could not open ${issue.requested}: ${e}`);
+        }
+    });
+
+    // working tree moved, renamed or unmounted -> relocate or close
+    TAURI.listen?.('repo:lost', async ({ payload }) => {
+        const lost = payload as LostRepo | undefined;
+        if (!lost?.path) return;
+        const guess = lost.candidates[0] ?? '';
+        const where = window.prompt(
+            `${lost.path} can no longer be found. It may have been moved, renamed or be on a drive that is not mounted.\n\n` +
+            (guess ? `It looks like it is now at ${guess}.\n\n` : '') +
+            `Enter its new location to reopen it, or cancel to close it:`,
+            guess
+        );
+        try {
+            if (where?.trim() && lost.options.includes('relocate')) {
+                await TAURI.invoke('relocate_repo', { path: where.trim() });
+            } else if (lost.options.includes('close')) {
+                await TAURI.invoke('close_repo');
+                resetRepoHeader();
+                refreshRepoActions();
+            }
+        } catch (e) {
+            notify(`Could not open ${where}: ${e}`);
+        }
+    });
+
+    TAURI.listen?.('repo:found', async ({ payload }) => {
+        const path = (payload as { path?: string } | undefined)?.path;
+        if (path) notify(`${path} is available again`);
+        await Promise.allSettled([hydrateStatus(), hydrateCommits()]);
+    });
+
+    // reports left by a previous crash -> ask before sending anything
+    void (async () => {
+        if (!TAURI.has) return;
+        const pending = await TAURI.invoke<{ reports: any[]; can_submit: boolean }>('list_crash_reports').catch(() => null);
+        const canSubmit = pending?.can_submit ?? false;
+        for (const r of pending?.reports ?? []) {
+            const when = new Date(r.time * 1000).toLocaleString();
+            const op = r.last_op ? `\nLast operation: ${r.last_op}` : '';
+            // Without a report endpoint in this build the only choice is to keep or delete it
+            const question = canSubmit ? 'Send this crash report?' : 'Delete this crash report?';
+            const yes = window.confirm(`OpenVCS crashed on ${when}:\n${r.message}${op}\n\n${question}`);
+            try {
+                if (yes && canSubmit) { await TAURI.invoke('submit_crash_report', { id: r.id }); notify('Crash report sent'); }
+                else if (canSubmit || yes) { await TAURI.invoke('dismiss_crash_report', { id: r.id }); }
+            } catch (e) {
+                notify(`Crash report: ${e}`);
+            }
+        }
+    })();
+
+    // plugins: ask before granting what a plugin declares, surface what they report
+    TAURI.listen?.('plugins:permission-request', async ({ payload }) => {
+        const plugins = (payload as PluginInfo[]) ?? [];
+        for (const p of plugins) {
+            const perms = p.permissions.length ? p.permissions.join(', ') : 'none';
+            const desc = p.description ? `\n${p.description}` : '';
+            const grant = window.confirm(`Plugin "${p.name}" (${p.path})${desc}\n\nPermissions: ${perms}\nRuns on: ${p.events.join(', ') || 'nothing'}\n\nAllow this plugin to run?`);
+            try { await TAURI.invoke('set_plugin_granted', { id: p.id, granted: grant }); }
+            catch (e) { notify(`Plugin ${p.name}: ${e}`); }
+        }
+    });
+    TAURI.listen?.('plugins:notify', ({ payload }) => {
+        const { plugin, message } = payload as { plugin: string; message: string };
+        notify(`${plugin}: ${message}`);
+    });
+    TAURI.listen?.('plugins:error', ({ payload }) => {
+        const { plugin, event, message } = payload as { plugin: string; event: string; message: string };
+        notify(`Plugin ${plugin} failed on ${event}: ${message}`);
+    });
+
+    // mutating operations are serialized per repo; tell the user when one has to wait
+    TAURI.listen?.('repo:op-queued', ({ payload }) => {
+        const p = payload as any;
+        notify(`${p?.op ?? 'Operation'} queued: waiting for ${p?.behind?.op ?? 'another operation'} to finish… (click the status bar to cancel)`);
+    });
+
+    // background status refreshes pushed by the backend orchestrator
+    TAURI.listen?.('repo:status', ({ payload }) => {
+        const p = payload as any;
+        if (p?.status) applyStatus(p.status);
+    });
+
+    // first history page, branches and HEAD commit read ahead after a repository opened
+    TAURI.listen?.('repo:prefetch', ({ payload }) => {
+        const p = payload as Prefetch | undefined;
+        if (p?.kind) applyPrefetch(p);
+    });
+
+    // refs moved outside the app (e.g. git in a terminal); status follows as `repo:status`
+    TAURI.listen?.('repo:refs-changed', async () => {
+        await Promise.allSettled([hydrateBranches(), hydrateCommits()]);
+        refreshRepoActions();
+    });
+
     // update available payload from backend -> open modal with notes
     TAURI.listen?.('ui:update-available', ({ payload }) => {
         showUpdateDialog(payload);
@@ -200,7 +382,8 @@ function boot() {
         async function refreshAll() {
             const statusEl = document.getElementById('status');
             if (statusEl) statusEl.textContent = 'Refreshing…';
-            await Promise.allSettled([hydrateBranches(), hydrateStatus(), hydrateCommits()]);
+            // status arrives separately as a coalesced `repo:status` event
+            await Promise.allSettled([hydrateBranches(), hydrateCommits()]);
             if (statusEl) statusEl.textContent = 'Ready';
         }
 
@@ -215,6 +398,7 @@ function boot() {
       TAURI.listen?.('ui:open-settings', () => openModal('settings-modal'));
       TAURI.listen?.('ui:open-about', () => openAbout());
       TAURI.listen?.('ui:open-repo-settings', () => openRepoSettings());
+      TAURI.listen?.('ui:open-repo-health', () => openRepoHealth());
   }
 
 boot();
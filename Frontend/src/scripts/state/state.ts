@@ -1,5 +1,5 @@
 // src/state/state.ts
-import type { AppPrefs, Branch, CommitItem, FileStatus } from '../types';
+import type { AppPrefs, Branch, CommitItem, FileStatus, RepoBusy } from '../types';
 
 export const defaultPrefs: AppPrefs = {
     theme: matchMedia('(prefers-color-scheme: dark)').matches ? 'dark' : 'light',
@@ -16,9 +16,11 @@ export function savePrefs() {
 export const state = {
     hasRepo: false,                 // backend truth (set after open/clone/add)
     branch: '' as string,           // current branch name
+    unborn: false as boolean,       // current branch has no commits yet
     branches: [] as Branch[],       // list of branches
     files: [] as FileStatus[],      // working tree status
     commits: [] as CommitItem[],    // recent commits
+    commitsComplete: true as boolean, // `commits` holds the whole history
     ahead: 0 as number,             // commits ahead of upstream
     behind: 0 as number,            // commits behind upstream
     defaultSelectAll: true as boolean, // by default select all files/hunks until user toggles
@@ -29,6 +31,8 @@ export const state = {
     selectedHunks: [] as number[],  // indices of selected hunks for current file
     selectedHunksByFile: {} as Record<string, number[]>,
     diffSelectedFiles: new Set<string>(), // files included in multi-file diff viewer
+    changesScope: null as string | null, // directory the Changes list is limited to
+    busy: null as RepoBusy | null,  // operation holding the repo (`repo:busy` until `repo:idle`)
     // Optional: track the current repo path if you want to show it anywhere
     // repoPath: '' as string,
 };
@@ -36,16 +40,22 @@ export const state = {
 /** True iff a repo is selected AND we know the current branch. Always boolean. */
 export const hasRepo = (): boolean => Boolean(state.hasRepo && state.branch);
 
+/** True while an operation holds the repository; actions that modify it would only queue. */
+export const isBusy = (): boolean => state.busy !== null;
+
 /** True iff there are staged/unstaged changes. Always boolean. */
 export const hasChanges = (): boolean =>
-    Array.isArray(state.files) && state.files.length > 0;
+    Array.isArray(state.files) && state.files.some(f => f.status !== '!');
 
 export const statusLabel = (s: string) =>
     s === 'A' ? 'Added' :
         s === 'M' ? 'Modified' :
-            s === 'D' ? 'Deleted' : 'Changed';
+            s === 'D' ? 'Deleted' :
+                s === 'T' ? 'Type changed' :
+                    s === '!' ? 'Ignored' : 'Changed';
 
 export const statusClass = (s: string) =>
     s === 'A' ? 'add' :
         s === 'M' ? 'mod' :
-            s === 'D' ? 'del' : 'mod';
+            s === 'D' ? 'del' :
+                s === '!' ? 'ign' : 'mod';
@@ -8,19 +8,270 @@ export interface Branch {
     name: string;
     current?: boolean;
     kind?: BranchKind;
+    upstream_gone?: boolean;
+    /** `branch.<name>.description` (local branches only). */
+    description?: string;
+    /** Notes kept by the app for this branch. */
+    notes?: string;
+}
+
+/** What HEAD points at (`git_head_status().state`). */
+export type HeadState =
+    | { kind: 'branch'; name: string; commit: string }
+    | { kind: 'detached'; commit: string; tags: string[] }
+    | { kind: 'unborn'; branch: string };
+
+export interface HeadStatus {
+    detached: boolean;
+    branch?: string | null;
+    commit?: string | null;
+    state: HeadState;
+    /** "main", "HEAD detached at v1.2.0", "No commits yet on main". */
+    label: string;
+}
+
+/** `check_large_files` result: files over the commit size threshold. */
+export interface LargeFileWarning {
+    threshold_bytes: number;
+    files: { path: string; size: number }[];
+    lfs_patterns: string[];
+    suggestion: string;
+}
+
+export type SecretScanMode = 'off' | 'warn' | 'block';
+
+export interface SecretRule {
+    id: string;
+    description: string;
+    /** Regular expression matched against each added line. */
+    pattern: string;
+}
+
+export interface SecretFinding {
+    rule: string;
+    description: string;
+    path: string;
+    line: number;
+    /** The line with the secret masked. */
+    excerpt: string;
+}
+
+/** `scan_secrets` result. */
+export interface SecretScanReport {
+    mode: SecretScanMode;
+    findings: SecretFinding[];
+}
+
+/** A recently checked out branch (`recent_branches`). */
+export interface RecentBranch {
+    name: string;
+    /** Unix time of the last checkout. */
+    last_used: number;
 }
 
 export interface FileStatus {
     path: string;
     status: 'A'|'M'|'D'|string;
     hunks?: string[];
+    /** Lines added/removed against HEAD; absent for binary files. */
+    additions?: number | null;
+    deletions?: number | null;
+    /** Size in the working tree minus size in HEAD, in bytes. */
+    size_delta?: number | null;
+    mode_change?: ModeChange | null;
+    /** Files under a directory listed as one row (path ends with `/`); counting stops at 100000. */
+    file_count?: number | null;
+}
+
+/** Tracked paths that differ only by case (see `StatusPayload.case_collisions`). */
+export interface CaseCollision {
+    folded: string;
+    paths: string[];
+}
+
+export type EntryKind = 'file' | 'executable' | 'symlink' | 'submodule';
+
+export type ModeChange =
+    | { kind: 'executable'; executable: boolean }
+    | { kind: 'type'; from: EntryKind; to: EntryKind }
+    | { kind: 'symlink_target' };
+
+export interface DiffLine {
+    kind: 'context' | 'add' | 'del';
+    text: string;
+    old_line: number | null;
+    new_line: number | null;
+    /** Differs only in whitespace under `diff.ignore_whitespace`. */
+    whitespace_only: boolean;
+    no_newline: boolean;
+}
+
+export interface DiffHunk {
+    /** `<file>.<hunk>`, both 0-based; for "next change" navigation. */
+    id: string;
+    header: string;
+    /** Enclosing function git printed after the `@@` ranges. */
+    function: string | null;
+    old_start: number;
+    old_lines: number;
+    new_start: number;
+    new_lines: number;
+    lines: DiffLine[];
+    whitespace_only: boolean;
+}
+
+export interface LfsPointer {
+    oid: string;
+    size: number;
+}
+
+/** Result of `git_diff_file_structured` / `git_diff_commit_structured`. */
+export interface FileDiff {
+    path: string;
+    old_path: string | null;
+    header: string[];
+    binary: boolean;
+    old_mode: number;
+    new_mode: number;
+    mode_change: ModeChange | null;
+    /** `filter` gitattribute, e.g. `lfs`: the diff shows cleaned (stored) content. */
+    filter: string | null;
+    /** Set for LFS pointer changes; `hunks` is empty then. */
+    lfs: { old: LfsPointer | null; new: LfsPointer | null } | null;
+    hunks: DiffHunk[];
+    whitespace_only: boolean;
+}
+
+/** Seconds since the Unix epoch and the recorder's UTC offset (east, in minutes). */
+export interface Timestamp {
+    seconds: number;
+    offset_minutes: number;
 }
 
 export interface CommitItem {
     id: string;
     msg?: string;
+    /** "<ISO date> • <short id>", for display only. */
     meta?: string;
+    /** "Name <email>". */
     author?: string;
+    author_name?: string;
+    author_email?: string;
+    committer_name?: string;
+    committer_email?: string;
+    /** Author time in Unix seconds (`author_time.seconds`). */
+    timestamp?: number;
+    author_time?: Timestamp;
+    committer_time?: Timestamp;
+    short_id?: string;
+}
+
+/** `git_commit_fetch_need`: files of a commit a partial clone would fetch before diffing it. */
+export interface FetchNeed {
+    remote: string;
+    paths: string[];
+    /** Offline mode is on: the diff is not fetched. */
+    offline: boolean;
+}
+
+/** `Key: value` line closing a commit message (`Signed-off-by`, `Co-authored-by`, `Fixes`, ...). */
+export interface Trailer {
+    key: string;
+    value: string;
+}
+
+/** `git_commit_detail`: full metadata of one commit. */
+export interface CommitRecord {
+    id: string;
+    parents: string[];
+    author_name: string;
+    author_email: string;
+    author_date: string;
+    committer_name: string;
+    committer_email: string;
+    committer_date: string;
+    summary: string;
+    body: string;
+    trailers: Trailer[];
+}
+
+/** `git_log_window`: a window of history served from the backend's open walk. */
+export interface LogWindow {
+    start: number;
+    items: CommitItem[];
+    /** Position of the anchor commit, when one was given. */
+    anchor_index: number | null;
+    /** Commits read so far (the total once `complete`). */
+    loaded: number;
+    complete: boolean;
+    /** Shallow clone: the history stops at its depth. */
+    truncated: boolean;
+}
+
+/** `repo:prefetch`: data read ahead in the background after a repository opened. */
+export type Prefetch = { path: string; job: number } & (
+    | { kind: 'log'; data: LogWindow }
+    | { kind: 'branches'; data: { path: string; current_branch: string; branches: any[]; is_shallow: boolean } }
+    | { kind: 'head-commit'; data: CommitRecord }
+);
+
+/** Overrides for one commit (`options` of the commit commands); the committer stays you. */
+export interface CommitOptions {
+    author?: { name: string; email: string } | null;
+    author_date?: Timestamp | null;
+}
+
+/** `stash_list` entry, newest first; preview with `stash_diff(index)`. */
+export interface StashEntry {
+    index: number;
+    id: string;
+    /** `WIP on main: 1a2b3c4 Fix` or `On main: <message>`. */
+    message: string;
+    /** Branch it was made on; null when HEAD was detached. */
+    branch?: string | null;
+    time: Timestamp;
+}
+
+/** `options` of `stash_save`. */
+export interface StashOptions {
+    /** Listed as `On <branch>: <message>`. */
+    message?: string | null;
+    /** Stash untracked files too. */
+    include_untracked?: boolean;
+    /** Leave staged changes in place. */
+    keep_index?: boolean;
+}
+
+export interface RefChange {
+    full_ref: string;
+    old: string | null;
+    new: string | null;
+}
+export interface FetchSummary {
+    remote: string;
+    new: RefChange[];
+    updated: RefChange[];
+    pruned: RefChange[];
+    diverged: { branch: string; upstream: string; ahead: number; behind: number }[];
+}
+
+/** `network_stats`: what one fetch, pull, push or clone transferred. */
+export interface TransferStats {
+    op: 'fetch' | 'pull' | 'push' | 'clone';
+    /** Remote name; the repository name for a clone. */
+    remote: string;
+    job?: number | null;
+    /** Unix seconds. */
+    started: number;
+    duration_ms: number;
+    /** Objects the remote enumerated while negotiating. */
+    enumerated?: number | null;
+    objects: number;
+    deltas: number;
+    bytes: number;
+    bytes_per_sec: number;
+    /** `ok`, or the error kind. */
+    outcome: string;
 }
 
 export interface AppPrefs {
@@ -39,13 +290,22 @@ export interface GlobalSettings {
         checks_on_launch?: boolean;
         telemetry?: boolean;
         crash_reports?: boolean;
+        shell_integration?: boolean;
     };
     git?: {
         backend?: 'system'|'libgit2'|string;
+        /** Git executable or install directory; empty uses PATH. */
+        executable_path?: string;
         default_branch?: string;
         prune_on_fetch?: boolean;
+        autostash_on_pull?: boolean;
+        wip_autosave_minutes?: number;
         allow_hooks?: string;
         respect_core_autocrlf?: boolean;
+        secret_scan?: SecretScanMode;
+        secret_rules?: SecretRule[];
+        disabled_secret_rules?: string[];
+        offline_partial_clone?: boolean;
     };
     diff?: {
         tab_width?: number;
@@ -62,10 +322,12 @@ export interface GlobalSettings {
         concurrency?: number;
         require_lock_before_edit?: boolean;
         background_fetch_on_checkout?: boolean;
+        large_file_warn_mb?: number;
     };
     performance?: {
         progressive_render?: boolean;
         gpu_accel?: boolean;
+        status_refresh_min_ms?: number;
     };
     ux?: {
         ui_scale?: number;
@@ -73,16 +335,207 @@ export interface GlobalSettings {
         vim_nav?: boolean;
         color_blind_mode?: string;
         recents_limit?: number;
+        /** List ignored files in Changes (status `!`; an ignored directory as one row). */
+        show_ignored?: boolean;
+        /** List a new directory as one row instead of every file in it. */
+        collapse_untracked_dirs?: boolean;
+    };
+    advanced?: {
+        confirm_force_push?: 'always'|'tracked-remotes'|'never'|string;
+        confirm_remote_delete?: boolean;
+        ssl_verify?: boolean;
     };
     logging?: {
         level?: 'trace'|'debug'|'info'|'warn'|'error'|string;
         live_viewer?: boolean;
         retain_archives?: number;
+        format?: 'text'|'json'|string;
+        max_file_size_mb?: number;
     };
+    plugins?: {
+        enabled?: boolean;
+        granted?: Record<string, PluginPermission[]>;
+    };
+}
+
+export type PluginPermission = 'notify' | 'read-repo' | 'fetch';
+
+export interface PluginInfo {
+    id: string;
+    name: string;
+    description: string;
+    path: string;
+    events: ('post-commit' | 'post-pull' | 'repo-opened')[];
+    permissions: PluginPermission[];
+    granted: boolean;
+    error?: string;
+}
+
+/** A worktree of the open repository (`list_worktrees`, `RepoSummary.worktrees`). */
+export interface WorktreeStatus {
+    path: string;
+    branch?: string | null;
+    head?: string | null;
+    current: boolean;
+    bare: boolean;
+    locked?: string | null;
+    prunable: boolean;
+    /** Files with changes; null when unknown. */
+    changes?: number | null;
+}
+
+export interface HookTemplate {
+    id: string;
+    /** Hook it installs as, e.g. `pre-commit`. */
+    hook: string;
+    title: string;
+    description: string;
+    version: number;
+}
+
+export type HookSource =
+    | { kind: 'template'; id: string; version: number; outdated: boolean }
+    | { kind: 'pre-commit' }
+    | { kind: 'husky' }
+    | { kind: 'custom' };
+
+export interface HookInfo {
+    name: string;
+    path: string;
+    source: HookSource;
+    executable: boolean;
+}
+
+/** Entry of `list_actions`; run it with `run_action`. */
+export interface ActionInfo {
+    id: string;
+    title: string;
+    category: 'File' | 'Repository' | 'Help';
+    keybinding?: string;
+    enabled: boolean;
+    /** Why it is disabled. */
+    reason?: string;
+}
+
+/** A config value as git resolves it (`RepoSettings.sources`). */
+export interface ConfigValue {
+    value: string;
+    scope: 'system' | 'global' | 'local' | 'worktree' | 'command';
+    /** File it was read from (an include target for included values); null when unknown. */
+    origin?: string | null;
 }
 
 export interface RepoSettings {
     user_name?: string;
     user_email?: string;
     origin_url?: string;
+    /** Notes per local branch name. */
+    branch_notes?: Record<string, string>;
+    /** Command run before every push; a failure aborts the push. */
+    pre_push_command?: string;
+    /** Where `user.name`, `user.email` and `core.hooksPath` come from (read-only). */
+    sources?: Record<string, ConfigValue>;
+}
+
+export interface CommitDraft {
+    summary: string;
+    description: string;
+    /** Unix seconds of the last save. */
+    updated: number;
+}
+
+/** `get_commit_draft`: what the commit box starts with on the current branch. */
+export interface CommitDraftState {
+    branch: string;
+    draft?: CommitDraft | null;
+    /** Repository template (`commit.template`, `.github/`, `.gitmessage`), comments stripped. */
+    template?: { source: string; text: string } | null;
+}
+
+/** `pushed_commits`: a commit already on a remote, which rewriting commands guard. */
+export interface PushedCommit {
+    id: string;
+    /** Remote-tracking branches containing it, e.g. `origin/main`. */
+    remotes: string[];
+}
+
+/** `last_head_move`: how "Undo Checkout/Reset" would put HEAD back. */
+export interface HeadMove {
+    kind: 'checkout' | 'reset';
+    /** Branch or commit to return to. */
+    target: string;
+    /** What undoing does, e.g. "Switch back to main". */
+    label: string;
+    /** Reflog message of the move being undone. */
+    message: string;
+    /** For a reset: commits undoing takes off the branch, newest first. */
+    dropped: string[];
+}
+
+/** `operation_progress`: a multi-commit cherry-pick or revert in progress. */
+export interface Operation {
+    kind: 'cherry-pick' | 'revert';
+    orig_head: string;
+    /** Full ids, in the order they are applied. */
+    commits: string[];
+    /** How many of `commits` are applied or skipped. */
+    done: number;
+    skipped: string[];
+    /** Files `commits[done]` conflicted in; resolve and stage them, then continue. */
+    conflicts: string[];
+    started: number;
+}
+
+/** `repo:dubious-ownership`: git refused to open a repository owned by another user. */
+export interface OwnershipIssue {
+    /** What was being opened. */
+    requested: string;
+    /** The repository directory to mark as `safe.directory`. */
+    path: string;
+}
+
+/** Payload of `repo:lost`: the open repository's working tree disappeared. */
+export interface LostRepo {
+    path: string;
+    /** Sibling directories with the same HEAD, likely the repository renamed. */
+    candidates: string[];
+    /** `relocate` and/or `close`. */
+    options: string[];
+}
+
+/** `git_list_tags` entry, sorted by name. */
+export interface TagItem {
+    name: string;
+    /** Commit the tag points at. */
+    target: string;
+    /** A tag object with tagger and message; false for lightweight tags. */
+    annotated: boolean;
+    /** First line of the annotation; empty for lightweight tags. */
+    summary: string;
+    tagger?: { name: string; email: string } | null;
+    /** Tagger date, or the commit's date for lightweight tags. */
+    time: Timestamp;
+}
+
+/** Options of `git_merge_branch`; every field may be omitted. */
+export interface MergeOptions {
+    /** `allow` (default), `never` (always a merge commit) or `only` (fail unless it fast-forwards). */
+    fast_forward?: 'allow' | 'never' | 'only';
+    /** Merge commit message; git's `Merge branch '…'` when omitted. */
+    message?: string | null;
+}
+
+/** What `git_merge_branch` did. `conflicted` leaves the merge in progress: commit once the files are resolved. */
+export type MergeOutcome =
+    | { kind: 'up-to-date' }
+    | { kind: 'fast-forward'; commit: string }
+    | { kind: 'merged'; commit: string }
+    | { kind: 'conflicted'; files: string[] };
+
+/** `repo:busy` / `get_repo_busy`: the operation holding a repository (others queue behind it). */
+export interface RepoBusy {
+    path: string;
+    /** e.g. `commit`, `pull`, `merge`, `rebase`. */
+    op: string;
+    job_id: number;
 }
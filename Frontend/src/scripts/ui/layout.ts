@@ -1,5 +1,5 @@
 import { qs, qsa, setText } from '../lib/dom';
-import { prefs, savePrefs, state, hasRepo, hasChanges } from '../state/state';
+import { prefs, savePrefs, state, hasRepo, hasChanges, isBusy } from '../state/state';
 import { TAURI } from '../lib/tauri';
 import { notify } from '../lib/notify';
 
@@ -111,6 +111,7 @@ export function initResizer() {
 export function refreshRepoActions() {
     const repoOn       = hasRepo();
     const changesOn    = hasChanges();
+    const idle         = !isBusy();
 
     const fetchBtn = qs<HTMLButtonElement>('#fetch-btn');
     const pushBtn  = qs<HTMLButtonElement>('#push-btn');
@@ -119,10 +120,10 @@ export function refreshRepoActions() {
     const desc     = qs<HTMLTextAreaElement>('#commit-desc');
     const commit   = qs<HTMLButtonElement>('#commit-btn');
 
-    // Repo-scoped actions
-    if (fetchBtn)  fetchBtn.disabled  = !repoOn;
-    if (pushBtn)   pushBtn.disabled   = !repoOn;
-    if (branchBtn) branchBtn.disabled = !repoOn;
+    // Repo-scoped actions; off while another operation holds the repo
+    if (fetchBtn)  fetchBtn.disabled  = !(repoOn && idle);
+    if (pushBtn)   pushBtn.disabled   = !(repoOn && idle);
+    if (branchBtn) branchBtn.disabled = !(repoOn && idle);
 
     // Text inputs are ONLY enabled when there are active changes in an open repo
     if (summary) summary.disabled = !(repoOn && changesOn);
@@ -134,7 +135,7 @@ export function refreshRepoActions() {
     const hunksSelected = Object.keys((state as any).selectedHunksByFile || {})
         .some((k) => Array.isArray((state as any).selectedHunksByFile[k]) && (state as any).selectedHunksByFile[k].length > 0);
     const filesSelected = !!((state as any).selectedFiles && (state as any).selectedFiles.size > 0);
-    if (commit)  commit.disabled  = !(repoOn && changesOn && summaryFilled && (hunksSelected || filesSelected));
+    if (commit)  commit.disabled  = !(repoOn && idle && changesOn && summaryFilled && (hunksSelected || filesSelected));
 
     // Optional hygiene: if changes disappear, clear any stale text so the next enablement starts clean
     if (!changesOn) {
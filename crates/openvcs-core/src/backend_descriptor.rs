@@ -5,16 +5,26 @@
 use std::path::Path;
 use std::sync::Arc;
 use crate::backend_id::BackendId;
-use crate::models::{Capabilities, OnEvent};
+use crate::models::{Capabilities, OnEvent, RemoteRefs};
 use crate::Vcs;
 
+/// Clone factory signature: `(url, dest, progress) -> repo handle`.
+pub type CloneFn = fn(&str, &Path, Option<OnEvent>) -> crate::Result<Arc<dyn Vcs>>;
+
 /// Factory & metadata for a backend implementation.
 pub struct BackendDescriptor {
     pub id: BackendId,
     pub name: &'static str,
     pub caps: fn() -> Capabilities,
+    /// Version of the underlying engine (e.g. the git executable or linked libgit2),
+    /// queried on demand; `None` if it can't be determined.
+    pub version: fn() -> Option<String>,
     pub open: fn(&Path) -> crate::Result<Arc<dyn Vcs>>,
-    pub clone_repo: fn(&str, &Path, Option<OnEvent>) -> crate::Result<Arc<dyn Vcs>>,
+    pub clone_repo: CloneFn,
+    pub init_repo: fn(&Path) -> crate::Result<Arc<dyn Vcs>>,
+    /// List the branches and tags a URL advertises, without a local repository
+    /// (for the clone dialog).
+    pub remote_refs: fn(&str) -> crate::Result<RemoteRefs>,
 }
 
 /// The global registry. Each backend crate declares exactly one `BackendDescriptor` here.
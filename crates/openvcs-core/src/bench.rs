@@ -0,0 +1,115 @@
+//! Backend benchmarks (feature `bench`).
+//!
+//! The [`WORKLOADS`] are what the app runs over and over on a repository: status on every
+//! refresh, the first page of history, history filtered to a path, and the diffs behind
+//! the file and commit views. [`measure`] times one against one backend; the criterion
+//! benches and `openvcs bench` both run them, on a generated repository or a real one, so
+//! a slow path in one backend (libgit2's path-filtered log, say) shows up as a number
+//! next to the other backend's.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::models::{LogQuery, StatusOptions};
+use crate::{Result, Vcs, VcsError};
+
+/// Commits a history page asks for, as the history view's first load.
+const PAGE: u32 = 100;
+
+/// One operation to time.
+pub struct Workload {
+    pub name: &'static str,
+    pub run: fn(&dyn Vcs, &Subject) -> Result<()>,
+}
+
+pub const WORKLOADS: &[Workload] = &[
+    Workload { name: "status", run: status },
+    Workload { name: "log", run: log },
+    Workload { name: "log-path", run: log_path },
+    Workload { name: "diff-file", run: diff_file },
+    Workload { name: "diff-commit", run: diff_commit },
+];
+
+/// What the workloads look at, found once per repository with [`Subject::probe`].
+#[derive(Debug, Clone)]
+pub struct Subject {
+    /// Full id of HEAD.
+    pub head: String,
+    /// A file HEAD changed, for the path-filtered log.
+    pub path: String,
+    /// A file with uncommitted changes, or [`path`](Self::path) in a clean tree.
+    pub changed: PathBuf,
+}
+
+impl Subject {
+    pub fn probe(vcs: &dyn Vcs) -> Result<Self> {
+        let none = |what: &str| VcsError::Backend { backend: vcs.id(), msg: format!("nothing to benchmark: {what}") };
+        let head = vcs.log_commits(&LogQuery { rev: Some("HEAD".into()), ..LogQuery::head(1) })?;
+        let head = head.into_iter().next().ok_or_else(|| none("no commits"))?.id;
+        let path = vcs
+            .commit_file_stats(&head)?
+            .into_iter()
+            .map(|f| f.path)
+            .next()
+            .ok_or_else(|| none("HEAD changes no files"))?;
+        let status = vcs.status_payload(&StatusOptions::default())?;
+        let changed = status.files.into_iter().find(|f| f.status == "M").map_or_else(|| path.clone(), |f| f.path);
+        Ok(Self { head, path, changed: PathBuf::from(changed) })
+    }
+}
+
+fn status(vcs: &dyn Vcs, _: &Subject) -> Result<()> {
+    vcs.status_payload(&StatusOptions::default()).map(drop)
+}
+
+fn log(vcs: &dyn Vcs, _: &Subject) -> Result<()> {
+    vcs.log_commits(&history(None)).map(drop)
+}
+
+fn log_path(vcs: &dyn Vcs, s: &Subject) -> Result<()> {
+    vcs.log_commits(&history(Some(&s.path))).map(drop)
+}
+
+fn diff_file(vcs: &dyn Vcs, s: &Subject) -> Result<()> {
+    vcs.diff_file(&s.changed).map(drop)
+}
+
+fn diff_commit(vcs: &dyn Vcs, s: &Subject) -> Result<()> {
+    vcs.diff_commit(&s.head).map(drop)
+}
+
+/// The history view's query, optionally filtered to `path`.
+fn history(path: Option<&str>) -> LogQuery {
+    LogQuery { path: path.map(str::to_string), topo_order: true, include_merges: true, ..LogQuery::head(PAGE) }
+}
+
+/// Times of `iterations` runs of one workload, after one untimed run to warm caches.
+#[derive(Debug, Clone)]
+pub struct Timing {
+    pub samples: Vec<Duration>,
+}
+
+impl Timing {
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.samples.clone();
+        sorted.sort();
+        sorted.get(sorted.len() / 2).copied().unwrap_or_default()
+    }
+
+    pub fn min(&self) -> Duration {
+        self.samples.iter().min().copied().unwrap_or_default()
+    }
+}
+
+pub fn measure(vcs: &dyn Vcs, subject: &Subject, workload: &Workload, iterations: usize) -> Result<Timing> {
+    (workload.run)(vcs, subject)?;
+    let mut samples = Vec::with_capacity(iterations);
+    for _ in 0..iterations.max(1) {
+        let started = Instant::now();
+        (workload.run)(vcs, subject)?;
+        samples.push(started.elapsed());
+    }
+    let timing = Timing { samples };
+    log::debug!("bench: {} on {}: median {:?}", workload.name, vcs.id(), timing.median());
+    Ok(timing)
+}
@@ -0,0 +1,59 @@
+//! Cooperative cancellation for long-running operations (fetch, pull, clone, push).
+//!
+//! The caller installs a [`CancelToken`] for the current thread with [`scope`] before
+//! calling into a backend; backends poll [`is_cancelled`] at safe points (between progress
+//! callbacks, while waiting on a child process) and give up with [`VcsError::Cancelled`].
+//! Keeping the token thread-scoped means no `Vcs` method signature has to carry it.
+//!
+//! [`VcsError::Cancelled`]: crate::VcsError::Cancelled
+
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Shared flag; clones observe the same cancellation.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// Restores the previously installed token when dropped.
+pub struct ScopeGuard(Option<CancelToken>);
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        let prev = self.0.take();
+        CURRENT.with(|c| *c.borrow_mut() = prev);
+    }
+}
+
+/// Make `token` the current thread's token until the guard is dropped.
+pub fn scope(token: CancelToken) -> ScopeGuard {
+    ScopeGuard(CURRENT.with(|c| c.borrow_mut().replace(token)))
+}
+
+/// The current thread's token, if any (to hand to helper threads or callbacks).
+pub fn current() -> Option<CancelToken> {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+/// Whether the current thread's operation has been cancelled.
+pub fn is_cancelled() -> bool {
+    CURRENT.with(|c| c.borrow().as_ref().is_some_and(CancelToken::is_cancelled))
+}
@@ -0,0 +1,585 @@
+//! Backend conformance suite (feature `test-support`).
+//!
+//! Switching backends must not change what a repository looks like. Each of [`SCENARIOS`]
+//! is a short script of ordinary operations (init, branch, commit, merge, stash, log
+//! filters, …) that records what the backend reports into a [`Transcript`], reduced to
+//! what may not depend on the backend: commits are named by their summary instead of
+//! their id, times are left out and lists are sorted. [`assert_parity`] runs every
+//! scenario against every registered backend, each in a fresh temporary repository, and
+//! fails listing the first step where transcripts differ. Operations a backend doesn't
+//! implement are listed by the caller as [`KnownGap`]s.
+//!
+//! Run it from an integration test that links the backends to compare:
+//!
+//! ```ignore
+//! use openvcs_git as _;
+//! use openvcs_git_libgit2 as _;
+//!
+//! #[test]
+//! fn backends_agree() {
+//!     openvcs_core::conformance::assert_parity(&[("stage-patch", "git-libgit2")]);
+//! }
+//! ```
+
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::backend_descriptor::{list_backends, BackendDescriptor};
+use crate::models::{CommitOptions, FastForward, Identity, LogQuery, MergeOptions, MergeOutcome, StashOptions, StatusOptions};
+use crate::{Result, Vcs, VcsError};
+
+const NAME: &str = "Conformance";
+const EMAIL: &str = "conformance@example.com";
+/// How [`Script::record_result`] records `VcsError::Unsupported`.
+const UNSUPPORTED: &str = "Err(\"unsupported\")";
+
+/// A named script run against each backend.
+pub struct Scenario {
+    pub name: &'static str,
+    pub run: fn(&mut Script) -> Result<()>,
+}
+
+/// Every scenario, in the order [`assert_parity`] runs them.
+pub const SCENARIOS: &[Scenario] = &[
+    Scenario { name: "init", run: init },
+    Scenario { name: "branch", run: branch },
+    Scenario { name: "commit", run: commit },
+    Scenario { name: "conflict", run: conflict },
+    Scenario { name: "merge", run: merge },
+    Scenario { name: "stash", run: stash },
+    Scenario { name: "log-filters", run: log_filters },
+    Scenario { name: "stage-patch", run: stage_patch },
+    Scenario { name: "diff-commit", run: diff_commit },
+    Scenario { name: "tags", run: tags },
+];
+
+/// What one backend reported through one scenario: `(step, value)` in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Transcript {
+    pub backend: String,
+    pub scenario: &'static str,
+    pub steps: Vec<(String, String)>,
+}
+
+/// A scenario's repository and the record of what it saw.
+pub struct Script {
+    vcs: Arc<dyn Vcs>,
+    dir: PathBuf,
+    init_repo: fn(&Path) -> Result<Arc<dyn Vcs>>,
+    /// Repositories made by [`Script::remote`], removed with this one.
+    remotes: Vec<PathBuf>,
+    /// Commit ids seen so far and the summary that stands in for them.
+    commits: Vec<(String, String)>,
+    steps: Vec<(String, String)>,
+}
+
+impl Script {
+    /// Initialise an empty repository at `dir` with `desc`'s backend and identity.
+    fn init(desc: &BackendDescriptor, dir: PathBuf) -> Result<Self> {
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        let vcs = (desc.init_repo)(&dir)?;
+        // Tagger and other config-identity operations, like the committer of `commit`
+        vcs.set_identity_local(NAME, EMAIL)?;
+        Ok(Self { vcs, dir, init_repo: desc.init_repo, remotes: Vec::new(), commits: Vec::new(), steps: Vec::new() })
+    }
+
+    pub fn vcs(&self) -> &dyn Vcs {
+        self.vcs.as_ref()
+    }
+
+    /// A second empty (bare) repository next to this one, added as remote `name`.
+    pub fn remote(&mut self, name: &str) -> Result<Arc<dyn Vcs>> {
+        let mut dir = self.dir.clone().into_os_string();
+        dir.push(format!("-{name}"));
+        let dir = PathBuf::from(dir);
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir)?;
+        self.remotes.push(dir.clone());
+        let remote = (self.init_repo)(&dir)?;
+        // A push target: libgit2 doesn't push into repositories with a working tree
+        remote.set_local_config("core.bare", Some("true"))?;
+        self.vcs.ensure_remote(name, &dir.to_string_lossy())?;
+        Ok(remote)
+    }
+
+    /// Write `contents` to `path` (relative to the working tree), creating directories.
+    pub fn write(&self, path: &str, contents: &str) -> Result<()> {
+        let path = self.dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(std::fs::write(path, contents)?)
+    }
+
+    pub fn remove(&self, path: &str) -> Result<()> {
+        Ok(std::fs::remove_file(self.dir.join(path))?)
+    }
+
+    /// Commit everything in the working tree as `message`; the id is recorded under the
+    /// message's summary.
+    pub fn commit(&mut self, message: &str) -> Result<String> {
+        self.commit_as(message, &CommitOptions::default())
+    }
+
+    pub fn commit_as(&mut self, message: &str, opts: &CommitOptions) -> Result<String> {
+        let id = self.vcs.commit(message, NAME, EMAIL, &[], opts)?;
+        let summary = message.lines().next().unwrap_or_default().to_string();
+        self.commits.push((id.clone(), format!("<{summary}>")));
+        Ok(id)
+    }
+
+    /// [`Vcs::merge_branch`], recording a merge commit's id under its summary.
+    pub fn merge(&mut self, name: &str, opts: &MergeOptions) -> Result<MergeOutcome> {
+        let outcome = self.vcs.merge_branch(name, opts)?;
+        if let MergeOutcome::Merged { commit } = &outcome {
+            let head = self.vcs.log_commits(&LogQuery { include_merges: true, ..LogQuery::head(1) })?;
+            let summary = head.first().map(|c| c.msg.lines().next().unwrap_or_default().to_string()).unwrap_or_default();
+            self.commits.push((commit.clone(), format!("<{summary}>")));
+        }
+        Ok(outcome)
+    }
+
+    /// First commit (`README.md`) on a branch named `main`, whatever the backend's
+    /// default branch name is.
+    pub fn setup(&mut self) -> Result<()> {
+        self.write("README.md", "# conformance\n")?;
+        self.commit("initial")?;
+        let initial = self.vcs.current_branch()?.unwrap_or_default();
+        if initial != "main" {
+            self.vcs.rename_branch(&initial, "main")?;
+        }
+        Ok(())
+    }
+
+    /// Record `value` under `step`, with commit ids replaced by their summaries.
+    pub fn record(&mut self, step: &str, value: impl Debug) {
+        let mut text = format!("{value:?}");
+        for (id, name) in &self.commits {
+            text = text.replace(id.as_str(), name);
+            if let Some(short) = id.get(..7) {
+                text = text.replace(short, name);
+            }
+        }
+        self.steps.push((step.to_string(), text));
+    }
+
+    /// Record the outcome of an operation: its value, or the kind of error (with the
+    /// files, for conflicts and blocked checkouts).
+    pub fn record_result<T: Debug>(&mut self, step: &str, result: Result<T>) {
+        match result {
+            Ok(v) => self.record(step, Ok::<T, ()>(v)),
+            Err(e) => {
+                let e = error_view(&e);
+                self.record(step, Err::<(), String>(e));
+            }
+        }
+    }
+
+    /// Path, status and line counts of every changed file.
+    pub fn record_status(&mut self, step: &str) -> Result<()> {
+        let status = self.vcs.status_payload(&StatusOptions::default())?;
+        let stats = self.vcs.worktree_file_stats(None)?;
+        let mut files: Vec<_> = status
+            .files
+            .into_iter()
+            .map(|mut f| {
+                if let Some(st) = stats.iter().find(|st| st.path == f.path) {
+                    f.apply_stat(st);
+                }
+                (f.path, f.status, f.additions, f.deletions)
+            })
+            .collect();
+        files.sort();
+        self.record(step, files);
+        Ok(())
+    }
+
+    /// Summary and author of each commit `query` selects, in order.
+    pub fn record_log(&mut self, step: &str, query: &LogQuery) -> Result<()> {
+        let log: Vec<_> = self.vcs.log_commits(query)?.into_iter().map(|c| (c.msg, c.author_name)).collect();
+        self.record(step, log);
+        Ok(())
+    }
+
+    /// Local branches and which one is current.
+    pub fn record_branches(&mut self, step: &str) -> Result<()> {
+        let mut branches: Vec<_> = self
+            .vcs
+            .branches()?
+            .into_iter()
+            .map(|b| (b.name, b.current))
+            .collect();
+        branches.sort();
+        self.record(step, branches);
+        Ok(())
+    }
+}
+
+impl Drop for Script {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+        for dir in &self.remotes {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+fn error_view(e: &VcsError) -> String {
+    match e {
+        VcsError::MergeConflict { files } | VcsError::DirtyWorktree { files } => {
+            let mut files = files.clone();
+            files.sort();
+            format!("{} {files:?}", e.kind())
+        }
+        _ => e.kind().to_string(),
+    }
+}
+
+fn log_all() -> LogQuery {
+    LogQuery { limit: 100, topo_order: true, include_merges: true, ..Default::default() }
+}
+
+/* ================================ scenarios ================================ */
+
+fn init(s: &mut Script) -> Result<()> {
+    s.record_status("empty status")?;
+    s.record_log("empty log", &log_all())?;
+    s.write("a.txt", "a\n")?;
+    s.write("dir/b.txt", "b\n")?;
+    s.record_status("untracked")?;
+    s.setup()?;
+    s.record_log("log", &log_all())?;
+    s.record_status("clean")?;
+    s.record_branches("branches")
+}
+
+fn branch(s: &mut Script) -> Result<()> {
+    s.setup()?;
+    s.vcs().create_branch("topic", None, true)?;
+    let current = s.vcs().current_branch();
+    s.record_result("current", current);
+    s.write("topic.txt", "topic\n")?;
+    s.commit("on topic")?;
+    let r = s.vcs().checkout_branch("main");
+    s.record_result("checkout main", r);
+    s.record_branches("branches")?;
+    s.record_log("topic log", &LogQuery { rev: Some("topic".into()), ..log_all() })?;
+    s.record("topic.txt on main", s.dir.join("topic.txt").exists());
+    let r = s.vcs().rename_branch("topic", "feature");
+    s.record_result("rename", r);
+    let r = s.vcs().delete_branch("feature", true);
+    s.record_result("delete", r);
+    let r = s.vcs().checkout_branch("missing");
+    s.record_result("checkout missing", r);
+    s.record_branches("branches after")
+}
+
+fn commit(s: &mut Script) -> Result<()> {
+    s.setup()?;
+    s.write("keep.txt", "one\ntwo\nthree\n")?;
+    s.write("gone.txt", "gone\n")?;
+    s.commit("add files")?;
+    s.write("keep.txt", "one\n2\nthree\nfour\n")?;
+    s.remove("gone.txt")?;
+    s.write("new.txt", "new\n")?;
+    s.record_status("changes")?;
+    let message = "change files\n\nLonger explanation.\n\nSigned-off-by: Conformance <conformance@example.com>\n";
+    let id = s.commit_as(message, &CommitOptions {
+        author: Some(Identity { name: "Ada".into(), email: "ada@example.com".into() }),
+        ..Default::default()
+    })?;
+    s.record_status("after commit")?;
+    let records = s.vcs().commit_records(std::slice::from_ref(&id))?;
+    let view: Vec<_> = records
+        .into_iter()
+        .map(|r| (r.parents, r.author_name, r.author_email, r.committer_name, r.summary, r.body, r.trailers))
+        .collect();
+    s.record("record", view);
+    let mut stats: Vec<_> = s
+        .vcs()
+        .commit_file_stats(&id)?
+        .into_iter()
+        .map(|f| (f.path, f.status, f.additions, f.deletions, f.size_delta))
+        .collect();
+    stats.sort();
+    s.record("file stats", stats);
+    let r = s.vcs().commit("nothing", NAME, EMAIL, &[], &CommitOptions::default());
+    s.record_result("empty commit", r.map(|_| ()));
+    Ok(())
+}
+
+fn conflict(s: &mut Script) -> Result<()> {
+    s.setup()?;
+    s.write("shared.txt", "base\n")?;
+    s.commit("base")?;
+    s.vcs().create_branch("topic", None, true)?;
+    s.write("shared.txt", "topic\n")?;
+    s.commit("topic change")?;
+    s.vcs().checkout_branch("main")?;
+    s.write("shared.txt", "main\n")?;
+    s.commit("main change")?;
+    let r = s.merge("topic", &MergeOptions::default());
+    s.record_result("merge", r);
+    s.record_status("conflicted status")?;
+    let r = s.vcs().hard_reset_head();
+    s.record_result("abort", r);
+    s.record_status("after abort")?;
+    let r = s.vcs().merge_into_current("topic");
+    s.record_result("merge_into_current", r);
+    s.write("shared.txt", "resolved\n")?;
+    s.commit("resolve")?;
+    s.record_log("resolved log", &log_all())?;
+    s.record_status("after resolving")
+}
+
+fn merge(s: &mut Script) -> Result<()> {
+    s.setup()?;
+    s.vcs().create_branch("ahead", None, true)?;
+    s.write("ahead.txt", "ahead\n")?;
+    s.commit("ahead")?;
+    s.vcs().checkout_branch("main")?;
+    let r = s.merge("missing", &MergeOptions::default());
+    s.record_result("missing", r);
+    let r = s.merge("main", &MergeOptions::default());
+    s.record_result("itself", r);
+    let r = s.merge("ahead", &MergeOptions { fast_forward: FastForward::Only, ..Default::default() });
+    s.record_result("fast-forward", r);
+    s.record_status("after fast-forward")?;
+    let r = s.merge("ahead", &MergeOptions::default());
+    s.record_result("up to date", r);
+
+    s.vcs().create_branch("topic", None, true)?;
+    s.write("topic.txt", "topic\n")?;
+    s.commit("topic")?;
+    s.vcs().checkout_branch("main")?;
+    s.write("main.txt", "main\n")?;
+    s.commit("main")?;
+    let r = s.merge("topic", &MergeOptions { fast_forward: FastForward::Only, ..Default::default() });
+    s.record_result("not fast-forward", r);
+    s.write("topic.txt", "local\n")?;
+    let r = s.merge("topic", &MergeOptions::default());
+    s.record_result("untracked in the way", r);
+    s.remove("topic.txt")?;
+    let r = s.merge("topic", &MergeOptions::default());
+    s.record_result("merge", r);
+    s.record_status("after merge")?;
+    s.record_log("merged log", &log_all())?;
+
+    s.vcs().create_branch("second", Some("topic"), true)?;
+    s.write("second.txt", "second\n")?;
+    s.commit("second")?;
+    let r = s.merge("main", &MergeOptions { fast_forward: FastForward::Never, message: Some("Bring in main".into()) });
+    s.record_result("no-ff with message", r);
+    s.record_log("second log", &log_all())
+}
+
+fn stash(s: &mut Script) -> Result<()> {
+    s.setup()?;
+    let r = s.vcs().stash_save(&StashOptions::default());
+    s.record_result("nothing to stash", r.map(|e| e.map(|e| e.message)));
+    s.write("README.md", "# changed\n")?;
+    s.write("untracked.txt", "u\n")?;
+    let r = s.vcs().stash_save(&StashOptions::default());
+    s.record_result("save", r.map(|e| e.map(|e| e.message)));
+    s.record_status("after save")?;
+    let r = s.vcs().stash_save(&StashOptions { message: Some("with untracked".into()), include_untracked: true, ..Default::default() });
+    s.record_result("save untracked", r.map(|e| e.map(|e| e.message)));
+    s.record_status("after save untracked")?;
+    let list: Vec<_> = s.vcs().stash_list()?.into_iter().map(|e| (e.index, e.message, e.branch)).collect();
+    s.record("list", list);
+    let r = s.vcs().stash_pop(0, false);
+    s.record_result("pop", r);
+    s.record_status("after pop")?;
+    let r = s.vcs().stash_drop(0);
+    s.record_result("drop", r);
+    let r = s.vcs().stash_drop(0);
+    s.record_result("drop missing", r);
+    let list: Vec<_> = s.vcs().stash_list()?.into_iter().map(|e| e.message).collect();
+    s.record("list after", list);
+    Ok(())
+}
+
+fn log_filters(s: &mut Script) -> Result<()> {
+    s.setup()?;
+    let ada = CommitOptions { author: Some(Identity { name: "Ada".into(), email: "ada@example.com".into() }), ..Default::default() };
+    s.write("src/lib.rs", "fn a() {}\n")?;
+    s.commit_as("src by ada", &ada)?;
+    s.write("docs/guide.md", "guide\n")?;
+    s.commit("docs")?;
+    s.write("src/main.rs", "fn main() {}\n")?;
+    s.write("docs/api.md", "api\n")?;
+    s.commit_as("src and docs by ada", &ada)?;
+    for (step, query) in [
+        ("all", log_all()),
+        ("path", LogQuery { path: Some("src".into()), ..log_all() }),
+        ("pathspecs", LogQuery { paths: vec!["docs/guide.md".into(), "src/main.rs".into()], ..log_all() }),
+        ("exclude", LogQuery { paths: vec![":!docs".into()], ..log_all() }),
+        ("glob", LogQuery { paths: vec![":(glob)docs/*.md".into()], ..log_all() }),
+        ("author", LogQuery { author_contains: Some("Ada".into()), ..log_all() }),
+        ("skip and limit", LogQuery { skip: 1, limit: 2, ..log_all() }),
+    ] {
+        s.record_log(step, &query)?;
+    }
+    Ok(())
+}
+
+fn stage_patch(s: &mut Script) -> Result<()> {
+    s.setup()?;
+    let lines: Vec<String> = (1..=12).map(|i| format!("line {i}\n")).collect();
+    s.write("f.txt", &lines.concat())?;
+    s.commit("twelve lines")?;
+    let mut changed = lines.clone();
+    changed[0] = "LINE 1\n".into();
+    changed[11] = "LINE 12\n".into();
+    s.write("f.txt", &changed.concat())?;
+    // Only the first hunk
+    let patch = "diff --git a/f.txt b/f.txt\n--- a/f.txt\n+++ b/f.txt\n@@ -1,3 +1,3 @@\n-line 1\n+LINE 1\n line 2\n line 3\n";
+    let r = s.vcs().stage_patch(patch);
+    s.record_result("stage first hunk", r);
+    s.record_status("status")?;
+    let r = s.vcs().commit_index("first hunk", NAME, EMAIL, &CommitOptions::default());
+    s.record_result("commit index", r.map(|_| ()));
+    s.record_status("after commit")?;
+    let diff: Vec<_> = s.vcs().diff_file(Path::new("f.txt"))?.into_iter().filter(|l| is_change(l)).collect();
+    s.record("remaining diff", diff);
+    Ok(())
+}
+
+fn diff_commit(s: &mut Script) -> Result<()> {
+    s.setup()?;
+    s.write("a.txt", "one\ntwo\nthree\n")?;
+    s.write("b.txt", "b\n")?;
+    s.commit("add")?;
+    s.write("a.txt", "one\n2\nthree\n")?;
+    s.remove("b.txt")?;
+    s.write("c.txt", "c\n")?;
+    let id = s.commit("modify")?;
+    let diff: Vec<_> = s.vcs().diff_commit(&id)?.into_iter().filter(|l| is_change(l)).collect();
+    s.record("diff", diff);
+    Ok(())
+}
+
+fn tags(s: &mut Script) -> Result<()> {
+    s.setup()?;
+    let r = s.vcs().create_tag("v0.1", None, None, None);
+    s.record_result("lightweight", r);
+    s.write("a.txt", "a\n")?;
+    s.commit("second")?;
+    let r = s.vcs().create_tag("v1.0", None, Some("Release 1.0\n\nNotes."), None);
+    s.record_result("annotated", r);
+    let r = s.vcs().create_tag("v1.0", None, None, None);
+    s.record("duplicate fails", r.is_err());
+    let r = s.vcs().create_tag("old", Some("v0.1"), Some("On the first commit"), None);
+    s.record_result("annotated on target", r);
+    let view = |tags: Vec<crate::models::TagItem>| -> Vec<_> {
+        tags.into_iter().map(|t| (t.name, t.target, t.annotated, t.summary, t.tagger)).collect()
+    };
+    let list = view(s.vcs().list_tags()?);
+    s.record("list", list);
+    let r = s.vcs().delete_tag("old");
+    s.record_result("delete", r);
+    let list = view(s.vcs().list_tags()?);
+    s.record("list after delete", list);
+
+    let remote = s.remote("origin")?;
+    let r = s.vcs().push_tags("origin", &["v0.1".into()], None);
+    s.record_result("push one", r);
+    let names: Vec<_> = remote.list_tags()?.into_iter().map(|t| t.name).collect();
+    s.record("remote after one", names);
+    let r = s.vcs().push_tags("origin", &[], None);
+    s.record_result("push all", r);
+    let list = view(remote.list_tags()?);
+    s.record("remote after all", list);
+    Ok(())
+}
+
+/// Added, removed and file header lines of a unified diff; hunk headers and `index`
+/// lines carry backend-specific context and abbreviations.
+fn is_change(line: &str) -> bool {
+    (line.starts_with('+') || line.starts_with('-')) && !line.starts_with("+++ /dev/null") && !line.starts_with("--- /dev/null")
+}
+
+/* ================================= driver ================================= */
+
+/// Run `scenario` against `desc`'s backend in a fresh repository under the temp dir.
+/// A step that fails outright ends the transcript with its error.
+pub fn run(desc: &BackendDescriptor, scenario: &Scenario) -> Transcript {
+    let dir = std::env::temp_dir().join(format!("openvcs-conformance-{}-{}-{}", std::process::id(), desc.id, scenario.name));
+    let mut transcript = Transcript { backend: desc.id.to_string(), scenario: scenario.name, steps: Vec::new() };
+    match Script::init(desc, dir) {
+        Ok(mut script) => {
+            if let Err(e) = (scenario.run)(&mut script) {
+                script.steps.push(("failed".into(), format!("{e}")));
+            }
+            transcript.steps = std::mem::take(&mut script.steps);
+        }
+        Err(e) => transcript.steps.push(("init failed".into(), format!("{e}"))),
+    }
+    transcript
+}
+
+/// Where two transcripts of a scenario part ways.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// 1-based step number.
+    pub step: usize,
+    /// Either side answered `Unsupported`: a missing feature rather than a disagreement.
+    pub unsupported: bool,
+    pub report: String,
+}
+
+/// The first step where `b` differs from `a`.
+pub fn compare(a: &Transcript, b: &Transcript) -> Option<Divergence> {
+    let len = a.steps.len().max(b.steps.len());
+    (0..len).find_map(|i| {
+        let (sa, sb) = (a.steps.get(i), b.steps.get(i));
+        (sa != sb).then(|| {
+            let show = |s: Option<&(String, String)>| s.map_or_else(|| "(missing)".to_string(), |(k, v)| format!("{k}: {v}"));
+            let unsupported = [sa, sb].iter().flatten().any(|(_, v)| v.contains(UNSUPPORTED));
+            let report = format!(
+                "scenario `{}`, step {}:\n  {}: {}\n  {}: {}",
+                a.scenario, i + 1, a.backend, show(sa), b.backend, show(sb)
+            );
+            Divergence { step: i + 1, unsupported, report }
+        })
+    })
+}
+
+/// `(scenario, backend id)`: the backend answers `Unsupported` in that scenario.
+pub type KnownGap = (&'static str, &'static str);
+
+/// Run every scenario against every registered backend and panic listing the first
+/// divergence of each scenario. A backend answering `Unsupported` where another has an
+/// answer fails too, unless the pair is in `known_gaps` (the rest of that scenario isn't
+/// compared then); a listed gap that no longer shows up fails as well, so the list stays
+/// current. Needs at least two backends linked in.
+pub fn assert_parity(known_gaps: &[KnownGap]) {
+    let backends: Vec<&BackendDescriptor> = list_backends().collect();
+    assert!(backends.len() >= 2, "conformance: link at least two backends (found {})", backends.len());
+    let mut failures = Vec::new();
+    let mut gaps_seen = Vec::new();
+    for scenario in SCENARIOS {
+        let transcripts: Vec<Transcript> = backends.iter().map(|b| run(b, scenario)).collect();
+        let (reference, rest) = transcripts.split_first().expect("at least two backends");
+        for (t, d) in rest.iter().filter_map(|t| compare(reference, t).map(|d| (t, d))) {
+            if !d.unsupported {
+                failures.push(d.report);
+                continue;
+            }
+            let unsupported_in = |t: &Transcript| t.steps.get(d.step - 1).is_some_and(|(_, v)| v.contains(UNSUPPORTED));
+            for gap in [reference, t].into_iter().filter(|t| unsupported_in(t)).map(|t| (scenario.name, t.backend.as_str())) {
+                if let Some(known) = known_gaps.iter().find(|g| **g == gap) {
+                    gaps_seen.push(*known);
+                } else {
+                    failures.push(format!("{}\n  (unsupported; not a known gap)", d.report));
+                }
+            }
+        }
+    }
+    for gap in known_gaps.iter().filter(|g| !gaps_seen.contains(g)) {
+        failures.push(format!("known gap {gap:?} no longer shows up; remove it from the list"));
+    }
+    assert!(failures.is_empty(), "backends disagree:\n{}", failures.join("\n\n"));
+}
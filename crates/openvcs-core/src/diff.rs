@@ -0,0 +1,252 @@
+//! Structured unified diffs.
+//!
+//! Backends return diffs as the lines git prints; [`parse_unified`] turns them into
+//! [`FileDiff`]s with numbered lines so the UI does no diff parsing of its own. Changes that
+//! only differ in whitespace (under a [`WhitespaceMode`]) are marked, so "hide whitespace
+//! changes" is a filter on the result rather than a second diff.
+
+use std::borrow::Cow;
+
+use crate::models::{DiffHunk, DiffLine, DiffLineKind, FileDiff, LfsChange, LfsPointer, ModeChange, WhitespaceMode};
+
+/// Parse the output of `git diff` / `git show --format=` (one or more files).
+/// Hunks that appear without a `diff --git` line are collected into a file with an empty path.
+pub fn parse_unified<S: AsRef<str>>(lines: &[S], ws: WhitespaceMode) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    // Lines still expected in the current hunk, and the next line numbers.
+    let (mut old_left, mut new_left) = (0u32, 0u32);
+    let (mut old_no, mut new_no) = (0u32, 0u32);
+
+    for line in lines {
+        let line = line.as_ref();
+        if line.starts_with('\\') {
+            // "\ No newline at end of file" belongs to the line before it.
+            if let Some(last) = files.last_mut().and_then(|f| f.hunks.last_mut()).and_then(|h| h.lines.last_mut()) {
+                last.no_newline = true;
+            }
+            continue;
+        }
+
+        // Inside a hunk until its line counts run out; anything else ends it early.
+        let body = (old_left > 0 || new_left > 0).then(|| hunk_line(line)).flatten();
+        if let (Some((kind, text)), Some(hunk)) = (body, files.last_mut().and_then(|f| f.hunks.last_mut())) {
+            let old_line = (kind != DiffLineKind::Add).then_some(old_no);
+            let new_line = (kind != DiffLineKind::Del).then_some(new_no);
+            if old_line.is_some() {
+                old_no += 1;
+                old_left = old_left.saturating_sub(1);
+            }
+            if new_line.is_some() {
+                new_no += 1;
+                new_left = new_left.saturating_sub(1);
+            }
+            hunk.lines.push(DiffLine {
+                kind,
+                text: text.to_string(),
+                old_line,
+                new_line,
+                whitespace_only: false,
+                no_newline: false,
+            });
+            continue;
+        }
+        old_left = 0;
+        new_left = 0;
+
+        if line.starts_with("diff --git ") || line.starts_with("diff --cc ") || line.starts_with("diff --combined ") {
+            files.push(FileDiff { path: git_header_path(line), header: vec![line.to_string()], ..Default::default() });
+            continue;
+        }
+
+        if let Some(hunk) = parse_hunk_header(line) {
+            if files.is_empty() {
+                files.push(FileDiff::default());
+            }
+            old_left = hunk.old_lines;
+            new_left = hunk.new_lines;
+            old_no = hunk.old_start;
+            new_no = hunk.new_start;
+            let file_no = files.len() - 1;
+            if let Some(f) = files.last_mut() {
+                f.hunks.push(DiffHunk { id: format!("{file_no}.{}", f.hunks.len()), ..hunk });
+            }
+            continue;
+        }
+
+        let Some(file) = files.last_mut() else { continue };
+        if !file.hunks.is_empty() {
+            continue;
+        }
+        file.header.push(line.to_string());
+        if let Some(p) = line.strip_prefix("+++ ") {
+            if let Some(p) = side_path(p, "b/") {
+                file.path = p;
+            }
+        } else if let Some(p) = line.strip_prefix("--- ") {
+            if let Some(p) = side_path(p, "a/") {
+                if file.path.is_empty() {
+                    file.path = p.clone();
+                }
+                file.old_path = Some(p);
+            }
+        } else if let Some(p) = line.strip_prefix("rename from ").or_else(|| line.strip_prefix("copy from ")) {
+            file.old_path = Some(unquote(p));
+        } else if let Some(p) = line.strip_prefix("rename to ").or_else(|| line.strip_prefix("copy to ")) {
+            file.path = unquote(p);
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        } else if let Some(m) = line.strip_prefix("old mode ").or_else(|| line.strip_prefix("deleted file mode ")) {
+            file.old_mode = parse_mode(m);
+        } else if let Some(m) = line.strip_prefix("new mode ").or_else(|| line.strip_prefix("new file mode ")) {
+            file.new_mode = parse_mode(m);
+        } else if let Some(rest) = line.strip_prefix("index ") {
+            // "index <old>..<new> <mode>" when the mode did not change.
+            if let Some((_, m)) = rest.split_once(' ') {
+                file.old_mode = parse_mode(m);
+                file.new_mode = file.old_mode;
+            }
+        }
+    }
+
+    for file in &mut files {
+        if file.old_path.as_deref() == Some(file.path.as_str()) {
+            file.old_path = None;
+        }
+        file.mode_change = ModeChange::between(file.old_mode, file.new_mode, !file.hunks.is_empty() || file.binary);
+        mark_lfs(file);
+        mark_whitespace(file, ws);
+    }
+    // A type change is printed as a deletion followed by an addition of the same path.
+    for i in 1..files.len() {
+        let (gone, added) = (&files[i - 1], &files[i]);
+        if gone.path == added.path && gone.new_mode == 0 && added.old_mode == 0 {
+            let change = ModeChange::between(gone.old_mode, added.new_mode, true);
+            files[i - 1].mode_change = change;
+            files[i].mode_change = change;
+        }
+    }
+    files
+}
+
+/// Set the `whitespace_only` flags of `file` for `ws` (clearing them for [`WhitespaceMode::None`]).
+///
+/// In a run of removed lines followed by the same number of added lines, each removed/added
+/// pair that is equal after normalizing whitespace is marked. Runs of uneven length, and so
+/// added or removed blank lines, are real changes, as they are for `git diff -w`.
+pub fn mark_whitespace(file: &mut FileDiff, ws: WhitespaceMode) {
+    for hunk in &mut file.hunks {
+        let mut i = 0;
+        while i < hunk.lines.len() {
+            if hunk.lines[i].kind == DiffLineKind::Context {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < hunk.lines.len() && hunk.lines[i].kind == DiffLineKind::Del {
+                i += 1;
+            }
+            let mid = i;
+            while i < hunk.lines.len() && hunk.lines[i].kind == DiffLineKind::Add {
+                i += 1;
+            }
+            let (dels, adds) = hunk.lines[start..i].split_at_mut(mid - start);
+            for l in dels.iter_mut().chain(adds.iter_mut()) {
+                l.whitespace_only = false;
+            }
+            if ws != WhitespaceMode::None && dels.len() == adds.len() {
+                for (d, a) in dels.iter_mut().zip(adds) {
+                    let same = d.no_newline == a.no_newline && normalize(&d.text, ws) == normalize(&a.text, ws);
+                    d.whitespace_only = same;
+                    a.whitespace_only = same;
+                }
+            }
+        }
+        let mut changed = hunk.lines.iter().filter(|l| l.kind != DiffLineKind::Context).peekable();
+        hunk.whitespace_only = changed.peek().is_some() && changed.all(|l| l.whitespace_only);
+    }
+    file.whitespace_only = !file.hunks.is_empty() && file.hunks.iter().all(|h| h.whitespace_only);
+}
+
+/// Replace the hunks of a changed LFS pointer with [`FileDiff::lfs`]: the pointer lines say
+/// nothing a reader wants to see. Pointers are a few short lines, so they fit in one hunk.
+fn mark_lfs(file: &mut FileDiff) {
+    let [hunk] = file.hunks.as_slice() else { return };
+    let side = |skip: DiffLineKind, len: u32| -> Option<Option<LfsPointer>> {
+        if len == 0 {
+            return Some(None);
+        }
+        let text: String = hunk.lines.iter().filter(|l| l.kind != skip).map(|l| format!("{}\n", l.text)).collect();
+        LfsPointer::parse(&text).map(Some)
+    };
+    let (Some(old), Some(new)) = (side(DiffLineKind::Add, hunk.old_lines), side(DiffLineKind::Del, hunk.new_lines)) else { return };
+    if (old.is_some() || new.is_some()) && hunk.old_start <= 1 && hunk.new_start <= 1 {
+        file.lfs = Some(LfsChange { old, new });
+        file.hunks.clear();
+    }
+}
+
+fn normalize(s: &str, ws: WhitespaceMode) -> Cow<'_, str> {
+    match ws {
+        WhitespaceMode::None => Cow::Borrowed(s),
+        WhitespaceMode::Eol => Cow::Borrowed(s.trim_end()),
+        WhitespaceMode::All => Cow::Owned(s.chars().filter(|c| !c.is_whitespace()).collect()),
+    }
+}
+
+fn hunk_line(line: &str) -> Option<(DiffLineKind, &str)> {
+    match line.split_at_checked(1) {
+        Some((" ", t)) => Some((DiffLineKind::Context, t)),
+        Some(("+", t)) => Some((DiffLineKind::Add, t)),
+        Some(("-", t)) => Some((DiffLineKind::Del, t)),
+        // Some tools strip the space of empty context lines.
+        None => Some((DiffLineKind::Context, "")),
+        _ => None,
+    }
+}
+
+/// `@@ -a[,b] +c[,d] @@ context` → an empty hunk with its ranges and function context.
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, function) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let range = |r: &str| -> Option<(u32, u32)> {
+        match r.split_once(',') {
+            Some((s, n)) => Some((s.parse().ok()?, n.parse().ok()?)),
+            None => Some((r.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old)?;
+    let (new_start, new_lines) = range(new)?;
+    let function = Some(function.trim()).filter(|f| !f.is_empty()).map(str::to_string);
+    Some(DiffHunk { header: line.to_string(), function, old_start, old_lines, new_start, new_lines, ..Default::default() })
+}
+
+fn parse_mode(m: &str) -> u32 {
+    u32::from_str_radix(m.trim(), 8).unwrap_or(0)
+}
+
+/// Path named by a `---`/`+++` line, `None` for `/dev/null`.
+fn side_path(p: &str, prefix: &str) -> Option<String> {
+    let p = unquote(p.trim_end_matches('\t'));
+    if p == "/dev/null" {
+        return None;
+    }
+    Some(p.strip_prefix(prefix).map(str::to_string).unwrap_or(p))
+}
+
+/// Best guess at the path of `diff --git a/<p> b/<p>`; refined by later header lines.
+fn git_header_path(line: &str) -> String {
+    let rest = line.split_once("diff --git ").map_or("", |(_, r)| r);
+    let rest = rest.strip_prefix("a/").unwrap_or(rest);
+    // Unrenamed files have the same path on both sides, which survives spaces in names.
+    let half = rest.len().saturating_sub(3) / 2;
+    if rest.get(half..half + 3) == Some(" b/") && rest[..half] == rest[half + 3..] {
+        return rest[..half].to_string();
+    }
+    rest.rsplit_once(" b/").map_or_else(|| rest.to_string(), |(_, b)| b.to_string())
+}
+
+/// Drop the C-style quotes git puts around unusual paths (escapes are left as printed).
+fn unquote(p: &str) -> String {
+    p.strip_prefix('"').and_then(|p| p.strip_suffix('"')).unwrap_or(p).to_string()
+}
@@ -0,0 +1,240 @@
+//! Commit graph layout.
+//!
+//! Assigns each commit of a history listing a column ("lane") and describes the lines
+//! between consecutive rows, so every backend (and any future front end) draws the same
+//! graph and the UI does no graph math. Commits must come children first (`git log
+//! --topo-order`, or date order); parents that are not in the listing simply run off the
+//! bottom.
+//!
+//! A lane holds the id of the commit it is waiting for. A commit takes the leftmost lane
+//! waiting for it (or the leftmost free one, for a branch tip); any other lanes waiting for
+//! it merge into it and are freed. Its first parent continues in its lane, other parents
+//! join a lane already waiting for them or open a new one to its right.
+
+use serde::{Deserialize, Serialize};
+
+/// A line from column `from` in one row to column `to` in the next.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GraphRow {
+    pub id: String,
+    /// Column of the commit's node.
+    pub column: usize,
+    /// Lines down to the next row: lanes passing by, and one per parent of this commit.
+    pub edges: Vec<GraphEdge>,
+    /// Columns used by this row, its node and its edges (max column + 1).
+    pub width: usize,
+}
+
+/// Incremental layout; feed commits with [`push`](Self::push) and collect the rows.
+#[derive(Debug, Default)]
+pub struct GraphLayout {
+    lanes: Vec<Option<String>>,
+    /// The last pushed row (edges not known until the next commit arrives) and its outgoing
+    /// lines as `(column here, lane)`.
+    pending: Option<(GraphRow, Vec<(usize, usize)>)>,
+}
+
+impl GraphLayout {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the next commit; returns the previous commit's row, now that its edges are known.
+    pub fn push(&mut self, id: &str, parents: &[String]) -> Option<GraphRow> {
+        let column = self
+            .lanes
+            .iter()
+            .position(|l| l.as_deref() == Some(id))
+            .unwrap_or_else(|| self.free_lane(0));
+
+        let done = self.pending.take().map(|(mut row, out)| {
+            row.edges = out
+                .into_iter()
+                .map(|(from, lane)| {
+                    let to = if self.lanes.get(lane).and_then(Option::as_deref) == Some(id) { column } else { lane };
+                    GraphEdge { from, to }
+                })
+                .collect();
+            row.width = row.edges.iter().map(|e| e.from.max(e.to) + 1).fold(row.width, usize::max);
+            row
+        });
+
+        // Lanes waiting for this commit end here.
+        for lane in self.lanes.iter_mut() {
+            if lane.as_deref() == Some(id) {
+                *lane = None;
+            }
+        }
+        if column == self.lanes.len() {
+            self.lanes.push(None);
+        }
+
+        // Lanes passing by this row.
+        let mut out: Vec<(usize, usize)> =
+            self.lanes.iter().enumerate().filter(|(_, l)| l.is_some()).map(|(i, _)| (i, i)).collect();
+        let mut seen: Vec<&str> = Vec::new();
+        for (i, parent) in parents.iter().enumerate() {
+            if seen.contains(&parent.as_str()) {
+                continue;
+            }
+            seen.push(parent);
+            // The first parent keeps this lane even if another lane already waits for it
+            // (the two meet at the parent); others join such a lane or open a new one.
+            let lane = match self.lanes.iter().position(|l| l.as_deref() == Some(parent.as_str())) {
+                _ if i == 0 => column,
+                Some(existing) => existing,
+                None => self.free_lane(column + 1),
+            };
+            if lane == self.lanes.len() {
+                self.lanes.push(None);
+            }
+            self.lanes[lane] = Some(parent.clone());
+            out.push((column, lane));
+        }
+        out.sort_by_key(|&(from, lane)| (lane, from));
+        while self.lanes.last().is_some_and(Option::is_none) {
+            self.lanes.pop();
+        }
+
+        let width = out.iter().map(|&(from, lane)| from.max(lane) + 1).fold(column + 1, usize::max);
+        self.pending = Some((GraphRow { id: id.to_string(), column, edges: Vec::new(), width }, out));
+        done
+    }
+
+    /// The last row; its edges lead straight down to parents that were not pushed.
+    pub fn finish(self) -> Option<GraphRow> {
+        self.pending.map(|(mut row, out)| {
+            row.edges = out.into_iter().map(|(from, lane)| GraphEdge { from, to: lane }).collect();
+            row
+        })
+    }
+
+    /// Leftmost free lane at or after `start` (may be one past the end).
+    fn free_lane(&self, start: usize) -> usize {
+        (start..self.lanes.len()).find(|&i| self.lanes[i].is_none()).unwrap_or(self.lanes.len().max(start))
+    }
+}
+
+/// Lay out a whole listing of `(id, parents)`, children first.
+pub fn layout<'a, I>(commits: I) -> Vec<GraphRow>
+where
+    I: IntoIterator<Item = (&'a str, &'a [String])>,
+{
+    let mut graph = GraphLayout::new();
+    let mut rows: Vec<GraphRow> = commits.into_iter().filter_map(|(id, parents)| graph.push(id, parents)).collect();
+    rows.extend(graph.finish());
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(commits: &[(&str, &[&str])]) -> Vec<GraphRow> {
+        let owned: Vec<(String, Vec<String>)> = commits
+            .iter()
+            .map(|(id, ps)| (id.to_string(), ps.iter().map(|p| p.to_string()).collect()))
+            .collect();
+        let rows = layout(owned.iter().map(|(id, ps)| (id.as_str(), ps.as_slice())));
+        check_connected(commits, &rows);
+        rows
+    }
+
+    fn edges(row: &GraphRow) -> Vec<(usize, usize)> {
+        row.edges.iter().map(|e| (e.from, e.to)).collect()
+    }
+
+    /// Every parent in the listing is reached by following edges down from its child.
+    fn check_connected(commits: &[(&str, &[&str])], rows: &[GraphRow]) {
+        assert_eq!(rows.len(), commits.len());
+        for (i, (_, parents)) in commits.iter().enumerate() {
+            for parent in *parents {
+                let Some(p) = commits.iter().position(|(id, _)| id == parent) else { continue };
+                let mut cols = vec![rows[i].column];
+                for row in &rows[i..p] {
+                    cols = row.edges.iter().filter(|e| cols.contains(&e.from)).map(|e| e.to).collect();
+                }
+                assert!(cols.contains(&rows[p].column), "{} does not reach parent {parent}", commits[i].0);
+            }
+        }
+    }
+
+    #[test]
+    fn linear_history_stays_in_one_lane() {
+        let rows = run(&[("c", &["b"]), ("b", &["a"]), ("a", &[])]);
+        assert!(rows.iter().all(|r| r.column == 0 && r.width == 1));
+        assert_eq!(edges(&rows[0]), [(0, 0)]);
+        assert!(rows[2].edges.is_empty());
+    }
+
+    #[test]
+    fn merge_opens_and_closes_a_lane() {
+        let rows = run(&[("m", &["a", "b"]), ("b", &["a"]), ("a", &[])]);
+        assert_eq!(rows.iter().map(|r| r.column).collect::<Vec<_>>(), [0, 1, 0]);
+        assert_eq!(edges(&rows[0]), [(0, 0), (0, 1)]);
+        assert_eq!(edges(&rows[1]), [(0, 0), (1, 0)]);
+        assert_eq!(rows[0].width, 2);
+    }
+
+    #[test]
+    fn octopus_merge_fans_out_to_each_parent() {
+        let rows = run(&[("o", &["a", "b", "c"]), ("c", &["r"]), ("b", &["r"]), ("a", &["r"]), ("r", &[])]);
+        assert_eq!(edges(&rows[0]), [(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(rows[0].width, 3);
+        assert_eq!(rows.iter().map(|r| r.column).collect::<Vec<_>>(), [0, 2, 1, 0, 0]);
+        // Each side keeps its lane down to the shared root, where all three meet.
+        assert_eq!(edges(&rows[1]), [(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(edges(&rows[3]), [(0, 0), (1, 0), (2, 0)]);
+        assert_eq!(rows[4].width, 1);
+    }
+
+    #[test]
+    fn octopus_with_repeated_parent_draws_one_line() {
+        let rows = run(&[("o", &["a", "b", "a"]), ("b", &[]), ("a", &[])]);
+        assert_eq!(edges(&rows[0]), [(0, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn criss_cross_merges() {
+        // a2 and b2 each merge the other side's first commit.
+        let rows = run(&[
+            ("a2", &["a1", "b1"]),
+            ("b2", &["b1", "a1"]),
+            ("a1", &["r"]),
+            ("b1", &["r"]),
+            ("r", &[]),
+        ]);
+        assert_eq!(rows.iter().map(|r| r.column).collect::<Vec<_>>(), [0, 2, 0, 1, 0]);
+        assert_eq!(edges(&rows[0]), [(0, 0), (0, 1)]);
+        // b2 opens a lane of its own and also joins the lane waiting for a1.
+        assert_eq!(edges(&rows[1]), [(0, 0), (2, 0), (1, 1), (2, 2)]);
+        assert_eq!(rows[1].width, 3);
+        // Both lanes waiting for b1 meet there.
+        assert_eq!(edges(&rows[2]), [(0, 0), (1, 1), (2, 1)]);
+        assert_eq!(edges(&rows[3]), [(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn branch_tips_reuse_freed_lanes() {
+        let rows = run(&[("x", &["a"]), ("y", &["b"]), ("a", &[]), ("z", &["b"]), ("b", &[])]);
+        assert_eq!(rows.iter().map(|r| r.column).collect::<Vec<_>>(), [0, 1, 0, 0, 0]);
+        assert_eq!(edges(&rows[2]), [(1, 1)]);
+        assert_eq!(edges(&rows[3]), [(0, 0), (1, 0)]);
+    }
+
+    #[test]
+    fn incremental_matches_batch_and_runs_parents_off_the_bottom() {
+        let mut graph = GraphLayout::new();
+        assert!(graph.push("c", &["b".into()]).is_none());
+        let first = graph.push("b", &["a".into(), "x".into()]).unwrap();
+        assert_eq!(edges(&first), [(0, 0)]);
+        let last = graph.finish().unwrap();
+        assert_eq!(edges(&last), [(0, 0), (0, 1)]);
+    }
+}
@@ -3,10 +3,19 @@
 pub mod models;
 pub mod backend_id;
 pub mod backend_descriptor;
+pub mod cancel;
+pub mod diff;
+pub mod graph;
+pub mod rewrite;
+pub mod secrets;
+#[cfg(feature = "bench")]
+pub mod bench;
+#[cfg(feature = "test-support")]
+pub mod conformance;
 
-use std::{path::{Path, PathBuf}, sync::Arc};
+use std::{collections::BTreeMap, path::{Path, PathBuf}, sync::Arc};
 pub use crate::backend_id::BackendId;
-pub use crate::models::{Capabilities, OnEvent};
+pub use crate::models::{Capabilities, OnEvent, OnJobEvent};
 
 #[derive(thiserror::Error, Debug)]
 pub enum VcsError {
@@ -18,6 +27,30 @@ pub enum VcsError {
     NothingToCommit,
     #[error("non-fast-forward; merge or rebase required")]
     NonFastForward,
+    #[error("authentication failed: {0}")]
+    AuthFailed(String),
+    #[error("network unreachable: {0}")]
+    NetworkUnreachable(String),
+    #[error("merge conflict in {}", files.join(", "))]
+    MergeConflict { files: Vec<String> },
+    #[error("local changes would be overwritten{}", if files.is_empty() { String::new() } else { format!(": {}", files.join(", ")) })]
+    DirtyWorktree { files: Vec<String> },
+    #[error("HEAD is detached; check out a branch first")]
+    DetachedHead,
+    #[error("remote rejected: {reason}")]
+    RemoteRejected { reason: String },
+    /// History rewriting refused: `commit` is already on these remote-tracking branches.
+    #[error("{commit} has already been pushed to {}", remotes.join(", "))]
+    AlreadyPushed { commit: String, remotes: Vec<String> },
+    /// Another git process holds a lock file (e.g. `.git/index.lock`).
+    #[error("repository is locked by another git process ({path})")]
+    LockHeld { path: String },
+    /// The repository is owned by another user and `path` is not in `safe.directory`.
+    #[error("the repository at {path} is owned by another user (dubious ownership); it must be marked as a safe directory to open it")]
+    DubiousOwnership { path: String },
+    /// The operation was cancelled through its [`cancel::CancelToken`].
+    #[error("operation cancelled")]
+    Cancelled,
     #[error("unsupported backend: {0}")]
     Unsupported(BackendId),
     #[error("io: {0}")]
@@ -26,8 +59,40 @@ pub enum VcsError {
     Backend { backend: BackendId, msg: String },
 }
 
+impl VcsError {
+    /// Stable, machine-readable category for UI branching and telemetry.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            VcsError::NotARepo(_) => "not-a-repo",
+            VcsError::NoSuchBranch(_) => "no-such-branch",
+            VcsError::NothingToCommit => "nothing-to-commit",
+            VcsError::NonFastForward => "non-fast-forward",
+            VcsError::AuthFailed(_) => "auth-failed",
+            VcsError::NetworkUnreachable(_) => "network-unreachable",
+            VcsError::MergeConflict { .. } => "merge-conflict",
+            VcsError::DirtyWorktree { .. } => "dirty-worktree",
+            VcsError::DetachedHead => "detached-head",
+            VcsError::RemoteRejected { .. } => "remote-rejected",
+            VcsError::AlreadyPushed { .. } => "already-pushed",
+            VcsError::LockHeld { .. } => "lock-held",
+            VcsError::DubiousOwnership { .. } => "dubious-ownership",
+            VcsError::Cancelled => "cancelled",
+            VcsError::Unsupported(_) => "unsupported",
+            VcsError::Io(_) => "io",
+            VcsError::Backend { .. } => "backend",
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, VcsError>;
 
+/// An open history walk (see [`Vcs::history_walk`]). Owns whatever it needs (a child
+/// process, its own repository handle), so it can outlive the call that created it.
+pub trait HistoryWalk: Send {
+    /// Up to `n` further commits in walk order; an empty result means the walk is done.
+    fn next_batch(&mut self, n: usize) -> Result<Vec<models::CommitItem>>;
+}
+
 /// The single trait every backend implements. This API is intentionally small and VCS-agnostic.
 pub trait Vcs: Send + Sync {
     fn id(&self) -> BackendId;
@@ -36,56 +101,264 @@ pub trait Vcs: Send + Sync {
     // lifecycle
     fn open(path: &Path) -> Result<Self> where Self: Sized;
     fn clone(url: &str, dest: &Path, on: Option<OnEvent>) -> Result<Self> where Self: Sized;
+    /// Create an empty repository at `path` (created if missing) and open it.
+    fn init(path: &Path) -> Result<Self> where Self: Sized;
 
     // context
     fn workdir(&self) -> &Path;
 
     // common ops
+    /// Branch HEAD is on, including an unborn one (no commits yet); `None` when detached.
     fn current_branch(&self) -> Result<Option<String>>;
+    /// What HEAD points at: a branch, a detached commit (with the tags there) or an unborn branch.
+    fn head_state(&self) -> Result<models::HeadState> {
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// The last `limit` entries of HEAD's reflog, newest first; empty when there is none.
+    fn head_reflog(&self, limit: usize) -> Result<Vec<models::ReflogEntry>> {
+        let _ = limit;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Every worktree of the repository: the main one first, then linked ones.
+    fn list_worktrees(&self) -> Result<Vec<models::WorktreeInfo>> {
+        Err(VcsError::Unsupported(self.id()))
+    }
 
     fn branches(&self) -> Result<Vec<models::BranchItem>>;
 
-    #[deprecated(since = "0.1", note = "This function is being replaced by `branches`.")]
+    /// Filtered, sorted page of branches. The default filters `branches()` in memory
+    /// (recency order falls back to name); backends override to sort by tip date.
+    fn branch_page(&self, query: &models::BranchQuery) -> Result<models::BranchPage> {
+        let mut q = query.clone();
+        q.sort = models::BranchSort::Name;
+        Ok(q.page(self.branches()?))
+    }
 
+    #[deprecated(since = "0.1.0", note = "This function is being replaced by `branches`.")]
     fn local_branches(&self) -> Result<Vec<String>>;
-    fn create_branch(&self, name: &str, checkout: bool) -> Result<()>;
+    /// Create branch `name` at `start_point` (any ref or commit; `None` = HEAD) without
+    /// touching the worktree, then check it out when `checkout` is set. A remote-tracking
+    /// start point becomes the new branch's upstream, as `git branch` does by default.
+    fn create_branch(&self, name: &str, start_point: Option<&str>, checkout: bool) -> Result<()>;
     fn checkout_branch(&self, name: &str) -> Result<()>;
+    /// Check out tag `name`, detaching HEAD at the commit it points to.
+    fn checkout_tag(&self, name: &str) -> Result<()> {
+        let _ = name;
+        Err(VcsError::Unsupported(self.id()))
+    }
+
+    /// Local tags, sorted by name.
+    fn list_tags(&self) -> Result<Vec<models::TagItem>> {
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Create tag `name` on `target` (`None` = HEAD). With a `message` it is annotated, with
+    /// the repository's identity as tagger, and signed with `sign` if given; without one it
+    /// is lightweight, unless signed (a signature needs an annotation, so it gets an empty
+    /// message).
+    fn create_tag(&self, name: &str, target: Option<&str>, message: Option<&str>, sign: Option<&models::SigningKey>) -> Result<()> {
+        let _ = (name, target, message, sign);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Delete local tag `name` (see [`Vcs::delete_remote_tag`] for a remote's).
+    fn delete_tag(&self, name: &str) -> Result<()> {
+        let _ = name;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Check the signature of tag `name`. Lightweight and unsigned tags report
+    /// `signed == false` rather than an error.
+    fn verify_tag(&self, name: &str) -> Result<models::TagVerification> {
+        let _ = name;
+        Err(VcsError::Unsupported(self.id()))
+    }
 
     // network
     fn ensure_remote(&self, name: &str, url: &str) -> Result<()>;
-    /// List configured remotes as (name, url) pairs (fetch URL if multiple).
-    fn list_remotes(&self) -> Result<Vec<(String, String)>>;
+    /// List configured remotes with their fetch and push URLs (first URL if multiple).
+    fn list_remotes(&self) -> Result<Vec<models::RemoteItem>>;
     /// Remove a configured remote by name (no-op if missing).
     fn remove_remote(&self, name: &str) -> Result<()>;
     fn fetch(&self, remote: &str, refspec: &str, on: Option<OnEvent>) -> Result<()>;
+    /// Fetch `remote` with its configured refspecs (and tags), optionally pruning
+    /// remote-tracking branches deleted on the remote, and report what changed.
+    fn fetch_all(&self, remote: &str, prune: bool, on: Option<OnEvent>) -> Result<models::FetchSummary> {
+        let _ = (remote, prune, on);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Whether this is a shallow clone (`.git/shallow`): history stops at the clone depth,
+    /// so walks, ahead/behind counts and merge bases may be incomplete.
+    fn is_shallow(&self) -> Result<bool> {
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Fetch the history missing from a shallow clone from `remote`; a no-op when the
+    /// repository is complete.
+    fn unshallow(&self, remote: &str, on: Option<OnEvent>) -> Result<()> {
+        let _ = (remote, on);
+        Err(VcsError::Unsupported(self.id()))
+    }
     fn push(&self, remote: &str, refspec: &str, on: Option<OnEvent>) -> Result<()>;
+    /// Push with server-side push options (`git push -o`, e.g. GitLab's `ci.skip`).
+    /// Per-ref results are reported as [`VcsEvent::PushStatus`](models::VcsEvent::PushStatus).
+    fn push_with_options(&self, remote: &str, refspec: &str, options: &[String], on: Option<OnEvent>) -> Result<()> {
+        if options.is_empty() { self.push(remote, refspec, on) } else { Err(VcsError::Unsupported(self.id())) }
+    }
+    /// Delete `branch` on `remote` (`git push <remote> --delete refs/heads/<branch>`).
+    fn delete_remote_branch(&self, remote: &str, branch: &str, on: Option<OnEvent>) -> Result<()> {
+        self.push(remote, &format!(":refs/heads/{branch}"), on)
+    }
+    /// Push tags `tags` (all local tags when empty) to `remote`; tags the remote already has
+    /// elsewhere are rejected, as without `--force`.
+    fn push_tags(&self, remote: &str, tags: &[String], on: Option<OnEvent>) -> Result<()> {
+        if tags.is_empty() {
+            return self.push(remote, "refs/tags/*:refs/tags/*", on);
+        }
+        for tag in tags {
+            self.push(remote, &format!("refs/tags/{tag}:refs/tags/{tag}"), on.clone())?;
+        }
+        Ok(())
+    }
+    /// Delete `tag` on `remote` (`git push <remote> --delete refs/tags/<tag>`).
+    fn delete_remote_tag(&self, remote: &str, tag: &str, on: Option<OnEvent>) -> Result<()> {
+        self.push(remote, &format!(":refs/tags/{tag}"), on)
+    }
 
     /// Fast-forward only pull of the current branch from the specified remote/branch.
     /// Implementations should fetch as needed and then update the current branch if a fast-forward is possible.
+    /// Local modifications the fast-forward would overwrite fail with [`VcsError::DirtyWorktree`]
+    /// before anything in the working tree changes.
     fn pull_ff_only(&self, remote: &str, branch: &str, on: Option<OnEvent>) -> Result<()>;
+    /// [`pull_ff_only`](Vcs::pull_ff_only) with local modifications stashed first and
+    /// re-applied afterwards.
+    fn pull_ff_only_autostash(&self, remote: &str, branch: &str, on: Option<OnEvent>) -> Result<()> {
+        let _ = (remote, branch, on);
+        Err(VcsError::Unsupported(self.id()))
+    }
 
     // content
-    fn commit(&self, message: &str, name: &str, email: &str, paths: &[PathBuf]) -> Result<String>;
+    /// Stage `paths` (everything when empty) and commit as `name <email>`; `opts` can
+    /// record another author or author date.
+    fn commit(&self, message: &str, name: &str, email: &str, paths: &[PathBuf], opts: &models::CommitOptions) -> Result<String>;
     /// Commit the current index as-is without staging additional paths.
     /// Implementations should not modify the index before committing.
-    fn commit_index(&self, message: &str, name: &str, email: &str) -> Result<String>;
+    fn commit_index(&self, message: &str, name: &str, email: &str, opts: &models::CommitOptions) -> Result<String>;
     fn status_summary(&self) -> Result<models::StatusSummary>;
 
-    /// Full working tree status for the UI (files + ahead/behind).
-    fn status_payload(&self) -> Result<models::StatusPayload>;
+    /// Full working tree status for the UI (files + ahead/behind). Directory entries
+    /// (collapsed untracked or ignored directories) carry their file count.
+    fn status_payload(&self, opts: &models::StatusOptions) -> Result<models::StatusPayload>;
+
+    /// Like `status_payload`, but hands entries to `on_batch` in groups of up to `batch`
+    /// as they are produced, so huge worktrees can render incrementally. The default
+    /// computes the full payload first and then splits it.
+    fn status_stream(
+        &self,
+        opts: &models::StatusOptions,
+        batch: usize,
+        on_batch: &mut dyn FnMut(Vec<models::FileEntry>),
+    ) -> Result<models::StatusTotals> {
+        let p = self.status_payload(opts)?;
+        for chunk in p.files.chunks(batch.max(1)) {
+            on_batch(chunk.to_vec());
+        }
+        Ok(models::StatusTotals { total: p.files.len(), ahead: p.ahead, behind: p.behind })
+    }
 
     /// History / log (VCS-agnostic). Returns a single page of commits.
     fn log_commits(&self, query: &models::LogQuery) -> Result<Vec<models::CommitItem>>;
 
+    /// Open an incremental walk over the history selected by `query` (`skip`/`limit`
+    /// are ignored). Unlike repeated `log_commits` calls with `skip`, the walk keeps its
+    /// position, so consumers can page through very long histories cheaply.
+    fn history_walk(&self, query: &models::LogQuery) -> Result<Box<dyn HistoryWalk>> {
+        let _ = query;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Full metadata of the commits `ids`, in the same order.
+    fn commit_records(&self, ids: &[String]) -> Result<Vec<models::CommitRecord>> {
+        let _ = ids;
+        Err(VcsError::Unsupported(self.id()))
+    }
+
+    /// Non-merge commits that changed `path`, newest first, with the file's blob at each,
+    /// following renames. `limit == 0` returns the whole history.
+    fn file_revisions(&self, path: &Path, limit: usize) -> Result<Vec<models::FileRevision>> {
+        let _ = (path, limit);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Raw contents of blob `oid`.
+    fn read_blob(&self, oid: &str) -> Result<Vec<u8>> {
+        let _ = oid;
+        Err(VcsError::Unsupported(self.id()))
+    }
+
     // Unified diff for a single file, returned as lines (with diff prefixes).
     /// Backends should:
     /// 1) Prefer workdir vs index (unstaged)
     /// 2) Fallback to index vs HEAD (staged)
     /// 3) Include untracked as additions
     fn diff_file(&self, path: &Path) -> Result<Vec<String>>;
+    /// Unified diff of every change under directory `prefix` (repo-relative) against HEAD,
+    /// staged and unstaged together, untracked files included as additions.
+    fn diff_dir(&self, prefix: &str) -> Result<Vec<String>> {
+        let _ = prefix;
+        Err(VcsError::Unsupported(self.id()))
+    }
     /// Unified diff for a specific commit (vs its first parent, or empty tree if none).
     fn diff_commit(&self, rev: &str) -> Result<Vec<String>>;
 
+    /// Per-file line and size changes of commit `rev` against its first parent (or the
+    /// empty tree), renames detected.
+    fn commit_file_stats(&self, rev: &str) -> Result<Vec<models::FileChangeStat>> {
+        let _ = rev;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Remotes a partial clone (`--filter=blob:none`) fetches left-out objects from on
+    /// demand; empty for a complete repository.
+    fn promisor_remotes(&self) -> Result<Vec<String>> {
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Files changed by `rev` (against its first parent) whose old or new contents are not
+    /// in the local object store, so diffing them needs a promisor remote. Checked without
+    /// fetching anything.
+    fn missing_blobs(&self, rev: &str) -> Result<Vec<String>> {
+        let _ = rev;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Per-file line and size changes of the working tree (staged and unstaged) against
+    /// HEAD, limited to the directory `scope` (see [`models::StatusOptions::scope`]);
+    /// untracked files count as added. Status leaves the `FileEntry` counts empty so it is
+    /// not held up by reading every changed file; callers fill them from this afterwards.
+    fn worktree_file_stats(&self, scope: Option<&str>) -> Result<Vec<models::FileChangeStat>> {
+        let _ = scope;
+        Err(VcsError::Unsupported(self.id()))
+    }
+
+    /// The gitattributes `names` that apply to each of `paths` (repo-relative), as
+    /// `git check-attr` resolves them. Unspecified attributes are left out.
+    fn path_attributes(&self, paths: &[String], names: &[&str]) -> Result<Vec<models::PathAttribute>> {
+        let _ = (paths, names);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Sizes of the blobs the index stages for the next commit: added and modified files
+    /// (not deletions or submodules) that differ from HEAD.
+    fn staged_blob_sizes(&self) -> Result<Vec<models::BlobSize>> {
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Repo-relative paths of every index entry (`/`-separated).
+    fn tracked_paths(&self) -> Result<Vec<String>> {
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Remove `paths` from the index, keeping the files on disk (`git rm --cached`).
+    fn untrack_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        let _ = paths;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Stage `paths` as they are in the working tree (`git add -A`): new and modified files
+    /// are added, deleted ones removed. A directory stands for everything under it.
+    fn stage_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        let _ = paths;
+        Err(VcsError::Unsupported(self.id()))
+    }
+
     /// Stage a unified-diff patch directly into the index (partial commit support).
     /// Backends may return `VcsError::Unsupported` if not implemented.
     fn stage_patch(&self, patch: &str) -> Result<()>;
@@ -100,15 +373,173 @@ pub trait Vcs: Send + Sync {
     fn delete_branch(&self, name: &str, force: bool) -> Result<()>;
     /// Rename a local branch from `old` to `new`.
     fn rename_branch(&self, old: &str, new: &str) -> Result<()>;
-    /// Merge the given branch into the current HEAD. Implementations may return
-    /// `VcsError::Unsupported` if not available.
-    fn merge_into_current(&self, name: &str) -> Result<()>;
+    /// Make local `branch` track `upstream`, a remote-tracking branch like `origin/main`.
+    fn set_upstream(&self, branch: &str, upstream: &str) -> Result<()> {
+        let _ = (branch, upstream);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Effective value of `key` as git itself resolves it: every level (system, global,
+    /// local, `config.worktree`), `include` and `includeIf` sections, last one wins; `None`
+    /// if unset.
+    fn config_value(&self, key: &str) -> Result<Option<models::ConfigValue>> {
+        let _ = key;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Value of `key` in the repository-local config (not global or system); `None` if unset.
+    fn local_config(&self, key: &str) -> Result<Option<String>> {
+        let _ = key;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Set `key` in the repository-local config; `None` removes it.
+    fn set_local_config(&self, key: &str, value: Option<&str>) -> Result<()> {
+        let _ = (key, value);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Directory git runs hooks from: `core.hooksPath` if set, else `hooks` in the
+    /// common git dir (shared by all worktrees).
+    fn hooks_dir(&self) -> Result<PathBuf> {
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Values of `branch.<name>.<key>` in the repository config, by branch name.
+    fn branch_config(&self, key: &str) -> Result<BTreeMap<String, String>> {
+        let _ = key;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Set `branch.<branch>.<key>` in the repository config; `None` removes it.
+    fn set_branch_config(&self, branch: &str, key: &str, value: Option<&str>) -> Result<()> {
+        let _ = (branch, key, value);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Set or clear (`None`) the description of local `branch`.
+    fn set_branch_description(&self, branch: &str, description: Option<&str>) -> Result<()> {
+        self.set_branch_config(branch, "description", description)
+    }
+    /// Merge `name` (a branch, remote-tracking branch, tag or commit) into the current
+    /// branch: a fast-forward or a merge commit as `opts` allow. Conflicts are an outcome,
+    /// not an error (see [`MergeOutcome::Conflicted`](models::MergeOutcome::Conflicted));
+    /// local changes the merge would overwrite fail with [`VcsError::DirtyWorktree`].
+    fn merge_branch(&self, name: &str, opts: &models::MergeOptions) -> Result<models::MergeOutcome> {
+        let _ = (name, opts);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Merge `name` into the current branch with a merge commit (`git merge --no-ff`);
+    /// conflicts fail with [`VcsError::MergeConflict`], the merge left in progress.
+    fn merge_into_current(&self, name: &str) -> Result<()> {
+        let opts = models::MergeOptions { fast_forward: models::FastForward::Never, ..Default::default() };
+        match self.merge_branch(name, &opts)? {
+            models::MergeOutcome::Conflicted { files } => Err(VcsError::MergeConflict { files }),
+            _ => Ok(()),
+        }
+    }
+
+    // history rewriting
+    /// Remote-tracking branches (`origin/main`) that contain `commit`; empty when it has
+    /// not been pushed. History-rewriting commands consult this before touching it.
+    fn is_pushed(&self, commit: &str) -> Result<Vec<String>> {
+        let _ = commit;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Replace `ids`, consecutive commits of the current branch in any order, with one
+    /// commit carrying `message`, the oldest one's author and the newest one's tree; the
+    /// commits after them are recreated on top (see [`rewrite`]). Unless `allow_pushed`,
+    /// refuses with [`VcsError::AlreadyPushed`] when a remote-tracking branch contains any
+    /// of them. Returns the new HEAD.
+    fn squash_commits(&self, ids: &[String], message: &str, allow_pushed: bool) -> Result<String> {
+        let _ = (ids, message, allow_pushed);
+        Err(VcsError::Unsupported(self.id()))
+    }
+
+    // cherry-pick / revert, one commit at a time
+    /// Apply `commit` on top of HEAD (its changes, or their inverse for
+    /// [`PickKind::Revert`](models::PickKind::Revert)) and commit the result with the message
+    /// from [`PickKind::message`](models::PickKind::message); a pick keeps the original author.
+    /// Merge commits are refused. On conflicts nothing is committed: the conflicted files stay
+    /// in the worktree and index, [`VcsError::MergeConflict`] lists them, and
+    /// [`commit_picked`](Vcs::commit_picked) finishes the step once they are resolved and
+    /// staged. A pick that changes nothing fails with [`VcsError::NothingToCommit`].
+    /// Returns the new HEAD.
+    fn pick_commit(&self, commit: &str, kind: models::PickKind) -> Result<String> {
+        let _ = (commit, kind);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Commit the index as the result of [`pick_commit`](Vcs::pick_commit)`(commit, kind)`
+    /// after its conflicts were resolved. Returns the new HEAD.
+    fn commit_picked(&self, commit: &str, kind: models::PickKind) -> Result<String> {
+        let _ = (commit, kind);
+        Err(VcsError::Unsupported(self.id()))
+    }
+
+    // stash
+    /// Shelve local modifications as `stash@{0}` and reset the working tree to HEAD.
+    /// Returns the new entry, or `None` when there was nothing to stash.
+    fn stash_save(&self, opts: &models::StashOptions) -> Result<Option<models::StashEntry>> {
+        let _ = opts;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// The stash list, newest first.
+    fn stash_list(&self) -> Result<Vec<models::StashEntry>> {
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Unified diff of stash `index` against the commit it was made on, followed by its
+    /// untracked files (if it saved any) as additions.
+    fn stash_diff(&self, index: usize) -> Result<Vec<String>> {
+        let _ = index;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Apply stash `index` to the working tree, keeping it in the list. With
+    /// `restore_index` the staged part is restored as staged, like `git stash apply --index`.
+    /// Conflicts are left in place and reported as [`VcsError::MergeConflict`].
+    fn stash_apply(&self, index: usize, restore_index: bool) -> Result<()> {
+        let _ = (index, restore_index);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// [`stash_apply`](Vcs::stash_apply), then drop the entry. On conflict the entry is kept,
+    /// as `git stash pop` does.
+    fn stash_pop(&self, index: usize, restore_index: bool) -> Result<()> {
+        let _ = (index, restore_index);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Delete stash `index`; later entries move up by one.
+    fn stash_drop(&self, index: usize) -> Result<()> {
+        let _ = index;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Write `paths` as saved in stash `index` (untracked ones included) into the working
+    /// tree, leaving the index and the stash alone. A path the stash does not contain fails
+    /// the whole restore before anything is written.
+    fn stash_restore_paths(&self, index: usize, paths: &[PathBuf]) -> Result<()> {
+        let _ = (index, paths);
+        Err(VcsError::Unsupported(self.id()))
+    }
 
     // recovery
     fn hard_reset_head(&self) -> Result<()>;
+    /// Move the current branch (or a detached HEAD) to `target` like `git reset --keep`:
+    /// files that differ between HEAD and `target` are updated, other local changes are
+    /// kept, and a local change in a file that would be updated fails the reset with
+    /// [`VcsError::DirtyWorktree`].
+    fn reset_keep(&self, target: &str) -> Result<()> {
+        let _ = target;
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Commit the working tree as it is (tracked and untracked, not ignored files) to
+    /// `refname` without touching the index or HEAD. The commit's parent is the previous
+    /// snapshot at `refname`, or HEAD for the first one. Returns `None` when nothing changed
+    /// since that parent.
+    fn snapshot_worktree(&self, refname: &str, message: &str) -> Result<Option<String>> {
+        let _ = (refname, message);
+        Err(VcsError::Unsupported(self.id()))
+    }
+    /// Write `paths` (everything when empty) from commit `source` into the working tree,
+    /// leaving the index and HEAD alone.
+    fn restore_worktree(&self, source: &str, paths: &[PathBuf]) -> Result<()> {
+        let _ = (source, paths);
+        Err(VcsError::Unsupported(self.id()))
+    }
 
     // config
-    /// Read repository-local identity (user.name, user.email). Returns None if missing.
+    /// Identity git would commit with (user.name, user.email), resolved like
+    /// [`config_value`](Vcs::config_value). Returns None if either is missing.
     fn get_identity(&self) -> Result<Option<(String, String)>>;
     /// Set repository-local identity (user.name, user.email).
     fn set_identity_local(&self, name: &str, email: &str) -> Result<()>;
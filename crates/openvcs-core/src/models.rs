@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +10,49 @@ pub enum BranchKind {
     Unknown,
 }
 
+/// What HEAD points at.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HeadState {
+    /// On local branch `name`, whose tip is `commit`.
+    Branch { name: String, commit: String },
+    /// Detached at `commit`; `tags` are the tags pointing at it (e.g. after checking out a tag).
+    Detached { commit: String, tags: Vec<String> },
+    /// On `branch`, which has no commits yet (a freshly initialized repository).
+    Unborn { branch: String },
+}
+
+impl HeadState {
+    /// The branch HEAD is on, born or not.
+    pub fn branch(&self) -> Option<&str> {
+        match self {
+            Self::Branch { name, .. } => Some(name),
+            Self::Unborn { branch } => Some(branch),
+            Self::Detached { .. } => None,
+        }
+    }
+
+    /// The commit HEAD resolves to, if any.
+    pub fn commit(&self) -> Option<&str> {
+        match self {
+            Self::Branch { commit, .. } | Self::Detached { commit, .. } => Some(commit),
+            Self::Unborn { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for HeadState {
+    /// "main", "HEAD detached at v1.2.0" / "HEAD detached at 1a2b3c4", "No commits yet on main".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Branch { name, .. } => f.write_str(name),
+            Self::Detached { tags, .. } if !tags.is_empty() => write!(f, "HEAD detached at {}", tags[0]),
+            Self::Detached { commit, .. } => write!(f, "HEAD detached at {}", commit.get(..7).unwrap_or(commit)),
+            Self::Unborn { branch } => write!(f, "No commits yet on {branch}"),
+        }
+    }
+}
+
 #[derive(Default, Clone, Copy, Debug)]
 pub struct StatusSummary {
     pub untracked: usize,
@@ -23,15 +67,671 @@ pub struct BranchItem {
     pub full_ref: String,     // full ref ("refs/heads/main", "refs/remotes/origin/main")
     pub kind: BranchKind,
     pub current: bool,
+    /// Local branch whose configured upstream no longer exists (typically deleted on the
+    /// remote after its PR was merged).
+    #[serde(default)]
+    pub upstream_gone: bool,
+    /// `branch.<name>.description` of a local branch (as set by `git branch --edit-description`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Free-form notes the app keeps for this branch; filled in by the app, not by backends.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteRefKind {
+    Branch,
+    Tag,
+}
+
+/// A branch or tag advertised by a remote (see `BackendDescriptor::remote_refs`).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RemoteRef {
+    /// Short name ("main", "v1.0").
+    pub name: String,
+    pub full_ref: String,
+    pub kind: RemoteRefKind,
+    pub oid: String,
+}
+
+/// What a URL offers before cloning it.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemoteRefs {
+    /// Branch the remote's HEAD points at, if advertised.
+    pub default_branch: Option<String>,
+    /// Branches first, then tags; each group sorted by name.
+    pub refs: Vec<RemoteRef>,
+}
+
+/// Config level a value was read from, named like `git config --show-scope`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigScope {
+    System,
+    /// `~/.gitconfig` or `$XDG_CONFIG_HOME/git/config`.
+    Global,
+    /// The repository's `config`.
+    Local,
+    /// `config.worktree` of the current worktree (`extensions.worktreeConfig`).
+    Worktree,
+    /// `git -c`, `GIT_CONFIG_PARAMETERS` and similar.
+    Command,
+}
+
+/// A config value as git resolves it, with where it came from.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConfigValue {
+    pub value: String,
+    /// Level of the file that set it, or that included the file that did.
+    pub scope: ConfigScope,
+    /// File it was read from (an `include`/`includeIf` target for included values);
+    /// `None` when the backend cannot tell.
+    pub origin: Option<String>,
+}
+
+/// A configured remote.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RemoteItem {
+    pub name: String,
+    pub fetch_url: String,
+    /// Effective push URL: `remote.<name>.pushurl` if set, otherwise the fetch URL.
+    pub push_url: String,
+}
+
+/// A ref that moved during a fetch. `old` is `None` for new refs, `new` for pruned ones.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RefChange {
+    pub full_ref: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// A local branch that has commits of its own and is also behind its upstream.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DivergedBranch {
+    pub branch: String,
+    pub upstream: String,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+/// A working tree of the repository, as `git worktree list` reports it: the main one
+/// first, then any linked worktrees.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorktreeInfo {
+    pub path: String,
+    /// Branch checked out there, including an unborn one; `None` when detached or bare.
+    pub branch: Option<String>,
+    /// Commit checked out; `None` on an unborn branch.
+    pub head: Option<String>,
+    /// This is the worktree the repository was opened at.
+    pub current: bool,
+    pub bare: bool,
+    /// Locked against pruning; holds the reason (possibly empty).
+    pub locked: Option<String>,
+    /// Its directory is gone, so `git worktree prune` would drop it.
+    pub prunable: bool,
+}
+
+/// A moment as git records it: seconds since the Unix epoch plus the UTC offset of whoever
+/// recorded it. Both backends fill these from the same raw values and format them with the
+/// helpers here, so dates, filters and "2 hours ago" labels agree between them.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Timestamp {
+    pub seconds: i64,
+    /// East of UTC, e.g. 120 for `+0200`.
+    pub offset_minutes: i32,
+}
+
+impl Timestamp {
+    pub fn new(seconds: i64, offset_minutes: i32) -> Self {
+        Self { seconds, offset_minutes }
+    }
+
+    /// Parse git's raw date (`1700000000 +0100`), RFC 3339 (`2025-09-01T10:00:00+02:00`)
+    /// or a plain date (`2025-09-01`, midnight UTC).
+    pub fn parse(s: &str) -> Option<Self> {
+        use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+        let s = s.trim();
+        if let Some((secs, tz)) = s.split_once(' ') {
+            let seconds = secs.parse().ok()?;
+            let (sign, hhmm) = match tz.as_bytes().first()? {
+                b'+' => (1, &tz[1..]),
+                b'-' => (-1, &tz[1..]),
+                _ => return None,
+            };
+            if hhmm.len() != 4 {
+                return None;
+            }
+            let (h, m): (i32, i32) = (hhmm[..2].parse().ok()?, hhmm[2..].parse().ok()?);
+            return Some(Self::new(seconds, sign * (h * 60 + m)));
+        }
+        let full = if s.len() == 10 { format!("{s}T00:00:00Z") } else { s.to_string() };
+        let dt = OffsetDateTime::parse(&full, &Rfc3339).ok()?;
+        Some(Self::new(dt.unix_timestamp(), i32::from(dt.offset().whole_minutes())))
+    }
+
+    /// git's raw date, `1700000000 +0100`.
+    pub fn to_raw(&self) -> String {
+        let sign = if self.offset_minutes < 0 { '-' } else { '+' };
+        let off = self.offset_minutes.unsigned_abs();
+        format!("{} {sign}{:02}{:02}", self.seconds, off / 60, off % 60)
+    }
+
+    /// Now, in UTC.
+    pub fn now() -> Self {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        Self::new(secs, 0)
+    }
+
+    /// RFC 3339 in the recorded offset, always with a numeric offset
+    /// (`2025-09-01T10:00:00+02:00`, `…+00:00` rather than `Z`), as `git log --date=iso-strict`.
+    pub fn to_rfc3339(&self) -> String {
+        use time::{OffsetDateTime, UtcOffset};
+        let offset = UtcOffset::from_whole_seconds(self.offset_minutes * 60).unwrap_or(UtcOffset::UTC);
+        let dt = OffsetDateTime::from_unix_timestamp(self.seconds)
+            .unwrap_or(OffsetDateTime::UNIX_EPOCH)
+            .to_offset(offset);
+        let sign = if offset.is_negative() { '-' } else { '+' };
+        let off = self.offset_minutes.unsigned_abs();
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{sign}{:02}:{:02}",
+            dt.year(),
+            u8::from(dt.month()),
+            dt.day(),
+            dt.hour(),
+            dt.minute(),
+            dt.second(),
+            off / 60,
+            off % 60,
+        )
+    }
+
+    /// "just now", "5 minutes ago", "yesterday", "3 weeks ago", … as of `now`; a time in the
+    /// future (clock skew) reads as "just now".
+    pub fn relative_to(&self, now: Timestamp) -> String {
+        let plural = |n: i64, unit: &str| format!("{n} {unit}{} ago", if n == 1 { "" } else { "s" });
+        let sec = (now.seconds - self.seconds).max(0);
+        let min = (sec + 30) / 60;
+        let hr = (min + 30) / 60;
+        let day = (hr + 12) / 24;
+        let (week, month) = ((day + 3) / 7, (day + 15) / 30);
+        if sec < 45 {
+            "just now".into()
+        } else if sec < 90 {
+            plural(1, "minute")
+        } else if min < 60 {
+            plural(min, "minute")
+        } else if hr < 24 {
+            plural(hr, "hour")
+        } else if day == 1 {
+            "yesterday".into()
+        } else if day < 7 {
+            plural(day, "day")
+        } else if week < 5 {
+            plural(week, "week")
+        } else if month < 12 {
+            plural(month, "month")
+        } else {
+            plural((day + 182) / 365, "year")
+        }
+    }
+}
+
+/// Whether [`Vcs::merge_branch`](crate::Vcs::merge_branch) may fast-forward.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FastForward {
+    /// Fast-forward when possible, merge commit otherwise (`git merge`).
+    #[default]
+    Allow,
+    /// Always create a merge commit (`--no-ff`).
+    Never,
+    /// Fail with `NonFastForward` unless it can fast-forward (`--ff-only`).
+    Only,
+}
+
+/// Options of [`Vcs::merge_branch`](crate::Vcs::merge_branch).
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct MergeOptions {
+    pub fast_forward: FastForward,
+    /// Message of the merge commit; `None` for git's (`Merge branch 'topic'`).
+    pub message: Option<String>,
+}
+
+/// What [`Vcs::merge_branch`](crate::Vcs::merge_branch) did.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum MergeOutcome {
+    /// HEAD already contains the branch.
+    UpToDate,
+    /// The current branch was moved to `commit`.
+    FastForward { commit: String },
+    /// Merge commit `commit` was created.
+    Merged { commit: String },
+    /// Stopped on conflicts in `files`, which are left in the worktree and index with the
+    /// merge in progress: committing once they are resolved and staged creates the merge
+    /// commit; resetting to HEAD abandons it.
+    Conflicted { files: Vec<String> },
+}
+
+/// How [`Vcs::pick_commit`](crate::Vcs::pick_commit) applies a commit.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PickKind {
+    CherryPick,
+    Revert,
+}
+
+impl PickKind {
+    /// Message for the commit that applies `id`, whose message is `original`: unchanged
+    /// for a pick, git's `Revert "<summary>"` text for a revert.
+    pub fn message(self, id: &str, original: &str) -> String {
+        match self {
+            PickKind::CherryPick => original.to_string(),
+            PickKind::Revert => {
+                let summary = original.lines().next().unwrap_or("").trim();
+                format!("Revert \"{summary}\"\n\nThis reverts commit {id}.\n")
+            }
+        }
+    }
+}
+
+/// An entry of the stash list, newest first (`stash@{index}`).
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StashEntry {
+    pub index: usize,
+    /// The stash commit.
+    pub id: String,
+    /// As `git stash list` shows it: `WIP on main: 1a2b3c4 Fix` or `On main: <message>`.
+    pub message: String,
+    /// Branch it was made on; `None` when HEAD was detached.
+    pub branch: Option<String>,
+    pub time: Timestamp,
+}
+
+impl StashEntry {
+    /// The branch named in a stash message (`WIP on <branch>: …` / `On <branch>: …`).
+    pub fn branch_of(message: &str) -> Option<String> {
+        let rest = message.strip_prefix("WIP on ").or_else(|| message.strip_prefix("On "))?;
+        let (branch, _) = rest.split_once(": ")?;
+        (branch != "(no branch)").then(|| branch.to_string())
+    }
+}
+
+/// What `stash_save` shelves and how the entry is described.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct StashOptions {
+    /// Listed as `On <branch>: <message>`; without one git's `WIP on <branch>: …` is used.
+    pub message: Option<String>,
+    /// Stash untracked files too (not ignored ones), like `git stash -u`.
+    pub include_untracked: bool,
+    /// Leave staged changes in the index and working tree, like `git stash --keep-index`.
+    pub keep_index: bool,
+}
+
+/// One move of HEAD from its reflog.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReflogEntry {
+    /// Commit before the move; empty for the entry that created HEAD.
+    pub old: String,
+    pub new: String,
+    /// What moved it, e.g. `checkout: moving from main to topic` or `reset: moving to HEAD~1`.
+    pub message: String,
+    #[serde(default)]
+    pub time: Timestamp,
+}
+
+/// What a fetch changed: remote-tracking branches and tags, plus local branches that have
+/// diverged from their upstream afterwards.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FetchSummary {
+    pub remote: String,
+    pub new: Vec<RefChange>,
+    pub updated: Vec<RefChange>,
+    pub pruned: Vec<RefChange>,
+    pub diverged: Vec<DivergedBranch>,
+}
+
+impl FetchSummary {
+    /// Compare `(full_ref, oid)` snapshots taken before and after the fetch.
+    pub fn between(remote: &str, before: &[(String, String)], after: &[(String, String)]) -> Self {
+        let old_of = |name: &str| before.iter().find(|(r, _)| r == name).map(|(_, o)| o.clone());
+        let mut out = FetchSummary { remote: remote.to_string(), ..Default::default() };
+        for (name, oid) in after {
+            match old_of(name) {
+                None => out.new.push(RefChange { full_ref: name.clone(), old: None, new: Some(oid.clone()) }),
+                Some(old) if &old != oid => {
+                    out.updated.push(RefChange { full_ref: name.clone(), old: Some(old), new: Some(oid.clone()) })
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, oid) in before {
+            if !after.iter().any(|(r, _)| r == name) {
+                out.pruned.push(RefChange { full_ref: name.clone(), old: Some(oid.clone()), new: None });
+            }
+        }
+        out
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.new.is_empty() && self.updated.is_empty() && self.pruned.is_empty()
+    }
 }
 
 /// A single file’s status in the working tree / index.
 /// `status` is backend-agnostic (e.g., "A" | "M" | "D" | "R?" etc).
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
 pub struct FileEntry {
     pub path: String,
     pub status: String,
     pub hunks: Vec<String>,
+    /// Lines added/removed against HEAD; `None` for binary files or when not computed.
+    #[serde(default)]
+    pub additions: Option<u32>,
+    #[serde(default)]
+    pub deletions: Option<u32>,
+    /// Size on disk minus size in HEAD, in bytes.
+    #[serde(default)]
+    pub size_delta: Option<i64>,
+    /// Executable-bit, symlink or type change against HEAD.
+    #[serde(default)]
+    pub mode_change: Option<ModeChange>,
+    /// Files under a directory reported as one entry (`path` ends with `/`), counted up to
+    /// [`DIR_COUNT_CAP`].
+    #[serde(default)]
+    pub file_count: Option<u32>,
+}
+
+/// Where [`FileEntry::count_dir_files`] stops counting.
+pub const DIR_COUNT_CAP: u32 = 100_000;
+
+impl FileEntry {
+    /// Fill `file_count` when this entry is a whole directory (untracked or ignored).
+    pub fn count_dir_files(&mut self, workdir: &Path) {
+        if !self.path.ends_with('/') {
+            return;
+        }
+        let mut count = 0u32;
+        let mut stack = vec![workdir.join(&self.path)];
+        'walk: while let Some(dir) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else { continue };
+            for entry in entries.flatten() {
+                // Not following symlinks, like git
+                match entry.file_type() {
+                    Ok(t) if t.is_dir() => stack.push(entry.path()),
+                    Ok(_) => count += 1,
+                    Err(_) => {}
+                }
+                if count >= DIR_COUNT_CAP {
+                    break 'walk;
+                }
+            }
+        }
+        self.file_count = Some(count);
+    }
+
+    /// Copy the counts of `stat` (same path) into this entry.
+    pub fn apply_stat(&mut self, stat: &FileChangeStat) {
+        self.additions = stat.additions;
+        self.deletions = stat.deletions;
+        self.size_delta = Some(stat.size_delta);
+        self.mode_change = stat.mode_change();
+        if stat.status == "T" && self.status == "M" {
+            self.status = "T".into();
+        }
+    }
+}
+
+/// Worktree files larger than this (in bytes) get no line counts in
+/// [`Vcs::worktree_file_stats`](crate::Vcs::worktree_file_stats): they are treated like
+/// binary files rather than read.
+pub const LINE_COUNT_MAX: u64 = 8 << 20;
+
+/// Line and size change of one file in a diff.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct FileChangeStat {
+    pub path: String,
+    /// Previous path of a rename or copy.
+    pub old_path: Option<String>,
+    /// `A`, `M`, `D`, `R`, `C` or `T`.
+    pub status: String,
+    /// `None` for binary files.
+    pub additions: Option<u32>,
+    pub deletions: Option<u32>,
+    /// New size minus old size, in bytes.
+    pub size_delta: i64,
+    /// Git file modes (`0o100644`, `0o100755`, `0o120000`, ...); 0 where the file is absent.
+    #[serde(default)]
+    pub old_mode: u32,
+    #[serde(default)]
+    pub new_mode: u32,
+}
+
+impl FileChangeStat {
+    pub fn mode_change(&self) -> Option<ModeChange> {
+        let changed = self.additions != Some(0) || self.deletions != Some(0);
+        ModeChange::between(self.old_mode, self.new_mode, changed)
+    }
+}
+
+/// What a git file mode denotes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    File,
+    Executable,
+    Symlink,
+    Submodule,
+}
+
+impl EntryKind {
+    pub fn from_mode(mode: u32) -> Option<Self> {
+        match mode {
+            0o100644 | 0o100664 => Some(Self::File),
+            0o100755 => Some(Self::Executable),
+            0o120000 => Some(Self::Symlink),
+            0o160000 => Some(Self::Submodule),
+            _ => None,
+        }
+    }
+}
+
+/// A change git reports as "modified" (or "T") that is about the entry rather than its text.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ModeChange {
+    /// Regular file whose executable bit was set (`true`) or cleared.
+    Executable { executable: bool },
+    /// Different kind of entry, e.g. a file replaced by a symlink.
+    Type { from: EntryKind, to: EntryKind },
+    /// Symlink now pointing somewhere else.
+    SymlinkTarget,
+}
+
+impl ModeChange {
+    /// Change between two modes of the same path; `changed` says whether the contents
+    /// (for a symlink, its target) differ too. `None` for additions and deletions.
+    pub fn between(old: u32, new: u32, changed: bool) -> Option<Self> {
+        let (from, to) = (EntryKind::from_mode(old)?, EntryKind::from_mode(new)?);
+        match (from, to) {
+            (EntryKind::File, EntryKind::Executable) => Some(Self::Executable { executable: true }),
+            (EntryKind::Executable, EntryKind::File) => Some(Self::Executable { executable: false }),
+            (EntryKind::Symlink, EntryKind::Symlink) if changed => Some(Self::SymlinkTarget),
+            _ if from != to => Some(Self::Type { from, to }),
+            _ => None,
+        }
+    }
+}
+
+/// Which whitespace differences count as "whitespace only" in a [`FileDiff`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum WhitespaceMode {
+    /// Whitespace is significant; nothing is marked.
+    #[default]
+    None,
+    /// Changes in trailing whitespace (git's `--ignore-space-at-eol`).
+    Eol,
+    /// Any whitespace change (git's `-w`).
+    All,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Add,
+    Del,
+}
+
+/// One line of a [`DiffHunk`], without its `+`/`-`/` ` prefix.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub text: String,
+    /// Line number in the old file (`None` for added lines).
+    pub old_line: Option<u32>,
+    /// Line number in the new file (`None` for removed lines).
+    pub new_line: Option<u32>,
+    /// Part of a change that only differs in whitespace under the requested mode.
+    #[serde(default)]
+    pub whitespace_only: bool,
+    /// Followed by `\ No newline at end of file`.
+    #[serde(default)]
+    pub no_newline: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct DiffHunk {
+    /// `<file>.<hunk>`: position in the parsed diff, both counted from 0.
+    pub id: String,
+    /// The `@@ -a,b +c,d @@` line as printed.
+    pub header: String,
+    /// Function (or other enclosing line) git printed after the ranges, if any.
+    pub function: Option<String>,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+    /// Every added/removed line is `whitespace_only`.
+    pub whitespace_only: bool,
+}
+
+/// Contents of a Git LFS pointer file.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LfsPointer {
+    /// `sha256:<hex>`.
+    pub oid: String,
+    /// Size of the real object in bytes.
+    pub size: u64,
+}
+
+impl LfsPointer {
+    /// Parse pointer text (`version …`, `oid sha256:…`, `size N`); `None` for anything else.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        if !lines.next()?.starts_with("version https://git-lfs.github.com/spec/") {
+            return None;
+        }
+        let (mut oid, mut size) = (None, None);
+        for line in lines {
+            let (key, value) = line.split_once(' ')?;
+            match key {
+                "oid" => oid = Some(value.to_string()),
+                "size" => size = value.parse().ok(),
+                // Extension lines are allowed, keys are lowercase.
+                _ if key.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'.') => {}
+                _ => return None,
+            }
+        }
+        Some(Self { oid: oid?, size: size? })
+    }
+}
+
+/// Old and new pointer of an LFS-tracked file; `None` where the file is absent.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LfsChange {
+    pub old: Option<LfsPointer>,
+    pub new: Option<LfsPointer>,
+}
+
+/// A gitattribute set on a path (`git check-attr`). Unspecified attributes are not reported.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PathAttribute {
+    pub path: String,
+    pub name: String,
+    /// `set`, `unset`, or the attribute's value.
+    pub value: String,
+}
+
+/// Size of a blob staged for the next commit.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlobSize {
+    pub path: String,
+    pub size: u64,
+}
+
+/// A unified diff of one file, parsed (see [`crate::diff::parse_unified`]).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct FileDiff {
+    pub path: String,
+    /// Previous path of a rename or copy.
+    pub old_path: Option<String>,
+    /// Extended header lines (`diff --git`, `index`, `new file mode`, ...).
+    pub header: Vec<String>,
+    pub binary: bool,
+    /// From the `old mode`/`new mode`/`index` header lines; 0 when not printed.
+    pub old_mode: u32,
+    pub new_mode: u32,
+    pub mode_change: Option<ModeChange>,
+    /// The path's `filter` attribute (e.g. `lfs`); filled by the app, not by the parser.
+    pub filter: Option<String>,
+    /// Both sides are Git LFS pointers (or absent); `hunks` is then left empty.
+    pub lfs: Option<LfsChange>,
+    pub hunks: Vec<DiffHunk>,
+    /// Has hunks and all of them are `whitespace_only`.
+    pub whitespace_only: bool,
+}
+
+/// What a status reports besides changes to tracked files and untracked files.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct StatusOptions {
+    /// Also report ignored paths, with status `!`. An ignored directory is one entry.
+    #[serde(default)]
+    pub include_ignored: bool,
+    /// Report a directory without tracked files as one entry (`dir/`) instead of listing
+    /// every file in it (`--untracked-files=normal`).
+    #[serde(default)]
+    pub collapse_untracked_dirs: bool,
+    /// Only report paths under this directory (repo-relative), e.g. the project of a
+    /// monorepo being worked on. Paths stay relative to the top of the working tree.
+    #[serde(default)]
+    pub prefix: Option<String>,
+}
+
+impl StatusOptions {
+    /// [`prefix`](Self::prefix) as a `/`-separated path without leading or trailing
+    /// separators; `None` when unset or naming the whole tree.
+    pub fn scope(&self) -> Option<String> {
+        self.prefix.as_deref().and_then(normalize_prefix)
+    }
+}
+
+/// `dir` as a repo-relative, `/`-separated prefix without surrounding separators or `./`;
+/// `None` when it names the whole tree.
+pub fn normalize_prefix(dir: &str) -> Option<String> {
+    let dir = dir.replace('\\', "/");
+    let parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty() && *p != ".").collect();
+    (!parts.is_empty()).then(|| parts.join("/"))
 }
 
 /// Flat status summary plus file list, suitable for your UI.
@@ -40,15 +740,314 @@ pub struct StatusPayload {
     pub files: Vec<FileEntry>,
     pub ahead: u32,
     pub behind: u32,
+    /// Tracked paths that clash on this case-insensitive filesystem; filled by the app,
+    /// not by backends.
+    #[serde(default)]
+    pub case_collisions: Vec<CaseCollision>,
+}
+
+/// Tracked paths that differ only by case, so they name the same file on a case-insensitive
+/// filesystem (macOS, Windows) and only one of them can be checked out.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct CaseCollision {
+    /// The lowercased path they share.
+    pub folded: String,
+    /// The spellings in the index, sorted.
+    pub paths: Vec<String>,
+}
+
+impl CaseCollision {
+    /// Groups of two or more `paths` that are equal ignoring case, sorted by `folded`.
+    pub fn find<S: AsRef<str>>(paths: &[S]) -> Vec<CaseCollision> {
+        let mut groups = std::collections::BTreeMap::<String, Vec<String>>::new();
+        for p in paths {
+            let p = p.as_ref();
+            groups.entry(p.to_lowercase()).or_default().push(p.to_string());
+        }
+        groups
+            .into_iter()
+            .filter_map(|(folded, mut paths)| {
+                paths.sort();
+                paths.dedup();
+                (paths.len() > 1).then_some(CaseCollision { folded, paths })
+            })
+            .collect()
+    }
+}
+
+/// Totals reported once a streamed status (`Vcs::status_stream`) has delivered every batch.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct StatusTotals {
+    pub total: usize,
+    pub ahead: u32,
+    pub behind: u32,
 }
 
 /// Lightweight commit representation for lists.
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
 pub struct CommitItem {
     pub id: String,   // revision/hash as string; backend decides encoding
     pub msg: String,
     pub meta: String, // e.g., date or short info
+    /// "Name <email>"; prefer `author_name` / `author_email`.
+    pub author: String,
+    #[serde(default)]
+    pub author_name: String,
+    #[serde(default)]
+    pub author_email: String,
+    #[serde(default)]
+    pub committer_name: String,
+    #[serde(default)]
+    pub committer_email: String,
+    /// Author time, seconds since the Unix epoch (`author_time.seconds`).
+    #[serde(default)]
+    pub timestamp: i64,
+    #[serde(default)]
+    pub author_time: Timestamp,
+    #[serde(default)]
+    pub committer_time: Timestamp,
+    /// First 7 characters of `id`.
+    #[serde(default)]
+    pub short_id: String,
+}
+
+/// Full metadata of one commit, for exports and audit reports.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct CommitRecord {
+    pub id: String,
+    pub parents: Vec<String>,
+    pub author_name: String,
+    pub author_email: String,
+    /// RFC 3339 with the author's offset.
+    pub author_date: String,
+    pub committer_name: String,
+    pub committer_email: String,
+    pub committer_date: String,
+    #[serde(default)]
+    pub author_time: Timestamp,
+    #[serde(default)]
+    pub committer_time: Timestamp,
+    pub summary: String,
+    /// Message after the summary line, trimmed.
+    pub body: String,
+    /// Trailers at the end of `body` (see [`parse_trailers`]).
+    #[serde(default)]
+    pub trailers: Vec<Trailer>,
+}
+
+/// A `Key: value` line from the closing block of a commit message (`Signed-off-by`,
+/// `Co-authored-by`, `Reviewed-by`, `Fixes`, ...).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Trailer {
+    /// As written, e.g. `Signed-off-by`.
+    pub key: String,
+    /// Value with continuation lines joined by a space.
+    pub value: String,
+}
+
+/// Trailers of a commit message body: its last paragraph, if every line there is a
+/// `Key: value` (key made of letters, digits and `-`, then `: `) or an indented continuation of one.
+/// A message with only a summary has none.
+pub fn parse_trailers(body: &str) -> Vec<Trailer> {
+    let body = body.trim_end();
+    let block = body.rsplit_once("\n\n").map_or(body, |(_, last)| last);
+    let mut out: Vec<Trailer> = Vec::new();
+    for line in block.lines() {
+        if line.starts_with([' ', '\t']) {
+            match out.last_mut() {
+                Some(t) => {
+                    t.value.push(' ');
+                    t.value.push_str(line.trim());
+                    continue;
+                }
+                None => return Vec::new(),
+            }
+        }
+        let Some((key, value)) = line.split_once(':') else { return Vec::new() };
+        let key_ok = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        if !key_ok || !(value.is_empty() || value.starts_with([' ', '\t'])) {
+            return Vec::new();
+        }
+        out.push(Trailer { key: key.to_string(), value: value.trim().to_string() });
+    }
+    out
+}
+
+/// Who a commit is by: `Name <email>`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+impl Identity {
+    /// Parse `Name <email>`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (name, rest) = s.trim().split_once('<')?;
+        let email = rest.strip_suffix('>')?.trim();
+        let name = name.trim();
+        let id = Self { name: name.to_string(), email: email.to_string() };
+        id.is_valid().then_some(id)
+    }
+
+    /// Both parts present and free of the characters that would break a signature line.
+    pub fn is_valid(&self) -> bool {
+        let clean = |s: &str| !s.trim().is_empty() && !s.contains(['<', '>', '\n', '\r', '\0']);
+        clean(&self.name) && clean(&self.email)
+    }
+}
+
+impl std::fmt::Display for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// Per-commit overrides for [`Vcs::commit`](crate::Vcs::commit) and
+/// [`Vcs::commit_index`](crate::Vcs::commit_index), e.g. to record someone else's patch
+/// under their name. The committer stays the identity passed to the call.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct CommitOptions {
+    /// Author instead of the committer.
+    pub author: Option<Identity>,
+    /// Author date instead of now.
+    pub author_date: Option<Timestamp>,
+}
+
+/// One version of a file in its history (see [`Vcs::file_revisions`](crate::Vcs::file_revisions)).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct FileRevision {
+    pub commit: String,
+    /// Blob id of the file's contents at `commit`; pass to `read_blob`.
+    pub blob: String,
+    /// Path at `commit` (differs from the requested path before a rename).
+    pub path: String,
     pub author: String,
+    /// RFC 3339 author date.
+    pub date: String,
+    #[serde(default)]
+    pub time: Timestamp,
+    pub summary: String,
+}
+
+/// Signature scheme for signed objects (git's `gpg.format`).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureFormat {
+    #[default]
+    Gpg,
+    Ssh,
+}
+
+/// Key used to sign tags: a GPG key id/fingerprint, or an SSH public key (file path or
+/// literal `ssh-…` key). An empty `key` leaves the choice to the backend (`user.signingkey`).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct SigningKey {
+    pub key: String,
+    pub format: SignatureFormat,
+    /// Signing program (`gpg`, `ssh-keygen`); `None` = backend default.
+    pub program: Option<String>,
+}
+
+impl SigningKey {
+    /// `key` with its format inferred from the spelling: SSH for `ssh-…`/`key::…` literals
+    /// and `.pub` paths, GPG otherwise.
+    pub fn new(key: impl Into<String>) -> Self {
+        let key = key.into();
+        let k = key.trim();
+        let format = if k.starts_with("ssh-") || k.starts_with("key::") || k.ends_with(".pub") {
+            SignatureFormat::Ssh
+        } else {
+            SignatureFormat::Gpg
+        };
+        Self { key, format, program: None }
+    }
+}
+
+/// A local tag (see [`Vcs::list_tags`](crate::Vcs::list_tags)).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct TagItem {
+    pub name: String,
+    /// Commit the tag points at (through the annotation, for annotated tags); the object
+    /// itself when it is not a commit.
+    pub target: String,
+    /// A tag object with tagger and message, rather than a plain ref.
+    pub annotated: bool,
+    /// First line of the annotation; empty for lightweight tags.
+    pub summary: String,
+    /// Tagger of an annotated tag.
+    pub tagger: Option<Identity>,
+    /// Tagger date, or the target's committer date for lightweight tags.
+    pub time: Timestamp,
+}
+
+/// Outcome of checking a tag's signature.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct TagVerification {
+    pub tag: String,
+    /// Annotated and carrying a signature.
+    pub signed: bool,
+    /// The signature is good and made by a known key (keyring / allowed signers).
+    pub valid: bool,
+    pub format: Option<SignatureFormat>,
+    /// Signer as reported by the verifier (GPG user id, SSH principal).
+    pub signer: Option<String>,
+    /// Key fingerprint as reported by the verifier.
+    pub fingerprint: Option<String>,
+    /// Verifier output, for display when the signature doesn't check out.
+    pub detail: String,
+}
+
+impl TagVerification {
+    /// Signature format of a raw tag object, from its trailing armor block.
+    pub fn detect_format(tag_object: &str) -> Option<SignatureFormat> {
+        if tag_object.contains("-----BEGIN PGP SIGNATURE-----") {
+            Some(SignatureFormat::Gpg)
+        } else if tag_object.contains("-----BEGIN SSH SIGNATURE-----") {
+            Some(SignatureFormat::Ssh)
+        } else {
+            None
+        }
+    }
+
+    /// Interpret verifier output: `gpg --status-fd` lines (`[GNUPG:] GOODSIG …`) or
+    /// `ssh-keygen -Y verify` messages (`Good "git" signature for … with … key SHA256:…`).
+    /// `ok` is the verifier's exit status.
+    pub fn from_verifier_output(tag: &str, format: SignatureFormat, ok: bool, output: &str) -> Self {
+        let mut v = Self { tag: tag.to_string(), signed: true, format: Some(format), detail: output.trim().to_string(), ..Default::default() };
+        match format {
+            SignatureFormat::Gpg => {
+                let mut good = false;
+                for line in output.lines() {
+                    let Some(status) = line.strip_prefix("[GNUPG:] ") else { continue };
+                    let mut parts = status.splitn(3, ' ');
+                    match (parts.next(), parts.next(), parts.next()) {
+                        (Some("GOODSIG"), Some(_), uid) => {
+                            good = true;
+                            v.signer = uid.map(str::to_string);
+                        }
+                        (Some("VALIDSIG"), Some(fpr), _) => v.fingerprint = Some(fpr.to_string()),
+                        (Some("BADSIG" | "EXPKEYSIG" | "REVKEYSIG"), Some(_), uid) => v.signer = uid.map(str::to_string),
+                        _ => {}
+                    }
+                }
+                v.valid = ok && good;
+            }
+            SignatureFormat::Ssh => {
+                for line in output.lines() {
+                    if let Some(rest) = line.strip_prefix("Good \"git\" signature for ")
+                        && let Some((who, key)) = rest.split_once(" with ")
+                    {
+                        v.signer = Some(who.to_string());
+                        v.fingerprint = key.rsplit(' ').next().map(str::to_string);
+                        v.valid = ok;
+                    }
+                }
+            }
+        }
+        v
+    }
 }
 
 /// Query for commit history. Keep this VCS-agnostic and stable.
@@ -56,8 +1055,12 @@ pub struct CommitItem {
 pub struct LogQuery {
     /// Show commits reachable from this ref. `None` = HEAD.
     pub rev: Option<String>,
-    /// Optional path filter (single path for now; extendable to Vec later).
+    /// Optional path filter; combined with `paths`.
     pub path: Option<String>,
+    /// Git pathspecs the commits must touch. `:!x`, `:^x` and `:(exclude)x` exclude; with
+    /// only exclusions every other path counts. See [`Pathspec`].
+    #[serde(default)]
+    pub paths: Vec<String>,
     /// ISO 8601 `since` (UTC) e.g. "2025-09-01T00:00:00Z".
     pub since_utc: Option<String>,
     /// ISO 8601 `until` (UTC).
@@ -77,9 +1080,240 @@ impl LogQuery {
     pub fn head(limit: u32) -> Self {
         Self { limit, ..Default::default() }
     }
+
+    /// `since_utc` in epoch seconds; `None` when unset or not a date [`Timestamp::parse`] reads.
+    pub fn since(&self) -> Option<i64> {
+        self.since_utc.as_deref().and_then(Timestamp::parse).map(|t| t.seconds)
+    }
+
+    /// `until_utc` in epoch seconds, like [`since`](Self::since).
+    pub fn until(&self) -> Option<i64> {
+        self.until_utc.as_deref().and_then(Timestamp::parse).map(|t| t.seconds)
+    }
+
+    /// `path` and `paths` together, empty ones dropped.
+    pub fn pathspecs(&self) -> Vec<String> {
+        self.path.iter().chain(&self.paths).filter(|p| !p.trim().is_empty()).cloned().collect()
+    }
+}
+
+/// Which branches a [`BranchQuery`] returns.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BranchScope {
+    #[default]
+    All,
+    Local,
+    Remote,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BranchSort {
+    /// Current branch, then locals, then remotes; each by name.
+    #[default]
+    Name,
+    /// Most recent tip commit first.
+    Recent,
+}
+
+/// Filtered, paginated branch listing.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+#[serde(default)]
+pub struct BranchQuery {
+    /// Glob (`*`, `?`) when it contains a wildcard, otherwise a substring; case-insensitive.
+    pub filter: Option<String>,
+    pub kind: BranchScope,
+    /// Page size; 0 returns everything after `cursor`.
+    pub limit: u32,
+    /// Opaque cursor from the previous page's `next_cursor`.
+    pub cursor: Option<String>,
+    pub sort: BranchSort,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+pub struct BranchPage {
+    pub items: Vec<BranchItem>,
+    /// Pass back as `cursor` to get the next page; `None` on the last page.
+    pub next_cursor: Option<String>,
+    /// Number of branches matching the filter, across all pages.
+    pub total: usize,
+}
+
+impl BranchQuery {
+    pub fn matches(&self, item: &BranchItem) -> bool {
+        let kind_ok = match self.kind {
+            BranchScope::All => true,
+            BranchScope::Local => matches!(item.kind, BranchKind::Local),
+            BranchScope::Remote => matches!(item.kind, BranchKind::Remote { .. }),
+        };
+        kind_ok && self.filter.as_deref().is_none_or(|f| name_matches(f, &item.name))
+    }
+
+    /// Filter, sort and cut one page out of a full branch list. With `BranchSort::Recent`
+    /// the incoming order is kept, so backends pass items already ordered by recency.
+    pub fn page(&self, items: Vec<BranchItem>) -> BranchPage {
+        let mut items: Vec<BranchItem> = items.into_iter().filter(|b| self.matches(b)).collect();
+        if self.sort == BranchSort::Name {
+            let bucket = |b: &BranchItem| match (b.current, &b.kind) {
+                (true, _) => 0,
+                (_, BranchKind::Local) => 1,
+                (_, BranchKind::Remote { .. }) => 2,
+                (_, BranchKind::Unknown) => 3,
+            };
+            items.sort_by(|a, b| bucket(a).cmp(&bucket(b)).then_with(|| a.name.cmp(&b.name)));
+        }
+
+        let total = items.len();
+        let start = self.cursor.as_deref().and_then(|c| c.parse::<usize>().ok()).unwrap_or(0).min(total);
+        let end = if self.limit == 0 { total } else { (start + self.limit as usize).min(total) };
+        BranchPage {
+            items: items.drain(start..end).collect(),
+            next_cursor: (end < total).then(|| end.to_string()),
+            total,
+        }
+    }
+}
+
+fn name_matches(filter: &str, name: &str) -> bool {
+    let filter = filter.trim().to_lowercase();
+    let name = name.to_lowercase();
+    if filter.contains(['*', '?']) {
+        glob_match(filter.as_bytes(), name.as_bytes())
+    } else {
+        name.contains(&filter)
+    }
+}
+
+/// `*` matches any run (including `/`), `?` any single byte.
+fn glob_match(pat: &[u8], s: &[u8]) -> bool {
+    let (mut p, mut i) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while i < s.len() {
+        if p < pat.len() && (pat[p] == b'?' || pat[p] == s[i]) {
+            p += 1;
+            i += 1;
+        } else if p < pat.len() && pat[p] == b'*' {
+            star = Some((p, i));
+            p += 1;
+        } else if let Some((sp, si)) = star {
+            p = sp + 1;
+            i = si + 1;
+            star = Some((sp, si + 1));
+        } else {
+            return false;
+        }
+    }
+    pat[p..].iter().all(|&c| c == b'*')
+}
+
+/// One pattern of a [`Pathspec`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PathspecItem {
+    pattern: String,
+    exclude: bool,
+    literal: bool,
+    icase: bool,
+}
+
+impl PathspecItem {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut item = PathspecItem { pattern: String::new(), exclude: false, literal: false, icase: false };
+        let mut rest = spec;
+        if let Some(long) = spec.strip_prefix(":(") {
+            let (magic, pattern) = long.split_once(')').ok_or_else(|| format!("missing ')' in pathspec {spec}"))?;
+            for word in magic.split(',').map(str::trim).filter(|w| !w.is_empty()) {
+                match word {
+                    "exclude" => item.exclude = true,
+                    "literal" => item.literal = true,
+                    "icase" => item.icase = true,
+                    "top" | "glob" => {}
+                    other => return Err(format!("unsupported pathspec magic '{other}' in {spec}")),
+                }
+            }
+            rest = pattern;
+        } else if let Some(short) = spec.strip_prefix(':') {
+            let end = short.find(|c| !matches!(c, '!' | '^' | '/')).unwrap_or(short.len());
+            item.exclude = short[..end].contains(['!', '^']);
+            rest = &short[end..];
+            rest = rest.strip_prefix(':').unwrap_or(rest);
+        }
+        item.pattern = normalize_prefix(rest).unwrap_or_default();
+        if item.icase {
+            item.pattern = item.pattern.to_lowercase();
+        }
+        Ok(item)
+    }
+
+    fn is_glob(&self) -> bool {
+        !self.literal && self.pattern.contains(['*', '?'])
+    }
+
+    /// `path` (repo-relative, `/`-separated) is this pattern, lies under it or matches it
+    /// as a wildcard (`*` crossing `/`, like git without `:(glob)`).
+    fn matches(&self, path: &str) -> bool {
+        let lowered;
+        let path = if self.icase {
+            lowered = path.to_lowercase();
+            lowered.as_str()
+        } else {
+            path
+        };
+        if self.pattern.is_empty() {
+            return true;
+        }
+        if let Some(rest) = path.strip_prefix(self.pattern.as_str())
+            && (rest.is_empty() || rest.starts_with('/'))
+        {
+            return true;
+        }
+        self.is_glob() && glob_match(self.pattern.as_bytes(), path.as_bytes())
+    }
 }
 
-#[derive(Clone, Debug, Default)]
+/// A list of git pathspecs (paths, directories, wildcards and `:!` exclusions) for backends
+/// that filter paths themselves. A path matches when it matches an included pattern (any
+/// path when there are none) and no excluded one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pathspec {
+    include: Vec<PathspecItem>,
+    exclude: Vec<PathspecItem>,
+}
+
+impl Pathspec {
+    /// Parse `specs`; errors on magic git would refuse as well.
+    pub fn parse(specs: &[String]) -> Result<Self, String> {
+        let mut out = Pathspec::default();
+        for spec in specs.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            let item = PathspecItem::parse(spec)?;
+            if item.exclude {
+                out.exclude.push(item);
+            } else {
+                out.include.push(item);
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|i| i.matches(path)))
+            && !self.exclude.iter().any(|i| i.matches(path))
+    }
+
+    /// The included patterns when they are all plain paths, so a backend can narrow its
+    /// diff to them before calling [`matches`](Self::matches).
+    pub fn literal_prefixes(&self) -> Option<Vec<&str>> {
+        let plain = !self.include.is_empty()
+            && self.include.iter().all(|i| !i.is_glob() && !i.icase && !i.pattern.is_empty());
+        plain.then(|| self.include.iter().map(|i| i.pattern.as_str()).collect())
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
 pub struct Capabilities {
     pub commits: bool,
     pub branches: bool,
@@ -87,16 +1321,156 @@ pub struct Capabilities {
     pub staging: bool,
     pub push_pull: bool,
     pub fast_forward: bool,
+    /// Working-tree operations (status, staging, commit, checkout, discard). Off for bare
+    /// repositories, which open read-only.
+    pub worktree: bool,
+    /// Anything that writes to the repository or its working tree. Off when either sits on
+    /// read-only media or the user may not write there; status, diffs and history still work.
+    pub writable: bool,
+}
+
+impl Capabilities {
+    /// These capabilities minus everything that needs a working tree (for bare repos).
+    pub fn read_only(self) -> Self {
+        Self { commits: false, staging: false, fast_forward: false, worktree: false, ..self }
+    }
+
+    /// These capabilities minus everything that writes (for repos on unwritable media).
+    pub fn unwritable(self) -> Self {
+        Self { commits: false, staging: false, fast_forward: false, push_pull: false, writable: false, ..self }
+    }
+}
+
+/// Whether files can be created in `dir`. Tries it (and removes the file again), since
+/// permission bits say nothing about read-only mounts or ACLs.
+pub fn dir_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".openvcs-write-probe-{}", std::process::id()));
+    match std::fs::OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(e) => !matches!(
+            e.kind(),
+            std::io::ErrorKind::ReadOnlyFilesystem | std::io::ErrorKind::PermissionDenied
+        ),
+    }
+}
+
+/// Stage of a transfer, reported the same way by every backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProgressPhase {
+    /// Remote side enumerating/counting objects.
+    Counting,
+    Compressing,
+    /// Downloading objects (fetch, pull, clone).
+    Receiving,
+    Resolving,
+    /// Uploading objects (push).
+    Writing,
+    /// Updating files in the worktree (clone, checkout).
+    CheckingOut,
+    /// Anything not recognised as one of the above.
+    Other,
+}
+
+impl ProgressPhase {
+    /// Classify a git-style progress line (`Receiving objects:  42% (420/1000), …`,
+    /// optionally prefixed with `remote: `) into its phase and percentage.
+    pub fn parse_line(line: &str) -> (Self, Option<u8>) {
+        let text = line.trim_start_matches("remote:").trim_start();
+        let (label, rest) = text.split_once(':').unwrap_or((text, ""));
+        let phase = match label.trim() {
+            "Enumerating objects" | "Counting objects" => Self::Counting,
+            "Compressing objects" => Self::Compressing,
+            "Receiving objects" => Self::Receiving,
+            "Resolving deltas" => Self::Resolving,
+            "Writing objects" => Self::Writing,
+            "Updating files" | "Checking out files" => Self::CheckingOut,
+            _ => return (Self::Other, None),
+        };
+        let percent = rest
+            .split_once('%')
+            .and_then(|(n, _)| n.trim().parse::<u8>().ok())
+            .map(|p| p.min(100));
+        (phase, percent)
+    }
+
+    /// Object count and transferred size of a git-style progress line: `(420, 1258291)` for
+    /// `Receiving objects:  42% (420/1000), 1.20 MiB | 800.00 KiB/s`, the plain count for
+    /// `Enumerating objects: 1234, done.`. Either is `None` when the line has none.
+    pub fn parse_amounts(line: &str) -> (Option<u64>, Option<u64>) {
+        let text = line.trim_start_matches("remote:").trim_start();
+        let Some((_, rest)) = text.split_once(':') else { return (None, None) };
+        let (count, after) = match rest.split_once('(') {
+            Some((_, inner)) => {
+                let (cur, after) = inner.split_once(')').unwrap_or((inner, ""));
+                (cur.split('/').next().and_then(|n| n.trim().parse().ok()), after)
+            }
+            None => {
+                let digits: String = rest.trim_start().chars().take_while(char::is_ascii_digit).collect();
+                (digits.parse().ok(), "")
+            }
+        };
+        let bytes = after
+            .trim_start_matches(',')
+            .split(['|', ','])
+            .next()
+            .and_then(|size| {
+                let (n, unit) = size.trim().split_once(' ')?;
+                let scale: u64 = match unit.trim() {
+                    "bytes" | "byte" => 1,
+                    "KiB" => 1 << 10,
+                    "MiB" => 1 << 20,
+                    "GiB" => 1 << 30,
+                    _ => return None,
+                };
+                Some((n.parse::<f64>().ok()? * scale as f64) as u64)
+            });
+        (count, bytes)
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum VcsEvent {
     Info(&'static str),
     RemoteMessage(String),
-    Progress { phase: &'static str, detail: String },
+    /// Transfer progress; `percent` is set when the phase reports one.
+    Progress { phase: ProgressPhase, percent: Option<u8>, detail: String },
     Auth { method: &'static str, detail: String },
-    PushStatus { refname: String, status: Option<String> },
+    /// Result for one ref pushed to `remote`; `status` is the rejection reason, `None` if accepted.
+    PushStatus { remote: String, refname: String, status: Option<String> },
     Warning(String),
     Error(String),
 }
-pub type OnEvent = Arc<dyn Fn(VcsEvent) + Send + Sync + 'static>;
\ No newline at end of file
+pub type OnEvent = Arc<dyn Fn(VcsEvent) + Send + Sync + 'static>;
+/// Receives the events of one job together with its [`JobId`] (see [`JobId::on_event`]).
+pub type OnJobEvent = Arc<dyn Fn(JobId, VcsEvent) + Send + Sync + 'static>;
+
+/// Correlation id for one user-initiated operation (clone, fetch, push, commit, ...).
+/// Progress events and log lines produced while it runs carry the same id, so
+/// interleaved operations can be told apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JobId(pub u64);
+
+impl JobId {
+    /// Allocate a fresh, process-unique id.
+    pub fn next() -> Self {
+        static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+        JobId(NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+
+    /// The [`OnEvent`] to hand backends for this job: every event reaches `sink` with the
+    /// id, whichever thread the backend reports it from.
+    pub fn on_event(self, sink: OnJobEvent) -> OnEvent {
+        Arc::new(move |evt| sink(self, evt))
+    }
+}
+
+impl std::fmt::Display for JobId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "job-{}", self.0)
+    }
+}
\ No newline at end of file
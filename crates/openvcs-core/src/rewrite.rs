@@ -0,0 +1,123 @@
+//! History rewriting shared by the backends.
+//!
+//! Squashing a contiguous run of commits keeps the tree of its newest commit, so the
+//! commits after the run can be recreated with their own trees on top of the new commit:
+//! nothing is merged, nothing can conflict, and the working tree and index are left as
+//! they are. Backends list the unpushed first-parent history of HEAD and let
+//! [`plan_squash`] check the selection against it.
+
+/// A commit of the first-parent history of HEAD.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainCommit {
+    pub id: String,
+    pub parents: Vec<String>,
+}
+
+/// What [`plan_squash`] decided; ids are full object ids.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SquashPlan {
+    /// Parent of the new commit.
+    pub base: String,
+    /// The commits to squash, oldest first. The new commit takes the author of the first
+    /// and the tree of the last.
+    pub squashed: Vec<String>,
+    /// Commits after the run, oldest first, to recreate on top of the new commit with the
+    /// first parent replaced.
+    pub replay: Vec<ChainCommit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PlanError {
+    /// The commit is not in the chain: it is on a remote or not on the current branch.
+    Missing(String),
+    /// The selection cannot be squashed; the message says why.
+    Invalid(String),
+}
+
+fn short(id: &str) -> &str {
+    id.get(..7).unwrap_or(id)
+}
+
+/// Plan squashing `ids` (full ids, any order) given `chain`, the unpushed first-parent
+/// history of HEAD, newest first.
+pub fn plan_squash(chain: &[ChainCommit], ids: &[String]) -> Result<SquashPlan, PlanError> {
+    let mut positions = Vec::with_capacity(ids.len());
+    for id in ids {
+        let pos = chain.iter().position(|c| &c.id == id).ok_or_else(|| PlanError::Missing(id.clone()))?;
+        if !positions.contains(&pos) {
+            positions.push(pos);
+        }
+    }
+    if positions.len() < 2 {
+        return Err(PlanError::Invalid("select at least two commits to squash".into()));
+    }
+    positions.sort_unstable();
+    let (newest, oldest) = (positions[0], positions[positions.len() - 1]);
+    if oldest - newest + 1 != positions.len() {
+        return Err(PlanError::Invalid("the commits to squash must be consecutive".into()));
+    }
+    let run = &chain[newest..=oldest];
+    if let Some(merge) = run.iter().find(|c| c.parents.len() > 1) {
+        return Err(PlanError::Invalid(format!("{} is a merge commit and cannot be squashed", short(&merge.id))));
+    }
+    let base = chain[oldest]
+        .parents
+        .first()
+        .ok_or_else(|| PlanError::Invalid("the first commit of the repository cannot be squashed".into()))?;
+    Ok(SquashPlan {
+        base: base.clone(),
+        squashed: run.iter().rev().map(|c| c.id.clone()).collect(),
+        replay: chain[..newest].iter().rev().cloned().collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Linear chain `c<n-1> … c0`, newest first; `c0` is the root.
+    fn chain(n: usize) -> Vec<ChainCommit> {
+        (0..n)
+            .rev()
+            .map(|i| ChainCommit {
+                id: format!("c{i}"),
+                parents: if i == 0 { vec![] } else { vec![format!("c{}", i - 1)] },
+            })
+            .collect()
+    }
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn squashes_a_run_and_replays_what_follows() {
+        let plan = plan_squash(&chain(5), &ids(&["c2", "c1"])).unwrap();
+        assert_eq!(plan.base, "c0");
+        assert_eq!(plan.squashed, ids(&["c1", "c2"]));
+        let replay: Vec<_> = plan.replay.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(replay, ["c3", "c4"]);
+    }
+
+    #[test]
+    fn refuses_gaps_single_commits_and_the_root() {
+        let c = chain(5);
+        assert!(matches!(plan_squash(&c, &ids(&["c1", "c3"])), Err(PlanError::Invalid(_))));
+        assert!(matches!(plan_squash(&c, &ids(&["c3", "c3"])), Err(PlanError::Invalid(_))));
+        assert!(matches!(plan_squash(&c, &ids(&["c0", "c1"])), Err(PlanError::Invalid(_))));
+    }
+
+    #[test]
+    fn reports_commits_outside_the_chain() {
+        assert_eq!(plan_squash(&chain(3), &ids(&["c2", "x"])), Err(PlanError::Missing("x".into())));
+    }
+
+    #[test]
+    fn refuses_merges_in_the_run_but_replays_them_after_it() {
+        let mut c = chain(4);
+        c[0].parents.push("side".into());
+        assert!(matches!(plan_squash(&c, &ids(&["c3", "c2"])), Err(PlanError::Invalid(_))));
+        let plan = plan_squash(&c, &ids(&["c2", "c1"])).unwrap();
+        assert_eq!(plan.replay[0].parents, ids(&["c2", "side"]));
+    }
+}
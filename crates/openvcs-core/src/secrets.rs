@@ -0,0 +1,148 @@
+//! Secret scanning of changes about to be committed.
+//!
+//! A [`SecretScanner`] runs a set of [`SecretRule`]s (regular expressions) over the added
+//! lines of structured diffs and reports [`SecretFinding`]s with the matched text redacted.
+//! [`default_rules`] covers the common cases (cloud keys, private key blocks, access
+//! tokens); callers add or disable rules from configuration. A line containing
+//! [`ALLOW_MARKER`] is never reported, for test fixtures and documented examples.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{DiffLineKind, FileDiff};
+
+/// Put this on a line (usually in a comment) to exempt it from scanning.
+pub const ALLOW_MARKER: &str = "openvcs:allow-secret";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SecretRule {
+    pub id: String,
+    pub description: String,
+    /// Regular expression (`regex` crate syntax) matched against each added line.
+    pub pattern: String,
+}
+
+impl SecretRule {
+    fn new(id: &str, description: &str, pattern: &str) -> Self {
+        Self { id: id.into(), description: description.into(), pattern: pattern.into() }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SecretFinding {
+    pub rule: String,
+    pub description: String,
+    pub path: String,
+    /// Line in the new version of the file.
+    pub line: u32,
+    /// The line with the matched text masked, trimmed to a readable length.
+    pub excerpt: String,
+}
+
+/// The built-in rules.
+pub fn default_rules() -> Vec<SecretRule> {
+    vec![
+        SecretRule::new("aws-access-key-id", "AWS access key ID", r"\b(?:AKIA|ASIA|AGPA|AIDA|AROA)[0-9A-Z]{16}\b"),
+        SecretRule::new(
+            "aws-secret-access-key",
+            "AWS secret access key",
+            r#"(?i)aws.{0,20}(?:secret|private).{0,20}[:=]\s*["']?[0-9A-Za-z/+]{40}\b"#,
+        ),
+        SecretRule::new("private-key", "Private key block", r"-----BEGIN (?:[A-Z0-9]+ )*PRIVATE KEY(?: BLOCK)?-----"),
+        SecretRule::new("github-token", "GitHub token", r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{22,})\b"),
+        SecretRule::new("gitlab-token", "GitLab personal access token", r"\bglpat-[A-Za-z0-9_\-]{20,}\b"),
+        SecretRule::new("slack-token", "Slack token", r"\bxox[abposr]-[A-Za-z0-9-]{10,}\b"),
+        SecretRule::new("google-api-key", "Google API key", r"\bAIza[0-9A-Za-z_\-]{35}\b"),
+        SecretRule::new("stripe-key", "Stripe live key", r"\b(?:sk|rk)_live_[0-9A-Za-z]{24,}\b"),
+        SecretRule::new(
+            "generic-credential",
+            "Hard-coded password, secret or API key",
+            r#"(?i)\b(?:password|passwd|secret|api[_-]?key|access[_-]?token|auth[_-]?token)\b["']?\s*[:=]\s*["'][^"'\s]{8,}["']"#,
+        ),
+    ]
+}
+
+/// Compiled rules.
+#[derive(Debug)]
+pub struct SecretScanner {
+    rules: Vec<(SecretRule, Regex)>,
+}
+
+impl SecretScanner {
+    /// Compile `rules`; fails on the first invalid pattern, naming its rule.
+    pub fn new(rules: Vec<SecretRule>) -> Result<Self, String> {
+        let rules = rules
+            .into_iter()
+            .map(|r| match Regex::new(&r.pattern) {
+                Ok(re) => Ok((r, re)),
+                Err(e) => Err(format!("secret rule '{}': {e}", r.id)),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    /// [`default_rules`] minus the ids in `disabled`, plus `extra` (which replace built-in
+    /// rules of the same id).
+    pub fn with_defaults(extra: &[SecretRule], disabled: &[String]) -> Result<Self, String> {
+        let mut rules: Vec<SecretRule> = default_rules()
+            .into_iter()
+            .filter(|r| !disabled.contains(&r.id) && !extra.iter().any(|e| e.id == r.id))
+            .collect();
+        rules.extend(extra.iter().filter(|r| !disabled.contains(&r.id)).cloned());
+        Self::new(rules)
+    }
+
+    /// Findings in one line of text.
+    pub fn scan_line(&self, path: &str, line_no: u32, text: &str) -> Vec<SecretFinding> {
+        if text.contains(ALLOW_MARKER) {
+            return Vec::new();
+        }
+        self.rules
+            .iter()
+            .filter_map(|(rule, re)| {
+                let m = re.find(text)?;
+                Some(SecretFinding {
+                    rule: rule.id.clone(),
+                    description: rule.description.clone(),
+                    path: path.to_string(),
+                    line: line_no,
+                    excerpt: redact(text, m.start(), m.end()),
+                })
+            })
+            .collect()
+    }
+
+    /// Findings in the added lines of `files`. Binary files and LFS pointers have no lines.
+    pub fn scan_diffs(&self, files: &[FileDiff]) -> Vec<SecretFinding> {
+        let mut out = Vec::new();
+        for file in files {
+            for line in file.hunks.iter().flat_map(|h| &h.lines) {
+                if line.kind == DiffLineKind::Add {
+                    out.extend(self.scan_line(&file.path, line.new_line.unwrap_or(0), &line.text));
+                }
+            }
+        }
+        out
+    }
+}
+
+/// `text` with `start..end` masked except for its first four characters, cut to ~120 chars
+/// around the match.
+fn redact(text: &str, start: usize, end: usize) -> String {
+    let secret = &text[start..end];
+    let keep: String = secret.chars().take(4).collect();
+    let masked = format!("{keep}{}", "*".repeat(secret.chars().count().saturating_sub(4).min(16)));
+    let before: String = {
+        let b = &text[..start];
+        let n = b.chars().count();
+        let skip = n.saturating_sub(50);
+        let tail: String = b.chars().skip(skip).collect();
+        if skip > 0 { format!("…{tail}") } else { tail }
+    };
+    let after: String = {
+        let a = &text[end..];
+        let head: String = a.chars().take(50).collect();
+        if a.chars().count() > 50 { format!("{head}…") } else { head }
+    };
+    format!("{}{masked}{}", before.trim_start(), after.trim_end())
+}
@@ -0,0 +1,35 @@
+//! Status, log and diff timings of both git backends on a generated repository.
+//!
+//! `cargo bench -p openvcs-git-libgit2`; `OPENVCS_BENCH_FILES` and `OPENVCS_BENCH_COMMITS`
+//! change the repository's size (5000 files, 500 commits by default).
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use openvcs_core::backend_descriptor::list_backends;
+use openvcs_core::bench::{Subject, WORKLOADS};
+use openvcs_git as _;
+use openvcs_git_libgit2 as _;
+
+fn size(var: &str, default: usize) -> usize {
+    std::env::var(var).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+fn backends(c: &mut Criterion) {
+    let (files, commits) = (size("OPENVCS_BENCH_FILES", 5000), size("OPENVCS_BENCH_COMMITS", 500));
+    let fixture = openvcs_testkit::synthetic(files, commits).build_temp("bench").expect("build the repository");
+    let backends: Vec<_> = list_backends().map(|d| (d.id.to_string(), (d.open)(fixture.path()).expect("open the repository"))).collect();
+    let subject = Subject::probe(backends[0].1.as_ref()).expect("probe the repository");
+
+    for workload in WORKLOADS {
+        let mut group = c.benchmark_group(format!("{}/{files}x{commits}", workload.name));
+        group.sample_size(20);
+        for (id, vcs) in &backends {
+            group.bench_function(BenchmarkId::from_parameter(id), |b| {
+                b.iter(|| (workload.run)(vcs.as_ref(), &subject).expect(workload.name));
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, backends);
+criterion_main!(benches);
@@ -1,16 +1,23 @@
 mod lowlevel;
+mod sign;
 
 use std::{path::{Path, PathBuf}, sync::Arc};
 use log::{debug, error, info, trace, warn};
 use openvcs_core::*;
 use openvcs_core::backend_descriptor::{BackendDescriptor, BACKENDS};
 use openvcs_core::backend_id::BackendId;
-use openvcs_core::models::{Capabilities, OnEvent, StatusSummary, VcsEvent};
+use openvcs_core::models::{Capabilities, ConfigValue, DivergedBranch, FetchSummary, OnEvent, ProgressPhase, StatusSummary, VcsEvent};
 
 pub const GIT_LIBGIT2_ID: BackendId = backend_id!("git-libgit2");
 
+/// Version of the libgit2 library this backend is linked against (e.g. `1.9.0`).
+pub fn libgit2_version() -> String {
+    let (major, minor, rev) = git2::Version::get().libgit2_version();
+    format!("{major}.{minor}.{rev}")
+}
+
 fn caps_static() -> Capabilities {
-    Capabilities { commits: true, branches: true, tags: true, staging: true, push_pull: true, fast_forward: true }
+    Capabilities { commits: true, branches: true, tags: true, staging: true, push_pull: true, fast_forward: true, worktree: true, writable: true }
 }
 fn open_factory(path: &Path) -> Result<Arc<dyn Vcs>> {
     GitLibGit2::open(path).map(|v| Arc::new(v) as Arc<dyn Vcs>)
@@ -18,16 +25,86 @@ fn open_factory(path: &Path) -> Result<Arc<dyn Vcs>> {
 fn clone_factory(url: &str, dest: &Path, on: Option<OnEvent>) -> Result<Arc<dyn Vcs>> {
     GitLibGit2::clone(url, dest, on).map(|v| Arc::new(v) as Arc<dyn Vcs>)
 }
+fn init_factory(path: &Path) -> Result<Arc<dyn Vcs>> {
+    GitLibGit2::init(path).map(|v| Arc::new(v) as Arc<dyn Vcs>)
+}
 
 #[linkme::distributed_slice(BACKENDS)]
 pub static GIT_LG2_DESC: BackendDescriptor = BackendDescriptor {
     id: GIT_LIBGIT2_ID,
     name: "Git (libgit2)",
     caps: caps_static,
+    version: || Some(libgit2_version()),
     open: open_factory,
     clone_repo: clone_factory,
+    init_repo: init_factory,
+    remote_refs: |url| lowlevel::remote_refs(url).map_err(GitLibGit2::map_err),
 };
 
+/* =========================================================================================
+   Error classification: map lowlevel / libgit2 errors onto structured VcsError variants.
+   ========================================================================================= */
+
+trait ClassifyError: std::fmt::Display {
+    /// A structured error when this one is recognised; `None` falls back to `Backend`.
+    fn classify(&self) -> Option<VcsError>;
+}
+
+impl ClassifyError for lowlevel::GitError {
+    fn classify(&self) -> Option<VcsError> {
+        use lowlevel::GitError as E;
+        match self {
+            E::NotARepo(s) => Some(VcsError::NotARepo(s.clone())),
+            E::NoSuchBranch(s) => Some(VcsError::NoSuchBranch(s.clone())),
+            E::NothingToCommit => Some(VcsError::NothingToCommit),
+            E::NonFastForward => Some(VcsError::NonFastForward),
+            E::DetachedHead => Some(VcsError::DetachedHead),
+            E::RemoteRejected(reason) => Some(VcsError::RemoteRejected { reason: reason.clone() }),
+            E::DirtyWorktree(files) => Some(VcsError::DirtyWorktree { files: files.clone() }),
+            E::MergeConflict(files) => Some(VcsError::MergeConflict { files: files.clone() }),
+            E::AlreadyPushed { commit, remotes } => {
+                Some(VcsError::AlreadyPushed { commit: commit.clone(), remotes: remotes.clone() })
+            }
+            E::LibGit2(e) => e.classify(),
+            E::Signing(_) | E::Rewrite(_) => None,
+            E::Io(_) => None,
+        }
+    }
+}
+
+impl ClassifyError for git2::Error {
+    fn classify(&self) -> Option<VcsError> {
+        use git2::{ErrorClass, ErrorCode};
+        let msg = self.message().to_string();
+        match self.code() {
+            ErrorCode::Auth => return Some(VcsError::AuthFailed(msg)),
+            ErrorCode::NotFastForward => return Some(VcsError::NonFastForward),
+            ErrorCode::Locked => return Some(VcsError::LockHeld { path: msg }),
+            // "repository path '/srv/share/repo/' is not owned by current user"
+            ErrorCode::Owner => {
+                let path = msg.split('\'').nth(1).unwrap_or(&msg).trim_end_matches(['/', '\\']);
+                return Some(VcsError::DubiousOwnership { path: path.to_string() });
+            }
+            ErrorCode::MergeConflict => return Some(VcsError::MergeConflict { files: Vec::new() }),
+            // Checkout would overwrite local modifications.
+            ErrorCode::Conflict | ErrorCode::Uncommitted => {
+                return Some(VcsError::DirtyWorktree { files: Vec::new() });
+            }
+            _ => {}
+        }
+        match self.class() {
+            ErrorClass::Ssh | ErrorClass::Http if msg.to_ascii_lowercase().contains("auth") => {
+                Some(VcsError::AuthFailed(msg))
+            }
+            ErrorClass::Net | ErrorClass::Http | ErrorClass::Ssl | ErrorClass::Ssh => {
+                Some(VcsError::NetworkUnreachable(msg))
+            }
+            ErrorClass::Repository if self.code() == ErrorCode::NotFound => Some(VcsError::NotARepo(msg)),
+            _ => None,
+        }
+    }
+}
+
 /* =========================================================================================
    Public wrapper: implement the openvcs-core::Vcs trait using the low-level libgit2 code.
    ========================================================================================= */
@@ -38,17 +115,34 @@ pub struct GitLibGit2 {
 }
 
 impl GitLibGit2 {
-    fn map_err<E: std::fmt::Display>(e: E) -> VcsError {
+    fn map_err<E: ClassifyError>(e: E) -> VcsError {
+        if cancel::is_cancelled() {
+            info!("backend: operation cancelled ({e})");
+            return VcsError::Cancelled;
+        }
+        if let Some(v) = e.classify() {
+            warn!("backend error ({}): {e}", v.kind());
+            return v;
+        }
         let msg = e.to_string();
         // Loud, because this bubbles up as a user-visible failure.
         error!("backend error: {msg}");
         VcsError::Backend { backend: GIT_LIBGIT2_ID, msg }
-}
-
+    }
 
-    fn adapt_progress(on: Option<OnEvent>) -> impl Fn(String) + Send + Sync + 'static {
+    fn adapt_progress(on: Option<OnEvent>, remote: &str) -> impl Fn(String) + Send + Sync + 'static {
+        let remote = remote.to_string();
         move |s: String| {
             // Always log locally; *also* forward to UI if a callback is present.
+            let (phase, percent) = ProgressPhase::parse_line(&s);
+            if phase != ProgressPhase::Other {
+                trace!("{s}");
+                if let Some(cb) = &on {
+                    cb(VcsEvent::Progress { phase, percent, detail: s });
+                }
+                return;
+            }
+
             if let Some(rest) = s.strip_prefix("remote: ") {
                 debug!("[remote]: {rest}");
                 if let Some(cb) = &on {
@@ -77,7 +171,7 @@ impl GitLibGit2 {
                     (rest.to_string(), None)
                 };
                 if let Some(cb) = &on {
-                    cb(VcsEvent::PushStatus { refname, status });
+                    cb(VcsEvent::PushStatus { remote: remote.clone(), refname, status });
                 }
                 return;
             }
@@ -85,7 +179,7 @@ impl GitLibGit2 {
             // Generic progress falls back to trace to avoid spamming normal logs.
             trace!("{s}");
             if let Some(cb) = &on {
-                cb(VcsEvent::Progress { phase: "libgit2", detail: s });
+                cb(VcsEvent::Progress { phase, percent, detail: s });
             }
         }
     }
@@ -96,7 +190,8 @@ impl Vcs for GitLibGit2 {
     fn id(&self) -> BackendId { GIT_LIBGIT2_ID }
     
     fn caps(&self) -> Capabilities {
-        Capabilities { commits: true, branches: true, tags: true, staging: true, push_pull: true, fast_forward: true }
+        let caps = if self.inner.is_bare() { caps_static().read_only() } else { caps_static() };
+        if self.inner.is_writable() { caps } else { caps.unwritable() }
     }
 
     fn open(path: &Path) -> Result<Self> {
@@ -109,6 +204,11 @@ impl Vcs for GitLibGit2 {
         lowlevel::Git::clone(url, dest).map(|inner| Self { inner }).map_err(Self::map_err)
     }
 
+    fn init(path: &Path) -> Result<Self> {
+        info!("git-libgit2: init {}", path.display());
+        lowlevel::Git::init(path).map(|inner| Self { inner }).map_err(Self::map_err)
+    }
+
     fn workdir(&self) -> &Path { self.inner.workdir() }
 
     fn current_branch(&self) -> Result<Option<String>> {
@@ -116,14 +216,29 @@ impl Vcs for GitLibGit2 {
         self.inner.current_branch().map_err(Self::map_err)
     }
 
+    fn head_reflog(&self, limit: usize) -> Result<Vec<models::ReflogEntry>> {
+        trace!("git-libgit2: head_reflog limit={limit}");
+        self.inner.head_reflog(limit).map_err(Self::map_err)
+    }
+
+    fn list_worktrees(&self) -> Result<Vec<models::WorktreeInfo>> {
+        trace!("git-libgit2: list_worktrees in {}", self.inner.workdir().display());
+        self.inner.list_worktrees().map_err(Self::map_err)
+    }
+
+    fn head_state(&self) -> Result<models::HeadState> {
+        trace!("git-libgit2: head_state in {}", self.inner.workdir().display());
+        self.inner.head_state().map_err(Self::map_err)
+    }
+
     fn local_branches(&self) -> Result<Vec<String>> {
         trace!("git-libgit2: local_branches");
         self.inner.local_branches().map_err(Self::map_err)
     }
 
-    fn create_branch(&self, name: &str, checkout: bool) -> Result<()> {
-        info!("git-libgit2: create_branch '{}' checkout={}", name, checkout);
-        self.inner.create_branch(name, checkout).map_err(Self::map_err)
+    fn create_branch(&self, name: &str, start_point: Option<&str>, checkout: bool) -> Result<()> {
+        info!("git-libgit2: create_branch '{}' from {:?} checkout={}", name, start_point, checkout);
+        self.inner.create_branch(name, start_point, checkout).map_err(Self::map_err)
     }
 
     fn checkout_branch(&self, name: &str) -> Result<()> {
@@ -131,24 +246,54 @@ impl Vcs for GitLibGit2 {
         self.inner.checkout_branch(name).map_err(Self::map_err)
     }
 
+    fn checkout_tag(&self, name: &str) -> Result<()> {
+        info!("git-libgit2: checkout_tag '{}'", name);
+        self.inner.checkout_tag(name).map_err(Self::map_err)
+    }
+
+    fn list_tags(&self) -> Result<Vec<models::TagItem>> {
+        trace!("git-libgit2: list_tags");
+        self.inner.list_tags().map_err(Self::map_err)
+    }
+
+    fn create_tag(&self, name: &str, target: Option<&str>, message: Option<&str>, sign: Option<&models::SigningKey>) -> Result<()> {
+        info!("git-libgit2: create_tag '{name}' target={target:?} signed={}", sign.is_some());
+        self.inner.create_tag(name, target, message, sign).map_err(Self::map_err)
+    }
+
+    fn delete_tag(&self, name: &str) -> Result<()> {
+        info!("git-libgit2: delete_tag '{name}'");
+        self.inner.delete_tag(name).map_err(Self::map_err)
+    }
+
+    fn verify_tag(&self, name: &str) -> Result<models::TagVerification> {
+        trace!("git-libgit2: verify_tag '{name}'");
+        self.inner.verify_tag(name).map_err(Self::map_err)
+    }
+
     fn ensure_remote(&self, name: &str, url: &str) -> Result<()> {
         info!("git-libgit2: ensure_remote '{}' -> {}", name, url);
         self.inner.ensure_remote(name, url).map_err(Self::map_err)
     }
 
-    fn list_remotes(&self) -> Result<Vec<(String, String)>> {
+    fn list_remotes(&self) -> Result<Vec<models::RemoteItem>> {
         trace!("git-libgit2: list_remotes");
-        // Prefer reading from the repository config: remote.<name>.url
-        let mut out: Vec<(String, String)> = Vec::new();
+        // Prefer reading from the repository config: remote.<name>.url / .pushurl
+        let mut out: Vec<models::RemoteItem> = Vec::new();
         let res = self.inner.with_repo(|repo| {
-            let cfg = repo.config().map_err(|e| Self::map_err(e))?;
+            let cfg = repo.config().map_err(Self::map_err)?;
             // Iterate over entries matching remote.*.url
-            let mut iter = cfg.entries(Some("remote.*.url")).map_err(|e| Self::map_err(e))?;
+            let mut iter = cfg.entries(Some("remote.*.url")).map_err(Self::map_err)?;
             while let Some(Ok(entry)) = iter.next() {
                 if let (Some(name), Some(val)) = (entry.name(), entry.value()) {
                     // name like "remote.origin.url" → extract "origin"
                     let remote_name = name.trim_start_matches("remote.").trim_end_matches(".url").to_string();
-                    out.push((remote_name, val.to_string()));
+                    // multi-valued url: keep the first
+                    if out.iter().any(|r| r.name == remote_name) { continue; }
+                    let push_url = cfg
+                        .get_string(&format!("remote.{remote_name}.pushurl"))
+                        .unwrap_or_else(|_| val.to_string());
+                    out.push(models::RemoteItem { name: remote_name, fetch_url: val.to_string(), push_url });
                 }
             }
             Ok::<(), VcsError>(())
@@ -158,19 +303,71 @@ impl Vcs for GitLibGit2 {
 
     fn remove_remote(&self, name: &str) -> Result<()> {
         info!("git-libgit2: remove_remote '{}'", name);
-        self.inner.with_repo(|repo| repo.remote_delete(name)).map_err(Self::map_err)
+        self.inner.with_repo_mut(|repo| repo.remote_delete(name)).map_err(Self::map_err)
     }
 
     fn fetch(&self, remote: &str, refspec: &str, on: Option<OnEvent>) -> Result<()> {
         info!("git-libgit2: fetch {} {}", remote, refspec);
-        self.inner.fetch_with_progress(remote, refspec, Self::adapt_progress(on))
+        self.inner.fetch_with_progress(remote, refspec, Self::adapt_progress(on, remote))
             .map(|_| ())
             .map_err(Self::map_err)
     }
 
+    fn is_shallow(&self) -> Result<bool> {
+        self.inner.is_shallow().map_err(Self::map_err)
+    }
+
+    fn unshallow(&self, remote: &str, on: Option<OnEvent>) -> Result<()> {
+        info!("git-libgit2: unshallow from {}", remote);
+        self.inner.unshallow_with_progress(remote, Self::adapt_progress(on, remote))
+            .map_err(Self::map_err)
+    }
+
+    fn fetch_all(&self, remote: &str, prune: bool, on: Option<OnEvent>) -> Result<FetchSummary> {
+        info!("git-libgit2: fetch {} (prune: {})", remote, prune);
+        let before = self.inner.fetched_refs(remote).map_err(Self::map_err)?;
+        self.inner.fetch_remote_with_progress(remote, prune, Self::adapt_progress(on, remote))
+            .map_err(Self::map_err)?;
+        let after = self.inner.fetched_refs(remote).map_err(Self::map_err)?;
+        let mut summary = FetchSummary::between(remote, &before, &after);
+        summary.diverged = self.inner.diverged_branches()
+            .map_err(Self::map_err)?
+            .into_iter()
+            .map(|(branch, upstream, ahead, behind)| DivergedBranch {
+                branch,
+                upstream,
+                ahead: ahead as u32,
+                behind: behind as u32,
+            })
+            .collect();
+        Ok(summary)
+    }
+
     fn push(&self, remote: &str, refspec: &str, on: Option<OnEvent>) -> Result<()> {
-        info!("git-libgit2: push {} {}", remote, refspec);
-        self.inner.push_refspec_with_progress(remote, refspec, Self::adapt_progress(on))
+        self.push_with_options(remote, refspec, &[], on)
+    }
+
+    fn push_with_options(&self, remote: &str, refspec: &str, options: &[String], on: Option<OnEvent>) -> Result<()> {
+        info!("git-libgit2: push {} {} (options: {:?})", remote, refspec, options);
+        self.inner.push_refspecs_with_progress(remote, &[refspec], options, Self::adapt_progress(on, remote))
+            .map_err(Self::map_err)
+    }
+
+    fn push_tags(&self, remote: &str, tags: &[String], on: Option<OnEvent>) -> Result<()> {
+        info!("git-libgit2: push tags {:?} to {}", tags, remote);
+        // Named one by one: libgit2 doesn't expand wildcards in push refspecs
+        let tags = if tags.is_empty() {
+            self.inner.list_tags().map_err(Self::map_err)?.into_iter().map(|t| t.name).collect()
+        } else {
+            tags.to_vec()
+        };
+        if tags.is_empty() {
+            // An empty push would fall back to the remote's configured refspecs
+            return Ok(());
+        }
+        let refspecs: Vec<String> = tags.iter().map(|t| format!("refs/tags/{t}:refs/tags/{t}")).collect();
+        let refspecs: Vec<&str> = refspecs.iter().map(String::as_str).collect();
+        self.inner.push_refspecs_with_progress(remote, &refspecs, &[], Self::adapt_progress(on, remote))
             .map_err(Self::map_err)
     }
 
@@ -182,22 +379,28 @@ impl Vcs for GitLibGit2 {
         self.inner.fast_forward(&upstream).map_err(Self::map_err)
     }
 
-    fn commit(&self, message: &str, name: &str, email: &str, paths: &[PathBuf]) -> Result<String> {
+    fn pull_ff_only_autostash(&self, remote: &str, branch: &str, _on: Option<OnEvent>) -> Result<()> {
+        let upstream = format!("{}/{}", remote, branch);
+        info!("git-libgit2: pull_ff_only (autostash) {}", upstream);
+        self.inner.with_autostash(|| self.inner.fast_forward(&upstream)).map_err(Self::map_err)
+    }
+
+    fn commit(&self, message: &str, name: &str, email: &str, paths: &[PathBuf], opts: &models::CommitOptions) -> Result<String> {
         info!(
-            "git-libgit2: commit message_len={} author='{} <{}>' paths={}",
-            message.len(), name, email, paths.len()
+            "git-libgit2: commit message_len={} committer='{} <{}>' author={:?} paths={}",
+            message.len(), name, email, opts.author.as_ref().map(|a| a.to_string()), paths.len()
         );
-        self.inner.commit(message, name, email, paths)
+        self.inner.commit(message, name, email, paths, opts)
             .map(|oid| oid.to_string())
             .map_err(Self::map_err)
     }
 
-    fn commit_index(&self, message: &str, name: &str, email: &str) -> Result<String> {
+    fn commit_index(&self, message: &str, name: &str, email: &str, opts: &models::CommitOptions) -> Result<String> {
         info!(
-            "git-libgit2: commit_index message_len={} author='{} <{}>'",
-            message.len(), name, email
+            "git-libgit2: commit_index message_len={} committer='{} <{}>' author={:?}",
+            message.len(), name, email, opts.author.as_ref().map(|a| a.to_string())
         );
-        self.inner.commit_index(message, name, email)
+        self.inner.commit_index(message, name, email, opts)
             .map(|oid| oid.to_string())
             .map_err(Self::map_err)
     }
@@ -217,14 +420,133 @@ impl Vcs for GitLibGit2 {
         self.inner.hard_reset_head().map_err(Self::map_err)
     }
 
+    fn reset_keep(&self, target: &str) -> Result<()> {
+        info!("git-libgit2: reset_keep {target}");
+        self.inner.reset_keep(target).map_err(Self::map_err)
+    }
+
+    fn snapshot_worktree(&self, refname: &str, message: &str) -> Result<Option<String>> {
+        trace!("git-libgit2: snapshot_worktree → {refname}");
+        self.inner.snapshot_worktree(refname, message).map_err(Self::map_err)
+    }
+
+    fn is_pushed(&self, commit: &str) -> Result<Vec<String>> {
+        trace!("git-libgit2: is_pushed {commit}");
+        self.inner.is_pushed(commit).map_err(Self::map_err)
+    }
+
+    fn squash_commits(&self, ids: &[String], message: &str, allow_pushed: bool) -> Result<String> {
+        info!("git-libgit2: squash_commits ({} commit(s), allow_pushed={allow_pushed})", ids.len());
+        self.inner.squash_commits(ids, message, allow_pushed).map_err(Self::map_err)
+    }
+
+    fn stash_save(&self, opts: &models::StashOptions) -> Result<Option<models::StashEntry>> {
+        info!("git-libgit2: stash_save (untracked: {}, keep index: {})", opts.include_untracked, opts.keep_index);
+        self.inner.stash_save(opts).map_err(Self::map_err)
+    }
+
+    fn stash_list(&self) -> Result<Vec<models::StashEntry>> {
+        self.inner.stash_list().map_err(Self::map_err)
+    }
+
+    fn stash_diff(&self, index: usize) -> Result<Vec<String>> {
+        self.inner.stash_diff(index).map_err(Self::map_err)
+    }
+
+    fn stash_apply(&self, index: usize, restore_index: bool) -> Result<()> {
+        info!("git-libgit2: stash apply {index} (index: {restore_index})");
+        self.inner.stash_apply(index, restore_index).map_err(Self::map_err)
+    }
+
+    fn stash_pop(&self, index: usize, restore_index: bool) -> Result<()> {
+        info!("git-libgit2: stash pop {index} (index: {restore_index})");
+        self.inner.stash_pop(index, restore_index).map_err(Self::map_err)
+    }
+
+    fn stash_drop(&self, index: usize) -> Result<()> {
+        info!("git-libgit2: stash drop {index}");
+        self.inner.stash_drop(index).map_err(Self::map_err)
+    }
+
+    fn stash_restore_paths(&self, index: usize, paths: &[PathBuf]) -> Result<()> {
+        info!("git-libgit2: stash_restore_paths {index} ({} path(s))", paths.len());
+        self.inner.stash_restore_paths(index, paths).map_err(Self::map_err)
+    }
+
+    fn pick_commit(&self, commit: &str, kind: models::PickKind) -> Result<String> {
+        info!("git-libgit2: pick_commit {commit} ({kind:?})");
+        self.inner.pick_commit(commit, kind).map_err(Self::map_err)
+    }
+
+    fn commit_picked(&self, commit: &str, kind: models::PickKind) -> Result<String> {
+        info!("git-libgit2: commit_picked {commit} ({kind:?})");
+        self.inner.commit_picked(commit, kind).map_err(Self::map_err)
+    }
+
+    fn restore_worktree(&self, source: &str, paths: &[PathBuf]) -> Result<()> {
+        info!("git-libgit2: restore_worktree from {source} ({} path(s))", paths.len());
+        self.inner.restore_worktree(source, paths).map_err(Self::map_err)
+    }
+
     fn log_commits(&self, q: &models::LogQuery) -> Result<Vec<models::CommitItem>> {
         trace!("git-libgit2: log_commits skip={} limit={}", q.skip, q.limit);
         self.inner.log_commits(q).map_err(Self::map_err)
     }
 
-    fn status_payload(&self) -> Result<models::StatusPayload> {
-        trace!("git-libgit2: status_payload");
-        self.inner.status_payload().map_err(Self::map_err)
+    fn commit_file_stats(&self, rev: &str) -> Result<Vec<models::FileChangeStat>> {
+        trace!("git-libgit2: commit_file_stats {rev}");
+        self.inner.commit_file_stats(rev).map_err(Self::map_err)
+    }
+
+    fn promisor_remotes(&self) -> Result<Vec<String>> {
+        self.inner.promisor_remotes().map_err(Self::map_err)
+    }
+
+    fn missing_blobs(&self, rev: &str) -> Result<Vec<String>> {
+        trace!("git-libgit2: missing_blobs {rev}");
+        self.inner.missing_blobs(rev).map_err(Self::map_err)
+    }
+
+    fn worktree_file_stats(&self, scope: Option<&str>) -> Result<Vec<models::FileChangeStat>> {
+        trace!("git-libgit2: worktree_file_stats {scope:?}");
+        self.inner.worktree_file_stats(scope).map_err(Self::map_err)
+    }
+
+    fn status_payload(&self, opts: &models::StatusOptions) -> Result<models::StatusPayload> {
+        trace!("git-libgit2: status_payload {opts:?}");
+        self.inner.status_payload(opts).map_err(Self::map_err)
+    }
+
+    fn commit_records(&self, ids: &[String]) -> Result<Vec<models::CommitRecord>> {
+        trace!("git-libgit2: commit_records ({})", ids.len());
+        self.inner.commit_records(ids).map_err(Self::map_err)
+    }
+
+    fn file_revisions(&self, path: &Path, limit: usize) -> Result<Vec<models::FileRevision>> {
+        trace!("git-libgit2: file_revisions {} limit={limit}", path.display());
+        self.inner.file_revisions(path, limit).map_err(Self::map_err)
+    }
+
+    fn read_blob(&self, oid: &str) -> Result<Vec<u8>> {
+        trace!("git-libgit2: read_blob {oid}");
+        self.inner.read_blob(oid).map_err(Self::map_err)
+    }
+
+    fn history_walk(&self, query: &models::LogQuery) -> Result<Box<dyn HistoryWalk>> {
+        trace!("git-libgit2: history_walk");
+        let walk = self.inner.history_walk(query).map_err(Self::map_err)?;
+        Ok(Box::new(Lg2LogWalk(walk)))
+    }
+
+    fn status_stream(
+        &self,
+        opts: &models::StatusOptions,
+        batch: usize,
+        on_batch: &mut dyn FnMut(Vec<models::FileEntry>),
+    ) -> Result<models::StatusTotals> {
+        trace!("git-libgit2: status_stream (batch {batch}) {opts:?}");
+        let (total, ahead, behind) = self.inner.status_stream(opts, batch, on_batch).map_err(Self::map_err)?;
+        Ok(models::StatusTotals { total, ahead, behind })
     }
 
     fn diff_file(&self, path: &Path) -> Result<Vec<String>> {
@@ -232,11 +554,41 @@ impl Vcs for GitLibGit2 {
         self.inner.diff_file(path).map_err(Self::map_err)
     }
 
+    fn diff_dir(&self, prefix: &str) -> Result<Vec<String>> {
+        trace!("git-libgit2: diff_dir {prefix}");
+        self.inner.diff_dir(models::normalize_prefix(prefix).as_deref()).map_err(Self::map_err)
+    }
+
     fn diff_commit(&self, rev: &str) -> Result<Vec<String>> {
         trace!("git-libgit2: diff_commit {}", rev);
         self.inner.diff_commit(rev).map_err(Self::map_err)
     }
 
+    fn path_attributes(&self, paths: &[String], names: &[&str]) -> Result<Vec<models::PathAttribute>> {
+        trace!("git-libgit2: path_attributes {} path(s)", paths.len());
+        self.inner.path_attributes(paths, names).map_err(Self::map_err)
+    }
+
+    fn staged_blob_sizes(&self) -> Result<Vec<models::BlobSize>> {
+        trace!("git-libgit2: staged_blob_sizes");
+        self.inner.staged_blob_sizes().map_err(Self::map_err)
+    }
+
+    fn tracked_paths(&self) -> Result<Vec<String>> {
+        trace!("git-libgit2: tracked_paths");
+        self.inner.tracked_paths().map_err(Self::map_err)
+    }
+
+    fn untrack_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        info!("git-libgit2: untrack_paths count={}", paths.len());
+        self.inner.untrack_paths(paths).map_err(Self::map_err)
+    }
+
+    fn stage_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        info!("git-libgit2: stage_paths count={}", paths.len());
+        self.inner.stage_paths(paths).map_err(Self::map_err)
+    }
+
     fn stage_patch(&self, _patch: &str) -> Result<()> {
         // Not implemented yet for libgit2 backend.
         warn!("git-libgit2: stage_patch requested but unsupported");
@@ -255,12 +607,41 @@ impl Vcs for GitLibGit2 {
         self.inner.branches().map_err(Self::map_err)
     }
 
+    fn branch_page(&self, query: &models::BranchQuery) -> Result<models::BranchPage> {
+        let mut items: Vec<models::BranchItem> = self
+            .inner
+            .branches()
+            .map_err(Self::map_err)?
+            .into_iter()
+            .filter(|b| query.matches(b))
+            .collect();
+        if query.sort == models::BranchSort::Recent {
+            // Tip commit time per branch (only for the filtered set), newest first.
+            let mut keyed: Vec<(i64, models::BranchItem)> = self.inner.with_repo(|repo| {
+                items
+                    .drain(..)
+                    .map(|b| {
+                        let t = repo
+                            .find_reference(&b.full_ref)
+                            .and_then(|r| r.peel_to_commit())
+                            .map(|c| c.time().seconds())
+                            .unwrap_or(0);
+                        (t, b)
+                    })
+                    .collect()
+            });
+            keyed.sort_by_key(|(t, _)| std::cmp::Reverse(*t));
+            items = keyed.into_iter().map(|(_, b)| b).collect();
+        }
+        Ok(query.page(items))
+    }
+
     fn get_identity(&self) -> Result<Option<(String, String)>> {
         Ok(lowlevel::git_identity(&self.inner))
     }
 
     fn set_identity_local(&self, name: &str, email: &str) -> Result<()> {
-        self.inner.with_repo(|repo| {
+        self.inner.with_repo_mut(|repo| {
             let mut cfg = repo.config()?;
             cfg.set_str("user.name", name)?;
             cfg.set_str("user.email", email)?;
@@ -269,13 +650,12 @@ impl Vcs for GitLibGit2 {
     }
 
     fn delete_branch(&self, name: &str, _force: bool) -> Result<()> {
-        self.inner.with_repo(|repo| {
+        self.inner.with_repo_mut(|repo| {
             use git2 as g;
             // Do not delete current branch
-            if let Ok(head) = repo.head() {
-                if head.is_branch() && head.shorthand() == Some(name) {
-                    return Err(g::Error::from_str("cannot delete current branch"));
-                }
+            if let Ok(head) = repo.head()
+                && head.is_branch() && head.shorthand() == Some(name) {
+                return Err(g::Error::from_str("cannot delete current branch"));
             }
             let mut br = repo.find_branch(name, g::BranchType::Local)?;
             br.delete()?;
@@ -284,7 +664,7 @@ impl Vcs for GitLibGit2 {
     }
 
     fn rename_branch(&self, old: &str, new: &str) -> Result<()> {
-        self.inner.with_repo(|repo| {
+        self.inner.with_repo_mut(|repo| {
             use git2 as g;
             let mut br = repo.find_branch(old, g::BranchType::Local)?;
             br.rename(new, false)?; // do not force; let libgit2 report conflicts
@@ -292,7 +672,53 @@ impl Vcs for GitLibGit2 {
         }).map_err(Self::map_err::<git2::Error>)
     }
 
-    fn merge_into_current(&self, _name: &str) -> Result<()> {
-        Err(VcsError::Unsupported(GIT_LIBGIT2_ID))
+    fn set_upstream(&self, branch: &str, upstream: &str) -> Result<()> {
+        info!("git-libgit2: set_upstream '{}' -> '{}'", branch, upstream);
+        self.inner.with_repo_mut(|repo| {
+            let mut br = repo.find_branch(branch, git2::BranchType::Local)?;
+            br.set_upstream(Some(upstream))
+        }).map_err(Self::map_err::<git2::Error>)
+    }
+
+    fn branch_config(&self, key: &str) -> Result<std::collections::BTreeMap<String, String>> {
+        trace!("git-libgit2: branch_config '{}'", key);
+        self.inner.branch_config(key).map_err(Self::map_err)
+    }
+
+    fn set_branch_config(&self, branch: &str, key: &str, value: Option<&str>) -> Result<()> {
+        info!("git-libgit2: set_branch_config branch.{}.{} = {:?}", branch, key, value);
+        self.inner.set_branch_config(branch, key, value).map_err(Self::map_err)
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<ConfigValue>> {
+        trace!("git-libgit2: config_value '{}'", key);
+        self.inner.config_value(key).map_err(Self::map_err)
+    }
+
+    fn local_config(&self, key: &str) -> Result<Option<String>> {
+        trace!("git-libgit2: local_config '{}'", key);
+        self.inner.local_config(key).map_err(Self::map_err)
+    }
+
+    fn set_local_config(&self, key: &str, value: Option<&str>) -> Result<()> {
+        debug!("git-libgit2: set_local_config {} = {:?}", key, value);
+        self.inner.set_local_config(key, value).map_err(Self::map_err)
+    }
+
+    fn hooks_dir(&self) -> Result<PathBuf> {
+        self.inner.hooks_dir().map_err(Self::map_err)
+    }
+
+    fn merge_branch(&self, name: &str, opts: &models::MergeOptions) -> Result<models::MergeOutcome> {
+        info!("git-libgit2: merge_branch '{}' ff={:?}", name, opts.fast_forward);
+        self.inner.merge_branch(name, opts).map_err(Self::map_err)
+    }
+}
+
+struct Lg2LogWalk(lowlevel::LogWalk);
+
+impl HistoryWalk for Lg2LogWalk {
+    fn next_batch(&mut self, n: usize) -> Result<Vec<models::CommitItem>> {
+        self.0.next_batch(n).map_err(GitLibGit2::map_err)
     }
 }
@@ -1,9 +1,10 @@
 /* =========================================================================================
-   Low-level module: your original git.rs, adapted to wrap Repository in Arc<Mutex<…>>.
+   Low-level module: your original git.rs, adapted to hand out pooled Repository handles.
    ========================================================================================= */
 use std::{
+    collections::BTreeMap,
     path::{Path, PathBuf},
-    sync::{Arc, Mutex, atomic::{AtomicUsize, Ordering}},
+    sync::{Arc, Mutex, RwLock, atomic::{AtomicUsize, Ordering}},
 };
 use git2::{
     self as g,
@@ -12,9 +13,8 @@ use git2::{
 };
 use log::{debug, error, info, trace, warn};
 use thiserror::Error;
-use time::{OffsetDateTime, UtcOffset};
-use time::format_description::well_known::Rfc3339;
-use openvcs_core::models::{BranchItem, BranchKind, CommitItem, FileEntry, LogQuery, StatusPayload};
+use openvcs_core::rewrite::{self, ChainCommit, PlanError};
+use openvcs_core::models::{BlobSize, BranchItem, BranchKind, CommitItem, CommitOptions, CommitRecord, ConfigScope, ConfigValue, FileChangeStat, FileEntry, LINE_COUNT_MAX, FastForward, FileRevision, HeadState, Identity, LogQuery, MergeOptions, MergeOutcome, PathAttribute, Pathspec, PickKind, ReflogEntry, RemoteRef, RemoteRefKind, RemoteRefs, SignatureFormat, SigningKey, StashEntry, StashOptions, StatusPayload, TagItem, TagVerification, Timestamp, WorktreeInfo, dir_writable, parse_trailers};
 
 pub type Result<T> = std::result::Result<T, GitError>;
 
@@ -28,15 +28,98 @@ pub enum GitError {
     NothingToCommit,
     #[error("non-fast-forward; merge or rebase required")]
     NonFastForward,
+    #[error("HEAD is detached")]
+    DetachedHead,
+    #[error("remote rejected: {0}")]
+    RemoteRejected(String),
+    #[error("local changes would be overwritten: {}", .0.join(", "))]
+    DirtyWorktree(Vec<String>),
+    #[error("merge conflict in {}", .0.join(", "))]
+    MergeConflict(Vec<String>),
+    #[error("{commit} has already been pushed to {}", remotes.join(", "))]
+    AlreadyPushed { commit: String, remotes: Vec<String> },
+    /// History rewriting refused; the message says why.
+    #[error("{0}")]
+    Rewrite(String),
+    /// `gpg`/`ssh-keygen` failed or isn't available.
+    #[error("{0}")]
+    Signing(String),
     #[error(transparent)]
     LibGit2(#[from] g::Error),
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
 
+/// Idle handles kept per repository; more may be open while operations overlap.
+const MAX_IDLE_HANDLES: usize = 4;
+
+/// `Repository` is not `Sync`, so each operation borrows its own handle: an idle one if
+/// available, otherwise a freshly opened one. Reads share `access`, so a long `log_commits`
+/// doesn't block `status_payload`; an operation that writes refs, the index, config or the
+/// working tree takes it exclusively (see `Git::with_repo_mut`), so no read sees it half done.
+struct RepoPool {
+    git_dir: PathBuf,
+    idle: Mutex<Vec<Repository>>,
+    /// The handle opened at construction; used if opening another one fails.
+    fallback: Mutex<Repository>,
+    access: RwLock<()>,
+}
+
+impl RepoPool {
+    fn new(repo: Repository) -> Self {
+        Self {
+            git_dir: repo.path().to_path_buf(),
+            idle: Mutex::new(Vec::new()),
+            fallback: Mutex::new(repo),
+            access: RwLock::new(()),
+        }
+    }
+
+    fn run<T>(&self, f: impl FnOnce(&Repository) -> T) -> T {
+        match self.checkout() {
+            Some(repo) => {
+                let result = f(&repo);
+                self.checkin(repo);
+                result
+            }
+            None => {
+                log::trace!("acquiring shared repo lock");
+                let repo = self.fallback.lock().expect("libgit2 repo poisoned");
+                f(&repo)
+            }
+        }
+    }
+
+    fn checkout(&self) -> Option<Repository> {
+        if let Some(r) = self.idle.lock().ok().and_then(|mut v| v.pop()) {
+            return Some(r);
+        }
+        match Repository::open(&self.git_dir) {
+            Ok(r) => {
+                trace!("opened extra repository handle");
+                Some(r)
+            }
+            Err(e) => {
+                warn!("could not open repository handle ({e}); using the shared one");
+                None
+            }
+        }
+    }
+
+    fn checkin(&self, repo: Repository) {
+        if let Ok(mut idle) = self.idle.lock()
+            && idle.len() < MAX_IDLE_HANDLES {
+            idle.push(repo);
+        }
+    }
+}
+
 pub struct Git {
-    repo: Arc<Mutex<Repository>>,
+    repo: RepoPool,
     workdir: PathBuf,
+    bare: bool,
+    /// The working tree and repository could be written when opened.
+    writable: bool,
 }
 
 impl Git {
@@ -52,21 +135,34 @@ impl Git {
             }
         };
 
-        let workdir: PathBuf = match repo.workdir() {
+        let (workdir, bare): (PathBuf, bool) = match repo.workdir() {
             Some(p) => {
                 let wd = p.to_path_buf();
                 debug!("resolved workdir {}", wd.display());
-                wd
+                (wd, false)
             }
             None => {
-                // Bare repos aren’t supported by this backend wrapper.
-                warn!("bare repository at {} (unsupported)", path.display());
-                return Err(GitError::NotARepo("bare repository is not supported".into()));
+                // Bare: no working tree, so the repository itself stands in for it (read-only).
+                let gd = repo.path().to_path_buf();
+                info!("bare repository at {} (read-only)", gd.display());
+                (gd, true)
             }
         };
 
+        let writable = dir_writable(repo.path()) && (bare || dir_writable(&workdir));
+        if !writable {
+            info!("{} is not writable, opened read-only", workdir.display());
+        }
         info!("repository opened at {}", workdir.display());
-        Ok(Self { repo: Arc::new(Mutex::new(repo)), workdir })
+        Ok(Self { repo: RepoPool::new(repo), workdir, bare, writable })
+    }
+
+    pub fn init(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        info!("initializing repository at {}", path.display());
+        let repo = Repository::init(path).inspect_err(|e| error!("init failed at {}: {e}", path.display()))?;
+        let workdir = repo.workdir().map(Path::to_path_buf).unwrap_or_else(|| path.to_path_buf());
+        Ok(Self { workdir, repo: RepoPool::new(repo), bare: false, writable: true })
     }
 
     pub fn clone(url: &str, dest: impl AsRef<Path>) -> Result<Self> {
@@ -105,21 +201,44 @@ impl Git {
 
         Ok(Self {
             workdir,
-            repo: Arc::new(Mutex::new(repo)),
+            repo: RepoPool::new(repo),
+            bare: false,
+            writable: true,
         })
     }
 
     #[inline]
     pub fn workdir(&self) -> &Path { &self.workdir }
 
+    /// Opened without a working tree (see `open`).
+    #[inline]
+    pub fn is_bare(&self) -> bool { self.bare }
+
+    /// Could be written when opened (see `open`).
+    #[inline]
+    pub fn is_writable(&self) -> bool { self.writable }
+
+    /// Run a read on a repository handle; reads run concurrently. Don't nest: a read waiting
+    /// behind a queued write would deadlock.
     #[inline]
     pub fn with_repo<T>(&self, f: impl FnOnce(&Repository) -> T) -> T {
-        log::trace!("acquiring repo lock");
-        let repo = self.repo.lock().expect("libgit2 repo poisoned");
-        log::trace!("repo lock acquired");
-        let result = f(&*repo);
-        log::trace!("repo lock released");
-        result
+        let _read = self.repo.access.read().unwrap_or_else(|p| p.into_inner());
+        self.repo.run(f)
+    }
+
+    /// Run an operation that changes the repository, once running reads are done and with
+    /// new ones held off until it finishes.
+    #[inline]
+    pub fn with_repo_mut<T>(&self, f: impl FnOnce(&Repository) -> T) -> T {
+        let _write = self.repo.access.write().unwrap_or_else(|p| p.into_inner());
+        self.repo.run(f)
+    }
+
+    /// `with_repo_mut` for the calls that need a `&mut Repository` (stashes): a handle of
+    /// their own, opened under the write lock.
+    fn with_own_repo_mut<T>(&self, f: impl FnOnce(&mut Repository) -> Result<T>) -> Result<T> {
+        let _write = self.repo.access.write().unwrap_or_else(|p| p.into_inner());
+        f(&mut Repository::open(&self.repo.git_dir)?)
     }
 
     pub fn current_branch(&self) -> Result<Option<String>> {
@@ -131,7 +250,8 @@ impl Git {
                 Ok(h) => h,
                 Err(e) if e.code() == g::ErrorCode::UnbornBranch => {
                     debug!("HEAD is unborn (no commits yet)");
-                    return Ok(None);
+                    let head = repo.find_reference("HEAD")?;
+                    return Ok(head.symbolic_target().and_then(|t| t.strip_prefix("refs/heads/")).map(str::to_string));
                 }
                 Err(e) if e.code() == g::ErrorCode::NotFound => {
                     debug!("HEAD not found");
@@ -157,6 +277,113 @@ impl Git {
         })
     }
 
+    /// Newest-first entries of HEAD's reflog (see `Vcs::head_reflog`).
+    pub fn head_reflog(&self, limit: usize) -> Result<Vec<ReflogEntry>> {
+        self.with_repo(|repo| {
+            let log = repo.reflog("HEAD")?;
+            Ok(log
+                .iter()
+                .take(limit)
+                .map(|e| ReflogEntry {
+                    old: if e.id_old().is_zero() { String::new() } else { e.id_old().to_string() },
+                    new: e.id_new().to_string(),
+                    message: e.message().unwrap_or_default().to_string(),
+                    time: timestamp(e.committer().when()),
+                })
+                .collect())
+        })
+    }
+
+    pub fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
+        self.with_repo(|repo| {
+            let here = repo.workdir().and_then(|p| p.canonicalize().ok());
+            let describe = |wt: &Repository, path: &Path| -> Result<WorktreeInfo> {
+                let mut info = WorktreeInfo {
+                    path: path.to_string_lossy().trim_end_matches(['/', '\\']).to_string(),
+                    bare: wt.is_bare(),
+                    current: here.is_some() && path.canonicalize().ok() == here,
+                    ..Default::default()
+                };
+                if !info.bare {
+                    let head = wt.find_reference("HEAD")?;
+                    info.branch = head
+                        .symbolic_target()
+                        .and_then(|t| t.strip_prefix("refs/heads/"))
+                        .map(str::to_string);
+                    info.head = head.resolve().ok().and_then(|r| r.target()).map(|o| o.to_string());
+                }
+                Ok(info)
+            };
+
+            let main = Repository::open(repo.commondir())?;
+            let mut list = vec![describe(&main, main.workdir().unwrap_or_else(|| main.path()))?];
+            for name in main.worktrees()?.iter().flatten() {
+                let wt = main.find_worktree(name)?;
+                let locked = match wt.is_locked()? {
+                    g::WorktreeLockStatus::Locked(reason) => Some(reason.unwrap_or_default().trim_end().to_string()),
+                    g::WorktreeLockStatus::Unlocked => None,
+                };
+                let mut info = match wt.validate() {
+                    Ok(()) => describe(&Repository::open_from_worktree(&wt)?, wt.path())?,
+                    Err(_) => WorktreeInfo {
+                        path: wt.path().to_string_lossy().into_owned(),
+                        prunable: true,
+                        ..Default::default()
+                    },
+                };
+                info.locked = locked;
+                list.push(info);
+            }
+            // Linked worktrees in path order, like `git worktree list`.
+            list[1..].sort_by(|a, b| a.path.cmp(&b.path));
+            Ok(list)
+        })
+    }
+
+    pub fn head_state(&self) -> Result<HeadState> {
+        self.with_repo(|repo| {
+            let head = match repo.head() {
+                Ok(h) => h,
+                Err(e) if e.code() == g::ErrorCode::UnbornBranch => {
+                    // HEAD still names the branch the first commit will create.
+                    let head = repo.find_reference("HEAD")?;
+                    let target = head.symbolic_target().unwrap_or_default();
+                    let branch = target.strip_prefix("refs/heads/").unwrap_or(target).to_string();
+                    return Ok(HeadState::Unborn { branch });
+                }
+                Err(e) => return Err(GitError::LibGit2(e)),
+            };
+            let oid = head.peel_to_commit()?.id();
+            if head.is_branch() {
+                let name = head.shorthand().unwrap_or_default().to_string();
+                return Ok(HeadState::Branch { name, commit: oid.to_string() });
+            }
+            let mut tags: Vec<String> = repo
+                .tag_names(None)?
+                .iter()
+                .flatten()
+                .filter(|t| {
+                    repo.find_reference(&format!("refs/tags/{t}"))
+                        .and_then(|r| r.peel_to_commit())
+                        .is_ok_and(|c| c.id() == oid)
+                })
+                .map(str::to_string)
+                .collect();
+            tags.sort_by(|a, b| b.cmp(a));
+            Ok(HeadState::Detached { commit: oid.to_string(), tags })
+        })
+    }
+
+    pub fn checkout_tag(&self, name: &str) -> Result<()> {
+        info!("checking out tag '{name}'");
+        self.with_repo_mut(|repo| {
+            let commit = repo.find_reference(&format!("refs/tags/{name}"))?.peel_to_commit()?;
+            repo.checkout_tree(commit.as_object(), None)?;
+            repo.set_head_detached(commit.id())?;
+            Ok(())
+        })
+    }
+
     pub fn local_branches(&self) -> Result<Vec<String>> {
         debug!("listing local branches…");
 
@@ -192,27 +419,34 @@ impl Git {
         })
     }
 
-    pub fn create_branch(&self, name: &str, checkout: bool) -> Result<()> {
-        info!("creating branch '{}'", name);
+    pub fn create_branch(&self, name: &str, start_point: Option<&str>, checkout: bool) -> Result<()> {
+        info!("creating branch '{}' from {:?}", name, start_point);
 
-        self.with_repo(|repo| -> Result<()> {
-            let head = repo.head()
-                .map_err(|e| {
-                    error!("failed to resolve HEAD for branch '{name}': {e}");
-                    e
-                })?
-                .peel_to_commit()
-                .map_err(|e| {
-                    error!("failed to peel HEAD to commit for branch '{name}': {e}");
-                    e
-                })?;
+        self.with_repo_mut(|repo| -> Result<()> {
+            let base = match start_point {
+                Some(rev) => repo.revparse_single(rev).and_then(|o| o.peel_to_commit()),
+                None => repo.head().and_then(|h| h.peel_to_commit()),
+            }
+            .map_err(|e| {
+                error!("failed to resolve start point {start_point:?} for branch '{name}': {e}");
+                e
+            })?;
 
-            repo.branch(name, &head, false)
+            let mut branch = repo.branch(name, &base, false)
                 .map_err(|e| {
                     error!("failed to create branch '{name}': {e}");
                     e
                 })?;
 
+            // Like git's default `branch.autoSetupMerge`: track a remote-tracking start point
+            // (git skips this quietly when no configured remote fetches into that ref)
+            if let Some(rev) = start_point
+                && repo.find_branch(rev, g::BranchType::Remote).is_ok()
+                && let Err(e) = branch.set_upstream(Some(rev))
+            {
+                debug!("not tracking '{rev}' from '{name}': {e}");
+            }
+
             debug!("branch '{name}' created");
             Ok(())
         })?;
@@ -229,7 +463,7 @@ impl Git {
         use git2 as g;
         info!("checking out branch '{name}'");
 
-        self.with_repo(|repo| {
+        self.with_repo_mut(|repo| {
             // Helper: checkout by full ref if present
             let checkout_ref = |repo: &g::Repository, full_ref: &str| -> Result<()> {
                 let (obj, reference) = repo.revparse_ext(full_ref)?;
@@ -250,44 +484,39 @@ impl Git {
             }
 
             // 2) Remote branch name like "origin/feature"
-            let mut tried_remote = false;
-            if name.contains('/') {
-                if repo.find_branch(name, g::BranchType::Remote).is_ok() {
-                    tried_remote = true;
-                    let local = name.split('/').last().unwrap_or(name);
-                    if repo.find_branch(local, g::BranchType::Local).is_err() {
-                        // Create local branch at the remote target
-                        let rb = repo.find_branch(name, g::BranchType::Remote)?;
-                        let target = rb.get().target().ok_or_else(|| g::Error::from_str("remote branch has no target"))?;
-                        let commit = repo.find_commit(target)?;
-                        repo.branch(local, &commit, false)?;
-                        // Set upstream to remote
-                        let mut lb = repo.find_branch(local, g::BranchType::Local)?;
-                        lb.set_upstream(Some(name))?;
-                    }
-                    checkout_ref(repo, &format!("refs/heads/{}", local))?;
-                    info!("created and checked out tracking branch '{}' for remote '{}'", local, name);
-                    return Ok(());
+            if name.contains('/')
+                && repo.find_branch(name, g::BranchType::Remote).is_ok() {
+                let local = name.split('/').next_back().unwrap_or(name);
+                if repo.find_branch(local, g::BranchType::Local).is_err() {
+                    // Create local branch at the remote target
+                    let rb = repo.find_branch(name, g::BranchType::Remote)?;
+                    let target = rb.get().target().ok_or_else(|| g::Error::from_str("remote branch has no target"))?;
+                    let commit = repo.find_commit(target)?;
+                    repo.branch(local, &commit, false)?;
+                    // Set upstream to remote
+                    let mut lb = repo.find_branch(local, g::BranchType::Local)?;
+                    lb.set_upstream(Some(name))?;
                 }
+                checkout_ref(repo, &format!("refs/heads/{}", local))?;
+                info!("created and checked out tracking branch '{}' for remote '{}'", local, name);
+                return Ok(());
             }
 
             // 3) Try default remote "origin/<name>"
-            if !tried_remote {
-                let remote_short = format!("origin/{name}");
-                if repo.find_branch(&remote_short, g::BranchType::Remote).is_ok() {
-                    let local = name;
-                    if repo.find_branch(local, g::BranchType::Local).is_err() {
-                        let rb = repo.find_branch(&remote_short, g::BranchType::Remote)?;
-                        let target = rb.get().target().ok_or_else(|| g::Error::from_str("remote branch has no target"))?;
-                        let commit = repo.find_commit(target)?;
-                        repo.branch(local, &commit, false)?;
-                        let mut lb = repo.find_branch(local, g::BranchType::Local)?;
-                        lb.set_upstream(Some(&remote_short))?;
-                    }
-                    checkout_ref(repo, &format!("refs/heads/{local}"))?;
-                    info!("created and checked out tracking branch '{}' for remote '{}'", local, remote_short);
-                    return Ok(());
+            let remote_short = format!("origin/{name}");
+            if repo.find_branch(&remote_short, g::BranchType::Remote).is_ok() {
+                let local = name;
+                if repo.find_branch(local, g::BranchType::Local).is_err() {
+                    let rb = repo.find_branch(&remote_short, g::BranchType::Remote)?;
+                    let target = rb.get().target().ok_or_else(|| g::Error::from_str("remote branch has no target"))?;
+                    let commit = repo.find_commit(target)?;
+                    repo.branch(local, &commit, false)?;
+                    let mut lb = repo.find_branch(local, g::BranchType::Local)?;
+                    lb.set_upstream(Some(&remote_short))?;
                 }
+                checkout_ref(repo, &format!("refs/heads/{local}"))?;
+                info!("created and checked out tracking branch '{}' for remote '{}'", local, remote_short);
+                return Ok(());
             }
 
             // 4) Fallback to local ref (may detach if it's a commit)
@@ -297,11 +526,131 @@ impl Git {
         })
     }
 
+    /// Local tags sorted by name, peeled to the commit they tag.
+    pub fn list_tags(&self) -> Result<Vec<TagItem>> {
+        self.with_repo(|repo| -> Result<Vec<TagItem>> {
+            let mut tags = Vec::new();
+            for reference in repo.references_glob("refs/tags/*")? {
+                let reference = reference?;
+                let Some(name) = reference.name().and_then(|n| n.strip_prefix("refs/tags/")) else { continue };
+                let Some(oid) = reference.resolve()?.target() else { continue };
+                let obj = repo.find_object(oid, None)?;
+                let target = obj.peel(g::ObjectType::Commit).map_or_else(|_| obj.id(), |c| c.id());
+                let commit_time = repo.find_commit(target).map(|c| timestamp(c.committer().when())).unwrap_or_default();
+                let item = match obj.as_tag() {
+                    Some(tag) => TagItem {
+                        name: name.to_string(),
+                        target: target.to_string(),
+                        annotated: true,
+                        summary: tag.message().and_then(|m| m.lines().next()).unwrap_or_default().to_string(),
+                        tagger: tag.tagger().map(|t| Identity {
+                            name: t.name().unwrap_or_default().to_string(),
+                            email: t.email().unwrap_or_default().to_string(),
+                        }),
+                        time: tag.tagger().map_or(commit_time, |t| timestamp(t.when())),
+                    },
+                    None => TagItem { name: name.to_string(), target: target.to_string(), time: commit_time, ..Default::default() },
+                };
+                tags.push(item);
+            }
+            tags.sort_by(|a, b| a.name.cmp(&b.name));
+            debug!("{} tag(s)", tags.len());
+            Ok(tags)
+        })
+    }
+
+    /// Annotated tag `name` on `target` (default HEAD). Signed tags are assembled by hand,
+    /// signed with [`sign::sign`](crate::sign::sign) and written straight to the odb, as
+    /// libgit2 has no tag signing of its own.
+    pub fn create_tag(&self, name: &str, target: Option<&str>, message: Option<&str>, sign: Option<&SigningKey>) -> Result<()> {
+        info!("creating tag '{name}' (annotated: {}, signed: {})", message.is_some(), sign.is_some());
+        let annotated = message.is_some() || sign.is_some();
+        let mut message = message.unwrap_or_default().trim_end().to_string();
+        if !message.is_empty() {
+            message.push('\n');
+        }
+        self.with_repo_mut(|repo| -> Result<()> {
+            let obj = repo.revparse_single(target.unwrap_or("HEAD"))?;
+            if !annotated {
+                repo.tag_lightweight(name, &obj, false)?;
+                return Ok(());
+            }
+            let tagger = repo.signature()?;
+            let Some(key) = sign else {
+                repo.tag(name, &obj, &tagger, &message, false)?;
+                return Ok(());
+            };
+
+            let full = format!("refs/tags/{name}");
+            if !g::Reference::is_valid_name(&full) {
+                return Err(g::Error::from_str(&format!("'{name}' is not a valid tag name")).into());
+            }
+            let mut key = key.clone();
+            if key.key.is_empty() && let Ok(k) = repo.config()?.get_string("user.signingkey") {
+                key.key = k;
+            }
+            let kind = obj.kind().map_or("commit", |k| k.str());
+            let when = tagger.when();
+            let off = when.offset_minutes();
+            let payload = format!(
+                "object {}\ntype {kind}\ntag {name}\ntagger {} <{}> {} {}{:02}{:02}\n\n{message}",
+                obj.id(),
+                tagger.name().unwrap_or_default(),
+                tagger.email().unwrap_or_default(),
+                when.seconds(),
+                if off < 0 { '-' } else { '+' },
+                off.abs() / 60,
+                off.abs() % 60,
+            );
+            let signature = crate::sign::sign(&payload, &key)?;
+            let oid = repo.odb()?.write(g::ObjectType::Tag, format!("{payload}{signature}").as_bytes())?;
+            repo.reference(&full, oid, false, &format!("tag: {name}"))?;
+            debug!("signed tag '{name}' → {oid}");
+            Ok(())
+        })
+    }
+
+    pub fn delete_tag(&self, name: &str) -> Result<()> {
+        info!("deleting tag '{name}'");
+        self.with_repo_mut(|repo| Ok(repo.tag_delete(name)?))
+    }
+
+    /// Split tag `name` into signed payload and signature and check it with `gpg` or
+    /// `ssh-keygen` (program and allowed signers from the repository config, as git does).
+    pub fn verify_tag(&self, name: &str) -> Result<TagVerification> {
+        self.with_repo(|repo| -> Result<TagVerification> {
+            let unsigned = TagVerification { tag: name.to_string(), ..Default::default() };
+            let reference = repo.find_reference(&format!("refs/tags/{name}"))?;
+            let Some(oid) = reference.target() else { return Ok(unsigned) };
+            let odb = repo.odb()?;
+            let obj = odb.read(oid)?;
+            if obj.kind() != g::ObjectType::Tag {
+                return Ok(unsigned);
+            }
+            let raw = String::from_utf8_lossy(obj.data()).into_owned();
+            let Some(format) = TagVerification::detect_format(&raw) else { return Ok(unsigned) };
+            let Some(at) = raw.rfind("\n-----BEGIN ") else { return Ok(unsigned) };
+            let (payload, signature) = raw.split_at(at + 1);
+
+            let cfg = repo.config()?;
+            let program = match format {
+                SignatureFormat::Gpg => cfg.get_string("gpg.openpgp.program").or_else(|_| cfg.get_string("gpg.program")),
+                SignatureFormat::Ssh => cfg.get_string("gpg.ssh.program"),
+            }
+            .ok();
+            let allowed = cfg.get_path("gpg.ssh.allowedSignersFile").ok();
+            let (ok, report) = crate::sign::verify(payload, signature, format, program.as_deref(), allowed.as_deref())?;
+            let v = TagVerification::from_verifier_output(name, format, ok, &report);
+            debug!("verify tag '{name}' → valid={}, signer={:?}", v.valid, v.signer);
+            Ok(v)
+        })
+    }
+
 
     pub fn ensure_remote(&self, name: &str, url: &str) -> Result<()> {
         info!("ensuring remote '{name}' points to '{url}'");
 
-        self.with_repo(|repo| {
+        self.with_repo_mut(|repo| {
             match repo.find_remote(name) {
                 Ok(r) => {
                     if r.url() != Some(url) {
@@ -341,7 +690,7 @@ impl Git {
         fo.download_tags(AutotagOption::All);
         debug!("fetch options prepared (download_tags=All)");
 
-        self.with_repo(|repo| {
+        self.with_repo_mut(|repo| {
             let mut r = repo.find_remote(remote).map_err(|e| {
                 error!("failed to find remote '{remote}': {e}");
                 e
@@ -371,14 +720,134 @@ impl Git {
         })
     }
 
-    pub fn fetch(&self, remote: &str, refspec: &str) -> Result<Option<Oid>> {
-        self.fetch_with_progress(remote, refspec, |_| {})
+    /// Fetch `remote` with its configured refspecs, optionally pruning stale tracking branches.
+    pub fn fetch_remote_with_progress<F>(&self, remote: &str, prune: bool, on: F) -> Result<()>
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        info!("fetching from remote '{remote}' (prune: {prune})");
+        let mut fo = FetchOptions::new();
+        fo.remote_callbacks(make_remote_callbacks_with_progress(on));
+        fo.download_tags(AutotagOption::Auto);
+        if prune {
+            fo.prune(g::FetchPrune::On);
+        }
+        self.with_repo_mut(|repo| {
+            let mut r = repo.find_remote(remote)?;
+            r.fetch::<&str>(&[], Some(&mut fo), None).map_err(|e| {
+                error!("fetch failed from '{remote}': {e}");
+                e
+            })?;
+            Ok(())
+        })
+    }
+
+    pub fn is_shallow(&self) -> Result<bool> {
+        self.with_repo(|repo| Ok(repo.is_shallow()))
+    }
+
+    /// Fetch the history cut off in a shallow clone; a no-op for a complete one.
+    pub fn unshallow_with_progress<F>(&self, remote: &str, on: F) -> Result<()>
+    where
+        F: Fn(String) + Send + Sync + 'static,
+    {
+        if !self.is_shallow()? {
+            debug!("unshallow: repository is complete");
+            return Ok(());
+        }
+        info!("unshallowing from remote '{remote}'");
+        let mut fo = FetchOptions::new();
+        fo.remote_callbacks(make_remote_callbacks_with_progress(on));
+        // GIT_FETCH_DEPTH_UNSHALLOW
+        fo.depth(i32::MAX);
+        self.with_repo_mut(|repo| {
+            let mut r = repo.find_remote(remote)?;
+            r.fetch::<&str>(&[], Some(&mut fo), None).map_err(|e| {
+                error!("unshallow failed from '{remote}': {e}");
+                e
+            })?;
+            Ok(())
+        })
+    }
+
+    /// Stash local modifications, run `f`, then re-apply them. When re-applying fails the
+    /// changes stay in the stash.
+    pub fn with_autostash<T>(&self, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let stashed = self.with_own_repo_mut(|repo| {
+            let sig = repo.signature().or_else(|_| g::Signature::now("OpenVCS", "openvcs@example"))?;
+            match repo.stash_save(&sig, "openvcs: autostash", None) {
+                Ok(oid) => {
+                    info!("stashed local changes ({oid})");
+                    Ok(true)
+                }
+                Err(e) if e.code() == g::ErrorCode::NotFound => Ok(false),
+                Err(e) => Err(e.into()),
+            }
+        })?;
+        // `f` takes the lock itself, so it isn't held in between
+        let res = f();
+        if !stashed {
+            return res;
+        }
+        // Fresh handle: the old one's index predates `f`
+        self.with_own_repo_mut(|repo| {
+            if let Err(e) = repo.stash_apply(0, None) {
+                warn!("re-applying the autostash failed: {e}");
+                let msg = format!("re-applying your local changes failed ({}); they are kept in the stash", e.message());
+                return res.and(Err(GitError::LibGit2(g::Error::from_str(&msg))));
+            }
+            let index = repo.index()?;
+            if index.has_conflicts() {
+                warn!("re-applying the autostash left conflicts; keeping the stash");
+                return res.and(Err(GitError::MergeConflict(conflicted_paths(&index)?)));
+            }
+            repo.stash_drop(0)?;
+            res
+        })
+    }
+
+    /// `(full_ref, oid)` of `remote`'s tracking branches and of all tags.
+    pub fn fetched_refs(&self, remote: &str) -> Result<Vec<(String, String)>> {
+        self.with_repo(|repo| {
+            let mut out = Vec::new();
+            for glob in [format!("refs/remotes/{remote}/*"), "refs/tags/*".to_string()] {
+                for r in repo.references_glob(&glob)? {
+                    let r = r?;
+                    if let (Some(name), Some(oid)) = (r.name(), r.target())
+                        && !name.ends_with("/HEAD")
+                    {
+                        out.push((name.to_string(), oid.to_string()));
+                    }
+                }
+            }
+            Ok(out)
+        })
+    }
+
+    /// Local branches that are both ahead of and behind their upstream:
+    /// `(branch, upstream, ahead, behind)`.
+    pub fn diverged_branches(&self) -> Result<Vec<(String, String, usize, usize)>> {
+        self.with_repo(|repo| {
+            let mut out = Vec::new();
+            for b in repo.branches(Some(BranchType::Local))? {
+                let (branch, _) = b?;
+                let Ok(up) = branch.upstream() else { continue };
+                let (Some(h), Some(u)) = (branch.get().target(), up.get().target()) else { continue };
+                let (ahead, behind) = repo.graph_ahead_behind(h, u)?;
+                if ahead > 0 && behind > 0 {
+                    let name = branch.name()?.unwrap_or("").to_string();
+                    let upstream = up.name()?.unwrap_or("").to_string();
+                    out.push((name, upstream, ahead, behind));
+                }
+            }
+            Ok(out)
+        })
     }
 
     pub fn fast_forward(&self, upstream: &str) -> Result<()> {
         info!("fetch + fast-forward to '{upstream}'");
 
-        self.with_repo(|repo| -> Result<()> {
+        self.with_repo_mut(|repo| -> Result<()> {
             // Parse "remote/branch"
             let (remote_name, remote_ref) = upstream
                 .split_once('/')
@@ -388,6 +857,11 @@ impl Git {
                 })?;
             debug!("remote='{remote_name}', ref='{remote_ref}'");
 
+            if repo.head_detached().unwrap_or(false) {
+                warn!("cannot fast-forward '{upstream}': HEAD is detached");
+                return Err(GitError::DetachedHead);
+            }
+
             // Fetch latest from remote
             let cb = make_remote_callbacks();
             let mut fo = git2::FetchOptions::new();
@@ -446,12 +920,25 @@ impl Git {
                     g::Error::from_str("no target")
                 })?;
 
+                let clobbered = clobbered_paths(repo, target)?;
+                if !clobbered.is_empty() {
+                    warn!("fast-forward to '{upstream}' would overwrite {} local change(s)", clobbered.len());
+                    return Err(GitError::DirtyWorktree(clobbered));
+                }
+
                 let mut reference = repo.find_reference(&head_name).map_err(|e| {
                     error!("find_reference('{head_name}') failed: {e}");
                     e
                 })?;
 
-                // Perform FF: move ref, set HEAD, update worktree
+                // Perform FF: update worktree and index first (safe: refuses to overwrite
+                // local changes), then move the ref and HEAD
+                let target_obj = repo.find_object(target, None)?;
+                repo.checkout_tree(&target_obj, Some(g::build::CheckoutBuilder::new().safe())).map_err(|e| {
+                    error!("checkout of {target} for FF failed: {e}");
+                    e
+                })?;
+
                 reference.set_target(target, "fast-forward").map_err(|e| {
                     error!("set_target('{head_name}', {target}) failed: {e}");
                     e
@@ -462,11 +949,6 @@ impl Git {
                     e
                 })?;
 
-                repo.checkout_head(None).map_err(|e| {
-                    error!("checkout_head after FF failed: {e}");
-                    e
-                })?;
-
                 info!("fast-forward to '{upstream}' completed");
                 Ok(())
             } else {
@@ -482,11 +964,12 @@ impl Git {
         name: &str,
         email: &str,
         paths: &[PathBuf],
+        opts: &CommitOptions,
     ) -> Result<g::Oid> {
         let msg_first = message.lines().next().unwrap_or("");
-        info!("committing (author='{} <{}>', summary='{}')", name, email, msg_first);
+        info!("committing (committer='{} <{}>', summary='{}')", name, email, msg_first);
 
-        self.with_repo(|repo| {
+        self.with_repo_mut(|repo| {
             let mut idx = repo.index().map_err(|e| {
                 error!("repo.index() failed: {e}");
                 e
@@ -543,9 +1026,10 @@ impl Git {
                 error!("Signature::now() failed for '{} <{}>': {e}", name, email);
                 e
             })?;
+            let author = author_signature(&sig, opts)?;
 
             // Parents: if HEAD is a branch, use its tip; otherwise initial commit.
-            let parents = match repo.head() {
+            let mut parents = match repo.head() {
                 Ok(h) if h.is_branch() => {
                     let c = repo.head()
                         .and_then(|h| h.peel_to_commit())
@@ -557,12 +1041,21 @@ impl Git {
                 }
                 _ => Vec::new(),
             };
+            // Concluding a merge: its commit may keep HEAD's tree
+            let merged = merge_heads(repo)?;
+            let concluding = !merged.is_empty();
+            parents.extend(merged);
+            if !concluding && parents.first().is_some_and(|p| p.tree_id() == tree_oid) {
+                warn!("tree unchanged from HEAD — nothing to commit");
+                return Err(GitError::NothingToCommit);
+            }
             let parent_refs: Vec<&g::Commit> = parents.iter().collect();
 
-            // Target ref when not an initial commit.
+            // Target ref when not an initial commit; an initial commit on an unborn branch
+            // goes through HEAD so the branch gets created.
             let head_ref = if parent_refs.is_empty() {
                 debug!("initial commit (no parents)");
-                None
+                repo.head().err().filter(|e| e.code() == g::ErrorCode::UnbornBranch).map(|_| "HEAD".to_string())
             } else {
                 repo.head()
                     .ok()
@@ -571,7 +1064,7 @@ impl Git {
 
             let oid = repo.commit(
                 head_ref.as_deref(),
-                &sig, &sig,
+                &author, &sig,
                 message,
                 &tree,
                 &parent_refs,
@@ -580,6 +1073,9 @@ impl Git {
                 e
             })?;
 
+            if concluding {
+                repo.cleanup_state()?;
+            }
             info!("commit created {}", oid);
             Ok(oid)
         })
@@ -591,8 +1087,9 @@ impl Git {
         message: &str,
         name: &str,
         email: &str,
+        opts: &CommitOptions,
     ) -> Result<g::Oid> {
-        self.with_repo(|repo| {
+        self.with_repo_mut(|repo| {
             let mut idx = repo.index()?;
             if idx.is_empty() {
                 return Err(GitError::NothingToCommit);
@@ -602,53 +1099,87 @@ impl Git {
             let tree = repo.find_tree(tree_oid)?;
 
             let sig = g::Signature::now(name, email)?;
+            let author = author_signature(&sig, opts)?;
 
-            let parents = match repo.head() {
+            let mut parents = match repo.head() {
                 Ok(h) if h.is_branch() => {
                     vec![repo.head()?.peel_to_commit()?]
                 }
                 _ => Vec::new(),
             };
+            let merged = merge_heads(repo)?;
+            let concluding = !merged.is_empty();
+            parents.extend(merged);
+            if !concluding && parents.first().is_some_and(|p| p.tree_id() == tree_oid) {
+                warn!("tree unchanged from HEAD — nothing to commit");
+                return Err(GitError::NothingToCommit);
+            }
             let parent_refs: Vec<&g::Commit> = parents.iter().collect();
 
             let head_ref = if parent_refs.is_empty() {
-                None
+                repo.head().err().filter(|e| e.code() == g::ErrorCode::UnbornBranch).map(|_| "HEAD".to_string())
             } else {
                 Some(repo.head()?.name().ok_or_else(|| g::Error::from_str("HEAD name missing"))?.to_string())
             };
 
-            let target_ref = if let Some(name) = head_ref { Some(name) } else { None };
+            let target_ref = head_ref;
             let oid = match &target_ref {
-                Some(name) => repo.commit(Some(name), &sig, &sig, message, &tree, &parent_refs)?,
-                None => repo.commit(None, &sig, &sig, message, &tree, &[])?
+                Some(name) => repo.commit(Some(name), &author, &sig, message, &tree, &parent_refs)?,
+                None => repo.commit(None, &author, &sig, message, &tree, &[])?
             };
+            if concluding {
+                repo.cleanup_state()?;
+            }
             Ok(oid)
         })
     }
 
-    pub fn push_refspec_with_progress<F>(&self, remote: &str, refspec: &str, on: F) -> Result<()>
+    pub fn push_refspecs_with_progress<F>(&self, remote: &str, refspecs: &[&str], options: &[String], on: F) -> Result<()>
     where
         F: Fn(String) + Send + Sync + 'static,
     {
-        info!("pushing '{refspec}' to remote '{remote}'");
-
+        info!("pushing {refspecs:?} to remote '{remote}'");
+
+        // libgit2 reports per-ref rejections via callback but still returns Ok; collect them.
+        let rejected: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let on = {
+            let rejected = Arc::clone(&rejected);
+            move |s: String| {
+                if let Some((refname, reason)) = s.strip_prefix("push status: ").and_then(|r| r.split_once(" → "))
+                    && let Ok(mut v) = rejected.lock()
+                {
+                    v.push(format!("{refname}: {reason}"));
+                }
+                on(s)
+            }
+        };
         let cb = make_remote_callbacks_with_progress(on);
         let mut opts = PushOptions::new();
         opts.remote_callbacks(cb);
-        debug!("push options prepared (callbacks attached)");
+        if !options.is_empty() {
+            let options: Vec<&str> = options.iter().map(String::as_str).collect();
+            opts.remote_push_options(&options);
+        }
+        debug!("push options prepared (callbacks attached, {} push option(s))", options.len());
 
-        self.with_repo(|repo| {
+        self.with_repo_mut(|repo| {
             let mut r = repo.find_remote(remote).map_err(|e| {
                 error!("find_remote('{remote}') failed: {e}");
                 e
             })?;
 
-            info!("starting push to '{remote}' with refspec '{refspec}'");
-            r.push(&[refspec], Some(&mut opts)).map_err(|e| {
-                error!("push to '{remote}' with '{refspec}' failed: {e}");
+            info!("starting push to '{remote}' with refspecs {refspecs:?}");
+            r.push(refspecs, Some(&mut opts)).map_err(|e| {
+                error!("push to '{remote}' with {refspecs:?} failed: {e}");
                 e
             })?;
 
+            let rejected = rejected.lock().map(|v| v.join("; ")).unwrap_or_default();
+            if !rejected.is_empty() {
+                warn!("push to '{remote}' rejected: {rejected}");
+                return Err(GitError::RemoteRejected(rejected));
+            }
+
             info!("push to '{remote}' completed");
             Ok(())
         })
@@ -695,7 +1226,7 @@ impl Git {
     pub fn hard_reset_head(&self) -> Result<()> {
         info!("resetting working tree to HEAD…");
 
-        self.with_repo(|repo| {
+        self.with_repo_mut(|repo| {
             let head = repo.head()?.peel_to_commit()?;
             debug!("HEAD commit = {}", head.id());
             repo.reset(head.as_object(), ResetType::Hard, None)?;
@@ -704,127 +1235,673 @@ impl Git {
         })
     }
 
-    /// Return a single page of commits based on the provided query.
-    pub fn log_commits(&self, q: &LogQuery) -> Result<Vec<CommitItem>> {
-        debug!(
-            "log_commits: rev={:?} path={:?} author~={:?} since={:?} until={:?} skip={} limit={} topo={} merges={}",
-            q.rev, q.path, q.author_contains, q.since_utc, q.until_utc, q.skip, q.limit, q.topo_order, q.include_merges
-        );
-
-        self.with_repo(|repo| -> Result<Vec<CommitItem>> {
-            let mut walk = repo.revwalk()?;
-            let sort = if q.topo_order { g::Sort::TOPOLOGICAL | g::Sort::TIME } else { g::Sort::TIME };
-            let _ = walk.set_sorting(sort);
-
-            let rev = q.rev.as_deref().unwrap_or("HEAD");
-            walk.push_ref(rev)?;
-
-            // Pre-parse filters once
-            let path_filter = q.path.as_deref();
-            let auth_sub = q.author_contains.as_ref().map(|s| s.to_lowercase());
-            let since = q.since_utc.as_deref().and_then(parse_iso_to_epoch_secs);
-            let until = q.until_utc.as_deref().and_then(parse_iso_to_epoch_secs);
-
-            let mut out = Vec::with_capacity(q.limit as usize);
-            let mut matched = 0u32;
-
-            for oid_res in walk {
-                let oid = oid_res?;
-                let commit = repo.find_commit(oid)?;
-
-                // merges?
-                if !q.include_merges && commit.parent_count() > 1 {
-                    continue;
-                }
+    /// `git reset --keep target`: a safe checkout of `target` (which refuses to overwrite a
+    /// local change in a file it has to update), then a mixed reset to move HEAD and the
+    /// index.
+    pub fn reset_keep(&self, target: &str) -> Result<()> {
+        self.with_repo_mut(|repo| {
+            let commit = repo.revparse_single(target)?.peel(g::ObjectType::Commit)?;
+            repo.checkout_tree(&commit, Some(g::build::CheckoutBuilder::new().safe()))?;
+            repo.reset(&commit, ResetType::Mixed, None)?;
+            info!("reset --keep to {}", commit.id());
+            Ok(())
+        })
+    }
 
-                // date filters (git time is seconds + offset)
-                let t = commit.time();
-                let secs = t.seconds();
-                if let Some(s) = since {
-                    if secs < s { continue; }
-                }
-                if let Some(u) = until {
-                    if secs > u { continue; }
-                }
+    /// Snapshot of the working tree at `refname` (see `Vcs::snapshot_worktree`). The tree is
+    /// HEAD's with every changed or untracked path replaced by the file on disk, built in
+    /// memory so the index is never written.
+    pub fn snapshot_worktree(&self, refname: &str, message: &str) -> Result<Option<String>> {
+        self.with_repo_mut(|repo| -> Result<Option<String>> {
+            let prev = repo.find_reference(refname).ok().and_then(|r| r.peel_to_commit().ok());
+            let head = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let base = prev.as_ref().or(head.as_ref());
+            let head_tree = match &head {
+                Some(c) => c.tree()?,
+                None => repo.find_tree(repo.treebuilder(None)?.write()?)?,
+            };
 
-                // author filter (substring on "Name <email>")
-                if let Some(sub) = &auth_sub {
-                    let who = {
-                        let a = commit.author();
-                        format!("{} <{}>", a.name().unwrap_or(""), a.email().unwrap_or(""))
-                    };
-                    if !who.to_lowercase().contains(sub) {
-                        continue;
+            let mut sopts = StatusOptions::new();
+            sopts.include_untracked(true).recurse_untracked_dirs(true).include_ignored(false);
+            let statuses = repo.statuses(Some(&mut sopts))?;
+            let mut update = g::build::TreeUpdateBuilder::new();
+            for e in statuses.iter() {
+                let Some(rel) = e.path() else { continue };
+                let full = self.workdir.join(rel);
+                match std::fs::symlink_metadata(&full) {
+                    Ok(md) if md.file_type().is_symlink() => {
+                        let target = std::fs::read_link(&full)?;
+                        let blob = repo.blob(target.to_string_lossy().as_bytes())?;
+                        update.upsert(rel, blob, g::FileMode::Link);
                     }
-                }
-
-                // path filter (touches prefix)
-                if let Some(prefix) = path_filter {
-                    if !commit_touches_path(repo, oid, prefix)? {
-                        continue;
+                    Ok(md) if md.is_file() => {
+                        let blob = repo.blob_path(&full)?;
+                        update.upsert(rel, blob, if is_executable(&md) { g::FileMode::BlobExecutable } else { g::FileMode::Blob });
                     }
+                    Ok(_) => {} // directories (submodules) are left as in HEAD
+                    Err(_) => { update.remove(rel); }
                 }
+            }
+            let tree = update.create_updated(repo, &head_tree)?;
 
-                // pagination (skip first N matches after filters)
-                if matched < q.skip {
-                    matched += 1;
-                    continue;
-                }
-
-                // DTO
-                let id_full = oid.to_string();
-                let short = &id_full[..id_full.len().min(7)];
-                let when = git_time_to_rfc3339(t);
-                let author = {
-                    let a = commit.author();
-                    format!("{} <{}>", a.name().unwrap_or(""), a.email().unwrap_or(""))
-                };
-                let msg = commit.summary().unwrap_or("").to_string();
-                let meta = format!("{when} • {short}");
+            if base.is_some_and(|b| b.tree_id() == tree) || (base.is_none() && statuses.is_empty()) {
+                trace!("worktree unchanged, no snapshot");
+                return Ok(None);
+            }
+            let sig = repo.signature().or_else(|_| g::Signature::now("OpenVCS", "openvcs@example"))?;
+            let tree = repo.find_tree(tree)?;
+            let parents: Vec<&g::Commit> = base.into_iter().collect();
+            let oid = repo.commit(None, &sig, &sig, message, &tree, &parents)?;
+            match &prev {
+                Some(p) => repo.reference_matching(refname, oid, true, p.id(), "openvcs: wip snapshot")?,
+                None => repo.reference(refname, oid, false, "openvcs: wip snapshot")?,
+            };
+            debug!("snapshot {oid} → {refname}");
+            Ok(Some(oid.to_string()))
+        })
+    }
 
-                out.push(CommitItem { id: id_full, msg, meta, author });
+    /// Remote-tracking branches containing `commit` (see `Vcs::is_pushed`).
+    pub fn is_pushed(&self, commit: &str) -> Result<Vec<String>> {
+        self.with_repo(|repo| {
+            let id = repo.revparse_single(commit)?.peel_to_commit()?.id();
+            remotes_containing(repo, id)
+        })
+    }
 
-                if out.len() as u32 >= q.limit {
-                    break;
+    /// Stash local modifications (see `Vcs::stash_save`); `None` when there were none.
+    pub fn stash_save(&self, opts: &StashOptions) -> Result<Option<StashEntry>> {
+        let mut flags = g::StashFlags::DEFAULT;
+        flags.set(g::StashFlags::INCLUDE_UNTRACKED, opts.include_untracked);
+        flags.set(g::StashFlags::KEEP_INDEX, opts.keep_index);
+        let message = opts.message.as_deref().map(str::trim).filter(|m| !m.is_empty());
+        let stashed = self.with_own_repo_mut(|repo| {
+            let sig = repo.signature().or_else(|_| g::Signature::now("OpenVCS", "openvcs@example"))?;
+            match repo.stash_save2(&sig, message, Some(flags)) {
+                Ok(oid) => {
+                    info!("stashed local changes ({oid})");
+                    Ok(true)
                 }
+                Err(e) if e.code() == g::ErrorCode::NotFound => Ok(false),
+                Err(e) => Err(e.into()),
             }
+        })?;
+        if !stashed {
+            return Ok(None);
+        }
+        Ok(self.stash_list()?.into_iter().next())
+    }
 
-            debug!("log_commits: returned {} item(s)", out.len());
-            Ok(out)
+    pub fn stash_list(&self) -> Result<Vec<StashEntry>> {
+        // stash_foreach needs a mutable handle
+        let mut repo = Repository::open(&self.repo.git_dir)?;
+        let mut found = Vec::new();
+        repo.stash_foreach(|index, message, oid| {
+            found.push((index, message.to_string(), *oid));
+            true
+        })?;
+        found
+            .into_iter()
+            .map(|(index, message, oid)| {
+                let time = timestamp(repo.find_commit(oid)?.committer().when());
+                let branch = StashEntry::branch_of(&message);
+                Ok(StashEntry { index, id: oid.to_string(), message, branch, time })
+            })
+            .collect()
+    }
+
+    /// Changes of stash `index` against its base, then its untracked files (third parent).
+    pub fn stash_diff(&self, index: usize) -> Result<Vec<String>> {
+        self.with_repo(|repo| {
+            let reflog = repo.reflog("refs/stash")?;
+            let entry = reflog
+                .get(index)
+                .ok_or_else(|| GitError::LibGit2(g::Error::from_str(&format!("no stash entry {index}"))))?;
+            let stash = repo.find_commit(entry.id_new())?;
+            let mut opts = g::DiffOptions::new();
+            opts.context_lines(3);
+            let base = stash.parent(0)?.tree()?;
+            let mut lines = collect_patch_lines(&repo.diff_tree_to_tree(Some(&base), Some(&stash.tree()?), Some(&mut opts))?)?;
+            if let Ok(untracked) = stash.parent(2) {
+                let diff = repo.diff_tree_to_tree(None, Some(&untracked.tree()?), Some(&mut opts))?;
+                lines.extend(collect_patch_lines(&diff)?);
+            }
+            Ok(lines)
         })
     }
 
-    pub fn status_payload(&self) -> Result<StatusPayload> {
-        self.with_repo(|repo| -> Result<StatusPayload> {
-            // Gather statuses
-            let mut sopts = g::StatusOptions::new();
-            sopts.include_untracked(true)
-                .recurse_untracked_dirs(true)
+    pub fn stash_apply(&self, index: usize, restore_index: bool) -> Result<()> {
+        let mut opts = g::StashApplyOptions::new();
+        if restore_index {
+            opts.reinstantiate_index();
+        }
+        self.with_own_repo_mut(|repo| {
+            repo.stash_apply(index, Some(&mut opts))?;
+            let index = repo.index()?;
+            if index.has_conflicts() {
+                return Err(GitError::MergeConflict(conflicted_paths(&index)?));
+            }
+            Ok(())
+        })
+    }
+
+    /// Apply stash `index`, then drop it unless applying left conflicts.
+    pub fn stash_pop(&self, index: usize, restore_index: bool) -> Result<()> {
+        self.stash_apply(index, restore_index)?;
+        self.stash_drop(index)
+    }
+
+    pub fn stash_drop(&self, index: usize) -> Result<()> {
+        self.with_own_repo_mut(|repo| Ok(repo.stash_drop(index)?))
+    }
+
+    /// Restore `paths` from stash `index`: tracked ones from the stash commit, untracked
+    /// ones from its third parent.
+    pub fn stash_restore_paths(&self, index: usize, paths: &[PathBuf]) -> Result<()> {
+        let stash = format!("stash@{{{index}}}");
+        let untracked_rev = format!("{stash}^3");
+        let rels = paths.iter().map(|p| rel_to_workdir(&self.workdir, p)).collect::<Result<Vec<_>>>()?;
+        let (tracked, untracked) = self.with_repo_mut(|repo| -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+            let commit = repo.revparse_single(&stash)?.peel_to_commit()?;
+            let tree = commit.tree()?;
+            let untracked_tree = commit.parent(2).ok().map(|c| c.tree()).transpose()?;
+            let (mut tracked, mut untracked) = (Vec::new(), Vec::new());
+            for rel in rels {
+                if tree.get_path(&rel).is_ok() {
+                    tracked.push(rel);
+                } else if untracked_tree.as_ref().is_some_and(|t| t.get_path(&rel).is_ok()) {
+                    untracked.push(rel);
+                } else {
+                    let msg = format!("{} is not in {stash}", rel.display());
+                    return Err(GitError::LibGit2(g::Error::from_str(&msg)));
+                }
+            }
+            Ok((tracked, untracked))
+        })?;
+        if !tracked.is_empty() {
+            self.restore_worktree(&stash, &tracked)?;
+        }
+        if !untracked.is_empty() {
+            self.restore_worktree(&untracked_rev, &untracked)?;
+        }
+        Ok(())
+    }
+
+    pub fn merge_branch(&self, name: &str, opts: &MergeOptions) -> Result<MergeOutcome> {
+        self.with_repo_mut(|repo| {
+            let missing = |_| GitError::NoSuchBranch(name.to_string());
+            let (object, reference) = repo.revparse_ext(name).map_err(missing)?;
+            let theirs = object.peel_to_commit().map_err(missing)?;
+            let annotated = repo.find_annotated_commit(theirs.id())?;
+            let (analysis, _) = repo.merge_analysis(&[&annotated])?;
+            if analysis.is_up_to_date() {
+                info!("already up to date with '{name}'");
+                return Ok(MergeOutcome::UpToDate);
+            }
+            // The branch HEAD points at, unless detached
+            let branch = repo.find_reference("HEAD")?.symbolic_target().map(str::to_string);
+            let commit = theirs.id().to_string();
+
+            if analysis.is_unborn() || (analysis.is_fast_forward() && opts.fast_forward != FastForward::Never) {
+                if !analysis.is_unborn() {
+                    let clobbered = clobbered_paths(repo, theirs.id())?;
+                    if !clobbered.is_empty() {
+                        warn!("fast-forward to '{name}' would overwrite {} local change(s)", clobbered.len());
+                        return Err(GitError::DirtyWorktree(clobbered));
+                    }
+                }
+                repo.checkout_tree(theirs.as_object(), Some(g::build::CheckoutBuilder::new().safe()))?;
+                let log = format!("merge {name}: Fast-forward");
+                match &branch {
+                    Some(b) => drop(repo.reference(b, theirs.id(), true, &log)?),
+                    None => repo.set_head_detached(theirs.id())?,
+                }
+                info!("fast-forwarded to '{name}' ({commit})");
+                return Ok(MergeOutcome::FastForward { commit });
+            }
+            if opts.fast_forward == FastForward::Only {
+                warn!("cannot fast-forward to '{name}'");
+                return Err(GitError::NonFastForward);
+            }
+
+            let message = opts.message.clone().unwrap_or_else(|| {
+                let merged = match &reference {
+                    Some(r) if r.is_branch() => format!("branch '{}'", r.shorthand().unwrap_or(name)),
+                    Some(r) if r.is_remote() => format!("remote-tracking branch '{}'", r.shorthand().unwrap_or(name)),
+                    Some(r) if r.is_tag() => format!("tag '{}'", r.shorthand().unwrap_or(name)),
+                    _ => format!("commit '{name}'"),
+                };
+                // As merge.suppressDest's default: no destination when merging into main/master
+                match branch.as_deref().and_then(|b| b.strip_prefix("refs/heads/")) {
+                    Some(b) if b != "main" && b != "master" => format!("Merge {merged} into {b}"),
+                    _ => format!("Merge {merged}"),
+                }
+            });
+            // libgit2 refuses to overwrite local changes too, but without naming them
+            let head = repo.head()?.peel_to_commit()?;
+            let mut preview = repo.merge_commits(&head, &theirs, None)?;
+            if !preview.has_conflicts() {
+                let clobbered = clobbered_by_tree(repo, &repo.find_tree(preview.write_tree_to(repo)?)?)?;
+                if !clobbered.is_empty() {
+                    warn!("merging '{name}' would overwrite {} local change(s)", clobbered.len());
+                    return Err(GitError::DirtyWorktree(clobbered));
+                }
+            }
+            info!("merging '{name}' ({commit})");
+            repo.merge(&[&annotated], None, Some(g::build::CheckoutBuilder::new().safe()))?;
+            let mut index = repo.index()?;
+            if index.has_conflicts() {
+                let files = conflicted_paths(&index)?;
+                info!("merge of '{name}' stopped on {} conflict(s)", files.len());
+                return Ok(MergeOutcome::Conflicted { files });
+            }
+            let tree = repo.find_tree(index.write_tree()?)?;
+            let sig = repo.signature()?;
+            let oid = repo.commit(Some("HEAD"), &sig, &sig, &message, &tree, &[&head, &theirs])?;
+            repo.cleanup_state()?;
+            info!("merged '{name}' as {oid}");
+            Ok(MergeOutcome::Merged { commit: oid.to_string() })
+        })
+    }
+
+    /// Apply `id` on HEAD (see `Vcs::pick_commit`); conflicts are left in the worktree and
+    /// index for `commit_picked`.
+    pub fn pick_commit(&self, id: &str, kind: PickKind) -> Result<String> {
+        let full = self.with_repo_mut(|repo| -> Result<String> {
+            let commit = repo.revparse_single(id)?.peel_to_commit()?;
+            let full = commit.id().to_string();
+            if commit.parent_count() > 1 {
+                return Err(GitError::Rewrite(format!("{} is a merge commit", &full[..7])));
+            }
+            info!("{kind:?} {full}");
+            match kind {
+                PickKind::CherryPick => repo.cherrypick(&commit, None)?,
+                PickKind::Revert => repo.revert(&commit, None)?,
+            }
+            let index = repo.index()?;
+            if index.has_conflicts() {
+                let files = conflicted_paths(&index)?;
+                info!("{kind:?} {full} stopped on {} conflict(s)", files.len());
+                return Err(GitError::MergeConflict(files));
+            }
+            Ok(full)
+        })?;
+        self.commit_picked(&full, kind)
+    }
+
+    /// Commit the index as the result of picking `id`, then clear the cherry-pick/revert
+    /// state `pick_commit` left.
+    pub fn commit_picked(&self, id: &str, kind: PickKind) -> Result<String> {
+        self.with_repo_mut(|repo| {
+            let original = repo.revparse_single(id)?.peel_to_commit()?;
+            let mut index = repo.index()?;
+            index.read(false)?;
+            if index.has_conflicts() {
+                return Err(GitError::MergeConflict(conflicted_paths(&index)?));
+            }
+            let head = repo.head()?.peel_to_commit()?;
+            let tree = repo.find_tree(index.write_tree()?)?;
+            if tree.id() == head.tree_id() {
+                repo.cleanup_state()?;
+                return Err(GitError::NothingToCommit);
+            }
+            let committer = repo.signature()?;
+            let author = match kind {
+                PickKind::CherryPick => original.author().to_owned(),
+                PickKind::Revert => committer.to_owned(),
+            };
+            let message = kind.message(&original.id().to_string(), &String::from_utf8_lossy(original.message_raw_bytes()));
+            let oid = repo.commit(Some("HEAD"), &author, &committer, &message, &tree, &[&head])?;
+            repo.cleanup_state()?;
+            debug!("{kind:?} {} committed as {oid}", original.id());
+            Ok(oid.to_string())
+        })
+    }
+
+    /// Squash `ids` into one commit (see `Vcs::squash_commits`), recreating later commits
+    /// on top with their own trees.
+    pub fn squash_commits(&self, ids: &[String], message: &str, allow_pushed: bool) -> Result<String> {
+        if message.trim().is_empty() {
+            return Err(GitError::Rewrite("the commit message is empty".into()));
+        }
+        self.with_repo_mut(|repo| -> Result<String> {
+            let head_ref = repo.head()?;
+            if !head_ref.is_branch() {
+                return Err(GitError::DetachedHead);
+            }
+            let branch = head_ref.name().unwrap_or("HEAD").to_string();
+            let head = head_ref.peel_to_commit()?.id();
+            let full = ids
+                .iter()
+                .map(|id| {
+                    let obj = repo.revparse_single(id).map_err(|_| GitError::Rewrite(format!("unknown commit {id}")))?;
+                    Ok(obj.peel_to_commit()?.id().to_string())
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            // First-parent history of HEAD (unpushed part only, unless allowed), newest
+            // first, up to the last selected commit
+            let mut walk = repo.revwalk()?;
+            walk.push(head)?;
+            if !allow_pushed {
+                walk.hide_glob("refs/remotes/*")?;
+            }
+            walk.simplify_first_parent()?;
+            let mut chain = Vec::new();
+            let mut pending: std::collections::HashSet<&str> = full.iter().map(String::as_str).collect();
+            for oid in walk {
+                let c = repo.find_commit(oid?)?;
+                let id = c.id().to_string();
+                pending.remove(id.as_str());
+                chain.push(ChainCommit { id, parents: c.parent_ids().map(|p| p.to_string()).collect() });
+                if pending.is_empty() {
+                    break;
+                }
+            }
+            let plan = match rewrite::plan_squash(&chain, &full) {
+                Ok(plan) => plan,
+                Err(PlanError::Invalid(msg)) => return Err(GitError::Rewrite(msg)),
+                Err(PlanError::Missing(id)) => {
+                    let remotes = remotes_containing(repo, Oid::from_str(&id)?)?;
+                    return Err(if remotes.is_empty() {
+                        GitError::Rewrite(format!("{} is not on the current branch", &id[..id.len().min(7)]))
+                    } else {
+                        GitError::AlreadyPushed { commit: id, remotes }
+                    });
+                }
+            };
+
+            let find = |id: &str| -> Result<g::Commit<'_>> { Ok(repo.find_commit(Oid::from_str(id)?)?) };
+            let committer = repo.signature()?;
+            let oldest = find(&plan.squashed[0])?;
+            let newest = find(&plan.squashed[plan.squashed.len() - 1])?;
+            let base = find(&plan.base)?;
+            let mut tip = repo.commit(None, &oldest.author(), &committer, message, &newest.tree()?, &[&base])?;
+            for c in &plan.replay {
+                let orig = find(&c.id)?;
+                let mut parents = vec![repo.find_commit(tip)?];
+                for p in orig.parent_ids().skip(1) {
+                    parents.push(repo.find_commit(p)?);
+                }
+                let parents: Vec<&g::Commit> = parents.iter().collect();
+                let msg = String::from_utf8_lossy(orig.message_raw_bytes());
+                tip = repo.commit(None, &orig.author(), &committer, &msg, &orig.tree()?, &parents)?;
+            }
+            let reflog = format!("openvcs: squash {} commits", plan.squashed.len());
+            repo.reference_matching(&branch, tip, true, head, &reflog)?;
+            info!("squashed {} commits into {tip}", plan.squashed.len());
+            Ok(tip.to_string())
+        })
+    }
+
+    /// Check out `paths` (all when empty) of commit `source` into the working tree only.
+    pub fn restore_worktree(&self, source: &str, paths: &[PathBuf]) -> Result<()> {
+        let rels = paths.iter().map(|p| rel_to_workdir(&self.workdir, p)).collect::<Result<Vec<_>>>()?;
+        self.with_repo_mut(|repo| {
+            let commit = repo.revparse_single(source)?.peel_to_commit()?;
+            let mut co = g::build::CheckoutBuilder::new();
+            co.force().update_index(false);
+            for p in &rels {
+                co.path(p);
+            }
+            repo.checkout_tree(commit.as_object(), Some(&mut co))?;
+            info!("restored {} path(s) from {}", rels.len(), commit.id());
+            Ok(())
+        })
+    }
+
+    /// Return a single page of commits based on the provided query.
+    pub fn log_commits(&self, q: &LogQuery) -> Result<Vec<CommitItem>> {
+        debug!(
+            "log_commits: rev={:?} paths={:?} author~={:?} since={:?} until={:?} skip={} limit={} topo={} merges={}",
+            q.rev, q.pathspecs(), q.author_contains, q.since_utc, q.until_utc, q.skip, q.limit, q.topo_order, q.include_merges
+        );
+
+        self.with_repo(|repo| -> Result<Vec<CommitItem>> {
+            let walk = start_revwalk(repo, q)?;
+
+            // Pre-parse filters once
+            let filter = CommitFilter::new(q)?;
+
+            let mut out = Vec::with_capacity(q.limit as usize);
+            let mut matched = 0u32;
+
+            for oid_res in walk {
+                let oid = oid_res?;
+                let commit = repo.find_commit(oid)?;
+                if !filter.accepts(repo, &commit)? {
+                    continue;
+                }
+
+                // pagination (skip first N matches after filters)
+                if matched < q.skip {
+                    matched += 1;
+                    continue;
+                }
+
+                out.push(commit_item(&commit));
+
+                if out.len() as u32 >= q.limit {
+                    break;
+                }
+            }
+
+            debug!("log_commits: returned {} item(s)", out.len());
+            Ok(out)
+        })
+    }
+
+    pub fn commit_records(&self, ids: &[String]) -> Result<Vec<CommitRecord>> {
+        self.with_repo(|repo| {
+            ids.iter()
+                .map(|id| Ok(commit_record(&repo.find_commit(Oid::from_str(id)?)?)))
+                .collect()
+        })
+    }
+
+    /// Non-merge commits that changed `path` (newest first) with its blob at each. Renames
+    /// are followed like `git log --follow`: where a commit adds the path, a rename-detecting
+    /// diff against its parent tells which path to follow further back.
+    pub fn file_revisions(&self, path: &Path, limit: usize) -> Result<Vec<FileRevision>> {
+        let mut current = rel_to_workdir(&self.workdir, path)?.to_string_lossy().replace('\\', "/");
+        self.with_repo(|repo| -> Result<Vec<FileRevision>> {
+            let mut walk = repo.revwalk()?;
+            // children before parents, so a rename is seen after the later revisions
+            walk.set_sorting(g::Sort::TOPOLOGICAL | g::Sort::TIME)?;
+            walk.push_head()?;
+
+            let mut out = Vec::new();
+            for oid in walk {
+                let commit = repo.find_commit(oid?)?;
+                if commit.parent_count() > 1 {
+                    continue;
+                }
+                let tree = commit.tree()?;
+                let parent_tree = if commit.parent_count() == 1 { Some(commit.parent(0)?.tree()?) } else { None };
+                let blob = blob_at(&tree, &current);
+                let before = parent_tree.as_ref().and_then(|t| blob_at(t, &current));
+                if blob == before {
+                    continue;
+                }
+                let Some(blob) = blob else { continue }; // deleted here
+
+                let a = commit.author();
+                out.push(FileRevision {
+                    commit: commit.id().to_string(),
+                    blob: blob.to_string(),
+                    path: current.clone(),
+                    author: a.name().unwrap_or("").to_string(),
+                    date: timestamp(a.when()).to_rfc3339(),
+                    time: timestamp(a.when()),
+                    summary: commit.summary().unwrap_or("").to_string(),
+                });
+                if limit > 0 && out.len() >= limit {
+                    break;
+                }
+
+                if before.is_none() && let Some(pt) = &parent_tree {
+                    let mut diff = repo.diff_tree_to_tree(Some(pt), Some(&tree), None)?;
+                    diff.find_similar(Some(g::DiffFindOptions::new().renames(true)))?;
+                    let renamed_from = diff.deltas().find_map(|d| {
+                        let to = d.new_file().path()?.to_string_lossy();
+                        (d.status() == g::Delta::Renamed && to == current)
+                            .then(|| d.old_file().path().map(|p| p.to_string_lossy().into_owned()))
+                            .flatten()
+                    });
+                    if let Some(from) = renamed_from {
+                        trace!("file_revisions: {current} was renamed from {from} in {}", commit.id());
+                        current = from;
+                    }
+                }
+            }
+            debug!("file_revisions: {} revision(s)", out.len());
+            Ok(out)
+        })
+    }
+
+    pub fn read_blob(&self, oid: &str) -> Result<Vec<u8>> {
+        self.with_repo(|repo| Ok(repo.find_blob(Oid::from_str(oid)?)?.content().to_vec()))
+    }
+
+    /// Incremental walk for `q` on a dedicated repository handle. The commit ids are
+    /// collected up front (cheap); commits are loaded and filtered batch by batch.
+    pub fn history_walk(&self, q: &LogQuery) -> Result<LogWalk> {
+        let repo = Repository::open(&self.repo.git_dir)?;
+        let oids = start_revwalk(&repo, q)?.collect::<std::result::Result<Vec<Oid>, g::Error>>()?;
+        debug!("history_walk: {} commit id(s) queued", oids.len());
+        Ok(LogWalk { repo, oids: oids.into_iter(), query: q.clone() })
+    }
+
+    pub fn status_payload(&self, opts: &openvcs_core::models::StatusOptions) -> Result<StatusPayload> {
+        let mut files = Vec::<FileEntry>::new();
+        let (_, ahead, behind) = self.status_stream(opts, usize::MAX, &mut |batch| files.extend(batch))?;
+        Ok(StatusPayload { files, ahead, behind, ..Default::default() })
+    }
+
+    /// Remotes named by `remote.<name>.promisor` or `extensions.partialClone`.
+    pub fn promisor_remotes(&self) -> Result<Vec<String>> {
+        self.with_repo(|repo| {
+            let cfg = repo.config()?;
+            let mut remotes: Vec<String> = Vec::new();
+            let mut entries = cfg.entries(Some(r"^(remote\..*\.promisor|extensions\.partialclone)$"))?;
+            while let Some(entry) = entries.next() {
+                let entry = entry?;
+                let (Some(key), Some(value)) = (entry.name(), entry.value()) else { continue };
+                let remote = match key.strip_prefix("remote.").and_then(|k| k.strip_suffix(".promisor")) {
+                    Some(name) if value.trim() == "true" => name,
+                    Some(_) => continue,
+                    None => value.trim(),
+                };
+                if !remote.is_empty() && !remotes.iter().any(|r| r == remote) {
+                    remotes.push(remote.to_string());
+                }
+            }
+            Ok(remotes)
+        })
+    }
+
+    /// Paths changed by `rev` whose old or new blob is not in the object database.
+    pub fn missing_blobs(&self, rev: &str) -> Result<Vec<String>> {
+        self.with_repo(|repo| {
+            let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+            let parent_tree = if commit.parent_count() > 0 { Some(commit.parent(0)?.tree()?) } else { None };
+            // Compares tree entries only; no blob is read
+            let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+            let odb = repo.odb()?;
+            let missing = |f: g::DiffFile| !f.id().is_zero() && f.mode() != g::FileMode::Commit && !odb.exists(f.id());
+            Ok(diff
+                .deltas()
+                .filter(|d| missing(d.old_file()) || missing(d.new_file()))
+                .filter_map(|d| d.new_file().path().or(d.old_file().path()).map(|p| p.to_string_lossy().replace('\\', "/")))
+                .collect())
+        })
+    }
+
+    /// Line and size changes of commit `rev` against its first parent (or the empty tree).
+    pub fn commit_file_stats(&self, rev: &str) -> Result<Vec<FileChangeStat>> {
+        self.with_repo(|repo| {
+            let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+            let parent_tree = if commit.parent_count() > 0 { Some(commit.parent(0)?.tree()?) } else { None };
+            let mut opts = g::DiffOptions::new();
+            opts.include_typechange(true);
+            let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut opts))?;
+            diff.find_similar(Some(g::DiffFindOptions::new().renames(true)))?;
+            diff_change_stats(&diff, None)
+        })
+    }
+
+    /// Line and size changes of the working tree (with the index) against HEAD, limited to
+    /// the directory `scope`, untracked files included as additions. Files over
+    /// [`LINE_COUNT_MAX`] count as binary so huge build outputs aren't read.
+    pub fn worktree_file_stats(&self, scope: Option<&str>) -> Result<Vec<FileChangeStat>> {
+        self.with_repo(|repo| {
+            let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+            let mut opts = g::DiffOptions::new();
+            opts.include_untracked(true).recurse_untracked_dirs(true).show_untracked_content(true).include_typechange(true);
+            opts.max_size(LINE_COUNT_MAX as i64);
+            if let Some(dir) = scope {
+                opts.pathspec(dir).disable_pathspec_match(true);
+            }
+            let mut diff = repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))?;
+            diff.find_similar(Some(g::DiffFindOptions::new().renames(true)))?;
+            let mut stats = diff_change_stats(&diff, Some(&self.workdir))?;
+
+            // Unmerged files have no patch; count the worktree (markers included) against
+            // HEAD, like `git diff HEAD`
+            for delta in diff.deltas().filter(|d| d.status() == g::Delta::Conflicted) {
+                let Some(path) = delta.new_file().path() else { continue };
+                let Some(stat) = stats.iter_mut().find(|s| Path::new(&s.path) == path) else { continue };
+                let Ok(contents) = std::fs::read(self.workdir.join(path)) else { continue };
+                let ours = head_tree.as_ref().and_then(|t| t.get_path(path).ok()).and_then(|e| repo.find_blob(e.id()).ok());
+                let patch = match &ours {
+                    Some(blob) => g::Patch::from_blob_and_buffer(blob, Some(path), &contents, Some(path), None)?,
+                    None => g::Patch::from_buffers(&[], None, &contents, Some(path), None)?,
+                };
+                let (_, additions, deletions) = patch.line_stats()?;
+                stat.additions = Some(additions as u32);
+                stat.deletions = Some(deletions as u32);
+            }
+            Ok(stats)
+        })
+    }
+
+    /// Status entries in batches of up to `batch`; returns (total, ahead, behind).
+    pub fn status_stream(&self, opts: &openvcs_core::models::StatusOptions, batch: usize, on_batch: &mut dyn FnMut(Vec<FileEntry>)) -> Result<(usize, u32, u32)> {
+        let batch = batch.max(1);
+        let scope = opts.scope();
+        self.with_repo(|repo| -> Result<(usize, u32, u32)> {
+
+            // Gather statuses
+            let mut sopts = g::StatusOptions::new();
+            // An ignored directory stays one entry, like `git status --ignored=matching`
+            sopts.include_untracked(true)
+                .recurse_untracked_dirs(!opts.collapse_untracked_dirs)
+                .include_ignored(opts.include_ignored)
+                .recurse_ignored_dirs(false)
                 .renames_head_to_index(true)
                 .renames_index_to_workdir(true);
+            if let Some(dir) = scope.as_deref() {
+                sopts.pathspec(dir).disable_pathspec_match(true);
+            }
 
             let statuses = repo.statuses(Some(&mut sopts))?;
 
-            let mut files = Vec::<FileEntry>::with_capacity(statuses.len());
-            let mut summary = StatusSummary::default();
+            let mut files = Vec::<FileEntry>::with_capacity(statuses.len().min(batch));
 
             for e in statuses.iter() {
                 let s = e.status();
 
-                if s.contains(g::Status::WT_NEW)                        { summary.untracked += 1; }
-                if s.intersects(g::Status::WT_MODIFIED | g::Status::WT_TYPECHANGE) { summary.modified  += 1; }
-                if s.intersects(g::Status::INDEX_NEW | g::Status::INDEX_MODIFIED | g::Status::INDEX_TYPECHANGE) {
-                    summary.staged += 1;
-                }
-                if s.contains(g::Status::CONFLICTED)                    { summary.conflicted += 1; }
-
                 let code = if s.contains(g::Status::CONFLICTED) {
                     "U"
+                } else if s.contains(g::Status::IGNORED) {
+                    "!"
                 } else if s.contains(g::Status::INDEX_DELETED) || s.contains(g::Status::WT_DELETED) {
                     "D"
                 } else if s.contains(g::Status::INDEX_NEW) || s.contains(g::Status::WT_NEW) {
                     "A"
-                } else if s.intersects(g::Status::INDEX_MODIFIED | g::Status::WT_MODIFIED | g::Status::INDEX_TYPECHANGE | g::Status::WT_TYPECHANGE) {
+                } else if s.intersects(g::Status::INDEX_TYPECHANGE | g::Status::WT_TYPECHANGE) {
+                    "T"
+                } else if s.intersects(g::Status::INDEX_MODIFIED | g::Status::WT_MODIFIED) {
                     "M"
                 } else {
                     "R?"
@@ -838,7 +1915,15 @@ impl Git {
                     .map(|p| p.to_string_lossy().to_string())
                     .unwrap_or_default();
 
-                files.push(FileEntry { path, status: code, hunks: Vec::new() });
+                let mut entry = FileEntry { path, status: code, ..Default::default() };
+                entry.count_dir_files(&self.workdir);
+                files.push(entry);
+                if files.len() >= batch {
+                    on_batch(std::mem::take(&mut files));
+                }
+            }
+            if !files.is_empty() {
+                on_batch(files);
             }
 
             // ahead/behind (best effort)
@@ -859,7 +1944,7 @@ impl Git {
                 } else { (0, 0) }
             };
 
-            Ok(StatusPayload { files, ahead, behind })
+            Ok((statuses.len(), ahead, behind))
         })
     }
 
@@ -886,6 +1971,21 @@ impl Git {
         })
     }
 
+    /// Changes under `scope` (the whole tree when `None`) against HEAD, index and working
+    /// tree together, untracked files as additions.
+    pub fn diff_dir(&self, scope: Option<&str>) -> Result<Vec<String>> {
+        self.with_repo(|repo| {
+            let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+            let mut opts = g::DiffOptions::new();
+            opts.context_lines(3).include_untracked(true).recurse_untracked_dirs(true).show_untracked_content(true);
+            if let Some(dir) = scope {
+                opts.pathspec(dir).disable_pathspec_match(true);
+            }
+            let diff = repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut opts))?;
+            collect_patch_lines(&diff)
+        })
+    }
+
     pub fn diff_file(&self, any_path: &Path) -> Result<Vec<String>> {
         self.with_repo(|repo| -> Result<Vec<String>> {
             // Repo-relative path
@@ -900,8 +2000,10 @@ impl Git {
             let mut opts = g::DiffOptions::new();
             opts.pathspec(rel_str.as_ref());
             opts.context_lines(3);
+            // Untracked files (every file of a fresh repository) diff as all-added lines
             opts.include_untracked(true)
-                .recurse_untracked_dirs(true);
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
 
             // 1) Unstaged: index → workdir
             let diff_unstaged = repo.diff_index_to_workdir(None, Some(&mut opts))?;
@@ -933,9 +2035,93 @@ impl Git {
         })
     }
 
+    /// Attributes `names` of each path; unspecified ones are skipped.
+    pub fn path_attributes(&self, paths: &[String], names: &[&str]) -> Result<Vec<PathAttribute>> {
+        self.with_repo(|repo| {
+            let mut out = Vec::new();
+            for path in paths {
+                for &name in names {
+                    let raw = repo.get_attr_bytes(Path::new(path), name, g::AttrCheckFlags::FILE_THEN_INDEX)?;
+                    let value = match g::AttrValue::from_bytes(raw) {
+                        g::AttrValue::True => "set".to_string(),
+                        g::AttrValue::False => "unset".to_string(),
+                        g::AttrValue::String(s) => s.to_string(),
+                        g::AttrValue::Bytes(b) => String::from_utf8_lossy(b).into_owned(),
+                        g::AttrValue::Unspecified => continue,
+                    };
+                    out.push(PathAttribute { path: path.clone(), name: name.to_string(), value });
+                }
+            }
+            Ok(out)
+        })
+    }
+
+    pub fn staged_blob_sizes(&self) -> Result<Vec<BlobSize>> {
+        self.with_repo(|repo| {
+            let mut index = repo.index()?;
+            index.read(false)?;
+            // Unborn HEAD: everything in the index is new.
+            let head = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+            let diff = repo.diff_tree_to_index(head.as_ref(), Some(&index), None)?;
+            let odb = repo.odb()?;
+            let mut out = Vec::new();
+            for delta in diff.deltas() {
+                let file = delta.new_file();
+                if delta.status() == g::Delta::Deleted || file.mode() == g::FileMode::Commit {
+                    continue;
+                }
+                let (size, _) = odb.read_header(file.id())?;
+                let path = file.path().map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_default();
+                out.push(BlobSize { path, size: size as u64 });
+            }
+            Ok(out)
+        })
+    }
+
+    pub fn tracked_paths(&self) -> Result<Vec<String>> {
+        self.with_repo(|repo| {
+            // Pooled handles cache the index; pick up changes made by other processes.
+            let mut index = repo.index()?;
+            index.read(false)?;
+            Ok(index.iter().map(|e| String::from_utf8_lossy(&e.path).into_owned()).collect())
+        })
+    }
+
+    /// Drop `paths` from the index; the files stay in the working tree.
+    pub fn untrack_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        self.with_repo_mut(|repo| {
+            let mut index = repo.index()?;
+            index.read(false)?;
+            for p in paths {
+                index.remove_path(&rel_to_workdir(&self.workdir, p)?)?;
+            }
+            index.write()?;
+            Ok(())
+        })
+    }
+
+    /// Stage `paths` (files or directories) as they are in the working tree, deletions
+    /// included.
+    pub fn stage_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let specs = paths.iter().map(|p| rel_to_workdir(&self.workdir, p)).collect::<Result<Vec<_>>>()?;
+        self.with_repo_mut(|repo| {
+            let mut index = repo.index()?;
+            index.read(false)?;
+            index.add_all(specs.iter(), g::IndexAddOption::DEFAULT, None)?;
+            // add_all skips files gone from disk; this drops their entries
+            index.update_all(specs.iter(), None)?;
+            index.write()?;
+            Ok(())
+        })
+    }
+
     pub fn branches(&self) -> Result<Vec<BranchItem>> {
         self.with_repo(|repo| -> Result<Vec<BranchItem>> {
             let mut items = Vec::new();
+            let mut descriptions = branch_config_in(repo, "description").unwrap_or_default();
 
             for br in repo.branches(None)? { // None => Local + Remote
                 let (branch, bty) = br?;
@@ -961,12 +2147,346 @@ impl Git {
                 // Only local branches can be “current”
                 let current = matches!(bty, git2::BranchType::Local) && branch.is_head();
 
-                items.push(BranchItem { name, full_ref, kind, current });
+                // Upstream configured (branch.<name>.merge) but its tracking ref is missing
+                let upstream_gone = matches!(bty, git2::BranchType::Local)
+                    && repo
+                        .branch_upstream_name(&full_ref)
+                        .ok()
+                        .is_some_and(|up| up.as_str().is_some_and(|u| repo.find_reference(u).is_err()));
+
+                let description =
+                    if matches!(bty, git2::BranchType::Local) { descriptions.remove(&name) } else { None };
+
+                items.push(BranchItem { name, full_ref, kind, current, upstream_gone, description, notes: None });
             }
 
             Ok(items)
         })
     }
+
+    pub fn branch_config(&self, key: &str) -> Result<BTreeMap<String, String>> {
+        self.with_repo(|repo| branch_config_in(repo, key))
+    }
+
+    pub fn set_branch_config(&self, branch: &str, key: &str, value: Option<&str>) -> Result<()> {
+        self.set_local_config(&format!("branch.{branch}.{key}"), value)
+    }
+
+    /// `key` from the full config stack (all levels, includes, `config.worktree`). libgit2
+    /// does not say which included file a value came from, so `origin` is only known for
+    /// values set directly in a level's own file.
+    pub fn config_value(&self, key: &str) -> Result<Option<ConfigValue>> {
+        self.with_repo(|repo| {
+            let cfg = repo.config()?;
+            let entry = match cfg.get_entry(key) {
+                Ok(e) => e,
+                Err(e) if e.code() == g::ErrorCode::NotFound => return Ok(None),
+                Err(e) => return Err(e.into()),
+            };
+            let (scope, file) = match entry.level() {
+                g::ConfigLevel::ProgramData | g::ConfigLevel::System => (ConfigScope::System, g::Config::find_system().ok()),
+                g::ConfigLevel::XDG => (ConfigScope::Global, g::Config::find_xdg().ok()),
+                g::ConfigLevel::Global => (ConfigScope::Global, g::Config::find_global().ok()),
+                g::ConfigLevel::Local => (ConfigScope::Local, Some(repo.commondir().join("config"))),
+                g::ConfigLevel::Worktree => (ConfigScope::Worktree, Some(repo.path().join("config.worktree"))),
+                g::ConfigLevel::App | g::ConfigLevel::Highest => (ConfigScope::Command, None),
+            };
+            let origin = file.filter(|_| entry.include_depth() == 0).map(|f| f.to_string_lossy().into_owned());
+            let value = String::from_utf8_lossy(entry.value_bytes()).into_owned();
+            Ok(Some(ConfigValue { value, scope, origin }))
+        })
+    }
+
+    pub fn local_config(&self, key: &str) -> Result<Option<String>> {
+        self.with_repo(|repo| {
+            let cfg = repo.config()?.open_level(g::ConfigLevel::Local)?;
+            match cfg.get_string(key) {
+                Ok(v) => Ok(Some(v)),
+                Err(e) if e.code() == g::ErrorCode::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    /// `core.hooksPath` (relative to the working tree, like git) or the common dir's `hooks`.
+    pub fn hooks_dir(&self) -> Result<PathBuf> {
+        self.with_repo(|repo| {
+            match repo.config()?.get_path("core.hooksPath") {
+                Ok(p) if p.is_absolute() => Ok(p),
+                Ok(p) => Ok(repo.workdir().unwrap_or_else(|| repo.path()).join(p)),
+                Err(e) if e.code() == g::ErrorCode::NotFound => Ok(repo.commondir().join("hooks")),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    pub fn set_local_config(&self, key: &str, value: Option<&str>) -> Result<()> {
+        self.with_repo_mut(|repo| {
+            let mut cfg = repo.config()?.open_level(g::ConfigLevel::Local)?;
+            match value {
+                Some(v) => cfg.set_str(key, v)?,
+                None => match cfg.remove_multivar(key, ".*") {
+                    Err(e) if e.code() == g::ErrorCode::NotFound => {}
+                    other => other?,
+                },
+            }
+            Ok(())
+        })
+    }
+}
+
+/// `branch.<name>.<key>` values from the repository-local config, by branch name.
+fn branch_config_in(repo: &Repository, key: &str) -> Result<BTreeMap<String, String>> {
+    let cfg = repo.config()?.open_level(g::ConfigLevel::Local)?;
+    // Entry names come back with the section and key lowercased.
+    let suffix = format!(".{}", key.to_ascii_lowercase());
+    let mut out = BTreeMap::new();
+    cfg.entries(Some(&format!("^branch\\..*\\.{key}$")))?.for_each(|entry| {
+        if let (Some(name), Some(value)) = (entry.name(), entry.value())
+            && let Some(branch) = name.strip_prefix("branch.").and_then(|n| n.strip_suffix(suffix.as_str()))
+        {
+            out.insert(branch.to_string(), value.to_string());
+        }
+    })?;
+    Ok(out)
+}
+
+/// Branches and tags advertised by `url`, via a detached (repository-less) remote.
+pub fn remote_refs(url: &str) -> Result<RemoteRefs> {
+    // git2's `list()` builds its slice from a null pointer when the local transport finds
+    // no refs at all, which aborts in debug builds; answer for empty local repos here.
+    if !url.contains("://") || url.starts_with("file://") {
+        let path = url.strip_prefix("file://").unwrap_or(url);
+        if let Ok(repo) = Repository::open(path)
+            && repo.references()?.next().is_none()
+        {
+            debug!("remote_refs: {url} is an empty repository");
+            return Ok(RemoteRefs::default());
+        }
+    }
+    debug!("remote_refs: connecting to {url}");
+    let mut remote = g::Remote::create_detached(url)?;
+    let conn = remote.connect_auth(g::Direction::Fetch, Some(make_remote_callbacks()), None)?;
+
+    let mut res = RemoteRefs::default();
+    for head in conn.list()? {
+        let name = head.name();
+        if name == "HEAD" {
+            res.default_branch = head
+                .symref_target()
+                .and_then(|t| t.strip_prefix("refs/heads/"))
+                .map(str::to_string);
+            continue;
+        }
+        let (kind, short) = if let Some(b) = name.strip_prefix("refs/heads/") {
+            (RemoteRefKind::Branch, b)
+        } else if let Some(t) = name.strip_prefix("refs/tags/") && !t.ends_with("^{}") {
+            (RemoteRefKind::Tag, t)
+        } else {
+            continue;
+        };
+        res.refs.push(RemoteRef {
+            name: short.to_string(),
+            full_ref: name.to_string(),
+            kind,
+            oid: head.oid().to_string(),
+        });
+    }
+    res.refs.sort_by(|a, b| (a.kind != RemoteRefKind::Branch, &a.name).cmp(&(b.kind != RemoteRefKind::Branch, &b.name)));
+    debug!("remote_refs: {} ref(s), default={:?}", res.refs.len(), res.default_branch);
+    Ok(res)
+}
+
+fn start_revwalk<'r>(repo: &'r Repository, q: &LogQuery) -> Result<g::Revwalk<'r>> {
+    let mut walk = repo.revwalk()?;
+    // TOPOLOGICAL alone is `--topo-order`; adding TIME would make it `--date-order`
+    let sort = if q.topo_order { g::Sort::TOPOLOGICAL } else { g::Sort::TIME };
+    let _ = walk.set_sorting(sort);
+
+    let rev = q.rev.as_deref().unwrap_or("HEAD");
+    // No commits yet: an empty history rather than "reference not found".
+    if rev == "HEAD" && repo.head().is_err_and(|e| e.code() == g::ErrorCode::UnbornBranch) {
+        debug!("start_revwalk: HEAD is unborn");
+        return Ok(walk);
+    }
+    // Any revision, as `git log <rev>` takes (branch names, tags, ids, `HEAD~2`)
+    walk.push(repo.revparse_single(rev)?.peel_to_commit()?.id())?;
+    Ok(walk)
+}
+
+/// The non-paging filters of a `LogQuery`, parsed once.
+struct CommitFilter<'q> {
+    q: &'q LogQuery,
+    auth_sub: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
+    paths: Option<Pathspec>,
+}
+
+impl<'q> CommitFilter<'q> {
+    fn new(q: &'q LogQuery) -> Result<Self> {
+        let paths = Pathspec::parse(&q.pathspecs()).map_err(|e| g::Error::from_str(&e))?;
+        Ok(Self {
+            q,
+            auth_sub: q.author_contains.as_ref().map(|s| s.to_lowercase()),
+            since: q.since(),
+            until: q.until(),
+            paths: (!paths.is_empty()).then_some(paths),
+        })
+    }
+
+    fn accepts(&self, repo: &Repository, commit: &g::Commit) -> Result<bool> {
+        // merges?
+        if !self.q.include_merges && commit.parent_count() > 1 {
+            return Ok(false);
+        }
+
+        // date filters (git time is seconds + offset)
+        let secs = commit.time().seconds();
+        if let Some(s) = self.since && secs < s { return Ok(false); }
+        if let Some(u) = self.until && secs > u { return Ok(false); }
+
+        // author filter (substring on "Name <email>")
+        if let Some(sub) = &self.auth_sub {
+            let who = {
+                let a = commit.author();
+                format!("{} <{}>", a.name().unwrap_or(""), a.email().unwrap_or(""))
+            };
+            if !who.to_lowercase().contains(sub) {
+                return Ok(false);
+            }
+        }
+
+        // path filter (touches a matching path)
+        if let Some(paths) = &self.paths
+            && !commit_touches_paths(repo, commit, paths)? {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+}
+
+fn commit_item(commit: &g::Commit) -> CommitItem {
+    let id_full = commit.id().to_string();
+    let short_id = id_full[..id_full.len().min(7)].to_string();
+    let (a, c) = (commit.author(), commit.committer());
+    let author_name = a.name().unwrap_or("").to_string();
+    let author_email = a.email().unwrap_or("").to_string();
+    let author_time = timestamp(a.when());
+    CommitItem {
+        msg: commit.summary().unwrap_or("").to_string(),
+        meta: format!("{} • {short_id}", author_time.to_rfc3339()),
+        author: format!("{author_name} <{author_email}>"),
+        committer_name: c.name().unwrap_or("").to_string(),
+        committer_email: c.email().unwrap_or("").to_string(),
+        timestamp: author_time.seconds,
+        author_time,
+        committer_time: timestamp(c.when()),
+        id: id_full,
+        author_name,
+        author_email,
+        short_id,
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(md: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    md.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_: &std::fs::Metadata) -> bool {
+    false
+}
+
+/// Per-file counts of `diff`. With `workdir`, new-side sizes are read from disk (as for
+/// the working tree they may not be known to libgit2).
+fn diff_change_stats(diff: &g::Diff, workdir: Option<&Path>) -> Result<Vec<FileChangeStat>> {
+    let mut out = Vec::with_capacity(diff.deltas().len());
+    for i in 0..diff.deltas().len() {
+        let Some(patch) = g::Patch::from_diff(diff, i)? else { continue };
+        let delta = patch.delta();
+        let status = match delta.status() {
+            g::Delta::Added | g::Delta::Untracked => "A",
+            g::Delta::Deleted => "D",
+            g::Delta::Renamed => "R",
+            g::Delta::Copied => "C",
+            g::Delta::Typechange => "T",
+            _ => "M",
+        };
+        let path_of = |f: g::DiffFile| f.path().map(|p| p.to_string_lossy().into_owned()).unwrap_or_default();
+        let path = path_of(delta.new_file());
+        let old_path = matches!(status, "R" | "C").then(|| path_of(delta.old_file()));
+        let (_, additions, deletions) = patch.line_stats()?;
+        let binary = delta.flags().is_binary();
+        let old_size = if delta.old_file().exists() { delta.old_file().size() as i64 } else { 0 };
+        let new_size = match workdir {
+            Some(wd) => std::fs::symlink_metadata(wd.join(&path)).map(|m| m.len() as i64).unwrap_or(0),
+            None if delta.new_file().exists() => delta.new_file().size() as i64,
+            None => 0,
+        };
+        out.push(FileChangeStat {
+            path,
+            old_path,
+            status: status.to_string(),
+            additions: (!binary).then_some(additions as u32),
+            deletions: (!binary).then_some(deletions as u32),
+            size_delta: new_size - old_size,
+            old_mode: delta.old_file().mode().into(),
+            new_mode: delta.new_file().mode().into(),
+        });
+    }
+    Ok(out)
+}
+
+/// Id of the blob at `path` in `tree`, if there is one.
+fn blob_at(tree: &g::Tree, path: &str) -> Option<Oid> {
+    let entry = tree.get_path(Path::new(path)).ok()?;
+    (entry.kind() == Some(g::ObjectType::Blob)).then(|| entry.id())
+}
+
+fn commit_record(commit: &g::Commit) -> CommitRecord {
+    let (a, c) = (commit.author(), commit.committer());
+    let body = commit.body().unwrap_or("").trim().to_string();
+    CommitRecord {
+        id: commit.id().to_string(),
+        parents: commit.parent_ids().map(|p| p.to_string()).collect(),
+        author_name: a.name().unwrap_or("").to_string(),
+        author_email: a.email().unwrap_or("").to_string(),
+        author_date: timestamp(a.when()).to_rfc3339(),
+        committer_name: c.name().unwrap_or("").to_string(),
+        committer_email: c.email().unwrap_or("").to_string(),
+        committer_date: timestamp(c.when()).to_rfc3339(),
+        author_time: timestamp(a.when()),
+        committer_time: timestamp(c.when()),
+        summary: commit.summary().unwrap_or("").to_string(),
+        trailers: parse_trailers(&body),
+        body,
+    }
+}
+
+/// History walk state owned independently of the `Git` handle (see `Git::history_walk`).
+pub struct LogWalk {
+    repo: Repository,
+    oids: std::vec::IntoIter<Oid>,
+    query: LogQuery,
+}
+
+impl LogWalk {
+    pub fn next_batch(&mut self, n: usize) -> Result<Vec<CommitItem>> {
+        let Self { repo, oids, query } = self;
+        let filter = CommitFilter::new(query)?;
+        let mut out = Vec::with_capacity(n.min(4096));
+        for oid in oids.by_ref() {
+            let commit = repo.find_commit(oid)?;
+            if filter.accepts(repo, &commit)? {
+                out.push(commit_item(&commit));
+                if out.len() >= n { break; }
+            }
+        }
+        Ok(out)
+    }
 }
 
 #[derive(Default, Clone, Copy, Debug)]
@@ -982,12 +2502,46 @@ fn make_remote_callbacks() -> git2::RemoteCallbacks<'static> {
 }
 
 
+/// Local changes (tracked or untracked) to paths that differ between HEAD and `target`.
+fn clobbered_paths(repo: &Repository, target: Oid) -> Result<Vec<String>> {
+    clobbered_by_tree(repo, &repo.find_commit(target)?.tree()?)
+}
+
+/// Local changes (tracked or untracked) to paths that differ between HEAD and `target_tree`.
+fn clobbered_by_tree(repo: &Repository, target_tree: &g::Tree) -> Result<Vec<String>> {
+    let head_tree = repo.head()?.peel_to_tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&head_tree), Some(target_tree), None)?;
+    let incoming: Vec<&Path> = diff
+        .deltas()
+        .flat_map(|d| [d.old_file().path(), d.new_file().path()])
+        .flatten()
+        .collect();
+    if incoming.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true).recurse_untracked_dirs(true).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut opts))?;
+    let mut out: Vec<String> = statuses
+        .iter()
+        .filter(|e| !e.status().is_empty() && !e.status().contains(Status::IGNORED))
+        .filter_map(|e| e.path().map(str::to_string))
+        .filter(|p| incoming.contains(&Path::new(p)))
+        .collect();
+    out.dedup();
+    Ok(out)
+}
+
 pub fn make_remote_callbacks_with_progress<F>(on: F) -> git2::RemoteCallbacks<'static>
 where
     F: Fn(String) + Send + Sync + 'static,
 {
     let on = Arc::new(on);
     let mut cb = git2::RemoteCallbacks::new();
+    // Returning false from a progress callback makes libgit2 abort the transfer.
+    let cancel = openvcs_core::cancel::current();
+    let keep_going = move || !cancel.as_ref().is_some_and(|t| t.is_cancelled());
 
     // ---- credentials: single attempt, then abort with Auth error ----
     let attempts = Arc::new(AtomicUsize::new(0));
@@ -1031,30 +2585,50 @@ where
     // sideband
     {
         let on = Arc::clone(&on);
+        let keep_going = keep_going.clone();
         cb.sideband_progress(move |data| {
             if let Ok(s) = std::str::from_utf8(data) {
-                let msg = format!("remote: {}", s.trim_end());
-                debug!("{msg}");
-                (on)(msg);
+                // progress meters redraw with `\r`; report each update separately
+                for part in s.split(['\r', '\n']).filter(|p| !p.trim().is_empty()) {
+                    let msg = format!("remote: {}", part.trim_end());
+                    debug!("{msg}");
+                    (on)(msg);
+                }
             }
-            true
+            keep_going()
         });
     }
 
-    // transfer/push progress
+    // transfer/push progress, phrased like git's own meters so both backends parse alike;
+    // only percentage changes are reported (libgit2 calls these very often)
+    let last = Arc::new(AtomicUsize::new(usize::MAX));
     {
         let on = Arc::clone(&on);
+        let keep_going = keep_going.clone();
+        let last = Arc::clone(&last);
         cb.transfer_progress(move |p| {
-            let msg = format!(
-                "pushing… {}/{} deltas, {}/{} objects",
-                p.indexed_deltas(),
-                p.total_deltas(),
-                p.indexed_objects(),
-                p.total_objects()
-            );
-            debug!("{msg}");
-            (on)(msg);
-            true
+            let (label, cur, total) = if p.received_objects() < p.total_objects() || p.total_deltas() == 0 {
+                ("Receiving objects", p.received_objects(), p.total_objects())
+            } else {
+                ("Resolving deltas", p.indexed_deltas(), p.total_deltas())
+            };
+            // bytes on every line, so the final size is reported even when receiving ends
+            // mid-percent and the meter switches straight to deltas
+            if let Some(msg) = meter_line(&last, label, cur, total, p.received_bytes()) {
+                trace!("{msg}");
+                (on)(msg);
+            }
+            keep_going()
+        });
+    }
+    {
+        let on = Arc::clone(&on);
+        let last = Arc::clone(&last);
+        cb.push_transfer_progress(move |cur, total, bytes| {
+            if let Some(msg) = meter_line(&last, "Writing objects", cur, total, bytes) {
+                trace!("{msg}");
+                (on)(msg);
+            }
         });
     }
 
@@ -1076,25 +2650,71 @@ where
     cb
 }
 
-/// Parse RFC3339/ISO8601 into epoch seconds; on failure return None (ignore filter).
-fn parse_iso_to_epoch_secs(s: &str) -> Option<i64> {
-    OffsetDateTime::parse(s, &Rfc3339).ok().map(|dt| dt.unix_timestamp())
+/// `"<label>: NN% (cur/total)"`, or `None` if the percentage for `label` hasn't changed
+/// since the last call sharing `last`.
+fn meter_line(last: &AtomicUsize, label: &str, cur: usize, total: usize, bytes: usize) -> Option<String> {
+    let pct = (cur * 100).checked_div(total).unwrap_or(100);
+    let key = label.len() * 1000 + pct;
+    (last.swap(key, Ordering::Relaxed) != key).then(|| format!("{label}: {pct}% ({cur}/{total}), {}", human_size(bytes)))
 }
 
-/// Convert git2::Time to RFC3339 string, honoring the embedded offset minutes.
-fn git_time_to_rfc3339(t: g::Time) -> String {
-    let offset = UtcOffset::from_whole_seconds((t.offset_minutes() * 60) as i32)
-        .unwrap_or(UtcOffset::UTC);
-    OffsetDateTime::from_unix_timestamp(t.seconds())
-        .unwrap_or(OffsetDateTime::UNIX_EPOCH)
-        .to_offset(offset)
-        .format(&Rfc3339)
-        .unwrap_or_else(|_| "1970-01-01T00:00:00Z".into())
+/// Size as git's progress meters print it: `290 bytes`, `1.20 MiB`.
+fn human_size(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{bytes} bytes");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.2} {}", UNITS[unit])
 }
 
-/// Fast check whether a commit touches a given path prefix.
-fn commit_touches_path(repo: &Repository, oid: Oid, path_prefix: &str) -> Result<bool> {
-    let commit = repo.find_commit(oid)?;
+/// Paths with conflict entries in `index`.
+fn conflicted_paths(index: &g::Index) -> Result<Vec<String>> {
+    Ok(index
+        .conflicts()?
+        .filter_map(|c| {
+            let c = c.ok()?;
+            let entry = c.our.or(c.their).or(c.ancestor)?;
+            Some(String::from_utf8_lossy(&entry.path).into_owned())
+        })
+        .collect())
+}
+
+/// The commits in MERGE_HEAD while a merge is being concluded, the next commit's other
+/// parents.
+fn merge_heads(repo: &Repository) -> Result<Vec<g::Commit<'_>>> {
+    if repo.state() != g::RepositoryState::Merge {
+        return Ok(Vec::new());
+    }
+    // One id per line; `mergehead_foreach` would need the repository mutably
+    let heads = std::fs::read_to_string(repo.path().join("MERGE_HEAD")).unwrap_or_default();
+    let commits = heads.split_whitespace().map(|id| repo.find_commit(Oid::from_str(id)?));
+    Ok(commits.collect::<std::result::Result<_, _>>()?)
+}
+
+/// Author of a commit by `committer`: the override in `opts`, at the given date or the
+/// committer's time.
+fn author_signature(committer: &g::Signature<'_>, opts: &CommitOptions) -> Result<g::Signature<'static>> {
+    let (name, email) = match &opts.author {
+        Some(a) => (a.name.as_str(), a.email.as_str()),
+        None => (committer.name().unwrap_or(""), committer.email().unwrap_or("")),
+    };
+    let when = opts.author_date.map_or(committer.when(), |t| g::Time::new(t.seconds, t.offset_minutes));
+    Ok(g::Signature::new(name, email, &when)?)
+}
+
+/// git2::Time → `Timestamp`, keeping the recorded offset.
+fn timestamp(t: g::Time) -> Timestamp {
+    Timestamp::new(t.seconds(), t.offset_minutes())
+}
+
+/// Whether `commit` changes a path matched by `paths` (compared to its first parent).
+fn commit_touches_paths(repo: &Repository, commit: &g::Commit, paths: &Pathspec) -> Result<bool> {
     let tree = commit.tree()?;
 
     // Parent tree or truly empty tree (works for SHA-1 and SHA-256 repos)
@@ -1107,27 +2727,42 @@ fn commit_touches_path(repo: &Repository, oid: Oid, path_prefix: &str) -> Result
         repo.find_tree(empty_oid)?
     };
 
-    // If you want a quick win, apply the pathspec here to let libgit2 filter for us.
+    // Plain paths narrow the diff up front; wildcards and exclusions are checked per delta.
     let mut opts = g::DiffOptions::new();
-    opts.pathspec(path_prefix);
-
-    let mut touched = false;
-    repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), Some(&mut opts))?
-        .foreach(
-            &mut |delta, _| {
-                // If a delta exists at all with our pathspec, we can bail out early.
-                if delta.status() != g::Delta::Unmodified {
-                    touched = true;
-                    return false; // stop
-                }
-                true
-            },
-            None, None, None,
-        )?;
+    if let Some(prefixes) = paths.literal_prefixes() {
+        opts.disable_pathspec_match(true);
+        for p in prefixes {
+            opts.pathspec(p);
+        }
+    }
+
+    let diff = repo.diff_tree_to_tree(Some(&parent_tree), Some(&tree), Some(&mut opts))?;
+    let touched = diff.deltas().any(|delta| {
+        [delta.old_file().path(), delta.new_file().path()]
+            .into_iter()
+            .flatten()
+            .any(|p| paths.matches(&p.to_string_lossy().replace('\\', "/")))
+    });
     Ok(touched)
 }
 
 
+/// Remote-tracking branches (`origin/main`) that contain commit `id`.
+fn remotes_containing(repo: &Repository, id: Oid) -> Result<Vec<String>> {
+    let mut out = Vec::new();
+    for r in repo.references_glob("refs/remotes/*")? {
+        let r = r?;
+        let (Some(name), Some(target)) = (r.shorthand(), r.target()) else { continue };
+        if name.ends_with("/HEAD") {
+            continue;
+        }
+        if target == id || repo.graph_descendant_of(target, id)? {
+            out.push(name.to_string());
+        }
+    }
+    Ok(out)
+}
+
 /// Turn absolute path into repo-relative for index operations.
 pub fn rel_to_workdir(workdir: &Path, p: &Path) -> Result<PathBuf> {
     if p.is_absolute() {
@@ -1198,8 +2833,13 @@ pub fn git_identity(git: &Git) -> Option<(String, String)> {
 fn collect_patch_lines(diff: &g::Diff) -> Result<Vec<String>> {
     let mut out = Vec::<String>::new();
     diff.print(g::DiffFormat::Patch, |_d, _h, l| {
-        let s = std::str::from_utf8(l.content()).unwrap_or_default();
-        out.push(format!("{}{}", l.origin(), s.trim_end_matches('\n')));
+        let s = String::from_utf8_lossy(l.content());
+        match l.origin() {
+            // File and hunk headers and end-of-file markers come unprefixed, possibly
+            // several lines at once; print them the way `git diff` does.
+            '+' | '-' | ' ' => out.push(format!("{}{}", l.origin(), s.trim_end_matches('\n'))),
+            _ => out.extend(s.lines().filter(|ln| !ln.is_empty()).map(str::to_string)),
+        }
         true
     })?;
     Ok(out)
@@ -0,0 +1,152 @@
+/* =========================================================================================
+   Signing: libgit2 writes signed objects but can't produce or check signatures, so these
+   run the same external programs git does (`gpg`, `ssh-keygen -Y`).
+   ========================================================================================= */
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use log::{debug, trace};
+use openvcs_core::models::{SignatureFormat, SigningKey};
+
+use crate::lowlevel::{GitError, Result};
+
+/// SSH signature namespace git uses for commits and tags.
+const NAMESPACE: &str = "git";
+
+/// A scratch file removed on drop (signature and key files handed to the signer).
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(contents: &str) -> Result<Self> {
+        static SEQ: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "openvcs-sign-{}-{}",
+            std::process::id(),
+            SEQ.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, contents)?;
+        Ok(Self(path))
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().unwrap_or_default()
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Run `program args` with `input` on stdin; `(success, stdout, stderr)`.
+fn run(program: &str, args: &[&str], input: &str) -> Result<(bool, String, String)> {
+    trace!("sign: {program} {}", args.join(" "));
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| GitError::Signing(format!("cannot run {program}: {e}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let out = child.wait_with_output()?;
+    Ok((
+        out.status.success(),
+        String::from_utf8_lossy(&out.stdout).into_owned(),
+        String::from_utf8_lossy(&out.stderr).into_owned(),
+    ))
+}
+
+fn program(key: &SigningKey) -> &str {
+    match (key.program.as_deref(), key.format) {
+        (Some(p), _) if !p.is_empty() => p,
+        (_, SignatureFormat::Gpg) => "gpg",
+        (_, SignatureFormat::Ssh) => "ssh-keygen",
+    }
+}
+
+/// Detached, armored signature over `payload`, to be appended to a tag object.
+pub fn sign(payload: &str, key: &SigningKey) -> Result<String> {
+    let prog = program(key);
+    debug!("sign: {:?} signature with {prog}", key.format);
+    match key.format {
+        SignatureFormat::Gpg => {
+            let mut args = vec!["--status-fd=2", "-bsa"];
+            if !key.key.is_empty() {
+                args.extend(["-u", key.key.as_str()]);
+            }
+            let (ok, sig, err) = run(prog, &args, payload)?;
+            if !ok || !err.contains("[GNUPG:] SIG_CREATED ") {
+                return Err(GitError::Signing(format!("gpg failed to sign the data: {}", err.trim())));
+            }
+            Ok(sig)
+        }
+        SignatureFormat::Ssh => {
+            let k = key.key.trim();
+            if k.is_empty() {
+                return Err(GitError::Signing("no SSH signing key configured (user.signingkey)".into()));
+            }
+            // Literal keys live in the agent; `-U` tells ssh-keygen not to look for a file.
+            let literal = k.strip_prefix("key::").or_else(|| k.starts_with("ssh-").then_some(k));
+            let key_file = literal.map(TempFile::new).transpose()?;
+            let path = match &key_file {
+                Some(f) => f.path().to_string(),
+                None => expand_home(k),
+            };
+            let mut args = vec!["-Y", "sign", "-n", NAMESPACE, "-f", path.as_str()];
+            if key_file.is_some() {
+                args.push("-U");
+            }
+            let (ok, sig, err) = run(prog, &args, payload)?;
+            if !ok || sig.is_empty() {
+                return Err(GitError::Signing(format!("ssh-keygen failed to sign the data: {}", err.trim())));
+            }
+            Ok(sig)
+        }
+    }
+}
+
+/// Check `signature` over `payload`. Returns the verifier's verdict and its report,
+/// in the form [`TagVerification::from_verifier_output`](openvcs_core::models::TagVerification::from_verifier_output) reads.
+pub fn verify(
+    payload: &str,
+    signature: &str,
+    format: SignatureFormat,
+    program: Option<&str>,
+    allowed_signers: Option<&Path>,
+) -> Result<(bool, String)> {
+    let key = SigningKey { key: String::new(), format, program: program.map(str::to_string) };
+    let prog = self::program(&key);
+    let sig = TempFile::new(signature)?;
+    match format {
+        SignatureFormat::Gpg => {
+            let (ok, status, err) = run(prog, &["--status-fd=1", "--verify", sig.path(), "-"], payload)?;
+            Ok((ok, format!("{status}{err}")))
+        }
+        SignatureFormat::Ssh => {
+            let Some(allowed) = allowed_signers.and_then(Path::to_str) else {
+                return Ok((false, "gpg.ssh.allowedSignersFile needs to be configured for SSH signature verification".into()));
+            };
+            let (_, principals, err) = run(prog, &["-Y", "find-principals", "-f", allowed, "-s", sig.path()], "")?;
+            let Some(principal) = principals.lines().next().filter(|p| !p.is_empty()) else {
+                return Ok((false, format!("No principal matched the signing key. {}", err.trim())));
+            };
+            let args = ["-Y", "verify", "-n", NAMESPACE, "-f", allowed, "-I", principal, "-s", sig.path()];
+            let (ok, out, err) = run(prog, &args, payload)?;
+            Ok((ok, format!("{out}{err}")))
+        }
+    }
+}
+
+fn expand_home(p: &str) -> String {
+    match (p.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(rest), Some(home)) => Path::new(&home).join(rest).to_string_lossy().into_owned(),
+        _ => p.to_string(),
+    }
+}
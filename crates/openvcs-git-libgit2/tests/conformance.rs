@@ -0,0 +1,12 @@
+//! Both git backends report the same results for the conformance scenarios.
+
+use openvcs_git as _;
+use openvcs_git_libgit2 as _;
+
+/// Operations the libgit2 backend doesn't implement.
+const KNOWN_GAPS: &[openvcs_core::conformance::KnownGap] = &[("stage-patch", "git-libgit2")];
+
+#[test]
+fn backends_agree() {
+    openvcs_core::conformance::assert_parity(KNOWN_GAPS);
+}
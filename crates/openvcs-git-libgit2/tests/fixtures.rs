@@ -0,0 +1,106 @@
+//! Both git backends read testkit-built repositories the same way.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use openvcs_core::backend_descriptor::list_backends;
+use openvcs_core::models::{LogQuery, StatusOptions};
+use openvcs_core::Vcs;
+use openvcs_git as _;
+use openvcs_git_libgit2 as _;
+use openvcs_testkit::{Change, Fixture, RepoSpec};
+
+fn open_all(fixture: &Fixture) -> Vec<Arc<dyn Vcs>> {
+    let backends: Vec<_> = list_backends().map(|d| (d.open)(fixture.path()).expect("open fixture")).collect();
+    assert!(backends.len() >= 2, "link both backends");
+    backends
+}
+
+/// `view` of every backend, which must all be equal.
+fn agree<T: Debug + PartialEq>(fixture: &Fixture, view: impl Fn(&dyn Vcs) -> T) -> T {
+    let mut views = open_all(fixture).into_iter().map(|vcs| (vcs.id(), view(vcs.as_ref())));
+    let (first_id, first) = views.next().unwrap();
+    for (id, v) in views {
+        assert_eq!(first, v, "{first_id} and {id} disagree");
+    }
+    first
+}
+
+fn log(vcs: &dyn Vcs) -> Vec<(String, String)> {
+    let q = LogQuery { limit: 100, topo_order: true, include_merges: true, ..Default::default() };
+    vcs.log_commits(&q).unwrap().into_iter().map(|c| (c.id, c.msg)).collect()
+}
+
+fn status(vcs: &dyn Vcs) -> Vec<(String, String)> {
+    let mut files: Vec<_> =
+        vcs.status_payload(&StatusOptions::default()).unwrap().files.into_iter().map(|f| (f.path, f.status)).collect();
+    files.sort();
+    files
+}
+
+fn history() -> RepoSpec {
+    RepoSpec::new()
+        .commit("Initial commit", [Change::write("README.md", "# Fixture\n"), Change::write("src/lib.rs", "pub fn a() {}\n")])
+        .tag("v0.1", None)
+        .branch("topic")
+        .commit("Rename lib", [Change::rename("src/lib.rs", "src/core.rs")])
+        .commit("Add a large asset", [Change::large("assets/blob.bin", 3 << 20)])
+        .switch("main")
+        .author("Second Author", "second@example.com")
+        .commit("Edit readme", [Change::write("README.md", "# Fixture\n\nMore.\n")])
+        .merge("topic", "Merge branch 'topic'")
+        .tag("v1.0", Some("Release 1.0"))
+        .submodule("vendor/dep", "https://example.com/dep.git", RepoSpec::new().commit("Dep", [Change::write("dep.txt", "dep\n")]))
+}
+
+#[test]
+fn builds_are_deterministic() {
+    let a = history().build_temp("det-a").unwrap();
+    let b = history().build_temp("det-b").unwrap();
+    assert_eq!(agree(&a, log), agree(&b, log));
+}
+
+#[test]
+fn history_fixture() {
+    let fixture = history().build_temp("history").unwrap();
+    let log = agree(&fixture, log);
+    let messages: Vec<_> = log.iter().map(|(_, m)| m.as_str()).collect();
+    assert_eq!(messages.len(), 6, "{messages:?}");
+    assert_eq!(messages[0], "Add submodule vendor/dep");
+    assert_eq!(messages[1], "Merge branch 'topic'");
+
+    let tags = agree(&fixture, |vcs| vcs.list_tags().unwrap());
+    assert_eq!(tags.iter().map(|t| (t.name.as_str(), t.annotated)).collect::<Vec<_>>(), [("v0.1", false), ("v1.0", true)]);
+    assert_eq!(tags[1].target, log[1].0);
+
+    let branches = agree(&fixture, |vcs| {
+        let mut b: Vec<_> = vcs.branches().unwrap().into_iter().map(|b| (b.name, b.current)).collect();
+        b.sort();
+        b
+    });
+    assert_eq!(branches, [("main".to_string(), true), ("topic".to_string(), false)]);
+    assert_eq!(agree(&fixture, status), []);
+}
+
+#[test]
+fn conflict_fixture() {
+    let fixture = RepoSpec::new()
+        .commit("Base", [Change::write("shared.txt", "base\n")])
+        .branch("topic")
+        .commit("Topic", [Change::write("shared.txt", "topic\n")])
+        .switch("main")
+        .commit("Main", [Change::write("shared.txt", "main\n"), Change::write("other.txt", "o\n")])
+        .conflict("topic")
+        .modify([Change::write("untracked.txt", "u\n")])
+        .build_temp("conflict")
+        .unwrap();
+    let files = agree(&fixture, status);
+    assert_eq!(files, [("shared.txt".to_string(), "U".to_string()), ("untracked.txt".to_string(), "A".to_string())]);
+}
+
+#[test]
+fn demo_fixture() {
+    let fixture = openvcs_testkit::demo().build_temp("demo").unwrap();
+    assert!(!agree(&fixture, status).is_empty());
+    assert!(agree(&fixture, log).len() > 5);
+}
@@ -0,0 +1,167 @@
+//! Long-lived `git cat-file --batch` / `--batch-check` channels.
+//!
+//! Reading a blob or an object's size used to spawn one git process per object, which is
+//! most of the cost for views doing hundreds of lookups (diffstats, file trees, the
+//! time-machine slider). Each repository instead keeps one `cat-file` child per mode,
+//! started on first use, and talks to it over its pipes: one object name per line in, a
+//! `<oid> <type> <size>` header (plus the contents for `--batch`) out. A child that died or
+//! answered out of protocol is restarted once before the lookup fails.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Stdio};
+use std::sync::Mutex;
+
+use openvcs_core::{Result, VcsError};
+
+use crate::git_command;
+
+/// Header of an object as `cat-file` reports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ObjectInfo {
+    pub oid: String,
+    /// `blob`, `tree`, `commit` or `tag`.
+    pub kind: String,
+    pub size: u64,
+}
+
+#[derive(Clone, Copy)]
+enum Mode {
+    /// `--batch-check`: headers only.
+    Check,
+    /// `--batch`: headers followed by contents.
+    Contents,
+}
+
+impl Mode {
+    fn arg(self) -> &'static str {
+        match self {
+            Mode::Check => "--batch-check",
+            Mode::Contents => "--batch",
+        }
+    }
+}
+
+struct Channel {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Channel {
+    fn spawn(workdir: &Path, mode: Mode) -> Result<Self> {
+        log::debug!("git(cat-file): starting {} in {}", mode.arg(), workdir.display());
+        let mut child = git_command(Some(workdir))
+            .args(["cat-file", mode.arg()])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(VcsError::Io)?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(VcsError::Io(broken("cat-file has no pipes")));
+        };
+        Ok(Self { child, stdin, stdout: BufReader::new(stdout) })
+    }
+
+    /// Ask for `name`; `None` when it names no object. The contents are read for `--batch`.
+    fn query(&mut self, name: &str, mode: Mode) -> std::io::Result<Option<(ObjectInfo, Vec<u8>)>> {
+        writeln!(self.stdin, "{name}")?;
+        self.stdin.flush()?;
+
+        let mut header = String::new();
+        if self.stdout.read_line(&mut header)? == 0 {
+            return Err(broken("cat-file exited"));
+        }
+        let header = header.trim_end();
+        if header.ends_with(" missing") || header.ends_with(" ambiguous") {
+            return Ok(None);
+        }
+        let mut fields = header.splitn(3, ' ');
+        let info = match (fields.next(), fields.next(), fields.next()) {
+            (Some(oid), Some(kind), Some(size)) => ObjectInfo {
+                oid: oid.to_string(),
+                kind: kind.to_string(),
+                size: size.parse().map_err(|_| broken("bad cat-file header"))?,
+            },
+            _ => return Err(broken("bad cat-file header")),
+        };
+        let mut contents = Vec::new();
+        if let Mode::Contents = mode {
+            // Contents are followed by a newline
+            contents.resize(info.size as usize + 1, 0);
+            self.stdout.read_exact(&mut contents)?;
+            if contents.pop() != Some(b'\n') {
+                return Err(broken("bad cat-file contents"));
+            }
+        }
+        Ok(Some((info, contents)))
+    }
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        // Closing stdin ends git; kill in case it is stuck mid-answer
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn broken(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::BrokenPipe, msg)
+}
+
+/// The `cat-file` children of one repository, started on demand.
+pub(crate) struct CatFile {
+    workdir: PathBuf,
+    check: Mutex<Option<Channel>>,
+    contents: Mutex<Option<Channel>>,
+}
+
+impl CatFile {
+    pub fn new(workdir: &Path) -> Self {
+        Self { workdir: workdir.to_path_buf(), check: Mutex::new(None), contents: Mutex::new(None) }
+    }
+
+    /// Type and size of the object `name` (an id or any revision), `None` if there is none.
+    pub fn info(&self, name: &str) -> Result<Option<ObjectInfo>> {
+        Ok(self.query(name, Mode::Check)?.map(|(info, _)| info))
+    }
+
+    /// Header and contents of the object `name`, `None` if there is none.
+    pub fn read(&self, name: &str) -> Result<Option<(ObjectInfo, Vec<u8>)>> {
+        self.query(name, Mode::Contents)
+    }
+
+    fn query(&self, name: &str, mode: Mode) -> Result<Option<(ObjectInfo, Vec<u8>)>> {
+        // One name per line, and nothing git could read as an option
+        if name.is_empty() || name.contains(['\n', '\r']) || name.starts_with('-') {
+            return Err(VcsError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid object name")));
+        }
+        let slot = match mode {
+            Mode::Check => &self.check,
+            Mode::Contents => &self.contents,
+        };
+        let mut slot = slot.lock().unwrap_or_else(|p| p.into_inner());
+        let mut restarted = false;
+        loop {
+            let mut channel = match slot.take() {
+                Some(c) => c,
+                None => Channel::spawn(&self.workdir, mode)?,
+            };
+            match channel.query(name, mode) {
+                Ok(answer) => {
+                    *slot = Some(channel);
+                    return Ok(answer);
+                }
+                Err(e) if restarted => return Err(VcsError::Io(e)),
+                Err(e) => {
+                    log::debug!("git(cat-file): {} failed ({e}), restarting", mode.arg());
+                    restarted = true;
+                }
+            }
+        }
+    }
+}
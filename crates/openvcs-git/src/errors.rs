@@ -0,0 +1,140 @@
+//! Map git's stderr output onto structured `VcsError`s.
+//!
+//! git reports most failures only as text, so this matches the stable parts of its
+//! messages (never localized: every invocation runs with `LC_ALL=C`, see `git_command`).
+
+use openvcs_core::VcsError;
+
+use crate::GIT_SYSTEM_ID;
+
+/// Build the error for a failed git invocation: a structured variant when the
+/// message is recognised, otherwise `Backend` carrying the trimmed stderr.
+pub(crate) fn from_failure(stderr: &str, status: std::process::ExitStatus) -> VcsError {
+    if let Some(e) = classify(stderr) {
+        log::debug!("git: classified failure as {}", e.kind());
+        return e;
+    }
+    let msg = stderr.trim();
+    let msg = if msg.is_empty() { format!("git exited with {status}") } else { msg.to_string() };
+    VcsError::Backend { backend: GIT_SYSTEM_ID, msg }
+}
+
+/// Recognise common git failure messages.
+pub(crate) fn classify(stderr: &str) -> Option<VcsError> {
+    let lower = stderr.to_ascii_lowercase();
+    let has = |needle: &str| lower.contains(needle);
+
+    // Lock file held: "fatal: Unable to create '/repo/.git/index.lock': File exists."
+    if has(".lock': file exists") || has("another git process seems to be running") {
+        let path = stderr
+            .split('\'')
+            .find(|seg| seg.ends_with(".lock"))
+            .unwrap_or_default()
+            .to_string();
+        return Some(VcsError::LockHeld { path });
+    }
+
+    // "fatal: detected dubious ownership in repository at '/srv/share/repo'"
+    if has("detected dubious ownership in repository at") {
+        let path = stderr
+            .lines()
+            .find_map(|l| l.split_once(" repository at '").and_then(|(_, rest)| rest.strip_suffix('\'')))
+            .unwrap_or_default()
+            .to_string();
+        return Some(VcsError::DubiousOwnership { path });
+    }
+
+    // Authentication (checked before network: auth failures also say "could not read from remote")
+    if has("authentication failed")
+        || has("permission denied (publickey")
+        || has("could not read username")
+        || has("could not read password")
+        || has("invalid username or password")
+        || has("terminal prompts disabled")
+        || has("host key verification failed")
+        || has("the requested url returned error: 403")
+        || has("the requested url returned error: 401")
+    {
+        return Some(VcsError::AuthFailed(first_line_with(stderr, &["fatal:", "error:", "remote:"])));
+    }
+
+    // Network
+    if has("could not resolve host")
+        || has("could not resolve hostname")
+        || has("connection timed out")
+        || has("connection refused")
+        || has("network is unreachable")
+        || has("no route to host")
+        || has("operation timed out")
+        || has("failed to connect to")
+        || has("could not read from remote repository")
+    {
+        return Some(VcsError::NetworkUnreachable(first_line_with(stderr, &["fatal:", "ssh:", "error:"])));
+    }
+
+    // Push rejections
+    if has("[rejected]") || has("[remote rejected]") || has("failed to push some refs") {
+        if has("non-fast-forward") || has("(fetch first)") {
+            return Some(VcsError::NonFastForward);
+        }
+        return Some(VcsError::RemoteRejected { reason: first_line_with(stderr, &["! [", "remote:", "error:"]) });
+    }
+
+    // Local changes in the way of a checkout/merge/pull
+    if has("would be overwritten by") {
+        return Some(VcsError::DirtyWorktree { files: indented_paths_after(stderr, "would be overwritten by") });
+    }
+
+    // `reset --keep` over a locally changed file: "error: Entry 'a.txt' not uptodate. Cannot merge."
+    if has("not uptodate. cannot merge") {
+        let files = stderr
+            .lines()
+            .filter_map(|l| l.split_once("Entry '")?.1.split_once("' not uptodate").map(|(f, _)| f.to_string()))
+            .collect();
+        return Some(VcsError::DirtyWorktree { files });
+    }
+
+    // Merge conflicts
+    if has("conflict (") || has("automatic merge failed") {
+        let files = stderr
+            .lines()
+            .filter_map(|l| l.split_once("Merge conflict in ").map(|(_, f)| f.trim().to_string()))
+            .collect();
+        return Some(VcsError::MergeConflict { files });
+    }
+
+    if has("not possible to fast-forward") || has("diverging branches can't be fast-forwarded") {
+        return Some(VcsError::NonFastForward);
+    }
+
+    if has("you are not currently on a branch") {
+        return Some(VcsError::DetachedHead);
+    }
+
+    if has("not a git repository") {
+        return Some(VcsError::NotARepo(first_line_with(stderr, &["fatal:"])));
+    }
+
+    None
+}
+
+/// First line starting with one of `prefixes` (prefix stripped), or the first non-empty line.
+fn first_line_with(stderr: &str, prefixes: &[&str]) -> String {
+    let lines = || stderr.lines().map(str::trim).filter(|l| !l.is_empty());
+    lines()
+        .find_map(|l| prefixes.iter().find(|p| l.starts_with(**p)).map(|p| l[p.len()..].trim().to_string()))
+        .or_else(|| lines().next().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Paths git lists (tab-indented) after a header line containing `marker`.
+fn indented_paths_after(stderr: &str, marker: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .skip_while(|l| !l.contains(marker))
+        .skip(1)
+        .take_while(|l| l.starts_with('\t') || l.starts_with("    "))
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
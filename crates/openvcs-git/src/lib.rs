@@ -1,3 +1,6 @@
+mod cat_file;
+mod errors;
+
 use openvcs_core::*;
 use std::{
     io::{BufRead, BufReader},
@@ -5,15 +8,15 @@ use std::{
     process::{Command, Stdio},
     sync::Arc,
 };
-use openvcs_core::backend_descriptor::{BackendDescriptor, BACKENDS};
+use openvcs_core::backend_descriptor::BackendDescriptor;
 use openvcs_core::backend_id::BackendId;
-use openvcs_core::models::{BranchItem, BranchKind, Capabilities, CommitItem, FileEntry, LogQuery, OnEvent, StatusPayload, StatusSummary, VcsEvent};
+use openvcs_core::models::{BlobSize, BranchItem, BranchKind, BranchPage, BranchQuery, BranchScope, BranchSort, CommitOptions, CommitRecord, ConfigScope, ConfigValue, DivergedBranch, FetchSummary, FileChangeStat, FileRevision, HeadState, Identity, FastForward, MergeOptions, MergeOutcome, PathAttribute, ReflogEntry, RemoteItem, RemoteRef, RemoteRefKind, RemoteRefs, SignatureFormat, PickKind, SigningKey, StashEntry, StashOptions, StatusTotals, TagItem, TagVerification, Timestamp, WorktreeInfo, Capabilities, CommitItem, FileEntry, LogQuery, OnEvent, ProgressPhase, StatusOptions, StatusPayload, StatusSummary, VcsEvent, LINE_COUNT_MAX, dir_writable, normalize_prefix, parse_trailers};
 /* ============================ registry wiring ============================ */
 
 pub const GIT_SYSTEM_ID: BackendId = backend_id!("git-system");
 
 fn caps_static() -> Capabilities {
-    Capabilities { commits: true, branches: true, tags: true, staging: true, push_pull: true, fast_forward: true }
+    Capabilities { commits: true, branches: true, tags: true, staging: true, push_pull: true, fast_forward: true, worktree: true, writable: true }
 }
 
 fn open_factory(path: &Path) -> Result<Arc<dyn Vcs>> {
@@ -24,21 +27,120 @@ fn clone_factory(url: &str, dest: &Path, on: Option<OnEvent>) -> Result<Arc<dyn
     GitSystem::clone(url, dest, on).map(|v| Arc::new(v) as Arc<dyn Vcs>)
 }
 
-#[linkme::distributed_slice(BACKENDS)]
+fn init_factory(path: &Path) -> Result<Arc<dyn Vcs>> {
+    GitSystem::init(path).map(|v| Arc::new(v) as Arc<dyn Vcs>)
+}
+
+/// Registered with feature `backend` (default); without it the crate only provides
+/// [`git_command`] and friends.
+#[cfg_attr(feature = "backend", linkme::distributed_slice(openvcs_core::backend_descriptor::BACKENDS))]
 pub static GIT_SYS_DESC: BackendDescriptor = BackendDescriptor {
     id: GIT_SYSTEM_ID,
     name: "Git (system)",
     caps: caps_static,
+    version: git_version,
     open: open_factory,
     clone_repo: clone_factory,
+    init_repo: init_factory,
+    remote_refs: GitSystem::remote_refs,
 };
 
-const GIT_COMMAND_NAME: &'static str = "git";
+const GIT_COMMAND_NAME: &str = "git";
+
+/// Executable run instead of `git` from PATH, when one is configured.
+static EXECUTABLE: std::sync::RwLock<Option<PathBuf>> = std::sync::RwLock::new(None);
+
+/// Run `path` for every git invocation from now on; `None` goes back to `git` on PATH.
+pub fn set_executable(path: Option<PathBuf>) {
+    log::info!("git-system: executable {}", path.as_deref().unwrap_or(Path::new(GIT_COMMAND_NAME)).display());
+    *EXECUTABLE.write().unwrap_or_else(|p| p.into_inner()) = path;
+}
+
+/// The executable git invocations run.
+pub fn executable() -> PathBuf {
+    EXECUTABLE.read().unwrap_or_else(|p| p.into_inner()).clone().unwrap_or_else(|| PathBuf::from(GIT_COMMAND_NAME))
+}
+/// Changed files passed to `rev-list` as pathspecs; above this the whole trees are listed.
+const MAX_PATHSPECS: usize = 200;
+
+/// Variables that would point git at another repository than the one we run it in
+/// (inherited when the app is started from a hook or a `git` alias).
+const REPO_ENV: &[&str] = &[
+    "GIT_DIR",
+    "GIT_WORK_TREE",
+    "GIT_INDEX_FILE",
+    "GIT_OBJECT_DIRECTORY",
+    "GIT_ALTERNATE_OBJECT_DIRECTORIES",
+    "GIT_COMMON_DIR",
+    "GIT_NAMESPACE",
+    "GIT_PREFIX",
+];
+
+/// A git command run in `cwd`, set up the same way for every invocation: C locale so the
+/// output and error messages we parse are not translated, no pager, no advice hints, no
+/// terminal prompts, none of the parent's repository variables, and no console window on
+/// Windows. The app's own git probes (doctor, health checks) build theirs here too.
+pub fn git_command(cwd: Option<&Path>) -> Command {
+    let mut cmd = Command::new(executable());
+    if let Some(c) = cwd {
+        cmd.current_dir(c);
+    }
+    for var in REPO_ENV {
+        cmd.env_remove(var);
+    }
+    cmd.env("LC_ALL", "C")
+        .env_remove("LANGUAGE")
+        .env("GIT_PAGER", "cat")
+        .env("PAGER", "cat")
+        .env("GIT_ADVICE", "0");
+    no_window(&mut cmd);
+    cmd
+}
+
+/// Keep a console program from flashing a console window on Windows (a GUI app has none
+/// for it to share); a no-op elsewhere.
+pub fn no_window(cmd: &mut Command) -> &mut Command {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+/// Version of the git executable (e.g. `2.45.1`), or `None` if it can't run.
+pub fn git_version() -> Option<String> {
+    let out = git_command(None).arg("--version").output().ok()?;
+    if !out.status.success() { return None; }
+    Some(version_from_output(&String::from_utf8_lossy(&out.stdout)))
+}
+
+/// The version in `git --version` output: `git version 2.45.1` → `2.45.1`.
+pub fn version_from_output(out: &str) -> String {
+    out.trim().trim_start_matches("git version ").to_string()
+}
+
+/// `(major, minor, patch)` of a git version (`2.45.1`, `2.39.3 (Apple Git-146)`,
+/// `2.44.0.windows.1`).
+pub fn parse_version(v: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = v.trim().split(|c: char| !c.is_ascii_digit());
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    Some((major, minor, patch))
+}
 
 /* ============================== implementation ============================== */
 
 pub struct GitSystem {
+    /// Top of the working tree, or the repository itself when bare.
     workdir: PathBuf,
+    bare: bool,
+    /// The working tree and repository could be written when opened.
+    writable: bool,
+    /// `cat-file` channels for object lookups.
+    objects: cat_file::CatFile,
 }
 
 impl GitSystem {
@@ -49,6 +151,163 @@ impl GitSystem {
         )))
     }
 
+    /// Whether `q` walks from HEAD and HEAD has no commits yet (`git log` fails there).
+    fn history_is_empty(&self, q: &LogQuery) -> bool {
+        matches!(q.rev.as_deref(), None | Some("HEAD"))
+            && Self::run_git_capture_any_exit(Some(&self.workdir), ["rev-parse", "-q", "--verify", "HEAD"])
+                .is_ok_and(|out| out.trim().is_empty())
+    }
+
+    /// Branches under `patterns` (`refs/heads`, `refs/remotes`), in `for-each-ref`
+    /// order or sorted by `sort` (a `--sort` key).
+    fn branch_refs(&self, patterns: &[&str], sort: Option<&str>) -> Result<Vec<BranchItem>> {
+        // name, short, head flag, upstream tracking ("[gone]", "[ahead 1]", …)
+        let mut args = vec!["for-each-ref", "--format=%(refname)\t%(refname:short)\t%(HEAD)\t%(upstream:track)"];
+        let sort_arg = sort.map(|key| format!("--sort={key}"));
+        if let Some(a) = &sort_arg { args.push(a); }
+        args.extend_from_slice(patterns);
+        let out = Self::run_git_capture(Some(&self.workdir), args)?;
+        let mut descriptions = self.branch_config("description").unwrap_or_default();
+
+        let mut items = Vec::new();
+        for line in out.lines() {
+            let mut parts = line.split('\t');
+            let full = parts.next().unwrap_or("");
+            let short = parts.next().unwrap_or("").to_string();
+            let head_flag = parts.next().unwrap_or("");
+            let track = parts.next().unwrap_or("");
+
+            if full.is_empty() || short.is_empty() { continue; }
+
+            if full.starts_with("refs/heads/") {
+                let current = head_flag == "*";
+                let description = descriptions.remove(&short);
+                items.push(BranchItem {
+                    name: short,
+                    full_ref: full.to_string(),
+                    kind: BranchKind::Local,
+                    current,
+                    upstream_gone: track == "[gone]",
+                    description,
+                    notes: None,
+                });
+            } else if let Some(after) = full.strip_prefix("refs/remotes/") {
+                // refs/remotes/<remote>/<branch>
+                // filter origin/HEAD
+                if full.ends_with("/HEAD") { continue; }
+                let remote = after.split('/').next().unwrap_or("").to_string();
+
+                items.push(BranchItem {
+                    name: short,                     // e.g., "origin/feature"
+                    full_ref: full.to_string(),      // full ref
+                    kind: BranchKind::Remote { remote },
+                    current: false,
+                    upstream_gone: false,
+                    description: None,
+                    notes: None,
+                });
+            }
+        }
+        Ok(items)
+    }
+
+    /// Commits (ahead, behind) relative to `@{upstream}`; zeros when there is no upstream.
+    fn ahead_behind(&self) -> (u32, u32) {
+        let (mut behind, mut ahead) = (0u32, 0u32);
+        if let Ok(ab) = Self::run_git_capture(Some(&self.workdir), ["rev-list", "--left-right", "--count", "@{upstream}...HEAD"]) {
+            let mut parts = ab.split_whitespace();
+            if let (Some(b), Some(a)) = (parts.next(), parts.next()) {
+                behind = b.parse().unwrap_or(0);
+                ahead  = a.parse().unwrap_or(0);
+            }
+        }
+        (ahead, behind)
+    }
+
+    /// `(full_ref, oid)` of `remote`'s tracking branches and of all tags.
+    fn fetched_refs(&self, remote: &str) -> Result<Vec<(String, String)>> {
+        let out = Self::run_git_capture(
+            Some(&self.workdir),
+            ["for-each-ref", "--format=%(refname) %(objectname)", &format!("refs/remotes/{remote}/"), "refs/tags/"],
+        )?;
+        Ok(out
+            .lines()
+            .filter_map(|l| l.split_once(' '))
+            .filter(|(r, _)| !r.ends_with("/HEAD"))
+            .map(|(r, o)| (r.to_string(), o.to_string()))
+            .collect())
+    }
+
+    /// Local branches that are both ahead of and behind their upstream.
+    fn diverged_branches(&self) -> Result<Vec<DivergedBranch>> {
+        let out = Self::run_git_capture(
+            Some(&self.workdir),
+            ["for-each-ref", "--format=%(refname:short)\t%(upstream:short)\t%(upstream:track,nobracket)", "refs/heads/"],
+        )?;
+        let count = |track: &str, key: &str| {
+            track.split(", ").find_map(|p| p.strip_prefix(key)).and_then(|n| n.trim().parse().ok()).unwrap_or(0)
+        };
+        Ok(out
+            .lines()
+            .filter_map(|l| {
+                let mut parts = l.split('\t');
+                let (branch, upstream, track) = (parts.next()?, parts.next()?, parts.next().unwrap_or(""));
+                let (ahead, behind) = (count(track, "ahead "), count(track, "behind "));
+                (ahead > 0 && behind > 0).then(|| DivergedBranch {
+                    branch: branch.to_string(),
+                    upstream: upstream.to_string(),
+                    ahead,
+                    behind,
+                })
+            })
+            .collect())
+    }
+
+    /// Fail with `DirtyWorktree` if fast-forwarding to `target` would overwrite local
+    /// changes (tracked or untracked). Not checked when `target` is not a fast-forward.
+    fn check_clobbered(&self, target: &str) -> Result<()> {
+        let wd = Some(self.workdir.as_path());
+        if Self::run_git(wd, ["merge-base", "--is-ancestor", "HEAD", target]).is_err() {
+            return Ok(());
+        }
+        let split = |s: String| s.split('\0').filter(|p| !p.is_empty()).map(str::to_string).collect::<Vec<_>>();
+        let incoming = split(Self::run_git_capture(wd, ["diff", "--name-only", "-z", "HEAD", target])?);
+        if incoming.is_empty() {
+            return Ok(());
+        }
+        let mut local = split(Self::run_git_capture(wd, ["diff", "--name-only", "-z", "HEAD"])?);
+        local.extend(split(Self::run_git_capture(wd, ["ls-files", "--others", "--exclude-standard", "-z"])?));
+        let files: Vec<String> = local.into_iter().filter(|f| incoming.contains(f)).collect();
+        if files.is_empty() {
+            Ok(())
+        } else {
+            log::warn!("git-system: pull would overwrite {} local change(s)", files.len());
+            Err(VcsError::DirtyWorktree { files })
+        }
+    }
+
+    /// `git stash apply|pop` of entry `index`; conflicts become `MergeConflict` (a popped
+    /// entry is then kept, as git does).
+    fn stash_reapply(&self, verb: &str, index: usize, restore_index: bool) -> Result<()> {
+        let stash = format!("stash@{{{index}}}");
+        log::info!("git-system: stash {verb} {stash} (index: {restore_index})");
+        let mut args = vec!["stash", verb];
+        if restore_index {
+            args.push("--index");
+        }
+        args.push(stash.as_str());
+        let (ok, report) = Self::run_git_report(Some(&self.workdir), args)?;
+        if ok {
+            return Ok(());
+        }
+        let files = self.conflicted_files()?;
+        if files.is_empty() {
+            let msg = report.trim().to_string();
+            return Err(errors::classify(&msg).unwrap_or(VcsError::Backend { backend: GIT_SYSTEM_ID, msg }));
+        }
+        Err(VcsError::MergeConflict { files })
+    }
+
     fn run_git<I, S>(cwd: Option<&Path>, args: I) -> Result<()>
     where
         I: IntoIterator<Item = S>,
@@ -61,21 +320,22 @@ impl GitSystem {
             argv.join(" ")
         );
 
-        let mut cmd = Command::new(GIT_COMMAND_NAME);
-        if let Some(c) = cwd { cmd.current_dir(c); }
-        let status = cmd
+        let mut cmd = git_command(cwd);
+        // Capture (rather than inherit) stdio so failures carry git's own message.
+        let out = cmd
             .args(&argv)
             // Disable interactive terminal prompts; rely on ssh-agent or fail fast
             .env("GIT_SSH_COMMAND", "ssh -oBatchMode=yes")
-            .env("GIT_TERMINAL_PROMPT", "0")
-            .status()
+            .stdin(Stdio::null())
+            .output()
             .map_err(VcsError::Io)?;
-        if status.success() {
+        if out.status.success() {
             log::trace!("git(run): exit=0");
             Ok(())
         } else {
-            log::debug!("git(run): exit={}", status);
-            Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: format!("git exited with {status}") })
+            let err = String::from_utf8_lossy(&out.stderr);
+            log::debug!("git(run): exit={}, stderr={}", out.status, err.trim());
+            Err(errors::from_failure(&err, out.status))
         }
     }
 
@@ -91,12 +351,10 @@ impl GitSystem {
             argv.join(" ")
         );
 
-        let mut cmd = Command::new(GIT_COMMAND_NAME);
-        if let Some(c) = cwd { cmd.current_dir(c); }
+        let mut cmd = git_command(cwd);
         let out = cmd
             .args(&argv)
             .env("GIT_SSH_COMMAND", "ssh -oBatchMode=yes")
-            .env("GIT_TERMINAL_PROMPT", "0")
             .output()
             .map_err(VcsError::Io)?;
         if out.status.success() {
@@ -106,10 +364,37 @@ impl GitSystem {
         } else {
             let err = String::from_utf8_lossy(&out.stderr).into_owned();
             log::debug!("git(capture): exit={}, stderr_bytes={}", out.status, err.len());
-            Err(VcsError::Backend {
-                backend: GIT_SYSTEM_ID,
-                msg: err,
-            })
+            Err(errors::from_failure(&err, out.status))
+        }
+    }
+
+    /// `run_git_capture` with extra environment (`GIT_INDEX_FILE` for a scratch index, …).
+    fn run_git_capture_env<I, S>(cwd: Option<&Path>, args: I, env: &[(&str, &std::ffi::OsStr)]) -> Result<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let argv: Vec<String> = args.into_iter().map(|s| s.as_ref().to_string()).collect();
+        log::trace!(
+            "git(capture-env): cwd={}, argv=[{}], env=[{}]",
+            cwd.map(|p| p.display().to_string()).unwrap_or_else(|| ".".into()),
+            argv.join(" "),
+            env.iter().map(|(k, _)| *k).collect::<Vec<_>>().join(" ")
+        );
+
+        let mut cmd = git_command(cwd);
+        let out = cmd
+            .args(&argv)
+            .envs(env.iter().copied())
+            .stdin(Stdio::null())
+            .output()
+            .map_err(VcsError::Io)?;
+        if out.status.success() {
+            Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+        } else {
+            let err = String::from_utf8_lossy(&out.stderr).into_owned();
+            log::debug!("git(capture-env): exit={}, stderr={}", out.status, err.trim());
+            Err(errors::from_failure(&err, out.status))
         }
     }
 
@@ -127,12 +412,10 @@ impl GitSystem {
             argv.join(" ")
         );
 
-        let mut cmd = Command::new(GIT_COMMAND_NAME);
-        if let Some(c) = cwd { cmd.current_dir(c); }
+        let mut cmd = git_command(cwd);
         let out = cmd
             .args(&argv)
             .env("GIT_SSH_COMMAND", "ssh -oBatchMode=yes")
-            .env("GIT_TERMINAL_PROMPT", "0")
             .output()
             .map_err(VcsError::Io)?;
         let s = String::from_utf8_lossy(&out.stdout).into_owned();
@@ -140,17 +423,66 @@ impl GitSystem {
         Ok(s)
     }
 
+    /// `run_git_capture` for lookups that exit 1 when there is nothing to report
+    /// (`config --get`): `None` then; other failures are errors.
+    fn run_git_capture_lookup<I, S>(cwd: Option<&Path>, args: I) -> Result<Option<String>>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let argv: Vec<String> = args.into_iter().map(|s| s.as_ref().to_string()).collect();
+        log::trace!(
+            "git(lookup): cwd={}, argv=[{}]",
+            cwd.map(|p| p.display().to_string()).unwrap_or_else(|| ".".into()),
+            argv.join(" ")
+        );
+
+        let mut cmd = git_command(cwd);
+        let out = cmd.args(&argv).stdin(Stdio::null()).output().map_err(VcsError::Io)?;
+        match out.status.code() {
+            Some(0) => Ok(Some(String::from_utf8_lossy(&out.stdout).into_owned())),
+            Some(1) => Ok(None),
+            _ => {
+                let err = String::from_utf8_lossy(&out.stderr).into_owned();
+                log::debug!("git(lookup): exit={}, stderr={}", out.status, err.trim());
+                Err(errors::from_failure(&err, out.status))
+            }
+        }
+    }
+
+    /// Exit status and stderr, for commands that report on stderr and give their verdict
+    /// through the exit code (`verify-tag`).
+    fn run_git_report<I, S>(cwd: Option<&Path>, args: I) -> Result<(bool, String)>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let argv: Vec<String> = args.into_iter().map(|s| s.as_ref().to_string()).collect();
+        log::trace!(
+            "git(report): cwd={}, argv=[{}]",
+            cwd.map(|p| p.display().to_string()).unwrap_or_else(|| ".".into()),
+            argv.join(" ")
+        );
+
+        let mut cmd = git_command(cwd);
+        let out = cmd
+            .args(&argv)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(VcsError::Io)?;
+        log::trace!("git(report): exit={}", out.status);
+        Ok((out.status.success(), String::from_utf8_lossy(&out.stderr).into_owned()))
+    }
+
     fn run_git_with_input<I, S>(cwd: Option<&Path>, args: I, input: &str) -> Result<()>
     where
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        let mut cmd = Command::new(GIT_COMMAND_NAME);
-        if let Some(c) = cwd { cmd.current_dir(c); }
+        let mut cmd = git_command(cwd);
         let mut child = cmd
             .args(args.into_iter().map(|s| s.as_ref().to_string()))
             .env("GIT_SSH_COMMAND", "ssh -oBatchMode=yes")
-            .env("GIT_TERMINAL_PROMPT", "0")
             .stdin(Stdio::piped())
             .stdout(Stdio::null())
             .stderr(Stdio::piped())
@@ -164,54 +496,371 @@ impl GitSystem {
 
         let out = child.wait_with_output().map_err(VcsError::Io)?;
         if out.status.success() { Ok(()) } else {
-            Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: String::from_utf8_lossy(&out.stderr).into_owned() })
+            Err(errors::from_failure(&String::from_utf8_lossy(&out.stderr), out.status))
+        }
+    }
+
+    /// Stdout of a command fed `input` on stdin (`cat-file --batch-check`, `hash-object --stdin`).
+    fn run_git_capture_with_input<I, S>(cwd: Option<&Path>, args: I, input: &str) -> Result<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut cmd = git_command(cwd);
+        let mut child = cmd
+            .args(args.into_iter().map(|s| s.as_ref().to_string()))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(VcsError::Io)?;
+
+        // Write from another thread so a large answer can't block us on a full pipe
+        let writer = child.stdin.take().map(|mut stdin| {
+            let input = input.to_string();
+            std::thread::spawn(move || {
+                use std::io::Write;
+                let _ = stdin.write_all(input.as_bytes());
+            })
+        });
+        let out = child.wait_with_output().map_err(VcsError::Io)?;
+        if let Some(w) = writer { let _ = w.join(); }
+        if out.status.success() {
+            Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+        } else {
+            Err(errors::from_failure(&String::from_utf8_lossy(&out.stderr), out.status))
+        }
+    }
+
+    /// `worktree_file_stats`, limited to `pathspec`.
+    fn worktree_file_stats_in(&self, pathspec: Option<&str>) -> Result<Vec<FileChangeStat>> {
+        let base = self.head_or_empty_tree()?;
+        let mut stats = self.diff_stats(&["diff", &base], true, pathspec)?;
+
+        let untracked = self.untracked_files(pathspec)?;
+        for path in untracked.split('\0').filter(|p| !p.is_empty()) {
+            let Ok(meta) = std::fs::metadata(self.workdir.join(path)) else { continue };
+            let lines = if meta.len() > LINE_COUNT_MAX { None } else { count_lines(&self.workdir.join(path)) };
+            stats.push(FileChangeStat {
+                path: path.to_string(),
+                old_path: None,
+                status: "A".into(),
+                additions: lines,
+                deletions: lines.map(|_| 0),
+                size_delta: meta.len() as i64,
+                ..Default::default()
+            });
+        }
+        Ok(stats)
+    }
+
+    /// HEAD's commit, or the empty tree on an unborn branch (its id depends on the hash
+    /// algorithm).
+    fn head_or_empty_tree(&self) -> Result<String> {
+        let wd = Some(self.workdir.as_path());
+        match Self::run_git_capture(wd, ["rev-parse", "--verify", "--quiet", "HEAD"]) {
+            Ok(head) => Ok(head.trim().to_string()),
+            Err(_) => Ok(Self::run_git_capture_with_input(wd, ["hash-object", "-t", "tree", "--stdin"], "")?.trim().to_string()),
+        }
+    }
+
+    /// NUL-separated untracked, not ignored files, limited to `pathspec`.
+    fn untracked_files(&self, pathspec: Option<&str>) -> Result<String> {
+        let mut args = vec!["ls-files", "--others", "--exclude-standard", "-z"];
+        if let Some(spec) = pathspec {
+            args.extend(["--", spec]);
+        }
+        Self::run_git_capture(Some(&self.workdir), args)
+    }
+
+    /// Line and size changes of `git <diff_args> --raw --numstat -z -M [-- <pathspec>]`.
+    /// Sizes come from `cat-file --batch-check`; with `worktree` the new side is read from disk.
+    fn diff_stats(&self, diff_args: &[&str], worktree: bool, pathspec: Option<&str>) -> Result<Vec<FileChangeStat>> {
+        let wd = Some(self.workdir.as_path());
+        let mut args: Vec<&str> = vec!["-c", "core.quotePath=false"];
+        args.extend(diff_args);
+        args.extend(["--raw", "--numstat", "-z", "-M", "--no-abbrev", "--no-color"]);
+        if let Some(spec) = pathspec {
+            args.extend(["--", spec]);
+        }
+        let changes = parse_raw_numstat(&Self::run_git_capture(wd, args)?);
+
+        let is_null = |oid: &str| oid.bytes().all(|b| b == b'0');
+        let mut wanted: Vec<&str> = Vec::new();
+        for c in &changes {
+            wanted.extend([Some(c.old_oid.as_str()), (!worktree).then_some(c.new_oid.as_str())].into_iter().flatten().filter(|o| !is_null(o)));
+        }
+        let mut sizes = std::collections::HashMap::<String, i64>::new();
+        for oid in wanted {
+            if let Some(info) = self.objects.info(oid)? {
+                sizes.insert(info.oid, info.size as i64);
+            }
+        }
+        let size_of = |oid: &str| sizes.get(oid).copied().unwrap_or(0);
+
+        Ok(changes
+            .into_iter()
+            .map(|c| {
+                let new_size = if worktree {
+                    std::fs::symlink_metadata(self.workdir.join(&c.path)).map(|m| m.len() as i64).unwrap_or(0)
+                } else {
+                    size_of(&c.new_oid)
+                };
+                FileChangeStat {
+                    size_delta: new_size - size_of(&c.old_oid),
+                    path: c.path,
+                    old_path: c.old_path,
+                    status: c.status,
+                    additions: c.additions,
+                    deletions: c.deletions,
+                    old_mode: c.old_mode,
+                    new_mode: c.new_mode,
+                }
+            })
+            .collect())
+    }
+
+    /// Branches and tags advertised by `url` (`git ls-remote`), no repository needed.
+    pub fn remote_refs(url: &str) -> Result<RemoteRefs> {
+        if url.starts_with('-') {
+            return Err(VcsError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid remote URL")));
         }
+        log::debug!("git-system: ls-remote {url}");
+        let out = Self::run_git_capture(None, ["ls-remote", "--symref", url])?;
+        Ok(parse_ls_remote(&out))
     }
 
-    fn run_git_streaming<const N: usize>(cwd: &Path, args: [&str; N], on: Option<OnEvent>) -> Result<()> {
+    fn run_git_streaming<S: AsRef<str>>(cwd: &Path, args: impl IntoIterator<Item = S>, on: Option<OnEvent>) -> Result<()> {
+        let args: Vec<String> = args.into_iter().map(|a| a.as_ref().to_string()).collect();
         log::trace!(
             "git(stream): cwd={}, argv=[{}]",
             cwd.display(),
             args.join(" ")
         );
 
-        let mut cmd = Command::new(GIT_COMMAND_NAME);
-        cmd.current_dir(cwd)
-            .args(args)
+        let mut cmd = git_command(Some(cwd));
+        cmd.args(args)
             .env("GIT_SSH_COMMAND", "ssh -oBatchMode=yes")
-            .env("GIT_TERMINAL_PROMPT", "0")
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
         let mut child = cmd.spawn().map_err(VcsError::Io)?;
 
-        if let Some(stderr) = child.stderr.take() {
+        // Forward stderr as progress, keeping the tail so a failure can be classified.
+        const STDERR_TAIL: usize = 64;
+        const STDOUT_TAIL: usize = 16;
+        let stderr_reader = child.stderr.take().map(|stderr| {
             let on_clone = on.clone();
             std::thread::spawn(move || {
-                for line in BufReader::new(stderr).lines().flatten() {
+                let mut tail = std::collections::VecDeque::with_capacity(STDERR_TAIL);
+                for line in progress_lines(stderr) {
                     if let Some(cb) = &on_clone {
-                        cb(VcsEvent::Progress { phase: "git", detail: line });
+                        cb(progress_event(&line));
                     }
+                    if tail.len() == STDERR_TAIL { tail.pop_front(); }
+                    tail.push_back(line);
                 }
-            });
-        }
-        if let Some(stdout) = child.stdout.take() {
-            for line in BufReader::new(stdout).lines().flatten() {
-                if let Some(cb) = &on {
-                    cb(VcsEvent::Progress { phase: "git", detail: line });
+                Vec::from(tail).join("\n")
+            })
+        });
+        let stdout_reader = child.stdout.take().map(|stdout| {
+            let on_clone = on.clone();
+            std::thread::spawn(move || {
+                // stdout matters for classification too (`push --porcelain` reports rejections there)
+                let mut tail = std::collections::VecDeque::with_capacity(STDOUT_TAIL);
+                for line in BufReader::new(stdout).lines().map_while(std::result::Result::ok) {
+                    if let Some(cb) = &on_clone {
+                        cb(VcsEvent::Progress { phase: ProgressPhase::Other, percent: None, detail: line.clone() });
+                    }
+                    if tail.len() == STDOUT_TAIL { tail.pop_front(); }
+                    tail.push_back(line);
                 }
-            }
-        }
+                Vec::from(tail).join("\n")
+            })
+        });
 
-        let status = child.wait().map_err(VcsError::Io)?;
+        // Poll rather than block in wait(), so a cancelled job can kill the child.
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(VcsError::Io)? {
+                break status;
+            }
+            if cancel::is_cancelled() {
+                log::info!("git(stream): cancelled; killing git");
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(VcsError::Cancelled);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        };
+        let stdout_tail = stdout_reader.and_then(|h| h.join().ok()).unwrap_or_default();
+        let stderr_tail = stderr_reader.and_then(|h| h.join().ok()).unwrap_or_default();
         if status.success() {
             log::trace!("git(stream): exit=0");
             Ok(())
         } else {
             log::debug!("git(stream): exit={}", status);
-            Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: format!("git exited with {status}") })
+            Err(errors::from_failure(&format!("{stderr_tail}\n{stdout_tail}"), status))
+        }
+    }
+
+    /// Recreate commit `id` with `tree` and `parents`, keeping its author and message
+    /// (the committer is the current user, as with `git rebase`).
+    fn recommit(&self, id: &str, tree: &str, parents: &[String], message: Option<&str>) -> Result<String> {
+        let wd = Some(self.workdir.as_path());
+        let out = Self::run_git_capture(wd, ["show", "-s", "--date=raw", "--format=%an%x00%ae%x00%ad%x00%B", id])?;
+        let mut parts = out.splitn(4, '\0');
+        let (name, email, date) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+        let message = message.unwrap_or_else(|| parts.next().unwrap_or("")).trim_end();
+        let env: [(&str, &std::ffi::OsStr); 3] =
+            [("GIT_AUTHOR_NAME", name.as_ref()), ("GIT_AUTHOR_EMAIL", email.as_ref()), ("GIT_AUTHOR_DATE", date.as_ref())];
+        let mut args = vec!["commit-tree".to_string(), tree.to_string(), "-m".into(), message.to_string()];
+        for p in parents {
+            args.extend(["-p".into(), p.clone()]);
+        }
+        Ok(Self::run_git_capture_env(wd, args, &env)?.trim().to_string())
+    }
+
+    /// `NothingToCommit` when the index matches HEAD (git reports that on stdout), unless a
+    /// merge is being concluded: its commit may keep HEAD's tree.
+    fn ensure_staged(&self) -> Result<()> {
+        let merging = Self::run_git_report(Some(&self.workdir), ["rev-parse", "--quiet", "--verify", "MERGE_HEAD"])?.0;
+        // Exits 0 when there is no difference
+        if !merging && Self::run_git_report(Some(&self.workdir), ["diff", "--cached", "--quiet"])?.0 {
+            return Err(VcsError::NothingToCommit);
+        }
+        Ok(())
+    }
+
+    /// `git push` of `refspecs` to `remote`, reporting each ref's result as a push status.
+    fn push_refspecs(&self, remote: &str, refspecs: &[&str], options: &[String], on: Option<OnEvent>) -> Result<()> {
+        let mut args = vec!["push", "--progress", "--porcelain"];
+        for o in options {
+            args.extend(["--push-option", o.as_str()]);
+        }
+        args.push(remote);
+        args.extend(refspecs);
+        // Turn the porcelain per-ref lines into push statuses
+        let on = on.map(|cb| {
+            let remote = remote.to_string();
+            Arc::new(move |evt: VcsEvent| {
+                if let VcsEvent::Progress { phase: ProgressPhase::Other, detail, .. } = &evt
+                    && let Some((refname, status)) = parse_push_porcelain(detail)
+                {
+                    return cb(VcsEvent::PushStatus { remote: remote.clone(), refname, status });
+                }
+                cb(evt)
+            }) as OnEvent
+        });
+        Self::run_git_streaming(&self.workdir, args, on)
+    }
+
+    /// Paths with unmerged entries in the index.
+    fn conflicted_files(&self) -> Result<Vec<String>> {
+        let out = Self::run_git_capture(Some(&self.workdir), ["diff", "--name-only", "--diff-filter=U"])?;
+        Ok(out.lines().map(str::to_string).collect())
+    }
+
+    /// Full id of commit `rev`.
+    fn resolve_commit(&self, rev: &str) -> Result<String> {
+        let spec = format!("{rev}^{{commit}}");
+        match Self::run_git_capture(Some(&self.workdir), ["rev-parse", "--verify", "--quiet", spec.as_str()]) {
+            Ok(oid) if !rev.starts_with('-') => Ok(oid.trim().to_string()),
+            _ => Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: format!("unknown commit {rev}") }),
+        }
+    }
+}
+
+/// Parse `git ls-remote --symref` output into branches and tags. Peeled tag entries
+/// (`^{}`) and other namespaces (pull requests, notes, …) are dropped.
+fn parse_ls_remote(out: &str) -> RemoteRefs {
+    let mut res = RemoteRefs::default();
+    for line in out.lines() {
+        let Some((left, name)) = line.split_once('\t') else { continue };
+        if let Some(target) = left.strip_prefix("ref: ") {
+            if name == "HEAD" {
+                res.default_branch = target.strip_prefix("refs/heads/").map(str::to_string);
+            }
+            continue;
+        }
+        let (kind, short) = if let Some(b) = name.strip_prefix("refs/heads/") {
+            (RemoteRefKind::Branch, b)
+        } else if let Some(t) = name.strip_prefix("refs/tags/") && !t.ends_with("^{}") {
+            (RemoteRefKind::Tag, t)
+        } else {
+            continue;
+        };
+        res.refs.push(RemoteRef { name: short.to_string(), full_ref: name.to_string(), kind, oid: left.to_string() });
+    }
+    res.refs.sort_by(|a, b| (a.kind != RemoteRefKind::Branch, &a.name).cmp(&(b.kind != RemoteRefKind::Branch, &b.name)));
+    res
+}
+
+/// Parse one `list_tags` line: name, object type, id, peeled id, subject, tagger name and
+/// `<email>`, tagger date, committer date and peeled committer date, NUL-separated.
+fn parse_tag_line(line: &str) -> Option<TagItem> {
+    let f: Vec<&str> = line.split('\0').collect();
+    let [name, kind, oid, peeled, subject, tagger, email, tagged, committed, peeled_committed] = f[..] else {
+        return None;
+    };
+    let annotated = kind == "tag";
+    let item = if annotated {
+        TagItem {
+            name: name.to_string(),
+            target: if peeled.is_empty() { oid } else { peeled }.to_string(),
+            annotated,
+            summary: subject.to_string(),
+            tagger: (!tagger.is_empty()).then(|| Identity {
+                name: tagger.to_string(),
+                email: email.trim_start_matches('<').trim_end_matches('>').to_string(),
+            }),
+            time: Timestamp::parse(if tagged.is_empty() { peeled_committed } else { tagged }).unwrap_or_default(),
+        }
+    } else {
+        TagItem {
+            name: name.to_string(),
+            target: oid.to_string(),
+            time: Timestamp::parse(committed).unwrap_or_default(),
+            ..Default::default()
+        }
+    };
+    Some(item)
+}
+
+/// Parse a `git push --porcelain` ref line (`<flag>\t<from>:<to>\t<summary>`) into the
+/// destination ref and, for rejected refs (`!`), the reason.
+fn parse_push_porcelain(line: &str) -> Option<(String, Option<String>)> {
+    let mut parts = line.splitn(3, '\t');
+    let flag = parts.next()?;
+    let (_, to) = parts.next()?.split_once(':')?;
+    let summary = parts.next()?.trim();
+    if !matches!(flag, " " | "+" | "-" | "*" | "=" | "!") {
+        return None;
+    }
+    Some((to.to_string(), (flag == "!").then(|| summary.to_string())))
+}
+
+/// Split git's stderr into lines on `\n` *and* `\r`: progress meters redraw in place
+/// with carriage returns, so each update is its own line.
+fn progress_lines(r: impl std::io::Read) -> impl Iterator<Item = String> {
+    let mut bytes = std::io::Read::bytes(BufReader::new(r)).map_while(std::result::Result::ok).peekable();
+    std::iter::from_fn(move || {
+        bytes.peek()?;
+        let mut buf = Vec::new();
+        for b in bytes.by_ref() {
+            if b == b'\n' || b == b'\r' { break; }
+            buf.push(b);
         }
+        Some(String::from_utf8_lossy(&buf).into_owned())
+    })
+    .filter(|l| !l.trim().is_empty())
+}
+
+/// Map one stderr line onto the backend-neutral event model.
+fn progress_event(line: &str) -> VcsEvent {
+    match ProgressPhase::parse_line(line) {
+        (ProgressPhase::Other, _) if line.starts_with("remote:") => VcsEvent::RemoteMessage(line.to_string()),
+        (phase, percent) => VcsEvent::Progress { phase, percent, detail: line.to_string() },
     }
 }
 
@@ -219,13 +868,43 @@ impl Vcs for GitSystem {
     fn id(&self) -> BackendId { GIT_SYSTEM_ID }
 
     fn caps(&self) -> Capabilities {
-        Capabilities { commits: true, branches: true, tags: true, staging: true, push_pull: true, fast_forward: true }
+        let caps = if self.bare { caps_static().read_only() } else { caps_static() };
+        if self.writable { caps } else { caps.unwritable() }
     }
 
     fn open(path: &Path) -> Result<Self> {
         log::debug!("git-system: open {}", path.display());
-        let top = Self::run_git_capture(None, ["-C", Self::path_str(path)?, "rev-parse", "--show-toplevel"])?;
-        Ok(Self { workdir: PathBuf::from(top.trim()) })
+        let p = Self::path_str(path)?;
+        match Self::run_git_capture(None, ["-C", p, "rev-parse", "--show-toplevel", "--absolute-git-dir"]) {
+            Ok(facts) => {
+                let mut lines = facts.lines().map(str::trim);
+                let workdir = PathBuf::from(lines.next().unwrap_or(p));
+                let git_dir = lines.next().map_or_else(|| workdir.join(".git"), PathBuf::from);
+                let writable = dir_writable(&workdir) && dir_writable(&git_dir);
+                if !writable {
+                    log::info!("git-system: {} is not writable, opened read-only", workdir.display());
+                }
+                let objects = cat_file::CatFile::new(&workdir);
+                Ok(Self { workdir, bare: false, writable, objects })
+            }
+            // Bare or not, git refuses it until it is marked safe
+            Err(e @ VcsError::DubiousOwnership { .. }) => Err(e),
+            Err(e) => {
+                // No working tree: accept bare repositories (read-only), nothing else
+                let facts = Self::run_git_capture(None, ["-C", p, "rev-parse", "--is-bare-repository", "--absolute-git-dir"])
+                    .map_err(|_| e)?;
+                let mut lines = facts.lines();
+                if lines.next() != Some("true") {
+                    return Err(VcsError::NotARepo(path.display().to_string()));
+                }
+                let git_dir = lines.next().unwrap_or(p).trim();
+                log::info!("git-system: opened bare repository {git_dir} read-only");
+                let writable = dir_writable(Path::new(git_dir));
+                let workdir = PathBuf::from(git_dir);
+                let objects = cat_file::CatFile::new(&workdir);
+                Ok(Self { workdir, bare: true, writable, objects })
+            }
+        }
     }
 
     fn clone(url: &str, dest: &Path, on: Option<OnEvent>) -> Result<Self> {
@@ -235,58 +914,112 @@ impl Vcs for GitSystem {
         Self::open(dest)
     }
 
+    fn init(path: &Path) -> Result<Self> {
+        log::info!("git-system: init {}", path.display());
+        Self::run_git_capture(None, ["init", Self::path_str(path)?])?;
+        Self::open(path)
+    }
+
     fn workdir(&self) -> &Path { &self.workdir }
 
     fn current_branch(&self) -> Result<Option<String>> {
         log::trace!("git-system: current_branch in {}", self.workdir.display());
-        let out = Self::run_git_capture(Some(&self.workdir), ["rev-parse", "--abbrev-ref", "HEAD"])?;
+        // Unlike `rev-parse --abbrev-ref HEAD` this also names an unborn branch; exits 1 when detached.
+        let out = Self::run_git_capture_any_exit(Some(&self.workdir), ["symbolic-ref", "-q", "--short", "HEAD"])?;
         let s = out.trim();
-        Ok(if s == "HEAD" { None } else { Some(s.to_string()) })
+        Ok(if s.is_empty() { None } else { Some(s.to_string()) })
     }
 
-    fn branches(&self) -> Result<Vec<BranchItem>> {
-        log::trace!("git-system: branches in {}", self.workdir.display());
-        // name, short, head flag
-        let out = Self::run_git_capture(
-            Some(&self.workdir),
-            ["for-each-ref",
-                "--format=%(refname) %(refname:short) %(HEAD)",
-                "refs/heads", "refs/remotes"]
-        )?;
-
-        let mut items = Vec::new();
-        for line in out.lines() {
-            let mut parts = line.split_whitespace();
-            let full = parts.next().unwrap_or("");
-            let short = parts.next().unwrap_or("").to_string();
-            let head_flag = parts.next().unwrap_or("");
-
-            if full.is_empty() || short.is_empty() { continue; }
+    fn head_state(&self) -> Result<HeadState> {
+        log::trace!("git-system: head_state in {}", self.workdir.display());
+        // Both exit 1 (printing nothing) when HEAD is detached / unborn respectively.
+        let symref = Self::run_git_capture_any_exit(Some(&self.workdir), ["symbolic-ref", "-q", "HEAD"])?;
+        let commit = Self::run_git_capture_any_exit(Some(&self.workdir), ["rev-parse", "-q", "--verify", "HEAD^{commit}"])?;
+        let commit = commit.trim().to_string();
+        let branch = symref.trim().strip_prefix("refs/heads/").map(str::to_string);
+        Ok(match branch {
+            Some(branch) if commit.is_empty() => HeadState::Unborn { branch },
+            Some(name) => HeadState::Branch { name, commit },
+            None => {
+                let tags = Self::run_git_capture(Some(&self.workdir), ["tag", "--points-at", "HEAD", "--sort=-version:refname"])?;
+                HeadState::Detached { commit, tags: tags.lines().map(str::to_string).collect() }
+            }
+        })
+    }
 
-            if full.starts_with("refs/heads/") {
-                let current = head_flag == "*";
-                items.push(BranchItem {
-                    name: short,
-                    full_ref: full.to_string(),
-                    kind: BranchKind::Local,
-                    current,
-                });
-            } else if full.starts_with("refs/remotes/") {
-                // refs/remotes/<remote>/<branch>
-                // filter origin/HEAD
-                if full.ends_with("/HEAD") { continue; }
-                let after = &full["refs/remotes/".len()..];
-                let remote = after.split('/').next().unwrap_or("").to_string();
+    fn head_reflog(&self, limit: usize) -> Result<Vec<ReflogEntry>> {
+        // `log -g` shows each entry's new value; its old value is the next entry's new one.
+        // With `--date=raw` the selector is `HEAD@{<seconds> <offset>}`.
+        // Exits non-zero when HEAD has no reflog yet.
+        let n = format!("-n{}", limit.saturating_add(1));
+        let out = Self::run_git_capture_any_exit(
+            Some(&self.workdir),
+            ["log", "-g", "--no-decorate", "--date=raw", "--format=%H%x1f%gd%x1f%gs", n.as_str(), "HEAD"],
+        )?;
+        let rows: Vec<Vec<&str>> = out
+            .lines()
+            .map(|l| l.splitn(3, '\x1f').collect::<Vec<_>>())
+            .filter(|f| f.len() == 3)
+            .collect();
+        Ok(rows
+            .iter()
+            .enumerate()
+            .take(limit)
+            .map(|(i, f)| ReflogEntry {
+                old: rows.get(i + 1).map(|next| next[0].to_string()).unwrap_or_default(),
+                new: f[0].to_string(),
+                message: f[2].to_string(),
+                time: f[1]
+                    .split_once('{')
+                    .and_then(|(_, d)| Timestamp::parse(d.trim_end_matches('}')))
+                    .unwrap_or_default(),
+            })
+            .collect())
+    }
 
-                items.push(BranchItem {
-                    name: short,                     // e.g., "origin/feature"
-                    full_ref: full.to_string(),      // full ref
-                    kind: BranchKind::Remote { remote },
-                    current: false,
-                });
+    fn list_worktrees(&self) -> Result<Vec<WorktreeInfo>> {
+        log::trace!("git-system: list_worktrees in {}", self.workdir.display());
+        let out = Self::run_git_capture(Some(&self.workdir), ["worktree", "list", "--porcelain"])?;
+        let here = self.workdir.canonicalize().unwrap_or_else(|_| self.workdir.clone());
+        // Blank-line separated records of "key [value]" lines.
+        let mut list = Vec::new();
+        for record in out.split("\n\n") {
+            let mut wt = WorktreeInfo::default();
+            for line in record.lines() {
+                let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+                match key {
+                    "worktree" => wt.path = value.to_string(),
+                    "HEAD" if value.bytes().any(|b| b != b'0') => wt.head = Some(value.to_string()),
+                    "branch" => wt.branch = Some(value.strip_prefix("refs/heads/").unwrap_or(value).to_string()),
+                    "bare" => wt.bare = true,
+                    "locked" => wt.locked = Some(value.to_string()),
+                    "prunable" => wt.prunable = true,
+                    _ => {}
+                }
+            }
+            if wt.path.is_empty() {
+                continue;
             }
+            wt.current = Path::new(&wt.path).canonicalize().is_ok_and(|p| p == here);
+            list.push(wt);
         }
-        Ok(items)
+        Ok(list)
+    }
+
+    fn branches(&self) -> Result<Vec<BranchItem>> {
+        log::trace!("git-system: branches in {}", self.workdir.display());
+        self.branch_refs(&["refs/heads", "refs/remotes"], None)
+    }
+
+    fn branch_page(&self, query: &BranchQuery) -> Result<BranchPage> {
+        log::trace!("git-system: branch_page {:?} in {}", query, self.workdir.display());
+        let patterns: &[&str] = match query.kind {
+            BranchScope::All => &["refs/heads", "refs/remotes"],
+            BranchScope::Local => &["refs/heads"],
+            BranchScope::Remote => &["refs/remotes"],
+        };
+        let sort = (query.sort == BranchSort::Recent).then_some("-committerdate");
+        Ok(query.page(self.branch_refs(patterns, sort)?))
     }
 
     fn local_branches(&self) -> Result<Vec<String>> {
@@ -294,12 +1027,23 @@ impl Vcs for GitSystem {
         Ok(out.lines().map(|l| l.trim().to_string()).filter(|s| !s.is_empty()).collect())
     }
 
-    fn create_branch(&self, name: &str, checkout: bool) -> Result<()> {
-        Self::run_git(Some(&self.workdir), ["branch", name])?;
+    fn create_branch(&self, name: &str, start_point: Option<&str>, checkout: bool) -> Result<()> {
+        log::info!("git-system: create_branch '{}' from {:?}", name, start_point);
+        let mut args = vec!["branch", name];
+        args.extend(start_point);
+        Self::run_git(Some(&self.workdir), args)?;
         if checkout { self.checkout_branch(name)?; }
         Ok(())
     }
 
+    fn checkout_tag(&self, name: &str) -> Result<()> {
+        log::info!("git-system: checkout_tag '{}'", name);
+        Self::run_git(
+            Some(&self.workdir),
+            ["-c", "advice.detachedHead=false", "checkout", "--detach", &format!("refs/tags/{name}")],
+        )
+    }
+
     fn checkout_branch(&self, name: &str) -> Result<()> {
         // 1) If local branch exists, just checkout
         if Self::run_git_capture(
@@ -344,9 +1088,78 @@ impl Vcs for GitSystem {
         }
 
         // 3) Fallback to a direct checkout (may detach if it's a commit)
+        if Self::run_git_capture(Some(&self.workdir), ["rev-parse", "--verify", "--quiet", &format!("{name}^{{commit}}")]).is_err() {
+            return Err(VcsError::NoSuchBranch(name.to_string()));
+        }
         Self::run_git(Some(&self.workdir), ["checkout", name])
     }
 
+    fn list_tags(&self) -> Result<Vec<TagItem>> {
+        // Tag objects carry the subject and tagger; lightweight tags take the commit's date
+        let format = [
+            "%(refname:strip=2)", "%(objecttype)", "%(objectname)", "%(*objectname)", "%(contents:subject)",
+            "%(taggername)", "%(taggeremail)", "%(taggerdate:raw)", "%(committerdate:raw)", "%(*committerdate:raw)",
+        ]
+        .join("%00");
+        let out = Self::run_git_capture(
+            Some(&self.workdir),
+            ["for-each-ref", "--sort=refname", &format!("--format={format}"), "refs/tags"],
+        )?;
+        let tags: Vec<TagItem> = out.lines().filter_map(parse_tag_line).collect();
+        log::debug!("git-system: {} tag(s)", tags.len());
+        Ok(tags)
+    }
+
+    fn create_tag(&self, name: &str, target: Option<&str>, message: Option<&str>, sign: Option<&SigningKey>) -> Result<()> {
+        if message.is_none() && sign.is_none() {
+            log::debug!("git-system: create lightweight tag {name}");
+            let mut args = vec!["tag", "--no-sign", name];
+            args.extend(target);
+            return Self::run_git(Some(&self.workdir), args);
+        }
+        let mut args: Vec<String> = Vec::new();
+        if let Some(k) = sign {
+            let (format, program_key) = match k.format {
+                SignatureFormat::Gpg => ("openpgp", "gpg.program"),
+                SignatureFormat::Ssh => ("ssh", "gpg.ssh.program"),
+            };
+            args.extend(["-c".into(), format!("gpg.format={format}")]);
+            if let Some(p) = k.program.as_deref().filter(|p| !p.is_empty()) {
+                args.extend(["-c".into(), format!("{program_key}={p}")]);
+            }
+        }
+        args.extend(["tag".into(), "-a".into(), "-m".into(), message.unwrap_or_default().into()]);
+        match sign {
+            Some(k) if !k.key.is_empty() => args.extend(["-u".into(), k.key.clone()]),
+            Some(_) => args.push("-s".into()),
+            None => args.push("--no-sign".into()),
+        }
+        args.push(name.into());
+        args.extend(target.map(str::to_string));
+        log::debug!("git-system: create tag {name} (signed: {})", sign.is_some());
+        Self::run_git(Some(&self.workdir), args)
+    }
+
+    fn delete_tag(&self, name: &str) -> Result<()> {
+        log::info!("git-system: delete tag {name}");
+        Self::run_git(Some(&self.workdir), ["tag", "-d", name])
+    }
+
+    fn verify_tag(&self, name: &str) -> Result<TagVerification> {
+        let full = format!("refs/tags/{name}");
+        let unsigned = TagVerification { tag: name.to_string(), ..Default::default() };
+        if Self::run_git_capture(Some(&self.workdir), ["cat-file", "-t", &full])?.trim() != "tag" {
+            return Ok(unsigned);
+        }
+        let object = Self::run_git_capture(Some(&self.workdir), ["cat-file", "tag", &full])?;
+        let Some(format) = TagVerification::detect_format(&object) else { return Ok(unsigned) };
+
+        let (ok, report) = Self::run_git_report(Some(&self.workdir), ["verify-tag", "--raw", &full])?;
+        let v = TagVerification::from_verifier_output(name, format, ok, &report);
+        log::debug!("git-system: verify tag {name} → valid={}, signer={:?}", v.valid, v.signer);
+        Ok(v)
+    }
+
     fn ensure_remote(&self, name: &str, url: &str) -> Result<()> {
         let remotes = Self::run_git_capture(Some(&self.workdir), ["remote"])?;
         if remotes.lines().any(|r| r.trim() == name) {
@@ -356,18 +1169,36 @@ impl Vcs for GitSystem {
         }
     }
 
-    fn list_remotes(&self) -> Result<Vec<(String, String)>> {
+    fn list_remotes(&self) -> Result<Vec<RemoteItem>> {
         log::trace!("git-system: list_remotes");
-        // List names first, then resolve fetch URL for each
-        let out = Self::run_git_capture(Some(&self.workdir), ["remote"])?;
-        let mut items = Vec::new();
-        for name in out.lines().map(|l| l.trim()).filter(|s| !s.is_empty()) {
-            // Prefer fetch URL; if multiple, git remote get-url returns one (the default)
-            if let Ok(url) = Self::run_git_capture(Some(&self.workdir), ["remote", "get-url", name]) {
-                let u = url.trim();
-                if !u.is_empty() { items.push((name.to_string(), u.to_string())); }
+        // One call: "<name>\t<url> (fetch|push)" per line, fetch before push
+        let out = Self::run_git_capture(Some(&self.workdir), ["remote", "-v"])?;
+        let mut items: Vec<RemoteItem> = Vec::new();
+        for line in out.lines() {
+            let Some((name, rest)) = line.split_once('\t') else { continue };
+            let Some((url, kind)) = rest.trim_end().rsplit_once(' ') else { continue };
+            let (name, url) = (name.trim(), url.trim());
+            if name.is_empty() || url.is_empty() { continue; }
+
+            let idx = match items.iter().position(|r| r.name == name) {
+                Some(i) => i,
+                None => {
+                    items.push(RemoteItem { name: name.to_string(), fetch_url: String::new(), push_url: String::new() });
+                    items.len() - 1
+                }
+            };
+            // With several URLs git prints one line each; keep the first of each kind
+            let item = &mut items[idx];
+            match kind {
+                "(fetch)" if item.fetch_url.is_empty() => item.fetch_url = url.to_string(),
+                "(push)" if item.push_url.is_empty() => item.push_url = url.to_string(),
+                _ => {}
             }
         }
+        for r in &mut items {
+            if r.push_url.is_empty() { r.push_url = r.fetch_url.clone(); }
+        }
+        items.retain(|r| !r.fetch_url.is_empty());
         Ok(items)
     }
 
@@ -382,27 +1213,79 @@ impl Vcs for GitSystem {
         Self::run_git_streaming(&self.workdir, ["fetch", "--progress", remote, refspec], on)
     }
 
+    fn fetch_all(&self, remote: &str, prune: bool, on: Option<OnEvent>) -> Result<FetchSummary> {
+        log::info!("git-system: fetch {} (prune: {})", remote, prune);
+        let before = self.fetched_refs(remote)?;
+        let mut args = vec!["fetch", "--progress"];
+        if prune {
+            args.push("--prune");
+        }
+        args.push(remote);
+        Self::run_git_streaming(&self.workdir, args, on)?;
+        let mut summary = FetchSummary::between(remote, &before, &self.fetched_refs(remote)?);
+        summary.diverged = self.diverged_branches()?;
+        Ok(summary)
+    }
+
+    fn is_shallow(&self) -> Result<bool> {
+        let out = Self::run_git_capture(Some(&self.workdir), ["rev-parse", "--is-shallow-repository"])?;
+        Ok(out.trim() == "true")
+    }
+
+    fn unshallow(&self, remote: &str, on: Option<OnEvent>) -> Result<()> {
+        if !self.is_shallow()? {
+            log::debug!("git-system: unshallow: already complete");
+            return Ok(());
+        }
+        log::info!("git-system: fetch --unshallow {}", remote);
+        Self::run_git_streaming(&self.workdir, ["fetch", "--progress", "--unshallow", remote], on)
+    }
+
     fn push(&self, remote: &str, refspec: &str, on: Option<OnEvent>) -> Result<()> {
-        log::info!("git-system: push {} {}", remote, refspec);
-        Self::run_git_streaming(&self.workdir, ["push", "--progress", remote, refspec], on)
+        self.push_with_options(remote, refspec, &[], on)
+    }
+
+    fn push_with_options(&self, remote: &str, refspec: &str, options: &[String], on: Option<OnEvent>) -> Result<()> {
+        log::info!("git-system: push {} {} (options: {:?})", remote, refspec, options);
+        self.push_refspecs(remote, &[refspec], options, on)
+    }
+
+    fn push_tags(&self, remote: &str, tags: &[String], on: Option<OnEvent>) -> Result<()> {
+        log::info!("git-system: push tags {:?} to {}", tags, remote);
+        let refspecs: Vec<String> = if tags.is_empty() {
+            vec!["refs/tags/*:refs/tags/*".into()]
+        } else {
+            tags.iter().map(|t| format!("refs/tags/{t}:refs/tags/{t}")).collect()
+        };
+        let refspecs: Vec<&str> = refspecs.iter().map(String::as_str).collect();
+        self.push_refspecs(remote, &refspecs, &[], on)
     }
 
     fn pull_ff_only(&self, remote: &str, branch: &str, on: Option<OnEvent>) -> Result<()> {
-        // Prefer a single pull with ff-only for simplicity and to surface server messages
-        // Equivalent to: git fetch <remote> <branch>; git merge --ff-only <remote>/<branch>
-        // Using streaming to forward progress to the UI when available.
+        // git fetch <remote> <branch>; git merge --ff-only FETCH_HEAD, with the local changes
+        // checked in between so git never gets to print "would be overwritten" into progress.
         log::info!("git-system: pull --ff-only {} {}", remote, branch);
+        Self::run_git_streaming(&self.workdir, ["fetch", "--progress", remote, branch], on)?;
+        self.check_clobbered("FETCH_HEAD")?;
+        Self::run_git(Some(&self.workdir), ["merge", "--ff-only", "FETCH_HEAD"])
+    }
+
+    fn pull_ff_only_autostash(&self, remote: &str, branch: &str, on: Option<OnEvent>) -> Result<()> {
+        log::info!("git-system: pull --ff-only --autostash {} {}", remote, branch);
         Self::run_git_streaming(
             &self.workdir,
-            ["pull", "--ff-only", "--no-rebase", remote, branch],
+            ["pull", "--progress", "--ff-only", "--no-rebase", "--autostash", remote, branch],
             on,
-        )
+        )?;
+        // git succeeds even when re-applying the stash conflicts (and keeps the stash)
+        let files = self.conflicted_files()?;
+        if files.is_empty() { Ok(()) } else { Err(VcsError::MergeConflict { files }) }
     }
 
-    fn commit(&self, message: &str, name: &str, email: &str, paths: &[PathBuf]) -> Result<String> {
+    fn commit(&self, message: &str, name: &str, email: &str, paths: &[PathBuf], opts: &CommitOptions) -> Result<String> {
         log::info!(
-            "git-system: commit message_len={} author='{} <{}>' paths={}",
-            message.len(), name, email, paths.len()
+            "git-system: commit message_len={} committer='{} <{}>' author={:?} paths={}",
+            message.len(), name, email, opts.author.as_ref().map(|a| a.to_string()), paths.len()
         );
         Self::run_git(Some(&self.workdir), ["config", "user.name", name])?;
         Self::run_git(Some(&self.workdir), ["config", "user.email", email])?;
@@ -415,20 +1298,22 @@ impl Vcs for GitSystem {
             }
             Self::run_git(Some(&self.workdir), args)?;
         }
-        Self::run_git(Some(&self.workdir), ["commit", "-m", message, "--no-edit"])?;
+        self.ensure_staged()?;
+        Self::run_git(Some(&self.workdir), commit_args(message, opts))?;
         let sha = Self::run_git_capture(Some(&self.workdir), ["rev-parse", "HEAD"])?;
         Ok(sha.trim().to_string())
     }
 
-    fn commit_index(&self, message: &str, name: &str, email: &str) -> Result<String> {
+    fn commit_index(&self, message: &str, name: &str, email: &str, opts: &CommitOptions) -> Result<String> {
         // Set identity and commit whatever is currently staged in the index.
         log::info!(
-            "git-system: commit_index message_len={} author='{} <{}>'",
-            message.len(), name, email
+            "git-system: commit_index message_len={} committer='{} <{}>' author={:?}",
+            message.len(), name, email, opts.author.as_ref().map(|a| a.to_string())
         );
         Self::run_git(Some(&self.workdir), ["config", "user.name", name])?;
         Self::run_git(Some(&self.workdir), ["config", "user.email", email])?;
-        Self::run_git(Some(&self.workdir), ["commit", "-m", message, "--no-edit"])?;
+        self.ensure_staged()?;
+        Self::run_git(Some(&self.workdir), commit_args(message, opts))?;
         let sha = Self::run_git_capture(Some(&self.workdir), ["rev-parse", "HEAD"])?;
         Ok(sha.trim().to_string())
     }
@@ -440,14 +1325,12 @@ impl Vcs for GitSystem {
             if line.starts_with("? ") {
                 // Untracked file (porcelain v2)
                 s.untracked += 1;
-            } else if line.starts_with("1 ") {
-                // Ordinary changed entry: "1 XY ... <path>"
-                let code = &line[2..4];
-                match code {
-                    " M" | " T" | " D" | "MM" | "MT" | "MD" | "AM" | "AT" => s.modified += 1,
-                    "M " | "T " | "A " => s.staged += 1,
-                    _ => {}
-                }
+            } else if line.starts_with("1 ") || line.starts_with("2 ") {
+                // Changed ("1 XY …") or renamed/copied ("2 XY …") entry; v2 prints '.' for an
+                // unchanged side, so a new file in a repository without commits is "A.".
+                let code = line.as_bytes();
+                if code[2] != b'.' { s.staged += 1; }
+                if code[3] != b'.' { s.modified += 1; }
             } else if line.starts_with("u ") {
                 // Unmerged/conflicted entry
                 s.conflicted += 1;
@@ -456,128 +1339,231 @@ impl Vcs for GitSystem {
         Ok(s)
     }
 
-    fn status_payload(&self) -> Result<StatusPayload> {
-        // Per-file changes via porcelain v2
-        let out = Self::run_git_capture(Some(&self.workdir), ["status", "--porcelain=v2"])?;
-        let mut files = Vec::<FileEntry>::new();
-
-        for line in out.lines() {
-            if line.starts_with("? ") {
-                // Untracked; token after "?" is the path
-                if let Some(path) = line.split_whitespace().last() {
-                    files.push(FileEntry { path: path.to_string(), status: "A".into(), hunks: Vec::new() });
-                }
-            } else if line.starts_with("1 ") {
-                // Ordinary changed entry: "1 XY ... <path>"
-                let xy = &line[2..4];
-                let x = xy.chars().nth(0).unwrap_or(' ');
-                let y = xy.chars().nth(1).unwrap_or(' ');
-                let is_mod = |c: char| c == 'M' || c == 'T';
-                let status = if x == 'A' || y == 'A' {
-                    "A"
-                } else if x == 'D' || y == 'D' {
-                    "D"
-                } else if is_mod(x) || is_mod(y) {
-                    "M"
-                } else {
-                    // Default to Modified for any other ordinary change combo
-                    "M"
-                }.to_string();
-
-                if let Some(path) = line.split_whitespace().last() {
-                    files.push(FileEntry { path: path.to_string(), status, hunks: Vec::new() });
-                }
-            } else if line.starts_with("2 ") {
-                // Rename/copy record; mark as rename and use new path
-                if let Some(path) = line.split_whitespace().last() {
-                    files.push(FileEntry { path: path.to_string(), status: "R".into(), hunks: Vec::new() });
-                }
-            } else if line.starts_with("u ") {
-                // conflicted; last token is path
-                if let Some(path) = line.split_whitespace().last() {
-                    files.push(FileEntry { path: path.to_string(), status: "U".into(), hunks: Vec::new() });
-                }
+    fn promisor_remotes(&self) -> Result<Vec<String>> {
+        let out = Self::run_git_capture_any_exit(
+            Some(&self.workdir),
+            ["config", "--get-regexp", r"^(remote\..*\.promisor|extensions\.partialclone)$"],
+        )?;
+        let mut remotes: Vec<String> = Vec::new();
+        for (key, value) in out.lines().filter_map(|l| l.split_once(' ')) {
+            let remote = match key.strip_prefix("remote.").and_then(|k| k.strip_suffix(".promisor")) {
+                Some(name) if value.trim() == "true" => name,
+                Some(_) => continue,
+                None => value.trim(),
+            };
+            if !remote.is_empty() && !remotes.iter().any(|r| r == remote) {
+                remotes.push(remote.to_string());
             }
         }
+        Ok(remotes)
+    }
 
-        // ahead/behind: @{upstream}...HEAD
-        let (mut behind, mut ahead) = (0u32, 0u32);
-        if let Ok(ab) = Self::run_git_capture(Some(&self.workdir), ["rev-list", "--left-right", "--count", "@{upstream}...HEAD"]) {
-            let mut parts = ab.split_whitespace();
-            if let (Some(b), Some(a)) = (parts.next(), parts.next()) {
-                behind = b.parse().unwrap_or(0);
-                ahead  = a.parse().unwrap_or(0);
-            }
+    fn missing_blobs(&self, rev: &str) -> Result<Vec<String>> {
+        if rev.starts_with('-') {
+            return Err(VcsError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid revision")));
+        }
+        // Tree entries only: neither step reads a blob, so nothing gets fetched
+        let parent = format!("{rev}^1");
+        let has_parent = Self::run_git_capture(Some(&self.workdir), ["rev-parse", "--verify", "--quiet", &parent]).is_ok();
+        let raw = if has_parent {
+            Self::run_git_capture(Some(&self.workdir), ["diff-tree", "-r", "--no-renames", "--raw", "-z", &parent, rev])?
+        } else {
+            Self::run_git_capture(Some(&self.workdir), ["diff-tree", "-r", "--root", "--no-renames", "--no-commit-id", "--raw", "-z", rev])?
+        };
+        let changes = parse_raw_z(&raw);
+        if changes.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut args = vec!["rev-list".to_string(), "--objects".into(), "--missing=print".into(), "--no-walk".into(), rev.to_string()];
+        if has_parent {
+            args.push(parent);
+        }
+        if changes.len() <= MAX_PATHSPECS {
+            args.push("--".into());
+            args.extend(changes.iter().map(|(_, _, path)| format!(":(literal){path}")));
         }
+        let listed = Self::run_git_capture(Some(&self.workdir), args)?;
+        let missing: std::collections::HashSet<&str> = listed.lines().filter_map(|l| l.strip_prefix('?')).map(str::trim).collect();
+        Ok(changes
+            .into_iter()
+            .filter(|(old, new, _)| missing.contains(old.as_str()) || missing.contains(new.as_str()))
+            .map(|(_, _, path)| path)
+            .collect())
+    }
 
-        Ok(StatusPayload { files, ahead, behind })
+    fn commit_file_stats(&self, rev: &str) -> Result<Vec<FileChangeStat>> {
+        if rev.starts_with('-') {
+            return Err(VcsError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid revision")));
+        }
+        let parent = format!("{rev}^1");
+        let has_parent = Self::run_git_capture(Some(&self.workdir), ["rev-parse", "--verify", "--quiet", &parent]).is_ok();
+        if has_parent {
+            self.diff_stats(&["diff", &parent, rev], false, None)
+        } else {
+            self.diff_stats(&["diff-tree", "-r", "--root", "--no-commit-id", rev], false, None)
+        }
     }
 
-    fn log_commits(&self, q: &LogQuery) -> Result<Vec<CommitItem>> {
-        // Build: git log [rev?] [--topo-order] [--no-merges] --date=iso-strict
-        //        [--since=..] [--until=..] [--author=..] --skip=N --max-count=M
-        //        --pretty='...%x00...' [-- path]
-        let mut args: Vec<String> = vec!["log".into()];
+    fn worktree_file_stats(&self, scope: Option<&str>) -> Result<Vec<FileChangeStat>> {
+        self.worktree_file_stats_in(scope.map(literal_pathspec).as_deref())
+    }
 
-        if let Some(rev) = &q.rev {
-            args.push(rev.clone());
-        }
+    fn status_payload(&self, opts: &StatusOptions) -> Result<StatusPayload> {
+        let mut files = Vec::<FileEntry>::new();
+        let totals = self.status_stream(opts, usize::MAX, &mut |batch| files.extend(batch))?;
+        Ok(StatusPayload { files, ahead: totals.ahead, behind: totals.behind, ..Default::default() })
+    }
 
-        if q.topo_order {
-            args.push("--topo-order".into());
+    fn status_stream(&self, opts: &StatusOptions, batch: usize, on_batch: &mut dyn FnMut(Vec<FileEntry>)) -> Result<StatusTotals> {
+        // Explicit modes, so status.showUntrackedFiles does not decide what the UI gets;
+        // "matching" reports an ignored directory once instead of every file in it
+        let mut args = vec!["status", "--porcelain=v2", "-z"];
+        args.push(if opts.collapse_untracked_dirs { "--untracked-files=normal" } else { "--untracked-files=all" });
+        if opts.include_ignored {
+            args.push("--ignored=matching");
         }
-        if !q.include_merges {
-            args.push("--no-merges".into());
+        let spec = opts.scope().map(|dir| literal_pathspec(&dir));
+        if let Some(spec) = spec.as_deref() {
+            args.extend(["--", spec]);
         }
+        log::trace!("git(stream): cwd={}, argv=[{}]", self.workdir.display(), args.join(" "));
+        let batch = batch.max(1);
 
-        args.push("--date=iso-strict".into());
-        if let Some(s) = &q.since_utc {
-            args.push(format!("--since={s}"));
-        }
-        if let Some(u) = &q.until_utc {
-            args.push(format!("--until={u}"));
+        let mut child = git_command(Some(&self.workdir))
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(VcsError::Io)?;
+        let stderr_reader = child.stderr.take().map(|mut stderr| {
+            std::thread::spawn(move || {
+                let mut buf = String::new();
+                let _ = std::io::Read::read_to_string(&mut stderr, &mut buf);
+                buf
+            })
+        });
+
+        // NUL-terminated records; a rename record ("2 …") is followed by its original path.
+        let mut total = 0usize;
+        let mut pending = Vec::with_capacity(batch.min(1024));
+        if let Some(stdout) = child.stdout.take() {
+            let mut records = BufReader::new(stdout).split(0);
+            while let Some(Ok(rec)) = records.next() {
+                let rec = String::from_utf8_lossy(&rec);
+                if rec.starts_with("2 ") {
+                    let _orig = records.next();
+                }
+                if let Some(mut entry) = parse_porcelain_v2(&rec) {
+                    entry.count_dir_files(&self.workdir);
+                    pending.push(entry);
+                    total += 1;
+                    if pending.len() >= batch {
+                        on_batch(std::mem::take(&mut pending));
+                    }
+                }
+            }
         }
-        if let Some(a) = &q.author_contains {
-            args.push(format!("--author={a}"));
+        if !pending.is_empty() {
+            on_batch(pending);
         }
 
-        args.push(format!("--skip={}", q.skip));
-        args.push(format!("--max-count={}", q.limit));
+        let status = child.wait().map_err(VcsError::Io)?;
+        if !status.success() {
+            let stderr = stderr_reader.and_then(|h| h.join().ok()).unwrap_or_default();
+            return Err(errors::from_failure(&stderr, status));
+        }
 
-        // NUL-separated fields, one commit per line
-        args.push("--pretty=format:%H%x00%an <%ae>%x00%ad%x00%s".into());
+        let (ahead, behind) = self.ahead_behind();
+        Ok(StatusTotals { total, ahead, behind })
+    }
 
-        if let Some(p) = &q.path {
-            args.push("--".into());
-            args.push(p.clone());
+    fn log_commits(&self, q: &LogQuery) -> Result<Vec<CommitItem>> {
+        if self.history_is_empty(q) {
+            return Ok(Vec::new());
         }
-
-        let out = Self::run_git_capture(Some(&self.workdir), args)?;
+        let out = Self::run_git_capture(Some(&self.workdir), log_args(q, true))?;
         let mut items = Vec::with_capacity(q.limit as usize);
+        items.extend(out.lines().filter_map(parse_log_line));
+        Ok(items)
+    }
 
-        for line in out.lines() {
-            // Each line → one commit with NUL-separated fields
-            let mut parts = line.split('\0');
-            let id = parts.next().unwrap_or_default();
-            if id.is_empty() {
-                continue;
-            }
-            let author = parts.next().unwrap_or_default().to_string();
-            let when   = parts.next().unwrap_or_default().to_string();
-            let msg    = parts.next().unwrap_or_default().to_string();
+    fn history_walk(&self, q: &LogQuery) -> Result<Box<dyn HistoryWalk>> {
+        if self.history_is_empty(q) {
+            return Ok(Box::new(EmptyWalk));
+        }
+        let args = log_args(q, false);
+        log::trace!("git(walk): cwd={}, argv=[{}]", self.workdir.display(), args.join(" "));
+        let mut child = git_command(Some(&self.workdir))
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(VcsError::Io)?;
+        let lines = child.stdout.take().map(|out| BufReader::new(out).lines());
+        // Drained as it comes so a chatty git can't fill the pipe and stall the walk
+        let stderr = child.stderr.take().map(|mut e| {
+            std::thread::spawn(move || {
+                let mut text = String::new();
+                let _ = std::io::Read::read_to_string(&mut e, &mut text);
+                text
+            })
+        });
+        Ok(Box::new(LogWalk { child, lines, stderr }))
+    }
 
-            let short = &id[..id.len().min(7)];
-            let meta  = format!("{when} • {short}");
+    fn commit_records(&self, ids: &[String]) -> Result<Vec<CommitRecord>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Unit/record separators keep multi-line bodies intact
+        let mut args = vec![
+            "show".to_string(),
+            "-s".into(),
+            "--no-color".into(),
+            "--date=raw".into(),
+            "--format=%H%x1f%P%x1f%an%x1f%ae%x1f%ad%x1f%cn%x1f%ce%x1f%cd%x1f%s%x1f%b%x1e".into(),
+        ];
+        args.extend(ids.iter().cloned());
+        let out = Self::run_git_capture(Some(&self.workdir), args)?;
+        Ok(out.split('\x1e').filter_map(parse_commit_record).collect())
+    }
 
-            items.push(CommitItem {
-                id: id.to_string(),
-                msg,
-                meta,
-                author,
-            });
+    fn file_revisions(&self, path: &Path, limit: usize) -> Result<Vec<FileRevision>> {
+        let p = Self::path_str(path)?;
+        // `--raw` reports the blob after each change; with `--follow` the path may change
+        let mut args = vec![
+            "-c".to_string(),
+            "core.quotePath=false".into(),
+            "log".into(),
+            "--follow".into(),
+            "--no-merges".into(),
+            "--raw".into(),
+            "--no-abbrev".into(),
+            "--no-color".into(),
+            "--date=raw".into(),
+            "--format=%x1e%H%x1f%an%x1f%ad%x1f%s".into(),
+        ];
+        if limit > 0 {
+            args.push(format!("-n{limit}"));
         }
+        args.extend(["HEAD".into(), "--".into(), p.into()]);
+        let out = Self::run_git_capture(Some(&self.workdir), args)?;
+        let revs: Vec<FileRevision> = out.split('\x1e').filter_map(parse_file_revision).collect();
+        log::debug!("git-system: {} revision(s) of {p}", revs.len());
+        Ok(revs)
+    }
 
-        Ok(items)
+    fn read_blob(&self, oid: &str) -> Result<Vec<u8>> {
+        if oid.starts_with('-') {
+            return Err(VcsError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid object id")));
+        }
+        match self.objects.read(oid)? {
+            Some((info, contents)) if info.kind == "blob" => Ok(contents),
+            Some((info, _)) => Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: format!("{oid} is a {}, not a blob", info.kind) }),
+            None => Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: format!("no such blob: {oid}") }),
+        }
     }
 
     fn diff_file(&self, path: &Path) -> Result<Vec<String>> {
@@ -605,9 +1591,11 @@ impl Vcs for GitSystem {
         // Only if the file exists, otherwise return empty
         let abs = if path.is_absolute() { path.to_path_buf() } else { self.workdir.join(path) };
         if abs.exists() {
+            // Relative to the workdir, so the headers name the file as tracked diffs do.
+            let rel = abs.strip_prefix(&self.workdir).unwrap_or(&abs);
             let out_noindex = Self::run_git_capture_any_exit(Some(&self.workdir), [
                 "diff", "--no-color", "--unified=3", "--no-index", "--",
-                "/dev/null", Self::path_str(&abs)?
+                "/dev/null", Self::path_str(rel)?
             ])?;
             let sn = out_noindex.trim_end();
             if !sn.is_empty() {
@@ -618,6 +1606,34 @@ impl Vcs for GitSystem {
         Ok(Vec::new())
     }
 
+    fn diff_dir(&self, prefix: &str) -> Result<Vec<String>> {
+        log::trace!("git-system: diff_dir {prefix}");
+        let spec = normalize_prefix(prefix).map_or_else(|| ".".to_string(), |dir| literal_pathspec(&dir));
+        let wd = Some(self.workdir.as_path());
+        let base = self.head_or_empty_tree()?;
+        let diff = ["diff", "--no-color", "--unified=3", base.as_str(), "--", spec.as_str()];
+        if self.untracked_files(Some(&spec))?.is_empty() {
+            let out = Self::run_git_capture(wd, diff)?;
+            return Ok(out.trim_end().lines().map(str::to_string).collect());
+        }
+
+        // Untracked files as additions, like diff_file: marked intent-to-add in a scratch copy
+        // of the index, one diff shows them with the tracked changes. The copy goes to the temp
+        // directory, so this works in read-only repositories too.
+        static SCRATCH: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        let n = SCRATCH.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let scratch = std::env::temp_dir().join(format!("openvcs-diff-index-{}-{n}", std::process::id()));
+        let index = self.workdir.join(Self::run_git_capture(wd, ["rev-parse", "--git-path", "index"])?.trim());
+        if std::fs::copy(&index, &scratch).is_err() {
+            let _ = std::fs::remove_file(&scratch);
+        }
+        let env = [("GIT_INDEX_FILE", scratch.as_os_str())];
+        let out = Self::run_git_capture_env(wd, ["add", "--intent-to-add", "--", spec.as_str()], &env)
+            .and_then(|_| Self::run_git_capture_env(wd, diff, &env));
+        let _ = std::fs::remove_file(&scratch);
+        Ok(out?.trim_end().lines().map(str::to_string).collect())
+    }
+
     fn diff_commit(&self, rev: &str) -> Result<Vec<String>> {
         log::trace!("git-system: diff_commit {}", rev);
         // Show patch only; no commit header/body
@@ -627,6 +1643,74 @@ impl Vcs for GitSystem {
         Ok(out.trim_end().lines().map(|l| l.to_string()).collect())
     }
 
+    fn path_attributes(&self, paths: &[String], names: &[&str]) -> Result<Vec<PathAttribute>> {
+        if paths.is_empty() || names.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut args = vec!["check-attr", "-z", "--stdin"];
+        args.extend(names);
+        let input: String = paths.iter().map(|p| format!("{p}\0")).collect();
+        let out = Self::run_git_capture_with_input(Some(&self.workdir), args, &input)?;
+        // -z: "<path>\0<attribute>\0<value>\0" per path and attribute
+        let fields: Vec<&str> = out.split('\0').collect();
+        Ok(fields
+            .chunks_exact(3)
+            .filter(|f| f[2] != "unspecified")
+            .map(|f| PathAttribute { path: f[0].to_string(), name: f[1].to_string(), value: f[2].to_string() })
+            .collect())
+    }
+
+    fn staged_blob_sizes(&self) -> Result<Vec<BlobSize>> {
+        // ":<old mode> <new mode> <old oid> <new oid> <status>\0<path>\0" per change; against
+        // the empty tree when HEAD is unborn.
+        let out = Self::run_git_capture(Some(&self.workdir), ["diff", "--cached", "--raw", "-z", "--no-renames", "--no-abbrev"])?;
+        let fields: Vec<&str> = out.split('\0').collect();
+        let staged: Vec<(&str, &str)> = fields
+            .chunks_exact(2)
+            .filter_map(|f| {
+                let meta: Vec<&str> = f[0].trim_start_matches(':').split(' ').collect();
+                let [_, new_mode, _, oid, status] = meta[..] else { return None };
+                (status != "D" && new_mode != "160000").then_some((f[1], oid))
+            })
+            .collect();
+        if staged.is_empty() {
+            return Ok(Vec::new());
+        }
+        staged
+            .iter()
+            .map(|(path, oid)| Ok(BlobSize { path: path.to_string(), size: self.objects.info(oid)?.map_or(0, |i| i.size) }))
+            .collect()
+    }
+
+    fn tracked_paths(&self) -> Result<Vec<String>> {
+        let out = Self::run_git_capture(Some(&self.workdir), ["ls-files", "-z"])?;
+        Ok(out.split('\0').filter(|p| !p.is_empty()).map(str::to_string).collect())
+    }
+
+    fn untrack_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        log::debug!("git-system: untrack_paths count={}", paths.len());
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args: Vec<&str> = vec!["rm", "--cached", "--quiet", "--"];
+        for p in paths {
+            args.push(Self::path_str(p)?);
+        }
+        Self::run_git(Some(&self.workdir), args)
+    }
+
+    fn stage_paths(&self, paths: &[PathBuf]) -> Result<()> {
+        log::debug!("git-system: stage_paths count={}", paths.len());
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let mut args: Vec<&str> = vec!["add", "-A", "--"];
+        for p in paths {
+            args.push(Self::path_str(p)?);
+        }
+        Self::run_git(Some(&self.workdir), args)
+    }
+
     fn stage_patch(&self, patch: &str) -> Result<()> {
         log::debug!("git-system: stage_patch bytes={}", patch.len());
         // Apply patch to the index only; do not touch working tree.
@@ -635,11 +1719,11 @@ impl Vcs for GitSystem {
         // - `--3way`: attempt a 3-way merge if the patch does not apply cleanly
         // - `-p1`: strip leading a/ and b/ introduced by unified diffs
         // - `--whitespace=nowarn`: do not reject because of whitespace-only issues
-        if let Err(_) = Self::run_git_with_input(
+        if Self::run_git_with_input(
             Some(&self.workdir),
             ["apply", "--cached", "--3way", "--whitespace=nowarn", "-p1", "-"],
             patch,
-        ) {
+        ).is_err() {
             // Some patches may not include a/ b/ prefixes; retry without stripping
             Self::run_git_with_input(
                 Some(&self.workdir),
@@ -657,9 +1741,9 @@ impl Vcs for GitSystem {
         for p in paths {
             args.push(Self::path_str(p)?.to_string());
         }
-        if let Err(_) = Self::run_git(Some(&self.workdir), args.clone()) {
+        if Self::run_git(Some(&self.workdir), args.clone()).is_err() {
             for p in paths {
-                let mut single = vec!["restore".to_string(), "--staged".into(), "--worktree".into(), "--source=HEAD".into(), "--".into(), Self::path_str(p)?.to_string()];
+                let single = vec!["restore".to_string(), "--staged".into(), "--worktree".into(), "--source=HEAD".into(), "--".into(), Self::path_str(p)?.to_string()];
                 let _ = Self::run_git(Some(&self.workdir), single);
             }
         }
@@ -680,6 +1764,302 @@ impl Vcs for GitSystem {
         Self::run_git(Some(&self.workdir), ["reset", "--hard", "HEAD"])
     }
 
+    fn reset_keep(&self, target: &str) -> Result<()> {
+        if target.starts_with('-') {
+            return Err(VcsError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid revision")));
+        }
+        log::info!("git-system: reset --keep {target}");
+        Self::run_git(Some(&self.workdir), ["reset", "--keep", target])
+    }
+
+    fn snapshot_worktree(&self, refname: &str, message: &str) -> Result<Option<String>> {
+        let wd = Some(self.workdir.as_path());
+        let rev = |r: &str| Self::run_git_capture(wd, ["rev-parse", "--verify", "--quiet", r]).ok().map(|s| s.trim().to_string());
+        let prev = rev(&format!("{refname}^{{commit}}"));
+        let base = prev.clone().or_else(|| rev("HEAD^{commit}"));
+
+        // A scratch copy of the index keeps its stat cache, so `add -A` only hashes what changed
+        let git_path = |p: &str| -> Result<PathBuf> {
+            Ok(self.workdir.join(Self::run_git_capture(wd, ["rev-parse", "--git-path", p])?.trim()))
+        };
+        let scratch = git_path("openvcs-wip-index")?;
+        let index = git_path("index")?;
+        if std::fs::copy(&index, &scratch).is_err() {
+            let _ = std::fs::remove_file(&scratch);
+        }
+        let mut env: Vec<(&str, &std::ffi::OsStr)> = vec![("GIT_INDEX_FILE", scratch.as_os_str())];
+        let tree = Self::run_git_capture_env(wd, ["add", "-A"], &env)
+            .and_then(|_| Self::run_git_capture_env(wd, ["write-tree"], &env));
+        let _ = std::fs::remove_file(&scratch);
+        let tree = tree?.trim().to_string();
+
+        if let Some(b) = &base && rev(&format!("{b}^{{tree}}")).as_deref() == Some(tree.as_str()) {
+            log::trace!("git-system: worktree unchanged since {b}, no snapshot");
+            return Ok(None);
+        }
+
+        if Self::run_git_capture(wd, ["var", "GIT_COMMITTER_IDENT"]).is_err() {
+            env.extend([
+                ("GIT_AUTHOR_NAME", "OpenVCS".as_ref()),
+                ("GIT_AUTHOR_EMAIL", "openvcs@example".as_ref()),
+                ("GIT_COMMITTER_NAME", "OpenVCS".as_ref()),
+                ("GIT_COMMITTER_EMAIL", "openvcs@example".as_ref()),
+            ]);
+        }
+        let mut args = vec!["commit-tree".to_string(), tree, "-m".into(), message.into()];
+        if let Some(b) = &base {
+            args.extend(["-p".into(), b.clone()]);
+        }
+        let commit = Self::run_git_capture_env(wd, args, &env[1..])?.trim().to_string();
+        // Passing the old value makes a concurrent snapshot fail rather than be overwritten
+        let mut update = vec!["update-ref", "-m", "openvcs: wip snapshot", refname, commit.as_str()];
+        update.extend(prev.as_deref());
+        Self::run_git(wd, update)?;
+        log::debug!("git-system: snapshot {commit} → {refname}");
+        Ok(Some(commit))
+    }
+
+    fn is_pushed(&self, commit: &str) -> Result<Vec<String>> {
+        let contains = format!("--contains={commit}");
+        let out = Self::run_git_capture(Some(&self.workdir), ["for-each-ref", "--format=%(refname)", contains.as_str(), "refs/remotes"])?;
+        Ok(out
+            .lines()
+            .filter(|r| !r.ends_with("/HEAD"))
+            .filter_map(|r| r.strip_prefix("refs/remotes/"))
+            .map(str::to_string)
+            .collect())
+    }
+
+    fn squash_commits(&self, ids: &[String], message: &str, allow_pushed: bool) -> Result<String> {
+        let wd = Some(self.workdir.as_path());
+        let invalid = |msg: String| VcsError::Backend { backend: GIT_SYSTEM_ID, msg };
+        if message.trim().is_empty() {
+            return Err(invalid("the commit message is empty".into()));
+        }
+        // Reject anything that isn't a plain revision before running git at all
+        if let Some(id) = ids.iter().find(|id| id.is_empty() || id.starts_with('-') || id.contains(|c: char| c.is_whitespace() || c.is_control())) {
+            return Err(invalid(format!("unknown commit {id}")));
+        }
+        if self.current_branch()?.is_none() {
+            return Err(VcsError::DetachedHead);
+        }
+        let head = Self::run_git_capture(wd, ["rev-parse", "--verify", "HEAD^{commit}"])?.trim().to_string();
+        let mut full = Vec::with_capacity(ids.len());
+        for id in ids {
+            let spec = format!("{id}^{{commit}}");
+            let oid = Self::run_git_capture(wd, ["rev-parse", "--verify", "--quiet", "--end-of-options", spec.as_str()])
+                .map_err(|_| invalid(format!("unknown commit {id}")))?;
+            full.push(oid.trim().to_string());
+        }
+
+        // First-parent history of HEAD (unpushed part only, unless allowed), newest first:
+        // "<id> <parent>…"
+        let mut args = vec!["rev-list", "--first-parent", "--parents", "HEAD"];
+        if !allow_pushed {
+            args.extend(["--not", "--remotes"]);
+        }
+        let out = Self::run_git_capture(wd, args)?;
+        let chain: Vec<rewrite::ChainCommit> = out
+            .lines()
+            .filter_map(|l| {
+                let mut ids = l.split_whitespace().map(str::to_string);
+                Some(rewrite::ChainCommit { id: ids.next()?, parents: ids.collect() })
+            })
+            .collect();
+        let plan = match rewrite::plan_squash(&chain, &full) {
+            Ok(plan) => plan,
+            Err(rewrite::PlanError::Invalid(msg)) => return Err(invalid(msg)),
+            Err(rewrite::PlanError::Missing(id)) => {
+                let remotes = self.is_pushed(&id)?;
+                return Err(if remotes.is_empty() {
+                    invalid(format!("{} is not on the current branch", &id[..id.len().min(7)]))
+                } else {
+                    VcsError::AlreadyPushed { commit: id, remotes }
+                });
+            }
+        };
+
+        let (oldest, newest) = (&plan.squashed[0], &plan.squashed[plan.squashed.len() - 1]);
+        let mut tip = self.recommit(oldest, &format!("{newest}^{{tree}}"), std::slice::from_ref(&plan.base), Some(message))?;
+        for c in &plan.replay {
+            let mut parents = vec![tip];
+            parents.extend(c.parents.iter().skip(1).cloned());
+            tip = self.recommit(&c.id, &format!("{}^{{tree}}", c.id), &parents, None)?;
+        }
+        // Passing the old value makes the update fail if HEAD moved meanwhile
+        let reflog = format!("openvcs: squash {} commits", plan.squashed.len());
+        Self::run_git(wd, ["update-ref", "-m", reflog.as_str(), "HEAD", tip.as_str(), head.as_str()])?;
+        log::info!("git-system: squashed {} commits into {tip}", plan.squashed.len());
+        Ok(tip)
+    }
+
+    fn stash_save(&self, opts: &StashOptions) -> Result<Option<StashEntry>> {
+        let wd = Some(self.workdir.as_path());
+        let top = || Self::run_git_capture_any_exit(wd, ["rev-parse", "-q", "--verify", "refs/stash"]);
+        let before = top()?;
+        let mut args = vec!["stash", "push"];
+        if opts.include_untracked {
+            args.push("--include-untracked");
+        }
+        if opts.keep_index {
+            args.push("--keep-index");
+        }
+        let message = opts.message.as_deref().map(str::trim).filter(|m| !m.is_empty());
+        if let Some(m) = message {
+            args.extend(["-m", m]);
+        }
+        log::info!("git-system: stash push (untracked: {}, keep index: {})", opts.include_untracked, opts.keep_index);
+        Self::run_git(wd, args)?;
+        // git succeeds with "No local changes to save" without creating an entry
+        if top()? == before {
+            return Ok(None);
+        }
+        Ok(self.stash_list()?.into_iter().next())
+    }
+
+    fn stash_list(&self) -> Result<Vec<StashEntry>> {
+        let out = Self::run_git_capture(
+            Some(&self.workdir),
+            ["stash", "list", "--date=raw", "--format=%H%x1f%cd%x1f%gs"],
+        )?;
+        Ok(out
+            .lines()
+            .filter_map(|l| {
+                let mut f = l.splitn(3, '\x1f');
+                let (id, date, message) = (f.next()?, f.next()?, f.next()?);
+                Some((id, date, message))
+            })
+            .enumerate()
+            .map(|(index, (id, date, message))| StashEntry {
+                index,
+                id: id.to_string(),
+                message: message.to_string(),
+                branch: StashEntry::branch_of(message),
+                time: Timestamp::parse(date).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    fn stash_diff(&self, index: usize) -> Result<Vec<String>> {
+        let wd = Some(self.workdir.as_path());
+        let stash = format!("stash@{{{index}}}");
+        let base = format!("{stash}^1");
+        let mut out = Self::run_git_capture(wd, ["diff", "--no-color", "--unified=3", base.as_str(), stash.as_str()])?;
+        // Untracked files are the tree of a root commit as third parent, when saved
+        let untracked = format!("{stash}^3");
+        if Self::run_git_capture(wd, ["rev-parse", "--verify", "--quiet", untracked.as_str()]).is_ok() {
+            out.push_str(&Self::run_git_capture(wd, ["show", "--no-color", "--unified=3", "--format=", untracked.as_str()])?);
+        }
+        Ok(out.trim_end().lines().map(str::to_string).collect())
+    }
+
+    fn stash_apply(&self, index: usize, restore_index: bool) -> Result<()> {
+        self.stash_reapply("apply", index, restore_index)
+    }
+
+    fn stash_pop(&self, index: usize, restore_index: bool) -> Result<()> {
+        self.stash_reapply("pop", index, restore_index)
+    }
+
+    fn stash_drop(&self, index: usize) -> Result<()> {
+        let stash = format!("stash@{{{index}}}");
+        log::info!("git-system: stash drop {stash}");
+        Self::run_git(Some(&self.workdir), ["stash", "drop", "-q", stash.as_str()])
+    }
+
+    fn stash_restore_paths(&self, index: usize, paths: &[PathBuf]) -> Result<()> {
+        let wd = Some(self.workdir.as_path());
+        let stash = format!("stash@{{{index}}}");
+        let untracked_rev = format!("{stash}^3");
+        let exists = |rev: &str, path: &str| {
+            Self::run_git_report(wd, ["cat-file", "-e", format!("{rev}:{path}").as_str()]).is_ok_and(|(ok, _)| ok)
+        };
+        // Tracked changes are in the stash commit, untracked files in its third parent
+        let (mut tracked, mut untracked) = (Vec::new(), Vec::new());
+        for p in paths {
+            let rel = p.strip_prefix(&self.workdir).unwrap_or(p);
+            let path = Self::path_str(rel)?;
+            if exists(&stash, path) {
+                tracked.push(rel.to_path_buf());
+            } else if exists(&untracked_rev, path) {
+                untracked.push(rel.to_path_buf());
+            } else {
+                return Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: format!("{path} is not in {stash}") });
+            }
+        }
+        if !tracked.is_empty() {
+            self.restore_worktree(&stash, &tracked)?;
+        }
+        if !untracked.is_empty() {
+            self.restore_worktree(&untracked_rev, &untracked)?;
+        }
+        Ok(())
+    }
+
+    fn pick_commit(&self, commit: &str, kind: PickKind) -> Result<String> {
+        let wd = Some(self.workdir.as_path());
+        let id = self.resolve_commit(commit)?;
+        let parents = Self::run_git_capture(wd, ["rev-list", "--parents", "-n1", id.as_str()])?;
+        if parents.split_whitespace().count() > 2 {
+            return Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: format!("{} is a merge commit", &id[..7]) });
+        }
+        let verb = match kind { PickKind::CherryPick => "cherry-pick", PickKind::Revert => "revert" };
+        log::info!("git-system: {verb} {id}");
+        // The commit is made by `commit_picked`, the same way as after a conflict
+        let (ok, report) = Self::run_git_report(wd, [verb, "--no-commit", id.as_str()])?;
+        if !ok {
+            let files = self.conflicted_files()?;
+            if files.is_empty() {
+                let msg = report.trim().to_string();
+                return Err(errors::classify(&msg).unwrap_or(VcsError::Backend { backend: GIT_SYSTEM_ID, msg }));
+            }
+            log::info!("git-system: {verb} {id} stopped on {} conflict(s)", files.len());
+            return Err(VcsError::MergeConflict { files });
+        }
+        self.commit_picked(&id, kind)
+    }
+
+    fn commit_picked(&self, commit: &str, kind: PickKind) -> Result<String> {
+        let wd = Some(self.workdir.as_path());
+        let id = self.resolve_commit(commit)?;
+        let files = self.conflicted_files()?;
+        if !files.is_empty() {
+            return Err(VcsError::MergeConflict { files });
+        }
+        // Exits 0 when the index matches HEAD
+        if Self::run_git_report(wd, ["diff", "--cached", "--quiet"])?.0 {
+            return Err(VcsError::NothingToCommit);
+        }
+        // Hooks don't run, as with `git cherry-pick`; `-C` keeps the author and message
+        match kind {
+            PickKind::CherryPick => {
+                Self::run_git(wd, ["commit", "--no-verify", "--cleanup=verbatim", "-C", id.as_str()])?
+            }
+            PickKind::Revert => {
+                let original = Self::run_git_capture(wd, ["show", "-s", "--format=%B", id.as_str()])?;
+                let message = kind.message(&id, &original);
+                Self::run_git(wd, ["commit", "--no-verify", "--cleanup=verbatim", "-m", message.as_str()])?
+            }
+        }
+        Ok(Self::run_git_capture(wd, ["rev-parse", "HEAD"])?.trim().to_string())
+    }
+
+    fn restore_worktree(&self, source: &str, paths: &[PathBuf]) -> Result<()> {
+        if source.starts_with('-') {
+            return Err(VcsError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid revision")));
+        }
+        let mut args = vec!["restore".to_string(), format!("--source={source}"), "--worktree".into(), "--".into()];
+        if paths.is_empty() {
+            args.push(".".into());
+        } else {
+            for p in paths {
+                args.push(Self::path_str(p)?.to_string());
+            }
+        }
+        log::info!("git-system: restore {} path(s) from {source}", paths.len());
+        Self::run_git(Some(&self.workdir), args)
+    }
+
     fn get_identity(&self) -> Result<Option<(String, String)>> {
         log::trace!("git-system: get_identity");
         // Prefer repo context, but allow Git's normal precedence (local → global → system)
@@ -704,8 +2084,8 @@ impl Vcs for GitSystem {
     fn delete_branch(&self, name: &str, force: bool) -> Result<()> {
         log::info!("git-system: delete_branch '{}' force={}", name, force);
         // Guard: do not delete current branch
-        if let Ok(cur) = self.current_branch() {
-            if let Some(c) = cur { if c == name { return Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: "cannot delete current branch".into() }); }}
+        if let Ok(Some(cur)) = self.current_branch() && cur == name {
+            return Err(VcsError::Backend { backend: GIT_SYSTEM_ID, msg: "cannot delete current branch".into() });
         }
         if force {
             Self::run_git(Some(&self.workdir), ["branch", "-D", name])
@@ -725,9 +2105,443 @@ impl Vcs for GitSystem {
         Self::run_git(Some(&self.workdir), ["branch", "-m", old, new])
     }
 
-    fn merge_into_current(&self, name: &str) -> Result<()> {
-        // Perform a merge into the current branch. Let git promptless merge and return any conflicts as error output.
-        log::info!("git-system: merge_into_current '{}'", name);
-        Self::run_git(Some(&self.workdir), ["merge", "--no-ff", name])
+    fn set_upstream(&self, branch: &str, upstream: &str) -> Result<()> {
+        log::info!("git-system: set_upstream '{}' -> '{}'", branch, upstream);
+        Self::run_git(Some(&self.workdir), ["branch", &format!("--set-upstream-to={upstream}"), branch])
+    }
+
+    fn branch_config(&self, key: &str) -> Result<std::collections::BTreeMap<String, String>> {
+        // "branch.<name>.<key>\n<value>\0" per entry; exits 1 when nothing matches.
+        let pattern = format!("^branch\\..*\\.{key}$");
+        let out = Self::run_git_capture_any_exit(Some(&self.workdir), ["config", "--local", "-z", "--get-regexp", &pattern])?;
+        let suffix = format!(".{}", key.to_ascii_lowercase());
+        Ok(out
+            .split('\0')
+            .filter_map(|entry| {
+                let (name, value) = entry.split_once('\n')?;
+                let branch = name.strip_prefix("branch.")?.strip_suffix(suffix.as_str())?;
+                Some((branch.to_string(), value.to_string()))
+            })
+            .collect())
+    }
+
+    fn set_branch_config(&self, branch: &str, key: &str, value: Option<&str>) -> Result<()> {
+        log::info!("git-system: set_branch_config branch.{}.{} = {:?}", branch, key, value);
+        self.set_local_config(&format!("branch.{branch}.{key}"), value)
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<ConfigValue>> {
+        // `<scope>\0<origin>\0<value>\0`; exits 1 when the key is missing. Relative origins
+        // are relative to the working tree, where git ran.
+        let Some(out) = Self::run_git_capture_lookup(
+            Some(&self.workdir),
+            ["config", "--show-scope", "--show-origin", "-z", "--get", key],
+        )?
+        else {
+            return Ok(None);
+        };
+        let mut fields = out.split('\0');
+        let (Some(scope), Some(origin), Some(value)) = (fields.next(), fields.next(), fields.next()) else {
+            return Ok(None);
+        };
+        let scope = match scope {
+            "system" => ConfigScope::System,
+            "global" => ConfigScope::Global,
+            "local" => ConfigScope::Local,
+            "worktree" => ConfigScope::Worktree,
+            _ => ConfigScope::Command,
+        };
+        let origin = origin.strip_prefix("file:").map(|f| self.workdir.join(f).to_string_lossy().into_owned());
+        Ok(Some(ConfigValue { value: value.to_string(), scope, origin }))
+    }
+
+    fn local_config(&self, key: &str) -> Result<Option<String>> {
+        // Exits 1 when the key is missing.
+        let out = Self::run_git_capture_lookup(Some(&self.workdir), ["config", "--local", "-z", "--get", key])?;
+        Ok(out.and_then(|v| v.strip_suffix('\0').map(str::to_string)))
+    }
+
+    fn hooks_dir(&self) -> Result<PathBuf> {
+        // Resolves core.hooksPath and worktrees; relative results are relative to the cwd.
+        let out = Self::run_git_capture(Some(&self.workdir), ["rev-parse", "--git-path", "hooks"])?;
+        Ok(self.workdir.join(out.trim()))
+    }
+
+    fn set_local_config(&self, key: &str, value: Option<&str>) -> Result<()> {
+        log::debug!("git-system: set_local_config {} = {:?}", key, value);
+        match value {
+            Some(v) => Self::run_git(Some(&self.workdir), ["config", "--local", key, v]),
+            // Unsetting a missing key exits 5; that is already the desired state.
+            None => Self::run_git_capture_any_exit(Some(&self.workdir), ["config", "--local", "--unset-all", key]).map(|_| ()),
+        }
+    }
+
+    fn merge_branch(&self, name: &str, opts: &MergeOptions) -> Result<MergeOutcome> {
+        log::info!("git-system: merge_branch '{}' ff={:?}", name, opts.fast_forward);
+        // Also keeps option-like names out of the merge's arguments
+        self.resolve_commit(name).map_err(|_| VcsError::NoSuchBranch(name.to_string()))?;
+        let head = || Self::run_git_capture(Some(&self.workdir), ["rev-parse", "--verify", "--quiet", "HEAD"]);
+        let before = head().unwrap_or_default();
+
+        let mut args = vec!["merge", "--no-edit"];
+        args.push(match opts.fast_forward {
+            FastForward::Allow => "--ff",
+            FastForward::Never => "--no-ff",
+            FastForward::Only => "--ff-only",
+        });
+        if let Some(msg) = &opts.message {
+            args.extend(["-m", msg.as_str()]);
+        }
+        args.push(name);
+        let (ok, report) = Self::run_git_report(Some(&self.workdir), args)?;
+        if !ok {
+            // Conflicts are reported on stdout; the index knows which files
+            let files = self.conflicted_files()?;
+            if files.is_empty() {
+                let msg = report.trim().to_string();
+                return Err(errors::classify(&msg).unwrap_or(VcsError::Backend { backend: GIT_SYSTEM_ID, msg }));
+            }
+            return Ok(MergeOutcome::Conflicted { files });
+        }
+
+        let after = head()?;
+        if after == before {
+            return Ok(MergeOutcome::UpToDate);
+        }
+        let parents = Self::run_git_capture(Some(&self.workdir), ["rev-list", "--parents", "-n", "1", "HEAD"])?;
+        if parents.split_whitespace().count() > 2 {
+            Ok(MergeOutcome::Merged { commit: after.trim().to_string() })
+        } else {
+            Ok(MergeOutcome::FastForward { commit: after.trim().to_string() })
+        }
+    }
+}
+
+/// One file of `--raw --numstat -z` output, before sizes are looked up.
+struct RawChange {
+    path: String,
+    old_path: Option<String>,
+    status: String,
+    old_mode: u32,
+    new_mode: u32,
+    old_oid: String,
+    new_oid: String,
+    additions: Option<u32>,
+    deletions: Option<u32>,
+}
+
+/// `--raw --numstat -z` prints every raw record (`:<modes> <old> <new> <status>\0<path>\0`,
+/// two paths for renames/copies) and then the numstat records in the same order
+/// (`<add>\t<del>\t<path>\0`, or `<add>\t<del>\t\0<old>\0<new>\0`; `-` for binary).
+fn parse_raw_numstat(out: &str) -> Vec<RawChange> {
+    let mut toks = out.split('\0');
+    let mut changes: Vec<RawChange> = Vec::new();
+    let mut numstat = 0usize;
+    while let Some(t) = toks.next() {
+        if let Some(meta) = t.strip_prefix(':') {
+            let f: Vec<&str> = meta.split(' ').collect();
+            let (Some(old_oid), Some(new_oid), Some(code)) = (f.get(2), f.get(3), f.get(4)) else { continue };
+            let mode = |i: usize| f.get(i).and_then(|m| u32::from_str_radix(m, 8).ok()).unwrap_or(0);
+            let status: String = code.chars().take(1).collect();
+            let first = toks.next().unwrap_or_default().to_string();
+            let (old_path, path) = if status == "R" || status == "C" {
+                (Some(first), toks.next().unwrap_or_default().to_string())
+            } else {
+                (None, first)
+            };
+            changes.push(RawChange {
+                path,
+                old_path,
+                status,
+                old_mode: mode(0),
+                new_mode: mode(1),
+                old_oid: old_oid.to_string(),
+                new_oid: new_oid.to_string(),
+                additions: None,
+                deletions: None,
+            });
+        } else if !t.is_empty() {
+            let mut f = t.splitn(3, '\t');
+            let (add, del, path) = (f.next().unwrap_or("-"), f.next().unwrap_or("-"), f.next().unwrap_or_default());
+            if path.is_empty() {
+                toks.next();
+                toks.next();
+            }
+            if let Some(c) = changes.get_mut(numstat) {
+                c.additions = add.parse().ok();
+                c.deletions = del.parse().ok();
+            }
+            numstat += 1;
+        }
+    }
+    changes
+}
+
+/// Lines of the file at `path`, read in blocks; `None` for binary files (a NUL in the
+/// first 8000 bytes, like git) or when it can't be read.
+fn count_lines(path: &Path) -> Option<u32> {
+    use std::io::Read;
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut buf = [0u8; 64 * 1024];
+    let (mut lines, mut read, mut last) = (0u32, 0usize, b'\n');
+    loop {
+        let n = file.read(&mut buf).ok()?;
+        if n == 0 {
+            break;
+        }
+        let block = &buf[..n];
+        if read < 8000 && block[..n.min(8000 - read)].contains(&0) {
+            return None;
+        }
+        read += n;
+        lines += block.iter().filter(|&&b| b == b'\n').count() as u32;
+        last = block[n - 1];
+    }
+    Some(lines + u32::from(last != b'\n'))
+}
+
+/// One `git status --porcelain=v2 -z` record → `FileEntry` (headers → `None`).
+/// Paths are the last field and may contain spaces, so split a fixed number of fields.
+fn parse_porcelain_v2(rec: &str) -> Option<FileEntry> {
+    let entry = |path: &str, status: &str| FileEntry { path: path.to_string(), status: status.into(), ..Default::default() };
+    match rec.as_bytes().first()? {
+        // Untracked: "? <path>"
+        b'?' => Some(entry(rec.get(2..)?, "A")),
+        // Ignored: "! <path>"
+        b'!' => Some(entry(rec.get(2..)?, "!")),
+        // Ordinary: "1 XY sub mH mI mW hH hI <path>"
+        b'1' => {
+            let mut f = rec.splitn(9, ' ');
+            let xy = f.nth(1)?;
+            let path = f.nth(6)?;
+            let (x, y) = (xy.chars().next().unwrap_or('.'), xy.chars().nth(1).unwrap_or('.'));
+            let status = if x == 'A' || y == 'A' {
+                "A"
+            } else if x == 'D' || y == 'D' {
+                "D"
+            } else if x == 'T' || y == 'T' {
+                "T"
+            } else {
+                // M and any other ordinary change combo
+                "M"
+            };
+            Some(entry(path, status))
+        }
+        // Rename/copy: "2 XY sub mH mI mW hH hI Xscore <path>" (original path is the next record)
+        b'2' => Some(entry(rec.splitn(10, ' ').nth(9)?, "R")),
+        // Unmerged: "u XY sub m1 m2 m3 mW h1 h2 h3 <path>"
+        b'u' => Some(entry(rec.splitn(11, ' ').nth(10)?, "U")),
+        _ => None,
+    }
+}
+
+/// A pathspec for `dir` and everything under it, without glob magic.
+fn literal_pathspec(dir: &str) -> String {
+    format!(":(literal){dir}")
+}
+
+/// `diff-tree --raw -z` output → (old blob, new blob, path); all-zero ids (added or
+/// deleted side) and submodules are left out.
+fn parse_raw_z(raw: &str) -> Vec<(String, String, String)> {
+    let zero = |id: &str| id.bytes().all(|b| b == b'0');
+    let mut out = Vec::new();
+    let mut fields = raw.split('\0');
+    while let Some(meta) = fields.next() {
+        let Some(meta) = meta.trim_start_matches('\n').strip_prefix(':') else { continue };
+        let Some(path) = fields.next() else { break };
+        let parts: Vec<&str> = meta.split(' ').collect();
+        if parts.len() < 4 || parts[0] == "160000" || parts[1] == "160000" {
+            continue;
+        }
+        let blob = |id: &str| if zero(id) { String::new() } else { id.to_string() };
+        out.push((blob(parts[2]), blob(parts[3]), path.to_string()));
+    }
+    out
+}
+
+/// `git commit` of the index with `message` and the overrides in `opts`.
+fn commit_args(message: &str, opts: &CommitOptions) -> Vec<String> {
+    let mut args: Vec<String> = vec!["commit".into(), "-m".into(), message.into(), "--no-edit".into()];
+    if let Some(author) = &opts.author {
+        args.push(format!("--author={author}"));
+    }
+    if let Some(date) = &opts.author_date {
+        args.push(format!("--date=@{}", date.to_raw()));
+    }
+    args
+}
+
+/// `git log` argv for `q`; `paged` adds `--skip`/`--max-count`.
+fn log_args(q: &LogQuery, paged: bool) -> Vec<String> {
+    // Build: git log [rev?] [--topo-order] [--no-merges] --date=raw
+    //        [--since=..] [--until=..] [--author=..] --skip=N --max-count=M
+    //        --pretty='...%x00...' [-- pathspec...]
+    let mut args: Vec<String> = vec!["log".into()];
+
+    if let Some(rev) = &q.rev {
+        args.push(rev.clone());
+    }
+
+    if q.topo_order {
+        args.push("--topo-order".into());
+    }
+    if !q.include_merges {
+        args.push("--no-merges".into());
+    }
+
+    args.push("--date=raw".into());
+    // Parsed here as libgit2 does, so both accept the same dates; `@<seconds>` is exact
+    if let Some(s) = q.since() {
+        args.push(format!("--since=@{s}"));
+    }
+    if let Some(u) = q.until() {
+        args.push(format!("--until=@{u}"));
+    }
+    if let Some(a) = &q.author_contains {
+        args.push(format!("--author={a}"));
+    }
+
+    if paged {
+        args.push(format!("--skip={}", q.skip));
+        args.push(format!("--max-count={}", q.limit));
+    }
+
+    // NUL-separated fields, one commit per line
+    args.push("--pretty=format:%H%x00%an%x00%ae%x00%cn%x00%ce%x00%ad%x00%cd%x00%s".into());
+
+    let specs = q.pathspecs();
+    if !specs.is_empty() {
+        args.push("--".into());
+        args.extend(specs);
+    }
+    args
+}
+
+/// One `log_args` output line → `CommitItem`.
+fn parse_log_line(line: &str) -> Option<CommitItem> {
+    // Each line → one commit with NUL-separated fields
+    let mut parts = line.split('\0');
+    let id = parts.next().unwrap_or_default();
+    if id.is_empty() {
+        return None;
+    }
+    let mut field = || parts.next().unwrap_or_default().to_string();
+    let author_name     = field();
+    let author_email    = field();
+    let committer_name  = field();
+    let committer_email = field();
+    let author_time     = Timestamp::parse(&field()).unwrap_or_default();
+    let committer_time  = Timestamp::parse(&field()).unwrap_or_default();
+    let msg             = field();
+
+    let short_id = id[..id.len().min(7)].to_string();
+    Some(CommitItem {
+        id: id.to_string(),
+        msg,
+        meta: format!("{} • {short_id}", author_time.to_rfc3339()),
+        author: format!("{author_name} <{author_email}>"),
+        author_name,
+        author_email,
+        committer_name,
+        committer_email,
+        timestamp: author_time.seconds,
+        author_time,
+        committer_time,
+        short_id,
+    })
+}
+
+fn parse_commit_record(rec: &str) -> Option<CommitRecord> {
+    let mut f = rec.trim_start_matches('\n').split('\x1f');
+    let id = f.next().filter(|s| !s.is_empty())?.to_string();
+    let mut next = || f.next().unwrap_or_default().to_string();
+    let parents = next().split_whitespace().map(str::to_string).collect();
+    let (author_name, author_email) = (next(), next());
+    let author_time = Timestamp::parse(&next()).unwrap_or_default();
+    let (committer_name, committer_email) = (next(), next());
+    let committer_time = Timestamp::parse(&next()).unwrap_or_default();
+    let summary = next();
+    let body = next().trim().to_string();
+    Some(CommitRecord {
+        id,
+        parents,
+        author_name,
+        author_email,
+        author_date: author_time.to_rfc3339(),
+        committer_name,
+        committer_email,
+        committer_date: committer_time.to_rfc3339(),
+        author_time,
+        committer_time,
+        summary,
+        trailers: parse_trailers(&body),
+        body,
+    })
+}
+
+/// One `--raw` log record: header fields, then `:<modes> <old> <new> <status>\t<path>[\t<new path>]`.
+/// Deletions (all-zero blob) are skipped.
+fn parse_file_revision(rec: &str) -> Option<FileRevision> {
+    let mut lines = rec.lines().filter(|l| !l.is_empty());
+    let mut f = lines.next()?.split('\x1f');
+    let commit = f.next().filter(|s| !s.is_empty())?.to_string();
+    let author = f.next()?.to_string();
+    let time = Timestamp::parse(f.next()?).unwrap_or_default();
+    let summary = f.next().unwrap_or_default().to_string();
+    let raw = lines.find(|l| l.starts_with(':'))?;
+    let (meta, paths) = raw.split_once('\t')?;
+    let blob = meta.split(' ').nth(3)?;
+    if blob.bytes().all(|b| b == b'0') {
+        return None;
+    }
+    let path = paths.rsplit('\t').next()?.to_string();
+    Some(FileRevision { commit, blob: blob.to_string(), path, author, date: time.to_rfc3339(), time, summary })
+}
+
+/// History of an unborn HEAD.
+struct EmptyWalk;
+
+impl HistoryWalk for EmptyWalk {
+    fn next_batch(&mut self, _n: usize) -> Result<Vec<CommitItem>> {
+        Ok(Vec::new())
+    }
+}
+
+/// A `git log` process kept running; each batch reads further lines from its stdout.
+struct LogWalk {
+    child: std::process::Child,
+    lines: Option<std::io::Lines<BufReader<std::process::ChildStdout>>>,
+    /// Collects git's stderr until it exits, for the error message.
+    stderr: Option<std::thread::JoinHandle<String>>,
+}
+
+impl HistoryWalk for LogWalk {
+    fn next_batch(&mut self, n: usize) -> Result<Vec<CommitItem>> {
+        let Some(lines) = self.lines.as_mut() else { return Ok(Vec::new()) };
+        let mut out = Vec::with_capacity(n.min(4096));
+        while out.len() < n {
+            match lines.next() {
+                Some(line) => out.extend(parse_log_line(&line.map_err(VcsError::Io)?)),
+                None => {
+                    self.lines = None;
+                    let status = self.child.wait().map_err(VcsError::Io)?;
+                    let stderr = self.stderr.take().and_then(|t| t.join().ok()).unwrap_or_default();
+                    if !status.success() {
+                        return Err(errors::from_failure(&stderr, status));
+                    }
+                    break;
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl Drop for LogWalk {
+    fn drop(&mut self) {
+        if self.lines.is_some() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
     }
 }
@@ -0,0 +1,48 @@
+//! The demo repository shown in onboarding and used for screenshots.
+
+use crate::{Change, RepoSpec};
+
+const README: &str = "# Lighthouse\n\nA tiny static site generator.\n\n```sh\nlighthouse build site/\n```\n";
+
+const MAIN_RS: &str = "mod render;\n\nfn main() {\n    let dir = std::env::args().nth(2).unwrap_or_else(|| \"site\".into());\n    render::build(&dir);\n}\n";
+
+const RENDER_RS: &str = "pub fn build(dir: &str) {\n    for page in pages(dir) {\n        println!(\"rendering {page}\");\n    }\n}\n\nfn pages(dir: &str) -> Vec<String> {\n    std::fs::read_dir(dir).into_iter().flatten().flatten().map(|e| e.path().display().to_string()).collect()\n}\n";
+
+const RENDER_RS_MARKDOWN: &str = "pub fn build(dir: &str) {\n    for page in pages(dir) {\n        let html = markdown(&page);\n        println!(\"rendering {page} ({} bytes)\", html.len());\n    }\n}\n\nfn pages(dir: &str) -> Vec<String> {\n    std::fs::read_dir(dir).into_iter().flatten().flatten().map(|e| e.path().display().to_string()).collect()\n}\n\nfn markdown(path: &str) -> String {\n    let text = std::fs::read_to_string(path).unwrap_or_default();\n    text.lines().map(|l| format!(\"<p>{l}</p>\")).collect()\n}\n";
+
+const STYLE: &str = "body {\n    font-family: sans-serif;\n    color: #222;\n    background: #fff;\n}\n";
+
+const STYLE_DARK: &str = "body {\n    font-family: sans-serif;\n    color: #222;\n    background: #fff;\n}\n\n@media (prefers-color-scheme: dark) {\n    body { color: #eee; background: #111; }\n}\n";
+
+/// A small project with a few authors, a merged feature branch, a rename, tags, an
+/// unmerged branch and uncommitted changes: something to look at in every panel.
+pub fn demo() -> RepoSpec {
+    RepoSpec::new()
+        .author("Ada Lovelace", "ada@example.com")
+        .commit("Initial commit", [
+            Change::write("README.md", README),
+            Change::write("src/main.rs", MAIN_RS),
+            Change::write("src/render.rs", RENDER_RS),
+            Change::write(".gitignore", "/target\n"),
+        ])
+        .commit("Add default stylesheet", [Change::write("theme/style.css", STYLE)])
+        .tag("v0.1.0", Some("First preview"))
+        .author("Grace Hopper", "grace@example.com")
+        .branch("feature/markdown")
+        .commit("Render pages as Markdown", [Change::write("src/render.rs", RENDER_RS_MARKDOWN)])
+        .commit("Document the build command", [Change::write("docs/usage.md", "# Usage\n\n`lighthouse build <dir>`\n")])
+        .switch("main")
+        .author("Ada Lovelace", "ada@example.com")
+        .commit("Move the stylesheet into assets", [Change::rename("theme/style.css", "assets/style.css")])
+        .merge("feature/markdown", "Merge branch 'feature/markdown'")
+        .tag("v1.0.0", Some("Lighthouse 1.0\n\nMarkdown pages and a new home for the stylesheet."))
+        .author("Alan Turing", "alan@example.com")
+        .branch("feature/dark-mode")
+        .commit("Add a dark color scheme", [Change::write("assets/style.css", STYLE_DARK)])
+        .switch("main")
+        .modify([
+            Change::write("README.md", format!("{README}\nSee docs/usage.md for more.\n")),
+            Change::write("NOTES.txt", "- publish to the package registry\n"),
+        ])
+        .stage([Change::write("docs/changelog.md", "# Changelog\n\n## 1.0.0\n\n- Markdown pages\n")])
+}
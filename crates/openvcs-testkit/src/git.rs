@@ -0,0 +1,128 @@
+//! Running git for a build, isolated from whatever environment the tests run in.
+
+use std::ffi::OsStr;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// Variables that would point git at another repository or change what it records.
+const CLEARED_ENV: &[&str] = &[
+    "GIT_DIR",
+    "GIT_WORK_TREE",
+    "GIT_INDEX_FILE",
+    "GIT_OBJECT_DIRECTORY",
+    "GIT_ALTERNATE_OBJECT_DIRECTORIES",
+    "GIT_COMMON_DIR",
+    "GIT_NAMESPACE",
+    "GIT_PREFIX",
+    "GIT_TEMPLATE_DIR",
+    "GIT_AUTHOR_NAME",
+    "GIT_AUTHOR_EMAIL",
+    "GIT_AUTHOR_DATE",
+    "GIT_COMMITTER_NAME",
+    "GIT_COMMITTER_EMAIL",
+    "GIT_COMMITTER_DATE",
+];
+
+/// Settings that would make the result depend on the machine, pinned for every command
+/// (the user's and the system's configuration are not read at all).
+const PINNED: &[&str] = &[
+    "commit.gpgSign=false",
+    "tag.gpgSign=false",
+    "core.autocrlf=false",
+    "core.fileMode=true",
+    "merge.conflictStyle=merge",
+    "protocol.file.allow=always",
+    "advice.detachedHead=false",
+];
+
+#[cfg(windows)]
+const NULL_DEVICE: &str = "NUL";
+#[cfg(not(windows))]
+const NULL_DEVICE: &str = "/dev/null";
+
+/// git in one repository, as one identity, at one time.
+#[derive(Clone)]
+pub(crate) struct Git {
+    program: PathBuf,
+    dir: PathBuf,
+    name: String,
+    email: String,
+    time: i64,
+}
+
+impl Git {
+    pub fn new(program: &Path, dir: &Path) -> Self {
+        Self {
+            program: program.to_path_buf(),
+            dir: dir.to_path_buf(),
+            name: crate::NAME.into(),
+            email: crate::EMAIL.into(),
+            time: crate::EPOCH,
+        }
+    }
+
+    pub fn identity(&mut self, name: &str, email: &str) {
+        self.name = name.into();
+        self.email = email.into();
+    }
+
+    /// The same git, recording `time` for commits and tags.
+    pub fn at(&self, time: i64) -> Self {
+        Self { time, ..self.clone() }
+    }
+
+    pub fn run<I, S>(&self, args: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.exec(args).map(drop)
+    }
+
+    /// Standard output of a successful command.
+    pub fn output<I, S>(&self, args: I) -> io::Result<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.exec(args).map(|out| String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+
+    fn exec<I, S>(&self, args: I) -> io::Result<Output>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let args: Vec<_> = args.into_iter().map(|a| a.as_ref().to_os_string()).collect();
+        log::trace!("testkit: git {args:?} in {}", self.dir.display());
+        let mut cmd = Command::new(&self.program);
+        for var in CLEARED_ENV {
+            cmd.env_remove(var);
+        }
+        let date = format!("@{} +0000", self.time);
+        cmd.current_dir(&self.dir)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_CONFIG_GLOBAL", NULL_DEVICE)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .env("LC_ALL", "C")
+            .env("GIT_AUTHOR_NAME", &self.name)
+            .env("GIT_AUTHOR_EMAIL", &self.email)
+            .env("GIT_AUTHOR_DATE", &date)
+            .env("GIT_COMMITTER_NAME", &self.name)
+            .env("GIT_COMMITTER_EMAIL", &self.email)
+            .env("GIT_COMMITTER_DATE", &date)
+            .stdin(Stdio::null());
+        for setting in PINNED {
+            cmd.args(["-c", setting]);
+        }
+        let out = cmd.args(&args).output()?;
+        if !out.status.success() {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            let stdout = String::from_utf8_lossy(&out.stdout);
+            let msg = if stderr.trim().is_empty() { stdout.trim() } else { stderr.trim() };
+            return Err(io::Error::other(format!("git {args:?} failed ({}): {msg}", out.status)));
+        }
+        Ok(out)
+    }
+}
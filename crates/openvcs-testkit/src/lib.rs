@@ -0,0 +1,326 @@
+//! OpenVCS testkit: deterministic repositories built from a description.
+//!
+//! A [`RepoSpec`] lists what happens in a repository — commits with their file changes,
+//! branches, merges, tags, submodules, a merge left in conflict, uncommitted edits — and
+//! [`RepoSpec::build`] replays it with the git executable. Every run gives the same commit
+//! ids: git runs without the user's or the system's configuration, with a fixed identity,
+//! and with a clock that starts at [`EPOCH`] and advances one hour per commit or tag.
+//!
+//! Backend tests use it to get repositories with the shapes they need (see
+//! [`RepoSpec::build_temp`]); the app builds [`demo`] for onboarding and screenshots, and
+//! benchmarks run on [`synthetic`] repositories of any size.
+//!
+//! ```no_run
+//! use openvcs_testkit::{Change, RepoSpec};
+//!
+//! let fixture = RepoSpec::new()
+//!     .commit("Initial commit", [Change::write("README.md", "# Demo\n")])
+//!     .branch("feature")
+//!     .commit("Move the readme", [Change::rename("README.md", "docs/README.md")])
+//!     .switch("main")
+//!     .merge("feature", "Merge branch 'feature'")
+//!     .tag("v1.0", Some("First release"))
+//!     .build_temp("readme")?;
+//! # let _ = fixture.path();
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+mod demo;
+mod git;
+mod synthetic;
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub use demo::demo;
+pub use synthetic::synthetic;
+
+/// The fixture clock's start (2023-11-14 22:13:20 UTC).
+pub const EPOCH: i64 = 1_700_000_000;
+
+/// Seconds the clock advances for each commit and tag.
+const TICK: i64 = 3600;
+
+/// Default author and committer.
+pub const NAME: &str = "Fixture Author";
+pub const EMAIL: &str = "author@example.com";
+
+/// One change to the working tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Create or overwrite a file (directories are created).
+    Write { path: String, contents: Vec<u8> },
+    Remove { path: String },
+    Rename { from: String, to: String },
+    /// A file of `size` pseudo-random bytes, the same for the same path and size.
+    Large { path: String, size: usize },
+}
+
+impl Change {
+    pub fn write(path: &str, contents: impl Into<Vec<u8>>) -> Self {
+        Self::Write { path: path.into(), contents: contents.into() }
+    }
+
+    pub fn remove(path: &str) -> Self {
+        Self::Remove { path: path.into() }
+    }
+
+    pub fn rename(from: &str, to: &str) -> Self {
+        Self::Rename { from: from.into(), to: to.into() }
+    }
+
+    pub fn large(path: &str, size: usize) -> Self {
+        Self::Large { path: path.into(), size }
+    }
+
+    /// The paths the change touches.
+    fn paths(&self) -> Vec<&str> {
+        match self {
+            Self::Write { path, .. } | Self::Remove { path } | Self::Large { path, .. } => vec![path],
+            Self::Rename { from, to } => vec![from, to],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Author { name: String, email: String },
+    Commit { message: String, changes: Vec<Change> },
+    Branch { name: String },
+    Switch { name: String },
+    Merge { branch: String, message: String },
+    Conflict { branch: String },
+    Tag { name: String, message: Option<String> },
+    Submodule { path: String, url: String, spec: RepoSpec },
+    Worktree { changes: Vec<Change>, stage: bool },
+}
+
+/// What happens in a repository, in order. Built with the methods below, then replayed
+/// with [`build`](Self::build).
+#[derive(Debug, Clone)]
+pub struct RepoSpec {
+    branch: String,
+    steps: Vec<Step>,
+}
+
+impl Default for RepoSpec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RepoSpec {
+    /// An empty repository on `main`.
+    pub fn new() -> Self {
+        Self { branch: "main".into(), steps: Vec::new() }
+    }
+
+    /// Name the initial branch `name` instead of `main`.
+    pub fn initial_branch(mut self, name: &str) -> Self {
+        self.branch = name.into();
+        self
+    }
+
+    /// Author (and committer) of the following commits and tags.
+    pub fn author(mut self, name: &str, email: &str) -> Self {
+        self.steps.push(Step::Author { name: name.into(), email: email.into() });
+        self
+    }
+
+    /// Apply `changes` and commit everything as `message`.
+    pub fn commit(mut self, message: &str, changes: impl IntoIterator<Item = Change>) -> Self {
+        self.steps.push(Step::Commit { message: message.into(), changes: changes.into_iter().collect() });
+        self
+    }
+
+    /// Create branch `name` at HEAD and switch to it.
+    pub fn branch(mut self, name: &str) -> Self {
+        self.steps.push(Step::Branch { name: name.into() });
+        self
+    }
+
+    /// Switch to existing branch `name`.
+    pub fn switch(mut self, name: &str) -> Self {
+        self.steps.push(Step::Switch { name: name.into() });
+        self
+    }
+
+    /// Merge `branch` into the current branch with a merge commit (never fast-forwarded).
+    /// The build fails if the merge conflicts.
+    pub fn merge(mut self, branch: &str, message: &str) -> Self {
+        self.steps.push(Step::Merge { branch: branch.into(), message: message.into() });
+        self
+    }
+
+    /// Start merging `branch` and stop with its conflicts unresolved, as `git merge` leaves
+    /// them. The build fails if the merge does not conflict; later steps must not need a
+    /// clean index.
+    pub fn conflict(mut self, branch: &str) -> Self {
+        self.steps.push(Step::Conflict { branch: branch.into() });
+        self
+    }
+
+    /// Tag HEAD: annotated with `message`, lightweight without one.
+    pub fn tag(mut self, name: &str, message: Option<&str>) -> Self {
+        self.steps.push(Step::Tag { name: name.into(), message: message.map(str::to_string) });
+        self
+    }
+
+    /// Build `spec` at `path` and commit it as a submodule whose `.gitmodules` URL is `url`
+    /// (never contacted). The submodule's own repository stays in place, as after
+    /// `git submodule update`.
+    pub fn submodule(mut self, path: &str, url: &str, spec: RepoSpec) -> Self {
+        self.steps.push(Step::Submodule { path: path.into(), url: url.into(), spec });
+        self
+    }
+
+    /// Apply `changes` without committing them.
+    pub fn modify(mut self, changes: impl IntoIterator<Item = Change>) -> Self {
+        self.steps.push(Step::Worktree { changes: changes.into_iter().collect(), stage: false });
+        self
+    }
+
+    /// Apply `changes` and stage them (and nothing else) without committing.
+    pub fn stage(mut self, changes: impl IntoIterator<Item = Change>) -> Self {
+        self.steps.push(Step::Worktree { changes: changes.into_iter().collect(), stage: true });
+        self
+    }
+
+    /// Build the repository at `dest`, which must not exist or be an empty directory.
+    /// `git` is the executable to run (`"git"` for the one on PATH).
+    pub fn build_with(&self, git: &Path, dest: &Path) -> io::Result<()> {
+        if dest.exists() && std::fs::read_dir(dest)?.next().is_some() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} is not empty", dest.display())));
+        }
+        std::fs::create_dir_all(dest)?;
+        log::info!("testkit: building {} step(s) in {}", self.steps.len(), dest.display());
+        let mut clock = EPOCH;
+        self.replay(git, dest, &mut clock)
+    }
+
+    /// [`build_with`](Self::build_with) the git on PATH.
+    pub fn build(&self, dest: &Path) -> io::Result<()> {
+        self.build_with(Path::new("git"), dest)
+    }
+
+    /// Build in a fresh directory under the temp dir, removed when the [`Fixture`] drops.
+    pub fn build_temp(&self, name: &str) -> io::Result<Fixture> {
+        self.build_temp_with(Path::new("git"), name)
+    }
+
+    /// [`build_temp`](Self::build_temp) with the git executable `git`.
+    pub fn build_temp_with(&self, git: &Path, name: &str) -> io::Result<Fixture> {
+        let dir = std::env::temp_dir().join(format!("openvcs-testkit-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let fixture = Fixture { dir };
+        self.build_with(git, &fixture.dir)?;
+        Ok(fixture)
+    }
+
+    fn replay(&self, program: &Path, dir: &Path, clock: &mut i64) -> io::Result<()> {
+        let mut git = git::Git::new(program, dir);
+        git.run(["init", "--quiet"])?;
+        git.run(["symbolic-ref", "HEAD", &format!("refs/heads/{}", self.branch)])?;
+
+        for step in &self.steps {
+            match step {
+                Step::Author { name, email } => git.identity(name, email),
+                Step::Commit { message, changes } => {
+                    apply(dir, changes)?;
+                    git.run(["add", "-A"])?;
+                    git.at(tick(clock)).run(["commit", "--quiet", "--allow-empty", "-m", message])?;
+                }
+                Step::Branch { name } => git.run(["checkout", "--quiet", "-b", name])?,
+                Step::Switch { name } => git.run(["checkout", "--quiet", name])?,
+                Step::Merge { branch, message } => {
+                    git.at(tick(clock)).run(["merge", "--quiet", "--no-ff", "-m", message, branch])?;
+                }
+                Step::Conflict { branch } => {
+                    // Expected to fail; the unmerged entries tell whether it conflicted
+                    let _ = git.at(tick(clock)).run(["merge", "--quiet", "--no-ff", branch]);
+                    if git.output(["diff", "--name-only", "--diff-filter=U"])?.trim().is_empty() {
+                        return Err(io::Error::other(format!("merging {branch} does not conflict")));
+                    }
+                }
+                Step::Tag { name, message: Some(message) } => {
+                    git.at(tick(clock)).run(["tag", "-a", "-m", message, name])?;
+                }
+                Step::Tag { name, message: None } => git.run(["tag", name])?,
+                Step::Submodule { path, url, spec } => {
+                    let sub = dir.join(path);
+                    std::fs::create_dir_all(&sub)?;
+                    spec.replay(program, &sub, clock)?;
+                    git.run(["submodule", "--quiet", "add", url, path])?;
+                    git.at(tick(clock)).run(["commit", "--quiet", "-m", &format!("Add submodule {path}")])?;
+                }
+                Step::Worktree { changes, stage } => {
+                    apply(dir, changes)?;
+                    if *stage {
+                        let mut args = vec!["add", "-A", "--"];
+                        args.extend(changes.iter().flat_map(Change::paths));
+                        git.run(args)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Advance the clock and return the time to record.
+fn tick(clock: &mut i64) -> i64 {
+    *clock += TICK;
+    *clock
+}
+
+fn apply(dir: &Path, changes: &[Change]) -> io::Result<()> {
+    let create = |rel: &str| -> io::Result<PathBuf> {
+        let path = dir.join(rel);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(path)
+    };
+    for change in changes {
+        match change {
+            Change::Write { path, contents } => std::fs::write(create(path)?, contents)?,
+            Change::Remove { path } => std::fs::remove_file(dir.join(path))?,
+            Change::Rename { from, to } => std::fs::rename(dir.join(from), create(to)?)?,
+            Change::Large { path, size } => std::fs::write(create(path)?, noise(path, *size))?,
+        }
+    }
+    Ok(())
+}
+
+/// `size` bytes of xorshift output seeded from `seed`: incompressible, like real binaries.
+fn noise(seed: &str, size: usize) -> Vec<u8> {
+    let mut x = seed.bytes().fold(0x9e37_79b9_7f4a_7c15_u64, |h, b| (h ^ u64::from(b)).wrapping_mul(0x100_0000_01b3));
+    let mut out = Vec::with_capacity(size + 8);
+    while out.len() < size {
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        out.extend_from_slice(&x.to_le_bytes());
+    }
+    out.truncate(size);
+    out
+}
+
+/// A repository built under the temp dir; deleted on drop.
+#[derive(Debug)]
+pub struct Fixture {
+    dir: PathBuf,
+}
+
+impl Fixture {
+    /// The working tree.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,65 @@
+//! Large generated repositories for benchmarks.
+
+use crate::{Change, RepoSpec};
+
+/// Files per directory, so the tree has depth like a real project's.
+const PER_DIR: usize = 50;
+
+/// Files each commit after the first changes.
+const TOUCHED: usize = 3;
+
+/// A repository with `files` source files, `commits` commits and uncommitted changes to
+/// about one file in a hundred (plus a few untracked files).
+///
+/// The first commit adds every file; each later commit edits [`TOUCHED`] files spread
+/// over the tree, so most files have a short history and a few a long one. Contents are
+/// text of a few kilobytes, the same for the same arguments.
+pub fn synthetic(files: usize, commits: usize) -> RepoSpec {
+    let files = files.max(1);
+    let path = |i: usize| format!("src/m{:03}/file{i:05}.rs", i / PER_DIR);
+    let mut revisions = vec![0usize; files];
+
+    let mut spec = RepoSpec::new().commit(
+        "Initial import",
+        (0..files).map(|i| Change::write(&path(i), contents(i, 0))),
+    );
+    for n in 1..commits {
+        let touched: Vec<usize> = (0..TOUCHED).map(|k| pick(n * TOUCHED + k, files)).collect();
+        let changes: Vec<Change> = touched
+            .iter()
+            .map(|&i| {
+                revisions[i] += 1;
+                Change::write(&path(i), contents(i, revisions[i]))
+            })
+            .collect();
+        spec = spec.commit(&format!("Change {} (#{n})", path(touched[0])), changes);
+    }
+
+    let edits = (0..files.div_ceil(100)).map(|k| {
+        let i = pick(usize::MAX - k, files);
+        Change::write(&path(i), format!("{}// local edit\n", contents(i, revisions[i])))
+    });
+    let untracked = (0..3).map(|k| Change::write(&format!("scratch/notes{k}.txt"), "todo\n"));
+    spec.modify(edits.chain(untracked).collect::<Vec<_>>())
+}
+
+/// The `n`th file to change: scattered over the tree, favouring low indices so some
+/// files change often.
+fn pick(n: usize, files: usize) -> usize {
+    let x = (n as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(17);
+    let spread = (x % files as u64) as usize;
+    if n.is_multiple_of(4) { spread % files.div_ceil(20) } else { spread }
+}
+
+/// Revision `rev` of file `i`: a fixed body plus one line per revision.
+fn contents(i: usize, rev: usize) -> String {
+    let mut out = format!("// file {i}\n\npub fn value_{i}() -> u64 {{\n");
+    for line in 0..60 {
+        out.push_str(&format!("    let v{line} = {} * {line} + {};\n", i % 97, (i * 31 + line) % 1009));
+    }
+    out.push_str("    0\n}\n");
+    for r in 1..=rev {
+        out.push_str(&format!("// revision {r}\n"));
+    }
+    out
+}